@@ -67,14 +67,26 @@ pub use abi_stable::{
 // Re-export core trait types
 pub use data::FfiDataFormat;
 pub use metadata::{FfiConfigParameter, FfiParameterType, FfiStageMetadata};
-pub use traits::{FfiExecutionContext, FfiStage, PluginCapability, StageType};
+pub use traits::{FfiBatchSink, FfiExecutionContext, FfiStage, PluginCapability, StageType};
 
 /// Plugin API version - increment when breaking changes occur
 ///
 /// This version is used to ensure compatibility between the host application
 /// and dynamically loaded plugins. Plugins compiled with a different API version
 /// will be rejected during loading.
-pub const PLUGIN_API_VERSION: u32 = 1;
+pub const PLUGIN_API_VERSION: u32 = 3;
+
+/// Host features a capability can list in `PluginCapability::required_features`
+///
+/// A host that does not support one of a capability's features skips that
+/// capability when loading the plugin.
+pub mod features {
+    /// The host calls `FfiStage::execute_stream` for stages configured with `stream = true`
+    pub const STREAMING: &str = "streaming";
+
+    /// The host passes upstream stage outputs in `FfiExecutionContext::inputs`
+    pub const INPUT_AWARE: &str = "input_aware";
+}
 
 /// Plugin metadata information
 #[derive(Debug, Clone, Serialize, Deserialize)]
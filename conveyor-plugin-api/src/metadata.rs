@@ -61,16 +61,43 @@ impl FfiStageMetadata {
     }
 }
 
-/// FFI-safe parameter type
+/// Parameter type
+///
+/// Crosses the FFI boundary as its `u32` discriminant in
+/// [`FfiConfigParameter::param_type`], so a type added by a newer API reaches
+/// an older host as an unknown number rather than an invalid enum.
 #[repr(C)]
 #[derive(StableAbi, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FfiParameterType {
-    String,
-    Integer,
-    Float,
-    Boolean,
-    Array,
-    Object,
+    String = 0,
+    Integer = 1,
+    Float = 2,
+    Boolean = 3,
+    Array = 4,
+    Object = 5,
+}
+
+impl From<FfiParameterType> for u32 {
+    fn from(param_type: FfiParameterType) -> Self {
+        param_type as u32
+    }
+}
+
+impl TryFrom<u32> for FfiParameterType {
+    /// The unknown discriminant
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FfiParameterType::String),
+            1 => Ok(FfiParameterType::Integer),
+            2 => Ok(FfiParameterType::Float),
+            3 => Ok(FfiParameterType::Boolean),
+            4 => Ok(FfiParameterType::Array),
+            5 => Ok(FfiParameterType::Object),
+            other => Err(other),
+        }
+    }
 }
 
 impl FfiParameterType {
@@ -93,8 +120,9 @@ pub struct FfiConfigParameter {
     /// Parameter name (as used in TOML config)
     pub name: RString,
 
-    /// Parameter type
-    pub param_type: FfiParameterType,
+    /// Parameter type as an [`FfiParameterType`] discriminant; read it with
+    /// [`FfiConfigParameter::parameter_type`]
+    pub param_type: u32,
 
     /// Whether this parameter is required
     pub required: bool,
@@ -118,7 +146,7 @@ impl FfiConfigParameter {
     ) -> Self {
         Self {
             name: name.into(),
-            param_type,
+            param_type: param_type.into(),
             required: true,
             default_value: RString::new(),
             description: description.into(),
@@ -135,7 +163,7 @@ impl FfiConfigParameter {
     ) -> Self {
         Self {
             name: name.into(),
-            param_type,
+            param_type: param_type.into(),
             required: false,
             default_value: default.into(),
             description: description.into(),
@@ -143,6 +171,11 @@ impl FfiConfigParameter {
         }
     }
 
+    /// The parameter type, or the raw value when this API version does not know it
+    pub fn parameter_type(&self) -> Result<FfiParameterType, u32> {
+        FfiParameterType::try_from(self.param_type)
+    }
+
     /// Add allowed values (enum-like validation)
     pub fn with_allowed_values(
         mut self,
@@ -175,7 +208,7 @@ mod tests {
         let param =
             FfiConfigParameter::required("url", FfiParameterType::String, "MongoDB connection URL");
         assert_eq!(param.name.as_str(), "url");
-        assert_eq!(param.param_type, FfiParameterType::String);
+        assert_eq!(param.parameter_type(), Ok(FfiParameterType::String));
         assert!(param.required);
         assert!(param.default_value.is_empty());
     }
@@ -210,4 +243,25 @@ mod tests {
         assert_eq!(FfiParameterType::Float.as_str(), "float");
         assert_eq!(FfiParameterType::Boolean.as_str(), "boolean");
     }
+
+    #[test]
+    fn test_parameter_type_round_trip() {
+        for param_type in [
+            FfiParameterType::String,
+            FfiParameterType::Integer,
+            FfiParameterType::Float,
+            FfiParameterType::Boolean,
+            FfiParameterType::Array,
+            FfiParameterType::Object,
+        ] {
+            assert_eq!(
+                FfiParameterType::try_from(u32::from(param_type)),
+                Ok(param_type)
+            );
+        }
+
+        let mut param = FfiConfigParameter::required("url", FfiParameterType::String, "URL");
+        param.param_type = 42;
+        assert_eq!(param.parameter_type(), Err(42));
+    }
 }
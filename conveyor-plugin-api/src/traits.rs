@@ -5,7 +5,10 @@
 
 #![allow(non_local_definitions)]
 
-use crate::{data::FfiDataFormat, sabi_trait, RBoxError, RHashMap, RResult, RStr, RString};
+use crate::{
+    data::FfiDataFormat, sabi_trait, RBox, RBoxError, RErr, RHashMap, ROk, RResult, RStr, RString,
+    RVec,
+};
 
 /// FFI-safe execution context
 ///
@@ -156,6 +159,35 @@ pub trait FfiStage: Send + Sync {
     /// # Returns
     /// * `RResult<(), RBoxError>` - Success or validation error
     fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError>;
+
+    /// Execute the stage incrementally, pushing each batch to `sink` as it is produced
+    ///
+    /// The host calls this from a blocking thread and `sink.emit` blocks while
+    /// the downstream channel is full, so a slow consumer throttles the stage.
+    /// When `emit` returns `false` the consumer is gone and the stage should stop.
+    ///
+    /// The default implementation runs `execute` and emits its result once.
+    fn execute_stream(
+        &self,
+        context: FfiExecutionContext,
+        mut sink: FfiBatchSink_TO<'static, RBox<()>>,
+    ) -> RResult<(), RBoxError> {
+        match self.execute(context) {
+            ROk(data) => {
+                sink.emit(data);
+                ROk(())
+            }
+            RErr(e) => RErr(e),
+        }
+    }
+}
+
+/// FFI-safe receiver for batches produced by `FfiStage::execute_stream`
+#[allow(non_local_definitions)]
+#[sabi_trait]
+pub trait FfiBatchSink: Send {
+    /// Deliver one batch; returns `false` once the consumer has stopped listening
+    fn emit(&mut self, batch: FfiDataFormat) -> bool;
 }
 
 /// Stage factory function type
@@ -197,6 +229,12 @@ pub struct PluginCapability {
 
     /// Detailed metadata for this stage
     pub metadata: crate::metadata::FfiStageMetadata,
+
+    /// Host features this stage needs (see [`crate::features`])
+    ///
+    /// The host skips the capability with a warning if it lacks any of them,
+    /// instead of failing when the stage runs.
+    pub required_features: RVec<RString>,
 }
 
 impl PluginCapability {
@@ -213,6 +251,7 @@ impl PluginCapability {
             description: description.into(),
             factory_symbol: factory_symbol.into(),
             metadata,
+            required_features: RVec::new(),
         }
     }
 
@@ -234,8 +273,19 @@ impl PluginCapability {
             description: desc_str,
             factory_symbol: factory_symbol.into(),
             metadata,
+            required_features: RVec::new(),
         }
     }
+
+    /// Declare host features this stage needs
+    pub fn with_required_features<I, S>(mut self, features: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<RString>,
+    {
+        self.required_features = features.into_iter().map(Into::into).collect();
+        self
+    }
 }
 
 #[cfg(test)]
@@ -378,6 +428,11 @@ mod tests {
         assert_eq!(cap.stage_type, StageType::Source);
         assert_eq!(cap.description.as_str(), "HTTP data source");
         assert_eq!(cap.factory_symbol.as_str(), "create_http_source");
+        assert!(cap.required_features.is_empty());
+
+        let cap = cap.with_required_features([crate::features::STREAMING]);
+        assert_eq!(cap.required_features.len(), 1);
+        assert_eq!(cap.required_features[0].as_str(), "streaming");
     }
 
     #[test]
@@ -85,6 +85,19 @@ impl Guest for EchoPlugin {
             )));
         }
 
+        // Simulate a transient failure: fail the first call, succeed afterwards
+        if let Some(marker) = get_config_value(&context.config, "fail_once_marker") {
+            if std::fs::metadata(marker).is_err() {
+                std::fs::write(marker, b"failed once").map_err(|e| {
+                    PluginError::IoError(format!("Failed to write marker '{}': {}", marker, e))
+                })?;
+                return Err(PluginError::RuntimeError(format!(
+                    "Simulated failure (marker '{}' created)",
+                    marker
+                )));
+            }
+        }
+
         // Determine operation type based on inputs
         if context.inputs.is_empty() {
             // No inputs = Source operation
@@ -0,0 +1,1099 @@
+//! Elasticsearch Plugin for Conveyor - Operation-based API
+//!
+//! Provides Elasticsearch/OpenSearch operations: index (bulk), search and scroll.
+//! Only the REST API is used, so the same stages work against both engines.
+
+use conveyor_plugin_api::sabi_trait::prelude::*;
+use conveyor_plugin_api::traits::{FfiExecutionContext, FfiStage, FfiStage_TO};
+use conveyor_plugin_api::{
+    rstr, FfiConfigParameter, FfiDataFormat, FfiParameterType, FfiStageMetadata, PluginCapability,
+    PluginDeclaration, RBox, RBoxError, RErr, RHashMap, ROk, RResult, RString, RVec, StageType,
+    PLUGIN_API_VERSION,
+};
+use reqwest::{Client, Method, RequestBuilder};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+const DEFAULT_BATCH_SIZE: usize = 500;
+const DEFAULT_PAGE_SIZE: usize = 1000;
+const DEFAULT_SCROLL: &str = "1m";
+
+/// Elasticsearch operation types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EsOperation {
+    Index,
+    Search,
+    Scroll,
+}
+
+/// Elasticsearch Stage - operation-based
+pub struct ElasticsearchStage {
+    name: String,
+    operation: EsOperation,
+    stage_type: StageType,
+}
+
+impl ElasticsearchStage {
+    fn new(name: String, operation: EsOperation, stage_type: StageType) -> Self {
+        Self {
+            name,
+            operation,
+            stage_type,
+        }
+    }
+
+    /// Execute index operation - bulk-index input records
+    async fn execute_index_async(
+        &self,
+        input_data: &FfiDataFormat,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let connection = match Connection::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let records = match input_data.to_json_records() {
+            ROk(records) => records,
+            RErr(e) => return RErr(e),
+        };
+
+        let batch_size = match parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE) {
+            Ok(n) => n,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+        let document_id = match (config.get("id_field"), transactional(config)) {
+            (Some(field), _) => DocumentId::Field(field),
+            // Elasticsearch has no transactions; content ids make a re-run overwrite
+            // what a failed run already indexed instead of duplicating it
+            (None, Ok(true)) => DocumentId::ContentHash,
+            (None, Ok(false)) => DocumentId::Auto,
+            (None, Err(e)) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let mut path = "/_bulk".to_string();
+        if let Some(refresh) = config.get("refresh") {
+            path.push_str(&format!("?refresh={}", refresh));
+        }
+
+        for batch in records.chunks(batch_size) {
+            let body = match build_bulk_body(batch, &connection.index, &document_id) {
+                Ok(b) => b,
+                Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+            };
+
+            let request = connection
+                .request(Method::POST, &path)
+                .header("Content-Type", "application/x-ndjson")
+                .body(body);
+
+            let response = match send_json(request).await {
+                Ok(r) => r,
+                Err(e) => {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Elasticsearch bulk request failed: {}",
+                        e
+                    )))
+                }
+            };
+
+            if let Some(failure) = bulk_failures(&response) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", failure)));
+            }
+        }
+
+        ROk(input_data.clone())
+    }
+
+    /// Execute search operation - run a query and return the hits
+    async fn execute_search_async(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let connection = match Connection::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let size = match config.get("size").map(|s| s.parse::<usize>()) {
+            Some(Ok(n)) => Some(n),
+            Some(Err(_)) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "'size' must be a non-negative integer"
+                )))
+            }
+            None => None,
+        };
+
+        let body = match search_body(config, size) {
+            Ok(b) => b,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let path = format!("/{}/_search", connection.index);
+        let response = match send_json(connection.request(Method::POST, &path).json(&body)).await {
+            Ok(r) => r,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Elasticsearch search failed: {}",
+                    e
+                )))
+            }
+        };
+
+        match hits_to_records(&response, include_meta(config)) {
+            Ok(records) => FfiDataFormat::from_json_records(&records),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+
+    /// Execute scroll operation - page through every hit of a query
+    async fn execute_scroll_async(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let connection = match Connection::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let page_size = match parse_positive(config, "page_size", DEFAULT_PAGE_SIZE) {
+            Ok(n) => n,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+        let max_documents = match config.get("max_documents").map(|s| s.parse::<usize>()) {
+            Some(Ok(n)) => Some(n),
+            Some(Err(_)) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "'max_documents' must be a non-negative integer"
+                )))
+            }
+            None => None,
+        };
+        let keep_alive = config
+            .get("scroll")
+            .map(|s| s.as_str())
+            .unwrap_or(DEFAULT_SCROLL);
+        let include_meta = include_meta(config);
+
+        let body = match search_body(config, Some(page_size)) {
+            Ok(b) => b,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let path = format!("/{}/_search?scroll={}", connection.index, keep_alive);
+        let mut response =
+            match send_json(connection.request(Method::POST, &path).json(&body)).await {
+                Ok(r) => r,
+                Err(e) => {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Elasticsearch scroll failed: {}",
+                        e
+                    )))
+                }
+            };
+
+        let mut records: Vec<HashMap<String, Value>> = Vec::new();
+        let mut scroll_id: Option<String> = None;
+
+        let result = loop {
+            if let Some(id) = response.get("_scroll_id").and_then(|v| v.as_str()) {
+                scroll_id = Some(id.to_string());
+            }
+
+            let page = match hits_to_records(&response, include_meta) {
+                Ok(page) => page,
+                Err(e) => break Err(e),
+            };
+            if page.is_empty() {
+                break Ok(());
+            }
+            records.extend(page);
+
+            if let Some(max) = max_documents {
+                if records.len() >= max {
+                    records.truncate(max);
+                    break Ok(());
+                }
+            }
+
+            let id = match &scroll_id {
+                Some(id) => id.clone(),
+                None => break Err("Scroll response did not include a _scroll_id".to_string()),
+            };
+            let next = json!({ "scroll": keep_alive, "scroll_id": id });
+            response = match send_json(
+                connection
+                    .request(Method::POST, "/_search/scroll")
+                    .json(&next),
+            )
+            .await
+            {
+                Ok(r) => r,
+                Err(e) => break Err(format!("Elasticsearch scroll failed: {}", e)),
+            };
+        };
+
+        // Release the scroll context; it would expire on its own, so failures are ignored
+        if let Some(id) = scroll_id {
+            let clear = json!({ "scroll_id": [id] });
+            let _ = connection
+                .request(Method::DELETE, "/_search/scroll")
+                .json(&clear)
+                .send()
+                .await;
+        }
+
+        match result {
+            Ok(()) => FfiDataFormat::from_json_records(&records),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+}
+
+impl FfiStage for ElasticsearchStage {
+    fn name(&self) -> conveyor_plugin_api::RStr<'_> {
+        self.name.as_str().into()
+    }
+
+    fn stage_type(&self) -> StageType {
+        self.stage_type
+    }
+
+    fn execute(&self, context: FfiExecutionContext) -> RResult<FfiDataFormat, RBoxError> {
+        // Convert config to HashMap
+        let config: HashMap<String, String> = context
+            .config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        // Use tokio runtime to execute async code
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Failed to create runtime: {}",
+                    e
+                )))
+            }
+        };
+
+        runtime.block_on(async {
+            let input_data = context.inputs.into_iter().next().map(|tuple| tuple.1);
+
+            match self.operation {
+                EsOperation::Index => {
+                    let input_data = match input_data {
+                        Some(data) => data,
+                        None => {
+                            return RErr(RBoxError::from_fmt(&format_args!(
+                                "index requires input data"
+                            )))
+                        }
+                    };
+                    self.execute_index_async(&input_data, &config).await
+                }
+                EsOperation::Search => self.execute_search_async(&config).await,
+                EsOperation::Scroll => self.execute_scroll_async(&config).await,
+            }
+        })
+    }
+
+    fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError> {
+        let config: HashMap<String, String> = config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        match validate(self.operation, &config) {
+            Ok(()) => ROk(()),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+}
+
+// ============================================================================
+// Connection Helpers
+// ============================================================================
+
+/// Credentials sent with every request
+#[derive(Debug, Clone, PartialEq)]
+enum Auth {
+    None,
+    Basic { username: String, password: String },
+    ApiKey(String),
+}
+
+impl Auth {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match (
+            config.get("username"),
+            config.get("password"),
+            config.get("api_key"),
+        ) {
+            (Some(_), _, Some(_)) => {
+                Err("Use either 'username'/'password' or 'api_key', not both".to_string())
+            }
+            (Some(username), Some(password), None) => Ok(Auth::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            }),
+            (Some(_), None, None) => Err("'username' requires a 'password'".to_string()),
+            (None, Some(_), _) => Err("'password' requires a 'username'".to_string()),
+            (None, None, Some(key)) => Ok(Auth::ApiKey(key.clone())),
+            (None, None, None) => Ok(Auth::None),
+        }
+    }
+}
+
+/// Cluster endpoint, target index and credentials of a stage
+struct Connection {
+    client: Client,
+    url: String,
+    index: String,
+    auth: Auth,
+}
+
+impl Connection {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        let url = config
+            .get("url")
+            .ok_or("Missing required 'url' configuration")?
+            .trim_end_matches('/')
+            .to_string();
+        let index = config
+            .get("index")
+            .ok_or("Missing required 'index' configuration")?
+            .clone();
+        let auth = Auth::from_config(config)?;
+
+        let timeout_secs: u64 = config
+            .get("timeout_seconds")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(30);
+        let client = Client::builder()
+            .timeout(Duration::from_secs(timeout_secs))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            url,
+            index,
+            auth,
+        })
+    }
+
+    /// Build an authenticated request for a path relative to the cluster URL
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.url, path));
+        match &self.auth {
+            Auth::None => request,
+            Auth::Basic { username, password } => request.basic_auth(username, Some(password)),
+            Auth::ApiKey(key) => request.header("Authorization", format!("ApiKey {}", key)),
+        }
+    }
+}
+
+/// Send a request and parse the JSON response, failing on non-success statuses
+async fn send_json(request: RequestBuilder) -> Result<Value, String> {
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| e.to_string())?;
+
+    if !status.is_success() {
+        return Err(format!("HTTP {}: {}", status, text));
+    }
+
+    serde_json::from_str(&text).map_err(|e| format!("Invalid JSON response: {}", e))
+}
+
+// ============================================================================
+// Request/Response Helpers
+// ============================================================================
+
+fn parse_positive(
+    config: &HashMap<String, String>,
+    key: &str,
+    default: usize,
+) -> Result<usize, String> {
+    match config.get(key) {
+        None => Ok(default),
+        Some(value) => match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("'{}' must be a positive integer", key)),
+        },
+    }
+}
+
+fn include_meta(config: &HashMap<String, String>) -> bool {
+    config
+        .get("include_meta")
+        .map(|s| s == "true")
+        .unwrap_or(false)
+}
+
+/// Build a search request body from the `query` and `sort` options
+///
+/// `query` is either a query clause (`{"match": {...}}`) or a full request body
+/// containing a top-level `query` key, which is used as-is.
+fn search_body(config: &HashMap<String, String>, size: Option<usize>) -> Result<Value, String> {
+    let query: Value = match config.get("query") {
+        Some(q) => serde_json::from_str(q).map_err(|e| format!("Invalid 'query' JSON: {}", e))?,
+        None => json!({ "match_all": {} }),
+    };
+    if !query.is_object() {
+        return Err("'query' must be a JSON object".to_string());
+    }
+
+    let mut body = if query.get("query").is_some() {
+        query
+    } else {
+        json!({ "query": query })
+    };
+
+    if let Some(sort) = config.get("sort") {
+        let sort: Value =
+            serde_json::from_str(sort).map_err(|e| format!("Invalid 'sort' JSON: {}", e))?;
+        body["sort"] = sort;
+    }
+    if let Some(size) = size {
+        body["size"] = json!(size);
+    }
+
+    Ok(body)
+}
+
+/// Turn `hits.hits` of a search response into records of their `_source`
+fn hits_to_records(
+    response: &Value,
+    include_meta: bool,
+) -> Result<Vec<HashMap<String, Value>>, String> {
+    let hits = response
+        .pointer("/hits/hits")
+        .and_then(|h| h.as_array())
+        .ok_or("Search response is missing hits.hits")?;
+
+    Ok(hits
+        .iter()
+        .map(|hit| {
+            let mut record: HashMap<String, Value> = hit
+                .get("_source")
+                .and_then(|s| s.as_object())
+                .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+                .unwrap_or_default();
+
+            if include_meta {
+                for field in ["_id", "_index", "_score"] {
+                    if let Some(value) = hit.get(field) {
+                        record.insert(field.to_string(), value.clone());
+                    }
+                }
+            }
+
+            record
+        })
+        .collect())
+}
+
+/// How `_bulk` actions get their document `_id`
+enum DocumentId<'a> {
+    /// Elasticsearch generates one
+    Auto,
+    /// The value of a record field
+    Field(&'a str),
+    /// A SHA-256 of the record, so sending the same record again overwrites it
+    ContentHash,
+}
+
+/// Parse `transactional` (default false)
+fn transactional(config: &HashMap<String, String>) -> Result<bool, String> {
+    match config.get("transactional").map(|v| v.trim()) {
+        None => Ok(false),
+        Some(value) => value.parse::<bool>().map_err(|_| {
+            format!(
+                "Invalid 'transactional' value '{}': expected true or false",
+                value
+            )
+        }),
+    }
+}
+
+/// Idempotency key of a record: the hex SHA-256 of its JSON with sorted keys
+fn content_id(record: &HashMap<String, Value>) -> Result<String, String> {
+    let sorted: BTreeMap<&String, &Value> = record.iter().collect();
+    let json = serde_json::to_string(&sorted).map_err(|e| e.to_string())?;
+    Ok(Sha256::digest(json.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Build an NDJSON `_bulk` body indexing each record into `index`
+///
+/// With an id field or content ids, re-running a pipeline overwrites
+/// documents instead of duplicating them.
+fn build_bulk_body(
+    records: &[HashMap<String, Value>],
+    index: &str,
+    document_id: &DocumentId,
+) -> Result<String, String> {
+    let mut body = String::new();
+
+    for (i, record) in records.iter().enumerate() {
+        let mut action = json!({ "_index": index });
+        match document_id {
+            DocumentId::Auto => {}
+            DocumentId::Field(field) => {
+                let id = match record.get(*field) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Number(n)) => n.to_string(),
+                    Some(_) => {
+                        return Err(format!(
+                            "Record {} has a non-scalar '{}' and cannot be used as _id",
+                            i, field
+                        ))
+                    }
+                    None => return Err(format!("Record {} is missing id field '{}'", i, field)),
+                };
+                action["_id"] = json!(id);
+            }
+            DocumentId::ContentHash => action["_id"] = json!(content_id(record)?),
+        }
+
+        body.push_str(&json!({ "index": action }).to_string());
+        body.push('\n');
+        body.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+        body.push('\n');
+    }
+
+    Ok(body)
+}
+
+/// Summarize per-document failures of a `_bulk` response, if any
+fn bulk_failures(response: &Value) -> Option<String> {
+    if !response
+        .get("errors")
+        .and_then(|e| e.as_bool())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+
+    let items = response
+        .get("items")
+        .and_then(|i| i.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let errors: Vec<&Value> = items
+        .iter()
+        .filter_map(|item| item.get("index").and_then(|r| r.get("error")))
+        .collect();
+
+    let first = errors
+        .first()
+        .map(|e| {
+            let kind = e.get("type").and_then(|t| t.as_str()).unwrap_or("unknown");
+            let reason = e.get("reason").and_then(|r| r.as_str()).unwrap_or("");
+            format!("{}: {}", kind, reason)
+        })
+        .unwrap_or_else(|| "unknown error".to_string());
+
+    Some(format!(
+        "Bulk indexing failed for {} of {} documents (first error: {})",
+        errors.len(),
+        items.len(),
+        first
+    ))
+}
+
+/// Validate the configuration of an operation
+fn validate(operation: EsOperation, config: &HashMap<String, String>) -> Result<(), String> {
+    if !config.contains_key("url") {
+        return Err("Missing required 'url' configuration".to_string());
+    }
+    if !config.contains_key("index") {
+        return Err("Missing required 'index' configuration".to_string());
+    }
+    Auth::from_config(config)?;
+
+    match operation {
+        EsOperation::Index => {
+            parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE)?;
+            transactional(config)?;
+        }
+        EsOperation::Search => {
+            if let Some(size) = config.get("size") {
+                size.parse::<usize>()
+                    .map_err(|_| "'size' must be a non-negative integer".to_string())?;
+            }
+            search_body(config, None)?;
+        }
+        EsOperation::Scroll => {
+            parse_positive(config, "page_size", DEFAULT_PAGE_SIZE)?;
+            if let Some(max) = config.get("max_documents") {
+                max.parse::<usize>()
+                    .map_err(|_| "'max_documents' must be a non-negative integer".to_string())?;
+            }
+            search_body(config, None)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Factory functions for each operation
+#[no_mangle]
+pub extern "C" fn create_elasticsearch_index() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        ElasticsearchStage::new(
+            "elasticsearch-index".to_string(),
+            EsOperation::Index,
+            StageType::Sink,
+        ),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_elasticsearch_search() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        ElasticsearchStage::new(
+            "elasticsearch-search".to_string(),
+            EsOperation::Search,
+            StageType::Source,
+        ),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_elasticsearch_scroll() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        ElasticsearchStage::new(
+            "elasticsearch-scroll".to_string(),
+            EsOperation::Scroll,
+            StageType::Source,
+        ),
+        TD_Opaque,
+    )
+}
+
+// ============================================================================
+// Metadata Helper Functions
+// ============================================================================
+
+/// Create common Elasticsearch parameters (url, index, auth)
+fn common_elasticsearch_parameters() -> Vec<FfiConfigParameter> {
+    vec![
+        FfiConfigParameter::required(
+            "url",
+            FfiParameterType::String,
+            "Cluster URL (e.g., http://localhost:9200)",
+        ),
+        FfiConfigParameter::required("index", FfiParameterType::String, "Index name"),
+        FfiConfigParameter::optional(
+            "username",
+            FfiParameterType::String,
+            "",
+            "Username for basic authentication",
+        ),
+        FfiConfigParameter::optional(
+            "password",
+            FfiParameterType::String,
+            "",
+            "Password for basic authentication",
+        ),
+        FfiConfigParameter::optional(
+            "api_key",
+            FfiParameterType::String,
+            "",
+            "Base64-encoded API key (sent as 'Authorization: ApiKey ...')",
+        ),
+        FfiConfigParameter::optional(
+            "timeout_seconds",
+            FfiParameterType::Integer,
+            "30",
+            "Request timeout in seconds",
+        ),
+    ]
+}
+
+/// Create query parameters shared by search and scroll
+fn query_parameters() -> Vec<FfiConfigParameter> {
+    vec![
+        FfiConfigParameter::optional(
+            "query",
+            FfiParameterType::String,
+            "{\"match_all\": {}}",
+            "Query DSL as JSON string: a query clause, or a full body with a 'query' key",
+        ),
+        FfiConfigParameter::optional(
+            "sort",
+            FfiParameterType::String,
+            "",
+            "Sort specification as JSON string (e.g., '[{\"created_at\": \"asc\"}]')",
+        ),
+        FfiConfigParameter::optional(
+            "include_meta",
+            FfiParameterType::Boolean,
+            "false",
+            "Add _id, _index and _score fields to each record",
+        ),
+    ]
+}
+
+/// Create metadata for index operation
+fn create_index_metadata() -> FfiStageMetadata {
+    let mut params = common_elasticsearch_parameters();
+    params.extend(vec![
+        FfiConfigParameter::optional(
+            "batch_size",
+            FfiParameterType::Integer,
+            "500",
+            "Documents per _bulk request",
+        ),
+        FfiConfigParameter::optional(
+            "id_field",
+            FfiParameterType::String,
+            "",
+            "Record field used as the document _id (auto-generated when unset)",
+        ),
+        FfiConfigParameter::optional(
+            "transactional",
+            FfiParameterType::Boolean,
+            "false",
+            "Elasticsearch has no transactions: without id_field, use a content hash as _id so re-runs after a partial failure overwrite instead of duplicating",
+        ),
+        FfiConfigParameter::optional(
+            "refresh",
+            FfiParameterType::String,
+            "",
+            "Refresh policy passed to the _bulk API",
+        )
+        .with_allowed_values(["true", "false", "wait_for"]),
+    ]);
+
+    FfiStageMetadata::new(
+        "elasticsearch.index",
+        "Bulk-index input records into an Elasticsearch index",
+        "Sends input records to the _bulk API in batches of 'batch_size'. \
+         Each record becomes one document; set 'id_field' to derive the document _id \
+         from a record field so re-runs overwrite instead of duplicating. \
+         Elasticsearch has no transactions, so a failed batch can leave some documents indexed; \
+         with 'transactional' and no 'id_field', each document's _id is the SHA-256 of its \
+         content, making the write idempotent (identical records collapse into one document). \
+         The stage fails if any document in a batch is rejected.",
+        params,
+        vec!["elasticsearch", "opensearch", "search", "sink", "bulk"],
+    )
+}
+
+/// Create metadata for search operation
+fn create_search_metadata() -> FfiStageMetadata {
+    let mut params = common_elasticsearch_parameters();
+    params.extend(query_parameters());
+    params.push(FfiConfigParameter::optional(
+        "size",
+        FfiParameterType::Integer,
+        "10",
+        "Maximum number of hits to return",
+    ));
+
+    FfiStageMetadata::new(
+        "elasticsearch.search",
+        "Run a query against an Elasticsearch index",
+        "Executes a single _search request and returns each hit's _source as a record. \
+         Results are limited by 'size' (and the index's max_result_window); \
+         use elasticsearch.scroll to read large result sets.",
+        params,
+        vec!["elasticsearch", "opensearch", "search", "source", "query"],
+    )
+}
+
+/// Create metadata for scroll operation
+fn create_scroll_metadata() -> FfiStageMetadata {
+    let mut params = common_elasticsearch_parameters();
+    params.extend(query_parameters());
+    params.extend(vec![
+        FfiConfigParameter::optional(
+            "page_size",
+            FfiParameterType::Integer,
+            "1000",
+            "Hits fetched per scroll page",
+        ),
+        FfiConfigParameter::optional(
+            "scroll",
+            FfiParameterType::String,
+            DEFAULT_SCROLL,
+            "How long the scroll context is kept alive between pages",
+        ),
+        FfiConfigParameter::optional(
+            "max_documents",
+            FfiParameterType::Integer,
+            "",
+            "Stop after this many documents",
+        ),
+    ]);
+
+    FfiStageMetadata::new(
+        "elasticsearch.scroll",
+        "Read every hit of a query using the scroll API",
+        "Pages through all documents matching the query with the scroll API, \
+         'page_size' hits at a time, and returns them as records. \
+         The scroll context is cleared when reading finishes.",
+        params,
+        vec!["elasticsearch", "opensearch", "search", "source", "scroll"],
+    )
+}
+
+// ============================================================================
+// Plugin Capabilities
+// ============================================================================
+
+// Plugin capabilities
+extern "C" fn get_capabilities() -> RVec<PluginCapability> {
+    vec![
+        PluginCapability::new(
+            "elasticsearch.search",
+            StageType::Source,
+            "Elasticsearch search - run a query DSL",
+            "create_elasticsearch_search",
+            create_search_metadata(),
+        ),
+        PluginCapability::new(
+            "elasticsearch.scroll",
+            StageType::Source,
+            "Elasticsearch scroll - read large result sets",
+            "create_elasticsearch_scroll",
+            create_scroll_metadata(),
+        ),
+        PluginCapability::new(
+            "elasticsearch.index",
+            StageType::Sink,
+            "Elasticsearch index - bulk-index documents",
+            "create_elasticsearch_index",
+            create_index_metadata(),
+        ),
+    ]
+    .into()
+}
+
+// Plugin declaration
+#[no_mangle]
+pub static _plugin_declaration: PluginDeclaration = PluginDeclaration {
+    api_version: PLUGIN_API_VERSION,
+    name: rstr!("elasticsearch"),
+    version: rstr!("0.1.0"),
+    description: rstr!(
+        "Elasticsearch/OpenSearch plugin with operation-based API (search, scroll, index)"
+    ),
+    get_capabilities,
+};
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_config() -> HashMap<String, String> {
+        HashMap::from([
+            ("url".to_string(), "http://localhost:9200".to_string()),
+            ("index".to_string(), "products".to_string()),
+        ])
+    }
+
+    fn record(fields: &[(&str, Value)]) -> HashMap<String, Value> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plugin_declaration() {
+        assert_eq!(_plugin_declaration.name, "elasticsearch");
+        assert!(_plugin_declaration.is_compatible());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let caps = get_capabilities();
+        assert_eq!(caps.len(), 3);
+
+        assert_eq!(caps[0].name.as_str(), "elasticsearch.search");
+        assert_eq!(caps[0].stage_type, StageType::Source);
+        assert_eq!(caps[1].name.as_str(), "elasticsearch.scroll");
+        assert_eq!(caps[1].stage_type, StageType::Source);
+        assert_eq!(caps[2].name.as_str(), "elasticsearch.index");
+        assert_eq!(caps[2].stage_type, StageType::Sink);
+    }
+
+    #[test]
+    fn test_validation() {
+        let stage = ElasticsearchStage::new(
+            "elasticsearch.search".to_string(),
+            EsOperation::Search,
+            StageType::Source,
+        );
+        let mut config = RHashMap::new();
+
+        // Missing url and index should fail
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("url"), RString::from("http://localhost:9200"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("index"), RString::from("products"));
+        assert!(stage.validate_config(config.clone()).is_ok());
+
+        // Query must be valid JSON
+        config.insert(RString::from("query"), RString::from("{not json"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(
+            RString::from("query"),
+            RString::from(r#"{"match": {"name": "lamp"}}"#),
+        );
+        assert!(stage.validate_config(config).is_ok());
+    }
+
+    #[test]
+    fn test_operation_specific_validation() {
+        let mut config = base_config();
+        config.insert("batch_size".to_string(), "0".to_string());
+        assert!(validate(EsOperation::Index, &config).is_err());
+        // batch_size only applies to index
+        assert!(validate(EsOperation::Search, &config).is_ok());
+
+        let mut config = base_config();
+        config.insert("page_size".to_string(), "many".to_string());
+        assert!(validate(EsOperation::Scroll, &config).is_err());
+
+        let mut config = base_config();
+        config.insert("username".to_string(), "elastic".to_string());
+        assert!(validate(EsOperation::Search, &config).is_err());
+        config.insert("password".to_string(), "changeme".to_string());
+        assert!(validate(EsOperation::Search, &config).is_ok());
+        config.insert("api_key".to_string(), "abc".to_string());
+        assert!(validate(EsOperation::Search, &config).is_err());
+    }
+
+    #[test]
+    fn test_build_bulk_body() {
+        let records = vec![
+            record(&[("sku", json!("A-1")), ("price", json!(10))]),
+            record(&[("sku", json!(42)), ("price", json!(12.5))]),
+        ];
+
+        let body = build_bulk_body(&records, "products", &DocumentId::Field("sku")).unwrap();
+
+        assert!(body.ends_with('\n'));
+        let lines: Vec<Value> = body
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            json!({ "index": { "_index": "products", "_id": "A-1" } })
+        );
+        assert_eq!(lines[1], json!({ "sku": "A-1", "price": 10 }));
+        // Numeric ids are sent as strings
+        assert_eq!(lines[2]["index"]["_id"], json!("42"));
+        assert_eq!(lines[3]["price"], json!(12.5));
+
+        // Without id_field, Elasticsearch generates ids
+        let body = build_bulk_body(&records[..1], "products", &DocumentId::Auto).unwrap();
+        let action: Value = serde_json::from_str(body.lines().next().unwrap()).unwrap();
+        assert_eq!(action, json!({ "index": { "_index": "products" } }));
+
+        let missing = vec![record(&[("price", json!(1))])];
+        assert!(build_bulk_body(&missing, "products", &DocumentId::Field("sku")).is_err());
+    }
+
+    #[test]
+    fn test_transactional_uses_content_ids() {
+        let records = vec![
+            record(&[("sku", json!("A-1")), ("price", json!(10))]),
+            record(&[("price", json!(10)), ("sku", json!("A-1"))]),
+            record(&[("sku", json!("A-2")), ("price", json!(10))]),
+        ];
+        let body = build_bulk_body(&records, "products", &DocumentId::ContentHash).unwrap();
+        let ids: Vec<String> = body
+            .lines()
+            .step_by(2)
+            .map(|l| {
+                let action: Value = serde_json::from_str(l).unwrap();
+                action["index"]["_id"].as_str().unwrap().to_string()
+            })
+            .collect();
+
+        // Re-sending a record (in any field order) reuses its id; other records differ
+        assert_eq!(ids[0].len(), 64);
+        assert_eq!(ids[0], ids[1]);
+        assert_ne!(ids[0], ids[2]);
+
+        let mut config = base_config();
+        config.insert("transactional".to_string(), "yes".to_string());
+        assert!(validate(EsOperation::Index, &config).is_err());
+    }
+
+    #[test]
+    fn test_bulk_failures() {
+        let ok = json!({ "errors": false, "items": [{ "index": { "status": 201 } }] });
+        assert!(bulk_failures(&ok).is_none());
+
+        let failed = json!({
+            "errors": true,
+            "items": [
+                { "index": { "status": 201 } },
+                { "index": { "status": 400, "error": {
+                    "type": "mapper_parsing_exception",
+                    "reason": "failed to parse field [price]"
+                } } }
+            ]
+        });
+        let message = bulk_failures(&failed).unwrap();
+        assert!(message.contains("1 of 2 documents"));
+        assert!(message.contains("mapper_parsing_exception"));
+    }
+
+    #[test]
+    fn test_search_body_and_hits() {
+        let mut config = base_config();
+        assert_eq!(
+            search_body(&config, Some(5)).unwrap(),
+            json!({ "query": { "match_all": {} }, "size": 5 })
+        );
+
+        // A full body is used as-is
+        config.insert(
+            "query".to_string(),
+            r#"{"query": {"term": {"status": "active"}}, "_source": ["name"]}"#.to_string(),
+        );
+        config.insert("sort".to_string(), r#"[{"name": "asc"}]"#.to_string());
+        let body = search_body(&config, None).unwrap();
+        assert_eq!(body["query"], json!({ "term": { "status": "active" } }));
+        assert_eq!(body["_source"], json!(["name"]));
+        assert_eq!(body["sort"], json!([{ "name": "asc" }]));
+
+        let response = json!({
+            "hits": { "hits": [
+                { "_index": "products", "_id": "1", "_score": 1.0, "_source": { "name": "lamp" } }
+            ] }
+        });
+        let records = hits_to_records(&response, false).unwrap();
+        assert_eq!(records, vec![record(&[("name", json!("lamp"))])]);
+
+        let records = hits_to_records(&response, true).unwrap();
+        assert_eq!(records[0]["_id"], json!("1"));
+        assert_eq!(records[0]["_index"], json!("products"));
+    }
+}
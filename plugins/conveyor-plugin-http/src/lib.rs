@@ -9,9 +9,11 @@ use conveyor_plugin_api::{
     rstr, FfiDataFormat, PluginCapability, PluginDeclaration, RBox, RBoxError, RErr, RHashMap, ROk,
     RResult, RString, RVec, StageType, PLUGIN_API_VERSION,
 };
+use handlebars::{handlebars_helper, Handlebars};
 use reqwest::{Client, Method};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// HTTP Stage - unified source and sink
@@ -26,10 +28,19 @@ impl HttpStage {
     }
 
     /// Execute as HTTP source (fetch data from URL)
+    ///
+    /// `url`, `body` and header values are Handlebars templates rendered
+    /// against the first record of `input_data`.
     async fn execute_source_async(
         &self,
         config: &HashMap<String, String>,
+        input_data: Option<&FfiDataFormat>,
     ) -> RResult<FfiDataFormat, RBoxError> {
+        let config = &match render_source_config(config, input_data) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
         // Get configuration
         let url = match config.get("url") {
             Some(u) => u,
@@ -47,11 +58,17 @@ impl HttpStage {
             .and_then(|s| s.parse().ok())
             .unwrap_or(30);
 
-        // Build HTTP client
-        let client = match Client::builder()
-            .timeout(Duration::from_secs(timeout_secs))
-            .build()
-        {
+        let accept = match AcceptStatus::from_config(config) {
+            Ok(a) => a,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        // Build HTTP client; redirects the stage accepts are returned, not followed
+        let mut builder = Client::builder().timeout(Duration::from_secs(timeout_secs));
+        if accept.as_ref().is_some_and(AcceptStatus::accepts_redirects) {
+            builder = builder.redirect(reqwest::redirect::Policy::none());
+        }
+        let client = match builder.build() {
             Ok(c) => c,
             Err(e) => {
                 return RErr(RBoxError::from_fmt(&format_args!(
@@ -72,132 +89,103 @@ impl HttpStage {
             }
         };
 
-        // Build request
-        let mut request = client.request(method_enum, url);
+        let retry = match RetryPolicy::from_config(config) {
+            Ok(r) => r,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        // Custom headers and the stage's API key
+        let headers = match request_headers(config) {
+            Ok(h) => h,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
 
-        // Add custom headers (supports both "header." and "headers." prefixes)
-        for (key, value) in config.iter() {
-            if key.starts_with("headers.") {
-                let header_name = key.strip_prefix("headers.").unwrap();
-                request = request.header(header_name, value);
-            } else if key.starts_with("header.") {
-                let header_name = key.strip_prefix("header.").unwrap();
-                request = request.header(header_name, value);
-            }
-        }
+        let source = SourceRequest {
+            client: &client,
+            method: &method_enum,
+            headers: &headers,
+            retry: &retry,
+            accept: accept.as_ref(),
+            debug: debug_http(config),
+            config,
+        };
 
-        // Add body if provided
-        if let Some(body) = config.get("body") {
-            request = request.body(body.clone());
+        let pagination = match Pagination::from_config(config) {
+            Ok(p) => p,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+        if let Some(pagination) = pagination {
+            return match fetch_pages(&source, url, format, &pagination).await {
+                Ok(records) => FfiDataFormat::from_json_records(&records),
+                Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+            };
         }
 
         // Execute request
-        let response = match request.send().await {
+        let response = match source.send(url).await {
             Ok(r) => r,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "HTTP request failed: {}",
-                    e
-                )))
-            }
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
         };
 
-        if !response.status().is_success() {
-            return RErr(RBoxError::from_fmt(&format_args!(
-                "HTTP request failed with status: {}",
-                response.status()
-            )));
-        }
-
-        // Parse response based on format
-        match format {
-            "json" => {
-                let response_text = match response.text().await {
-                    Ok(t) => t,
-                    Err(e) => {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Failed to read response: {}",
-                            e
-                        )))
-                    }
-                };
-
-                let json: Value = match serde_json::from_str(&response_text) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Failed to parse JSON response: {}",
-                            e
-                        )))
-                    }
-                };
-
-                // Convert to records
-                let records = if let Some(array) = json.as_array() {
-                    array
-                        .iter()
-                        .filter_map(|v| {
-                            if let Some(obj) = v.as_object() {
-                                let mut map = HashMap::new();
-                                for (k, v) in obj {
-                                    map.insert(k.clone(), v.clone());
-                                }
-                                Some(map)
-                            } else {
-                                None
-                            }
-                        })
-                        .collect()
-                } else if let Some(obj) = json.as_object() {
-                    let mut map = HashMap::new();
-                    for (k, v) in obj {
-                        map.insert(k.clone(), v.clone());
-                    }
-                    vec![map]
-                } else {
-                    return RErr(RBoxError::from_fmt(&format_args!("Unexpected JSON format")));
-                };
+        let status = response.status();
+        let response_headers = response.headers().clone();
+        let metadata = match response_metadata(status, &response_headers, config) {
+            Ok(m) => m,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
 
-                FfiDataFormat::from_json_records(&records)
+        // Large downloads go straight to disk; only the file's location is emitted
+        if let Some(target) = stream_to_file(config) {
+            if format != "raw" {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "'stream_to_file' requires format = \"raw\""
+                )));
             }
-            "jsonl" => {
-                let text = match response.text().await {
-                    Ok(t) => t,
-                    Err(e) => {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Failed to read response text: {}",
-                            e
-                        )))
-                    }
-                };
+            let path = download_path(target);
+            let written = match stream_body_to_file(response, &path).await {
+                Ok(n) => n,
+                Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+            };
+            if source.debug {
+                log_response(status, &response_headers, b"", config);
+            }
+            let mut record = HashMap::new();
+            record.insert(
+                "path".to_string(),
+                Value::String(path.to_string_lossy().into_owned()),
+            );
+            record.insert("bytes".to_string(), Value::from(written));
+            let mut records = [record];
+            add_metadata(&mut records, metadata.as_ref());
+            return FfiDataFormat::from_json_records(&records);
+        }
 
-                let records: Result<Vec<HashMap<String, Value>>, _> = text
-                    .lines()
-                    .filter(|line| !line.trim().is_empty())
-                    .map(serde_json::from_str)
-                    .collect();
+        let bytes = match source.body(response).await {
+            Ok(b) => b,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
 
-                match records {
-                    Ok(r) => FfiDataFormat::from_json_records(&r),
-                    Err(e) => RErr(RBoxError::from_fmt(&format_args!(
-                        "Failed to parse JSONL: {}",
-                        e
-                    ))),
+        // Parse response based on format
+        match format {
+            "json" | "jsonl" => match parse_records(&bytes, format) {
+                Ok(mut records) => {
+                    add_metadata(&mut records, metadata.as_ref());
+                    FfiDataFormat::from_json_records(&records)
                 }
-            }
-            "raw" => {
-                let bytes = match response.bytes().await {
-                    Ok(b) => b.to_vec(),
-                    Err(e) => {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Failed to read response bytes: {}",
-                            e
-                        )))
-                    }
-                };
+                Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+            },
+            // With metadata, the bytes travel base64-encoded in a `body` field beside it
+            "raw" => match metadata {
+                Some(_) => {
+                    use base64::Engine;
 
-                ROk(FfiDataFormat::from_raw(bytes))
-            }
+                    let body = base64::engine::general_purpose::STANDARD.encode(&bytes);
+                    let mut records = [HashMap::from([("body".to_string(), Value::String(body))])];
+                    add_metadata(&mut records, metadata.as_ref());
+                    FfiDataFormat::from_json_records(&records)
+                }
+                None => ROk(FfiDataFormat::from_raw(bytes)),
+            },
             _ => RErr(RBoxError::from_fmt(&format_args!(
                 "Unsupported format: {}",
                 format
@@ -227,11 +215,17 @@ impl HttpStage {
             .and_then(|s| s.parse().ok())
             .unwrap_or(30);
 
-        // Build HTTP client
-        let client = match Client::builder()
-            .timeout(Duration::from_secs(timeout_secs))
-            .build()
-        {
+        let accept = match AcceptStatus::from_config(config) {
+            Ok(a) => a,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        // Build HTTP client; redirects the stage accepts are returned, not followed
+        let mut builder = Client::builder().timeout(Duration::from_secs(timeout_secs));
+        if accept.as_ref().is_some_and(AcceptStatus::accepts_redirects) {
+            builder = builder.redirect(reqwest::redirect::Policy::none());
+        }
+        let client = match builder.build() {
             Ok(c) => c,
             Err(e) => {
                 return RErr(RBoxError::from_fmt(&format_args!(
@@ -247,40 +241,10 @@ impl HttpStage {
             RErr(e) => return RErr(e),
         };
 
-        // Get format (default: json)
-        let format = config.get("format").map(|s| s.as_str()).unwrap_or("json");
-
-        // Serialize body based on format
-        let body = match format {
-            "json" => match serde_json::to_string(&records) {
-                Ok(s) => s,
-                Err(e) => {
-                    return RErr(RBoxError::from_fmt(&format_args!(
-                        "Failed to serialize JSON: {}",
-                        e
-                    )))
-                }
-            },
-            "jsonl" => {
-                let lines: Result<Vec<String>, _> =
-                    records.iter().map(serde_json::to_string).collect();
-
-                match lines {
-                    Ok(l) => l.join("\n"),
-                    Err(e) => {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Failed to serialize JSONL: {}",
-                            e
-                        )))
-                    }
-                }
-            }
-            _ => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Invalid format: {}. Use 'json' or 'jsonl'",
-                    format
-                )))
-            }
+        // One body per request, depending on batch_mode
+        let bodies = match build_request_bodies(&records, config) {
+            Ok(b) => b,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
         };
 
         // Parse method
@@ -294,39 +258,76 @@ impl HttpStage {
             }
         };
 
-        // Build request
-        let mut request = client
-            .request(method_enum, url)
-            .header("Content-Type", "application/json")
-            .body(body);
+        let headers = match request_headers(config) {
+            Ok(h) => h,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+        let retry = match RetryPolicy::from_config(config) {
+            Ok(r) => r,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+        let debug = debug_http(config);
+
+        for body in bodies {
+            // Build the request afresh for every attempt, with custom headers
+            // and the stage's API key
+            let build_request = || {
+                let mut request = client
+                    .request(method_enum.clone(), url)
+                    .header("Content-Type", "application/json")
+                    .body(body.clone());
+                for (name, value) in &headers {
+                    request = request.header(name, value);
+                }
+                request
+            };
 
-        // Add custom headers (supports both "header." and "headers." prefixes)
-        for (key, value) in config.iter() {
-            if key.starts_with("headers.") {
-                let header_name = key.strip_prefix("headers.").unwrap();
-                request = request.header(header_name, value);
-            } else if key.starts_with("header.") {
-                let header_name = key.strip_prefix("header.").unwrap();
-                request = request.header(header_name, value);
+            if debug {
+                let mut logged = vec![("Content-Type".to_string(), "application/json".to_string())];
+                logged.extend(headers.iter().cloned());
+                log_request(&method_enum, url, &logged, config);
             }
-        }
 
-        // Send request
-        let response = match request.send().await {
-            Ok(r) => r,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "HTTP request failed: {}",
-                    e
-                )))
+            // Send request
+            let response = match retry.send(build_request).await {
+                Ok(r) => r,
+                Err(e) => {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "HTTP request failed: {}",
+                        e
+                    )))
+                }
+            };
+
+            if !is_accepted(response.status(), accept.as_ref()) {
+                let status = response.status();
+                let response_headers = response.headers().clone();
+                let body = match error_body(response, config).await {
+                    Ok(b) => b,
+                    Err(e) => format!("<{}>", e),
+                };
+                if debug {
+                    log_response(status, &response_headers, body.as_bytes(), config);
+                }
+                return if body.is_empty() {
+                    RErr(RBoxError::from_fmt(&format_args!(
+                        "HTTP request failed with status: {}",
+                        status
+                    )))
+                } else {
+                    RErr(RBoxError::from_fmt(&format_args!(
+                        "HTTP request failed with status: {}, response body: {}",
+                        status, body
+                    )))
+                };
             }
-        };
 
-        if !response.status().is_success() {
-            return RErr(RBoxError::from_fmt(&format_args!(
-                "HTTP request failed with status: {}",
-                response.status()
-            )));
+            if debug {
+                let status = response.status();
+                let response_headers = response.headers().clone();
+                let body = response.bytes().await.unwrap_or_default();
+                log_response(status, &response_headers, &body, config);
+            }
         }
 
         // Return the original data (sinks pass through data)
@@ -334,148 +335,1374 @@ impl HttpStage {
     }
 }
 
-impl FfiStage for HttpStage {
-    fn name(&self) -> conveyor_plugin_api::RStr<'_> {
-        self.name.as_str().into()
+/// Everything the source needs to send one request
+struct SourceRequest<'a> {
+    client: &'a Client,
+    method: &'a Method,
+    headers: &'a [(String, String)],
+    retry: &'a RetryPolicy,
+    accept: Option<&'a AcceptStatus>,
+    debug: bool,
+    config: &'a HashMap<String, String>,
+}
+
+impl SourceRequest<'_> {
+    /// Send the request to `url`, retrying per the policy, and fail on a status
+    /// the stage does not accept
+    async fn send(&self, url: &str) -> Result<reqwest::Response, String> {
+        if self.debug {
+            log_request(self.method, url, self.headers, self.config);
+        }
+
+        // Build the request afresh for every attempt
+        let build_request = || {
+            let mut request = self.client.request(self.method.clone(), url);
+            for (name, value) in self.headers {
+                request = request.header(name, value);
+            }
+            if let Some(body) = self.config.get("body") {
+                request = request.body(body.clone());
+            }
+            request
+        };
+
+        let response = self
+            .retry
+            .send(build_request)
+            .await
+            .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+        let status = response.status();
+        if !is_accepted(status, self.accept) {
+            if self.debug {
+                let headers = response.headers().clone();
+                let body = response.bytes().await.unwrap_or_default();
+                log_response(status, &headers, &body, self.config);
+            }
+            return Err(format!("HTTP request failed with status: {}", status));
+        }
+        Ok(response)
     }
 
-    fn stage_type(&self) -> StageType {
-        self.stage_type
+    /// Read the whole response body
+    async fn body(&self, response: reqwest::Response) -> Result<Vec<u8>, String> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        if self.debug {
+            log_response(status, &headers, &bytes, self.config);
+        }
+        Ok(bytes.to_vec())
     }
+}
 
-    fn execute(&self, context: FfiExecutionContext) -> RResult<FfiDataFormat, RBoxError> {
-        // Convert config to HashMap
-        let config: HashMap<String, String> = context
-            .config
-            .into_iter()
-            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
-            .collect();
+/// Records of a `json` or `jsonl` response body
+fn parse_records(bytes: &[u8], format: &str) -> Result<Vec<HashMap<String, Value>>, String> {
+    match format {
+        "json" => {
+            let json: Value = serde_json::from_slice(bytes)
+                .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
+            json_records(&json)
+        }
+        _ => String::from_utf8_lossy(bytes)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to parse JSONL: {}", e)),
+    }
+}
 
-        // Use tokio runtime to execute async code
-        let runtime = match tokio::runtime::Runtime::new() {
-            Ok(rt) => rt,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Failed to create runtime: {}",
-                    e
-                )))
+/// Records of a JSON response: the objects of an array, or a single object
+fn json_records(json: &Value) -> Result<Vec<HashMap<String, Value>>, String> {
+    let to_record = |obj: &serde_json::Map<String, Value>| -> HashMap<String, Value> {
+        obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    };
+    if let Some(array) = json.as_array() {
+        Ok(array
+            .iter()
+            .filter_map(|v| v.as_object().map(to_record))
+            .collect())
+    } else if let Some(obj) = json.as_object() {
+        Ok(vec![to_record(obj)])
+    } else {
+        Err("Unexpected JSON format".to_string())
+    }
+}
+
+/// How the next page is requested
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PaginationType {
+    /// `offset_param` advanced by the records received so far
+    Offset,
+    /// `page_param` counting up from `start_page`
+    Page,
+    /// `cursor_param` set to the value at `next_cursor_path` in the last response
+    Cursor,
+    /// The `rel="next"` URL of the `Link` header
+    LinkHeader,
+}
+
+/// Default cap on the pages a paginated source fetches
+const DEFAULT_MAX_PAGES: usize = 100;
+
+/// Pagination of the HTTP source, read from the `pagination.*` options
+///
+/// Pages are fetched until one comes back empty, has no next cursor or link,
+/// or `pagination.max_pages` pages have been fetched.
+#[derive(Debug, Clone, PartialEq)]
+struct Pagination {
+    kind: PaginationType,
+    /// Query parameter carrying the offset, page number or cursor
+    param: String,
+    limit_param: String,
+    limit: Option<u64>,
+    start_page: u64,
+    next_cursor_path: Option<String>,
+    records_path: Option<String>,
+    max_pages: usize,
+}
+
+impl Pagination {
+    fn from_config(config: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        let option = |name: &str| config.get(&format!("pagination.{}", name));
+
+        let Some(kind) = option("type") else {
+            if let Some(key) = config.keys().find(|k| k.starts_with("pagination.")) {
+                return Err(format!("'{}' requires 'pagination.type'", key));
             }
+            return Ok(None);
+        };
+        let (kind, default_param) = match kind.as_str() {
+            "offset" => (PaginationType::Offset, "offset"),
+            "page" => (PaginationType::Page, "page"),
+            "cursor" => (PaginationType::Cursor, "cursor"),
+            "link_header" => (PaginationType::LinkHeader, ""),
+            other => {
+                return Err(format!(
+                    "Invalid pagination.type: {}. Use 'offset', 'page', 'cursor' or 'link_header'",
+                    other
+                ))
+            }
+        };
+        let param_option = match kind {
+            PaginationType::Offset => "offset_param",
+            PaginationType::Page => "page_param",
+            PaginationType::Cursor => "cursor_param",
+            PaginationType::LinkHeader => "",
         };
 
-        runtime.block_on(async {
-            match self.stage_type {
-                StageType::Source => {
-                    // Source mode: fetch from HTTP
-                    self.execute_source_async(&config).await
-                }
-                StageType::Sink => {
-                    // Sink mode: send to HTTP
-                    // Get input data (should have exactly one input)
-                    let input_data = match context.inputs.into_iter().next() {
-                        Some(tuple) => tuple.1,
-                        None => {
-                            return RErr(RBoxError::from_fmt(&format_args!(
-                                "HTTP sink requires input data"
-                            )))
-                        }
-                    };
-
-                    self.execute_sink_async(&input_data, &config).await
-                }
-                StageType::Transform => RErr(RBoxError::from_fmt(&format_args!(
-                    "HTTP transform not supported in this plugin"
-                ))),
+        let positive = |name: &str| -> Result<Option<u64>, String> {
+            option(name)
+                .map(|v| {
+                    v.parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("'pagination.{}' must be a positive integer", name))
+                })
+                .transpose()
+        };
+        let pointer = |name: &str| -> Result<Option<String>, String> {
+            match option(name) {
+                Some(p) if !p.starts_with('/') => Err(format!(
+                    "'pagination.{}' must be a JSON pointer such as /meta/next_cursor",
+                    name
+                )),
+                other => Ok(other.cloned()),
             }
-        })
+        };
+
+        let pagination = Self {
+            kind,
+            param: option(param_option)
+                .cloned()
+                .unwrap_or_else(|| default_param.to_string()),
+            limit_param: option("limit_param")
+                .cloned()
+                .unwrap_or_else(|| "limit".to_string()),
+            limit: positive("limit")?,
+            start_page: match option("start_page") {
+                Some(v) => v
+                    .parse()
+                    .map_err(|_| "'pagination.start_page' must be a non-negative integer")?,
+                None => 1,
+            },
+            next_cursor_path: pointer("next_cursor_path")?,
+            records_path: pointer("records_path")?,
+            max_pages: positive("max_pages")?
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_MAX_PAGES),
+        };
+
+        if kind == PaginationType::Cursor && pagination.next_cursor_path.is_none() {
+            return Err(
+                "pagination.type = \"cursor\" requires 'pagination.next_cursor_path'".to_string(),
+            );
+        }
+        let format = config.get("format").map(|s| s.as_str()).unwrap_or("json");
+        if format == "raw" || stream_to_file(config).is_some() {
+            return Err("Pagination requires format = \"json\" or \"jsonl\"".to_string());
+        }
+        if format != "json"
+            && (pagination.records_path.is_some() || pagination.next_cursor_path.is_some())
+        {
+            return Err(
+                "'pagination.records_path' and 'pagination.next_cursor_path' require format = \"json\""
+                    .to_string(),
+            );
+        }
+
+        Ok(Some(pagination))
     }
+}
 
-    fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError> {
-        // Check required fields
-        if !config.contains_key("url") {
-            return RErr(RBoxError::from_fmt(&format_args!(
-                "Missing required 'url' configuration"
-            )));
+/// Fetch every page of a paginated source and concatenate their records
+///
+/// With `include_response_metadata`, each record carries the status and
+/// headers of the page it came from.
+async fn fetch_pages(
+    source: &SourceRequest<'_>,
+    url: &str,
+    format: &str,
+    pagination: &Pagination,
+) -> Result<Vec<HashMap<String, Value>>, String> {
+    let base = match pagination.limit {
+        Some(limit) => with_query(url, &pagination.limit_param, &limit.to_string())?,
+        None => url.to_string(),
+    };
+    let mut offset = 0usize;
+    let mut page = pagination.start_page;
+    let mut next_url = Some(match pagination.kind {
+        PaginationType::Offset => with_query(&base, &pagination.param, "0")?,
+        PaginationType::Page => with_query(&base, &pagination.param, &page.to_string())?,
+        PaginationType::Cursor | PaginationType::LinkHeader => base.clone(),
+    });
+
+    let mut records = Vec::new();
+    for fetched in 1..=pagination.max_pages {
+        let Some(page_url) = next_url.take() else {
+            break;
+        };
+        let response = source.send(&page_url).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let metadata = response_metadata(status, &headers, source.config)?;
+        let bytes = source.body(response).await?;
+
+        let (mut page_records, json) = if format == "json" {
+            let json: Value = serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
+            let page_records = match &pagination.records_path {
+                Some(path) => match json.pointer(path) {
+                    Some(array @ Value::Array(_)) => json_records(array)?,
+                    Some(Value::Null) | None => Vec::new(),
+                    Some(_) => {
+                        return Err(format!(
+                            "'pagination.records_path' {} is not an array",
+                            path
+                        ))
+                    }
+                },
+                None => json_records(&json)?,
+            };
+            (page_records, Some(json))
+        } else {
+            (parse_records(&bytes, format)?, None)
+        };
+
+        let received = page_records.len();
+        add_metadata(&mut page_records, metadata.as_ref());
+        records.extend(page_records);
+        if received == 0 {
+            break;
         }
 
-        // Validate method if provided
-        if let Some(method) = config.get("method") {
-            let method_str = method.as_str().to_uppercase();
-            match self.stage_type {
-                StageType::Source => {
-                    if !["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD"]
-                        .contains(&method_str.as_str())
-                    {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Invalid HTTP method: {}",
-                            method_str
-                        )));
+        next_url = match pagination.kind {
+            PaginationType::Offset => {
+                offset += received;
+                Some(with_query(&base, &pagination.param, &offset.to_string())?)
+            }
+            PaginationType::Page => {
+                page += 1;
+                Some(with_query(&base, &pagination.param, &page.to_string())?)
+            }
+            PaginationType::Cursor => {
+                let path = pagination.next_cursor_path.as_deref().unwrap_or_default();
+                match json.as_ref().and_then(|j| j.pointer(path)) {
+                    Some(Value::String(cursor)) if !cursor.is_empty() => {
+                        Some(with_query(&base, &pagination.param, cursor)?)
                     }
-                }
-                StageType::Sink => {
-                    if !["POST", "PUT", "PATCH"].contains(&method_str.as_str()) {
-                        return RErr(RBoxError::from_fmt(&format_args!(
-                            "Invalid HTTP method for sink: {}",
-                            method_str
-                        )));
+                    Some(Value::Number(cursor)) => {
+                        Some(with_query(&base, &pagination.param, &cursor.to_string())?)
                     }
+                    _ => None,
                 }
-                _ => {}
             }
-        }
+            PaginationType::LinkHeader => next_link(&headers, &page_url),
+        };
 
-        // Validate format if provided
-        if let Some(format) = config.get("format") {
-            let format_str = format.as_str();
-            if !["json", "jsonl", "raw"].contains(&format_str) {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Invalid format: {}",
-                    format_str
-                )));
-            }
+        if fetched == pagination.max_pages && next_url.is_some() {
+            tracing::warn!(
+                "Stopped paginating {} after pagination.max_pages = {} pages",
+                redact_url(url),
+                pagination.max_pages
+            );
         }
-
-        ROk(())
     }
+
+    Ok(records)
 }
 
-// Factory functions
-#[no_mangle]
-pub extern "C" fn create_http_source() -> FfiStage_TO<'static, RBox<()>> {
-    FfiStage_TO::from_value(
-        HttpStage::new("http".to_string(), StageType::Source),
-        TD_Opaque,
-    )
+/// `url` with query parameter `name` set to `value`, replacing any earlier value
+fn with_query(url: &str, name: &str, value: &str) -> Result<String, String> {
+    let mut parsed = reqwest::Url::parse(url)
+        .map_err(|e| format!("Invalid URL '{}': {}", redact_url(url), e))?;
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| k != name)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(name, value);
+    Ok(parsed.to_string())
 }
 
-#[no_mangle]
-pub extern "C" fn create_http_sink() -> FfiStage_TO<'static, RBox<()>> {
-    FfiStage_TO::from_value(
-        HttpStage::new("http".to_string(), StageType::Sink),
-        TD_Opaque,
-    )
+/// The `rel="next"` target of the `Link` headers, resolved against `current`
+fn next_link(headers: &reqwest::header::HeaderMap, current: &str) -> Option<String> {
+    let base = reqwest::Url::parse(current).ok()?;
+    headers
+        .get_all(reqwest::header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let mut parts = link.split(';');
+            let target = parts.next()?.trim();
+            let is_next = parts.any(|param| {
+                param.trim().strip_prefix("rel=").is_some_and(|rel| {
+                    rel.trim_matches('"')
+                        .split_whitespace()
+                        .any(|r| r.eq_ignore_ascii_case("next"))
+                })
+            });
+            let target = target.strip_prefix('<')?.strip_suffix('>')?;
+            is_next.then(|| base.join(target).ok().map(String::from))?
+        })
 }
 
-// Plugin capabilities
-extern "C" fn get_capabilities() -> RVec<PluginCapability> {
-    vec![
-        PluginCapability::simple(
-            "http",
-            StageType::Source,
-            "HTTP source - fetch data from REST APIs",
-            "create_http_source",
-        ),
-        PluginCapability::simple(
-            "http",
-            StageType::Sink,
-            "HTTP sink - send data to REST APIs",
-            "create_http_sink",
-        ),
-    ]
-    .into()
+/// Copy of the source `config` with the `url`, `body` and `headers.*` values
+/// rendered as templates against the first input record
+///
+/// As for the MongoDB plugin, an empty context is used without input, so a
+/// plain value passes through unchanged.
+fn render_source_config(
+    config: &HashMap<String, String>,
+    input_data: Option<&FfiDataFormat>,
+) -> Result<HashMap<String, String>, String> {
+    let context = match input_data.map(FfiDataFormat::to_json_records) {
+        Some(ROk(records)) if !records.is_empty() => {
+            serde_json::to_value(&records[0]).unwrap_or(serde_json::json!({}))
+        }
+        _ => serde_json::json!({}),
+    };
+
+    let handlebars = body_template_registry();
+    let mut rendered = config.clone();
+    for (key, value) in rendered.iter_mut() {
+        let templated = key == "url"
+            || key == "body"
+            || key.starts_with("headers.")
+            || key.starts_with("header.");
+        if templated && value.contains("{{") {
+            *value = handlebars
+                .render_template(value, &context)
+                .map_err(|e| format!("Template rendering failed for '{}': {}", key, e))?;
+        }
+    }
+    Ok(rendered)
 }
 
-// Plugin declaration
-#[no_mangle]
+/// Handlebars registry for request body templates
+///
+/// Bodies are JSON rather than HTML, so escaping is disabled and a `json`
+/// helper is provided to embed values verbatim (e.g. `{{json this}}`).
+fn body_template_registry() -> Handlebars<'static> {
+    handlebars_helper!(json: |value: Json| serde_json::to_string(value).unwrap_or_default());
+
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars.register_helper("json", Box::new(json));
+    handlebars
+}
+
+/// Serialize records with the configured `format` (json or jsonl)
+fn serialize_records(records: &[HashMap<String, Value>], format: &str) -> Result<String, String> {
+    match format {
+        "json" => {
+            serde_json::to_string(records).map_err(|e| format!("Failed to serialize JSON: {}", e))
+        }
+        "jsonl" => {
+            let lines: Result<Vec<String>, _> = records.iter().map(serde_json::to_string).collect();
+
+            lines
+                .map(|l| l.join("\n"))
+                .map_err(|e| format!("Failed to serialize JSONL: {}", e))
+        }
+        _ => Err(format!("Invalid format: {}. Use 'json' or 'jsonl'", format)),
+    }
+}
+
+/// Collect `header.*`/`headers.*` options plus the API key header from `api_key_env`
+///
+/// The key is read from the environment per stage, so stages talking to
+/// different services can each name their own variable.
+fn request_headers(config: &HashMap<String, String>) -> Result<Vec<(String, String)>, String> {
+    let mut headers = Vec::new();
+    for (key, value) in config.iter() {
+        if let Some(header_name) = key
+            .strip_prefix("headers.")
+            .or_else(|| key.strip_prefix("header."))
+        {
+            headers.push((header_name.to_string(), value.clone()));
+        }
+    }
+
+    if let Some(api_key_env) = config.get("api_key_env") {
+        let api_key = std::env::var(api_key_env)
+            .map_err(|_| format!("Environment variable '{}' not set", api_key_env))?;
+        let header = config
+            .get("api_key_header")
+            .map(|s| s.as_str())
+            .unwrap_or("Authorization");
+        let value = if header.eq_ignore_ascii_case("authorization") {
+            format!("Bearer {}", api_key)
+        } else {
+            api_key
+        };
+        headers.push((header.to_string(), value));
+    }
+
+    Ok(headers)
+}
+
+/// Retry policy for requests
+///
+/// Read from the stage config: `retry_attempts` (total attempts, default 1),
+/// `retry_backoff_ms` (delay before the first retry, default 500) and
+/// `retry_backoff_multiplier` (growth of the delay per retry, default 2.0).
+/// Connection errors, timeouts and 5xx responses are retried, as are 429
+/// responses unless `retry_on_429 = false`. A `Retry-After` header given in
+/// seconds overrides the backoff.
+#[derive(Debug, Clone, PartialEq)]
+struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    backoff_multiplier: f64,
+    retry_on_429: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Duration::from_millis(500),
+            backoff_multiplier: 2.0,
+            retry_on_429: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        if let Some(value) = config.get("retry_attempts") {
+            policy.max_attempts = value
+                .parse()
+                .ok()
+                .filter(|n| *n >= 1)
+                .ok_or_else(|| "'retry_attempts' must be a positive integer".to_string())?;
+        }
+
+        if let Some(value) = config.get("retry_backoff_ms") {
+            let ms: u64 = value
+                .parse()
+                .map_err(|_| "'retry_backoff_ms' must be a non-negative integer".to_string())?;
+            policy.backoff = Duration::from_millis(ms);
+        }
+
+        if let Some(value) = config.get("retry_backoff_multiplier") {
+            policy.backoff_multiplier = value
+                .parse()
+                .ok()
+                .filter(|m: &f64| m.is_finite() && *m >= 1.0)
+                .ok_or_else(|| "'retry_backoff_multiplier' must be a number >= 1.0".to_string())?;
+        }
+
+        if let Some(value) = config.get("retry_on_429") {
+            policy.retry_on_429 = match value.as_str() {
+                "true" => true,
+                "false" => false,
+                _ => return Err("'retry_on_429' must be true or false".to_string()),
+            };
+        }
+
+        Ok(policy)
+    }
+
+    /// Delay before retry number `retry` (1-based)
+    fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .mul_f64(self.backoff_multiplier.powi(retry.saturating_sub(1) as i32))
+    }
+
+    fn is_retryable(&self, status: reqwest::StatusCode) -> bool {
+        status.is_server_error()
+            || (self.retry_on_429 && status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    /// Send the request built by `request` until it gets a response that is
+    /// not worth retrying, or the attempts are used up
+    ///
+    /// The last response is returned whatever its status, so callers report
+    /// failed statuses as before; other 4xx responses are never retried.
+    async fn send<F>(&self, request: F) -> Result<reqwest::Response, reqwest::Error>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 1;
+        loop {
+            let retry_in = match request().send().await {
+                Ok(response)
+                    if attempt < self.max_attempts && self.is_retryable(response.status()) =>
+                {
+                    let delay = retry_after(&response).unwrap_or_else(|| self.delay(attempt));
+                    tracing::warn!(
+                        "HTTP {} from {} on attempt {}/{}. Retrying in {:?}...",
+                        response.status(),
+                        redact_url(response.url().as_str()),
+                        attempt,
+                        self.max_attempts,
+                        delay
+                    );
+                    delay
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if attempt < self.max_attempts && (e.is_connect() || e.is_timeout()) => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!(
+                        "Request to {} failed on attempt {}/{}: {}. Retrying in {:?}...",
+                        e.url().map(|u| redact_url(u.as_str())).unwrap_or_default(),
+                        attempt,
+                        self.max_attempts,
+                        e.without_url(),
+                        delay
+                    );
+                    delay
+                }
+                Err(e) => return Err(e),
+            };
+            tokio::time::sleep(retry_in).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Statuses a stage treats as success, from `accept_status` (e.g. `200-204,302`)
+///
+/// Without `accept_status`, any 2xx status is a success.
+#[derive(Debug, Clone, PartialEq)]
+struct AcceptStatus(Vec<(u16, u16)>);
+
+impl AcceptStatus {
+    fn from_config(config: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        config
+            .get("accept_status")
+            .map(|spec| Self::parse(spec))
+            .transpose()
+    }
+
+    /// Parse a comma-separated list of codes and inclusive `low-high` ranges
+    fn parse(spec: &str) -> Result<Self, String> {
+        let code = |s: &str| -> Result<u16, String> {
+            s.trim()
+                .parse()
+                .ok()
+                .filter(|c| (100..=599).contains(c))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid accept_status '{}': '{}' is not an HTTP status code",
+                        spec,
+                        s.trim()
+                    )
+                })
+        };
+
+        let mut ranges = Vec::new();
+        for part in spec.split(',') {
+            let range = match part.split_once('-') {
+                Some((low, high)) => (code(low)?, code(high)?),
+                None => {
+                    let c = code(part)?;
+                    (c, c)
+                }
+            };
+            if range.0 > range.1 {
+                return Err(format!(
+                    "Invalid accept_status '{}': range '{}' is reversed",
+                    spec,
+                    part.trim()
+                ));
+            }
+            ranges.push(range);
+        }
+        Ok(Self(ranges))
+    }
+
+    fn contains(&self, status: u16) -> bool {
+        self.0
+            .iter()
+            .any(|(low, high)| (*low..=*high).contains(&status))
+    }
+
+    /// Whether any 3xx status is accepted
+    fn accepts_redirects(&self) -> bool {
+        self.0.iter().any(|(low, high)| *low <= 399 && *high >= 300)
+    }
+}
+
+fn is_accepted(status: reqwest::StatusCode, accept: Option<&AcceptStatus>) -> bool {
+    match accept {
+        Some(accept) => accept.contains(status.as_u16()),
+        None => status.is_success(),
+    }
+}
+
+/// Delay requested by a `Retry-After` header given in seconds
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Fields describing the response, when `include_response_metadata = true`
+///
+/// `_http_status` holds the status code. Each header named in
+/// `response_metadata_headers` (comma-separated) becomes an
+/// `_http_header_<name>` field, lowercased with `-` turned into `_`, so
+/// `X-RateLimit-Remaining` is `_http_header_x_ratelimit_remaining`. Headers
+/// missing from the response are null, so every page has the same fields.
+fn response_metadata(
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+    config: &HashMap<String, String>,
+) -> Result<Option<HashMap<String, Value>>, String> {
+    match config.get("include_response_metadata").map(|s| s.as_str()) {
+        None | Some("false") => return Ok(None),
+        Some("true") => {}
+        Some(_) => return Err("'include_response_metadata' must be true or false".to_string()),
+    }
+
+    let mut metadata = HashMap::from([("_http_status".to_string(), Value::from(status.as_u16()))]);
+    for name in metadata_header_names(config)? {
+        let value = headers
+            .get(name.as_str())
+            .map(|v| Value::String(String::from_utf8_lossy(v.as_bytes()).into_owned()))
+            .unwrap_or(Value::Null);
+        metadata.insert(format!("_http_header_{}", name.replace('-', "_")), value);
+    }
+    Ok(Some(metadata))
+}
+
+/// Lowercased header names listed in `response_metadata_headers`
+fn metadata_header_names(config: &HashMap<String, String>) -> Result<Vec<String>, String> {
+    let Some(list) = config.get("response_metadata_headers") else {
+        return Ok(Vec::new());
+    };
+    list.split(',')
+        .map(|name| {
+            let name = name.trim().to_ascii_lowercase();
+            match reqwest::header::HeaderName::from_bytes(name.as_bytes()) {
+                Ok(_) => Ok(name),
+                Err(_) => Err(format!(
+                    "Invalid header name '{}' in response_metadata_headers",
+                    name
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Copy the response metadata into every record
+fn add_metadata(records: &mut [HashMap<String, Value>], metadata: Option<&HashMap<String, Value>>) {
+    if let Some(metadata) = metadata {
+        for record in records {
+            for (key, value) in metadata {
+                record.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Default number of bytes of a failed response body kept in the error
+const DEFAULT_ERROR_BODY_LIMIT: usize = 1024;
+
+/// Upper bound on how much of a failed response body is read and decoded
+const MAX_ERROR_BODY_READ: usize = 1024 * 1024;
+
+/// Read a failed response's body for the error message
+///
+/// Chunked bodies are collected and gzip/deflate `Content-Encoding` is decoded
+/// before `error_body_redact` matches are masked and the text is cut to
+/// `error_body_limit` bytes. A limit of 0 leaves the body out.
+async fn error_body(
+    mut response: reqwest::Response,
+    config: &HashMap<String, String>,
+) -> Result<String, String> {
+    let limit = error_body_limit(config)?;
+    if limit == 0 {
+        return Ok(String::new());
+    }
+    let redact = error_body_redact(config)?;
+
+    let encoding = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase());
+
+    let mut raw = Vec::new();
+    while raw.len() < MAX_ERROR_BODY_READ {
+        match response.chunk().await {
+            Ok(Some(chunk)) => raw.extend_from_slice(&chunk),
+            Ok(None) => break,
+            Err(e) => return Err(format!("failed to read response body: {}", e)),
+        }
+    }
+
+    let decoded = decode_body(&raw, encoding.as_deref())?;
+    let mut text = String::from_utf8_lossy(&decoded).trim().to_string();
+
+    if let Some(redact) = redact {
+        text = redact.replace_all(&text, "[REDACTED]").into_owned();
+    }
+
+    if text.len() > limit {
+        let mut end = limit;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("... (truncated)");
+    }
+
+    Ok(text)
+}
+
+/// Decode a body according to its `Content-Encoding`, leaving unknown encodings as-is
+fn decode_body(raw: &[u8], encoding: Option<&str>) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let limit = MAX_ERROR_BODY_READ as u64;
+    let mut decoded = Vec::new();
+    let result = match encoding {
+        Some("gzip") | Some("x-gzip") => flate2::read::MultiGzDecoder::new(raw)
+            .take(limit)
+            .read_to_end(&mut decoded),
+        Some("deflate") => flate2::read::ZlibDecoder::new(raw)
+            .take(limit)
+            .read_to_end(&mut decoded),
+        _ => return Ok(raw.to_vec()),
+    };
+
+    result.map(|_| decoded).map_err(|e| {
+        format!(
+            "failed to decode {} response body: {}",
+            encoding.unwrap_or_default(),
+            e
+        )
+    })
+}
+
+fn error_body_limit(config: &HashMap<String, String>) -> Result<usize, String> {
+    match config.get("error_body_limit") {
+        Some(s) => s
+            .parse()
+            .map_err(|_| "'error_body_limit' must be a non-negative integer".to_string()),
+        None => Ok(DEFAULT_ERROR_BODY_LIMIT),
+    }
+}
+
+fn error_body_redact(config: &HashMap<String, String>) -> Result<Option<regex::Regex>, String> {
+    config
+        .get("error_body_redact")
+        .map(|pattern| {
+            regex::Regex::new(pattern)
+                .map_err(|e| format!("Invalid error_body_redact pattern: {}", e))
+        })
+        .transpose()
+}
+
+/// Whether `debug_http` asks for requests and responses to be logged
+fn debug_http(config: &HashMap<String, String>) -> bool {
+    config.get("debug_http").is_some_and(|v| v == "true")
+}
+
+/// How much of a response body `debug_http` logs
+const DEBUG_BODY_LIMIT: usize = 1024;
+
+/// Headers (and auth options) whose values never appear in `debug_http` logs
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "auth_bearer",
+    "auth_basic",
+    "cookie",
+    "set-cookie",
+];
+
+/// Header or query parameter names that suggest a credential
+const SECRET_NAME_PATTERN: &str = r"(?i)(key|token|secret|password|passwd|signature|session)";
+
+/// Values that look like credentials whatever header carries them
+const SECRET_VALUE_PATTERN: &str = r"(?i)^(bearer|basic|token|digest)\s+\S|^(sk|pk|rk|ghp|gho|xox[abprs])[-_][a-z0-9]|^[a-z0-9+/=_.-]{32,}$";
+
+/// Mask `value` if the header is known to carry credentials or the value looks like one
+///
+/// The `api_key_header` a stage's `api_key_env` key is sent in is always masked.
+fn redact_header(name: &str, value: &str, config: &HashMap<String, String>) -> String {
+    let api_key_header = config
+        .get("api_key_env")
+        .map(|_| {
+            config
+                .get("api_key_header")
+                .map(|s| s.as_str())
+                .unwrap_or("Authorization")
+        })
+        .unwrap_or_default();
+    let secret_name = regex::Regex::new(SECRET_NAME_PATTERN).ok();
+    let secret_value = regex::Regex::new(SECRET_VALUE_PATTERN).ok();
+
+    let sensitive = SENSITIVE_HEADERS
+        .iter()
+        .any(|h| h.eq_ignore_ascii_case(name))
+        || name.eq_ignore_ascii_case(api_key_header)
+        || secret_name.is_some_and(|re| re.is_match(name))
+        || secret_value.is_some_and(|re| re.is_match(value.trim()));
+
+    if sensitive {
+        "[REDACTED]".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Mask query parameters whose names suggest a credential (e.g. `?api_key=...`)
+fn redact_url(url: &str) -> String {
+    let (Ok(mut parsed), Ok(secret_name)) = (
+        reqwest::Url::parse(url),
+        regex::Regex::new(SECRET_NAME_PATTERN),
+    ) else {
+        return url.to_string();
+    };
+    if parsed.query().is_none() {
+        return url.to_string();
+    }
+
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if secret_name.is_match(&name) {
+                "[REDACTED]".to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// Whether this library has a tracing subscriber of its own
+///
+/// The plugin is loaded as a separate library and does not share the host's
+/// subscriber.
+fn has_subscriber() -> bool {
+    tracing::dispatcher::get_default(|dispatch| !dispatch.is::<tracing::subscriber::NoSubscriber>())
+}
+
+/// Emit a `debug_http` line at debug level
+///
+/// Only called for stages with `debug_http = true`: having asked for the
+/// lines, the user gets them on stderr when no subscriber would record them.
+fn emit_debug(line: String) {
+    if has_subscriber() {
+        tracing::debug!("{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
+
+/// Log a request's method, URL and headers at debug level, with secrets masked
+fn log_request(
+    method: &Method,
+    url: &str,
+    headers: &[(String, String)],
+    config: &HashMap<String, String>,
+) {
+    let headers: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, redact_header(name, value, config)))
+        .collect();
+    emit_debug(format!(
+        "HTTP request: {} {} [{}]",
+        method,
+        redact_url(url),
+        headers.join(", ")
+    ));
+}
+
+/// Log a response's status, headers and body (cut to `DEBUG_BODY_LIMIT`) at debug level
+fn log_response(
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+    body: &[u8],
+    config: &HashMap<String, String>,
+) {
+    let headers: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            format!("{}: {}", name, redact_header(name.as_str(), &value, config))
+        })
+        .collect();
+
+    let mut text = String::from_utf8_lossy(body).into_owned();
+    if text.len() > DEBUG_BODY_LIMIT {
+        let mut end = DEBUG_BODY_LIMIT;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("... (truncated)");
+    }
+
+    emit_debug(format!(
+        "HTTP response: {} [{}] {}",
+        status,
+        headers.join(", "),
+        text
+    ));
+}
+
+/// `stream_to_file` target, unless unset or `false`
+fn stream_to_file(config: &HashMap<String, String>) -> Option<&str> {
+    config
+        .get("stream_to_file")
+        .map(|s| s.as_str())
+        .filter(|s| *s != "false")
+}
+
+/// File a streamed body is written to: the configured path, or a new file in
+/// the system temp directory for `stream_to_file = true`
+fn download_path(target: &str) -> PathBuf {
+    if target != "true" {
+        return PathBuf::from(target);
+    }
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!(
+        "conveyor-http-{}-{}.body",
+        std::process::id(),
+        nanos
+    ))
+}
+
+/// Write a response body to `path` one chunk at a time, returning its size
+///
+/// Chunks go to `<path>.part`, which is renamed once the body is complete, so
+/// a failed download never leaves a truncated file at `path`.
+async fn stream_body_to_file(mut response: reqwest::Response, path: &Path) -> Result<u64, String> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let result = async {
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+        let mut written = 0u64;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+            written += chunk.len() as u64;
+        }
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+        Ok(written)
+    }
+    .await;
+
+    match result {
+        Ok(written) => {
+            tokio::fs::rename(&partial, path)
+                .await
+                .map_err(|e| format!("Failed to move download to {}: {}", path.display(), e))?;
+            Ok(written)
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            Err(e)
+        }
+    }
+}
+
+/// Build the request bodies for the sink
+///
+/// `batch_mode` picks how records are split into requests: `all` (one request,
+/// the default), `single` (one per record) or `chunked` (`batch_size` records
+/// per request). With `body_template`, each body is rendered from the record
+/// (single) or from `{ records: [...] }` (all/chunked) instead of serialized.
+fn build_request_bodies(
+    records: &[HashMap<String, Value>],
+    config: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    let format = config.get("format").map(|s| s.as_str()).unwrap_or("json");
+    let batch_mode = config
+        .get("batch_mode")
+        .map(|s| s.as_str())
+        .unwrap_or("all");
+
+    let template = config.get("body_template");
+    let handlebars = body_template_registry();
+
+    let render = |context: &Value| -> Result<String, String> {
+        handlebars
+            .render_template(template.map(|t| t.as_str()).unwrap_or_default(), context)
+            .map_err(|e| format!("Failed to render body_template: {}", e))
+    };
+
+    match batch_mode {
+        "all" => {
+            let body = match template {
+                Some(_) => render(&serde_json::json!({ "records": records }))?,
+                None => serialize_records(records, format)?,
+            };
+            Ok(vec![body])
+        }
+        "single" => records
+            .iter()
+            .map(|record| match template {
+                Some(_) => render(&serde_json::json!(record)),
+                None => serde_json::to_string(record)
+                    .map_err(|e| format!("Failed to serialize JSON: {}", e)),
+            })
+            .collect(),
+        "chunked" => {
+            let batch_size: usize = match config.get("batch_size") {
+                Some(s) => s
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| "'batch_size' must be a positive integer".to_string())?,
+                None => 100,
+            };
+
+            records
+                .chunks(batch_size)
+                .map(|chunk| match template {
+                    Some(_) => render(&serde_json::json!({ "records": chunk })),
+                    None => serialize_records(chunk, format),
+                })
+                .collect()
+        }
+        _ => Err(format!(
+            "Invalid batch_mode: {}. Use 'all', 'single', or 'chunked'",
+            batch_mode
+        )),
+    }
+}
+
+impl FfiStage for HttpStage {
+    fn name(&self) -> conveyor_plugin_api::RStr<'_> {
+        self.name.as_str().into()
+    }
+
+    fn stage_type(&self) -> StageType {
+        self.stage_type
+    }
+
+    fn execute(&self, context: FfiExecutionContext) -> RResult<FfiDataFormat, RBoxError> {
+        // Convert config to HashMap
+        let config: HashMap<String, String> = context
+            .config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        // Use tokio runtime to execute async code
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Failed to create runtime: {}",
+                    e
+                )))
+            }
+        };
+
+        runtime.block_on(async {
+            match self.stage_type {
+                StageType::Source => {
+                    // Source mode: fetch from HTTP; an input only feeds the templates
+                    let input_data = context.inputs.into_iter().next().map(|tuple| tuple.1);
+                    self.execute_source_async(&config, input_data.as_ref())
+                        .await
+                }
+                StageType::Sink => {
+                    // Sink mode: send to HTTP
+                    // Get input data (should have exactly one input)
+                    let input_data = match context.inputs.into_iter().next() {
+                        Some(tuple) => tuple.1,
+                        None => {
+                            return RErr(RBoxError::from_fmt(&format_args!(
+                                "HTTP sink requires input data"
+                            )))
+                        }
+                    };
+
+                    self.execute_sink_async(&input_data, &config).await
+                }
+                StageType::Transform => RErr(RBoxError::from_fmt(&format_args!(
+                    "HTTP transform not supported in this plugin"
+                ))),
+            }
+        })
+    }
+
+    fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError> {
+        // Check required fields
+        if !config.contains_key("url") {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Missing required 'url' configuration"
+            )));
+        }
+
+        // Validate method if provided
+        if let Some(method) = config.get("method") {
+            let method_str = method.as_str().to_uppercase();
+            match self.stage_type {
+                StageType::Source
+                    if !["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD"]
+                        .contains(&method_str.as_str()) =>
+                {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Invalid HTTP method: {}",
+                        method_str
+                    )));
+                }
+                StageType::Sink if !["POST", "PUT", "PATCH"].contains(&method_str.as_str()) => {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Invalid HTTP method for sink: {}",
+                        method_str
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(debug) = config.get("debug_http") {
+            if !["true", "false"].contains(&debug.as_str()) {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "'debug_http' must be true or false"
+                )));
+            }
+        }
+
+        let retry_config: HashMap<String, String> = config
+            .iter()
+            .filter(|tuple| tuple.0.starts_with("retry_"))
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+        if let Err(e) = RetryPolicy::from_config(&retry_config) {
+            return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+        }
+
+        if let Some(spec) = config.get("accept_status") {
+            if let Err(e) = AcceptStatus::parse(spec) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+            }
+        }
+
+        if config.get("api_key_env").is_some_and(|v| v.is_empty()) {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "'api_key_env' must name an environment variable"
+            )));
+        }
+
+        if self.stage_type == StageType::Source {
+            if let Some(flag) = config.get("include_response_metadata") {
+                if !["true", "false"].contains(&flag.as_str()) {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "'include_response_metadata' must be true or false"
+                    )));
+                }
+            }
+            if let Some(list) = config.get("response_metadata_headers") {
+                let headers =
+                    HashMap::from([("response_metadata_headers".to_string(), list.to_string())]);
+                if let Err(e) = metadata_header_names(&headers) {
+                    return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+                }
+            }
+
+            let source_config: HashMap<String, String> = config
+                .iter()
+                .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+                .collect();
+            if let Err(e) = Pagination::from_config(&source_config) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+            }
+
+            if let Some(target) = config.get("stream_to_file") {
+                if target.is_empty() {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "'stream_to_file' must be a path, true or false"
+                    )));
+                }
+                let format = config.get("format").map(|s| s.as_str()).unwrap_or("json");
+                if target.as_str() != "false" && format != "raw" {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "'stream_to_file' requires format = \"raw\""
+                    )));
+                }
+            }
+        }
+
+        if self.stage_type == StageType::Sink {
+            if let Some(batch_mode) = config.get("batch_mode") {
+                if !["all", "single", "chunked"].contains(&batch_mode.as_str()) {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Invalid batch_mode: {}. Use 'all', 'single', or 'chunked'",
+                        batch_mode
+                    )));
+                }
+            }
+
+            if let Some(batch_size) = config.get("batch_size") {
+                if !matches!(batch_size.parse::<usize>(), Ok(n) if n > 0) {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "'batch_size' must be a positive integer"
+                    )));
+                }
+            }
+
+            if let Some(limit) = config.get("error_body_limit") {
+                if limit.parse::<usize>().is_err() {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "'error_body_limit' must be a non-negative integer"
+                    )));
+                }
+            }
+
+            if let Some(pattern) = config.get("error_body_redact") {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Invalid error_body_redact pattern: {}",
+                        e
+                    )));
+                }
+            }
+
+            // Make sure the template compiles and renders against an empty record
+            if let Some(template) = config.get("body_template") {
+                if let Err(e) =
+                    body_template_registry().render_template(template, &serde_json::json!({}))
+                {
+                    return RErr(RBoxError::from_fmt(&format_args!(
+                        "Invalid body_template: {}",
+                        e
+                    )));
+                }
+            }
+        }
+
+        // Validate format if provided
+        if let Some(format) = config.get("format") {
+            let format_str = format.as_str();
+            if !["json", "jsonl", "raw"].contains(&format_str) {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Invalid format: {}",
+                    format_str
+                )));
+            }
+        }
+
+        ROk(())
+    }
+}
+
+// Factory functions
+#[no_mangle]
+pub extern "C" fn create_http_source() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        HttpStage::new("http".to_string(), StageType::Source),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_http_sink() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        HttpStage::new("http".to_string(), StageType::Sink),
+        TD_Opaque,
+    )
+}
+
+// Plugin capabilities
+extern "C" fn get_capabilities() -> RVec<PluginCapability> {
+    vec![
+        PluginCapability::simple(
+            "http",
+            StageType::Source,
+            "HTTP source - fetch data from REST APIs",
+            "create_http_source",
+        ),
+        PluginCapability::simple(
+            "http",
+            StageType::Sink,
+            "HTTP sink - send data to REST APIs",
+            "create_http_sink",
+        ),
+    ]
+    .into()
+}
+
+// Plugin declaration
+#[no_mangle]
 pub static _plugin_declaration: PluginDeclaration = PluginDeclaration {
     api_version: PLUGIN_API_VERSION,
     name: rstr!("http"),
@@ -484,75 +1711,1114 @@ pub static _plugin_declaration: PluginDeclaration = PluginDeclaration {
     get_capabilities,
 };
 
-// ============================================================================
-// Tests
-// ============================================================================
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_source() {
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        assert_eq!(stage.name(), "http");
+        assert_eq!(stage.stage_type(), StageType::Source);
+    }
+
+    #[test]
+    fn test_http_sink() {
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        assert_eq!(stage.name(), "http");
+        assert_eq!(stage.stage_type(), StageType::Sink);
+    }
+
+    #[test]
+    fn test_plugin_declaration() {
+        assert_eq!(_plugin_declaration.name, "http");
+        assert_eq!(_plugin_declaration.version, "1.0.0");
+        assert!(_plugin_declaration.is_compatible());
+    }
+
+    #[test]
+    fn test_source_validation() {
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let mut config = RHashMap::new();
+
+        // Missing URL should fail
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        // With URL should succeed
+        config.insert(
+            RString::from("url"),
+            RString::from("https://api.example.com"),
+        );
+        assert!(stage.validate_config(config.clone()).is_ok());
+
+        // Invalid method should fail
+        config.insert(RString::from("method"), RString::from("INVALID"));
+        assert!(stage.validate_config(config).is_err());
+    }
+
+    #[test]
+    fn test_sink_validation() {
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        let mut config = RHashMap::new();
+
+        // Missing URL should fail
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        // With URL should succeed
+        config.insert(
+            RString::from("url"),
+            RString::from("https://api.example.com"),
+        );
+        assert!(stage.validate_config(config.clone()).is_ok());
+
+        // GET method should fail for sink
+        config.insert(RString::from("method"), RString::from("GET"));
+        assert!(stage.validate_config(config).is_err());
+    }
+
+    #[test]
+    fn test_api_key_env_per_stage() {
+        std::env::set_var("CONVEYOR_TEST_HTTP_PLUGIN_KEY_A", "key-a");
+        std::env::set_var("CONVEYOR_TEST_HTTP_PLUGIN_KEY_B", "key-b");
+
+        let stage_a = HashMap::from([
+            (
+                "api_key_env".to_string(),
+                "CONVEYOR_TEST_HTTP_PLUGIN_KEY_A".to_string(),
+            ),
+            ("headers.Accept".to_string(), "application/json".to_string()),
+        ]);
+        let stage_b = HashMap::from([
+            (
+                "api_key_env".to_string(),
+                "CONVEYOR_TEST_HTTP_PLUGIN_KEY_B".to_string(),
+            ),
+            ("api_key_header".to_string(), "X-API-Key".to_string()),
+        ]);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut headers_a = request_headers(&stage_a).unwrap();
+        headers_a.sort();
+        assert_eq!(
+            headers_a,
+            vec![
+                ("Accept".to_string(), "application/json".to_string()),
+                ("Authorization".to_string(), "Bearer key-a".to_string()),
+            ]
+        );
+        assert_eq!(
+            request_headers(&stage_b).unwrap(),
+            vec![("X-API-Key".to_string(), "key-b".to_string())]
+        );
 
-    #[test]
-    fn test_http_source() {
-        let stage = HttpStage::new("http".to_string(), StageType::Source);
-        assert_eq!(stage.name(), "http");
-        assert_eq!(stage.stage_type(), StageType::Source);
+        std::env::remove_var("CONVEYOR_TEST_HTTP_PLUGIN_KEY_A");
+        std::env::remove_var("CONVEYOR_TEST_HTTP_PLUGIN_KEY_B");
+
+        assert!(request_headers(&stage_a).is_err());
+    }
+
+    fn sample_records() -> Vec<HashMap<String, Value>> {
+        vec![
+            HashMap::from([
+                ("id".to_string(), serde_json::json!(1)),
+                ("name".to_string(), serde_json::json!("Alice")),
+            ]),
+            HashMap::from([
+                ("id".to_string(), serde_json::json!(2)),
+                ("name".to_string(), serde_json::json!("Bob \"B\"")),
+            ]),
+            HashMap::from([
+                ("id".to_string(), serde_json::json!(3)),
+                ("name".to_string(), serde_json::json!("Carol")),
+            ]),
+        ]
     }
 
     #[test]
-    fn test_http_sink() {
-        let stage = HttpStage::new("http".to_string(), StageType::Sink);
-        assert_eq!(stage.name(), "http");
-        assert_eq!(stage.stage_type(), StageType::Sink);
+    fn test_body_template_single() {
+        let mut config = HashMap::new();
+        config.insert("batch_mode".to_string(), "single".to_string());
+        config.insert(
+            "body_template".to_string(),
+            r#"{"data": {"type": "user", "id": {{id}}, "attributes": {"name": {{json name}}}}}"#
+                .to_string(),
+        );
+
+        let bodies = build_request_bodies(&sample_records(), &config).unwrap();
+        assert_eq!(bodies.len(), 3);
+
+        let second: Value = serde_json::from_str(&bodies[1]).unwrap();
+        assert_eq!(
+            second,
+            serde_json::json!({
+                "data": {"type": "user", "id": 2, "attributes": {"name": "Bob \"B\""}}
+            })
+        );
     }
 
     #[test]
-    fn test_plugin_declaration() {
-        assert_eq!(_plugin_declaration.name, "http");
-        assert_eq!(_plugin_declaration.version, "1.0.0");
-        assert!(_plugin_declaration.is_compatible());
+    fn test_body_template_chunked() {
+        let mut config = HashMap::new();
+        config.insert("batch_mode".to_string(), "chunked".to_string());
+        config.insert("batch_size".to_string(), "2".to_string());
+        config.insert(
+            "body_template".to_string(),
+            r#"{"count": {{len records}}, "items": {{json records}}}"#.to_string(),
+        );
+
+        let bodies = build_request_bodies(&sample_records(), &config).unwrap();
+        assert_eq!(bodies.len(), 2);
+
+        let first: Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(first["count"], serde_json::json!(2));
+        assert_eq!(first["items"][1]["name"], serde_json::json!("Bob \"B\""));
+
+        let last: Value = serde_json::from_str(&bodies[1]).unwrap();
+        assert_eq!(last["count"], serde_json::json!(1));
     }
 
     #[test]
-    fn test_source_validation() {
-        let stage = HttpStage::new("http".to_string(), StageType::Source);
-        let mut config = RHashMap::new();
+    fn test_bodies_without_template() {
+        // Default mode sends everything as one JSON array
+        let bodies = build_request_bodies(&sample_records(), &HashMap::new()).unwrap();
+        assert_eq!(bodies.len(), 1);
+        let all: Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(all.as_array().unwrap().len(), 3);
 
-        // Missing URL should fail
-        assert!(stage.validate_config(config.clone()).is_err());
+        let mut config = HashMap::new();
+        config.insert("batch_mode".to_string(), "single".to_string());
+        let bodies = build_request_bodies(&sample_records(), &config).unwrap();
+        assert_eq!(bodies.len(), 3);
+        let first: Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(first["name"], serde_json::json!("Alice"));
+    }
 
-        // With URL should succeed
+    #[test]
+    fn test_body_template_validation() {
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        let mut config = RHashMap::new();
         config.insert(
             RString::from("url"),
             RString::from("https://api.example.com"),
         );
+        config.insert(RString::from("batch_mode"), RString::from("single"));
+        config.insert(
+            RString::from("body_template"),
+            RString::from(r#"{"id": {{json id}}}"#),
+        );
         assert!(stage.validate_config(config.clone()).is_ok());
 
-        // Invalid method should fail
-        config.insert(RString::from("method"), RString::from("INVALID"));
+        // Unclosed block does not compile
+        config.insert(
+            RString::from("body_template"),
+            RString::from(r#"{"id": {{#if id}}1}"#),
+        );
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.remove(&RString::from("body_template"));
+        config.insert(RString::from("batch_mode"), RString::from("per_row"));
         assert!(stage.validate_config(config).is_err());
     }
 
+    /// Answer the first request with `status`, extra `headers` and `body`, sent chunked
+    async fn serve_once(status: &'static str, headers: &'static str, body: Vec<u8>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+
+                let mut response = format!(
+                    "HTTP/1.1 {}\r\n{}Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+                    status, headers
+                )
+                .into_bytes();
+                for chunk in body.chunks(16) {
+                    response.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+                    response.extend_from_slice(chunk);
+                    response.extend_from_slice(b"\r\n");
+                }
+                response.extend_from_slice(b"0\r\n\r\n");
+
+                let _ = socket.write_all(&response).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}/ingest", addr)
+    }
+
+    /// Byte at `offset` of the body served by `serve_large`
+    fn large_body_byte(offset: usize) -> u8 {
+        (offset % 251) as u8
+    }
+
+    /// Answer the first request with a `len`-byte body, generated while sending
+    async fn serve_large(len: usize) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    len
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let mut sent = 0;
+                while sent < len {
+                    let chunk: Vec<u8> = (sent..(sent + 65536).min(len))
+                        .map(large_body_byte)
+                        .collect();
+                    if socket.write_all(&chunk).await.is_err() {
+                        return;
+                    }
+                    sent += chunk.len();
+                }
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}/export", addr)
+    }
+
+    /// Peak resident set size of this process in KiB (Linux only)
+    fn peak_rss_kib() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))
+            .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse().ok())
+    }
+
+    #[tokio::test]
+    async fn test_source_streams_large_body_to_file() {
+        const LEN: usize = 128 * 1024 * 1024;
+
+        let dir = std::env::temp_dir().join(format!("conveyor-http-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("export.bin");
+
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let config = HashMap::from([
+            ("url".to_string(), serve_large(LEN).await),
+            ("format".to_string(), "raw".to_string()),
+            (
+                "stream_to_file".to_string(),
+                path.to_string_lossy().into_owned(),
+            ),
+        ]);
+
+        let before = peak_rss_kib();
+        let output = match stage.execute_source_async(&config, None).await {
+            ROk(output) => output,
+            RErr(e) => panic!("Download failed: {}", e),
+        };
+        let after = peak_rss_kib();
+
+        // Buffering the body would raise the peak by the full 128 MiB
+        if let (Some(before), Some(after)) = (before, after) {
+            assert!(
+                after - before < 32 * 1024,
+                "peak RSS grew by {} KiB",
+                after - before
+            );
+        }
+
+        let records = output.to_json_records().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0]["path"],
+            Value::String(path.to_string_lossy().into_owned())
+        );
+        assert_eq!(records[0]["bytes"], Value::from(LEN as u64));
+
+        // Compare the file against the served bytes without loading it whole
+        use std::io::Read;
+        let mut file = std::fs::File::open(&path).unwrap();
+        let mut buf = vec![0u8; 1 << 20];
+        let mut offset = 0;
+        loop {
+            let n = file.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            assert!(buf[..n]
+                .iter()
+                .enumerate()
+                .all(|(i, b)| *b == large_body_byte(offset + i)));
+            offset += n;
+        }
+        assert_eq!(offset, LEN);
+        assert!(!dir.join("export.bin.part").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
-    fn test_sink_validation() {
-        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+    fn test_stream_to_file_requires_raw_format() {
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
         let mut config = RHashMap::new();
-
-        // Missing URL should fail
+        config.insert(
+            RString::from("url"),
+            RString::from("https://example.com/export"),
+        );
+        config.insert(RString::from("stream_to_file"), RString::from("true"));
         assert!(stage.validate_config(config.clone()).is_err());
 
-        // With URL should succeed
+        config.insert(RString::from("format"), RString::from("raw"));
+        assert!(stage.validate_config(config).is_ok());
+    }
+
+    async fn send_to(url: String, extra: &[(&str, &str)]) -> String {
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        let mut config = HashMap::from([("url".to_string(), url)]);
+        for (k, v) in extra {
+            config.insert(k.to_string(), v.to_string());
+        }
+        let input = FfiDataFormat::from_json_records(&sample_records()).unwrap();
+
+        match stage.execute_sink_async(&input, &config).await {
+            RErr(e) => e.to_string(),
+            ROk(_) => panic!("Expected the sink to fail"),
+        }
+    }
+
+    /// Answer one request per connection with each of `responses` in turn,
+    /// counting the requests
+    async fn serve_sequence(
+        responses: Vec<&'static str>,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = std::sync::Arc::new(AtomicUsize::new(0));
+
+        let counter = std::sync::Arc::clone(&requests);
+        tokio::spawn(async move {
+            for head in responses {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let mut buf = [0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let response = format!(
+                    "{}\r\nContent-Length: 2\r\nConnection: close\r\n\r\n[]",
+                    head
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (format!("http://{}/ingest", addr), requests)
+    }
+
+    fn retry_config(url: &str, attempts: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("url".to_string(), url.to_string()),
+            ("retry_attempts".to_string(), attempts.to_string()),
+            ("retry_backoff_ms".to_string(), "10".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_retry_backoff_sequence() {
+        let policy = RetryPolicy::from_config(&HashMap::from([
+            ("retry_attempts".to_string(), "4".to_string()),
+            ("retry_backoff_ms".to_string(), "100".to_string()),
+            ("retry_backoff_multiplier".to_string(), "3".to_string()),
+        ]))
+        .unwrap();
+        let delays: Vec<u128> = (1..=3).map(|r| policy.delay(r).as_millis()).collect();
+        assert_eq!(delays, vec![100, 300, 900]);
+
+        assert_eq!(
+            RetryPolicy::from_config(&HashMap::new()).unwrap(),
+            RetryPolicy::default()
+        );
+
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        for (key, value) in [
+            ("retry_attempts", "0"),
+            ("retry_attempts", "three"),
+            ("retry_backoff_ms", "-5"),
+            ("retry_backoff_multiplier", "0.5"),
+            ("retry_on_429", "sometimes"),
+        ] {
+            let mut config = RHashMap::new();
+            config.insert(RString::from("url"), RString::from("http://example.com"));
+            config.insert(RString::from(key), RString::from(value));
+            assert!(
+                stage.validate_config(config).is_err(),
+                "{} = {}",
+                key,
+                value
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_retries_server_errors() {
+        use std::sync::atomic::Ordering;
+
+        let (url, requests) = serve_sequence(vec![
+            "HTTP/1.1 503 Service Unavailable",
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0",
+            "HTTP/1.1 200 OK",
+        ])
+        .await;
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let result = stage
+            .execute_source_async(&retry_config(&url, "3"), None)
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // With 429 retries off the rate limit is reported straight away
+        let (url, requests) =
+            serve_sequence(vec!["HTTP/1.1 429 Too Many Requests", "HTTP/1.1 200 OK"]).await;
+        let mut config = retry_config(&url, "3");
+        config.insert("retry_on_429".to_string(), "false".to_string());
+        let message = match stage.execute_source_async(&config, None).await {
+            RErr(e) => e.to_string(),
+            ROk(_) => panic!("Expected the source to fail"),
+        };
+        assert!(message.contains("429"));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sink_retry_gives_up() {
+        use std::sync::atomic::Ordering;
+
+        // Attempts are used up on repeated 5xx responses
+        let (url, requests) = serve_sequence(vec!["HTTP/1.1 502 Bad Gateway"; 3]).await;
+        let extra = [("retry_attempts", "2"), ("retry_backoff_ms", "10")];
+        let message = send_to(url, &extra).await;
+        assert!(message.contains("502 Bad Gateway"));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Other client errors are not retried
+        let (url, requests) = serve_sequence(vec!["HTTP/1.1 400 Bad Request"; 3]).await;
+        let message = send_to(url, &extra).await;
+        assert!(message.contains("400 Bad Request"));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Connection errors are retried, then reported
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ingest", listener.local_addr().unwrap());
+        drop(listener);
+        let config = retry_config(&url, "2");
+        let input = FfiDataFormat::from_json_records(&sample_records()).unwrap();
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        let started = std::time::Instant::now();
+        assert!(stage.execute_sink_async(&input, &config).await.is_err());
+        assert!(started.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_accept_status_spec() {
+        use reqwest::StatusCode;
+
+        // A single code replaces the 2xx default
+        let accept = AcceptStatus::parse("302").unwrap();
+        assert!(is_accepted(StatusCode::FOUND, Some(&accept)));
+        assert!(!is_accepted(StatusCode::OK, Some(&accept)));
+        assert!(accept.accepts_redirects());
+        assert!(is_accepted(StatusCode::NO_CONTENT, None));
+
+        // Ranges are inclusive and can be mixed with codes
+        let accept = AcceptStatus::parse("200-204, 302").unwrap();
+        assert!(is_accepted(StatusCode::OK, Some(&accept)));
+        assert!(is_accepted(StatusCode::NO_CONTENT, Some(&accept)));
+        assert!(!is_accepted(StatusCode::PARTIAL_CONTENT, Some(&accept)));
+        assert!(is_accepted(StatusCode::FOUND, Some(&accept)));
+        assert!(!AcceptStatus::parse("200-299").unwrap().accepts_redirects());
+
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        for spec in ["", "ok", "200,", "204-200", "200-", "99", "600"] {
+            let err = AcceptStatus::parse(spec).unwrap_err();
+            assert!(err.starts_with("Invalid accept_status"), "{}", err);
+
+            let mut config = RHashMap::new();
+            config.insert(RString::from("url"), RString::from("http://example.com"));
+            config.insert(RString::from("accept_status"), RString::from(spec));
+            assert!(stage.validate_config(config).is_err(), "{}", spec);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_accept_status() {
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+
+        // A redirect the stage accepts is returned instead of followed
+        let (url, _) = serve_sequence(vec!["HTTP/1.1 302 Found\r\nLocation: /elsewhere"]).await;
+        let mut config = HashMap::from([
+            ("url".to_string(), url),
+            ("accept_status".to_string(), "200-204,302".to_string()),
+        ]);
+        assert!(stage.execute_source_async(&config, None).await.is_ok());
+
+        let (url, _) = serve_sequence(vec!["HTTP/1.1 200 OK"]).await;
+        config.insert("url".to_string(), url);
+        config.insert("accept_status".to_string(), "201".to_string());
+        let message = match stage.execute_source_async(&config, None).await {
+            RErr(e) => e.to_string(),
+            ROk(_) => panic!("Expected 200 to be rejected"),
+        };
+        assert!(message.contains("200 OK"));
+    }
+
+    async fn fetch(url: String, extra: &[(&str, &str)]) -> Vec<HashMap<String, Value>> {
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let mut config = HashMap::from([("url".to_string(), url)]);
+        for (k, v) in extra {
+            config.insert(k.to_string(), v.to_string());
+        }
+        stage
+            .execute_source_async(&config, None)
+            .await
+            .unwrap()
+            .to_json_records()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_source_response_metadata() {
+        let headers = "Content-Type: application/json\r\nX-RateLimit-Remaining: 42\r\n";
+        let body = br#"[{"id": 1}, {"id": 2}]"#.to_vec();
+        let extra = [
+            ("include_response_metadata", "true"),
+            (
+                "response_metadata_headers",
+                "X-RateLimit-Remaining, Retry-After",
+            ),
+        ];
+
+        let records = fetch(serve_once("200 OK", headers, body.clone()).await, &extra).await;
+        assert_eq!(records.len(), 2);
+        for record in &records {
+            assert_eq!(record["_http_status"], serde_json::json!(200));
+            assert_eq!(
+                record["_http_header_x_ratelimit_remaining"],
+                serde_json::json!("42")
+            );
+            assert_eq!(record["_http_header_retry_after"], Value::Null);
+        }
+
+        // Without the flag the records are untouched
+        let records = fetch(serve_once("200 OK", headers, body).await, &[]).await;
+        assert_eq!(records[1].keys().collect::<Vec<_>>(), vec!["id"]);
+    }
+
+    #[tokio::test]
+    async fn test_source_raw_response_metadata() {
+        use base64::Engine;
+
+        let url = serve_once(
+            "203 Non-Authoritative Information",
+            "",
+            b"\x00\x01raw".to_vec(),
+        )
+        .await;
+        let records = fetch(
+            url,
+            &[("format", "raw"), ("include_response_metadata", "true")],
+        )
+        .await;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["_http_status"], serde_json::json!(203));
+        let body = records[0]["body"].as_str().unwrap();
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD
+                .decode(body)
+                .unwrap(),
+            b"\x00\x01raw"
+        );
+
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        for (key, value) in [
+            ("include_response_metadata", "yes"),
+            ("response_metadata_headers", "X-Ok, bad header"),
+        ] {
+            let mut config = RHashMap::new();
+            config.insert(RString::from("url"), RString::from("http://example.com"));
+            config.insert(RString::from(key), RString::from(value));
+            assert!(
+                stage.validate_config(config).is_err(),
+                "{} = {}",
+                key,
+                value
+            );
+        }
+    }
+
+    /// Answer one request per connection with each of `pages` (extra headers
+    /// and JSON body) in turn, recording the request targets
+    async fn serve_pages(
+        pages: Vec<(&'static str, String)>,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let targets = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let seen = std::sync::Arc::clone(&targets);
+        tokio::spawn(async move {
+            for (headers, body) in pages {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let mut buf = [0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let target = request.split_whitespace().nth(1).unwrap_or_default();
+                seen.lock().unwrap().push(target.to_string());
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    headers,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (format!("http://{}/items?sort=id", addr), targets)
+    }
+
+    #[tokio::test]
+    async fn test_source_offset_pagination() {
+        let (url, targets) = serve_pages(vec![
+            ("", r#"[{"id": 1}, {"id": 2}]"#.to_string()),
+            ("", r#"[{"id": 3}]"#.to_string()),
+            ("", "[]".to_string()),
+            ("", r#"[{"id": 99}]"#.to_string()),
+        ])
+        .await;
+        let records = fetch(
+            url,
+            &[("pagination.type", "offset"), ("pagination.limit", "2")],
+        )
+        .await;
+        let ids: Vec<&Value> = records.iter().map(|r| &r["id"]).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        // The empty page ends the walk; offsets advance by the records received
+        assert_eq!(
+            *targets.lock().unwrap(),
+            vec![
+                "/items?sort=id&limit=2&offset=0",
+                "/items?sort=id&limit=2&offset=2",
+                "/items?sort=id&limit=2&offset=3",
+            ]
+        );
+
+        // max_pages caps the walk even while pages keep coming
+        let (url, targets) = serve_pages(vec![
+            ("", r#"[{"id": 1}]"#.to_string()),
+            ("", r#"[{"id": 2}]"#.to_string()),
+            ("", r#"[{"id": 3}]"#.to_string()),
+        ])
+        .await;
+        let records = fetch(
+            url,
+            &[("pagination.type", "page"), ("pagination.max_pages", "2")],
+        )
+        .await;
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            *targets.lock().unwrap(),
+            vec!["/items?sort=id&page=1", "/items?sort=id&page=2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_source_cursor_pagination() {
+        let (url, targets) = serve_pages(vec![
+            (
+                "X-RateLimit-Remaining: 9\r\n",
+                r#"{"data": [{"id": 1}, {"id": 2}], "meta": {"next": "c2"}}"#.to_string(),
+            ),
+            (
+                "X-RateLimit-Remaining: 8\r\n",
+                r#"{"data": [{"id": 3}], "meta": {"next": null}}"#.to_string(),
+            ),
+        ])
+        .await;
+        let records = fetch(
+            url,
+            &[
+                ("pagination.type", "cursor"),
+                ("pagination.cursor_param", "after"),
+                ("pagination.next_cursor_path", "/meta/next"),
+                ("pagination.records_path", "/data"),
+                ("include_response_metadata", "true"),
+                ("response_metadata_headers", "X-RateLimit-Remaining"),
+            ],
+        )
+        .await;
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2]["id"], serde_json::json!(3));
+        // Each record carries the headers of its own page
+        assert_eq!(
+            records[0]["_http_header_x_ratelimit_remaining"],
+            serde_json::json!("9")
+        );
+        assert_eq!(
+            records[2]["_http_header_x_ratelimit_remaining"],
+            serde_json::json!("8")
+        );
+        assert_eq!(
+            *targets.lock().unwrap(),
+            vec!["/items?sort=id", "/items?sort=id&after=c2"]
+        );
+
+        // An empty first page ends the walk straight away
+        let (url, targets) = serve_pages(vec![(
+            "",
+            r#"{"data": [], "meta": {"next": "c2"}}"#.to_string(),
+        )])
+        .await;
+        let records = fetch(
+            url,
+            &[
+                ("pagination.type", "cursor"),
+                ("pagination.next_cursor_path", "/meta/next"),
+                ("pagination.records_path", "/data"),
+            ],
+        )
+        .await;
+        assert!(records.is_empty());
+        assert_eq!(targets.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_pagination_config() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::LINK,
+            r#"<https://api.example.com/items?page=1>; rel="prev", </items?page=3>; rel="next""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            next_link(&headers, "https://api.example.com/items?page=2").as_deref(),
+            Some("https://api.example.com/items?page=3")
+        );
+        assert_eq!(
+            next_link(&reqwest::header::HeaderMap::new(), "https://a.example"),
+            None
+        );
+
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let validate = |pairs: &[(&str, &str)]| {
+            let mut config = RHashMap::new();
+            config.insert(RString::from("url"), RString::from("http://example.com"));
+            for (k, v) in pairs {
+                config.insert(RString::from(*k), RString::from(*v));
+            }
+            stage.validate_config(config)
+        };
+        assert!(validate(&[("pagination.type", "link_header")]).is_ok());
+        assert!(validate(&[("pagination.type", "scroll")]).is_err());
+        assert!(validate(&[("pagination.limit", "10")]).is_err());
+        assert!(validate(&[("pagination.type", "cursor")]).is_err());
+        assert!(validate(&[
+            ("pagination.type", "cursor"),
+            ("pagination.next_cursor_path", "meta.next")
+        ])
+        .is_err());
+        assert!(validate(&[("pagination.type", "page"), ("pagination.max_pages", "0")]).is_err());
+        assert!(validate(&[("pagination.type", "offset"), ("format", "raw")]).is_err());
+    }
+
+    /// Answer one request with `[{"request": <the raw request>}]`
+    async fn serve_echo() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                // Read the head, then as much body as Content-Length announces
+                let mut request = Vec::new();
+                let mut buf = [0u8; 8192];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    let complete = text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .and_then(|v| v.trim().parse::<usize>().ok())
+                            })
+                            .unwrap_or(0);
+                        body.len() >= length
+                    });
+                    if n == 0 || complete {
+                        break;
+                    }
+                }
+
+                let body = serde_json::json!([{
+                    "request": String::from_utf8_lossy(&request)
+                }])
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_source_renders_templates_from_input() {
+        let base = serve_echo().await;
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let config = HashMap::from([
+            ("url".to_string(), format!("{}/users/{{{{ id }}}}", base)),
+            ("method".to_string(), "POST".to_string()),
+            (
+                "body".to_string(),
+                r#"{"user": {{ id }}, "name": "{{ name }}", "tags": {{json tags}}}"#.to_string(),
+            ),
+            ("headers.X-Tenant".to_string(), "{{ tenant }}".to_string()),
+        ]);
+        let input = FfiDataFormat::from_json_records(&[HashMap::from([
+            ("id".to_string(), serde_json::json!(42)),
+            ("name".to_string(), serde_json::json!("O'Brien & Co")),
+            ("tags".to_string(), serde_json::json!(["a", "b"])),
+            ("tenant".to_string(), serde_json::json!("acme")),
+        ])])
+        .unwrap();
+
+        let output = match stage.execute_source_async(&config, Some(&input)).await {
+            ROk(output) => output,
+            RErr(e) => panic!("source failed: {}", e),
+        };
+        let records = output.to_json_records().unwrap();
+        let request = records[0]["request"].as_str().unwrap();
+        assert!(
+            request.starts_with("POST /users/42 HTTP/1.1"),
+            "{}",
+            request
+        );
+        assert!(
+            request.to_ascii_lowercase().contains("x-tenant: acme"),
+            "{}",
+            request
+        );
+        // Values are inserted as-is, not HTML-escaped
+        assert!(
+            request.ends_with(r#"{"user": 42, "name": "O'Brien & Co", "tags": ["a","b"]}"#),
+            "{}",
+            request
+        );
+    }
+
+    #[tokio::test]
+    async fn test_source_template_errors() {
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let config = HashMap::from([(
+            "url".to_string(),
+            "http://127.0.0.1:9/users/{{#if id}}".to_string(),
+        )]);
+        let message = match stage.execute_source_async(&config, None).await {
+            ROk(_) => panic!("expected a rendering error"),
+            RErr(e) => e.to_string(),
+        };
+        assert!(
+            message.starts_with("Template rendering failed for 'url':"),
+            "{}",
+            message
+        );
+
+        // Without input a value with no template passes through unchanged
+        let plain = HashMap::from([
+            ("url".to_string(), "http://example.com/a?b={c}".to_string()),
+            ("body".to_string(), "{\"raw\": true}".to_string()),
+        ]);
+        assert_eq!(render_source_config(&plain, None).unwrap(), plain);
+    }
+
+    #[tokio::test]
+    async fn test_sink_error_includes_response_body() {
+        let body = br#"{"error": "invalid_record", "detail": "field 'email' is required", "token": "sk-12345"}"#;
+        let url = serve_once(
+            "400 Bad Request",
+            "Content-Type: application/json\r\n",
+            body.to_vec(),
+        )
+        .await;
+
+        let message = send_to(url, &[("error_body_redact", r"sk-[0-9a-z]+")]).await;
+        assert!(message.contains("400 Bad Request"));
+        assert!(message.contains(r#""detail": "field 'email' is required""#));
+        assert!(message.contains("[REDACTED]"));
+        assert!(!message.contains("sk-12345"));
+    }
+
+    #[tokio::test]
+    async fn test_sink_error_body_gzip_and_limit() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(br#"{"error": "rate_limited", "retry_after": 30}"#)
+            .unwrap();
+        let url = serve_once(
+            "429 Too Many Requests",
+            "Content-Encoding: gzip\r\n",
+            encoder.finish().unwrap(),
+        )
+        .await;
+
+        let message = send_to(url, &[("error_body_limit", "24")]).await;
+        assert!(message.contains(r#"{"error": "rate_limited"... (truncated)"#));
+        assert!(!message.contains("retry_after"));
+    }
+
+    #[test]
+    fn test_error_body_validation() {
+        let stage = HttpStage::new("http".to_string(), StageType::Sink);
+        let mut config = RHashMap::new();
         config.insert(
             RString::from("url"),
             RString::from("https://api.example.com"),
         );
+        config.insert(RString::from("error_body_limit"), RString::from("0"));
+        config.insert(RString::from("error_body_redact"), RString::from("secret"));
         assert!(stage.validate_config(config.clone()).is_ok());
 
-        // GET method should fail for sink
-        config.insert(RString::from("method"), RString::from("GET"));
+        config.insert(RString::from("error_body_limit"), RString::from("-1"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("error_body_limit"), RString::from("512"));
+        config.insert(
+            RString::from("error_body_redact"),
+            RString::from("(unclosed"),
+        );
         assert!(stage.validate_config(config).is_err());
     }
 
+    /// Log lines captured from a `tracing` subscriber installed for one test
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn text(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_http_redacts_authorization() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let url = serve_once(
+            "200 OK",
+            "Content-Type: application/json\r\n",
+            br#"[{"id": 1, "name": "Alice"}]"#.to_vec(),
+        )
+        .await;
+        let stage = HttpStage::new("http".to_string(), StageType::Source);
+        let config = HashMap::from([
+            ("url".to_string(), format!("{}?page=2&api_key=k-123", url)),
+            ("debug_http".to_string(), "true".to_string()),
+            (
+                "header.Authorization".to_string(),
+                "Bearer super-secret-token".to_string(),
+            ),
+            ("header.Accept".to_string(), "application/json".to_string()),
+        ]);
+        assert!(stage.execute_source_async(&config, None).await.is_ok());
+
+        let text = logs.text();
+        assert!(text.contains("HTTP request: GET"));
+        assert!(text.contains("Authorization: [REDACTED]"));
+        assert!(text.contains("Accept: application/json"));
+        assert!(text.contains("page=2&api_key=%5BREDACTED%5D"));
+        assert!(text.contains("HTTP response: 200 OK"));
+        assert!(text.contains(r#"[{"id": 1, "name": "Alice"}]"#));
+        assert!(!text.contains("super-secret-token"));
+        assert!(!text.contains("k-123"));
+    }
+
+    #[test]
+    fn test_redact_header_values() {
+        let config = HashMap::from([
+            ("api_key_env".to_string(), "SERVICE_KEY".to_string()),
+            ("api_key_header".to_string(), "X-Service".to_string()),
+        ]);
+        assert_eq!(redact_header("X-Service", "abc", &config), "[REDACTED]");
+        assert_eq!(
+            redact_header("auth_basic", "dXNlcjpw", &config),
+            "[REDACTED]"
+        );
+        assert_eq!(redact_header("X-Api-Key", "abc", &config), "[REDACTED]");
+        assert_eq!(
+            redact_header("X-Forward", "Basic dXNlcjpw", &config),
+            "[REDACTED]"
+        );
+        assert_eq!(
+            redact_header("X-Custom", "sk-live12345", &config),
+            "[REDACTED]"
+        );
+        assert_eq!(redact_header("X-Request-Id", "req-42", &config), "req-42");
+        assert_eq!(
+            redact_header("Accept", "text/csv", &HashMap::new()),
+            "text/csv"
+        );
+    }
+
     #[test]
     fn test_capabilities() {
         let caps = get_capabilities();
@@ -4,17 +4,20 @@
 //! Uses rdkafka for Apache Kafka integration.
 
 use conveyor_plugin_api::sabi_trait::prelude::*;
-use conveyor_plugin_api::traits::{FfiExecutionContext, FfiStage, FfiStage_TO};
+use conveyor_plugin_api::traits::{FfiBatchSink_TO, FfiExecutionContext, FfiStage, FfiStage_TO};
 use conveyor_plugin_api::{
     rstr, FfiDataFormat, PluginCapability, PluginDeclaration, RBox, RBoxError, RErr, RHashMap, ROk,
     RResult, RString, RVec, StageType, PLUGIN_API_VERSION,
 };
 use rdkafka::{
-    consumer::{Consumer, StreamConsumer},
+    consumer::{CommitMode, Consumer, StreamConsumer},
+    error::{KafkaError, RDKafkaErrorCode},
+    message::{Header, OwnedHeaders},
     producer::{FutureProducer, FutureRecord, Producer},
     ClientConfig, Message,
 };
 use serde_json::Value;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::time::Duration;
 use tokio::time::timeout;
@@ -35,31 +38,89 @@ impl KafkaStage {
         &self,
         config: &HashMap<String, String>,
     ) -> RResult<FfiDataFormat, RBoxError> {
-        let brokers = match config.get("brokers") {
-            Some(b) => b,
-            None => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Missing required 'brokers' configuration"
-                )))
-            }
+        let (topic, group_id) = match source_settings(config) {
+            ROk(settings) => settings,
+            RErr(e) => return RErr(e),
         };
 
-        let topic = match config.get("topic") {
-            Some(t) => t,
-            None => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Missing required 'topic' configuration"
-                )))
-            }
+        let max_messages: usize = config
+            .get("max_messages")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1000);
+
+        let timeout_ms: u64 = config
+            .get("timeout_ms")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(30000);
+
+        let commit = match OffsetCommit::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
         };
 
-        let group_id = match config.get("group_id") {
-            Some(g) => g,
-            None => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Missing required 'group_id' configuration"
-                )))
+        let compression = match ValueCompression::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
+
+        let consumer = match create_consumer(config, group_id, topic, commit) {
+            ROk(c) => c,
+            RErr(e) => return RErr(e),
+        };
+
+        let (policy, dead_letters) = match parse_error_handling(config) {
+            ROk(handling) => handling,
+            RErr(e) => return RErr(e),
+        };
+        let (consumer, policy, dead_letters) = (&consumer, &policy, dead_letters.as_ref());
+        let skipped = &Cell::new(0);
+
+        // Collect messages
+        let mut records = Vec::new();
+        let recv = || async move {
+            receive_record(consumer, compression, policy, dead_letters, skipped).await
+        };
+        let result = consume_messages(
+            recv,
+            max_messages,
+            Duration::from_millis(timeout_ms),
+            |record| {
+                records.push(record);
+                true
+            },
+        )
+        .await;
+        report_skipped(topic, skipped.get());
+
+        if let Err(e) = result {
+            return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+        }
+
+        // Convert to FfiDataFormat
+        let data = match FfiDataFormat::from_json_records(&records) {
+            ROk(data) => data,
+            RErr(e) => return RErr(e),
+        };
+
+        // The whole batch is in hand, so its offsets can be committed
+        if commit == OffsetCommit::Manual {
+            if let Err(e) = commit_offsets(consumer) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
             }
+        }
+
+        ROk(data)
+    }
+
+    /// Execute as Kafka consumer, emitting each message as soon as it is received
+    async fn execute_source_stream_async(
+        &self,
+        config: &HashMap<String, String>,
+        sink: &mut FfiBatchSink_TO<'static, RBox<()>>,
+    ) -> RResult<(), RBoxError> {
+        let (topic, group_id) = match source_settings(config) {
+            ROk(settings) => settings,
+            RErr(e) => return RErr(e),
         };
 
         let max_messages: usize = config
@@ -72,123 +133,60 @@ impl KafkaStage {
             .and_then(|s| s.parse().ok())
             .unwrap_or(30000);
 
-        // Create consumer
-        let consumer: StreamConsumer = match ClientConfig::new()
-            .set("bootstrap.servers", brokers)
-            .set("group.id", group_id)
-            .set("enable.auto.commit", "true")
-            .set("auto.offset.reset", "earliest")
-            .create()
-        {
+        let commit = match OffsetCommit::from_config(config) {
             Ok(c) => c,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Failed to create Kafka consumer: {}",
-                    e
-                )))
-            }
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
         };
 
-        // Subscribe to topic
-        if let Err(e) = consumer.subscribe(&[topic]) {
-            return RErr(RBoxError::from_fmt(&format_args!(
-                "Failed to subscribe to topic: {}",
-                e
-            )));
-        }
+        let compression = match ValueCompression::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
 
-        // Collect messages
-        let mut records = Vec::new();
-        let start = std::time::Instant::now();
-        let timeout_duration = Duration::from_millis(timeout_ms);
+        let consumer = match create_consumer(config, group_id, topic, commit) {
+            ROk(c) => c,
+            RErr(e) => return RErr(e),
+        };
 
-        while records.len() < max_messages {
-            // Check timeout
-            if start.elapsed() >= timeout_duration {
-                break;
-            }
+        let (policy, dead_letters) = match parse_error_handling(config) {
+            ROk(handling) => handling,
+            RErr(e) => return RErr(e),
+        };
+        let (consumer, policy, dead_letters) = (&consumer, &policy, dead_letters.as_ref());
+        let skipped = &Cell::new(0);
 
-            // Try to receive message with timeout
-            let remaining = timeout_duration - start.elapsed();
-            match timeout(remaining, consumer.recv()).await {
-                Ok(Ok(message)) => {
-                    // Extract message data
-                    let payload = message.payload().unwrap_or(&[]);
-                    let key = message
-                        .key()
-                        .map(|k| String::from_utf8_lossy(k).to_string());
-
-                    // Try to parse as JSON
-                    match serde_json::from_slice::<Value>(payload) {
-                        Ok(mut json_value) => {
-                            // Add metadata if JSON object
-                            if let Value::Object(ref mut map) = json_value {
-                                if let Some(k) = key {
-                                    map.insert("_kafka_key".to_string(), Value::String(k));
-                                }
-                                map.insert(
-                                    "_kafka_partition".to_string(),
-                                    Value::Number(message.partition().into()),
-                                );
-                                map.insert(
-                                    "_kafka_offset".to_string(),
-                                    Value::Number(message.offset().into()),
-                                );
-                                if let Some(timestamp) = message.timestamp().to_millis() {
-                                    map.insert(
-                                        "_kafka_timestamp".to_string(),
-                                        Value::Number(timestamp.into()),
-                                    );
-                                }
-                            }
-                            records.push(json_value);
-                        }
-                        Err(_) => {
-                            // If not JSON, create a wrapper object
-                            let mut wrapper = serde_json::Map::new();
-                            wrapper.insert(
-                                "_kafka_payload".to_string(),
-                                Value::String(String::from_utf8_lossy(payload).to_string()),
-                            );
-                            if let Some(k) = key {
-                                wrapper.insert("_kafka_key".to_string(), Value::String(k));
-                            }
-                            wrapper.insert(
-                                "_kafka_partition".to_string(),
-                                Value::Number(message.partition().into()),
-                            );
-                            wrapper.insert(
-                                "_kafka_offset".to_string(),
-                                Value::Number(message.offset().into()),
-                            );
-                            if let Some(timestamp) = message.timestamp().to_millis() {
-                                wrapper.insert(
-                                    "_kafka_timestamp".to_string(),
-                                    Value::Number(timestamp.into()),
-                                );
-                            }
-                            records.push(Value::Object(wrapper));
-                        }
-                    }
-                }
-                Ok(Err(e)) => {
-                    return RErr(RBoxError::from_fmt(&format_args!(
-                        "Error receiving message: {}",
-                        e
-                    )));
-                }
-                Err(_) => {
-                    // Timeout on recv - no more messages
-                    break;
-                }
-            }
+        let mut delivered = true;
+        let recv = || async move {
+            receive_record(consumer, compression, policy, dead_letters, skipped).await
+        };
+        let result = consume_messages(
+            recv,
+            max_messages,
+            Duration::from_millis(timeout_ms),
+            |record| {
+                delivered = match FfiDataFormat::from_json_records(&vec![record]) {
+                    // emit blocks while downstream is busy and returns false once it is gone
+                    ROk(batch) => sink.emit(batch),
+                    RErr(_) => false,
+                };
+                delivered
+            },
+        )
+        .await;
+        report_skipped(topic, skipped.get());
+
+        if let Err(e) = result {
+            return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
         }
 
-        // Convert to FfiDataFormat
-        match FfiDataFormat::from_json_records(&records) {
-            ROk(data) => ROk(data),
-            RErr(e) => RErr(e),
+        // Commit only when downstream took every record; otherwise they are redelivered
+        if commit == OffsetCommit::Manual && delivered {
+            if let Err(e) = commit_offsets(consumer) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+            }
         }
+
+        ROk(())
     }
 
     /// Execute as Kafka producer (sink)
@@ -197,14 +195,11 @@ impl KafkaStage {
         input_data: &FfiDataFormat,
         config: &HashMap<String, String>,
     ) -> RResult<FfiDataFormat, RBoxError> {
-        let brokers = match config.get("brokers") {
-            Some(b) => b,
-            None => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Missing required 'brokers' configuration"
-                )))
-            }
-        };
+        if !config.contains_key("brokers") {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Missing required 'brokers' configuration"
+            )));
+        }
 
         let topic = match config.get("topic") {
             Some(t) => t,
@@ -215,21 +210,16 @@ impl KafkaStage {
             }
         };
 
-        let key_field = config.get("key_field").map(|s| s.as_str());
+        let fields = SinkFields::from_config(config);
+        let compression = match ValueCompression::from_config(config) {
+            Ok(c) => c,
+            Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        };
 
         // Create producer
-        let producer: FutureProducer = match ClientConfig::new()
-            .set("bootstrap.servers", brokers)
-            .set("message.timeout.ms", "30000")
-            .create()
-        {
-            Ok(p) => p,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Failed to create Kafka producer: {}",
-                    e
-                )))
-            }
+        let producer = match create_producer(config) {
+            ROk(p) => p,
+            RErr(e) => return RErr(e),
         };
 
         // Convert data to records
@@ -240,33 +230,14 @@ impl KafkaStage {
 
         // Send messages
         for record in records.iter() {
-            // Extract key if specified
-            let key = if let Some(key_field_name) = key_field {
-                record
-                    .get(key_field_name)
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-            } else {
-                None
-            };
-
-            // Serialize record to JSON
-            let payload = match serde_json::to_string(&record) {
-                Ok(s) => s,
-                Err(e) => {
-                    return RErr(RBoxError::from_fmt(&format_args!(
-                        "Failed to serialize record: {}",
-                        e
-                    )))
-                }
+            let message = match fields
+                .message(record)
+                .and_then(|m| m.compressed(compression))
+            {
+                Ok(m) => m,
+                Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
             };
-
-            // Create Kafka record
-            let mut kafka_record = FutureRecord::to(topic).payload(&payload);
-
-            if let Some(k) = &key {
-                kafka_record = kafka_record.key(k);
-            }
+            let kafka_record = message.record(topic);
 
             // Send and wait
             match producer.send(kafka_record, Duration::from_secs(30)).await {
@@ -293,6 +264,711 @@ impl KafkaStage {
     }
 }
 
+/// Record fields the producer reads message parts from
+#[derive(Debug, Clone, Default)]
+struct SinkFields {
+    key: Option<String>,
+    value: Option<String>,
+    headers: Option<String>,
+    partition: Option<String>,
+}
+
+impl SinkFields {
+    fn from_config(config: &HashMap<String, String>) -> Self {
+        let field = |name: &str| config.get(name).filter(|f| !f.is_empty()).cloned();
+        Self {
+            key: field("key_field"),
+            value: field("value_field"),
+            headers: field("headers_field"),
+            partition: field("partition_field"),
+        }
+    }
+
+    /// Build the message for one record
+    ///
+    /// The payload is the `value_field` value (strings as their raw text, null as
+    /// a tombstone), or else the whole record as JSON without its `headers_field`.
+    fn message(&self, record: &HashMap<String, Value>) -> Result<OutgoingMessage, String> {
+        let key = self
+            .key
+            .as_ref()
+            .and_then(|field| record.get(field))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let payload = match &self.value {
+            Some(field) => match record.get(field) {
+                None => return Err(format!("Record has no value_field '{}'", field)),
+                Some(Value::Null) => None,
+                Some(Value::String(s)) => Some(s.clone().into_bytes()),
+                Some(other) => Some(other.to_string().into_bytes()),
+            },
+            None => {
+                let mut record = record.clone();
+                if let Some(field) = &self.headers {
+                    record.remove(field);
+                }
+                let payload = serde_json::to_vec(&record)
+                    .map_err(|e| format!("Failed to serialize record: {}", e))?;
+                Some(payload)
+            }
+        };
+
+        let headers = match self.headers.as_ref().map(|f| (f, record.get(f))) {
+            None | Some((_, None)) | Some((_, Some(Value::Null))) => None,
+            Some((_, Some(Value::Object(fields)))) => {
+                let mut headers = OwnedHeaders::new_with_capacity(fields.len());
+                for (name, value) in fields {
+                    let value = match value {
+                        Value::Null => None,
+                        Value::String(s) => Some(s.clone()),
+                        other => Some(other.to_string()),
+                    };
+                    headers = headers.insert(Header {
+                        key: name,
+                        value: value.as_deref(),
+                    });
+                }
+                Some(headers)
+            }
+            Some((field, Some(other))) => {
+                return Err(format!(
+                    "headers_field '{}' must hold an object, got {}",
+                    field, other
+                ))
+            }
+        };
+
+        let partition = match self.partition.as_ref().map(|f| (f, record.get(f))) {
+            None | Some((_, None)) | Some((_, Some(Value::Null))) => None,
+            Some((field, Some(value))) => match value.as_i64().map(i32::try_from) {
+                Some(Ok(p)) if p >= 0 => Some(p),
+                _ => {
+                    return Err(format!(
+                        "partition_field '{}' must hold a non-negative integer, got {}",
+                        field, value
+                    ))
+                }
+            },
+        };
+
+        Ok(OutgoingMessage {
+            key,
+            payload,
+            headers,
+            partition,
+        })
+    }
+}
+
+/// Parts of one message to produce
+#[derive(Debug)]
+struct OutgoingMessage {
+    key: Option<String>,
+    payload: Option<Vec<u8>>,
+    headers: Option<OwnedHeaders>,
+    partition: Option<i32>,
+}
+
+impl OutgoingMessage {
+    /// Compress the payload; tombstones stay without one
+    fn compressed(mut self, compression: ValueCompression) -> Result<Self, String> {
+        if let Some(payload) = self.payload.take() {
+            self.payload = Some(compression.compress(payload)?);
+        }
+        Ok(self)
+    }
+
+    fn record<'a>(&'a self, topic: &'a str) -> FutureRecord<'a, String, Vec<u8>> {
+        let mut record = FutureRecord::to(topic);
+        if let Some(key) = &self.key {
+            record = record.key(key);
+        }
+        if let Some(payload) = &self.payload {
+            record = record.payload(payload);
+        }
+        if let Some(headers) = &self.headers {
+            record = record.headers(headers.clone());
+        }
+        if let Some(partition) = self.partition {
+            record = record.partition(partition);
+        }
+        record
+    }
+}
+
+/// Read the required consumer settings (topic, group_id), after checking
+/// that `brokers` is set
+fn source_settings(config: &HashMap<String, String>) -> RResult<(&str, &str), RBoxError> {
+    if !config.contains_key("brokers") {
+        return RErr(RBoxError::from_fmt(&format_args!(
+            "Missing required 'brokers' configuration"
+        )));
+    }
+
+    let topic = match config.get("topic") {
+        Some(t) => t,
+        None => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Missing required 'topic' configuration"
+            )))
+        }
+    };
+
+    let group_id = match config.get("group_id") {
+        Some(g) => g,
+        None => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Missing required 'group_id' configuration"
+            )))
+        }
+    };
+
+    ROk((topic, group_id))
+}
+
+const SECURITY_PROTOCOLS: &[&str] = &["PLAINTEXT", "SSL", "SASL_PLAINTEXT", "SASL_SSL"];
+/// Mechanisms that work with the bundled librdkafka, which is built with
+/// OpenSSL but without Cyrus SASL (so no GSSAPI)
+const SASL_MECHANISMS: &[&str] = &["PLAIN", "SCRAM-SHA-256", "SCRAM-SHA-512"];
+
+/// Client settings shared by the consumer and producer: `brokers` and the
+/// TLS/SASL options, mapped to their rdkafka keys
+fn client_config(config: &HashMap<String, String>) -> Result<ClientConfig, String> {
+    let brokers = config
+        .get("brokers")
+        .ok_or_else(|| "Missing required 'brokers' configuration".to_string())?;
+    let mut client = ClientConfig::new();
+    client.set("bootstrap.servers", brokers);
+
+    let protocol = match config.get("security_protocol") {
+        Some(p) => {
+            let p = p.to_uppercase();
+            if !SECURITY_PROTOCOLS.contains(&p.as_str()) {
+                return Err(format!(
+                    "Invalid 'security_protocol': '{}'. Must be one of: {}",
+                    p,
+                    SECURITY_PROTOCOLS.join(", ")
+                ));
+            }
+            client.set("security.protocol", &p);
+            p
+        }
+        None => "PLAINTEXT".to_string(),
+    };
+
+    let sasl = protocol.starts_with("SASL_");
+    let sasl_keys = ["sasl_mechanism", "sasl_username", "sasl_password"];
+    if !sasl {
+        if let Some(key) = sasl_keys.iter().find(|k| config.contains_key(**k)) {
+            return Err(format!(
+                "'{}' requires security_protocol = SASL_SSL or SASL_PLAINTEXT",
+                key
+            ));
+        }
+    } else {
+        let mechanism = config
+            .get("sasl_mechanism")
+            .map(|m| m.to_uppercase())
+            .ok_or_else(|| format!("security_protocol = {} requires 'sasl_mechanism'", protocol))?;
+        if !SASL_MECHANISMS.contains(&mechanism.as_str()) {
+            return Err(format!(
+                "Invalid 'sasl_mechanism': '{}'. Must be one of: {}",
+                mechanism,
+                SASL_MECHANISMS.join(", ")
+            ));
+        }
+        for key in ["sasl_username", "sasl_password"] {
+            if config.get(key).is_none_or(|v| v.is_empty()) {
+                return Err(format!(
+                    "security_protocol = {} with sasl_mechanism = {} requires '{}'",
+                    protocol, mechanism, key
+                ));
+            }
+        }
+        client
+            .set("sasl.mechanism", &mechanism)
+            .set("sasl.username", &config["sasl_username"])
+            .set("sasl.password", &config["sasl_password"]);
+    }
+
+    if let Some(ca) = config.get("ssl_ca_location") {
+        if !protocol.ends_with("SSL") {
+            return Err(
+                "'ssl_ca_location' requires security_protocol = SSL or SASL_SSL".to_string(),
+            );
+        }
+        client.set("ssl.ca.location", ca);
+    }
+
+    Ok(client)
+}
+
+/// When the consumer commits the offsets it has read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OffsetCommit {
+    /// librdkafka commits in the background as messages are read
+    Auto,
+    /// Offsets are committed once the consumed records were handed over
+    Manual,
+}
+
+impl OffsetCommit {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match config.get("offset_commit").map(|s| s.as_str()) {
+            None | Some("auto") => Ok(OffsetCommit::Auto),
+            Some("manual") => Ok(OffsetCommit::Manual),
+            Some(other) => Err(format!(
+                "Invalid 'offset_commit': '{}'. Must be one of: auto, manual",
+                other
+            )),
+        }
+    }
+}
+
+/// Client settings of a consumer in `group_id`
+fn consumer_config(
+    config: &HashMap<String, String>,
+    group_id: &str,
+    commit: OffsetCommit,
+) -> Result<ClientConfig, String> {
+    let mut client = client_config(config)?;
+    client
+        .set("group.id", group_id)
+        .set(
+            "enable.auto.commit",
+            if commit == OffsetCommit::Auto {
+                "true"
+            } else {
+                "false"
+            },
+        )
+        .set("auto.offset.reset", "earliest");
+    Ok(client)
+}
+
+/// Create a consumer subscribed to `topic`
+fn create_consumer(
+    config: &HashMap<String, String>,
+    group_id: &str,
+    topic: &str,
+    commit: OffsetCommit,
+) -> RResult<StreamConsumer, RBoxError> {
+    let client = match consumer_config(config, group_id, commit) {
+        Ok(c) => c,
+        Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+    };
+    let consumer: StreamConsumer = match client.create() {
+        Ok(c) => c,
+        Err(e) => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Failed to create Kafka consumer: {}",
+                e
+            )))
+        }
+    };
+
+    if let Err(e) = consumer.subscribe(&[topic]) {
+        return RErr(RBoxError::from_fmt(&format_args!(
+            "Failed to subscribe to topic: {}",
+            e
+        )));
+    }
+
+    ROk(consumer)
+}
+
+/// Synchronously commit the offsets of every message consumed so far
+///
+/// Nothing to commit (no message was read) is not an error.
+fn commit_offsets(consumer: &StreamConsumer) -> Result<(), String> {
+    match consumer.commit_consumer_state(CommitMode::Sync) {
+        Ok(()) => Ok(()),
+        Err(KafkaError::ConsumerCommit(RDKafkaErrorCode::NoOffset)) => Ok(()),
+        Err(e) => Err(format!("Failed to commit Kafka offsets: {}", e)),
+    }
+}
+
+/// Create a producer for the stage's brokers
+fn create_producer(config: &HashMap<String, String>) -> RResult<FutureProducer, RBoxError> {
+    let mut client = match client_config(config) {
+        Ok(c) => c,
+        Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+    };
+    match client.set("message.timeout.ms", "30000").create() {
+        Ok(p) => ROk(p),
+        Err(e) => RErr(RBoxError::from_fmt(&format_args!(
+            "Failed to create Kafka producer: {}",
+            e
+        ))),
+    }
+}
+
+/// Compression applied to message values, independent of the producer's
+/// batch-level `compression.type`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl ValueCompression {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match config.get("value_compression").map(|s| s.as_str()) {
+            None | Some("none") => Ok(ValueCompression::None),
+            Some("gzip") => Ok(ValueCompression::Gzip),
+            Some("zstd") => Ok(ValueCompression::Zstd),
+            Some(other) => Err(format!(
+                "Invalid 'value_compression': '{}'. Must be one of: none, gzip, zstd",
+                other
+            )),
+        }
+    }
+
+    fn compress(self, payload: Vec<u8>) -> Result<Vec<u8>, String> {
+        match self {
+            ValueCompression::None => Ok(payload),
+            ValueCompression::Gzip => {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(&payload)
+                    .and_then(|_| encoder.finish())
+                    .map_err(|e| format!("Failed to gzip message value: {}", e))
+            }
+            ValueCompression::Zstd => zstd::encode_all(payload.as_slice(), 0)
+                .map_err(|e| format!("Failed to zstd-compress message value: {}", e)),
+        }
+    }
+
+    fn decompress(self, payload: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>, String> {
+        match self {
+            ValueCompression::None => Ok(payload.into()),
+            ValueCompression::Gzip => {
+                use std::io::Read;
+                let mut decoded = Vec::new();
+                flate2::read::GzDecoder::new(payload)
+                    .read_to_end(&mut decoded)
+                    .map(|_| decoded.into())
+                    .map_err(|e| format!("invalid gzip value: {}", e))
+            }
+            ValueCompression::Zstd => zstd::decode_all(payload)
+                .map(Into::into)
+                .map_err(|e| format!("invalid zstd value: {}", e)),
+        }
+    }
+}
+
+/// How the consumer handles a message whose payload is not valid JSON
+#[derive(Debug, Clone, PartialEq)]
+enum ParseErrorPolicy {
+    /// Wrap the raw payload in a `_kafka_payload` field
+    Wrap,
+    /// Drop the message from the batch
+    Skip,
+    /// Produce the original bytes to the given topic and drop it from the batch
+    DeadLetter(String),
+    /// Abort the batch
+    Fail,
+}
+
+impl ParseErrorPolicy {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match config.get("on_parse_error").map(|s| s.as_str()) {
+            None | Some("wrap") => Ok(ParseErrorPolicy::Wrap),
+            Some("skip") => Ok(ParseErrorPolicy::Skip),
+            Some("fail") => Ok(ParseErrorPolicy::Fail),
+            Some("dead_letter") => match config.get("dead_letter_topic") {
+                Some(topic) if !topic.is_empty() => Ok(ParseErrorPolicy::DeadLetter(topic.clone())),
+                _ => Err("'on_parse_error = dead_letter' requires 'dead_letter_topic'".to_string()),
+            },
+            Some(other) => Err(format!(
+                "Invalid 'on_parse_error': '{}'. Must be one of: wrap, skip, dead_letter, fail",
+                other
+            )),
+        }
+    }
+}
+
+/// Parse `on_parse_error` and create the dead-letter producer when it is needed
+fn parse_error_handling(
+    config: &HashMap<String, String>,
+) -> RResult<(ParseErrorPolicy, Option<FutureProducer>), RBoxError> {
+    let policy = match ParseErrorPolicy::from_config(config) {
+        Ok(p) => p,
+        Err(e) => return RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+    };
+
+    let producer = match policy {
+        ParseErrorPolicy::DeadLetter(_) => match create_producer(config) {
+            ROk(p) => Some(p),
+            RErr(e) => return RErr(e),
+        },
+        _ => None,
+    };
+
+    ROk((policy, producer))
+}
+
+/// An unparseable message bound for the dead-letter topic
+///
+/// Carries the original key and payload bytes untouched; the parse error and
+/// source coordinates travel as `dlq_*` headers.
+#[derive(Debug)]
+struct DeadLetter {
+    topic: String,
+    key: Option<Vec<u8>>,
+    payload: Vec<u8>,
+    headers: OwnedHeaders,
+}
+
+/// Outcome of decoding a single consumed message
+#[derive(Debug)]
+enum Decoded {
+    Record(Value),
+    Skipped,
+    DeadLetter(DeadLetter),
+}
+
+/// Convert a Kafka message into a JSON record with `_kafka_*` metadata
+///
+/// The payload is decompressed according to `compression` before parsing.
+/// What happens to payloads that fail either step is decided by `policy`;
+/// with [`ParseErrorPolicy::Fail`] the error names the offending message.
+fn decode_message<M: Message>(
+    message: &M,
+    compression: ValueCompression,
+    policy: &ParseErrorPolicy,
+) -> Result<Decoded, String> {
+    let payload = message.payload().unwrap_or(&[]);
+    let key = message
+        .key()
+        .map(|k| String::from_utf8_lossy(k).to_string());
+
+    // Tombstones carry no payload, so there is nothing to decompress
+    let value = match payload {
+        [] => Ok(payload.into()),
+        _ => compression.decompress(payload),
+    };
+    let parsed = value.as_ref().map_err(Clone::clone).and_then(|value| {
+        serde_json::from_slice::<Value>(value).map_err(|e| format!("invalid JSON: {}", e))
+    });
+
+    let mut record = match parsed {
+        Ok(json_value) => json_value,
+        Err(e) => match policy {
+            ParseErrorPolicy::Wrap => {
+                // The decompressed text when there is one, else the raw bytes
+                let text = match &value {
+                    Ok(value) => String::from_utf8_lossy(value),
+                    Err(_) => String::from_utf8_lossy(payload),
+                };
+                let mut wrapper = serde_json::Map::new();
+                wrapper.insert(
+                    "_kafka_payload".to_string(),
+                    Value::String(text.to_string()),
+                );
+                Value::Object(wrapper)
+            }
+            ParseErrorPolicy::Skip => {
+                tracing::warn!(
+                    "Skipping unparseable message at {}/{}@{}: {}",
+                    message.topic(),
+                    message.partition(),
+                    message.offset(),
+                    e
+                );
+                return Ok(Decoded::Skipped);
+            }
+            ParseErrorPolicy::DeadLetter(topic) => {
+                let reason = e;
+                let partition = message.partition().to_string();
+                let offset = message.offset().to_string();
+                let headers = OwnedHeaders::new()
+                    .insert(Header {
+                        key: "dlq_error",
+                        value: Some(&reason),
+                    })
+                    .insert(Header {
+                        key: "dlq_source_topic",
+                        value: Some(message.topic()),
+                    })
+                    .insert(Header {
+                        key: "dlq_source_partition",
+                        value: Some(&partition),
+                    })
+                    .insert(Header {
+                        key: "dlq_source_offset",
+                        value: Some(&offset),
+                    });
+
+                return Ok(Decoded::DeadLetter(DeadLetter {
+                    topic: topic.clone(),
+                    key: message.key().map(|k| k.to_vec()),
+                    payload: payload.to_vec(),
+                    headers,
+                }));
+            }
+            ParseErrorPolicy::Fail => {
+                return Err(format!(
+                    "Failed to parse message at {}/{}@{}: {}",
+                    message.topic(),
+                    message.partition(),
+                    message.offset(),
+                    e
+                ))
+            }
+        },
+    };
+
+    // Add metadata if JSON object
+    if let Value::Object(ref mut map) = record {
+        if let Some(k) = key {
+            map.insert("_kafka_key".to_string(), Value::String(k));
+        }
+        map.insert(
+            "_kafka_partition".to_string(),
+            Value::Number(message.partition().into()),
+        );
+        map.insert(
+            "_kafka_offset".to_string(),
+            Value::Number(message.offset().into()),
+        );
+        if let Some(timestamp) = message.timestamp().to_millis() {
+            map.insert(
+                "_kafka_timestamp".to_string(),
+                Value::Number(timestamp.into()),
+            );
+        }
+    }
+
+    Ok(Decoded::Record(record))
+}
+
+/// Receive and decode the next message, producing dead letters along the way
+///
+/// Returns `None` for messages that were consumed but yield no record;
+/// those dropped by `on_parse_error = "skip"` are counted in `skipped`.
+async fn receive_record(
+    consumer: &StreamConsumer,
+    compression: ValueCompression,
+    policy: &ParseErrorPolicy,
+    dead_letters: Option<&FutureProducer>,
+    skipped: &Cell<usize>,
+) -> Result<Option<Value>, String> {
+    let message = consumer
+        .recv()
+        .await
+        .map_err(|e| format!("Error receiving message: {}", e))?;
+
+    match decode_message(&message, compression, policy)? {
+        Decoded::Record(record) => Ok(Some(record)),
+        Decoded::Skipped => {
+            skipped.set(skipped.get() + 1);
+            Ok(None)
+        }
+        Decoded::DeadLetter(letter) => {
+            let producer =
+                dead_letters.ok_or_else(|| "Dead-letter producer is not configured".to_string())?;
+
+            let mut record = FutureRecord::to(&letter.topic)
+                .payload(&letter.payload)
+                .headers(letter.headers);
+            if let Some(key) = &letter.key {
+                record = record.key(key);
+            }
+
+            producer
+                .send(record, Duration::from_secs(30))
+                .await
+                .map_err(|(e, _)| {
+                    format!(
+                        "Failed to produce to dead-letter topic '{}': {}",
+                        letter.topic, e
+                    )
+                })?;
+            Ok(None)
+        }
+    }
+}
+
+/// Summary of the messages a run dropped with `on_parse_error = "skip"`
+fn skipped_summary(topic: &str, skipped: usize) -> Option<String> {
+    (skipped > 0).then(|| {
+        format!(
+            "Skipped {} unparseable message(s) from topic '{}' (on_parse_error = \"skip\")",
+            skipped, topic
+        )
+    })
+}
+
+/// Report the messages a run skipped, once per run
+///
+/// The plugin is loaded as a separate library and does not share the host's
+/// tracing subscriber, so without one of its own the summary goes to stderr.
+fn report_skipped(topic: &str, skipped: usize) {
+    let Some(line) = skipped_summary(topic, skipped) else {
+        return;
+    };
+    let has_subscriber = tracing::dispatcher::get_default(|dispatch| {
+        !dispatch.is::<tracing::subscriber::NoSubscriber>()
+    });
+    if has_subscriber {
+        tracing::warn!("{}", line);
+    } else {
+        eprintln!("WARN conveyor_plugin_kafka: {}", line);
+    }
+}
+
+/// Receive records and hand each one to `on_record` as soon as it arrives
+///
+/// Stops after `max_messages` messages, once `timeout_duration` has elapsed,
+/// or when `on_record` returns `false`. Messages that `recv` resolves to `None`
+/// (skipped or dead-lettered) count towards `max_messages` but are not handed
+/// over. Returns the number of messages consumed.
+async fn consume_messages<R, Fut, E, F>(
+    mut recv: R,
+    max_messages: usize,
+    timeout_duration: Duration,
+    mut on_record: F,
+) -> Result<usize, E>
+where
+    R: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<Option<Value>, E>>,
+    F: FnMut(Value) -> bool,
+{
+    let start = std::time::Instant::now();
+    let mut count = 0;
+
+    while count < max_messages {
+        // Check timeout
+        if start.elapsed() >= timeout_duration {
+            break;
+        }
+
+        // Try to receive message with timeout
+        let remaining = timeout_duration.saturating_sub(start.elapsed());
+        match timeout(remaining, recv()).await {
+            Ok(Ok(Some(record))) => {
+                count += 1;
+                if !on_record(record) {
+                    break;
+                }
+            }
+            Ok(Ok(None)) => count += 1,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => {
+                // Timeout on recv - no more messages
+                break;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
 impl FfiStage for KafkaStage {
     fn name(&self) -> conveyor_plugin_api::RStr<'_> {
         self.name.as_str().into()
@@ -348,6 +1024,40 @@ impl FfiStage for KafkaStage {
         })
     }
 
+    fn execute_stream(
+        &self,
+        context: FfiExecutionContext,
+        mut sink: FfiBatchSink_TO<'static, RBox<()>>,
+    ) -> RResult<(), RBoxError> {
+        if self.stage_type != StageType::Source {
+            return match self.execute(context) {
+                ROk(data) => {
+                    sink.emit(data);
+                    ROk(())
+                }
+                RErr(e) => RErr(e),
+            };
+        }
+
+        let config: HashMap<String, String> = context
+            .config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Failed to create runtime: {}",
+                    e
+                )))
+            }
+        };
+
+        runtime.block_on(self.execute_source_stream_async(&config, &mut sink))
+    }
+
     fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError> {
         // Check required fields
         if !config.contains_key("brokers") {
@@ -387,6 +1097,27 @@ impl FfiStage for KafkaStage {
             }
         }
 
+        let config: HashMap<String, String> = config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+        if let Err(e) = client_config(&config) {
+            return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+        }
+
+        if self.stage_type == StageType::Source {
+            if let Err(e) = ParseErrorPolicy::from_config(&config) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+            }
+            if let Err(e) = OffsetCommit::from_config(&config) {
+                return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+            }
+        }
+
+        if let Err(e) = ValueCompression::from_config(&config) {
+            return RErr(RBoxError::from_fmt(&format_args!("{}", e)));
+        }
+
         ROk(())
     }
 }
@@ -517,6 +1248,596 @@ mod tests {
         assert!(stage.validate_config(config).is_ok());
     }
 
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_client_config_security() {
+        let client = client_config(&settings(&[
+            ("brokers", "kafka-1:9096,kafka-2:9096"),
+            ("security_protocol", "sasl_ssl"),
+            ("sasl_mechanism", "SCRAM-SHA-512"),
+            ("sasl_username", "etl"),
+            ("sasl_password", "s3cret"),
+            ("ssl_ca_location", "/etc/ssl/kafka-ca.pem"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            client.get("bootstrap.servers"),
+            Some("kafka-1:9096,kafka-2:9096")
+        );
+        assert_eq!(client.get("security.protocol"), Some("SASL_SSL"));
+        assert_eq!(client.get("sasl.mechanism"), Some("SCRAM-SHA-512"));
+        assert_eq!(client.get("sasl.username"), Some("etl"));
+        assert_eq!(client.get("sasl.password"), Some("s3cret"));
+        assert_eq!(client.get("ssl.ca.location"), Some("/etc/ssl/kafka-ca.pem"));
+
+        // Without security options only the brokers are set
+        let plain = client_config(&settings(&[("brokers", "localhost:9092")])).unwrap();
+        assert_eq!(plain.get("security.protocol"), None);
+        assert_eq!(plain.get("sasl.mechanism"), None);
+    }
+
+    #[test]
+    fn test_client_config_security_validation() {
+        let sasl_ssl = [
+            ("brokers", "localhost:9092"),
+            ("security_protocol", "SASL_SSL"),
+        ];
+        let err = client_config(&settings(&sasl_ssl)).unwrap_err();
+        assert_eq!(
+            err,
+            "security_protocol = SASL_SSL requires 'sasl_mechanism'"
+        );
+
+        let err = client_config(&settings(
+            &[&sasl_ssl[..], &[("sasl_mechanism", "SCRAM-SHA-256")]].concat(),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "security_protocol = SASL_SSL with sasl_mechanism = SCRAM-SHA-256 requires 'sasl_username'"
+        );
+
+        for bad in [
+            vec![("security_protocol", "TLS")],
+            vec![("sasl_username", "etl")],
+            vec![("ssl_ca_location", "/ca.pem")],
+            vec![
+                ("security_protocol", "SASL_SSL"),
+                ("sasl_mechanism", "GSSAPI"),
+            ],
+        ] {
+            let config = settings(&[&[("brokers", "localhost:9092")][..], &bad].concat());
+            assert!(client_config(&config).is_err(), "{:?}", bad);
+        }
+
+        // Validation rejects the same settings before anything connects
+        let stage = KafkaStage::new("kafka".to_string(), StageType::Sink);
+        let mut config = RHashMap::new();
+        for (k, v) in [
+            ("brokers", "localhost:9092"),
+            ("topic", "events"),
+            ("security_protocol", "SASL_SSL"),
+        ] {
+            config.insert(RString::from(k), RString::from(v));
+        }
+        assert!(stage.validate_config(config.clone()).is_err());
+        for (k, v) in [
+            ("sasl_mechanism", "PLAIN"),
+            ("sasl_username", "etl"),
+            ("sasl_password", "s3cret"),
+        ] {
+            config.insert(RString::from(k), RString::from(v));
+        }
+        assert!(stage.validate_config(config).is_ok());
+    }
+
+    fn record_of(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_sink_message_parts() {
+        use rdkafka::message::Headers;
+
+        let fields = SinkFields::from_config(&settings(&[
+            ("key_field", "user_id"),
+            ("headers_field", "meta"),
+            ("partition_field", "shard"),
+        ]));
+        let record = record_of(serde_json::json!({
+            "user_id": "u-7",
+            "shard": 3,
+            "amount": 12.5,
+            "meta": {"trace_id": "abc", "attempt": 2, "empty": null},
+        }));
+
+        let message = fields.message(&record).unwrap();
+        let kafka_record = message.record("orders");
+        assert_eq!(kafka_record.topic, "orders");
+        assert_eq!(kafka_record.key.map(|k| k.as_str()), Some("u-7"));
+        assert_eq!(kafka_record.partition, Some(3));
+
+        let headers = kafka_record.headers.as_ref().unwrap();
+        let mut headers: Vec<(&str, Option<&[u8]>)> =
+            headers.iter().map(|h| (h.key, h.value)).collect();
+        headers.sort();
+        assert_eq!(
+            headers,
+            vec![
+                ("attempt", Some(&b"2"[..])),
+                ("empty", None),
+                ("trace_id", Some(&b"abc"[..])),
+            ]
+        );
+
+        // The headers object is not repeated in the payload
+        let payload: Value = serde_json::from_slice(kafka_record.payload.unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({"user_id": "u-7", "shard": 3, "amount": 12.5})
+        );
+    }
+
+    #[test]
+    fn test_sink_value_field() {
+        let fields = SinkFields::from_config(&settings(&[("value_field", "body")]));
+
+        let message = fields
+            .message(&record_of(
+                serde_json::json!({"id": 1, "body": "raw,csv,line"}),
+            ))
+            .unwrap();
+        assert_eq!(message.payload.as_deref(), Some(&b"raw,csv,line"[..]));
+        assert!(message.headers.is_none());
+        assert!(message.partition.is_none());
+
+        let message = fields
+            .message(&record_of(serde_json::json!({"body": {"nested": true}})))
+            .unwrap();
+        assert_eq!(message.payload.as_deref(), Some(&b"{\"nested\":true}"[..]));
+
+        // Null is a tombstone
+        let message = fields
+            .message(&record_of(serde_json::json!({"body": null})))
+            .unwrap();
+        assert!(message.record("t").payload.is_none());
+
+        let err = fields
+            .message(&record_of(serde_json::json!({"id": 1})))
+            .unwrap_err();
+        assert_eq!(err, "Record has no value_field 'body'");
+
+        let fields = SinkFields::from_config(&settings(&[
+            ("headers_field", "meta"),
+            ("partition_field", "shard"),
+        ]));
+        let err = fields
+            .message(&record_of(serde_json::json!({"meta": "trace"})))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "headers_field 'meta' must hold an object, got \"trace\""
+        );
+        let err = fields
+            .message(&record_of(serde_json::json!({"shard": -1})))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "partition_field 'shard' must hold a non-negative integer, got -1"
+        );
+    }
+
+    #[test]
+    fn test_offset_commit_mode() {
+        let base = settings(&[("brokers", "localhost:9092")]);
+        let auto =
+            consumer_config(&base, "etl", OffsetCommit::from_config(&base).unwrap()).unwrap();
+        assert_eq!(auto.get("enable.auto.commit"), Some("true"));
+        assert_eq!(auto.get("group.id"), Some("etl"));
+
+        let manual = settings(&[("brokers", "localhost:9092"), ("offset_commit", "manual")]);
+        let commit = OffsetCommit::from_config(&manual).unwrap();
+        assert_eq!(commit, OffsetCommit::Manual);
+        let client = consumer_config(&manual, "etl", commit).unwrap();
+        assert_eq!(client.get("enable.auto.commit"), Some("false"));
+
+        let err = OffsetCommit::from_config(&settings(&[("offset_commit", "eager")])).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid 'offset_commit': 'eager'. Must be one of: auto, manual"
+        );
+
+        let stage = KafkaStage::new("kafka".to_string(), StageType::Source);
+        let mut config = RHashMap::new();
+        for (k, v) in [
+            ("brokers", "localhost:9092"),
+            ("topic", "events"),
+            ("group_id", "etl"),
+            ("offset_commit", "manual"),
+        ] {
+            config.insert(RString::from(k), RString::from(v));
+        }
+        assert!(stage.validate_config(config.clone()).is_ok());
+        config.insert(RString::from("offset_commit"), RString::from("eager"));
+        assert!(stage.validate_config(config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_consume_messages_emits_incrementally() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let received = AtomicUsize::new(0);
+        let mut seen_at_emit = Vec::new();
+
+        let count = consume_messages(
+            || async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                let n = received.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>(Some(serde_json::json!({ "id": n })))
+            },
+            3,
+            Duration::from_secs(5),
+            |record| {
+                // Number of messages received when this record was handed over
+                seen_at_emit.push((record["id"].clone(), received.load(Ordering::SeqCst)));
+                true
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            seen_at_emit,
+            vec![
+                (serde_json::json!(0), 1),
+                (serde_json::json!(1), 2),
+                (serde_json::json!(2), 3),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_consume_messages_stops_when_consumer_gone() {
+        let mut emitted = 0;
+        let count = consume_messages(
+            || async { Ok::<_, String>(Some(serde_json::json!({}))) },
+            100,
+            Duration::from_secs(5),
+            |_| {
+                emitted += 1;
+                emitted < 2
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_consume_messages_timeout() {
+        let count = consume_messages(
+            || async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok::<_, String>(Some(serde_json::json!({})))
+            },
+            100,
+            Duration::from_millis(20),
+            |_| true,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count, 0);
+    }
+
+    fn poison_message() -> rdkafka::message::OwnedMessage {
+        rdkafka::message::OwnedMessage::new(
+            Some(b"\xffnot json".to_vec()),
+            Some(b"key-1".to_vec()),
+            "events".to_string(),
+            rdkafka::Timestamp::NotAvailable,
+            2,
+            42,
+            None,
+        )
+    }
+
+    fn policy(on_parse_error: &str) -> ParseErrorPolicy {
+        let mut config = HashMap::new();
+        config.insert("on_parse_error".to_string(), on_parse_error.to_string());
+        config.insert("dead_letter_topic".to_string(), "events-dlq".to_string());
+        ParseErrorPolicy::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn test_parse_error_wrap() {
+        match decode_message(&poison_message(), ValueCompression::None, &policy("wrap")).unwrap() {
+            Decoded::Record(record) => {
+                assert!(record["_kafka_payload"]
+                    .as_str()
+                    .unwrap()
+                    .ends_with("not json"));
+                assert_eq!(record["_kafka_offset"], serde_json::json!(42));
+            }
+            other => panic!("expected wrapped record, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_skip() {
+        assert!(matches!(
+            decode_message(&poison_message(), ValueCompression::None, &policy("skip")).unwrap(),
+            Decoded::Skipped
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_dead_letter() {
+        use rdkafka::message::Headers;
+
+        let letter = match decode_message(
+            &poison_message(),
+            ValueCompression::None,
+            &policy("dead_letter"),
+        )
+        .unwrap()
+        {
+            Decoded::DeadLetter(letter) => letter,
+            other => panic!("expected dead letter, got {:?}", other),
+        };
+
+        assert_eq!(letter.topic, "events-dlq");
+        assert_eq!(letter.payload, b"\xffnot json".to_vec());
+        assert_eq!(letter.key, Some(b"key-1".to_vec()));
+
+        let header = |name: &str| {
+            letter
+                .headers
+                .iter()
+                .find(|h| h.key == name)
+                .and_then(|h| h.value)
+                .map(|v| String::from_utf8_lossy(v).to_string())
+        };
+        assert!(header("dlq_error").is_some_and(|e| !e.is_empty()));
+        assert_eq!(header("dlq_source_topic").as_deref(), Some("events"));
+        assert_eq!(header("dlq_source_partition").as_deref(), Some("2"));
+        assert_eq!(header("dlq_source_offset").as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_parse_error_fail() {
+        let err =
+            decode_message(&poison_message(), ValueCompression::None, &policy("fail")).unwrap_err();
+        assert!(err.contains("events/2@42"));
+    }
+
+    #[test]
+    fn test_valid_json_ignores_policy() {
+        let message = rdkafka::message::OwnedMessage::new(
+            Some(br#"{"id": 1}"#.to_vec()),
+            None,
+            "events".to_string(),
+            rdkafka::Timestamp::NotAvailable,
+            0,
+            7,
+            None,
+        );
+
+        for name in ["wrap", "skip", "dead_letter", "fail"] {
+            match decode_message(&message, ValueCompression::None, &policy(name)).unwrap() {
+                Decoded::Record(record) => assert_eq!(record["id"], serde_json::json!(1)),
+                other => panic!("expected record under '{}', got {:?}", name, other),
+            }
+        }
+    }
+
+    fn message_with(payload: Option<Vec<u8>>) -> rdkafka::message::OwnedMessage {
+        rdkafka::message::OwnedMessage::new(
+            payload,
+            None,
+            "events".to_string(),
+            rdkafka::Timestamp::NotAvailable,
+            1,
+            9,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_gzip_value_decoded() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(br#"{"id": 5, "event": "login"}"#)
+            .unwrap();
+        let message = message_with(Some(encoder.finish().unwrap()));
+
+        match decode_message(&message, ValueCompression::Gzip, &policy("fail")).unwrap() {
+            Decoded::Record(record) => {
+                assert_eq!(record["id"], serde_json::json!(5));
+                assert_eq!(record["event"], serde_json::json!("login"));
+                assert_eq!(record["_kafka_offset"], serde_json::json!(9));
+            }
+            other => panic!("expected record, got {:?}", other),
+        }
+
+        // Compressed bytes are not mistaken for an uncompressed value
+        assert!(decode_message(&message, ValueCompression::None, &policy("fail")).is_err());
+    }
+
+    #[test]
+    fn test_value_compression_round_trip() {
+        let fields = SinkFields::default();
+        let record = record_of(serde_json::json!({"id": 3, "tags": ["a", "b"]}));
+
+        for compression in [
+            ValueCompression::None,
+            ValueCompression::Gzip,
+            ValueCompression::Zstd,
+        ] {
+            let message = fields
+                .message(&record)
+                .unwrap()
+                .compressed(compression)
+                .unwrap();
+            let consumed = message_with(message.payload);
+            match decode_message(&consumed, compression, &policy("fail")).unwrap() {
+                Decoded::Record(decoded) => {
+                    assert_eq!(decoded["id"], serde_json::json!(3));
+                    assert_eq!(decoded["tags"], serde_json::json!(["a", "b"]));
+                }
+                other => panic!("expected record for {:?}, got {:?}", compression, other),
+            }
+        }
+
+        // Tombstones stay empty
+        let fields = SinkFields::from_config(&settings(&[("value_field", "body")]));
+        let message = fields
+            .message(&record_of(serde_json::json!({"body": null})))
+            .unwrap()
+            .compressed(ValueCompression::Zstd)
+            .unwrap();
+        assert!(message.payload.is_none());
+    }
+
+    #[test]
+    fn test_corrupt_compressed_value() {
+        let message = message_with(Some(b"plain text".to_vec()));
+
+        let err = decode_message(&message, ValueCompression::Gzip, &policy("fail")).unwrap_err();
+        assert!(err.contains("events/1@9: invalid gzip value"), "{}", err);
+
+        match decode_message(&message, ValueCompression::Zstd, &policy("wrap")).unwrap() {
+            Decoded::Record(record) => {
+                assert_eq!(record["_kafka_payload"], serde_json::json!("plain text"))
+            }
+            other => panic!("expected wrapped record, got {:?}", other),
+        }
+
+        // Dead letters keep the original compressed bytes
+        match decode_message(&message, ValueCompression::Gzip, &policy("dead_letter")).unwrap() {
+            Decoded::DeadLetter(letter) => assert_eq!(letter.payload, b"plain text".to_vec()),
+            other => panic!("expected dead letter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_value_compression_validation() {
+        let err =
+            ValueCompression::from_config(&settings(&[("value_compression", "lz4")])).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid 'value_compression': 'lz4'. Must be one of: none, gzip, zstd"
+        );
+
+        for stage_type in [StageType::Source, StageType::Sink] {
+            let stage = KafkaStage::new("kafka".to_string(), stage_type);
+            let mut config = RHashMap::new();
+            for (k, v) in [
+                ("brokers", "localhost:9092"),
+                ("topic", "events"),
+                ("group_id", "etl"),
+                ("value_compression", "zstd"),
+            ] {
+                config.insert(RString::from(k), RString::from(v));
+            }
+            assert!(stage.validate_config(config.clone()).is_ok());
+            config.insert(RString::from("value_compression"), RString::from("lz4"));
+            assert!(stage.validate_config(config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_skipped_summary() {
+        assert_eq!(skipped_summary("events", 0), None);
+        assert_eq!(
+            skipped_summary("events", 3).unwrap(),
+            "Skipped 3 unparseable message(s) from topic 'events' (on_parse_error = \"skip\")"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_consume_messages_skipped_and_failed() {
+        // Skipped messages count as consumed but are not emitted
+        let mut n = 0;
+        let mut emitted = Vec::new();
+        let count = consume_messages(
+            || {
+                n += 1;
+                let current = n;
+                async move {
+                    Ok::<_, String>((current % 2 == 1).then(|| serde_json::json!({ "n": current })))
+                }
+            },
+            4,
+            Duration::from_secs(5),
+            |record| {
+                emitted.push(record["n"].clone());
+                true
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count, 4);
+        assert_eq!(emitted, vec![serde_json::json!(1), serde_json::json!(3)]);
+
+        // A failing message aborts consumption
+        let mut n = 0;
+        let result = consume_messages(
+            || {
+                n += 1;
+                let current = n;
+                async move {
+                    if current == 2 {
+                        Err("Failed to parse message".to_string())
+                    } else {
+                        Ok(Some(serde_json::json!({})))
+                    }
+                }
+            },
+            10,
+            Duration::from_secs(5),
+            |_| true,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_error_validation() {
+        let stage = KafkaStage::new("kafka".to_string(), StageType::Source);
+        let mut config = RHashMap::new();
+        config.insert(RString::from("brokers"), RString::from("localhost:9092"));
+        config.insert(RString::from("topic"), RString::from("events"));
+        config.insert(RString::from("group_id"), RString::from("group"));
+
+        config.insert(RString::from("on_parse_error"), RString::from("explode"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        // dead_letter needs a topic
+        config.insert(
+            RString::from("on_parse_error"),
+            RString::from("dead_letter"),
+        );
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(
+            RString::from("dead_letter_topic"),
+            RString::from("events-dlq"),
+        );
+        assert!(stage.validate_config(config).is_ok());
+    }
+
     #[test]
     fn test_capabilities() {
         let caps = get_capabilities();
@@ -1,7 +1,8 @@
 //! MongoDB Plugin for Conveyor - Operation-based API
 //!
-//! Provides MongoDB operations: find, findOne, createOne, createMany,
-//! updateOne, updateMany, deleteOne, deleteMany, replaceOne, replaceMany
+//! Provides MongoDB operations: find, findOne, count, distinct, createOne,
+//! createMany, updateOne, updateMany, deleteOne, deleteMany, replaceOne,
+//! replaceMany, createIndex
 
 use conveyor_plugin_api::sabi_trait::prelude::*;
 use conveyor_plugin_api::traits::{FfiExecutionContext, FfiStage, FfiStage_TO};
@@ -13,17 +14,24 @@ use conveyor_plugin_api::{
 use handlebars::Handlebars;
 use mongodb::{
     bson::Document,
-    options::{ClientOptions, FindOneOptions, FindOptions, ReplaceOptions, UpdateOptions},
-    Client,
+    error::{ErrorKind, InsertManyError, WriteFailure},
+    options::{
+        ClientOptions, FindOneOptions, FindOptions, IndexOptions, InsertManyOptions,
+        ReplaceOptions, UpdateOptions,
+    },
+    Client, IndexModel,
 };
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// MongoDB operation types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MongoOperation {
     Find,
     FindOne,
+    Count,
+    Distinct,
     InsertOne,
     InsertMany,
     UpdateOne,
@@ -34,6 +42,7 @@ pub enum MongoOperation {
     ReplaceMany,
     Aggregate,
     BulkWrite,
+    CreateIndex,
     ToObjectId,
 }
 
@@ -224,6 +233,78 @@ impl MongoDbStage {
         )))
     }
 
+    /// Execute count operation - count documents matching the query
+    async fn execute_count_async(
+        &self,
+        config: &HashMap<String, String>,
+        input_data: Option<&FfiDataFormat>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let (client, db_name, collection_name) =
+            match self.connect_mongodb(config, input_data).await {
+                ROk(conn) => conn,
+                RErr(e) => return RErr(e),
+            };
+        let db = client.database(&db_name);
+        let collection = db.collection::<Document>(&collection_name);
+
+        let filter = match self.parse_query(config, input_data) {
+            ROk(f) => f,
+            RErr(e) => return RErr(e),
+        };
+
+        let count = match collection.count_documents(filter).await {
+            Ok(n) => n,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "MongoDB count failed: {}",
+                    e
+                )))
+            }
+        };
+
+        FfiDataFormat::from_json_records(&[HashMap::from([(
+            "count".to_string(),
+            Value::from(count),
+        )])])
+    }
+
+    /// Execute distinct operation - distinct values of a field
+    async fn execute_distinct_async(
+        &self,
+        config: &HashMap<String, String>,
+        input_data: Option<&FfiDataFormat>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let field = match parse_distinct_field(config) {
+            ROk(field) => field,
+            RErr(e) => return RErr(e),
+        };
+
+        let (client, db_name, collection_name) =
+            match self.connect_mongodb(config, input_data).await {
+                ROk(conn) => conn,
+                RErr(e) => return RErr(e),
+            };
+        let db = client.database(&db_name);
+        let collection = db.collection::<Document>(&collection_name);
+
+        let filter = match self.parse_query(config, input_data) {
+            ROk(f) => f,
+            RErr(e) => return RErr(e),
+        };
+
+        let values = match collection.distinct(&field, filter).await {
+            Ok(values) => values,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "MongoDB distinct failed: {}",
+                    e
+                )))
+            }
+        };
+
+        FfiDataFormat::from_json_records(&distinct_records(values))
+    }
+
     /// Execute insertOne operation - insert single document
     async fn execute_insert_one_async(
         &self,
@@ -261,9 +342,16 @@ impl MongoDbStage {
             }
         };
 
+        let skip_duplicates = match parse_bool_option(config, "skip_duplicates", false) {
+            ROk(skip) => skip,
+            RErr(e) => return RErr(e),
+        };
+
         // Insert document
         match collection.insert_one(doc).await {
+            Ok(_) if skip_duplicates => insert_summary(1, 0),
             Ok(_) => ROk(input_data.clone()),
+            Err(e) if skip_duplicates && duplicate_key_count(&e) == Some(1) => insert_summary(0, 1),
             Err(e) => RErr(RBoxError::from_fmt(&format_args!(
                 "MongoDB insertOne failed: {}",
                 e
@@ -317,13 +405,42 @@ impl MongoDbStage {
             )));
         }
 
-        // Insert documents
-        match collection.insert_many(documents).await {
+        let (ordered, skip_duplicates) = match parse_insert_options(config) {
+            ROk(options) => options,
+            RErr(e) => return RErr(e),
+        };
+
+        let transactional = match parse_transactional(config) {
+            ROk(transactional) => transactional,
+            RErr(e) => return RErr(e),
+        };
+        if transactional {
+            return match insert_many_in_transaction(&client, &collection, documents).await {
+                Ok(()) => ROk(input_data.clone()),
+                Err(e) => RErr(RBoxError::from_fmt(&format_args!(
+                    "MongoDB transactional insertMany failed, no documents were written: {}",
+                    e
+                ))),
+            };
+        }
+
+        // Insert documents; unordered inserts keep going past failed documents
+        let total = documents.len();
+        let options = InsertManyOptions::builder().ordered(ordered).build();
+        match collection
+            .insert_many(documents)
+            .with_options(options)
+            .await
+        {
+            Ok(_) if skip_duplicates => insert_summary(total, 0),
             Ok(_) => ROk(input_data.clone()),
-            Err(e) => RErr(RBoxError::from_fmt(&format_args!(
-                "MongoDB insertMany failed: {}",
-                e
-            ))),
+            Err(e) => match duplicate_key_count(&e) {
+                Some(skipped) if skip_duplicates => insert_summary(total - skipped, skipped),
+                _ => RErr(RBoxError::from_fmt(&format_args!(
+                    "MongoDB insertMany failed: {}",
+                    e
+                ))),
+            },
         }
     }
 
@@ -370,15 +487,23 @@ impl MongoDbStage {
             }
         };
 
-        // Wrap in $set operator
-        let update = mongodb::bson::doc! { "$set": update_doc };
+        let (options, summary) = match parse_update_options(config) {
+            ROk(parsed) => parsed,
+            RErr(e) => return RErr(e),
+        };
+
+        let update = match update_document(update_doc) {
+            ROk(update) => update,
+            RErr(e) => return RErr(e),
+        };
 
         // Execute update
         match collection
             .update_one(filter, update)
-            .with_options(UpdateOptions::default())
+            .with_options(options)
             .await
         {
+            Ok(result) if summary => update_summary(&result),
             Ok(_) => ROk(input_data.clone()),
             Err(e) => RErr(RBoxError::from_fmt(&format_args!(
                 "MongoDB updateOne failed: {}",
@@ -430,15 +555,23 @@ impl MongoDbStage {
             }
         };
 
-        // Wrap in $set operator
-        let update = mongodb::bson::doc! { "$set": update_doc };
+        let (options, summary) = match parse_update_options(config) {
+            ROk(parsed) => parsed,
+            RErr(e) => return RErr(e),
+        };
+
+        let update = match update_document(update_doc) {
+            ROk(update) => update,
+            RErr(e) => return RErr(e),
+        };
 
         // Execute update
         match collection
             .update_many(filter, update)
-            .with_options(UpdateOptions::default())
+            .with_options(options)
             .await
         {
+            Ok(result) if summary => update_summary(&result),
             Ok(_) => ROk(input_data.clone()),
             Err(e) => RErr(RBoxError::from_fmt(&format_args!(
                 "MongoDB updateMany failed: {}",
@@ -888,7 +1021,10 @@ impl MongoDbStage {
                             ROk(d) => d,
                             RErr(e) => return RErr(e),
                         };
-                        let update = mongodb::bson::doc! { "$set": update_doc };
+                        let update = match update_document(update_doc) {
+                            ROk(update) => update,
+                            RErr(e) => return RErr(e),
+                        };
                         match collection.update_one(filter, update).await {
                             Ok(_) => update_count += 1,
                             Err(e) => {
@@ -923,7 +1059,10 @@ impl MongoDbStage {
                             ROk(d) => d,
                             RErr(e) => return RErr(e),
                         };
-                        let update = mongodb::bson::doc! { "$set": update_doc };
+                        let update = match update_document(update_doc) {
+                            ROk(update) => update,
+                            RErr(e) => return RErr(e),
+                        };
                         match collection.update_many(filter, update).await {
                             Ok(result) => update_count += result.modified_count as i32,
                             Err(e) => {
@@ -1138,12 +1277,55 @@ impl MongoDbStage {
 
     // Helper methods
 
+    /// Execute createIndex operation - ensure an index exists
+    ///
+    /// The server treats creating an index that already exists with the same
+    /// keys and options as a no-op, so the stage can run before every load.
+    async fn execute_create_index_async(
+        &self,
+        config: &HashMap<String, String>,
+        input_data: Option<&FfiDataFormat>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let index = match parse_index_model(config) {
+            ROk(index) => index,
+            RErr(e) => return RErr(e),
+        };
+
+        let (client, db_name, collection_name) =
+            match self.connect_mongodb(config, input_data).await {
+                ROk(conn) => conn,
+                RErr(e) => return RErr(e),
+            };
+        let collection = client
+            .database(&db_name)
+            .collection::<Document>(&collection_name);
+
+        let index_name = match collection.create_index(index).await {
+            Ok(result) => result.index_name,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "MongoDB createIndex failed: {}",
+                    index_error(e)
+                )))
+            }
+        };
+
+        // Pass the input on so the stage can sit in front of the load it prepares
+        match input_data {
+            Some(data) => ROk(data.clone()),
+            None => FfiDataFormat::from_json_records(&[HashMap::from([(
+                "index".to_string(),
+                Value::String(index_name),
+            )])]),
+        }
+    }
+
     /// Connect to MongoDB and return client, database name, and collection name
     async fn connect_mongodb(
         &self,
         config: &HashMap<String, String>,
         input_data: Option<&FfiDataFormat>,
-    ) -> RResult<(Client, String, String), RBoxError> {
+    ) -> RResult<(Arc<Client>, String, String), RBoxError> {
         let uri = match self.render_config_value(config, "uri", input_data) {
             ROk(Some(u)) => u,
             ROk(None) => {
@@ -1174,24 +1356,9 @@ impl MongoDbStage {
             RErr(e) => return RErr(e),
         };
 
-        let client_options = match ClientOptions::parse(&uri).await {
-            Ok(opts) => opts,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Failed to parse MongoDB URI: {}",
-                    e
-                )))
-            }
-        };
-
-        let client = match Client::with_options(client_options) {
-            Ok(c) => c,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Failed to create MongoDB client: {}",
-                    e
-                )))
-            }
+        let client = match cached_client(&uri).await {
+            ROk(c) => c,
+            RErr(e) => return RErr(e),
         };
 
         ROk((client, database, collection))
@@ -1373,14 +1540,9 @@ impl FfiStage for MongoDbStage {
             .collect();
 
         // Use tokio runtime to execute async code
-        let runtime = match tokio::runtime::Runtime::new() {
-            Ok(rt) => rt,
-            Err(e) => {
-                return RErr(RBoxError::from_fmt(&format_args!(
-                    "Failed to create runtime: {}",
-                    e
-                )))
-            }
+        let runtime = match shared_runtime() {
+            ROk(rt) => rt,
+            RErr(e) => return RErr(e),
         };
 
         runtime.block_on(async {
@@ -1393,6 +1555,13 @@ impl FfiStage for MongoDbStage {
                     self.execute_find_one_async(&config, input_data.as_ref())
                         .await
                 }
+                MongoOperation::Count => {
+                    self.execute_count_async(&config, input_data.as_ref()).await
+                }
+                MongoOperation::Distinct => {
+                    self.execute_distinct_async(&config, input_data.as_ref())
+                        .await
+                }
                 MongoOperation::Aggregate => {
                     self.execute_aggregate_async(&config, input_data.as_ref())
                         .await
@@ -1482,6 +1651,10 @@ impl FfiStage for MongoDbStage {
                     };
                     self.execute_replace_many_async(&input_data, &config).await
                 }
+                MongoOperation::CreateIndex => {
+                    self.execute_create_index_async(&config, input_data.as_ref())
+                        .await
+                }
                 MongoOperation::ToObjectId => {
                     let input_data = match input_data {
                         Some(data) => data,
@@ -1528,10 +1701,460 @@ impl FfiStage for MongoDbStage {
             )));
         }
 
+        let config: HashMap<String, String> = config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        if matches!(
+            self.operation,
+            MongoOperation::InsertOne | MongoOperation::InsertMany
+        ) {
+            if let RErr(e) = parse_insert_options(&config) {
+                return RErr(e);
+            }
+            if self.operation == MongoOperation::InsertMany {
+                if let RErr(e) = parse_transactional(&config) {
+                    return RErr(e);
+                }
+            }
+        }
+
+        if matches!(
+            self.operation,
+            MongoOperation::UpdateOne | MongoOperation::UpdateMany
+        ) {
+            if let RErr(e) = parse_update_options(&config) {
+                return RErr(e);
+            }
+        }
+
+        if self.operation == MongoOperation::CreateIndex {
+            if let RErr(e) = parse_index_model(&config) {
+                return RErr(e);
+            }
+        }
+
+        if self.operation == MongoOperation::Distinct {
+            if let RErr(e) = parse_distinct_field(&config) {
+                return RErr(e);
+            }
+        }
+
         ROk(())
     }
 }
 
+/// Runtime shared by every stage of the plugin
+///
+/// Cached clients run their connection pools on the runtime they were
+/// created on, so it must outlive a single `execute`.
+fn shared_runtime() -> RResult<&'static tokio::runtime::Runtime, RBoxError> {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    if let Some(runtime) = RUNTIME.get() {
+        return ROk(runtime);
+    }
+    match tokio::runtime::Runtime::new() {
+        // Another thread may have won the race; its runtime is used instead
+        Ok(runtime) => ROk(RUNTIME.get_or_init(|| runtime)),
+        Err(e) => RErr(RBoxError::from_fmt(&format_args!(
+            "Failed to create runtime: {}",
+            e
+        ))),
+    }
+}
+
+/// Clients by connection URI, shared by every stage of the process
+fn client_cache() -> &'static Mutex<HashMap<String, Arc<Client>>> {
+    static CLIENTS: OnceLock<Mutex<HashMap<String, Arc<Client>>>> = OnceLock::new();
+    CLIENTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Client for `uri`, reusing its connection pool across stages and runs
+///
+/// Only successfully built clients are cached, so a URI that failed to parse
+/// is tried again on the next connect. A cached client reconnects on its own
+/// once an unreachable server comes back.
+async fn cached_client(uri: &str) -> RResult<Arc<Client>, RBoxError> {
+    if let Some(client) = client_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(uri)
+    {
+        return ROk(client.clone());
+    }
+
+    // Parsing may resolve SRV records, so build the client without the lock held
+    let client_options = match ClientOptions::parse(uri).await {
+        Ok(opts) => opts,
+        Err(e) => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Failed to parse MongoDB URI: {}",
+                e
+            )))
+        }
+    };
+
+    let client = match Client::with_options(client_options) {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Failed to create MongoDB client: {}",
+                e
+            )))
+        }
+    };
+
+    // A concurrent connect may have cached a client first; keep that one
+    let mut clients = client_cache().lock().unwrap_or_else(|e| e.into_inner());
+    ROk(clients.entry(uri.to_string()).or_insert(client).clone())
+}
+
+/// MongoDB error code for a duplicate key (e.g. an existing `_id`)
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+/// Read the required 'field' option of distinct
+fn parse_distinct_field(config: &HashMap<String, String>) -> RResult<String, RBoxError> {
+    match config.get("field").map(|f| f.trim()) {
+        Some(field) if !field.is_empty() => ROk(field.to_string()),
+        Some(_) => RErr(RBoxError::from_fmt(&format_args!(
+            "'field' for distinct must not be empty"
+        ))),
+        None => RErr(RBoxError::from_fmt(&format_args!(
+            "Missing required 'field' configuration for distinct"
+        ))),
+    }
+}
+
+/// One `{ "value": ... }` record per distinct value
+fn distinct_records(values: Vec<mongodb::bson::Bson>) -> Vec<HashMap<String, Value>> {
+    values
+        .into_iter()
+        .map(|value| {
+            let value = serde_json::to_value(&value).unwrap_or(Value::Null);
+            HashMap::from([("value".to_string(), value)])
+        })
+        .collect()
+}
+
+/// Parse a "true"/"false" config option
+fn parse_bool_option(
+    config: &HashMap<String, String>,
+    key: &str,
+    default: bool,
+) -> RResult<bool, RBoxError> {
+    match config.get(key).map(|v| v.trim()) {
+        None => ROk(default),
+        Some(value) => match value.parse::<bool>() {
+            Ok(b) => ROk(b),
+            Err(_) => RErr(RBoxError::from_fmt(&format_args!(
+                "Invalid '{}' value '{}': expected true or false",
+                key, value
+            ))),
+        },
+    }
+}
+
+/// Parse `ordered` (default true) and `skip_duplicates` (default false)
+///
+/// Skipping duplicates needs an unordered insert: an ordered insert stops at
+/// the first duplicate, so the documents after it would be silently dropped.
+fn parse_insert_options(config: &HashMap<String, String>) -> RResult<(bool, bool), RBoxError> {
+    let ordered = match parse_bool_option(config, "ordered", true) {
+        ROk(ordered) => ordered,
+        RErr(e) => return RErr(e),
+    };
+    let skip_duplicates = match parse_bool_option(config, "skip_duplicates", false) {
+        ROk(skip) => skip,
+        RErr(e) => return RErr(e),
+    };
+    if skip_duplicates && ordered && config.contains_key("ordered") {
+        return RErr(RBoxError::from_fmt(&format_args!(
+            "'skip_duplicates = true' requires 'ordered = false'"
+        )));
+    }
+    // skip_duplicates alone implies an unordered insert
+    ROk((ordered && !skip_duplicates, skip_duplicates))
+}
+
+/// Parse `transactional` (default false), which cannot be combined with `skip_duplicates`
+///
+/// Inside a transaction any failed document aborts the whole batch, so there is
+/// nothing to skip past.
+fn parse_transactional(config: &HashMap<String, String>) -> RResult<bool, RBoxError> {
+    let transactional = match parse_bool_option(config, "transactional", false) {
+        ROk(transactional) => transactional,
+        RErr(e) => return RErr(e),
+    };
+    if transactional
+        && matches!(
+            parse_bool_option(config, "skip_duplicates", false),
+            ROk(true)
+        )
+    {
+        return RErr(RBoxError::from_fmt(&format_args!(
+            "'transactional = true' cannot be combined with 'skip_duplicates = true'"
+        )));
+    }
+    ROk(transactional)
+}
+
+/// Parse `upsert` and `summary` (both default false) of the update operations
+///
+/// Returns the driver options and whether to return an update summary
+/// instead of passing the input through.
+fn parse_update_options(
+    config: &HashMap<String, String>,
+) -> RResult<(UpdateOptions, bool), RBoxError> {
+    let upsert = match parse_bool_option(config, "upsert", false) {
+        ROk(upsert) => upsert,
+        RErr(e) => return RErr(e),
+    };
+    let summary = match parse_bool_option(config, "summary", false) {
+        ROk(summary) => summary,
+        RErr(e) => return RErr(e),
+    };
+    ROk((UpdateOptions::builder().upsert(upsert).build(), summary))
+}
+
+/// Build the update of an update operation from the provided document
+///
+/// A document of update operators, such as `{"$inc": {"views": 1}}`, is sent
+/// as it is. A document of plain fields is wrapped in `$set`. Mixing the two
+/// is rejected rather than guessing which fields were meant to be set.
+fn update_document(update_doc: Document) -> RResult<Document, RBoxError> {
+    let (mut operators, mut fields): (Vec<&String>, Vec<&String>) =
+        update_doc.keys().partition(|key| key.starts_with('$'));
+    if operators.is_empty() {
+        return ROk(mongodb::bson::doc! { "$set": update_doc });
+    }
+    if fields.is_empty() {
+        return ROk(update_doc);
+    }
+    operators.sort();
+    fields.sort();
+    RErr(RBoxError::from_fmt(&format_args!(
+        "Update document mixes update operators ({}) with plain fields ({}); \
+         move the plain fields into \"$set\"",
+        operators
+            .iter()
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        fields
+            .iter()
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )))
+}
+
+/// Insert `documents` in one multi-document transaction: all of them or none
+///
+/// Transactions need a replica set or sharded cluster; a standalone server
+/// rejects them, so that error gets a hint.
+async fn insert_many_in_transaction(
+    client: &Client,
+    collection: &mongodb::Collection<Document>,
+    documents: Vec<Document>,
+) -> Result<(), String> {
+    let mut session = client.start_session().await.map_err(|e| e.to_string())?;
+    session
+        .start_transaction()
+        .await
+        .map_err(transaction_error)?;
+
+    match collection
+        .insert_many(documents)
+        .session(&mut session)
+        .await
+    {
+        Ok(_) => session
+            .commit_transaction()
+            .await
+            .map_err(transaction_error),
+        Err(e) => {
+            // The batch already failed; an abort error would only hide the cause
+            let _ = session.abort_transaction().await;
+            Err(transaction_error(e))
+        }
+    }
+}
+
+/// MongoDB error code for "Transaction numbers are only allowed on a replica set member or mongos"
+const ILLEGAL_OPERATION_CODE: i32 = 20;
+
+fn transaction_error(error: mongodb::error::Error) -> String {
+    match error.kind.as_ref() {
+        ErrorKind::Command(e) if e.code == ILLEGAL_OPERATION_CODE => format!(
+            "{} (transactions require a replica set or sharded cluster)",
+            error
+        ),
+        _ => error.to_string(),
+    }
+}
+
+/// Index types accepted as key values besides `1` (ascending) and `-1` (descending)
+const INDEX_TYPES: &[&str] = &["text", "hashed", "2d", "2dsphere"];
+
+/// Build the index from `keys` (a JSON object such as `{"email": 1}`) and the
+/// `unique`, `sparse`, `expire_after_seconds` and `name` options
+///
+/// Keys are parsed straight into a BSON document so compound indexes keep
+/// their field order.
+fn parse_index_model(config: &HashMap<String, String>) -> RResult<IndexModel, RBoxError> {
+    let keys_str = match config.get("keys") {
+        Some(k) => k,
+        None => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "Missing required 'keys' configuration for createIndex"
+            )))
+        }
+    };
+    let keys: Document = match serde_json::from_str(keys_str) {
+        Ok(keys) => keys,
+        Err(e) => {
+            return RErr(RBoxError::from_fmt(&format_args!(
+                "'keys' must be a JSON object such as {{\"email\": 1}}: {}",
+                e
+            )))
+        }
+    };
+    if keys.is_empty() {
+        return RErr(RBoxError::from_fmt(&format_args!(
+            "'keys' must name at least one field"
+        )));
+    }
+    // JSON numbers arrive as Int32 or Int64 depending on sign; send them as Int32
+    let mut keys = keys;
+    for (field, direction) in keys.iter_mut() {
+        let normalized = match &*direction {
+            mongodb::bson::Bson::String(kind) if INDEX_TYPES.contains(&kind.as_str()) => {
+                Some(direction.clone())
+            }
+            mongodb::bson::Bson::Int32(n) if n.abs() == 1 => Some(mongodb::bson::Bson::Int32(*n)),
+            mongodb::bson::Bson::Int64(n) if n.abs() == 1 => {
+                Some(mongodb::bson::Bson::Int32(*n as i32))
+            }
+            _ => None,
+        };
+        match normalized {
+            Some(value) => *direction = value,
+            None => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Invalid index key '{}': {}. Use 1, -1 or one of {}",
+                    field,
+                    direction,
+                    INDEX_TYPES.join(", ")
+                )))
+            }
+        }
+    }
+
+    let unique = match parse_bool_option(config, "unique", false) {
+        ROk(unique) => unique,
+        RErr(e) => return RErr(e),
+    };
+    let sparse = match parse_bool_option(config, "sparse", false) {
+        ROk(sparse) => sparse,
+        RErr(e) => return RErr(e),
+    };
+    let expire_after = match config.get("expire_after_seconds").map(|v| v.trim()) {
+        None => None,
+        Some(value) => match value.parse::<u64>() {
+            Ok(secs) => Some(std::time::Duration::from_secs(secs)),
+            Err(_) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Invalid 'expire_after_seconds' value '{}': expected a non-negative integer",
+                    value
+                )))
+            }
+        },
+    };
+    if expire_after.is_some() && keys.len() > 1 {
+        return RErr(RBoxError::from_fmt(&format_args!(
+            "'expire_after_seconds' needs a single-field index"
+        )));
+    }
+
+    let options = IndexOptions::builder()
+        .unique(unique.then_some(true))
+        .sparse(sparse.then_some(true))
+        .expire_after(expire_after)
+        .name(config.get("name").cloned())
+        .build();
+
+    ROk(IndexModel::builder().keys(keys).options(options).build())
+}
+
+/// MongoDB error codes for an index that exists with other options or keys under the same name
+const INDEX_CONFLICT_CODES: &[i32] = &[85, 86];
+
+fn index_error(error: mongodb::error::Error) -> String {
+    match error.kind.as_ref() {
+        ErrorKind::Command(e) if INDEX_CONFLICT_CODES.contains(&e.code) => format!(
+            "{} (an index with this name already exists with a different definition)",
+            error
+        ),
+        _ => error.to_string(),
+    }
+}
+
+/// Number of failed documents if every failure is a duplicate-key error
+fn duplicate_key_count(error: &mongodb::error::Error) -> Option<usize> {
+    match error.kind.as_ref() {
+        ErrorKind::InsertMany(InsertManyError {
+            write_errors: Some(errors),
+            write_concern_error: None,
+            ..
+        }) if !errors.is_empty() && errors.iter().all(|e| e.code == DUPLICATE_KEY_CODE) => {
+            Some(errors.len())
+        }
+        ErrorKind::Write(WriteFailure::WriteError(e)) if e.code == DUPLICATE_KEY_CODE => Some(1),
+        _ => None,
+    }
+}
+
+/// Summary record returned by inserts that skip duplicates
+fn insert_summary(inserted: usize, skipped: usize) -> RResult<FfiDataFormat, RBoxError> {
+    let summary = vec![HashMap::from([
+        ("inserted".to_string(), Value::Number(inserted.into())),
+        (
+            "skipped_duplicates".to_string(),
+            Value::Number(skipped.into()),
+        ),
+    ])];
+
+    FfiDataFormat::from_json_records(&summary)
+}
+
+/// Summary record returned by updates with `summary = true`
+///
+/// `_upserted_id` holds the `_id` of the document an upsert inserted, and is
+/// null when existing documents were updated instead.
+fn update_summary(result: &mongodb::results::UpdateResult) -> RResult<FfiDataFormat, RBoxError> {
+    FfiDataFormat::from_json_records(&[update_summary_record(
+        result.matched_count,
+        result.modified_count,
+        result.upserted_id.as_ref(),
+    )])
+}
+
+fn update_summary_record(
+    matched: u64,
+    modified: u64,
+    upserted_id: Option<&mongodb::bson::Bson>,
+) -> HashMap<String, Value> {
+    let upserted_id = upserted_id
+        .and_then(|id| serde_json::to_value(id).ok())
+        .unwrap_or(Value::Null);
+    HashMap::from([
+        ("matched".to_string(), Value::from(matched)),
+        ("modified".to_string(), Value::from(modified)),
+        ("_upserted_id".to_string(), upserted_id),
+    ])
+}
+
 // Helper function to convert JSON value to BSON
 fn json_to_bson(value: &Value) -> Option<mongodb::bson::Bson> {
     match value {
@@ -1641,11 +2264,35 @@ pub extern "C" fn create_mongodb_findone() -> FfiStage_TO<'static, RBox<()>> {
 }
 
 #[no_mangle]
-pub extern "C" fn create_mongodb_insertone() -> FfiStage_TO<'static, RBox<()>> {
+pub extern "C" fn create_mongodb_count() -> FfiStage_TO<'static, RBox<()>> {
     FfiStage_TO::from_value(
         MongoDbStage::new(
-            "mongodb-insertone".to_string(),
-            MongoOperation::InsertOne,
+            "mongodb-count".to_string(),
+            MongoOperation::Count,
+            StageType::Source,
+        ),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_mongodb_distinct() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        MongoDbStage::new(
+            "mongodb-distinct".to_string(),
+            MongoOperation::Distinct,
+            StageType::Source,
+        ),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_mongodb_insertone() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        MongoDbStage::new(
+            "mongodb-insertone".to_string(),
+            MongoOperation::InsertOne,
             StageType::Sink,
         ),
         TD_Opaque,
@@ -1760,6 +2407,18 @@ pub extern "C" fn create_mongodb_bulkwrite() -> FfiStage_TO<'static, RBox<()>> {
     )
 }
 
+#[no_mangle]
+pub extern "C" fn create_mongodb_createindex() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        MongoDbStage::new(
+            "mongodb-createindex".to_string(),
+            MongoOperation::CreateIndex,
+            StageType::Sink,
+        ),
+        TD_Opaque,
+    )
+}
+
 #[no_mangle]
 pub extern "C" fn create_mongodb_toobjectid() -> FfiStage_TO<'static, RBox<()>> {
     FfiStage_TO::from_value(
@@ -1840,6 +2499,53 @@ fn create_findone_metadata() -> FfiStageMetadata {
     )
 }
 
+/// Create metadata for count operation
+fn create_count_metadata() -> FfiStageMetadata {
+    let mut params = common_mongodb_parameters();
+    params.push(FfiConfigParameter::optional(
+        "query",
+        FfiParameterType::String,
+        "{}",
+        "MongoDB query filter as JSON string",
+    ));
+
+    FfiStageMetadata::new(
+        "mongodb.count",
+        "Count documents in MongoDB collection",
+        "Counts the documents matching the query filter (all documents without one) \
+         and returns a single record {count: N}.",
+        params,
+        vec!["mongodb", "database", "source", "count", "query"],
+    )
+}
+
+/// Create metadata for distinct operation
+fn create_distinct_metadata() -> FfiStageMetadata {
+    let mut params = common_mongodb_parameters();
+    params.extend(vec![
+        FfiConfigParameter::required(
+            "field",
+            FfiParameterType::String,
+            "Field to collect distinct values of (dot notation for nested fields)",
+        ),
+        FfiConfigParameter::optional(
+            "query",
+            FfiParameterType::String,
+            "{}",
+            "MongoDB query filter as JSON string",
+        ),
+    ]);
+
+    FfiStageMetadata::new(
+        "mongodb.distinct",
+        "Find distinct values of a field in MongoDB collection",
+        "Returns one record {value: ...} per distinct value of 'field' among the documents \
+         matching the query filter. Array fields contribute each of their elements.",
+        params,
+        vec!["mongodb", "database", "source", "distinct", "query"],
+    )
+}
+
 /// Create metadata for insertOne operation
 fn create_insertone_metadata() -> FfiStageMetadata {
     let mut params = common_mongodb_parameters();
@@ -1849,12 +2555,19 @@ fn create_insertone_metadata() -> FfiStageMetadata {
         "",
         "Document to insert as JSON string (alternative to input data). Example: '{\"name\": \"John\", \"age\": 30}'",
     ));
+    params.push(FfiConfigParameter::optional(
+        "skip_duplicates",
+        FfiParameterType::Boolean,
+        "false",
+        "Tolerate a duplicate-key error and return an {inserted, skipped_duplicates} summary",
+    ));
 
     FfiStageMetadata::new(
         "mongodb.insertOne",
         "Insert single document into MongoDB collection",
         "Inserts a single document into MongoDB. \
-         Document can be provided via input data (first record) or 'document' config parameter.",
+         Document can be provided via input data (first record) or 'document' config parameter. \
+         With 'skip_duplicates', a document whose key already exists is skipped instead of failing.",
         params,
         vec!["mongodb", "database", "sink", "insert"],
     )
@@ -1869,18 +2582,57 @@ fn create_insertmany_metadata() -> FfiStageMetadata {
         "",
         "Documents to insert as JSON array string (alternative to input data). Example: '[{\"name\": \"John\"}, {\"name\": \"Jane\"}]'",
     ));
+    params.push(FfiConfigParameter::optional(
+        "ordered",
+        FfiParameterType::Boolean,
+        "true",
+        "Stop at the first failed document; false inserts every document it can",
+    ));
+    params.push(FfiConfigParameter::optional(
+        "skip_duplicates",
+        FfiParameterType::Boolean,
+        "false",
+        "Tolerate duplicate-key errors (implies ordered = false) and return an {inserted, skipped_duplicates} summary",
+    ));
+    params.push(FfiConfigParameter::optional(
+        "transactional",
+        FfiParameterType::Boolean,
+        "false",
+        "Insert the whole batch in one transaction so a failure leaves no documents (requires a replica set)",
+    ));
 
     FfiStageMetadata::new(
         "mongodb.insertMany",
         "Insert multiple documents into MongoDB collection",
         "Inserts multiple documents into MongoDB in a single batch operation. \
          Documents can be provided via input data (all records) or 'documents' config parameter. \
-         Efficient for bulk inserts.",
+         Efficient for bulk inserts. With 'skip_duplicates', documents whose key already exists \
+         are skipped while the rest of the batch is inserted. With 'transactional', the batch is \
+         inserted in a multi-document transaction, so a failure mid-batch leaves none of it behind \
+         and the stage can be re-run without double-writing.",
         params,
         vec!["mongodb", "database", "sink", "insert", "bulk"],
     )
 }
 
+/// Create the `upsert` and `summary` parameters of the update operations
+fn update_option_parameters() -> Vec<FfiConfigParameter> {
+    vec![
+        FfiConfigParameter::optional(
+            "upsert",
+            FfiParameterType::Boolean,
+            "false",
+            "Insert a document built from the query and update when no document matches",
+        ),
+        FfiConfigParameter::optional(
+            "summary",
+            FfiParameterType::Boolean,
+            "false",
+            "Return a {matched, modified, _upserted_id} record instead of the input data",
+        ),
+    ]
+}
+
 /// Create metadata for updateOne operation
 fn create_updateone_metadata() -> FfiStageMetadata {
     let mut params = common_mongodb_parameters();
@@ -1893,14 +2645,17 @@ fn create_updateone_metadata() -> FfiStageMetadata {
         "update",
         FfiParameterType::String,
         "",
-        "Fields to update as JSON string (alternative to input data). Example: '{\"status\": \"active\", \"updated_at\": \"2024-01-01\"}'",
+        "Fields or update operators to apply as JSON string (alternative to input data). Example: '{\"status\": \"active\"}' or '{\"$inc\": {\"views\": 1}}'",
     ));
+    params.extend(update_option_parameters());
 
     FfiStageMetadata::new(
         "mongodb.updateOne",
         "Update single document in MongoDB collection",
-        "Updates the first document matching the query filter using $set operator. \
-         Update fields can be provided via input data (first record) or 'update' config parameter.",
+        "Updates the first document matching the query filter. Plain fields are applied \
+         with $set; a document of update operators ($inc, $push, $unset, ...) is used as is. \
+         Update fields can be provided via input data (first record) or 'update' config parameter. \
+         With upsert = true a document is inserted when none matches.",
         params,
         vec!["mongodb", "database", "sink", "update"],
     )
@@ -1918,14 +2673,17 @@ fn create_updatemany_metadata() -> FfiStageMetadata {
         "update",
         FfiParameterType::String,
         "",
-        "Fields to update as JSON string (alternative to input data). Example: '{\"status\": \"active\", \"updated_at\": \"2024-01-01\"}'",
+        "Fields or update operators to apply as JSON string (alternative to input data). Example: '{\"status\": \"active\"}' or '{\"$inc\": {\"views\": 1}}'",
     ));
+    params.extend(update_option_parameters());
 
     FfiStageMetadata::new(
         "mongodb.updateMany",
         "Update multiple documents in MongoDB collection",
-        "Updates all documents matching the query filter using $set operator. \
-         Update fields can be provided via input data (first record) or 'update' config parameter.",
+        "Updates all documents matching the query filter. Plain fields are applied \
+         with $set; a document of update operators ($inc, $push, $unset, ...) is used as is. \
+         Update fields can be provided via input data (first record) or 'update' config parameter. \
+         With upsert = true a document is inserted when none matches.",
         params,
         vec!["mongodb", "database", "sink", "update", "bulk"],
     )
@@ -2061,6 +2819,53 @@ fn create_bulkwrite_metadata() -> FfiStageMetadata {
     )
 }
 
+/// Create metadata for createIndex operation
+fn create_createindex_metadata() -> FfiStageMetadata {
+    let mut params = common_mongodb_parameters();
+    params.extend(vec![
+        FfiConfigParameter::required(
+            "keys",
+            FfiParameterType::String,
+            "Index keys as JSON object, in order (e.g., '{\"customer_id\": 1, \"created_at\": -1}')",
+        ),
+        FfiConfigParameter::optional(
+            "unique",
+            FfiParameterType::Boolean,
+            "false",
+            "Reject documents whose key values already exist",
+        ),
+        FfiConfigParameter::optional(
+            "sparse",
+            FfiParameterType::Boolean,
+            "false",
+            "Only index documents that have the indexed fields",
+        ),
+        FfiConfigParameter::optional(
+            "expire_after_seconds",
+            FfiParameterType::Integer,
+            "",
+            "Make it a TTL index that deletes documents this many seconds after the indexed date",
+        ),
+        FfiConfigParameter::optional(
+            "name",
+            FfiParameterType::String,
+            "",
+            "Index name (defaults to MongoDB's generated name, e.g. customer_id_1)",
+        ),
+    ]);
+
+    FfiStageMetadata::new(
+        "mongodb.createIndex",
+        "Ensure an index exists on a MongoDB collection",
+        "Creates an index unless an identical one already exists, so it can run before every load. \
+         Key values are 1 (ascending), -1 (descending), or text, hashed, 2d or 2dsphere. \
+         An existing index with the same name or keys but other options fails the stage. \
+         Input data is passed through unchanged; without input the stage returns {index: name}.",
+        params,
+        vec!["mongodb", "database", "sink", "index", "schema"],
+    )
+}
+
 /// Create metadata for toObjectId operation
 fn create_toobjectid_metadata() -> FfiStageMetadata {
     let params = vec![FfiConfigParameter::required(
@@ -2105,6 +2910,20 @@ extern "C" fn get_capabilities() -> RVec<PluginCapability> {
             "create_mongodb_findone",
             create_findone_metadata(),
         ),
+        PluginCapability::new(
+            "mongodb.count",
+            StageType::Source,
+            "MongoDB count - count matching documents",
+            "create_mongodb_count",
+            create_count_metadata(),
+        ),
+        PluginCapability::new(
+            "mongodb.distinct",
+            StageType::Source,
+            "MongoDB distinct - distinct values of a field",
+            "create_mongodb_distinct",
+            create_distinct_metadata(),
+        ),
         PluginCapability::new(
             "mongodb.aggregate",
             StageType::Source,
@@ -2175,6 +2994,13 @@ extern "C" fn get_capabilities() -> RVec<PluginCapability> {
             "create_mongodb_bulkwrite",
             create_bulkwrite_metadata(),
         ),
+        PluginCapability::new(
+            "mongodb.createIndex",
+            StageType::Sink,
+            "MongoDB createIndex - ensure an index exists",
+            "create_mongodb_createindex",
+            create_createindex_metadata(),
+        ),
         PluginCapability::new(
             "mongodb.toObjectId",
             StageType::Transform,
@@ -2193,7 +3019,7 @@ pub static _plugin_declaration: PluginDeclaration = PluginDeclaration {
     name: rstr!("mongodb"),
     version: rstr!("2.3.0"),
     description: rstr!(
-        "MongoDB plugin with operation-based API (find, findOne, aggregate, insert, update, delete, replace, bulkWrite, toObjectId)"
+        "MongoDB plugin with operation-based API (find, findOne, count, distinct, aggregate, insert, update, delete, replace, bulkWrite, createIndex, toObjectId)"
     ),
     get_capabilities,
 };
@@ -2216,7 +3042,7 @@ mod tests {
     #[test]
     fn test_capabilities() {
         let caps = get_capabilities();
-        assert_eq!(caps.len(), 13);
+        assert_eq!(caps.len(), 16);
 
         // Check find operation
         assert_eq!(caps[0].name.as_str(), "mongodb.find");
@@ -2226,21 +3052,31 @@ mod tests {
         assert_eq!(caps[1].name.as_str(), "mongodb.findOne");
         assert_eq!(caps[1].stage_type, StageType::Source);
 
-        // Check aggregate operation
-        assert_eq!(caps[2].name.as_str(), "mongodb.aggregate");
+        // Check count and distinct operations
+        assert_eq!(caps[2].name.as_str(), "mongodb.count");
         assert_eq!(caps[2].stage_type, StageType::Source);
+        assert_eq!(caps[3].name.as_str(), "mongodb.distinct");
+        assert_eq!(caps[3].stage_type, StageType::Source);
+
+        // Check aggregate operation
+        assert_eq!(caps[4].name.as_str(), "mongodb.aggregate");
+        assert_eq!(caps[4].stage_type, StageType::Source);
 
         // Check insertOne operation
-        assert_eq!(caps[3].name.as_str(), "mongodb.insertOne");
-        assert_eq!(caps[3].stage_type, StageType::Sink);
+        assert_eq!(caps[5].name.as_str(), "mongodb.insertOne");
+        assert_eq!(caps[5].stage_type, StageType::Sink);
 
         // Check insertMany operation
-        assert_eq!(caps[4].name.as_str(), "mongodb.insertMany");
-        assert_eq!(caps[4].stage_type, StageType::Sink);
+        assert_eq!(caps[6].name.as_str(), "mongodb.insertMany");
+        assert_eq!(caps[6].stage_type, StageType::Sink);
 
         // Check bulkWrite operation
-        assert_eq!(caps[11].name.as_str(), "mongodb.bulkWrite");
-        assert_eq!(caps[11].stage_type, StageType::Sink);
+        assert_eq!(caps[13].name.as_str(), "mongodb.bulkWrite");
+        assert_eq!(caps[13].stage_type, StageType::Sink);
+
+        // Check createIndex operation
+        assert_eq!(caps[14].name.as_str(), "mongodb.createIndex");
+        assert_eq!(caps[14].stage_type, StageType::Sink);
     }
 
     #[test]
@@ -2271,6 +3107,615 @@ mod tests {
         assert!(stage.validate_config(config).is_ok());
     }
 
+    fn string_config(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_connect_reuses_client_per_uri() {
+        let stage = MongoDbStage::new(
+            "mongodb.find".to_string(),
+            MongoOperation::Find,
+            StageType::Source,
+        );
+        let config = |uri: &str, collection: &str| {
+            string_config(&[
+                ("uri", uri),
+                ("database", "testdb"),
+                ("collection", collection),
+            ])
+        };
+
+        // Building a client does not contact the server
+        let (first, _, _) = stage
+            .connect_mongodb(&config("mongodb://localhost:27117", "orders"), None)
+            .await
+            .unwrap();
+        let (second, _, collection) = stage
+            .connect_mongodb(&config("mongodb://localhost:27117", "users"), None)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(collection, "users");
+
+        let (other, _, _) = stage
+            .connect_mongodb(&config("mongodb://localhost:27118", "orders"), None)
+            .await
+            .unwrap();
+        assert!(!Arc::ptr_eq(&first, &other));
+
+        // A failed connect leaves nothing behind in the cache
+        assert!(stage
+            .connect_mongodb(&config("not-a-uri", "orders"), None)
+            .await
+            .is_err());
+        assert!(!client_cache().lock().unwrap().contains_key("not-a-uri"));
+    }
+
+    #[test]
+    fn test_parse_insert_options() {
+        assert!(matches!(
+            parse_insert_options(&HashMap::new()),
+            ROk((true, false))
+        ));
+        assert!(matches!(
+            parse_insert_options(&string_config(&[("ordered", "false")])),
+            ROk((false, false))
+        ));
+        // skip_duplicates implies an unordered insert
+        assert!(matches!(
+            parse_insert_options(&string_config(&[("skip_duplicates", "true")])),
+            ROk((false, true))
+        ));
+        assert!(parse_insert_options(&string_config(&[
+            ("ordered", "true"),
+            ("skip_duplicates", "true")
+        ]))
+        .is_err());
+        assert!(parse_insert_options(&string_config(&[("ordered", "yes")])).is_err());
+    }
+
+    #[test]
+    fn test_parse_update_options() {
+        let (options, summary) = parse_update_options(&HashMap::new()).unwrap();
+        assert_eq!(options.upsert, Some(false));
+        assert!(!summary);
+
+        let (options, summary) =
+            parse_update_options(&string_config(&[("upsert", "true"), ("summary", "true")]))
+                .unwrap();
+        assert_eq!(options.upsert, Some(true));
+        assert!(summary);
+
+        for stage in [
+            MongoDbStage::new(
+                "mongodb.updateOne".to_string(),
+                MongoOperation::UpdateOne,
+                StageType::Sink,
+            ),
+            MongoDbStage::new(
+                "mongodb.updateMany".to_string(),
+                MongoOperation::UpdateMany,
+                StageType::Sink,
+            ),
+        ] {
+            let mut config = RHashMap::new();
+            for (key, value) in [
+                ("uri", "mongodb://localhost:27017"),
+                ("database", "testdb"),
+                ("collection", "testcol"),
+                ("upsert", "yes"),
+            ] {
+                config.insert(RString::from(key), RString::from(value));
+            }
+            let err = stage.validate_config(config.clone()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Invalid 'upsert' value 'yes': expected true or false"
+            );
+
+            config.insert(RString::from("upsert"), RString::from("true"));
+            assert!(stage.validate_config(config).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_update_document() {
+        use mongodb::bson::doc;
+
+        // Plain fields keep being wrapped in $set
+        let update = update_document(doc! { "status": "active" }).unwrap();
+        assert_eq!(update, doc! { "$set": { "status": "active" } });
+
+        let inc = doc! { "$inc": { "views": 1 }, "$unset": { "draft": "" } };
+        assert_eq!(update_document(inc.clone()).unwrap(), inc);
+
+        let err = update_document(doc! { "$inc": { "views": 1 }, "status": "active" })
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Update document mixes update operators ($inc) with plain fields (status); \
+             move the plain fields into \"$set\""
+        );
+    }
+
+    #[test]
+    fn test_update_summary_record() {
+        let inserted = update_summary_record(
+            0,
+            0,
+            Some(&mongodb::bson::Bson::String("sku-1".to_string())),
+        );
+        assert_eq!(inserted["matched"], serde_json::json!(0));
+        assert_eq!(inserted["_upserted_id"], serde_json::json!("sku-1"));
+
+        let updated = update_summary_record(1, 1, None);
+        assert_eq!(updated["modified"], serde_json::json!(1));
+        assert_eq!(updated["_upserted_id"], Value::Null);
+    }
+
+    #[test]
+    fn test_parse_transactional() {
+        assert!(matches!(parse_transactional(&HashMap::new()), ROk(false)));
+        assert!(matches!(
+            parse_transactional(&string_config(&[("transactional", "true")])),
+            ROk(true)
+        ));
+        assert!(parse_transactional(&string_config(&[
+            ("transactional", "true"),
+            ("skip_duplicates", "true")
+        ]))
+        .is_err());
+        assert!(parse_transactional(&string_config(&[("transactional", "1")])).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_key_count() {
+        let write_error = |code: i32| serde_json::json!({ "index": 0, "code": code, "errmsg": "E11000 duplicate key error" });
+        let insert_many_error = |errors: Vec<serde_json::Value>| {
+            let error: InsertManyError =
+                serde_json::from_value(serde_json::json!({ "writeErrors": errors })).unwrap();
+            mongodb::error::Error::from(ErrorKind::InsertMany(error))
+        };
+
+        let duplicates = insert_many_error(vec![write_error(11000), write_error(11000)]);
+        assert_eq!(duplicate_key_count(&duplicates), Some(2));
+
+        // Any other write error must still fail the insert
+        let mixed = insert_many_error(vec![write_error(11000), write_error(121)]);
+        assert_eq!(duplicate_key_count(&mixed), None);
+    }
+
+    #[test]
+    fn test_insert_validation_rejects_ordered_skip() {
+        let stage = MongoDbStage::new(
+            "mongodb.insertMany".to_string(),
+            MongoOperation::InsertMany,
+            StageType::Sink,
+        );
+        let mut config = RHashMap::new();
+        for (key, value) in [
+            ("uri", "mongodb://localhost:27017"),
+            ("database", "testdb"),
+            ("collection", "testcol"),
+            ("skip_duplicates", "true"),
+        ] {
+            config.insert(RString::from(key), RString::from(value));
+        }
+        assert!(stage.validate_config(config.clone()).is_ok());
+
+        config.insert(RString::from("ordered"), RString::from("true"));
+        assert!(stage.validate_config(config).is_err());
+    }
+
+    #[test]
+    fn test_distinct_requires_field() {
+        let stage = MongoDbStage::new(
+            "mongodb.distinct".to_string(),
+            MongoOperation::Distinct,
+            StageType::Source,
+        );
+        let mut config = RHashMap::new();
+        for (key, value) in [
+            ("uri", "mongodb://localhost:27017"),
+            ("database", "testdb"),
+            ("collection", "testcol"),
+        ] {
+            config.insert(RString::from(key), RString::from(value));
+        }
+
+        let err = stage.validate_config(config.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing required 'field' configuration for distinct"
+        );
+
+        config.insert(RString::from("field"), RString::from("  "));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("field"), RString::from("address.city"));
+        assert!(stage.validate_config(config).is_ok());
+        assert_eq!(
+            parse_distinct_field(&string_config(&[("field", " status ")])).unwrap(),
+            "status"
+        );
+
+        // count takes the common options only
+        let count = MongoDbStage::new(
+            "mongodb.count".to_string(),
+            MongoOperation::Count,
+            StageType::Source,
+        );
+        let mut config = RHashMap::new();
+        for (key, value) in [
+            ("uri", "mongodb://localhost:27017"),
+            ("database", "testdb"),
+            ("collection", "testcol"),
+        ] {
+            config.insert(RString::from(key), RString::from(value));
+        }
+        assert!(count.validate_config(config).is_ok());
+    }
+
+    #[test]
+    fn test_distinct_records() {
+        use mongodb::bson::Bson;
+
+        let records = distinct_records(vec![
+            Bson::String("active".to_string()),
+            Bson::Int32(3),
+            Bson::Null,
+        ]);
+        let values: Vec<&Value> = records.iter().map(|r| &r["value"]).collect();
+        assert_eq!(
+            values,
+            vec![
+                &serde_json::json!("active"),
+                &serde_json::json!(3),
+                &Value::Null
+            ]
+        );
+        assert!(records.iter().all(|r| r.len() == 1));
+    }
+
+    #[test]
+    fn test_create_index_validation() {
+        let stage = MongoDbStage::new(
+            "mongodb.createIndex".to_string(),
+            MongoOperation::CreateIndex,
+            StageType::Sink,
+        );
+        let validate = |extra: &[(&str, &str)]| {
+            let mut config = RHashMap::new();
+            for (key, value) in [
+                ("uri", "mongodb://localhost:27017"),
+                ("database", "testdb"),
+                ("collection", "testcol"),
+            ]
+            .iter()
+            .chain(extra)
+            {
+                config.insert(RString::from(*key), RString::from(*value));
+            }
+            stage.validate_config(config)
+        };
+
+        assert!(validate(&[]).is_err());
+        assert!(validate(&[("keys", r#"{"email": 1}"#), ("unique", "true")]).is_ok());
+        assert!(validate(&[("keys", r#"{"location": "2dsphere"}"#)]).is_ok());
+        assert!(validate(&[("keys", "{}")]).is_err());
+        assert!(validate(&[("keys", r#"["email"]"#)]).is_err());
+        assert!(validate(&[("keys", r#"{"email": 2}"#)]).is_err());
+        assert!(validate(&[("keys", r#"{"email": "up"}"#)]).is_err());
+        assert!(validate(&[("keys", r#"{"email": 1}"#), ("sparse", "maybe")]).is_err());
+        assert!(validate(&[
+            ("keys", r#"{"created_at": 1}"#),
+            ("expire_after_seconds", "-1")
+        ])
+        .is_err());
+        // TTL indexes are single-field only
+        assert!(validate(&[
+            ("keys", r#"{"user": 1, "created_at": 1}"#),
+            ("expire_after_seconds", "3600")
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_index_model() {
+        let index = parse_index_model(&string_config(&[
+            ("keys", r#"{"tenant": 1, "created_at": -1, "body": "text"}"#),
+            ("unique", "true"),
+            ("name", "tenant_recent"),
+        ]))
+        .unwrap();
+        // Compound keys keep their order
+        let fields: Vec<&String> = index.keys.keys().collect();
+        assert_eq!(fields, vec!["tenant", "created_at", "body"]);
+        assert_eq!(index.keys.get_i32("created_at"), Ok(-1));
+
+        let options = index.options.unwrap();
+        assert_eq!(options.unique, Some(true));
+        assert_eq!(options.sparse, None);
+        assert_eq!(options.name.as_deref(), Some("tenant_recent"));
+
+        // The command sent to the server uses MongoDB's option names
+        let index = parse_index_model(&string_config(&[
+            ("keys", r#"{"created_at": 1}"#),
+            ("sparse", "true"),
+            ("expire_after_seconds", "86400"),
+        ]))
+        .unwrap();
+        let command = mongodb::bson::to_document(&index).unwrap();
+        assert_eq!(
+            command,
+            mongodb::bson::doc! {
+                "key": { "created_at": 1 },
+                "expireAfterSeconds": 86400,
+                "sparse": true,
+            }
+        );
+    }
+
+    /// Needs Docker: `cargo test -p conveyor-plugin-mongodb --features mongo-integration`
+    #[cfg(feature = "mongo-integration")]
+    #[tokio::test]
+    async fn test_count_and_distinct() {
+        use testcontainers_modules::{mongo::Mongo, testcontainers::runners::AsyncRunner};
+
+        let container = Mongo::default().start().await.unwrap();
+        let uri = format!(
+            "mongodb://{}:{}",
+            container.get_host().await.unwrap(),
+            container.get_host_port_ipv4(27017).await.unwrap()
+        );
+        let client = Client::with_uri_str(&uri).await.unwrap();
+        client
+            .database("conveyor_test")
+            .collection::<Document>("orders")
+            .insert_many(vec![
+                mongodb::bson::doc! { "status": "paid", "region": "eu" },
+                mongodb::bson::doc! { "status": "paid", "region": "us" },
+                mongodb::bson::doc! { "status": "open", "region": "eu" },
+            ])
+            .await
+            .unwrap();
+
+        let mut config = string_config(&[
+            ("uri", uri.as_str()),
+            ("database", "conveyor_test"),
+            ("collection", "orders"),
+            ("query", r#"{"status": "paid"}"#),
+        ]);
+        let count = MongoDbStage::new(
+            "mongodb.count".to_string(),
+            MongoOperation::Count,
+            StageType::Source,
+        );
+        let records = count
+            .execute_count_async(&config, None)
+            .await
+            .unwrap()
+            .to_json_records()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["count"], serde_json::json!(2));
+
+        config.insert("field".to_string(), "region".to_string());
+        config.insert("query".to_string(), r#"{"region": "eu"}"#.to_string());
+        let distinct = MongoDbStage::new(
+            "mongodb.distinct".to_string(),
+            MongoOperation::Distinct,
+            StageType::Source,
+        );
+        let records = distinct
+            .execute_distinct_async(&config, None)
+            .await
+            .unwrap()
+            .to_json_records()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["value"], serde_json::json!("eu"));
+    }
+
+    /// Needs Docker: `cargo test -p conveyor-plugin-mongodb --features mongo-integration`
+    #[cfg(feature = "mongo-integration")]
+    #[tokio::test]
+    async fn test_update_one_upserts() {
+        use testcontainers_modules::{mongo::Mongo, testcontainers::runners::AsyncRunner};
+
+        let container = Mongo::default().start().await.unwrap();
+        let uri = format!(
+            "mongodb://{}:{}",
+            container.get_host().await.unwrap(),
+            container.get_host_port_ipv4(27017).await.unwrap()
+        );
+        let config = string_config(&[
+            ("uri", uri.as_str()),
+            ("database", "conveyor_test"),
+            ("collection", "stock"),
+            ("query", r#"{"sku": "A-1"}"#),
+            ("upsert", "true"),
+            ("summary", "true"),
+        ]);
+        let stage = MongoDbStage::new(
+            "mongodb.updateOne".to_string(),
+            MongoOperation::UpdateOne,
+            StageType::Sink,
+        );
+        let update = |qty: i64| {
+            FfiDataFormat::from_json_records(&[HashMap::from([(
+                "qty".to_string(),
+                serde_json::json!(qty),
+            )])])
+            .unwrap()
+        };
+
+        // Nothing matches yet, so the first update inserts
+        let summary = stage
+            .execute_update_one_async(&update(5), &config)
+            .await
+            .unwrap()
+            .to_json_records()
+            .unwrap();
+        assert_eq!(summary[0]["matched"], serde_json::json!(0));
+        assert!(summary[0]["_upserted_id"].is_object());
+
+        let summary = stage
+            .execute_update_one_async(&update(7), &config)
+            .await
+            .unwrap()
+            .to_json_records()
+            .unwrap();
+        assert_eq!(summary[0]["modified"], serde_json::json!(1));
+        assert_eq!(summary[0]["_upserted_id"], Value::Null);
+
+        let client = Client::with_uri_str(&uri).await.unwrap();
+        let doc = client
+            .database("conveyor_test")
+            .collection::<Document>("stock")
+            .find_one(mongodb::bson::doc! { "sku": "A-1" })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(doc.get_i64("qty").unwrap(), 7);
+    }
+
+    /// Needs Docker: `cargo test -p conveyor-plugin-mongodb --features mongo-integration`
+    #[cfg(feature = "mongo-integration")]
+    #[tokio::test]
+    async fn test_insert_many_skips_duplicates() {
+        use testcontainers_modules::{mongo::Mongo, testcontainers::runners::AsyncRunner};
+
+        let container = Mongo::default().start().await.unwrap();
+        let uri = format!(
+            "mongodb://{}:{}",
+            container.get_host().await.unwrap(),
+            container.get_host_port_ipv4(27017).await.unwrap()
+        );
+        let config = string_config(&[
+            ("uri", uri.as_str()),
+            ("database", "conveyor_test"),
+            ("collection", "people"),
+            ("skip_duplicates", "true"),
+        ]);
+        let stage = MongoDbStage::new(
+            "mongodb.insertMany".to_string(),
+            MongoOperation::InsertMany,
+            StageType::Sink,
+        );
+
+        let record = |id: i64, name: &str| {
+            HashMap::from([
+                ("_id".to_string(), serde_json::json!(id)),
+                ("name".to_string(), serde_json::json!(name)),
+            ])
+        };
+
+        let first = FfiDataFormat::from_json_records(&vec![record(1, "Alice")]).unwrap();
+        stage
+            .execute_insert_many_async(&first, &config)
+            .await
+            .unwrap();
+
+        // _id 1 already exists; the other documents must still be inserted
+        let batch = FfiDataFormat::from_json_records(&vec![
+            record(2, "Bob"),
+            record(1, "Alice again"),
+            record(3, "Carol"),
+        ])
+        .unwrap();
+        let summary = stage
+            .execute_insert_many_async(&batch, &config)
+            .await
+            .unwrap()
+            .to_json_records()
+            .unwrap();
+        assert_eq!(summary[0]["inserted"], serde_json::json!(2));
+        assert_eq!(summary[0]["skipped_duplicates"], serde_json::json!(1));
+
+        let client = Client::with_uri_str(&uri).await.unwrap();
+        let count = client
+            .database("conveyor_test")
+            .collection::<Document>("people")
+            .count_documents(mongodb::bson::doc! {})
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    /// Needs Docker: `cargo test -p conveyor-plugin-mongodb --features mongo-integration`
+    #[cfg(feature = "mongo-integration")]
+    #[tokio::test]
+    async fn test_transactional_insert_many_leaves_no_partial_rows() {
+        use testcontainers_modules::{mongo::Mongo, testcontainers::runners::AsyncRunner};
+
+        // Transactions need a replica set
+        let container = Mongo::repl_set().start().await.unwrap();
+        let uri = format!(
+            "mongodb://{}:{}/?directConnection=true",
+            container.get_host().await.unwrap(),
+            container.get_host_port_ipv4(27017).await.unwrap()
+        );
+        let client = Client::with_uri_str(&uri).await.unwrap();
+        let collection = client
+            .database("conveyor_test")
+            .collection::<Document>("orders");
+        // Create the collection up front; it cannot be created inside a transaction on older servers
+        collection
+            .insert_one(mongodb::bson::doc! { "_id": 1, "item": "existing" })
+            .await
+            .unwrap();
+
+        let stage = MongoDbStage::new(
+            "mongodb.insertMany".to_string(),
+            MongoOperation::InsertMany,
+            StageType::Sink,
+        );
+        let record = |id: i64| HashMap::from([("_id".to_string(), serde_json::json!(id))]);
+        // _id 1 already exists, so the third document fails after two succeeded
+        let batch =
+            FfiDataFormat::from_json_records(&vec![record(2), record(3), record(1), record(4)])
+                .unwrap();
+
+        let transactional = string_config(&[
+            ("uri", uri.as_str()),
+            ("database", "conveyor_test"),
+            ("collection", "orders"),
+            ("transactional", "true"),
+        ]);
+        let err = match stage
+            .execute_insert_many_async(&batch, &transactional)
+            .await
+        {
+            RErr(e) => e.to_string(),
+            ROk(_) => panic!("Expected the duplicate _id to fail the batch"),
+        };
+        assert!(err.contains("no documents were written"), "{}", err);
+        let count = collection
+            .count_documents(mongodb::bson::doc! {})
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        // Without a transaction the documents before the failure stay behind
+        let mut plain = transactional.clone();
+        plain.remove("transactional");
+        assert!(stage
+            .execute_insert_many_async(&batch, &plain)
+            .await
+            .is_err());
+        let count = collection
+            .count_documents(mongodb::bson::doc! {})
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
     #[test]
     fn test_json_to_bson() {
         use serde_json::json;
@@ -2339,9 +3784,9 @@ mod tests {
         ));
 
         // Test $numberDouble
-        let val = json!({"$numberDouble": "3.14159"});
+        let val = json!({"$numberDouble": "1.23456"});
         if let Some(mongodb::bson::Bson::Double(d)) = json_to_bson(&val) {
-            assert!((d - 3.14159).abs() < 0.00001);
+            assert!((d - 1.23456).abs() < 0.00001);
         } else {
             panic!("Expected Double");
         }
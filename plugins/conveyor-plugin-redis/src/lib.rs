@@ -0,0 +1,1257 @@
+//! Redis Plugin for Conveyor - Operation-based API
+//!
+//! Provides Redis operations: read (list, stream or key lookups) and write
+//! (SET, HSET, XADD or RPUSH). The `mode` of a stage picks the command, so the
+//! same two stages cover Redis as a lookup cache and as a streaming bus.
+
+use conveyor_plugin_api::sabi_trait::prelude::*;
+use conveyor_plugin_api::traits::{FfiExecutionContext, FfiStage, FfiStage_TO};
+use conveyor_plugin_api::{
+    rstr, FfiConfigParameter, FfiDataFormat, FfiParameterType, FfiStageMetadata, PluginCapability,
+    PluginDeclaration, RBox, RBoxError, RErr, RHashMap, ROk, RResult, RString, RVec, StageType,
+    PLUGIN_API_VERSION,
+};
+use redis::aio::MultiplexedConnection;
+use redis::streams::StreamReadReply;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+
+const DEFAULT_BATCH_SIZE: usize = 500;
+const DEFAULT_STREAM_COUNT: usize = 1000;
+const DEFAULT_VALUE_FIELD: &str = "value";
+
+type Record = HashMap<String, Value>;
+
+/// Redis operation types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisOperation {
+    Read,
+    Write,
+}
+
+/// Redis Stage - operation-based
+pub struct RedisStage {
+    name: String,
+    operation: RedisOperation,
+    stage_type: StageType,
+}
+
+impl RedisStage {
+    fn new(name: String, operation: RedisOperation, stage_type: StageType) -> Self {
+        Self {
+            name,
+            operation,
+            stage_type,
+        }
+    }
+
+    /// Execute read operation - read a list or stream, or look up keys
+    async fn execute_read_async(
+        &self,
+        input_data: Option<&FfiDataFormat>,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let records = match input_data {
+            Some(data) => match data.to_json_records() {
+                ROk(records) => records,
+                RErr(e) => return RErr(e),
+            },
+            None => Vec::new(),
+        };
+
+        let result = async {
+            let mut conn = connect(config).await?;
+            read(&mut conn, config, records).await
+        }
+        .await;
+
+        match result {
+            Ok(records) => FfiDataFormat::from_json_records(&records),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+
+    /// Execute write operation - write each input record
+    async fn execute_write_async(
+        &self,
+        input_data: &FfiDataFormat,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let records = match input_data.to_json_records() {
+            ROk(records) => records,
+            RErr(e) => return RErr(e),
+        };
+
+        let result = async {
+            let mut conn = connect(config).await?;
+            write(&mut conn, config, &records).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => ROk(input_data.clone()),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+}
+
+impl FfiStage for RedisStage {
+    fn name(&self) -> conveyor_plugin_api::RStr<'_> {
+        self.name.as_str().into()
+    }
+
+    fn stage_type(&self) -> StageType {
+        self.stage_type
+    }
+
+    fn execute(&self, context: FfiExecutionContext) -> RResult<FfiDataFormat, RBoxError> {
+        // Convert config to HashMap
+        let config: HashMap<String, String> = context
+            .config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        // Use tokio runtime to execute async code
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Failed to create runtime: {}",
+                    e
+                )))
+            }
+        };
+
+        runtime.block_on(async {
+            let input_data = context.inputs.into_iter().next().map(|tuple| tuple.1);
+
+            match self.operation {
+                RedisOperation::Read => self.execute_read_async(input_data.as_ref(), &config).await,
+                RedisOperation::Write => {
+                    let input_data = match input_data {
+                        Some(data) => data,
+                        None => {
+                            return RErr(RBoxError::from_fmt(&format_args!(
+                                "write requires input data"
+                            )))
+                        }
+                    };
+                    self.execute_write_async(&input_data, &config).await
+                }
+            }
+        })
+    }
+
+    fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError> {
+        let config: HashMap<String, String> = config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        match validate(self.operation, &config) {
+            Ok(()) => ROk(()),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+}
+
+// ============================================================================
+// Config Helpers
+// ============================================================================
+
+/// What a read stage fetches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadMode {
+    /// LRANGE of one list
+    List,
+    /// XREAD of one stream, page by page
+    Stream,
+    /// GET of one key, or MGET of a key per input record
+    Get,
+}
+
+impl ReadMode {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match config.get("mode").map(|m| m.as_str()) {
+            Some("list") => Ok(ReadMode::List),
+            Some("stream") => Ok(ReadMode::Stream),
+            Some("get") => Ok(ReadMode::Get),
+            Some(other) => Err(format!(
+                "Invalid read mode '{}': must be list, stream or get",
+                other
+            )),
+            None => Err("Missing required 'mode' configuration".to_string()),
+        }
+    }
+}
+
+/// Command a write stage sends per record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteMode {
+    Set,
+    Hset,
+    Xadd,
+    Rpush,
+}
+
+impl WriteMode {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match config.get("mode").map(|m| m.as_str()) {
+            Some("set") => Ok(WriteMode::Set),
+            Some("hset") => Ok(WriteMode::Hset),
+            Some("xadd") => Ok(WriteMode::Xadd),
+            Some("rpush") => Ok(WriteMode::Rpush),
+            Some(other) => Err(format!(
+                "Invalid write mode '{}': must be set, hset, xadd or rpush",
+                other
+            )),
+            None => Err("Missing required 'mode' configuration".to_string()),
+        }
+    }
+
+    fn command(self) -> &'static str {
+        match self {
+            WriteMode::Set => "SET",
+            WriteMode::Hset => "HSET",
+            WriteMode::Xadd => "XADD",
+            WriteMode::Rpush => "RPUSH",
+        }
+    }
+}
+
+/// Where the key of a command comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeySource<'a> {
+    /// The same `key` for every record
+    Fixed(&'a str),
+    /// `key_prefix` followed by the record's `key_field`
+    Field { field: &'a str, prefix: &'a str },
+}
+
+impl<'a> KeySource<'a> {
+    fn from_config(config: &'a HashMap<String, String>) -> Result<Self, String> {
+        match (config.get("key"), config.get("key_field")) {
+            (Some(key), None) => Ok(KeySource::Fixed(key)),
+            (None, Some(field)) => Ok(KeySource::Field {
+                field,
+                prefix: config.get("key_prefix").map(|p| p.as_str()).unwrap_or(""),
+            }),
+            (Some(_), Some(_)) => Err("'key' and 'key_field' are mutually exclusive".to_string()),
+            (None, None) => Err("Missing required 'key' or 'key_field' configuration".to_string()),
+        }
+    }
+
+    /// Key for a record; `None` when its key field is missing or null
+    fn key_for(&self, record: &Record) -> Option<String> {
+        match self {
+            KeySource::Fixed(key) => Some(key.to_string()),
+            KeySource::Field { field, prefix } => match record.get(*field) {
+                None | Some(Value::Null) => None,
+                Some(Value::String(s)) => Some(format!("{}{}", prefix, s)),
+                Some(other) => Some(format!("{}{}", prefix, other)),
+            },
+        }
+    }
+}
+
+/// The single key that list and stream reads need
+fn single_key<'a>(config: &'a HashMap<String, String>, mode: &str) -> Result<&'a str, String> {
+    match KeySource::from_config(config)? {
+        KeySource::Fixed(key) => Ok(key),
+        KeySource::Field { .. } => Err(format!(
+            "'{}' mode reads a single key: set 'key' instead of 'key_field'",
+            mode
+        )),
+    }
+}
+
+fn parse_positive(
+    config: &HashMap<String, String>,
+    key: &str,
+    default: usize,
+) -> Result<usize, String> {
+    match config.get(key) {
+        None => Ok(default),
+        Some(value) => match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("'{}' must be a positive integer", key)),
+        },
+    }
+}
+
+fn parse_optional_positive(
+    config: &HashMap<String, String>,
+    key: &str,
+) -> Result<Option<usize>, String> {
+    match config.get(key) {
+        None => Ok(None),
+        Some(_) => parse_positive(config, key, 1).map(Some),
+    }
+}
+
+fn parse_index(config: &HashMap<String, String>, key: &str, default: i64) -> Result<i64, String> {
+    match config.get(key) {
+        None => Ok(default),
+        Some(value) => value
+            .parse::<i64>()
+            .map_err(|_| format!("'{}' must be an integer", key)),
+    }
+}
+
+fn value_field(config: &HashMap<String, String>) -> &str {
+    config
+        .get("value_field")
+        .map(|f| f.as_str())
+        .unwrap_or(DEFAULT_VALUE_FIELD)
+}
+
+// ============================================================================
+// Value Conversion
+// ============================================================================
+
+/// Read a stored string back: JSON when it parses, the raw text otherwise
+fn decode_value(text: String) -> Value {
+    serde_json::from_str(&text).unwrap_or(Value::String(text))
+}
+
+/// Record for a list element: JSON objects as-is, anything else under `value_field`
+fn decode_record(text: String, value_field: &str) -> Record {
+    match decode_value(text) {
+        Value::Object(fields) => fields.into_iter().collect(),
+        other => HashMap::from([(value_field.to_string(), other)]),
+    }
+}
+
+/// Hash or stream fields of a record; strings are stored raw, nulls are left out
+fn encode_fields(record: &Record) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = record
+        .iter()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| match v {
+            Value::String(s) => (k.clone(), s.clone()),
+            other => (k.clone(), other.to_string()),
+        })
+        .collect();
+    fields.sort();
+    fields
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+async fn connect(config: &HashMap<String, String>) -> Result<MultiplexedConnection, String> {
+    let url = config
+        .get("url")
+        .ok_or("Missing required 'url' configuration")?;
+    let client =
+        redis::Client::open(url.as_str()).map_err(|e| format!("Invalid Redis 'url': {}", e))?;
+    client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| format!("Failed to connect to Redis: {}", e))
+}
+
+async fn read(
+    conn: &mut MultiplexedConnection,
+    config: &HashMap<String, String>,
+    input: Vec<Record>,
+) -> Result<Vec<Record>, String> {
+    match ReadMode::from_config(config)? {
+        ReadMode::List => read_list(conn, config).await,
+        ReadMode::Stream => read_stream(conn, config).await,
+        ReadMode::Get => lookup(conn, config, input).await,
+    }
+}
+
+async fn read_list(
+    conn: &mut MultiplexedConnection,
+    config: &HashMap<String, String>,
+) -> Result<Vec<Record>, String> {
+    let key = single_key(config, "list")?;
+    let items: Vec<String> = redis::cmd("LRANGE")
+        .arg(key)
+        .arg(parse_index(config, "start", 0)?)
+        .arg(parse_index(config, "stop", -1)?)
+        .query_async(conn)
+        .await
+        .map_err(|e| format!("Redis LRANGE failed: {}", e))?;
+
+    let value_field = value_field(config);
+    Ok(items
+        .into_iter()
+        .map(|item| decode_record(item, value_field))
+        .collect())
+}
+
+/// Read the entries after `start_id`, `count` per XREAD, until the stream is exhausted
+async fn read_stream(
+    conn: &mut MultiplexedConnection,
+    config: &HashMap<String, String>,
+) -> Result<Vec<Record>, String> {
+    let key = single_key(config, "stream")?;
+    let count = parse_positive(config, "count", DEFAULT_STREAM_COUNT)?;
+    let max_entries = parse_optional_positive(config, "max_entries")?;
+    let mut last_id = config
+        .get("start_id")
+        .cloned()
+        .unwrap_or_else(|| "0".to_string());
+
+    let mut records = Vec::new();
+    loop {
+        let wanted = match max_entries {
+            Some(max) => count.min(max - records.len()),
+            None => count,
+        };
+        if wanted == 0 {
+            break;
+        }
+
+        let reply: Option<StreamReadReply> = redis::cmd("XREAD")
+            .arg("COUNT")
+            .arg(wanted)
+            .arg("STREAMS")
+            .arg(key)
+            .arg(&last_id)
+            .query_async(conn)
+            .await
+            .map_err(|e| format!("Redis XREAD failed: {}", e))?;
+        let entries: Vec<_> = reply
+            .map(|r| r.keys.into_iter().flat_map(|k| k.ids).collect())
+            .unwrap_or_default();
+
+        let received = entries.len();
+        for entry in entries {
+            let mut record = Record::new();
+            for (field, value) in entry.map {
+                let text: String = redis::from_redis_value(&value)
+                    .map_err(|e| format!("Stream field '{}' is not a string: {}", field, e))?;
+                record.insert(field, decode_value(text));
+            }
+            record.insert("_id".to_string(), json!(entry.id));
+            last_id = entry.id;
+            records.push(record);
+        }
+        if received < wanted {
+            break;
+        }
+    }
+
+    Ok(records)
+}
+
+/// GET a fixed key, or MGET each input record's key into `value_field`
+async fn lookup(
+    conn: &mut MultiplexedConnection,
+    config: &HashMap<String, String>,
+    mut input: Vec<Record>,
+) -> Result<Vec<Record>, String> {
+    let value_field = value_field(config).to_string();
+    let source = KeySource::from_config(config)?;
+
+    if let KeySource::Fixed(key) = source {
+        let value: Option<String> = redis::cmd("GET")
+            .arg(key)
+            .query_async(conn)
+            .await
+            .map_err(|e| format!("Redis GET failed: {}", e))?;
+        return Ok(vec![HashMap::from([
+            ("key".to_string(), json!(key)),
+            (value_field, value.map(decode_value).unwrap_or(Value::Null)),
+        ])]);
+    }
+
+    let batch_size = parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE)?;
+    for batch in input.chunks_mut(batch_size) {
+        let keys: Vec<Option<String>> = batch.iter().map(|r| source.key_for(r)).collect();
+        let present: Vec<&String> = keys.iter().flatten().collect();
+
+        let mut values = if present.is_empty() {
+            Vec::new()
+        } else {
+            redis::cmd("MGET")
+                .arg(&present)
+                .query_async::<Vec<Option<String>>>(conn)
+                .await
+                .map_err(|e| format!("Redis MGET failed: {}", e))?
+        }
+        .into_iter();
+
+        // Records without a key get null, like a key that is not set
+        for (record, key) in batch.iter_mut().zip(&keys) {
+            let value = match key {
+                Some(_) => values.next().flatten().map(decode_value),
+                None => None,
+            };
+            record.insert(value_field.clone(), value.unwrap_or(Value::Null));
+        }
+    }
+
+    Ok(input)
+}
+
+/// Send one command per record, pipelined `batch_size` records at a time
+async fn write(
+    conn: &mut MultiplexedConnection,
+    config: &HashMap<String, String>,
+    records: &[Record],
+) -> Result<(), String> {
+    let mode = WriteMode::from_config(config)?;
+    let source = KeySource::from_config(config)?;
+    let ttl = parse_optional_positive(config, "ttl_seconds")?;
+    let max_len = parse_optional_positive(config, "max_len")?;
+    let batch_size = parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE)?;
+
+    for (batch_index, batch) in records.chunks(batch_size).enumerate() {
+        let mut pipe = redis::pipe();
+        let mut expiring = BTreeSet::new();
+
+        for (offset, record) in batch.iter().enumerate() {
+            let index = batch_index * batch_size + offset;
+            let key = source.key_for(record).ok_or_else(|| match &source {
+                KeySource::Field { field, .. } => {
+                    format!("Record {} has no value for key field '{}'", index, field)
+                }
+                KeySource::Fixed(_) => unreachable!("a fixed key is always present"),
+            })?;
+
+            match mode {
+                WriteMode::Set => {
+                    pipe.cmd("SET").arg(&key).arg(json!(record).to_string());
+                    if let Some(ttl) = ttl {
+                        pipe.arg("EX").arg(ttl);
+                    }
+                }
+                WriteMode::Hset | WriteMode::Xadd => {
+                    let fields = encode_fields(record);
+                    if fields.is_empty() {
+                        return Err(format!("Record {} has no non-null fields to write", index));
+                    }
+                    pipe.cmd(mode.command()).arg(&key);
+                    if mode == WriteMode::Xadd {
+                        if let Some(max_len) = max_len {
+                            pipe.arg("MAXLEN").arg("~").arg(max_len);
+                        }
+                        pipe.arg("*");
+                    }
+                    for (field, value) in fields {
+                        pipe.arg(field).arg(value);
+                    }
+                }
+                WriteMode::Rpush => {
+                    pipe.cmd("RPUSH").arg(&key).arg(json!(record).to_string());
+                }
+            }
+            pipe.ignore();
+
+            if ttl.is_some() && mode != WriteMode::Set {
+                expiring.insert(key);
+            }
+        }
+
+        // SET takes its TTL inline; the other commands add to a key, so expire it once per batch
+        if let Some(ttl) = ttl {
+            for key in expiring {
+                pipe.cmd("EXPIRE").arg(key).arg(ttl).ignore();
+            }
+        }
+
+        pipe.query_async::<()>(conn)
+            .await
+            .map_err(|e| format!("Redis {} failed: {}", mode.command(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Validate the configuration of an operation
+fn validate(operation: RedisOperation, config: &HashMap<String, String>) -> Result<(), String> {
+    let url = config
+        .get("url")
+        .ok_or("Missing required 'url' configuration")?;
+    redis::Client::open(url.as_str()).map_err(|e| format!("Invalid Redis 'url': {}", e))?;
+    KeySource::from_config(config)?;
+    parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE)?;
+
+    match operation {
+        RedisOperation::Read => match ReadMode::from_config(config)? {
+            ReadMode::List => {
+                single_key(config, "list")?;
+                parse_index(config, "start", 0)?;
+                parse_index(config, "stop", -1)?;
+            }
+            ReadMode::Stream => {
+                single_key(config, "stream")?;
+                parse_positive(config, "count", DEFAULT_STREAM_COUNT)?;
+                parse_optional_positive(config, "max_entries")?;
+            }
+            ReadMode::Get => {}
+        },
+        RedisOperation::Write => {
+            let mode = WriteMode::from_config(config)?;
+            parse_optional_positive(config, "ttl_seconds")?;
+            if config.contains_key("max_len") {
+                if mode != WriteMode::Xadd {
+                    return Err("'max_len' only applies to xadd mode".to_string());
+                }
+                parse_optional_positive(config, "max_len")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Factory functions for each operation
+#[no_mangle]
+pub extern "C" fn create_redis_read() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        RedisStage::new(
+            "redis-read".to_string(),
+            RedisOperation::Read,
+            StageType::Source,
+        ),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_redis_write() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        RedisStage::new(
+            "redis-write".to_string(),
+            RedisOperation::Write,
+            StageType::Sink,
+        ),
+        TD_Opaque,
+    )
+}
+
+// ============================================================================
+// Metadata Helper Functions
+// ============================================================================
+
+/// Create common Redis parameters (url, key, batching)
+fn common_redis_parameters() -> Vec<FfiConfigParameter> {
+    vec![
+        FfiConfigParameter::required(
+            "url",
+            FfiParameterType::String,
+            "Redis URL (e.g., redis://localhost:6379/0, rediss:// for TLS)",
+        ),
+        FfiConfigParameter::optional(
+            "key",
+            FfiParameterType::String,
+            "",
+            "Key used for every record (list, stream or string key)",
+        ),
+        FfiConfigParameter::optional(
+            "key_field",
+            FfiParameterType::String,
+            "",
+            "Record field holding each record's key (instead of 'key')",
+        ),
+        FfiConfigParameter::optional(
+            "key_prefix",
+            FfiParameterType::String,
+            "",
+            "Prepended to 'key_field' values (e.g., 'user:')",
+        ),
+        FfiConfigParameter::optional(
+            "batch_size",
+            FfiParameterType::Integer,
+            "500",
+            "Keys per MGET, or records per pipeline when writing",
+        ),
+    ]
+}
+
+/// Create metadata for read operation
+fn create_read_metadata() -> FfiStageMetadata {
+    let mut params = vec![FfiConfigParameter::required(
+        "mode",
+        FfiParameterType::String,
+        "What to read: a list (LRANGE), a stream (XREAD) or keys (GET/MGET)",
+    )
+    .with_allowed_values(["list", "stream", "get"])];
+    params.extend(common_redis_parameters());
+    params.extend(vec![
+        FfiConfigParameter::optional(
+            "value_field",
+            FfiParameterType::String,
+            DEFAULT_VALUE_FIELD,
+            "Field receiving looked-up values and non-object list items",
+        ),
+        FfiConfigParameter::optional(
+            "start",
+            FfiParameterType::Integer,
+            "0",
+            "First list index to read (list mode)",
+        ),
+        FfiConfigParameter::optional(
+            "stop",
+            FfiParameterType::Integer,
+            "-1",
+            "Last list index to read, inclusive; negative counts from the end (list mode)",
+        ),
+        FfiConfigParameter::optional(
+            "start_id",
+            FfiParameterType::String,
+            "0",
+            "Read stream entries after this ID (stream mode)",
+        ),
+        FfiConfigParameter::optional(
+            "count",
+            FfiParameterType::Integer,
+            "1000",
+            "Entries per XREAD call (stream mode)",
+        ),
+        FfiConfigParameter::optional(
+            "max_entries",
+            FfiParameterType::Integer,
+            "",
+            "Stop after this many stream entries (stream mode)",
+        ),
+    ]);
+
+    FfiStageMetadata::new(
+        "redis.read",
+        "Read a Redis list or stream, or look up keys",
+        "In 'list' mode, returns the LRANGE of 'key' from 'start' to 'stop'. \
+         In 'stream' mode, reads the entries of stream 'key' after 'start_id' with XREAD, \
+         'count' at a time, until the stream is exhausted or 'max_entries' is reached; \
+         each entry becomes a record of its fields plus an '_id' field. \
+         In 'get' mode, GETs 'key' into a single record, or with 'key_field' MGETs each \
+         input record's key and adds the value as 'value_field' (null when unset). \
+         Values holding JSON are parsed; JSON objects in a list become records.",
+        params,
+        vec!["redis", "cache", "stream", "lookup", "source"],
+    )
+}
+
+/// Create metadata for write operation
+fn create_write_metadata() -> FfiStageMetadata {
+    let mut params = vec![FfiConfigParameter::required(
+        "mode",
+        FfiParameterType::String,
+        "Command sent per record",
+    )
+    .with_allowed_values(["set", "hset", "xadd", "rpush"])];
+    params.extend(common_redis_parameters());
+    params.extend(vec![
+        FfiConfigParameter::optional(
+            "ttl_seconds",
+            FfiParameterType::Integer,
+            "",
+            "Expire written keys after this many seconds",
+        ),
+        FfiConfigParameter::optional(
+            "max_len",
+            FfiParameterType::Integer,
+            "",
+            "Trim the stream to about this many entries (xadd mode)",
+        ),
+    ]);
+
+    FfiStageMetadata::new(
+        "redis.write",
+        "Write input records to Redis",
+        "Sends one command per input record, pipelined 'batch_size' records at a time. \
+         'set' stores the record as a JSON string, 'hset' stores its fields in a hash, \
+         'xadd' appends its fields to a stream and 'rpush' appends the record as JSON \
+         to a list. Hash and stream fields hold strings as-is and other values as JSON; \
+         null fields are skipped. With 'ttl_seconds', SET writes expire and the keys \
+         written by the other modes get an EXPIRE. Input data is passed through unchanged.",
+        params,
+        vec!["redis", "cache", "stream", "queue", "sink"],
+    )
+}
+
+// ============================================================================
+// Plugin Capabilities
+// ============================================================================
+
+// Plugin capabilities
+extern "C" fn get_capabilities() -> RVec<PluginCapability> {
+    vec![
+        PluginCapability::new(
+            "redis.read",
+            StageType::Source,
+            "Redis read - lists, streams and key lookups",
+            "create_redis_read",
+            create_read_metadata(),
+        ),
+        PluginCapability::new(
+            "redis.write",
+            StageType::Sink,
+            "Redis write - SET, HSET, XADD or RPUSH records",
+            "create_redis_write",
+            create_write_metadata(),
+        ),
+    ]
+    .into()
+}
+
+// Plugin declaration
+#[no_mangle]
+pub static _plugin_declaration: PluginDeclaration = PluginDeclaration {
+    api_version: PLUGIN_API_VERSION,
+    name: rstr!("redis"),
+    version: rstr!("0.1.0"),
+    description: rstr!("Redis plugin with operation-based API (read, write)"),
+    get_capabilities,
+};
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// In-memory data behind the mock server
+    #[derive(Default)]
+    struct MockStore {
+        strings: HashMap<String, String>,
+        hashes: HashMap<String, BTreeMap<String, String>>,
+        lists: HashMap<String, Vec<String>>,
+        streams: HashMap<String, Vec<(u64, Vec<String>)>>,
+        ttls: HashMap<String, u64>,
+    }
+
+    enum Reply {
+        Ok,
+        Int(i64),
+        Bulk(Option<String>),
+        Array(Option<Vec<Reply>>),
+        Error(String),
+    }
+
+    impl Reply {
+        fn encode(&self, out: &mut Vec<u8>) {
+            match self {
+                Reply::Ok => out.extend_from_slice(b"+OK\r\n"),
+                Reply::Int(n) => out.extend_from_slice(format!(":{}\r\n", n).as_bytes()),
+                Reply::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+                Reply::Bulk(Some(s)) => {
+                    out.extend_from_slice(format!("${}\r\n{}\r\n", s.len(), s).as_bytes())
+                }
+                Reply::Array(None) => out.extend_from_slice(b"*-1\r\n"),
+                Reply::Array(Some(items)) => {
+                    out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                    for item in items {
+                        item.encode(out);
+                    }
+                }
+                Reply::Error(e) => out.extend_from_slice(format!("-{}\r\n", e).as_bytes()),
+            }
+        }
+    }
+
+    fn bulk(s: &str) -> Reply {
+        Reply::Bulk(Some(s.to_string()))
+    }
+
+    /// Parse one RESP array of bulk strings, returning it and the bytes consumed
+    fn parse_command(buf: &[u8]) -> Option<(Vec<String>, usize)> {
+        fn line(buf: &[u8], pos: usize) -> Option<(usize, usize)> {
+            let end = buf[pos..].windows(2).position(|w| w == b"\r\n")? + pos;
+            let n = std::str::from_utf8(&buf[pos + 1..end]).ok()?.parse().ok()?;
+            Some((n, end + 2))
+        }
+
+        let (count, mut pos) = line(buf, 0)?;
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (len, start) = line(buf, pos)?;
+            if buf.len() < start + len + 2 {
+                return None;
+            }
+            args.push(String::from_utf8_lossy(&buf[start..start + len]).to_string());
+            pos = start + len + 2;
+        }
+        Some((args, pos))
+    }
+
+    fn handle(store: &mut MockStore, args: &[String]) -> Reply {
+        match args[0].to_uppercase().as_str() {
+            "CLIENT" => Reply::Ok,
+            "GET" => Reply::Bulk(store.strings.get(&args[1]).cloned()),
+            "MGET" => Reply::Array(Some(
+                args[1..]
+                    .iter()
+                    .map(|k| Reply::Bulk(store.strings.get(k).cloned()))
+                    .collect(),
+            )),
+            "SET" => {
+                store.strings.insert(args[1].clone(), args[2].clone());
+                if args.len() == 5 && args[3] == "EX" {
+                    store.ttls.insert(args[1].clone(), args[4].parse().unwrap());
+                }
+                Reply::Ok
+            }
+            "HSET" => {
+                let hash = store.hashes.entry(args[1].clone()).or_default();
+                for pair in args[2..].chunks(2) {
+                    hash.insert(pair[0].clone(), pair[1].clone());
+                }
+                Reply::Int((args.len() as i64 - 2) / 2)
+            }
+            "EXPIRE" => {
+                store.ttls.insert(args[1].clone(), args[2].parse().unwrap());
+                Reply::Int(1)
+            }
+            "RPUSH" => {
+                let list = store.lists.entry(args[1].clone()).or_default();
+                list.extend(args[2..].iter().cloned());
+                Reply::Int(list.len() as i64)
+            }
+            "LRANGE" => {
+                let list = store.lists.get(&args[1]).cloned().unwrap_or_default();
+                let len = list.len() as i64;
+                let index = |s: &str| {
+                    let i: i64 = s.parse().unwrap();
+                    if i < 0 {
+                        (len + i).max(0)
+                    } else {
+                        i
+                    }
+                };
+                let (start, stop) = (index(&args[2]), index(&args[3]).min(len - 1));
+                Reply::Array(Some(
+                    (start..=stop).map(|i| bulk(&list[i as usize])).collect(),
+                ))
+            }
+            "XADD" => {
+                let fields_at = args.iter().position(|a| a == "*").unwrap() + 1;
+                let stream = store.streams.entry(args[1].clone()).or_default();
+                let id = stream.len() as u64 + 1;
+                stream.push((id, args[fields_at..].to_vec()));
+                bulk(&format!("{}-0", id))
+            }
+            "XREAD" => {
+                let count: usize = args[2].parse().unwrap();
+                let (key, after) = (&args[4], &args[5]);
+                let after: u64 = after.split('-').next().unwrap().parse().unwrap();
+                let entries: Vec<Reply> = store
+                    .streams
+                    .get(key)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(id, _)| *id > after)
+                    .take(count)
+                    .map(|(id, fields)| {
+                        Reply::Array(Some(vec![
+                            bulk(&format!("{}-0", id)),
+                            Reply::Array(Some(fields.iter().map(|f| bulk(f)).collect())),
+                        ]))
+                    })
+                    .collect();
+                if entries.is_empty() {
+                    Reply::Array(None)
+                } else {
+                    Reply::Array(Some(vec![Reply::Array(Some(vec![
+                        bulk(key),
+                        Reply::Array(Some(entries)),
+                    ]))]))
+                }
+            }
+            other => Reply::Error(format!("ERR unknown command '{}'", other)),
+        }
+    }
+
+    /// Serve the Redis commands the plugin sends from an in-memory store
+    async fn mock_redis(store: Arc<Mutex<MockStore>>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let store = Arc::clone(&store);
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    loop {
+                        let n = match socket.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        buf.extend_from_slice(&chunk[..n]);
+
+                        let mut out = Vec::new();
+                        while let Some((args, used)) = parse_command(&buf) {
+                            handle(&mut store.lock().unwrap(), &args).encode(&mut out);
+                            buf.drain(..used);
+                        }
+                        if socket.write_all(&out).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        format!("redis://{}", addr)
+    }
+
+    fn config(url: &str, pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        let mut config = HashMap::from([("url".to_string(), url.to_string())]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.to_string());
+        }
+        config
+    }
+
+    fn record(fields: &[(&str, Value)]) -> Record {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn users() -> Vec<Record> {
+        vec![
+            record(&[
+                ("id", json!(1)),
+                ("name", json!("Ann")),
+                ("vip", json!(true)),
+            ]),
+            record(&[
+                ("id", json!(2)),
+                ("name", json!("Bob")),
+                ("note", Value::Null),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn test_plugin_declaration() {
+        assert_eq!(_plugin_declaration.name, "redis");
+        assert!(_plugin_declaration.is_compatible());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let caps = get_capabilities();
+        assert_eq!(caps.len(), 2);
+
+        assert_eq!(caps[0].name.as_str(), "redis.read");
+        assert_eq!(caps[0].stage_type, StageType::Source);
+        assert_eq!(caps[1].name.as_str(), "redis.write");
+        assert_eq!(caps[1].stage_type, StageType::Sink);
+    }
+
+    #[test]
+    fn test_validation() {
+        let stage = RedisStage::new(
+            "redis.read".to_string(),
+            RedisOperation::Read,
+            StageType::Source,
+        );
+        let mut config = RHashMap::new();
+
+        // Missing url, mode and key should fail
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(
+            RString::from("url"),
+            RString::from("redis://localhost:6379"),
+        );
+        config.insert(RString::from("mode"), RString::from("list"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("key"), RString::from("queue"));
+        assert!(stage.validate_config(config.clone()).is_ok());
+
+        // 'key' and 'key_field' are mutually exclusive
+        config.insert(RString::from("key_field"), RString::from("id"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("url"), RString::from("localhost:6379"));
+        config.remove(&RString::from("key_field"));
+        assert!(stage.validate_config(config).is_err());
+    }
+
+    #[test]
+    fn test_operation_specific_validation() {
+        let url = "redis://localhost:6379";
+
+        // Lists and streams read one key; lookups may use a key per record
+        let by_field = [("key_field", "id")];
+        assert!(validate(
+            RedisOperation::Read,
+            &config(url, &[("mode", "list"), by_field[0]])
+        )
+        .is_err());
+        assert!(validate(
+            RedisOperation::Read,
+            &config(url, &[("mode", "stream"), by_field[0]])
+        )
+        .is_err());
+        assert!(validate(
+            RedisOperation::Read,
+            &config(url, &[("mode", "get"), by_field[0]])
+        )
+        .is_ok());
+        assert!(validate(
+            RedisOperation::Read,
+            &config(
+                url,
+                &[("mode", "stream"), ("key", "events"), ("count", "0")]
+            )
+        )
+        .is_err());
+
+        // Read modes are not write modes
+        assert!(validate(
+            RedisOperation::Write,
+            &config(url, &[("mode", "get"), ("key", "k")])
+        )
+        .is_err());
+        assert!(validate(
+            RedisOperation::Write,
+            &config(
+                url,
+                &[("mode", "set"), ("key_field", "id"), ("ttl_seconds", "0")]
+            )
+        )
+        .is_err());
+        assert!(validate(
+            RedisOperation::Write,
+            &config(url, &[("mode", "set"), ("key", "k"), ("max_len", "100")])
+        )
+        .is_err());
+        assert!(validate(
+            RedisOperation::Write,
+            &config(
+                url,
+                &[("mode", "xadd"), ("key", "events"), ("max_len", "100")]
+            )
+        )
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_write_and_read_back() {
+        let store = Arc::new(Mutex::new(MockStore::default()));
+        let url = mock_redis(Arc::clone(&store)).await;
+        let mut conn = connect(&config(&url, &[])).await.unwrap();
+
+        let cache = config(
+            &url,
+            &[
+                ("mode", "set"),
+                ("key_field", "id"),
+                ("key_prefix", "user:"),
+                ("ttl_seconds", "60"),
+                ("batch_size", "1"),
+            ],
+        );
+        write(&mut conn, &cache, &users()).await.unwrap();
+        let hashes = config(
+            &url,
+            &[
+                ("mode", "hset"),
+                ("key_field", "name"),
+                ("ttl_seconds", "30"),
+            ],
+        );
+        write(&mut conn, &hashes, &users()).await.unwrap();
+        let queue = config(&url, &[("mode", "rpush"), ("key", "queue")]);
+        write(&mut conn, &queue, &users()).await.unwrap();
+
+        {
+            let store = store.lock().unwrap();
+            assert_eq!(store.ttls["user:1"], 60);
+            assert_eq!(store.ttls["Ann"], 30);
+            assert_eq!(
+                store.hashes["Ann"],
+                BTreeMap::from([
+                    ("id".to_string(), "1".to_string()),
+                    ("name".to_string(), "Ann".to_string()),
+                    ("vip".to_string(), "true".to_string()),
+                ])
+            );
+            // Null fields are not written
+            assert!(!store.hashes["Bob"].contains_key("note"));
+        }
+
+        // Lookups add the cached record, or null for unset and missing keys
+        let lookup = config(
+            &url,
+            &[
+                ("mode", "get"),
+                ("key_field", "user_id"),
+                ("key_prefix", "user:"),
+                ("value_field", "user"),
+            ],
+        );
+        let input = vec![
+            record(&[("user_id", json!(2))]),
+            record(&[("user_id", json!(9))]),
+            record(&[("other", json!(1))]),
+        ];
+        let rows = read(&mut conn, &lookup, input).await.unwrap();
+        assert_eq!(
+            rows[0]["user"],
+            json!({"id": 2, "name": "Bob", "note": null})
+        );
+        assert_eq!(rows[1]["user"], Value::Null);
+        assert_eq!(rows[2]["user"], Value::Null);
+
+        let list = config(&url, &[("mode", "list"), ("key", "queue"), ("start", "1")]);
+        assert_eq!(
+            read(&mut conn, &list, Vec::new()).await.unwrap(),
+            vec![users()[1].clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_round_trip() {
+        let store = Arc::new(Mutex::new(MockStore::default()));
+        let url = mock_redis(Arc::clone(&store)).await;
+        let mut conn = connect(&config(&url, &[])).await.unwrap();
+
+        let events = vec![
+            record(&[("type", json!("click")), ("x", json!(10))]),
+            record(&[("type", json!("view"))]),
+            record(&[("type", json!("click")), ("x", json!(7))]),
+        ];
+        let append = config(
+            &url,
+            &[("mode", "xadd"), ("key", "events"), ("max_len", "1000")],
+        );
+        write(&mut conn, &append, &events).await.unwrap();
+
+        // Pages of two entries, resuming after the last ID read
+        let tail = config(
+            &url,
+            &[("mode", "stream"), ("key", "events"), ("count", "2")],
+        );
+        let rows = read(&mut conn, &tail, Vec::new()).await.unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["_id"], json!("1-0"));
+        assert_eq!(rows[0]["x"], json!(10));
+        assert_eq!(rows[2]["type"], json!("click"));
+
+        let after = config(
+            &url,
+            &[
+                ("mode", "stream"),
+                ("key", "events"),
+                ("start_id", "1-0"),
+                ("max_entries", "1"),
+            ],
+        );
+        let rows = read(&mut conn, &after, Vec::new()).await.unwrap();
+        assert_eq!(
+            rows,
+            vec![record(&[("_id", json!("2-0")), ("type", json!("view"))])]
+        );
+    }
+}
@@ -0,0 +1,1113 @@
+//! S3 Plugin for Conveyor - Operation-based API
+//!
+//! Provides object storage operations: read (one key, or every key under a
+//! prefix) and write (one key from the input data). Works with AWS S3 and
+//! S3-compatible stores such as MinIO through a custom `endpoint`.
+
+use conveyor_plugin_api::sabi_trait::prelude::*;
+use conveyor_plugin_api::traits::{FfiBatchSink_TO, FfiExecutionContext, FfiStage, FfiStage_TO};
+use conveyor_plugin_api::{
+    rstr, FfiConfigParameter, FfiDataFormat, FfiParameterType, FfiStageMetadata, PluginCapability,
+    PluginDeclaration, RBox, RBoxError, RErr, RHashMap, ROk, RResult, RString, RVec, StageType,
+    PLUGIN_API_VERSION,
+};
+use futures::TryStreamExt;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::buffered::BufWriter;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// S3 operation types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum S3Operation {
+    Read,
+    Write,
+}
+
+/// S3 Stage - operation-based
+pub struct S3Stage {
+    name: String,
+    operation: S3Operation,
+    stage_type: StageType,
+}
+
+impl S3Stage {
+    fn new(name: String, operation: S3Operation, stage_type: StageType) -> Self {
+        Self {
+            name,
+            operation,
+            stage_type,
+        }
+    }
+
+    /// Execute read operation - read all matching objects into one batch
+    async fn execute_read_async(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let mut raw = Vec::new();
+        let mut records = Vec::new();
+
+        let result = async {
+            let format = Format::from_config(config)?;
+            let store = build_store(config)?;
+            read(&store, config, |chunk| {
+                match chunk {
+                    Chunk::Raw(bytes) => raw.extend(bytes),
+                    Chunk::Records(batch) => records.extend(batch),
+                }
+                true
+            })
+            .await?;
+            Ok::<_, String>(format)
+        }
+        .await;
+
+        match result {
+            Ok(Format::Raw) => ROk(FfiDataFormat::from_raw(raw)),
+            Ok(_) => FfiDataFormat::from_json_records(&records),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+
+    /// Execute read operation, emitting each object or record batch as soon
+    /// as it has been read
+    async fn execute_read_stream_async(
+        &self,
+        config: &HashMap<String, String>,
+        sink: &mut FfiBatchSink_TO<'static, RBox<()>>,
+    ) -> RResult<(), RBoxError> {
+        let result = async {
+            let store = build_store(config)?;
+            read(&store, config, |chunk| {
+                let batch = match chunk {
+                    Chunk::Raw(bytes) => FfiDataFormat::from_raw(bytes),
+                    Chunk::Records(batch) => match FfiDataFormat::from_json_records(&batch) {
+                        ROk(batch) => batch,
+                        RErr(_) => return false,
+                    },
+                };
+                // emit blocks while downstream is busy and returns false once it is gone
+                sink.emit(batch)
+            })
+            .await
+        }
+        .await;
+
+        match result {
+            Ok(()) => ROk(()),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+
+    /// Execute write operation - write the input data to one object
+    async fn execute_write_async(
+        &self,
+        input_data: &FfiDataFormat,
+        config: &HashMap<String, String>,
+    ) -> RResult<FfiDataFormat, RBoxError> {
+        let result = async {
+            let store = build_store(config)?;
+            write(Arc::new(store), config, input_data).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => ROk(input_data.clone()),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+}
+
+impl FfiStage for S3Stage {
+    fn name(&self) -> conveyor_plugin_api::RStr<'_> {
+        self.name.as_str().into()
+    }
+
+    fn stage_type(&self) -> StageType {
+        self.stage_type
+    }
+
+    fn execute(&self, context: FfiExecutionContext) -> RResult<FfiDataFormat, RBoxError> {
+        // Convert config to HashMap
+        let config: HashMap<String, String> = context
+            .config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        // Use tokio runtime to execute async code
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Failed to create runtime: {}",
+                    e
+                )))
+            }
+        };
+
+        runtime.block_on(async {
+            match self.operation {
+                S3Operation::Read => self.execute_read_async(&config).await,
+                S3Operation::Write => {
+                    let input_data = match context.inputs.into_iter().next() {
+                        Some(tuple) => tuple.1,
+                        None => {
+                            return RErr(RBoxError::from_fmt(&format_args!(
+                                "write requires input data"
+                            )))
+                        }
+                    };
+                    self.execute_write_async(&input_data, &config).await
+                }
+            }
+        })
+    }
+
+    fn execute_stream(
+        &self,
+        context: FfiExecutionContext,
+        mut sink: FfiBatchSink_TO<'static, RBox<()>>,
+    ) -> RResult<(), RBoxError> {
+        if self.operation != S3Operation::Read {
+            return match self.execute(context) {
+                ROk(data) => {
+                    sink.emit(data);
+                    ROk(())
+                }
+                RErr(e) => RErr(e),
+            };
+        }
+
+        let config: HashMap<String, String> = context
+            .config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                return RErr(RBoxError::from_fmt(&format_args!(
+                    "Failed to create runtime: {}",
+                    e
+                )))
+            }
+        };
+
+        runtime.block_on(self.execute_read_stream_async(&config, &mut sink))
+    }
+
+    fn validate_config(&self, config: RHashMap<RString, RString>) -> RResult<(), RBoxError> {
+        let config: HashMap<String, String> = config
+            .into_iter()
+            .map(|tuple| (tuple.0.to_string(), tuple.1.to_string()))
+            .collect();
+
+        match validate(self.operation, &config) {
+            Ok(()) => ROk(()),
+            Err(e) => RErr(RBoxError::from_fmt(&format_args!("{}", e))),
+        }
+    }
+}
+
+// ============================================================================
+// Config Helpers
+// ============================================================================
+
+/// How object contents map to data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// The object bytes as they are
+    Raw,
+    /// A JSON array of records, or a single record
+    Json,
+    /// One JSON record per line
+    Jsonl,
+}
+
+impl Format {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        match config.get("format").map(|s| s.as_str()) {
+            None | Some("raw") => Ok(Format::Raw),
+            Some("json") => Ok(Format::Json),
+            Some("jsonl") => Ok(Format::Jsonl),
+            Some(other) => Err(format!(
+                "Invalid 'format': '{}'. Must be one of: raw, json, jsonl",
+                other
+            )),
+        }
+    }
+}
+
+/// Which objects a read stage fetches
+#[derive(Debug)]
+enum ObjectSource {
+    Key(Path),
+    /// Every object under `prefix` whose path relative to it matches `pattern`
+    Prefix {
+        prefix: Path,
+        pattern: Option<glob::Pattern>,
+    },
+}
+
+impl ObjectSource {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        let pattern = match config.get("glob") {
+            None => None,
+            Some(glob) => Some(
+                glob::Pattern::new(glob).map_err(|e| format!("Invalid 'glob' pattern: {}", e))?,
+            ),
+        };
+
+        match (config.get("key"), config.get("prefix")) {
+            (Some(_), Some(_)) => Err("Set either 'key' or 'prefix', not both".to_string()),
+            (None, None) => Err("Missing required 'key' or 'prefix' configuration".to_string()),
+            (Some(key), None) => {
+                if pattern.is_some() {
+                    return Err("'glob' filters a 'prefix' listing; use it with 'prefix'".into());
+                }
+                Ok(ObjectSource::Key(object_path(key)?))
+            }
+            (None, Some(prefix)) => Ok(ObjectSource::Prefix {
+                prefix: Path::from(prefix.as_str()),
+                pattern,
+            }),
+        }
+    }
+}
+
+fn object_path(key: &str) -> Result<Path, String> {
+    let path = Path::from(key);
+    if path.as_ref().is_empty() {
+        return Err("'key' must name an object".to_string());
+    }
+    Ok(path)
+}
+
+fn parse_positive(
+    config: &HashMap<String, String>,
+    key: &str,
+    default: usize,
+) -> Result<usize, String> {
+    match config.get(key) {
+        None => Ok(default),
+        Some(value) => match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("'{}' must be a positive integer", key)),
+        },
+    }
+}
+
+/// Build the S3 client from the stage config
+///
+/// Starts from the standard `AWS_*` environment variables, so credentials and
+/// the region may be left out of the pipeline file. An `http://` endpoint
+/// allows unencrypted connections, as local MinIO instances need.
+fn store_builder(config: &HashMap<String, String>) -> Result<AmazonS3Builder, String> {
+    let bucket = config
+        .get("bucket")
+        .filter(|b| !b.is_empty())
+        .ok_or("Missing required 'bucket' configuration")?;
+    let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+
+    if let Some(region) = config.get("region") {
+        builder = builder.with_region(region);
+    }
+    if let Some(endpoint) = config.get("endpoint") {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(format!(
+                "'endpoint' must be an http:// or https:// URL, got '{}'",
+                endpoint
+            ));
+        }
+        builder = builder
+            .with_endpoint(endpoint)
+            .with_allow_http(endpoint.starts_with("http://"));
+    }
+
+    match (config.get("access_key_id"), config.get("secret_access_key")) {
+        (Some(id), Some(secret)) => {
+            builder = builder
+                .with_access_key_id(id)
+                .with_secret_access_key(secret);
+        }
+        (None, None) => {}
+        _ => {
+            return Err("'access_key_id' and 'secret_access_key' must be set together".to_string())
+        }
+    }
+    if let Some(token) = config.get("session_token") {
+        builder = builder.with_token(token);
+    }
+
+    Ok(builder)
+}
+
+fn build_store(config: &HashMap<String, String>) -> Result<AmazonS3, String> {
+    store_builder(config)?
+        .build()
+        .map_err(|e| format!("Failed to configure S3 client: {}", e))
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// A piece of read output: a whole raw object, or a batch of records
+#[derive(Debug)]
+enum Chunk {
+    Raw(Vec<u8>),
+    Records(Vec<Value>),
+}
+
+/// Paths of the objects to read, in key order
+async fn list_objects(store: &dyn ObjectStore, source: &ObjectSource) -> Result<Vec<Path>, String> {
+    let (prefix, pattern) = match source {
+        ObjectSource::Key(path) => return Ok(vec![path.clone()]),
+        ObjectSource::Prefix { prefix, pattern } => (prefix, pattern),
+    };
+
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    let objects: Vec<_> = store
+        .list(Some(prefix))
+        .try_collect()
+        .await
+        .map_err(|e| format!("Failed to list objects under '{}': {}", prefix, e))?;
+
+    let mut paths: Vec<Path> = objects
+        .into_iter()
+        .map(|meta| meta.location)
+        .filter(|location| match pattern {
+            None => true,
+            Some(pattern) => {
+                let relative = location
+                    .as_ref()
+                    .strip_prefix(prefix.as_ref())
+                    .unwrap_or(location.as_ref())
+                    .trim_start_matches('/');
+                pattern.matches_with(relative, options)
+            }
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Parse one line of a JSON Lines object; blank lines yield nothing
+fn parse_line(line: &[u8], path: &Path, number: usize) -> Result<Option<Value>, String> {
+    if line.iter().all(|b| b.is_ascii_whitespace()) {
+        return Ok(None);
+    }
+    match serde_json::from_slice::<Value>(line) {
+        Ok(value @ Value::Object(_)) => Ok(Some(value)),
+        Ok(_) => Err(format!(
+            "Line {} of '{}' is not a JSON object",
+            number, path
+        )),
+        Err(e) => Err(format!(
+            "Line {} of '{}' is not valid JSON: {}",
+            number, path, e
+        )),
+    }
+}
+
+/// Stream a JSON Lines object, handing over `batch_size` records at a time
+///
+/// Returns `false` once `on_chunk` does.
+async fn read_lines<F>(
+    store: &dyn ObjectStore,
+    path: &Path,
+    batch_size: usize,
+    on_chunk: &mut F,
+) -> Result<bool, String>
+where
+    F: FnMut(Chunk) -> bool,
+{
+    let mut body = store
+        .get(path)
+        .await
+        .map_err(|e| format!("Failed to get '{}': {}", path, e))?
+        .into_stream();
+
+    let mut pending: Vec<u8> = Vec::new();
+    let mut batch = Vec::new();
+    let mut number = 0;
+
+    while let Some(bytes) = body
+        .try_next()
+        .await
+        .map_err(|e| format!("Failed to read '{}': {}", path, e))?
+    {
+        pending.extend_from_slice(&bytes);
+
+        let mut start = 0;
+        while let Some(end) = pending[start..].iter().position(|b| *b == b'\n') {
+            number += 1;
+            if let Some(record) = parse_line(&pending[start..start + end], path, number)? {
+                batch.push(record);
+            }
+            start += end + 1;
+
+            if batch.len() >= batch_size && !on_chunk(Chunk::Records(std::mem::take(&mut batch))) {
+                return Ok(false);
+            }
+        }
+        pending.drain(..start);
+    }
+
+    // The last line need not end with a newline
+    if let Some(record) = parse_line(&pending, path, number + 1)? {
+        batch.push(record);
+    }
+    if !batch.is_empty() {
+        return Ok(on_chunk(Chunk::Records(batch)));
+    }
+    Ok(true)
+}
+
+/// Read the objects selected by the config, handing each piece to `on_chunk`
+///
+/// Raw and JSON objects are read whole and handed over one at a time; JSON
+/// Lines objects are streamed and handed over in batches of `batch_size`
+/// records. Stops early when `on_chunk` returns `false`.
+async fn read<F>(
+    store: &dyn ObjectStore,
+    config: &HashMap<String, String>,
+    mut on_chunk: F,
+) -> Result<(), String>
+where
+    F: FnMut(Chunk) -> bool,
+{
+    let source = ObjectSource::from_config(config)?;
+    let format = Format::from_config(config)?;
+    let batch_size = parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE)?;
+
+    for path in list_objects(store, &source).await? {
+        let more = match format {
+            Format::Jsonl => read_lines(store, &path, batch_size, &mut on_chunk).await?,
+            Format::Raw | Format::Json => {
+                let bytes = store
+                    .get(&path)
+                    .await
+                    .map_err(|e| format!("Failed to get '{}': {}", path, e))?
+                    .bytes()
+                    .await
+                    .map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+
+                if format == Format::Raw {
+                    on_chunk(Chunk::Raw(bytes.to_vec()))
+                } else {
+                    let records = match serde_json::from_slice::<Value>(&bytes) {
+                        Ok(Value::Array(records)) => records,
+                        Ok(record @ Value::Object(_)) => vec![record],
+                        Ok(_) => {
+                            return Err(format!(
+                                "'{}' holds neither a JSON array nor an object",
+                                path
+                            ))
+                        }
+                        Err(e) => return Err(format!("'{}' is not valid JSON: {}", path, e)),
+                    };
+                    on_chunk(Chunk::Records(records))
+                }
+            }
+        };
+        if !more {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+/// Write the input data to the configured key
+///
+/// The object is uploaded through a buffered writer, which switches to a
+/// multipart upload once the data outgrows a single request. A failed write
+/// aborts the upload, so no partial object is left behind.
+async fn write(
+    store: Arc<dyn ObjectStore>,
+    config: &HashMap<String, String>,
+    input: &FfiDataFormat,
+) -> Result<(), String> {
+    if config.contains_key("prefix") {
+        return Err("write targets a single object: set 'key' instead of 'prefix'".to_string());
+    }
+    let key = config
+        .get("key")
+        .ok_or("Missing required 'key' configuration")?;
+    let path = object_path(key)?;
+    let format = Format::from_config(config)?;
+
+    let records = match format {
+        Format::Raw => Vec::new(),
+        Format::Json | Format::Jsonl => match input.to_json_records() {
+            ROk(records) => records,
+            RErr(e) => return Err(format!("Failed to read input records: {}", e)),
+        },
+    };
+
+    let mut writer = BufWriter::new(store, path.clone());
+    let result = async {
+        match format {
+            Format::Raw => writer.write_all(input.as_bytes()).await?,
+            Format::Json => writer.write_all(&serde_json::to_vec(&records)?).await?,
+            Format::Jsonl => {
+                for record in &records {
+                    let mut line = serde_json::to_vec(record)?;
+                    line.push(b'\n');
+                    writer.write_all(&line).await?;
+                }
+            }
+        }
+        writer.shutdown().await
+    }
+    .await;
+
+    if let Err(e) = result {
+        // Best effort: the original error matters more than a failed abort
+        let _ = writer.abort().await;
+        return Err(format!("Failed to write '{}': {}", path, e));
+    }
+    Ok(())
+}
+
+/// Validate the configuration of an operation
+fn validate(operation: S3Operation, config: &HashMap<String, String>) -> Result<(), String> {
+    store_builder(config)?;
+    Format::from_config(config)?;
+
+    match operation {
+        S3Operation::Read => {
+            ObjectSource::from_config(config)?;
+            parse_positive(config, "batch_size", DEFAULT_BATCH_SIZE)?;
+        }
+        S3Operation::Write => {
+            if config.contains_key("prefix") || config.contains_key("glob") {
+                return Err("write targets a single object: set 'key' only".to_string());
+            }
+            let key = config
+                .get("key")
+                .ok_or("Missing required 'key' configuration")?;
+            object_path(key)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Factory functions for each operation
+#[no_mangle]
+pub extern "C" fn create_s3_read() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        S3Stage::new("s3-read".to_string(), S3Operation::Read, StageType::Source),
+        TD_Opaque,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn create_s3_write() -> FfiStage_TO<'static, RBox<()>> {
+    FfiStage_TO::from_value(
+        S3Stage::new("s3-write".to_string(), S3Operation::Write, StageType::Sink),
+        TD_Opaque,
+    )
+}
+
+// ============================================================================
+// Metadata Helper Functions
+// ============================================================================
+
+/// Create common S3 parameters (bucket, connection, credentials, format)
+fn common_s3_parameters() -> Vec<FfiConfigParameter> {
+    vec![
+        FfiConfigParameter::required("bucket", FfiParameterType::String, "Bucket name"),
+        FfiConfigParameter::optional(
+            "region",
+            FfiParameterType::String,
+            "",
+            "Bucket region (defaults to AWS_REGION, then us-east-1)",
+        ),
+        FfiConfigParameter::optional(
+            "endpoint",
+            FfiParameterType::String,
+            "",
+            "Endpoint URL of an S3-compatible store (e.g., http://localhost:9000 for MinIO)",
+        ),
+        FfiConfigParameter::optional(
+            "access_key_id",
+            FfiParameterType::String,
+            "",
+            "Access key ID (defaults to AWS_ACCESS_KEY_ID)",
+        ),
+        FfiConfigParameter::optional(
+            "secret_access_key",
+            FfiParameterType::String,
+            "",
+            "Secret access key (defaults to AWS_SECRET_ACCESS_KEY)",
+        ),
+        FfiConfigParameter::optional(
+            "session_token",
+            FfiParameterType::String,
+            "",
+            "Session token for temporary credentials",
+        ),
+        FfiConfigParameter::optional(
+            "format",
+            FfiParameterType::String,
+            "raw",
+            "Object contents: raw bytes, a JSON array, or JSON Lines",
+        )
+        .with_allowed_values(["raw", "json", "jsonl"]),
+    ]
+}
+
+/// Create metadata for read operation
+fn create_read_metadata() -> FfiStageMetadata {
+    let mut params = common_s3_parameters();
+    params.extend(vec![
+        FfiConfigParameter::optional(
+            "key",
+            FfiParameterType::String,
+            "",
+            "Key of the object to read",
+        ),
+        FfiConfigParameter::optional(
+            "prefix",
+            FfiParameterType::String,
+            "",
+            "Read every object under this prefix instead of one 'key'",
+        ),
+        FfiConfigParameter::optional(
+            "glob",
+            FfiParameterType::String,
+            "",
+            "Only read objects whose key below 'prefix' matches (e.g., '*.jsonl')",
+        ),
+        FfiConfigParameter::optional(
+            "batch_size",
+            FfiParameterType::Integer,
+            "1000",
+            "Records per emitted batch when streaming JSON Lines",
+        ),
+    ]);
+
+    FfiStageMetadata::new(
+        "s3.read",
+        "Read objects from an S3 bucket",
+        "Reads the object at 'key', or every object under 'prefix' in key order, \
+         optionally filtered by 'glob'. With format 'raw' the bytes are returned as-is, \
+         concatenated when several objects match; 'json' objects hold an array of records \
+         or a single record, and 'jsonl' objects one record per line. JSON Lines objects \
+         are streamed and, when the pipeline streams, emitted 'batch_size' records at a \
+         time; raw and JSON objects are read whole.",
+        params,
+        vec!["s3", "object-store", "minio", "files", "source"],
+    )
+}
+
+/// Create metadata for write operation
+fn create_write_metadata() -> FfiStageMetadata {
+    let mut params = common_s3_parameters();
+    params.push(FfiConfigParameter::required(
+        "key",
+        FfiParameterType::String,
+        "Key of the object to write",
+    ));
+
+    FfiStageMetadata::new(
+        "s3.write",
+        "Write input data to an S3 object",
+        "Writes the input to the object at 'key', replacing it if it exists. \
+         With format 'raw' the input bytes are written as-is; 'json' writes the records \
+         as a JSON array and 'jsonl' one record per line. Large objects are uploaded \
+         in parts, and a failed write leaves no object behind. Input data is passed \
+         through unchanged.",
+        params,
+        vec!["s3", "object-store", "minio", "files", "sink"],
+    )
+}
+
+// ============================================================================
+// Plugin Capabilities
+// ============================================================================
+
+// Plugin capabilities
+extern "C" fn get_capabilities() -> RVec<PluginCapability> {
+    vec![
+        PluginCapability::new(
+            "s3.read",
+            StageType::Source,
+            "S3 read - one object or every object under a prefix",
+            "create_s3_read",
+            create_read_metadata(),
+        ),
+        PluginCapability::new(
+            "s3.write",
+            StageType::Sink,
+            "S3 write - upload input data as an object",
+            "create_s3_write",
+            create_write_metadata(),
+        ),
+    ]
+    .into()
+}
+
+// Plugin declaration
+#[no_mangle]
+pub static _plugin_declaration: PluginDeclaration = PluginDeclaration {
+    api_version: PLUGIN_API_VERSION,
+    name: rstr!("s3"),
+    version: rstr!("0.1.0"),
+    description: rstr!("S3 plugin with operation-based API (read, write)"),
+    get_capabilities,
+};
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::aws::AmazonS3ConfigKey;
+    use object_store::memory::InMemory;
+    use object_store::PutPayload;
+    use serde_json::json;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    async fn store_with(objects: &[(&str, &str)]) -> Arc<dyn ObjectStore> {
+        let store = InMemory::new();
+        for (key, body) in objects {
+            store
+                .put(&Path::from(*key), PutPayload::from(body.to_string()))
+                .await
+                .unwrap();
+        }
+        Arc::new(store)
+    }
+
+    async fn read_all(
+        store: &dyn ObjectStore,
+        config: &HashMap<String, String>,
+    ) -> Result<Vec<Chunk>, String> {
+        let mut chunks = Vec::new();
+        read(store, config, |chunk| {
+            chunks.push(chunk);
+            true
+        })
+        .await?;
+        Ok(chunks)
+    }
+
+    fn ids(chunk: &Chunk) -> Vec<i64> {
+        match chunk {
+            Chunk::Records(records) => records.iter().map(|r| r["id"].as_i64().unwrap()).collect(),
+            Chunk::Raw(_) => panic!("expected records, got raw bytes"),
+        }
+    }
+
+    #[test]
+    fn test_plugin_declaration() {
+        assert_eq!(_plugin_declaration.name, "s3");
+        assert_eq!(_plugin_declaration.version, "0.1.0");
+        assert!(_plugin_declaration.is_compatible());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let caps = get_capabilities();
+        assert_eq!(caps.len(), 2);
+
+        assert_eq!(caps[0].name.as_str(), "s3.read");
+        assert_eq!(caps[0].stage_type, StageType::Source);
+        assert_eq!(caps[0].factory_symbol.as_str(), "create_s3_read");
+
+        assert_eq!(caps[1].name.as_str(), "s3.write");
+        assert_eq!(caps[1].stage_type, StageType::Sink);
+        assert_eq!(caps[1].factory_symbol.as_str(), "create_s3_write");
+    }
+
+    #[test]
+    fn test_validation() {
+        let stage = S3Stage::new("s3-read".to_string(), S3Operation::Read, StageType::Source);
+        let mut config = RHashMap::new();
+
+        // Missing bucket and key should fail
+        assert!(stage.validate_config(config.clone()).is_err());
+        config.insert(RString::from("bucket"), RString::from("data"));
+        assert!(stage.validate_config(config.clone()).is_err());
+
+        config.insert(RString::from("key"), RString::from("orders.jsonl"));
+        assert!(stage.validate_config(config.clone()).is_ok());
+
+        config.insert(RString::from("format"), RString::from("parquet"));
+        assert!(stage.validate_config(config.clone()).is_err());
+        config.insert(RString::from("format"), RString::from("jsonl"));
+
+        // Credentials come in pairs
+        config.insert(RString::from("access_key_id"), RString::from("AKIA"));
+        assert!(stage.validate_config(config.clone()).is_err());
+        config.insert(RString::from("secret_access_key"), RString::from("s3cret"));
+        assert!(stage.validate_config(config).is_ok());
+    }
+
+    #[test]
+    fn test_operation_specific_validation() {
+        let read = S3Stage::new("s3-read".to_string(), S3Operation::Read, StageType::Source);
+        let write = S3Stage::new("s3-write".to_string(), S3Operation::Write, StageType::Sink);
+        let config = |pairs: &[(&str, &str)]| {
+            let mut config = RHashMap::new();
+            config.insert(RString::from("bucket"), RString::from("data"));
+            for (k, v) in pairs {
+                config.insert(RString::from(*k), RString::from(*v));
+            }
+            config
+        };
+
+        assert!(read
+            .validate_config(config(&[("prefix", "logs/"), ("glob", "*.jsonl")]))
+            .is_ok());
+        assert!(read
+            .validate_config(config(&[("key", "a.json"), ("prefix", "logs/")]))
+            .is_err());
+        assert!(read
+            .validate_config(config(&[("key", "a.json"), ("glob", "*.json")]))
+            .is_err());
+        assert!(read
+            .validate_config(config(&[("prefix", "logs/"), ("glob", "[")]))
+            .is_err());
+        assert!(read
+            .validate_config(config(&[("key", "a.json"), ("batch_size", "0")]))
+            .is_err());
+
+        assert!(write
+            .validate_config(config(&[("key", "out.json")]))
+            .is_ok());
+        assert!(write.validate_config(config(&[])).is_err());
+        assert!(write
+            .validate_config(config(&[("prefix", "exports/")]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_store_config() {
+        let builder = store_builder(&settings(&[
+            ("bucket", "lake"),
+            ("region", "eu-west-1"),
+            ("endpoint", "http://localhost:9000"),
+            ("access_key_id", "minio"),
+            ("secret_access_key", "minio123"),
+        ]))
+        .unwrap();
+        let value = |key| builder.get_config_value(&key);
+        assert_eq!(value(AmazonS3ConfigKey::Bucket).as_deref(), Some("lake"));
+        assert_eq!(
+            value(AmazonS3ConfigKey::Region).as_deref(),
+            Some("eu-west-1")
+        );
+        assert_eq!(
+            value(AmazonS3ConfigKey::Endpoint).as_deref(),
+            Some("http://localhost:9000")
+        );
+        assert_eq!(
+            value(AmazonS3ConfigKey::AccessKeyId).as_deref(),
+            Some("minio")
+        );
+        assert!(builder.build().is_ok());
+
+        let err = store_builder(&settings(&[
+            ("bucket", "lake"),
+            ("endpoint", "localhost:9000"),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "'endpoint' must be an http:// or https:// URL, got 'localhost:9000'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_single_key_raw() {
+        let store = store_with(&[("exports/report.csv", "id,total\n1,9.5\n")]).await;
+        let chunks = read_all(
+            store.as_ref(),
+            &settings(&[("bucket", "b"), ("key", "exports/report.csv")]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(chunks.len(), 1);
+        assert!(matches!(&chunks[0], Chunk::Raw(bytes) if bytes == b"id,total\n1,9.5\n"));
+
+        let err = read_all(
+            store.as_ref(),
+            &settings(&[("bucket", "b"), ("key", "exports/missing.csv")]),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.starts_with("Failed to get 'exports/missing.csv'"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_prefix_jsonl_in_batches() {
+        let store = store_with(&[
+            (
+                "logs/2024-01-02.jsonl",
+                "{\"id\": 3}\n\n{\"id\": 4}\n{\"id\": 5}",
+            ),
+            ("logs/2024-01-01.jsonl", "{\"id\": 1}\n{\"id\": 2}\n"),
+            ("logs/2024-01-01.txt", "not json"),
+            ("logs/archive/2023-12-31.jsonl", "{\"id\": 0}\n"),
+        ])
+        .await;
+
+        let chunks = read_all(
+            store.as_ref(),
+            &settings(&[
+                ("bucket", "b"),
+                ("prefix", "logs"),
+                ("glob", "*.jsonl"),
+                ("format", "jsonl"),
+                ("batch_size", "2"),
+            ]),
+        )
+        .await
+        .unwrap();
+
+        // Objects in key order, batches never spanning two objects; '*' does
+        // not descend into archive/
+        let batches: Vec<Vec<i64>> = chunks.iter().map(ids).collect();
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        // Stopping early reads no further
+        let mut seen = 0;
+        read(
+            store.as_ref(),
+            &settings(&[
+                ("bucket", "b"),
+                ("prefix", "logs"),
+                ("glob", "**/*.jsonl"),
+                ("format", "jsonl"),
+                ("batch_size", "1"),
+            ]),
+            |_| {
+                seen += 1;
+                seen < 2
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(seen, 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_json_and_errors() {
+        let store = store_with(&[
+            ("a.json", r#"[{"id": 1}, {"id": 2}]"#),
+            ("b.json", r#"{"id": 3}"#),
+            ("bad.jsonl", "{\"id\": 1}\n[1, 2]\n"),
+        ])
+        .await;
+
+        let chunks = read_all(
+            store.as_ref(),
+            &settings(&[
+                ("bucket", "b"),
+                ("prefix", ""),
+                ("glob", "?.json"),
+                ("format", "json"),
+            ]),
+        )
+        .await
+        .unwrap();
+        let batches: Vec<Vec<i64>> = chunks.iter().map(ids).collect();
+        assert_eq!(batches, vec![vec![1, 2], vec![3]]);
+
+        let err = read_all(
+            store.as_ref(),
+            &settings(&[("bucket", "b"), ("key", "bad.jsonl"), ("format", "jsonl")]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err, "Line 2 of 'bad.jsonl' is not a JSON object");
+    }
+
+    #[tokio::test]
+    async fn test_write_round_trip() {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let records = vec![
+            json!({"id": 1, "name": "Alice"}),
+            json!({"id": 2, "name": "Bob"}),
+        ];
+        let input = FfiDataFormat::from_json_records(&records).unwrap();
+
+        let config = settings(&[
+            ("bucket", "b"),
+            ("key", "exports/users.jsonl"),
+            ("format", "jsonl"),
+        ]);
+        write(store.clone(), &config, &input).await.unwrap();
+
+        let body = store
+            .get(&Path::from("exports/users.jsonl"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(body.iter().filter(|b| **b == b'\n').count(), 2);
+
+        let chunks = read_all(store.as_ref(), &config).await.unwrap();
+        assert_eq!(chunks.iter().flat_map(ids).collect::<Vec<_>>(), vec![1, 2]);
+
+        // Raw input is written byte for byte
+        let raw = settings(&[("bucket", "b"), ("key", "exports/blob.bin")]);
+        write(
+            store.clone(),
+            &raw,
+            &FfiDataFormat::from_raw(vec![0, 159, 146, 150]),
+        )
+        .await
+        .unwrap();
+        let chunks = read_all(store.as_ref(), &raw).await.unwrap();
+        assert!(matches!(&chunks[0], Chunk::Raw(bytes) if bytes == &[0, 159, 146, 150]));
+
+        // Raw bytes that are not records cannot be written as JSON
+        let json = settings(&[
+            ("bucket", "b"),
+            ("key", "exports/x.json"),
+            ("format", "json"),
+        ]);
+        assert!(
+            write(store.clone(), &json, &FfiDataFormat::from_raw(vec![0, 1]))
+                .await
+                .is_err()
+        );
+        assert!(store.head(&Path::from("exports/x.json")).await.is_err());
+    }
+}
@@ -291,15 +291,11 @@ impl InteractiveBuilder {
             // Check numeric range
             if let Some(min) = validation.min {
                 match &value {
-                    toml::Value::Integer(i) => {
-                        if (*i as f64) < min {
-                            anyhow::bail!("Value must be >= {}", min);
-                        }
+                    toml::Value::Integer(i) if (*i as f64) < min => {
+                        anyhow::bail!("Value must be >= {}", min);
                     }
-                    toml::Value::Float(f) => {
-                        if *f < min {
-                            anyhow::bail!("Value must be >= {}", min);
-                        }
+                    toml::Value::Float(f) if *f < min => {
+                        anyhow::bail!("Value must be >= {}", min);
                     }
                     _ => {}
                 }
@@ -307,15 +303,11 @@ impl InteractiveBuilder {
 
             if let Some(max) = validation.max {
                 match &value {
-                    toml::Value::Integer(i) => {
-                        if (*i as f64) > max {
-                            anyhow::bail!("Value must be <= {}", max);
-                        }
+                    toml::Value::Integer(i) if (*i as f64) > max => {
+                        anyhow::bail!("Value must be <= {}", max);
                     }
-                    toml::Value::Float(f) => {
-                        if *f > max {
-                            anyhow::bail!("Value must be <= {}", max);
-                        }
+                    toml::Value::Float(f) if *f > max => {
+                        anyhow::bail!("Value must be <= {}", max);
                     }
                     _ => {}
                 }
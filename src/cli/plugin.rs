@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use std::path::{Path, PathBuf};
 
 use crate::core::plugin_manager::PluginManager;
 
@@ -40,6 +41,16 @@ pub enum PluginCommand {
 
     /// Update plugin registry cache
     Update,
+
+    /// Create a new plugin project from the plugin template
+    New {
+        /// Plugin name (e.g. "my-plugin")
+        name: String,
+
+        /// Directory to create (default: plugins/conveyor-plugin-<name>)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 impl PluginCommand {
@@ -50,6 +61,7 @@ impl PluginCommand {
             PluginCommand::Uninstall { name } => uninstall_plugin(name),
             PluginCommand::Info { name, refresh } => show_plugin_info(name, *refresh).await,
             PluginCommand::Update => update_registry().await,
+            PluginCommand::New { name, output } => new_plugin(name, output.as_deref()),
         }
     }
 }
@@ -214,3 +226,178 @@ async fn update_registry() -> Result<()> {
 
     Ok(())
 }
+
+/// Files of `examples/plugin-template`, embedded so the command works outside the repo
+const PLUGIN_TEMPLATE: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        include_str!("../../examples/plugin-template/Cargo.toml"),
+    ),
+    (
+        "README.md",
+        include_str!("../../examples/plugin-template/README.md"),
+    ),
+    (
+        "src/lib.rs",
+        include_str!("../../examples/plugin-template/src/lib.rs"),
+    ),
+];
+
+/// Spellings of a plugin name used by the template's placeholders
+struct PluginName {
+    /// `my-plugin`: package name and prose
+    kebab: String,
+    /// `my_plugin`: stage names, the plugin declaration and library file
+    snake: String,
+    /// `MyPlugin`: type names
+    pascal: String,
+}
+
+impl PluginName {
+    fn parse(name: &str) -> Result<Self> {
+        let valid = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            && name.starts_with(|c: char| c.is_ascii_alphabetic());
+        if !valid {
+            anyhow::bail!(
+                "Invalid plugin name '{}': use letters, digits, '-' or '_', starting with a letter",
+                name
+            );
+        }
+
+        // Split on separators and on lower-to-upper case changes ("MyPlugin")
+        let mut words: Vec<String> = Vec::new();
+        let mut prev_lower = false;
+        for c in name.chars() {
+            if c == '-' || c == '_' {
+                words.push(String::new());
+                prev_lower = false;
+                continue;
+            }
+            if words.is_empty() || (c.is_ascii_uppercase() && prev_lower) {
+                words.push(String::new());
+            }
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            if let Some(word) = words.last_mut() {
+                word.push(c.to_ascii_lowercase());
+            }
+        }
+        words.retain(|w| !w.is_empty());
+
+        Ok(Self {
+            kebab: words.join("-"),
+            snake: words.join("_"),
+            pascal: words
+                .iter()
+                .map(|w| {
+                    let mut chars = w.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                        .unwrap_or_default()
+                })
+                .collect(),
+        })
+    }
+
+    fn substitute(&self, template: &str) -> String {
+        let package = format!("conveyor-plugin-{}", self.kebab);
+        template
+            .replace(
+                "\n//!\n//! Replace YOURNAME with your actual plugin name throughout this file.",
+                "",
+            )
+            .replace("conveyor-plugin-YOURNAME", &package)
+            .replace("conveyor-plugin-yourname", &package)
+            .replace("YOURNAME", &self.kebab)
+            .replace("YourName", &self.pascal)
+            .replace("yourname", &self.snake)
+    }
+}
+
+/// Write a copy of the plugin template for `name` into `output`
+fn scaffold_plugin(name: &str, output: &Path) -> Result<()> {
+    let name = PluginName::parse(name)?;
+    if output.exists() {
+        anyhow::bail!("Output directory '{}' already exists", output.display());
+    }
+
+    for (relative, template) in PLUGIN_TEMPLATE {
+        let path = output.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, name.substitute(template))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Create a new plugin project
+fn new_plugin(name: &str, output: Option<&Path>) -> Result<()> {
+    let package = format!("conveyor-plugin-{}", PluginName::parse(name)?.kebab);
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| Path::new("plugins").join(&package));
+
+    scaffold_plugin(name, &output)?;
+
+    println!("✓ Created plugin '{}' in {}", package, output.display());
+    println!("\nNext steps:");
+    println!(
+        "  1. Add \"{}\" to the workspace members in the root Cargo.toml",
+        output.display()
+    );
+    println!("     (or fix the conveyor-plugin-api path in its Cargo.toml if it lives elsewhere)");
+    println!("  2. cargo build -p {}", package);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_name_variants() {
+        for input in ["csv-tools", "csv_tools", "CsvTools"] {
+            let name = PluginName::parse(input).unwrap();
+            assert_eq!(name.kebab, "csv-tools");
+            assert_eq!(name.snake, "csv_tools");
+            assert_eq!(name.pascal, "CsvTools");
+        }
+        assert!(PluginName::parse("1st plugin").is_err());
+    }
+
+    #[test]
+    fn test_scaffold_plugin_substitutes_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("conveyor-plugin-csv-tools");
+        scaffold_plugin("csv-tools", &output).unwrap();
+
+        let manifest = std::fs::read_to_string(output.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"conveyor-plugin-csv-tools\""));
+
+        let source = std::fs::read_to_string(output.join("src/lib.rs")).unwrap();
+        assert!(source.contains("struct CsvToolsSource;"));
+        assert!(source.contains("\"csv_tools_source\""));
+        assert!(source.contains("rstr!(\"csv_tools\")"));
+
+        let readme = std::fs::read_to_string(output.join("README.md")).unwrap();
+        for content in [&manifest, &source, &readme] {
+            for placeholder in ["YOURNAME", "YourName", "yourname"] {
+                assert!(
+                    !content.contains(placeholder),
+                    "{} left in output",
+                    placeholder
+                );
+            }
+        }
+
+        // An existing directory is never overwritten
+        assert!(scaffold_plugin("csv-tools", &output).is_err());
+    }
+}
@@ -43,7 +43,7 @@ description = "{}"
 
 [global]
 log_level = "info"  # Options: trace, debug, info, warn, error
-max_parallel_tasks = 4
+# max_parallel_tasks = 4  # Cap stages of one level running at once (unlimited when unset)
 timeout_seconds = 300
 # plugins = []  # Load dynamic plugins: ["http", "mongodb"]
 
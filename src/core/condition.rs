@@ -0,0 +1,196 @@
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+
+use crate::core::traits::DataFormat;
+
+/// Predicate over an upstream stage's output that gates a dependency edge
+///
+/// Written as `<operand> <operator> <literal>`, for example `row_count > 0` or
+/// `first.ready == true`. The operand is `row_count` or `first.<column>` (the
+/// column's value in the first row, null when there is no such row or
+/// column); the literal is a number, `true`/`false`, `null`, or a quoted
+/// string. Operators are `==`, `!=`, `>`, `>=`, `<` and `<=`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeCondition {
+    expression: String,
+    operand: Operand,
+    operator: Operator,
+    value: JsonValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    RowCount,
+    First(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// Operators in matching order, so `>=` is found before `>`
+const OPERATORS: [(&str, Operator); 6] = [
+    (">=", Operator::Ge),
+    ("<=", Operator::Le),
+    ("==", Operator::Eq),
+    ("!=", Operator::Ne),
+    (">", Operator::Gt),
+    ("<", Operator::Lt),
+];
+
+impl EdgeCondition {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let (position, symbol, operator) = OPERATORS
+            .iter()
+            .filter_map(|(symbol, operator)| {
+                expression
+                    .find(symbol)
+                    .map(|position| (position, *symbol, *operator))
+            })
+            .min_by_key(|(position, _, _)| *position)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid condition '{}': expected '<operand> <operator> <value>'",
+                    expression
+                )
+            })?;
+
+        let left = expression[..position].trim();
+        let right = expression[position + symbol.len()..].trim();
+
+        let operand = match left {
+            "row_count" => Operand::RowCount,
+            _ => match left.strip_prefix("first.") {
+                Some(column) if !column.is_empty() => Operand::First(column.to_string()),
+                _ => anyhow::bail!(
+                    "Invalid condition '{}': operand must be 'row_count' or 'first.<column>'",
+                    expression
+                ),
+            },
+        };
+
+        let value = parse_literal(right).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid condition '{}': '{}' is not a number, boolean, null or quoted string",
+                expression,
+                right
+            )
+        })?;
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            operand,
+            operator,
+            value,
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Whether the condition holds for `output`
+    ///
+    /// Streams cannot be inspected without consuming them, so they are an error.
+    pub fn evaluate(&self, output: &DataFormat) -> Result<bool> {
+        let actual = match (&self.operand, output) {
+            (_, DataFormat::Stream(_)) => {
+                anyhow::bail!(
+                    "Cannot evaluate condition '{}' on streaming output",
+                    self.expression
+                )
+            }
+            (_, DataFormat::Raw(_)) => {
+                anyhow::bail!(
+                    "Cannot evaluate condition '{}' on raw output",
+                    self.expression
+                )
+            }
+            (Operand::RowCount, DataFormat::DataFrame(df)) => JsonValue::from(df.height()),
+            (Operand::RowCount, DataFormat::RecordBatch(records)) => JsonValue::from(records.len()),
+            (Operand::First(column), DataFormat::DataFrame(df)) => match df.column(column) {
+                Ok(values) if df.height() > 0 => {
+                    crate::core::stage::anyvalue_to_json(&values.get(0)?)
+                }
+                _ => JsonValue::Null,
+            },
+            (Operand::First(column), DataFormat::RecordBatch(records)) => records
+                .first()
+                .and_then(|record| record.get(column).cloned())
+                .unwrap_or(JsonValue::Null),
+        };
+
+        let ordering = match (&actual, &self.value) {
+            (JsonValue::Number(a), JsonValue::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+            (JsonValue::String(a), JsonValue::String(b)) => Some(a.cmp(b)),
+            (a, b) if a == b => Some(Ordering::Equal),
+            _ => None,
+        };
+
+        Ok(match self.operator {
+            Operator::Eq => ordering == Some(Ordering::Equal),
+            Operator::Ne => ordering != Some(Ordering::Equal),
+            Operator::Gt => ordering == Some(Ordering::Greater),
+            Operator::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+            Operator::Lt => ordering == Some(Ordering::Less),
+            Operator::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        })
+    }
+}
+
+fn parse_literal(text: &str) -> Option<JsonValue> {
+    match text {
+        "true" => return Some(JsonValue::Bool(true)),
+        "false" => return Some(JsonValue::Bool(false)),
+        "null" => return Some(JsonValue::Null),
+        _ => {}
+    }
+    for quote in ['"', '\''] {
+        if text.len() >= 2 && text.starts_with(quote) && text.ends_with(quote) {
+            return Some(JsonValue::String(text[1..text.len() - 1].to_string()));
+        }
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(JsonValue::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    #[test]
+    fn test_edge_condition_row_count_and_first_row() {
+        let df = DataFormat::DataFrame(df! { "ready" => &[true, false] }.unwrap());
+        let empty = DataFormat::RecordBatch(Vec::new());
+
+        let non_empty = EdgeCondition::parse("row_count > 0").unwrap();
+        assert!(non_empty.evaluate(&df).unwrap());
+        assert!(!non_empty.evaluate(&empty).unwrap());
+
+        let flag = EdgeCondition::parse("first.ready == true").unwrap();
+        assert!(flag.evaluate(&df).unwrap());
+        // No first row compares as null
+        assert!(!flag.evaluate(&empty).unwrap());
+        assert!(EdgeCondition::parse("first.ready != null")
+            .unwrap()
+            .evaluate(&df)
+            .unwrap());
+
+        let err = EdgeCondition::parse("rows > 0").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid condition 'rows > 0': operand must be 'row_count' or 'first.<column>'"
+        );
+        assert!(EdgeCondition::parse("row_count >= many").is_err());
+    }
+}
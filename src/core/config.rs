@@ -4,29 +4,26 @@ use std::collections::HashMap;
 use std::path::Path;
 use tokio::fs;
 
-use crate::core::strategy::ErrorStrategy;
+use crate::core::notify::NotificationConfig;
+use crate::core::strategy::{EmptyOutputPolicy, ErrorStrategy};
 
 /// Pipeline execution mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ExecutionMode {
     /// Traditional batch processing
+    #[default]
     Batch,
     /// Stream processing with micro-batching
     Streaming,
 }
 
-impl Default for ExecutionMode {
-    fn default() -> Self {
-        Self::Batch
-    }
-}
-
 /// DAG executor type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ExecutorType {
     /// Default DAG executor (level-by-level, sequential)
+    #[default]
     Dag,
     /// Channel-based executor (backpressure, concurrent)
     Channel,
@@ -34,12 +31,6 @@ pub enum ExecutorType {
     Async,
 }
 
-impl Default for ExecutorType {
-    fn default() -> Self {
-        Self::Dag
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineMetadata {
     pub name: String,
@@ -56,8 +47,9 @@ pub struct GlobalConfig {
     #[serde(default = "default_log_level")]
     pub log_level: String,
 
-    #[serde(default = "default_max_parallel_tasks")]
-    pub max_parallel_tasks: usize,
+    /// Stages of one level the dag executor runs at once; unlimited unless set
+    #[serde(default)]
+    pub max_parallel_tasks: Option<usize>,
 
     #[serde(default = "default_timeout_seconds")]
     pub timeout_seconds: u64,
@@ -98,6 +90,26 @@ pub struct GlobalConfig {
     /// Concurrency level for concurrent executors
     #[serde(default = "default_concurrency")]
     pub concurrency: usize,
+
+    /// Batches an `ordered` stage may hold, in flight or finished but waiting
+    /// for an earlier batch (channel executor)
+    #[serde(default = "default_channel_buffer_size")]
+    pub reorder_buffer_size: usize,
+
+    /// Run-wide cap on the records emitted by all sources combined
+    /// (overridden by `conveyor run --max-records`)
+    #[serde(default)]
+    pub max_records: Option<usize>,
+
+    /// Only run stages tagged with one of these, plus their upstream stages
+    /// (overridden by `conveyor run --tag`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Fail validation (instead of warning) when a stage has no path from a
+    /// source or, for non-sinks, no path to a sink
+    #[serde(default)]
+    pub strict_connectivity: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -124,10 +136,6 @@ fn default_log_level() -> String {
     "info".to_string()
 }
 
-fn default_max_parallel_tasks() -> usize {
-    4
-}
-
 fn default_timeout_seconds() -> u64 {
     300
 }
@@ -152,7 +160,7 @@ impl Default for GlobalConfig {
     fn default() -> Self {
         Self {
             log_level: default_log_level(),
-            max_parallel_tasks: default_max_parallel_tasks(),
+            max_parallel_tasks: None,
             timeout_seconds: default_timeout_seconds(),
             plugins: Vec::new(),
             wasm_plugins: Vec::new(),
@@ -163,12 +171,51 @@ impl Default for GlobalConfig {
             executor: ExecutorType::default(),
             channel_buffer_size: default_channel_buffer_size(),
             concurrency: default_concurrency(),
+            reorder_buffer_size: default_channel_buffer_size(),
+            max_records: None,
+            tags: Vec::new(),
+            strict_connectivity: false,
         }
     }
 }
 
+/// Prefix of stage config keys that reference secrets
+///
+/// `secret.api_key = "API_KEY"` names the environment variable holding the
+/// secret. WASM plugin stages receive the variable's value as `api_key` when
+/// they run; the value is never stored in the config, and config dumps and
+/// host logs show [`REDACTED`] in place of the reference.
+pub const SECRET_PREFIX: &str = "secret.";
+
+/// Placeholder shown instead of secret config values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Copy of a stage config with every secret reference replaced by [`REDACTED`]
+///
+/// Handles both quoted keys (`"secret.api_key" = ...`) and TOML's dotted form,
+/// which parses as a `secret` table.
+pub fn redact_secrets(config: &HashMap<String, toml::Value>) -> HashMap<String, toml::Value> {
+    let redacted = || toml::Value::String(REDACTED.to_string());
+    config
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::Table(table) if key == "secret" => toml::Value::Table(
+                    table
+                        .keys()
+                        .map(|name| (name.clone(), redacted()))
+                        .collect(),
+                ),
+                _ if key == "secret" || key.starts_with(SECRET_PREFIX) => redacted(),
+                _ => value.clone(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
 /// Stage configuration for DAG-based pipelines
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StageConfig {
     /// Unique identifier for this stage
     pub id: String,
@@ -183,6 +230,58 @@ pub struct StageConfig {
     /// Stage-specific configuration
     #[serde(default)]
     pub config: HashMap<String, toml::Value>,
+
+    /// What to do if this stage produces zero rows
+    #[serde(default)]
+    pub empty_output: EmptyOutputPolicy,
+
+    /// Scheduling priority within an execution level; higher runs first
+    /// (dag executor only)
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Memory budget in MB; heavy transforms then collect with polars'
+    /// streaming engine and fail cleanly when their result is over it
+    /// (dag executor only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+
+    /// Run the stage once per batch of a streamed input, up to
+    /// `global.concurrency` batches at a time (channel executor only)
+    #[serde(default)]
+    pub concurrent: bool,
+
+    /// Keep the batches of a `concurrent` stage in input order, holding at
+    /// most `global.reorder_buffer_size` of them (channel executor only)
+    #[serde(default)]
+    pub ordered: bool,
+
+    /// Tags for `conveyor run --tag`, in addition to the function's own tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Conditions on input edges, keyed by input stage id (e.g. `row_count > 0`);
+    /// when one is false the stage and its dependents do not run (dag executor only)
+    #[serde(default)]
+    pub conditions: HashMap<String, String>,
+}
+
+impl std::fmt::Debug for StageConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StageConfig")
+            .field("id", &self.id)
+            .field("function", &self.function)
+            .field("inputs", &self.inputs)
+            .field("config", &redact_secrets(&self.config))
+            .field("empty_output", &self.empty_output)
+            .field("priority", &self.priority)
+            .field("max_memory_mb", &self.max_memory_mb)
+            .field("concurrent", &self.concurrent)
+            .field("ordered", &self.ordered)
+            .field("tags", &self.tags)
+            .field("conditions", &self.conditions)
+            .finish()
+    }
 }
 
 /// DAG-based pipeline configuration
@@ -198,10 +297,30 @@ pub struct DagPipelineConfig {
 
     #[serde(default)]
     pub error_handling: ErrorHandlingConfig,
+
+    /// Webhooks to call when the run finishes (`[[notifications]]`)
+    #[serde(default)]
+    pub notifications: Vec<NotificationConfig>,
 }
 
 impl DagPipelineConfig {
+    /// Load a config from a local path or a remote `http(s)://` location
+    ///
+    /// Remote content is fetched first and then goes through the same variable
+    /// resolution and interpolation as a local file.
     pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let location = path.as_ref().to_string_lossy();
+        if location.starts_with("s3://") {
+            anyhow::bail!(
+                "Cannot load pipeline config from '{}': s3:// config locations are not supported yet",
+                location
+            );
+        }
+        if is_remote_location(&location) {
+            let content = fetch_remote_config(&location).await?;
+            return Self::from_str(&content);
+        }
+
         let content = fs::read_to_string(path).await?;
         Self::from_str(&content)
     }
@@ -245,6 +364,33 @@ impl DagPipelineConfig {
                     );
                 }
             }
+
+            if stage.max_memory_mb == Some(0) {
+                anyhow::bail!("Stage '{}': max_memory_mb must be greater than 0", stage.id);
+            }
+
+            if stage.ordered && !stage.concurrent {
+                anyhow::bail!(
+                    "Stage '{}': ordered only applies to a stage with concurrent = true",
+                    stage.id
+                );
+            }
+
+            for (input_id, condition) in &stage.conditions {
+                if !stage.inputs.contains(input_id) {
+                    anyhow::bail!(
+                        "Stage '{}' has a condition on '{}', which is not one of its inputs",
+                        stage.id,
+                        input_id
+                    );
+                }
+                crate::core::condition::EdgeCondition::parse(condition)
+                    .map_err(|e| anyhow::anyhow!("Stage '{}': {}", stage.id, e))?;
+            }
+        }
+
+        for notification in &self.notifications {
+            notification.validate()?;
         }
 
         // Validate log level
@@ -352,10 +498,54 @@ impl DagPipelineConfig {
             stage.config = new_config;
         }
 
+        // Webhook URLs and headers usually come from variables, not templates
+        let mut notifications = std::mem::take(&mut self.notifications);
+        for notification in &mut notifications {
+            notification.url = self.interpolate_value(&notification.url)?;
+            for value in notification.headers.values_mut() {
+                *value = self.interpolate_value(value)?;
+            }
+        }
+        self.notifications = notifications;
+
         Ok(())
     }
 }
 
+/// Whether a config location refers to a remote resource rather than a local file
+fn is_remote_location(location: &str) -> bool {
+    ["http://", "https://"]
+        .iter()
+        .any(|scheme| location.starts_with(scheme))
+}
+
+/// Fetch a pipeline config from a remote location and check it parses as TOML
+async fn fetch_remote_config(location: &str) -> Result<String> {
+    let response = reqwest::get(location).await.map_err(|e| {
+        anyhow::anyhow!("Failed to fetch pipeline config from '{}': {}", location, e)
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!(
+            "Failed to fetch pipeline config from '{}': HTTP {}",
+            location,
+            status
+        );
+    }
+
+    let content = response.text().await?;
+    toml::from_str::<toml::Value>(&content).map_err(|e| {
+        anyhow::anyhow!(
+            "Pipeline config fetched from '{}' is not valid TOML: {}",
+            location,
+            e
+        )
+    })?;
+
+    Ok(content)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -564,4 +754,150 @@ url = "{{base_url}}"
 
         std::env::remove_var("TEST_SECRET");
     }
+
+    #[test]
+    fn test_max_parallel_tasks_defaults_to_unlimited() {
+        let toml_str = r#"
+[pipeline]
+name = "test"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "csv.read"
+        "#;
+
+        let config = DagPipelineConfig::from_str(toml_str).unwrap();
+        assert_eq!(config.global.max_parallel_tasks, None);
+
+        let capped = toml_str.replace(
+            "[[stages]]",
+            "[global]\nmax_parallel_tasks = 2\n\n[[stages]]",
+        );
+        let config = DagPipelineConfig::from_str(&capped).unwrap();
+        assert_eq!(config.global.max_parallel_tasks, Some(2));
+    }
+
+    #[test]
+    fn test_empty_output_policy_parsing() {
+        let toml_str = r#"
+[pipeline]
+name = "test"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "csv.read"
+empty_output = "error"
+
+[[stages]]
+id = "filter"
+function = "filter.apply"
+inputs = ["load"]
+        "#;
+
+        let config = DagPipelineConfig::from_str(toml_str).unwrap();
+
+        assert_eq!(config.stages[0].empty_output, EmptyOutputPolicy::Error);
+        assert_eq!(config.stages[1].empty_output, EmptyOutputPolicy::Ok);
+
+        let invalid = toml_str.replace(r#"empty_output = "error""#, r#"empty_output = "loud""#);
+        assert!(DagPipelineConfig::from_str(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_conditions_must_name_inputs() {
+        let toml_str = r#"
+[pipeline]
+name = "test"
+version = "1.0"
+
+[[stages]]
+id = "errors"
+function = "csv.read"
+
+[[stages]]
+id = "report"
+function = "json.write"
+inputs = ["errors"]
+
+[stages.conditions]
+errors = "row_count > 0"
+        "#;
+
+        let config = DagPipelineConfig::from_str(toml_str).unwrap();
+        assert_eq!(config.stages[1].conditions["errors"], "row_count > 0");
+
+        let err = DagPipelineConfig::from_str(&toml_str.replace("errors = \"row", "load = \"row"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Stage 'report' has a condition on 'load', which is not one of its inputs"
+        );
+    }
+
+    #[test]
+    fn test_max_memory_mb_parsing() {
+        let toml_str = r#"
+[pipeline]
+name = "test"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "csv.read"
+
+[[stages]]
+id = "totals"
+function = "group_by"
+inputs = ["load"]
+max_memory_mb = 512
+        "#;
+
+        let config = DagPipelineConfig::from_str(toml_str).unwrap();
+        assert_eq!(config.stages[0].max_memory_mb, None);
+        assert_eq!(config.stages[1].max_memory_mb, Some(512));
+
+        let err = DagPipelineConfig::from_str(&toml_str.replace("= 512", "= 0")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Stage 'totals': max_memory_mb must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_ordered_stage_parsing() {
+        let toml_str = r#"
+[pipeline]
+name = "test"
+version = "1.0"
+
+[global]
+executor = "channel"
+reorder_buffer_size = 16
+
+[[stages]]
+id = "load"
+function = "csv.read"
+
+[[stages]]
+id = "enrich"
+function = "http.fetch"
+inputs = ["load"]
+concurrent = true
+ordered = true
+        "#;
+
+        let config = DagPipelineConfig::from_str(toml_str).unwrap();
+        assert_eq!(config.global.reorder_buffer_size, 16);
+        assert!(!config.stages[0].concurrent && !config.stages[0].ordered);
+        assert!(config.stages[1].concurrent && config.stages[1].ordered);
+
+        let err =
+            DagPipelineConfig::from_str(&toml_str.replace("concurrent = true", "")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Stage 'enrich': ordered only applies to a stage with concurrent = true"
+        );
+    }
 }
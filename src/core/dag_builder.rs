@@ -1,12 +1,18 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::core::condition::EdgeCondition;
 use crate::core::config::{DagPipelineConfig, ExecutorType, StageConfig};
 use crate::core::dag_executor::{AsyncPipeline, ChannelDagExecutor, DagExecutor};
 use crate::core::error::ConveyorError;
+use crate::core::progress::{ProgressReporter, ProgressStage};
 use crate::core::registry::ModuleRegistry;
-use crate::core::stage::{FfiPluginStageAdapter, StageRef, WasmPluginStageAdapter};
+use crate::core::run_context::RunContext;
+use crate::core::stage::{
+    FfiPluginStageAdapter, RecordLimit, RecordLimitedStage, StageRef, WasmPluginStageAdapter,
+};
+use crate::core::strategy::EmptyOutputPolicy;
 use crate::plugin_loader::PluginLoader;
 use crate::wasm_plugin_loader::WasmPluginLoader;
 
@@ -45,7 +51,18 @@ trait ExecutorBuilder {
         stage: StageRef,
         config: HashMap<String, toml::Value>,
     ) -> Result<()>;
+    fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()>;
+    fn set_priority(&mut self, id: &str, priority: i32) -> Result<()>;
+    fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()>;
+    fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()>;
+    fn set_strict_connectivity(&mut self, strict: bool);
     fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()>;
+    fn add_conditional_dependency(
+        &mut self,
+        from_id: &str,
+        to_id: &str,
+        condition: EdgeCondition,
+    ) -> Result<()>;
     fn validate(&self) -> Result<()>;
 }
 
@@ -59,10 +76,39 @@ impl ExecutorBuilder for DagExecutor {
         self.add_stage(id, stage, config)
     }
 
+    fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()> {
+        self.set_empty_output_policy(id, policy)
+    }
+
+    fn set_priority(&mut self, id: &str, priority: i32) -> Result<()> {
+        self.set_priority(id, priority)
+    }
+
+    fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()> {
+        self.set_memory_limit(id, max_memory_mb)
+    }
+
+    fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()> {
+        self.set_concurrency(id, concurrent, ordered)
+    }
+
+    fn set_strict_connectivity(&mut self, strict: bool) {
+        self.set_strict_connectivity(strict)
+    }
+
     fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()> {
         self.add_dependency(from_id, to_id)
     }
 
+    fn add_conditional_dependency(
+        &mut self,
+        from_id: &str,
+        to_id: &str,
+        condition: EdgeCondition,
+    ) -> Result<()> {
+        self.add_conditional_dependency(from_id, to_id, condition)
+    }
+
     fn validate(&self) -> Result<()> {
         self.validate()
     }
@@ -78,10 +124,39 @@ impl ExecutorBuilder for ChannelDagExecutor {
         self.add_stage(id, stage, config)
     }
 
+    fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()> {
+        self.set_empty_output_policy(id, policy)
+    }
+
+    fn set_priority(&mut self, id: &str, priority: i32) -> Result<()> {
+        self.set_priority(id, priority)
+    }
+
+    fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()> {
+        self.set_memory_limit(id, max_memory_mb)
+    }
+
+    fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()> {
+        self.set_concurrency(id, concurrent, ordered)
+    }
+
+    fn set_strict_connectivity(&mut self, strict: bool) {
+        self.set_strict_connectivity(strict)
+    }
+
     fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()> {
         self.add_dependency(from_id, to_id)
     }
 
+    fn add_conditional_dependency(
+        &mut self,
+        _from_id: &str,
+        to_id: &str,
+        _condition: EdgeCondition,
+    ) -> Result<()> {
+        Err(unsupported_condition(to_id))
+    }
+
     fn validate(&self) -> Result<()> {
         self.validate()
     }
@@ -97,20 +172,67 @@ impl ExecutorBuilder for AsyncPipeline {
         self.add_stage(id, stage, config)
     }
 
+    fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()> {
+        self.set_empty_output_policy(id, policy)
+    }
+
+    fn set_priority(&mut self, id: &str, priority: i32) -> Result<()> {
+        self.set_priority(id, priority)
+    }
+
+    fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()> {
+        self.set_memory_limit(id, max_memory_mb)
+    }
+
+    fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()> {
+        self.set_concurrency(id, concurrent, ordered)
+    }
+
+    fn set_strict_connectivity(&mut self, strict: bool) {
+        self.set_strict_connectivity(strict)
+    }
+
     fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()> {
         self.add_dependency(from_id, to_id)
     }
 
+    fn add_conditional_dependency(
+        &mut self,
+        _from_id: &str,
+        to_id: &str,
+        _condition: EdgeCondition,
+    ) -> Result<()> {
+        Err(unsupported_condition(to_id))
+    }
+
     fn validate(&self) -> Result<()> {
         self.validate()
     }
 }
 
+/// Input conditions need the level-by-level `dag` executor, which knows an
+/// upstream stage's complete output before starting the downstream stage
+fn unsupported_condition(stage_id: &str) -> anyhow::Error {
+    ConveyorError::PipelineError(format!(
+        "Stage '{}': input conditions are only supported by the 'dag' executor",
+        stage_id
+    ))
+    .into()
+}
+
+/// Picks a replacement for a stage after it has been created from its config,
+/// or `None` to keep it; used to run a pipeline against test doubles
+pub type StageSubstitute = Arc<dyn Fn(&StageConfig, &StageRef) -> Option<StageRef> + Send + Sync>;
+
 /// Builder for constructing DAG pipelines from configuration
 pub struct DagPipelineBuilder {
     registry: Arc<ModuleRegistry>,
     plugin_loader: Option<Arc<PluginLoader>>,
     wasm_plugin_loader: Option<Arc<WasmPluginLoader>>,
+    record_limit: Option<Arc<RecordLimit>>,
+    progress: Option<Arc<ProgressReporter>>,
+    run_context: Option<Arc<RunContext>>,
+    substitute: Option<StageSubstitute>,
 }
 
 impl DagPipelineBuilder {
@@ -119,6 +241,10 @@ impl DagPipelineBuilder {
             registry,
             plugin_loader: None,
             wasm_plugin_loader: None,
+            record_limit: None,
+            progress: None,
+            run_context: None,
+            substitute: None,
         }
     }
 
@@ -134,6 +260,30 @@ impl DagPipelineBuilder {
         self
     }
 
+    /// Cap the records emitted by all source stages (stages without inputs) combined
+    pub fn with_record_limit(mut self, limit: Arc<RecordLimit>) -> Self {
+        self.record_limit = Some(limit);
+        self
+    }
+
+    /// Show a progress bar for every stage while the pipeline runs
+    pub fn with_progress(mut self, reporter: Arc<ProgressReporter>) -> Self {
+        self.progress = Some(reporter);
+        self
+    }
+
+    /// Describe the run to `run_info.read` stages
+    pub fn with_run_context(mut self, run: Arc<RunContext>) -> Self {
+        self.run_context = Some(run);
+        self
+    }
+
+    /// Replace stages chosen by `substitute` before they are added
+    pub fn with_substitute(mut self, substitute: StageSubstitute) -> Self {
+        self.substitute = Some(substitute);
+        self
+    }
+
     /// Build a DAG executor from configuration
     pub fn build(&self, config: &DagPipelineConfig) -> Result<ExecutorVariant> {
         let error_strategy = config.error_handling.strategy.clone();
@@ -142,6 +292,9 @@ impl DagPipelineBuilder {
         match executor_type {
             ExecutorType::Dag => {
                 let mut executor = DagExecutor::new(error_strategy);
+                if let Some(max) = config.global.max_parallel_tasks {
+                    executor.set_max_parallel_tasks(max);
+                }
                 self.build_stages(&mut executor, config)?;
                 Ok(ExecutorVariant::Dag(executor))
             }
@@ -150,6 +303,7 @@ impl DagPipelineBuilder {
                 let concurrency = config.global.concurrency;
                 let mut executor =
                     ChannelDagExecutor::new(error_strategy, buffer_size, concurrency);
+                executor.set_reorder_buffer_size(config.global.reorder_buffer_size);
                 self.build_stages(&mut executor, config)?;
                 Ok(ExecutorVariant::Channel(executor))
             }
@@ -168,20 +322,97 @@ impl DagPipelineBuilder {
         executor: &mut E,
         config: &DagPipelineConfig,
     ) -> Result<()> {
-        // Create stages and add to executor
+        let mut stages = Vec::with_capacity(config.stages.len());
         for stage_config in &config.stages {
-            let stage = self.create_stage(stage_config)?;
+            let mut stage = self.create_stage(stage_config)?;
+            if let Some(substitute) = &self.substitute {
+                if let Some(replacement) = substitute(stage_config, &stage) {
+                    stage = replacement;
+                }
+            }
+            stages.push((stage_config, stage));
+        }
+
+        // Keep only tagged stages and what they depend on
+        if !config.global.tags.is_empty() {
+            let tagged: Vec<&str> =
+                stages
+                    .iter()
+                    .filter(|(stage_config, stage)| {
+                        let function_tags = stage.metadata().tags;
+                        config.global.tags.iter().any(|tag| {
+                            stage_config.tags.contains(tag) || function_tags.contains(tag)
+                        })
+                    })
+                    .map(|(stage_config, _)| stage_config.id.as_str())
+                    .collect();
+            if tagged.is_empty() {
+                return Err(ConveyorError::PipelineError(format!(
+                    "No stage matches tag(s): {}",
+                    config.global.tags.join(", ")
+                ))
+                .into());
+            }
+
+            let selected = with_upstream(config, &tagged);
+            let pruned: Vec<&str> = stages
+                .iter()
+                .map(|(stage_config, _)| stage_config.id.as_str())
+                .filter(|id| !selected.contains(id))
+                .collect();
+            if !pruned.is_empty() {
+                tracing::info!(
+                    "Skipping {} stage(s) not selected by tag(s) {}: {}",
+                    pruned.len(),
+                    config.global.tags.join(", "),
+                    pruned.join(", ")
+                );
+            }
+            stages.retain(|(stage_config, _)| selected.contains(stage_config.id.as_str()));
+        }
+
+        // Add stages to executor
+        for (stage_config, mut stage) in stages.iter().cloned() {
+            if let Some(limit) = &self.record_limit {
+                if stage_config.inputs.is_empty() {
+                    stage = Arc::new(RecordLimitedStage::new(stage, Arc::clone(limit)));
+                }
+            }
+            // Wrap last so the bars count what downstream stages actually receive
+            if let Some(reporter) = &self.progress {
+                stage = Arc::new(ProgressStage::new(
+                    stage,
+                    stage_config.id.clone(),
+                    Arc::clone(reporter),
+                ));
+            }
             executor.add_stage(stage_config.id.clone(), stage, stage_config.config.clone())?;
+            executor.set_empty_output_policy(&stage_config.id, stage_config.empty_output)?;
+            executor.set_priority(&stage_config.id, stage_config.priority)?;
+            executor.set_memory_limit(&stage_config.id, stage_config.max_memory_mb)?;
+            executor.set_concurrency(
+                &stage_config.id,
+                stage_config.concurrent,
+                stage_config.ordered,
+            )?;
         }
 
         // Add dependencies
-        for stage_config in &config.stages {
+        for (stage_config, _) in &stages {
             for input_id in &stage_config.inputs {
-                executor.add_dependency(input_id, &stage_config.id)?;
+                match stage_config.conditions.get(input_id) {
+                    Some(condition) => executor.add_conditional_dependency(
+                        input_id,
+                        &stage_config.id,
+                        EdgeCondition::parse(condition)?,
+                    )?,
+                    None => executor.add_dependency(input_id, &stage_config.id)?,
+                }
             }
         }
 
-        // Validate the DAG (check for cycles)
+        // Validate the DAG (check for cycles and unconnected stages)
+        executor.set_strict_connectivity(config.global.strict_connectivity);
         executor.validate()?;
 
         Ok(())
@@ -196,6 +427,14 @@ impl DagPipelineBuilder {
     fn create_stage(&self, stage_config: &StageConfig) -> Result<StageRef> {
         let function_name = &stage_config.function;
 
+        // Run metadata comes from this build's run rather than the registry default
+        if function_name == "run_info.read" {
+            if let Some(run) = &self.run_context {
+                use crate::modules::sources::run_info::RunInfoSource;
+                return Ok(Arc::new(RunInfoSource::new(Arc::clone(run))));
+            }
+        }
+
         // 1. Try registry lookup (built-in functions)
         if let Some(stage) = self.registry.get_function(function_name) {
             tracing::debug!(
@@ -269,6 +508,29 @@ impl DagPipelineBuilder {
     }
 }
 
+/// Ids of the given stages and of every stage they (transitively) read from
+pub(crate) fn with_upstream<'a>(
+    config: &'a DagPipelineConfig,
+    ids: &[&'a str],
+) -> HashSet<&'a str> {
+    let inputs: HashMap<&str, &[String]> = config
+        .stages
+        .iter()
+        .map(|stage| (stage.id.as_str(), stage.inputs.as_slice()))
+        .collect();
+
+    let mut selected = HashSet::new();
+    let mut pending = ids.to_vec();
+    while let Some(id) = pending.pop() {
+        if selected.insert(id) {
+            if let Some(stage_inputs) = inputs.get(id) {
+                pending.extend(stage_inputs.iter().map(String::as_str));
+            }
+        }
+    }
+    selected
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -340,4 +602,35 @@ inputs = []
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_dag_builder_tag_without_match() {
+        let registry = Arc::new(ModuleRegistry::with_defaults().await.unwrap());
+        let builder = DagPipelineBuilder::new(registry);
+
+        let config_str = r#"
+[pipeline]
+name = "test"
+version = "1.0"
+
+[global]
+tags = ["nightly"]
+
+[[stages]]
+id = "load_data"
+function = "json.read"
+inputs = []
+tags = ["hourly"]
+
+[stages.config]
+path = "test.json"
+"#;
+
+        let config = DagPipelineConfig::from_str(config_str).unwrap();
+        let err = match builder.build(&config) {
+            Ok(_) => panic!("expected no stage to match"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("No stage matches tag(s): nightly"));
+    }
 }
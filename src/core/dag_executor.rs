@@ -1,15 +1,20 @@
 use anyhow::Result;
 use petgraph::algo::toposort;
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
+use crate::core::condition::EdgeCondition;
 use crate::core::error::ConveyorError;
+use crate::core::memory::MemoryLimit;
+use crate::core::metadata::StageCategory;
 use crate::core::stage::StageRef;
-use crate::core::strategy::ErrorStrategy;
+use crate::core::strategy::{EmptyOutputPolicy, ErrorStrategy};
+use crate::core::streaming::StreamProcessor;
 use crate::core::traits::DataFormat;
 
 /// Message type for broadcast channels (used in fan-out scenarios)
@@ -54,13 +59,149 @@ pub struct StageNode {
     pub id: String,
     pub stage: StageRef,
     pub config: HashMap<String, toml::Value>,
+    pub empty_output: EmptyOutputPolicy,
+    pub priority: i32,
+    pub max_memory_mb: Option<u64>,
+    pub concurrent: bool,
+    pub ordered: bool,
+}
+
+/// Set the empty-output policy of a stage already added to `graph`
+fn set_node_empty_output<E>(
+    graph: &mut DiGraph<StageNode, E>,
+    node_map: &HashMap<String, NodeIndex>,
+    id: &str,
+    policy: EmptyOutputPolicy,
+) -> Result<()> {
+    let index = node_map
+        .get(id)
+        .ok_or_else(|| ConveyorError::PipelineError(format!("Stage '{}' not found", id)))?;
+    graph[*index].empty_output = policy;
+    Ok(())
+}
+
+/// Set the scheduling priority of a stage already added to `graph`
+fn set_node_priority<E>(
+    graph: &mut DiGraph<StageNode, E>,
+    node_map: &HashMap<String, NodeIndex>,
+    id: &str,
+    priority: i32,
+) -> Result<()> {
+    let index = node_map
+        .get(id)
+        .ok_or_else(|| ConveyorError::PipelineError(format!("Stage '{}' not found", id)))?;
+    graph[*index].priority = priority;
+    Ok(())
+}
+
+/// Set the memory budget of a stage already added to `graph`
+fn set_node_memory_limit<E>(
+    graph: &mut DiGraph<StageNode, E>,
+    node_map: &HashMap<String, NodeIndex>,
+    id: &str,
+    max_memory_mb: Option<u64>,
+) -> Result<()> {
+    let index = node_map
+        .get(id)
+        .ok_or_else(|| ConveyorError::PipelineError(format!("Stage '{}' not found", id)))?;
+    graph[*index].max_memory_mb = max_memory_mb;
+    Ok(())
+}
+
+/// Set how a stage already added to `graph` processes a streamed input
+fn set_node_concurrency<E>(
+    graph: &mut DiGraph<StageNode, E>,
+    node_map: &HashMap<String, NodeIndex>,
+    id: &str,
+    concurrent: bool,
+    ordered: bool,
+) -> Result<()> {
+    let index = node_map
+        .get(id)
+        .ok_or_else(|| ConveyorError::PipelineError(format!("Stage '{}' not found", id)))?;
+    graph[*index].concurrent = concurrent;
+    graph[*index].ordered = ordered;
+    Ok(())
+}
+
+/// Find stages with no path from a source stage, and non-sink stages with no
+/// path to a sink stage. These are reported as warnings, or as an error when
+/// `strict` is set.
+fn check_connectivity<E>(graph: &DiGraph<StageNode, E>, strict: bool) -> Result<()> {
+    let categories: HashMap<NodeIndex, StageCategory> = graph
+        .node_indices()
+        .map(|idx| (idx, graph[idx].stage.metadata().category))
+        .collect();
+    let is_sink = |idx: NodeIndex| {
+        categories[&idx] == StageCategory::Sink || !graph[idx].stage.produces_output()
+    };
+
+    let reachable = |starts: Vec<NodeIndex>, direction: petgraph::Direction| {
+        let mut seen: HashSet<NodeIndex> = starts.iter().copied().collect();
+        let mut pending = starts;
+        while let Some(idx) = pending.pop() {
+            for next in graph.neighbors_directed(idx, direction) {
+                if seen.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+        seen
+    };
+    let from_source = reachable(
+        graph
+            .node_indices()
+            .filter(|idx| categories[idx] == StageCategory::Source)
+            .collect(),
+        petgraph::Direction::Outgoing,
+    );
+    let to_sink = reachable(
+        graph.node_indices().filter(|idx| is_sink(*idx)).collect(),
+        petgraph::Direction::Incoming,
+    );
+
+    let mut problems = Vec::new();
+    for idx in graph.node_indices() {
+        if !from_source.contains(&idx) {
+            problems.push(format!(
+                "stage '{}' has no incoming path from a source",
+                graph[idx].id
+            ));
+        }
+        if !is_sink(idx) && !to_sink.contains(&idx) {
+            problems.push(format!(
+                "stage '{}' has no outgoing path to a sink",
+                graph[idx].id
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        return Err(ConveyorError::PipelineError(format!(
+            "Unconnected stages: {}",
+            problems.join("; ")
+        ))
+        .into());
+    }
+    for problem in &problems {
+        warn!("Pipeline connectivity: {}", problem);
+    }
+    Ok(())
 }
 
 /// DAG-based pipeline executor
+///
+/// Edges may carry an [`EdgeCondition`]; a stage with a false condition on any
+/// input edge is skipped together with everything downstream of it.
 pub struct DagExecutor {
-    graph: DiGraph<StageNode, ()>,
+    graph: DiGraph<StageNode, Option<EdgeCondition>>,
     node_map: HashMap<String, NodeIndex>,
     error_strategy: ErrorStrategy,
+    strict_connectivity: bool,
+    max_parallel_tasks: Option<usize>,
 }
 
 impl DagExecutor {
@@ -69,9 +210,19 @@ impl DagExecutor {
             graph: DiGraph::new(),
             node_map: HashMap::new(),
             error_strategy,
+            strict_connectivity: false,
+            max_parallel_tasks: None,
         }
     }
 
+    /// Run at most `max` stages of a level at once (0 means unlimited)
+    ///
+    /// Slots are handed out in priority order, so when stages compete for
+    /// them the higher-priority ones start first.
+    pub fn set_max_parallel_tasks(&mut self, max: usize) {
+        self.max_parallel_tasks = (max > 0).then_some(max);
+    }
+
     /// Add a stage to the DAG
     pub fn add_stage(
         &mut self,
@@ -89,6 +240,11 @@ impl DagExecutor {
             id: id.clone(),
             stage,
             config,
+            empty_output: EmptyOutputPolicy::default(),
+            priority: 0,
+            max_memory_mb: None,
+            concurrent: false,
+            ordered: false,
         };
         let node_index = self.graph.add_node(node);
         self.node_map.insert(id, node_index);
@@ -96,9 +252,56 @@ impl DagExecutor {
         Ok(())
     }
 
+    /// Set what happens when the stage `id` produces zero rows
+    pub fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()> {
+        set_node_empty_output(&mut self.graph, &self.node_map, id, policy)
+    }
+
+    /// Set the scheduling priority of stage `id` (higher runs first)
+    pub fn set_priority(&mut self, id: &str, priority: i32) -> Result<()> {
+        set_node_priority(&mut self.graph, &self.node_map, id, priority)
+    }
+
+    /// Limit stage `id` to `max_memory_mb`: heavy transforms stream, and a
+    /// result over the limit fails the stage
+    pub fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()> {
+        set_node_memory_limit(&mut self.graph, &self.node_map, id, max_memory_mb)
+    }
+
+    /// Record how stage `id` processes a streamed input (only the channel
+    /// executor runs batches concurrently)
+    pub fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()> {
+        set_node_concurrency(&mut self.graph, &self.node_map, id, concurrent, ordered)
+    }
+
+    /// Fail validation when a stage is cut off from every source or sink
+    pub fn set_strict_connectivity(&mut self, strict: bool) {
+        self.strict_connectivity = strict;
+    }
+
     /// Add a dependency edge from `from_id` to `to_id`
     /// (to_id depends on from_id)
     pub fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()> {
+        self.add_edge(from_id, to_id, None)
+    }
+
+    /// Add a dependency edge that only lets `to_id` run when `condition`
+    /// holds for the output of `from_id`
+    pub fn add_conditional_dependency(
+        &mut self,
+        from_id: &str,
+        to_id: &str,
+        condition: EdgeCondition,
+    ) -> Result<()> {
+        self.add_edge(from_id, to_id, Some(condition))
+    }
+
+    fn add_edge(
+        &mut self,
+        from_id: &str,
+        to_id: &str,
+        condition: Option<EdgeCondition>,
+    ) -> Result<()> {
         let from_index = self.node_map.get(from_id).ok_or_else(|| {
             ConveyorError::PipelineError(format!("Stage '{}' not found", from_id))
         })?;
@@ -108,16 +311,63 @@ impl DagExecutor {
             .get(to_id)
             .ok_or_else(|| ConveyorError::PipelineError(format!("Stage '{}' not found", to_id)))?;
 
-        self.graph.add_edge(*from_index, *to_index, ());
+        self.graph.add_edge(*from_index, *to_index, condition);
 
         Ok(())
     }
 
-    /// Validate the DAG (check for cycles)
+    /// Why the stage at `node_index` must not run, if it must not
+    ///
+    /// A stage is skipped when an upstream stage was skipped, or when a
+    /// condition on one of its input edges is false.
+    fn skip_reason(
+        &self,
+        node_index: NodeIndex,
+        outputs: &HashMap<String, DataFormat>,
+        skipped: &HashSet<String>,
+    ) -> Result<Option<String>> {
+        let edges: Vec<_> = self
+            .graph
+            .edges_directed(node_index, petgraph::Direction::Incoming)
+            .collect();
+
+        for edge in &edges {
+            let pred_id = &self.graph[edge.source()].id;
+            if skipped.contains(pred_id) {
+                return Ok(Some(format!("upstream stage '{}' did not run", pred_id)));
+            }
+        }
+
+        for edge in &edges {
+            let (Some(condition), Some(data)) =
+                (edge.weight(), outputs.get(&self.graph[edge.source()].id))
+            else {
+                continue;
+            };
+            let pred_id = &self.graph[edge.source()].id;
+            let holds = condition.evaluate(data).map_err(|e| {
+                ConveyorError::PipelineError(format!(
+                    "Stage '{}': condition on input '{}': {}",
+                    self.graph[node_index].id, pred_id, e
+                ))
+            })?;
+            if !holds {
+                return Ok(Some(format!(
+                    "condition '{}' on input '{}' is false",
+                    condition.expression(),
+                    pred_id
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Validate the DAG (check for cycles and unconnected stages)
     pub fn validate(&self) -> Result<()> {
         // Try topological sort to detect cycles
         match toposort(&self.graph, None) {
-            Ok(_) => Ok(()),
+            Ok(_) => check_connectivity(&self.graph, self.strict_connectivity),
             Err(cycle) => Err(ConveyorError::PipelineError(format!(
                 "Cycle detected in pipeline at stage '{}'",
                 self.graph[cycle.node_id()].id
@@ -140,9 +390,18 @@ impl DagExecutor {
 
         // Store outputs from each stage
         let mut outputs: HashMap<String, DataFormat> = HashMap::new();
+        // Stages that did not run because of an edge condition
+        let mut skipped: HashSet<String> = HashSet::new();
+        let slots = self
+            .max_parallel_tasks
+            .map(|max| Arc::new(tokio::sync::Semaphore::new(max)));
 
         // Execute each level
         for (level_idx, level) in levels.iter().enumerate() {
+            // Spawn higher-priority stages first; ties keep topological order
+            let mut level = level.clone();
+            level.sort_by_key(|idx| std::cmp::Reverse(self.graph[*idx].priority));
+
             info!(
                 "Executing level {} with {} stage(s)",
                 level_idx,
@@ -152,11 +411,21 @@ impl DagExecutor {
             // Execute all stages in this level in parallel
             let mut tasks = Vec::new();
 
-            for &node_index in level {
+            for &node_index in &level {
                 let node = &self.graph[node_index];
                 let stage = Arc::clone(&node.stage);
                 let config = node.config.clone();
                 let id = node.id.clone();
+                let empty_output = node.empty_output;
+                let memory_limit = node
+                    .max_memory_mb
+                    .map(|max_mb| MemoryLimit::new(id.clone(), max_mb));
+
+                if let Some(reason) = self.skip_reason(node_index, &outputs, &skipped)? {
+                    info!("Skipping stage '{}': {}", id, reason);
+                    skipped.insert(id);
+                    continue;
+                }
 
                 // Collect inputs from predecessor stages
                 let mut inputs = HashMap::new();
@@ -192,10 +461,26 @@ impl DagExecutor {
                 let stage_clone = Arc::clone(&stage);
                 let id_clone = id.clone();
 
+                // Wait for a slot before spawning so slots go out in priority order
+                let slot = match &slots {
+                    Some(slots) => Some(Arc::clone(slots).acquire_owned().await.map_err(|e| {
+                        ConveyorError::PipelineError(format!("Task scheduling failed: {}", e))
+                    })?),
+                    None => None,
+                };
+
                 // Spawn task for this stage
                 let task = tokio::spawn(async move {
+                    let _slot = slot;
                     info!("Executing stage '{}'", id_clone);
-                    let result = stage_clone.execute(inputs_clone, &config_clone).await;
+                    let result = match memory_limit {
+                        Some(limit) => limit
+                            .clone()
+                            .scope(stage_clone.execute(inputs_clone, &config_clone))
+                            .await
+                            .and_then(|data| limit.check(&data).map(|_| data)),
+                        None => stage_clone.execute(inputs_clone, &config_clone).await,
+                    };
 
                     let result = match result {
                         Ok(data) => {
@@ -220,6 +505,13 @@ impl DagExecutor {
                         }
                     };
 
+                    // Sinks always return empty data, so only check producing stages
+                    if let Ok((id, data)) = &final_result {
+                        if stage_clone.produces_output() {
+                            empty_output.check(id, data)?;
+                        }
+                    }
+
                     final_result
                 });
 
@@ -360,6 +652,458 @@ mod tests {
 
         assert!(executor.validate().is_err());
     }
+
+    /// Source appending "<id> start" and "<id> end" to a shared log
+    struct LoggingStage {
+        id: &'static str,
+        log: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Stage for LoggingStage {
+        fn name(&self) -> &str {
+            "logging"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder(
+                "logging",
+                crate::core::metadata::StageCategory::Source,
+            )
+            .build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            self.log.lock().unwrap().push(format!("{} start", self.id));
+            tokio::task::yield_now().await;
+            self.log.lock().unwrap().push(format!("{} end", self.id));
+            Ok(DataFormat::DataFrame(DataFrame::empty()))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// "backfill" (priority 0) is added before "realtime" (priority 10)
+    fn prioritized_sources(log: &Arc<std::sync::Mutex<Vec<String>>>) -> DagExecutor {
+        let mut executor = DagExecutor::new(ErrorStrategy::Stop);
+        for (id, priority) in [("backfill", 0), ("realtime", 10)] {
+            let stage = Arc::new(LoggingStage {
+                id,
+                log: Arc::clone(log),
+            }) as StageRef;
+            executor
+                .add_stage(id.to_string(), stage, HashMap::new())
+                .unwrap();
+            executor.set_priority(id, priority).unwrap();
+        }
+        executor
+    }
+
+    #[tokio::test]
+    async fn test_dag_executor_starts_higher_priority_first() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        prioritized_sources(&log).execute().await.unwrap();
+
+        let log = log.lock().unwrap();
+        let position = |entry: &str| log.iter().position(|e| e == entry).unwrap();
+        assert!(position("realtime start") < position("backfill start"));
+    }
+
+    #[tokio::test]
+    async fn test_dag_executor_gives_slots_by_priority() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut executor = prioritized_sources(&log);
+        executor.set_max_parallel_tasks(1);
+        executor.execute().await.unwrap();
+
+        // With a single slot the realtime source finishes before backfill starts
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "realtime start",
+                "realtime end",
+                "backfill start",
+                "backfill end"
+            ]
+        );
+    }
+
+    /// Stage reporting the given category, for connectivity checks
+    struct CategoryStage(crate::core::metadata::StageCategory);
+
+    #[async_trait]
+    impl Stage for CategoryStage {
+        fn name(&self) -> &str {
+            "category"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder("category", self.0).build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            Ok(DataFormat::DataFrame(DataFrame::empty()))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// source -> transform -> sink, plus an orphaned transform feeding the sink
+    fn orphaned_transform_executor() -> DagExecutor {
+        use crate::core::metadata::StageCategory;
+
+        let mut executor = DagExecutor::new(ErrorStrategy::Stop);
+        for (id, stage) in [
+            (
+                "source",
+                Arc::new(CategoryStage(StageCategory::Source)) as StageRef,
+            ),
+            ("transform", Arc::new(MockStage) as StageRef),
+            ("orphan", Arc::new(MockStage) as StageRef),
+            (
+                "sink",
+                Arc::new(CategoryStage(StageCategory::Sink)) as StageRef,
+            ),
+        ] {
+            executor
+                .add_stage(id.to_string(), stage, HashMap::new())
+                .unwrap();
+        }
+        executor.add_dependency("source", "transform").unwrap();
+        executor.add_dependency("transform", "sink").unwrap();
+        executor.add_dependency("orphan", "sink").unwrap();
+        executor
+    }
+
+    #[tokio::test]
+    async fn test_dag_executor_reports_orphaned_stage() {
+        let mut executor = orphaned_transform_executor();
+        // Warnings only by default
+        assert!(executor.validate().is_ok());
+
+        executor.set_strict_connectivity(true);
+        let err = executor.validate().unwrap_err().to_string();
+        assert!(err.contains("stage 'orphan' has no incoming path from a source"));
+        assert!(!err.contains("'transform'"));
+        assert!(!err.contains("'sink'"));
+    }
+
+    #[tokio::test]
+    async fn test_dag_executor_reports_dead_end_stage() {
+        use crate::core::metadata::StageCategory;
+
+        let mut executor = orphaned_transform_executor();
+        executor
+            .add_stage(
+                "dead_end".to_string(),
+                Arc::new(MockStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor.add_dependency("source", "dead_end").unwrap();
+        executor.set_strict_connectivity(true);
+        let err = executor.validate().unwrap_err().to_string();
+        assert!(err.contains("stage 'dead_end' has no outgoing path to a sink"));
+        assert!(!err.contains("stage 'dead_end' has no incoming path"));
+
+        // A fully wired pipeline passes strict validation
+        let mut executor = DagExecutor::new(ErrorStrategy::Stop);
+        executor
+            .add_stage(
+                "source".to_string(),
+                Arc::new(CategoryStage(StageCategory::Source)) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor
+            .add_stage(
+                "sink".to_string(),
+                Arc::new(CategoryStage(StageCategory::Sink)) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor.add_dependency("source", "sink").unwrap();
+        executor.set_strict_connectivity(true);
+        assert!(executor.validate().is_ok());
+    }
+
+    struct FailingStage;
+
+    #[async_trait]
+    impl Stage for FailingStage {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder(
+                "failing",
+                crate::core::metadata::StageCategory::Source,
+            )
+            .description("Always fails")
+            .build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            anyhow::bail!("source unavailable")
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn empty_stage_executor(
+        stage: StageRef,
+        strategy: ErrorStrategy,
+        policy: EmptyOutputPolicy,
+    ) -> DagExecutor {
+        let mut executor = DagExecutor::new(strategy);
+        executor
+            .add_stage("empty".to_string(), stage, HashMap::new())
+            .unwrap();
+        executor
+            .add_stage(
+                "downstream".to_string(),
+                Arc::new(MockStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor.add_dependency("empty", "downstream").unwrap();
+        executor.set_empty_output_policy("empty", policy).unwrap();
+        executor
+    }
+
+    #[tokio::test]
+    async fn test_empty_output_ok_and_warn_policies_pass() {
+        for policy in [EmptyOutputPolicy::Ok, EmptyOutputPolicy::Warn] {
+            let executor = empty_stage_executor(Arc::new(MockStage), ErrorStrategy::Stop, policy);
+            assert!(executor.execute().await.is_ok(), "{:?}", policy);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_output_error_policy_fails() {
+        let executor = empty_stage_executor(
+            Arc::new(MockStage),
+            ErrorStrategy::Stop,
+            EmptyOutputPolicy::Error,
+        );
+
+        let err = executor.execute().await.unwrap_err().to_string();
+        assert!(err.contains("Stage 'empty' produced 0 rows"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_empty_output_error_policy_catches_continue_substitution() {
+        // A failed stage under `continue` is replaced by an empty DataFrame
+        let executor = empty_stage_executor(
+            Arc::new(FailingStage),
+            ErrorStrategy::Continue,
+            EmptyOutputPolicy::Warn,
+        );
+        assert!(executor.execute().await.is_ok());
+
+        let executor = empty_stage_executor(
+            Arc::new(FailingStage),
+            ErrorStrategy::Continue,
+            EmptyOutputPolicy::Error,
+        );
+        assert!(executor.execute().await.is_err());
+    }
+
+    #[test]
+    fn test_empty_output_policy_ignores_non_empty_and_streams() {
+        let rows = DataFormat::RecordBatch(vec![HashMap::from([(
+            "id".to_string(),
+            serde_json::json!(1),
+        )])]);
+        assert!(EmptyOutputPolicy::Error.check("stage", &rows).is_ok());
+
+        let stream = DataFormat::Stream(Box::pin(tokio_stream::empty()));
+        assert!(EmptyOutputPolicy::Error.check("stage", &stream).is_ok());
+
+        let empty = DataFormat::RecordBatch(Vec::new());
+        assert!(EmptyOutputPolicy::Error.check("stage", &empty).is_err());
+        assert!(EmptyOutputPolicy::Warn.check("stage", &empty).is_ok());
+    }
+
+    /// Source emitting `rows` records
+    struct RowsSource {
+        rows: usize,
+    }
+
+    #[async_trait]
+    impl Stage for RowsSource {
+        fn name(&self) -> &str {
+            "rows"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder(
+                "rows",
+                crate::core::metadata::StageCategory::Source,
+            )
+            .build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            Ok(DataFormat::RecordBatch(
+                (0..self.rows)
+                    .map(|i| HashMap::from([("id".to_string(), serde_json::json!(i))]))
+                    .collect(),
+            ))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// errors -> alert (only if `row_count > 0`) -> archive, plus errors -> audit
+    async fn run_conditional_alert(rows: usize) -> Vec<String> {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut executor = DagExecutor::new(ErrorStrategy::Stop);
+        executor
+            .add_stage(
+                "errors".to_string(),
+                Arc::new(RowsSource { rows }) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        for id in ["alert", "archive", "audit"] {
+            let stage = Arc::new(LoggingStage {
+                id,
+                log: Arc::clone(&log),
+            }) as StageRef;
+            executor
+                .add_stage(id.to_string(), stage, HashMap::new())
+                .unwrap();
+        }
+        executor
+            .add_conditional_dependency(
+                "errors",
+                "alert",
+                EdgeCondition::parse("row_count > 0").unwrap(),
+            )
+            .unwrap();
+        executor.add_dependency("alert", "archive").unwrap();
+        executor.add_dependency("errors", "audit").unwrap();
+
+        executor.execute().await.unwrap();
+        let mut ran: Vec<String> = log
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| entry.strip_suffix(" end").map(String::from))
+            .collect();
+        ran.sort();
+        ran
+    }
+
+    #[tokio::test]
+    async fn test_conditional_edge_skips_stage_and_dependents() {
+        // An empty upstream skips the alert and everything after it, without failing
+        assert_eq!(run_conditional_alert(0).await, vec!["audit"]);
+        assert_eq!(
+            run_conditional_alert(3).await,
+            vec!["alert", "archive", "audit"]
+        );
+    }
+
+    /// Source emitting `rows` rows with a unique `id` and seven `key` values
+    struct WideSource(i64);
+
+    #[async_trait]
+    impl Stage for WideSource {
+        fn name(&self) -> &str {
+            "wide"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder("wide", StageCategory::Source)
+                .description("Emits a wide frame")
+                .build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            let df = polars::df! {
+                "id" => (0..self.0).collect::<Vec<_>>(),
+                "key" => (0..self.0).map(|i| i % 7).collect::<Vec<_>>(),
+            }?;
+            Ok(DataFormat::DataFrame(df))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn run_limited_group_by(by: &str) -> Result<()> {
+        let mut executor = DagExecutor::new(ErrorStrategy::Stop);
+        executor
+            .add_stage(
+                "source".to_string(),
+                Arc::new(WideSource(200_000)) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor
+            .add_stage(
+                "totals".to_string(),
+                Arc::new(crate::modules::transforms::group_by::GroupByTransform) as StageRef,
+                toml::from_str(&format!(
+                    r#"
+by = ["{}"]
+aggregations = [{{ column = "key", operation = "count", output_column = "rows" }}]
+"#,
+                    by
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+        executor.add_dependency("source", "totals").unwrap();
+        executor.set_memory_limit("totals", Some(1)).unwrap();
+        executor.execute().await
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit_streams_or_fails_cleanly() {
+        // Seven groups stream through the limit; the input alone is over it
+        run_limited_group_by("key").await.unwrap();
+
+        let err = run_limited_group_by("id").await.unwrap_err().to_string();
+        assert!(
+            err.contains("Stage 'totals' exceeded its memory limit")
+                && err.ends_with("max_memory_mb is 1"),
+            "{}",
+            err
+        );
+    }
 }
 
 // ============================================================================
@@ -402,6 +1146,15 @@ mod tests {
 /// - Uses tokio-stream's buffer_unordered for I/O parallelism
 /// - Dramatically improves throughput for I/O-bound operations
 ///
+/// ## Ordering
+///
+/// A stage marked `concurrent` runs once per batch of a streamed input, up to
+/// `stage_concurrency` batches at a time, and batches leave as they finish.
+/// Marking it `ordered` as well tags the batches with sequence numbers and
+/// reorders them before the downstream send. The reorder buffer holds at most
+/// `reorder_buffer_size` batches, in flight or waiting, so a slow batch
+/// stalls intake instead of growing memory.
+///
 /// ## Example usage
 ///
 /// ```rust,ignore
@@ -439,6 +1192,8 @@ pub struct ChannelDagExecutor {
     error_strategy: ErrorStrategy,
     buffer_size: usize,
     stage_concurrency: usize,
+    reorder_buffer_size: usize,
+    strict_connectivity: bool,
 }
 
 impl ChannelDagExecutor {
@@ -459,6 +1214,8 @@ impl ChannelDagExecutor {
             error_strategy,
             buffer_size,
             stage_concurrency,
+            reorder_buffer_size: buffer_size,
+            strict_connectivity: false,
         }
     }
 
@@ -479,6 +1236,11 @@ impl ChannelDagExecutor {
             id: id.clone(),
             stage,
             config,
+            empty_output: EmptyOutputPolicy::default(),
+            priority: 0,
+            max_memory_mb: None,
+            concurrent: false,
+            ordered: false,
         };
         let node_index = self.graph.add_node(node);
         self.node_map.insert(id, node_index);
@@ -486,6 +1248,38 @@ impl ChannelDagExecutor {
         Ok(())
     }
 
+    /// Set what happens when the stage `id` produces zero rows
+    pub fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()> {
+        set_node_empty_output(&mut self.graph, &self.node_map, id, policy)
+    }
+
+    /// Set the scheduling priority of stage `id` (higher runs first)
+    pub fn set_priority(&mut self, id: &str, priority: i32) -> Result<()> {
+        set_node_priority(&mut self.graph, &self.node_map, id, priority)
+    }
+
+    /// Record the memory budget of stage `id` (only the dag executor enforces it)
+    pub fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()> {
+        set_node_memory_limit(&mut self.graph, &self.node_map, id, max_memory_mb)
+    }
+
+    /// Run the batches of a streamed input to stage `id` concurrently, in
+    /// input order when `ordered` is set
+    pub fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()> {
+        set_node_concurrency(&mut self.graph, &self.node_map, id, concurrent, ordered)
+    }
+
+    /// Set how many batches an ordered stage may hold, in flight or waiting
+    /// for an earlier batch
+    pub fn set_reorder_buffer_size(&mut self, size: usize) {
+        self.reorder_buffer_size = size;
+    }
+
+    /// Fail validation when a stage is cut off from every source or sink
+    pub fn set_strict_connectivity(&mut self, strict: bool) {
+        self.strict_connectivity = strict;
+    }
+
     /// Add a dependency edge from `from_id` to `to_id`
     pub fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()> {
         let from_index = self.node_map.get(from_id).ok_or_else(|| {
@@ -502,10 +1296,10 @@ impl ChannelDagExecutor {
         Ok(())
     }
 
-    /// Validate the DAG (check for cycles)
+    /// Validate the DAG (check for cycles and unconnected stages)
     pub fn validate(&self) -> Result<()> {
         match toposort(&self.graph, None) {
-            Ok(_) => Ok(()),
+            Ok(_) => check_connectivity(&self.graph, self.strict_connectivity),
             Err(cycle) => Err(ConveyorError::PipelineError(format!(
                 "Cycle detected in pipeline at stage '{}'",
                 self.graph[cycle.node_id()].id
@@ -608,7 +1402,14 @@ impl ChannelDagExecutor {
             let stage = Arc::clone(&node.stage);
             let config = node.config.clone();
             let id = node.id.clone();
+            let empty_output = node.empty_output;
             let is_fanout = fanout_nodes.contains(node_idx);
+            let concurrency = node.concurrent.then(|| {
+                (
+                    self.stage_concurrency,
+                    node.ordered.then_some(self.reorder_buffer_size),
+                )
+            });
 
             // Collect input receivers (from either mpsc or broadcast channels)
             let predecessors: Vec<_> = self
@@ -675,6 +1476,8 @@ impl ChannelDagExecutor {
                     mpsc_output_senders,
                     broadcast_sender,
                     error_strategy,
+                    empty_output,
+                    concurrency,
                 )
                 .await
             });
@@ -716,6 +1519,7 @@ impl ChannelDagExecutor {
         mut input_receivers: Vec<(String, mpsc::Receiver<DataFormat>)>,
         output_senders: Vec<mpsc::Sender<DataFormat>>,
         error_strategy: ErrorStrategy,
+        empty_output: EmptyOutputPolicy,
     ) -> Result<()> {
         info!("Channel stage '{}' started", id);
 
@@ -757,6 +1561,10 @@ impl ChannelDagExecutor {
             }
         };
 
+        if stage.produces_output() {
+            empty_output.check(&id, &output)?;
+        }
+
         // Send to output channels
         for tx in output_senders {
             // Clone data for each output
@@ -777,6 +1585,82 @@ impl ChannelDagExecutor {
         Ok(())
     }
 
+    /// Execute `stage` on its inputs
+    ///
+    /// With `concurrency = Some((n, reorder_buffer))` and a single streamed
+    /// input, the stage runs once per batch with up to `n` batches at a time
+    /// and its output is streamed on. `reorder_buffer` keeps the output in
+    /// input order (see [`StreamProcessor::map_concurrent`]); without it
+    /// batches leave in the order they finish. Any other input is a single
+    /// unit of work and runs as usual.
+    async fn execute_stage(
+        id: &str,
+        stage: &StageRef,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+        concurrency: Option<(usize, Option<usize>)>,
+        error_strategy: &ErrorStrategy,
+    ) -> Result<DataFormat> {
+        let Some((concurrency, reorder_buffer)) = concurrency else {
+            return stage.execute(inputs, config).await;
+        };
+        if inputs.len() != 1 {
+            return stage.execute(inputs, config).await;
+        }
+        let (input_id, stream) = match inputs.into_iter().next() {
+            Some((input_id, DataFormat::Stream(stream))) => (input_id, stream),
+            Some((input_id, input)) => {
+                return stage
+                    .execute(HashMap::from([(input_id, input)]), config)
+                    .await;
+            }
+            None => return stage.execute(HashMap::new(), config).await,
+        };
+
+        info!(
+            "Stage '{}': processing streamed batches with concurrency {}{}",
+            id,
+            concurrency,
+            if reorder_buffer.is_some() {
+                " (ordered)"
+            } else {
+                ""
+            }
+        );
+        let stage = Arc::clone(stage);
+        let config = Arc::new(config.clone());
+        let id = id.to_string();
+        let continue_on_error = error_strategy.should_continue_on_error();
+        Ok(DataFormat::Stream(StreamProcessor::map_concurrent(
+            stream,
+            concurrency,
+            reorder_buffer,
+            move |batch| {
+                let stage = Arc::clone(&stage);
+                let config = Arc::clone(&config);
+                let id = id.clone();
+                let inputs = HashMap::from([(input_id.clone(), DataFormat::RecordBatch(batch))]);
+                async move {
+                    let result = match stage.execute(inputs, &config).await {
+                        Ok(DataFormat::RecordBatch(batch)) => Ok(batch),
+                        Ok(output) => output.as_record_batch(),
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Err(e) if continue_on_error => {
+                            warn!(
+                                "Channel stage '{}' failed on a batch: {}. Continuing...",
+                                id, e
+                            );
+                            Ok(Vec::new())
+                        }
+                        result => result,
+                    }
+                }
+            },
+        )))
+    }
+
     /// Run a single stage with fan-out support (broadcast channels)
     #[allow(clippy::too_many_arguments)]
     async fn run_stage_with_fanout(
@@ -788,6 +1672,8 @@ impl ChannelDagExecutor {
         mpsc_output_senders: Vec<mpsc::Sender<DataFormat>>,
         broadcast_sender: Option<broadcast::Sender<BroadcastMessage>>,
         error_strategy: ErrorStrategy,
+        empty_output: EmptyOutputPolicy,
+        concurrency: Option<(usize, Option<usize>)>,
     ) -> Result<()> {
         info!("Channel stage '{}' started (fan-out aware)", id);
 
@@ -848,24 +1734,36 @@ impl ChannelDagExecutor {
 
         // Execute stage
         info!("Executing channel stage '{}'", id);
-        let output = match stage.execute(inputs, &config).await {
-            Ok(data) => {
-                info!("Channel stage '{}' completed successfully", id);
-                data
-            }
-            Err(e) => {
-                if error_strategy.should_continue_on_error() {
-                    warn!("Channel stage '{}' failed: {}. Continuing...", id, e);
-                    DataFormat::DataFrame(polars::prelude::DataFrame::empty())
-                } else {
-                    // Send error to broadcast channel if this is a fan-out node
-                    if let Some(tx) = broadcast_sender {
-                        let _ = tx.send(BroadcastMessage::Error(e.to_string()));
+        let output =
+            match Self::execute_stage(&id, &stage, inputs, &config, concurrency, &error_strategy)
+                .await
+            {
+                Ok(data) => {
+                    info!("Channel stage '{}' completed successfully", id);
+                    data
+                }
+                Err(e) => {
+                    if error_strategy.should_continue_on_error() {
+                        warn!("Channel stage '{}' failed: {}. Continuing...", id, e);
+                        DataFormat::DataFrame(polars::prelude::DataFrame::empty())
+                    } else {
+                        // Send error to broadcast channel if this is a fan-out node
+                        if let Some(tx) = broadcast_sender {
+                            let _ = tx.send(BroadcastMessage::Error(e.to_string()));
+                        }
+                        return Err(e);
                     }
-                    return Err(e);
                 }
+            };
+
+        if stage.produces_output() {
+            if let Err(e) = empty_output.check(&id, &output) {
+                if let Some(tx) = broadcast_sender {
+                    let _ = tx.send(BroadcastMessage::Error(e.to_string()));
+                }
+                return Err(e);
             }
-        };
+        }
 
         // Send to output channels
         if let Some(tx) = broadcast_sender {
@@ -883,16 +1781,23 @@ impl ChannelDagExecutor {
             // Send completion signal
             let _ = tx.send(BroadcastMessage::Complete);
         } else {
-            // Single output: send to mpsc channels
-            for tx in mpsc_output_senders {
-                let data = output.try_clone().map_err(|e| {
-                    anyhow::anyhow!(
-                        "Cannot clone output from stage '{}': {}. Streaming data can only be consumed once.",
-                        id, e
-                    )
-                })?;
-
-                if let Err(e) = tx.send(data).await {
+            // Single output: send to mpsc channels, moving the output into the
+            // last one so a streamed output can be passed on
+            if let Some((last, rest)) = mpsc_output_senders.split_last() {
+                for tx in rest {
+                    let data = output.try_clone().map_err(|e| {
+                        anyhow::anyhow!(
+                            "Cannot clone output from stage '{}': {}. Streaming data can only be consumed once.",
+                            id, e
+                        )
+                    })?;
+
+                    if let Err(e) = tx.send(data).await {
+                        warn!("Channel stage '{}': failed to send output: {}", id, e);
+                    }
+                }
+
+                if let Err(e) = last.send(output).await {
                     warn!("Channel stage '{}': failed to send output: {}", id, e);
                 }
             }
@@ -993,6 +1898,37 @@ mod channel_tests {
         assert!(executor.execute().await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_channel_dag_empty_output_error_policy() {
+        let mut executor = ChannelDagExecutor::new(ErrorStrategy::Stop, 10, 10);
+
+        executor
+            .add_stage(
+                "source".to_string(),
+                Arc::new(FastSourceStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor
+            .add_stage(
+                "sink".to_string(),
+                Arc::new(SlowSinkStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor.add_dependency("source", "sink").unwrap();
+
+        executor
+            .set_empty_output_policy("source", EmptyOutputPolicy::Warn)
+            .unwrap();
+        assert!(executor.execute().await.is_ok());
+
+        executor
+            .set_empty_output_policy("source", EmptyOutputPolicy::Error)
+            .unwrap();
+        assert!(executor.execute().await.is_err());
+    }
+
     #[tokio::test]
     async fn test_channel_dag_executor_cycle_detection() {
         let mut executor = ChannelDagExecutor::new(ErrorStrategy::Stop, 10, 10);
@@ -1137,6 +2073,167 @@ mod channel_tests {
             result
         );
     }
+
+    /// Streams the ids 0..8, one record per batch
+    struct BatchSourceStage;
+
+    #[async_trait]
+    impl Stage for BatchSourceStage {
+        fn name(&self) -> &str {
+            "batch_source"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder(
+                "batch_source",
+                crate::core::metadata::StageCategory::Source,
+            )
+            .description("Streaming source for testing ordering")
+            .build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            let batches: Vec<Result<crate::core::traits::RecordBatch>> = (0..8)
+                .map(|id| Ok(vec![HashMap::from([("id".to_string(), id.into())])]))
+                .collect();
+            Ok(DataFormat::Stream(Box::pin(tokio_stream::iter(batches))))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Takes longer for earlier ids, so concurrent batches finish in reverse
+    struct DelayStage;
+
+    #[async_trait]
+    impl Stage for DelayStage {
+        fn name(&self) -> &str {
+            "delay"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder(
+                "delay",
+                crate::core::metadata::StageCategory::Transform,
+            )
+            .description("Per-record delay for testing ordering")
+            .build()
+        }
+
+        async fn execute(
+            &self,
+            inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            let records = inputs.values().next().unwrap().as_record_batch()?;
+            let id = records[0]["id"].as_u64().unwrap();
+            tokio::time::sleep(Duration::from_millis(10 * (8 - id))).await;
+            Ok(DataFormat::RecordBatch(records))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct CollectSinkStage(Arc<std::sync::Mutex<Vec<u64>>>);
+
+    #[async_trait]
+    impl Stage for CollectSinkStage {
+        fn name(&self) -> &str {
+            "collect_sink"
+        }
+
+        fn metadata(&self) -> crate::core::metadata::StageMetadata {
+            crate::core::metadata::StageMetadata::builder(
+                "collect_sink",
+                crate::core::metadata::StageCategory::Sink,
+            )
+            .description("Sink recording the order ids arrive in")
+            .build()
+        }
+
+        async fn execute(
+            &self,
+            inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            use futures::StreamExt;
+
+            for (_, input) in inputs {
+                let DataFormat::Stream(mut stream) = input else {
+                    anyhow::bail!("expected a streamed input");
+                };
+                while let Some(batch) = stream.next().await {
+                    for record in batch? {
+                        self.0.lock().unwrap().push(record["id"].as_u64().unwrap());
+                    }
+                }
+            }
+            Ok(DataFormat::DataFrame(DataFrame::empty()))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+
+        fn produces_output(&self) -> bool {
+            false
+        }
+    }
+
+    async fn run_delayed(ordered: bool) -> Vec<u64> {
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut executor = ChannelDagExecutor::new(ErrorStrategy::Stop, 10, 4);
+        executor
+            .add_stage(
+                "source".to_string(),
+                Arc::new(BatchSourceStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor
+            .add_stage(
+                "delay".to_string(),
+                Arc::new(DelayStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor
+            .add_stage(
+                "sink".to_string(),
+                Arc::new(CollectSinkStage(Arc::clone(&received))) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        executor.add_dependency("source", "delay").unwrap();
+        executor.add_dependency("delay", "sink").unwrap();
+        executor.set_concurrency("delay", true, ordered).unwrap();
+
+        executor.execute().await.unwrap();
+        let received = received.lock().unwrap().clone();
+        received
+    }
+
+    #[tokio::test]
+    async fn test_channel_dag_ordered_stage_keeps_input_order() {
+        let input_order: Vec<u64> = (0..8).collect();
+
+        // Four batches run at once and the later ones finish first
+        let unordered = run_delayed(false).await;
+        assert_ne!(unordered, input_order);
+        let mut sorted = unordered.clone();
+        sorted.sort();
+        assert_eq!(sorted, input_order);
+
+        assert_eq!(run_delayed(true).await, input_order);
+    }
 }
 
 // ============================================================================
@@ -1211,6 +2308,7 @@ pub struct AsyncPipeline {
     error_strategy: ErrorStrategy,
     buffer_size: usize,
     cancellation_token: CancellationToken,
+    strict_connectivity: bool,
 }
 
 impl AsyncPipeline {
@@ -1222,6 +2320,7 @@ impl AsyncPipeline {
             error_strategy,
             buffer_size,
             cancellation_token: CancellationToken::new(),
+            strict_connectivity: false,
         }
     }
 
@@ -1242,6 +2341,11 @@ impl AsyncPipeline {
             id: id.clone(),
             stage,
             config,
+            empty_output: EmptyOutputPolicy::default(),
+            priority: 0,
+            max_memory_mb: None,
+            concurrent: false,
+            ordered: false,
         };
         let node_index = self.graph.add_node(node);
         self.node_map.insert(id, node_index);
@@ -1249,6 +2353,32 @@ impl AsyncPipeline {
         Ok(())
     }
 
+    /// Set what happens when the stage `id` produces zero rows
+    pub fn set_empty_output_policy(&mut self, id: &str, policy: EmptyOutputPolicy) -> Result<()> {
+        set_node_empty_output(&mut self.graph, &self.node_map, id, policy)
+    }
+
+    /// Set the scheduling priority of stage `id` (higher runs first)
+    pub fn set_priority(&mut self, id: &str, priority: i32) -> Result<()> {
+        set_node_priority(&mut self.graph, &self.node_map, id, priority)
+    }
+
+    /// Record the memory budget of stage `id` (only the dag executor enforces it)
+    pub fn set_memory_limit(&mut self, id: &str, max_memory_mb: Option<u64>) -> Result<()> {
+        set_node_memory_limit(&mut self.graph, &self.node_map, id, max_memory_mb)
+    }
+
+    /// Record how stage `id` processes a streamed input (only the channel
+    /// executor runs batches concurrently)
+    pub fn set_concurrency(&mut self, id: &str, concurrent: bool, ordered: bool) -> Result<()> {
+        set_node_concurrency(&mut self.graph, &self.node_map, id, concurrent, ordered)
+    }
+
+    /// Fail validation when a stage is cut off from every source or sink
+    pub fn set_strict_connectivity(&mut self, strict: bool) {
+        self.strict_connectivity = strict;
+    }
+
     /// Add a dependency edge from `from_id` to `to_id`
     pub fn add_dependency(&mut self, from_id: &str, to_id: &str) -> Result<()> {
         let from_index = self.node_map.get(from_id).ok_or_else(|| {
@@ -1265,10 +2395,10 @@ impl AsyncPipeline {
         Ok(())
     }
 
-    /// Validate the pipeline (check for cycles)
+    /// Validate the pipeline (check for cycles and unconnected stages)
     pub fn validate(&self) -> Result<()> {
         match toposort(&self.graph, None) {
-            Ok(_) => Ok(()),
+            Ok(_) => check_connectivity(&self.graph, self.strict_connectivity),
             Err(cycle) => Err(ConveyorError::PipelineError(format!(
                 "Cycle detected in pipeline at stage '{}'",
                 self.graph[cycle.node_id()].id
@@ -1322,6 +2452,7 @@ impl AsyncPipeline {
             let stage = Arc::clone(&node.stage);
             let config = node.config.clone();
             let id = node.id.clone();
+            let empty_output = node.empty_output;
 
             // Collect input receivers
             let predecessors: Vec<_> = self
@@ -1363,6 +2494,7 @@ impl AsyncPipeline {
                     input_receivers,
                     output_senders,
                     error_strategy,
+                    empty_output,
                     cancel_token,
                 )
                 .await
@@ -1400,6 +2532,7 @@ impl AsyncPipeline {
     }
 
     /// Run a single stage as an Actor
+    #[allow(clippy::too_many_arguments)]
     async fn run_stage_actor(
         id: String,
         stage: StageRef,
@@ -1407,6 +2540,7 @@ impl AsyncPipeline {
         mut input_receivers: Vec<(String, mpsc::Receiver<PipelineMessage>)>,
         output_senders: Vec<mpsc::Sender<PipelineMessage>>,
         error_strategy: ErrorStrategy,
+        empty_output: EmptyOutputPolicy,
         cancel_token: CancellationToken,
     ) -> Result<()> {
         info!("Actor '{}' started", id);
@@ -1436,6 +2570,13 @@ impl AsyncPipeline {
                                 Ok(output) => {
                                     info!("Actor '{}': execution successful", id);
 
+                                    if stage.produces_output() {
+                                        if let Err(e) = empty_output.check(&id, &output) {
+                                            cancel_token.cancel();
+                                            return Err(e);
+                                        }
+                                    }
+
                                     // Send to all downstream actors
                                     for sender in &output_senders {
                                         let data = output.try_clone().map_err(|e| {
@@ -1483,6 +2624,13 @@ impl AsyncPipeline {
 
                                 match result {
                                     Ok(output) => {
+                                        if stage.produces_output() {
+                                            if let Err(e) = empty_output.check(&id, &output) {
+                                                cancel_token.cancel();
+                                                return Err(e);
+                                            }
+                                        }
+
                                         // Send to downstream
                                         for sender in &output_senders {
                                             let data = output.try_clone().map_err(|e| {
@@ -1638,6 +2786,33 @@ mod async_pipeline_tests {
         assert!(pipeline.execute().await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_async_pipeline_empty_output_error_policy() {
+        let mut pipeline = AsyncPipeline::new(ErrorStrategy::Stop, 10);
+
+        pipeline
+            .add_stage(
+                "source".to_string(),
+                Arc::new(TestSourceStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        pipeline
+            .add_stage(
+                "transform".to_string(),
+                Arc::new(TestTransformStage) as StageRef,
+                HashMap::new(),
+            )
+            .unwrap();
+        pipeline.add_dependency("source", "transform").unwrap();
+        pipeline
+            .set_empty_output_policy("transform", EmptyOutputPolicy::Error)
+            .unwrap();
+
+        let err = pipeline.execute().await.unwrap_err().to_string();
+        assert!(err.contains("Stage 'transform' produced 0 rows"), "{}", err);
+    }
+
     #[tokio::test]
     async fn test_async_pipeline_fanout() {
         let mut pipeline = AsyncPipeline::new(ErrorStrategy::Stop, 10);
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use polars::prelude::{DataFrame, LazyFrame};
+use std::future::Future;
+
+use crate::core::error::ConveyorError;
+use crate::core::traits::DataFormat;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+tokio::task_local! {
+    static STAGE_MEMORY: MemoryLimit;
+}
+
+/// Memory budget of one stage, from its `max_memory_mb` setting
+///
+/// While a stage runs inside [`MemoryLimit::scope`], heavy transforms that
+/// collect through [`collect`] use polars' streaming engine, which processes
+/// the data in batches (and spills to disk where polars supports it) instead
+/// of materializing every intermediate result at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryLimit {
+    stage: String,
+    max_mb: u64,
+}
+
+impl MemoryLimit {
+    pub fn new(stage: impl Into<String>, max_mb: u64) -> Self {
+        Self {
+            stage: stage.into(),
+            max_mb,
+        }
+    }
+
+    /// Limit of the stage running on the current task, if it has one
+    pub fn current() -> Option<Self> {
+        STAGE_MEMORY.try_with(|limit| limit.clone()).ok()
+    }
+
+    /// Run `future` with this limit as the current one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        STAGE_MEMORY.scope(self, future).await
+    }
+
+    fn max_bytes(&self) -> usize {
+        (self.max_mb as usize).saturating_mul(BYTES_PER_MB)
+    }
+
+    /// Fail when the estimated in-memory size of `df` is over the limit
+    pub fn check_frame(&self, df: &DataFrame) -> Result<()> {
+        let size = df.estimated_size();
+        if size > self.max_bytes() {
+            return Err(ConveyorError::PipelineError(format!(
+                "Stage '{}' exceeded its memory limit: result needs about {:.1} MB, max_memory_mb is {}",
+                self.stage,
+                size as f64 / BYTES_PER_MB as f64,
+                self.max_mb
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Fail when `data` is over the limit; only DataFrames are measured
+    pub fn check(&self, data: &DataFormat) -> Result<()> {
+        match data {
+            DataFormat::DataFrame(df) => self.check_frame(df),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Collect `lf` for the running stage
+///
+/// Without a memory limit this is a plain `collect`. With one, the query runs
+/// on the streaming engine and the result is checked against the limit, so an
+/// oversized result fails the stage with a clear error.
+pub fn collect(lf: LazyFrame) -> Result<DataFrame> {
+    match MemoryLimit::current() {
+        None => Ok(lf.collect()?),
+        Some(limit) => {
+            let df = lf.with_streaming(true).collect()?;
+            limit.check_frame(&df)?;
+            Ok(df)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    fn wide_frame(rows: i64) -> DataFrame {
+        df! {
+            "key" => (0..rows).map(|i| i % 7).collect::<Vec<_>>(),
+            "id" => (0..rows).collect::<Vec<_>>(),
+        }
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_collect_streams_under_limit() {
+        let lf = wide_frame(200_000)
+            .lazy()
+            .group_by([col("key")])
+            .agg([col("id").sum()]);
+
+        // Seven groups fit easily even though the input does not
+        let df = MemoryLimit::new("totals", 1)
+            .scope(async { collect(lf) })
+            .await
+            .unwrap();
+        assert_eq!(df.height(), 7);
+        assert!(MemoryLimit::current().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_collect_fails_over_limit() {
+        let lf = wide_frame(200_000)
+            .lazy()
+            .group_by([col("id")])
+            .agg([col("key").first()]);
+
+        let err = MemoryLimit::new("by_id", 1)
+            .scope(async { collect(lf) })
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.starts_with("Pipeline execution error: Stage 'by_id' exceeded its memory limit: result needs about"),
+            "{}",
+            err
+        );
+        assert!(err.ends_with("max_memory_mb is 1"), "{}", err);
+    }
+}
@@ -94,6 +94,12 @@ impl MetadataBuilder {
         self
     }
 
+    /// Add multiple parameters
+    pub fn parameters(mut self, params: impl IntoIterator<Item = ConfigParameter>) -> Self {
+        self.parameters.extend(params);
+        self
+    }
+
     /// Add an example
     pub fn example(mut self, example: ConfigExample) -> Self {
         self.examples.push(example);
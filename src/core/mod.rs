@@ -1,13 +1,20 @@
+pub mod condition;
 pub mod config;
 pub mod dag_builder;
 pub mod dag_executor;
 pub mod error;
+pub mod memory;
 pub mod metadata;
+pub mod notify;
 pub mod pipeline;
 pub mod plugin_manager;
 pub mod plugin_registry;
+pub mod progress;
 pub mod registry;
+pub mod replay;
+pub mod run_context;
 pub mod stage;
 pub mod strategy;
 pub mod streaming;
+pub mod test_runner;
 pub mod traits;
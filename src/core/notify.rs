@@ -0,0 +1,369 @@
+//! Webhook notifications sent after a pipeline run.
+//!
+//! Each `[[notifications]]` entry posts the run's [`ExecutionReport`] to a
+//! URL (a Slack incoming webhook, a chat bot, an alerting endpoint) when its
+//! `when` condition matches the outcome. Without a `template` the payload is a
+//! JSON object with a Slack-compatible `text` summary plus the report fields;
+//! with one, the Handlebars template is rendered against the report. Values
+//! are JSON-escaped as they are substituted, so `"{{error}}"` stays valid JSON.
+//!
+//! Before rendering, the values of the run's `secret.` references and of the
+//! notification's own headers are replaced with [`REDACTED`] wherever they
+//! appear in the error, and credentials in URLs are masked, so an error that
+//! quotes a token doesn't forward it to a chat channel.
+
+use anyhow::Result;
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::core::config::{DagPipelineConfig, REDACTED, SECRET_PREFIX};
+use crate::utils::http::{redact_url, HttpClientPool};
+
+/// Outcome of a pipeline run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Success,
+    Failure,
+}
+
+/// Summary of a finished pipeline run, as sent to notifications
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionReport {
+    pub pipeline: String,
+    pub run_id: String,
+    pub status: RunStatus,
+    pub duration_ms: u64,
+    /// Error of a failed run, including its causes
+    pub error: Option<String>,
+    /// Records emitted by sources, when `max_records` is set
+    pub records: Option<usize>,
+}
+
+impl ExecutionReport {
+    /// Build the report for a run that took `duration` and ended with `result`
+    pub fn new(
+        pipeline: &str,
+        run_id: &str,
+        duration: Duration,
+        result: &Result<()>,
+        records: Option<usize>,
+    ) -> Self {
+        Self {
+            pipeline: pipeline.to_string(),
+            run_id: run_id.to_string(),
+            status: match result {
+                Ok(()) => RunStatus::Success,
+                Err(_) => RunStatus::Failure,
+            },
+            duration_ms: duration.as_millis() as u64,
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            records,
+        }
+    }
+
+    /// One-line summary, used as the default message text
+    pub fn summary(&self) -> String {
+        let seconds = self.duration_ms as f64 / 1000.0;
+        match &self.error {
+            None => format!(
+                "Pipeline '{}' succeeded in {:.1}s (run {})",
+                self.pipeline, seconds, self.run_id
+            ),
+            Some(error) => format!(
+                "Pipeline '{}' failed after {:.1}s (run {}): {}",
+                self.pipeline, seconds, self.run_id, error
+            ),
+        }
+    }
+}
+
+/// Which run outcomes fire a notification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyWhen {
+    OnSuccess,
+    OnFailure,
+    #[default]
+    Always,
+}
+
+impl NotifyWhen {
+    pub fn matches(self, status: RunStatus) -> bool {
+        match self {
+            Self::OnSuccess => status == RunStatus::Success,
+            Self::OnFailure => status == RunStatus::Failure,
+            Self::Always => true,
+        }
+    }
+}
+
+/// A webhook to call when a run finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// Webhook URL the payload is POSTed to
+    pub url: String,
+
+    /// Run outcomes that fire this notification
+    #[serde(default)]
+    pub when: NotifyWhen,
+
+    /// Handlebars template for the request body, rendered against the report
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Extra request headers (e.g. `Authorization`)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Request timeout in milliseconds
+    #[serde(default = "default_notify_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_notify_timeout_ms() -> u64 {
+    10_000
+}
+
+impl NotificationConfig {
+    pub fn validate(&self) -> Result<()> {
+        reqwest::Url::parse(&self.url)
+            .map_err(|e| anyhow::anyhow!("Notification URL is invalid: {}", e))?;
+        if let Some(template) = &self.template {
+            Handlebars::new()
+                .register_template_string("notification", template)
+                .map_err(|e| anyhow::anyhow!("Notification template is invalid: {}", e))?;
+        }
+        if self.timeout_ms == 0 {
+            anyhow::bail!("Notification 'timeout_ms' must be a positive integer");
+        }
+        Ok(())
+    }
+
+    /// Request body for `report`, with `secrets` masked in the error
+    pub fn render(&self, report: &ExecutionReport, secrets: &[String]) -> Result<String> {
+        let mut report = report.clone();
+        let header_values: Vec<String> = self.headers.values().cloned().collect();
+        report.error = report
+            .error
+            .map(|error| redact_text(&error, secrets.iter().chain(&header_values)));
+
+        match &self.template {
+            None => {
+                let mut payload = serde_json::to_value(&report)?;
+                payload["text"] = serde_json::Value::String(report.summary());
+                Ok(payload.to_string())
+            }
+            Some(template) => {
+                let mut context = serde_json::to_value(&report)?;
+                context["summary"] = serde_json::Value::String(report.summary());
+                let mut handlebars = Handlebars::new();
+                handlebars.register_escape_fn(json_escape);
+                Ok(handlebars.render_template(template, &context)?)
+            }
+        }
+    }
+
+    /// POST the payload for `report` to the webhook
+    pub async fn send(&self, report: &ExecutionReport, secrets: &[String]) -> Result<()> {
+        let body = self.render(report, secrets)?;
+        let client = HttpClientPool::shared().client(Duration::from_millis(self.timeout_ms))?;
+
+        let mut request = client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let url = webhook_host(&self.url);
+        let response =
+            request.body(body).send().await.map_err(|e| {
+                anyhow::anyhow!("Notification to {} failed: {}", url, e.without_url())
+            })?;
+        if !response.status().is_success() {
+            anyhow::bail!("Notification to {} failed: HTTP {}", url, response.status());
+        }
+        Ok(())
+    }
+}
+
+/// Send every notification of `config` that matches the run's outcome
+///
+/// A notification that fails is logged and does not change the run's result.
+pub async fn notify(config: &DagPipelineConfig, report: &ExecutionReport) {
+    let secrets = secret_values(config);
+    for notification in &config.notifications {
+        if !notification.when.matches(report.status) {
+            continue;
+        }
+        match notification.send(report, &secrets).await {
+            Ok(()) => tracing::info!("Sent notification to {}", webhook_host(&notification.url)),
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+}
+
+/// Scheme and host of a webhook URL, for logs
+///
+/// Webhook URLs such as Slack's carry their token in the path, so the full
+/// URL is never logged.
+fn webhook_host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .map(|url| format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default()))
+        .unwrap_or_else(|_| "webhook".to_string())
+}
+
+/// Values of the environment variables that `secret.` references point to
+fn secret_values(config: &DagPipelineConfig) -> Vec<String> {
+    config
+        .stages
+        .iter()
+        .flat_map(|stage| &stage.config)
+        .flat_map(|(key, value)| match value {
+            toml::Value::Table(table) if key == "secret" => table.values().collect(),
+            _ if key.starts_with(SECRET_PREFIX) => vec![value],
+            _ => Vec::new(),
+        })
+        .filter_map(|value| value.as_str())
+        .filter_map(|name| std::env::var(name).ok())
+        .filter(|secret| !secret.is_empty())
+        .collect()
+}
+
+/// `text` with each secret replaced by [`REDACTED`] and URL credentials masked
+fn redact_text<'a>(text: &str, secrets: impl Iterator<Item = &'a String>) -> String {
+    let mut text = text.to_string();
+    for secret in secrets.filter(|secret| !secret.is_empty()) {
+        text = text.replace(secret.as_str(), REDACTED);
+    }
+    match regex::Regex::new(r#"https?://[^\s'"]+"#) {
+        Ok(url) => url
+            .replace_all(&text, |caps: &regex::Captures| redact_url(&caps[0]))
+            .into_owned(),
+        Err(_) => text,
+    }
+}
+
+/// Escape a substituted value for use inside a JSON string literal
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Accept one request, answer 200 and hand back the request body
+    async fn mock_webhook() -> (String, tokio::sync::oneshot::Receiver<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read until the whole body announced by content-length has arrived
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|n| n.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        socket
+                            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                            .await
+                            .unwrap();
+                        let _ = tx.send(body.to_string());
+                        return;
+                    }
+                }
+                if n == 0 {
+                    return;
+                }
+            }
+        });
+        (url, rx)
+    }
+
+    fn failed_report() -> ExecutionReport {
+        let result: Result<()> =
+            Err(anyhow::anyhow!("token sk-123 rejected").context("Stage 'load' failed"));
+        ExecutionReport::new(
+            "nightly",
+            "run-1",
+            Duration::from_millis(1500),
+            &result,
+            None,
+        )
+    }
+
+    fn notification(url: &str, when: NotifyWhen) -> NotificationConfig {
+        NotificationConfig {
+            url: url.to_string(),
+            when,
+            template: None,
+            headers: HashMap::new(),
+            timeout_ms: default_notify_timeout_ms(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failure_notification_includes_error() {
+        let (url, body) = mock_webhook().await;
+        let report = failed_report();
+        assert!(!NotifyWhen::OnSuccess.matches(report.status));
+        assert!(NotifyWhen::OnFailure.matches(report.status));
+
+        notification(&url, NotifyWhen::OnFailure)
+            .send(&report, &["sk-123".to_string()])
+            .await
+            .unwrap();
+
+        let payload: serde_json::Value = serde_json::from_str(&body.await.unwrap()).unwrap();
+        assert_eq!(payload["status"], "failure");
+        assert_eq!(payload["duration_ms"], 1500);
+        assert_eq!(
+            payload["error"],
+            "Stage 'load' failed: token [REDACTED] rejected"
+        );
+        assert_eq!(
+            payload["text"],
+            "Pipeline 'nightly' failed after 1.5s (run run-1): \
+            Stage 'load' failed: token [REDACTED] rejected"
+        );
+    }
+
+    #[test]
+    fn test_template_escapes_values_as_json() {
+        let result: Result<()> = Err(anyhow::anyhow!(
+            "GET https://api.example.com/v1?api_key=abc returned \"bad\""
+        ));
+        let report = ExecutionReport::new("p", "r", Duration::ZERO, &result, None);
+        let mut config = notification("https://hooks.example.com", NotifyWhen::Always);
+        config.template = Some(r#"{"text": "{{pipeline}} {{status}}: {{error}}"}"#.to_string());
+
+        let payload: serde_json::Value =
+            serde_json::from_str(&config.render(&report, &[]).unwrap()).unwrap();
+        assert_eq!(
+            payload["text"],
+            "p failure: GET https://api.example.com/v1?api_key=%5BREDACTED%5D returned \"bad\""
+        );
+
+        config.template = Some("{{#if error}}".to_string());
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,14 +1,18 @@
 use anyhow::Result;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use tracing::{error, info};
 
 use crate::core::config::DagPipelineConfig;
-use crate::core::dag_builder::{DagPipelineBuilder, ExecutorVariant};
+use crate::core::dag_builder::{DagPipelineBuilder, ExecutorVariant, StageSubstitute};
 use crate::core::error::ConveyorError;
+use crate::core::notify::{self, ExecutionReport};
+use crate::core::progress::ProgressReporter;
 use crate::core::registry::ModuleRegistry;
+use crate::core::run_context::RunContext;
+use crate::core::stage::RecordLimit;
 use crate::plugin_loader::PluginLoader;
 use crate::wasm_plugin_loader::WasmPluginLoader;
 
@@ -22,19 +26,49 @@ pub struct DagPipeline {
     plugin_loader: Option<Arc<PluginLoader>>,
     #[allow(dead_code)]
     wasm_plugin_loader: Option<Arc<WasmPluginLoader>>,
+    record_limit: Option<Arc<RecordLimit>>,
+    run: Arc<RunContext>,
 }
 
 impl DagPipeline {
-    /// Create a DAG pipeline from a file
+    /// Create a DAG pipeline from a file or an `http(s)://` URL
     pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = tokio::fs::read_to_string(path).await?;
-        let dag_config = DagPipelineConfig::from_str(&content)?;
+        let dag_config = DagPipelineConfig::from_file(path).await?;
         info!("Loading DAG-based pipeline configuration");
         Self::new(dag_config).await
     }
 
     /// Create a DAG pipeline from configuration
     pub async fn new(config: DagPipelineConfig) -> Result<Self> {
+        Self::create(config, None, None).await
+    }
+
+    /// Create a DAG pipeline that reports per-stage progress (`--progress`)
+    ///
+    /// Stages are only wrapped when the reporter is enabled, so a disabled
+    /// reporter (no TTY, JSON logs) leaves the pipeline untouched.
+    pub async fn with_progress(
+        config: DagPipelineConfig,
+        reporter: Arc<ProgressReporter>,
+    ) -> Result<Self> {
+        let progress = reporter.is_enabled().then_some(reporter);
+        Self::create(config, progress, None).await
+    }
+
+    /// Create a DAG pipeline whose stages may be swapped for others by
+    /// `substitute`, such as sample sources and capturing sinks in tests
+    pub async fn with_substitute(
+        config: DagPipelineConfig,
+        substitute: StageSubstitute,
+    ) -> Result<Self> {
+        Self::create(config, None, Some(substitute)).await
+    }
+
+    async fn create(
+        config: DagPipelineConfig,
+        progress: Option<Arc<ProgressReporter>>,
+        substitute: Option<StageSubstitute>,
+    ) -> Result<Self> {
         let registry = Arc::new(ModuleRegistry::with_defaults().await?);
 
         // Load FFI plugins specified in config
@@ -64,9 +98,24 @@ impl DagPipeline {
         // Build DAG executor with plugin loaders
         let plugin_loader_arc = Arc::new(plugin_loader);
         let wasm_plugin_loader_arc = Arc::new(wasm_plugin_loader);
-        let builder = DagPipelineBuilder::new(registry.clone())
+        let run = Arc::new(RunContext::for_config(&config));
+        let mut builder = DagPipelineBuilder::new(registry.clone())
             .with_plugin_loader(plugin_loader_arc.clone())
-            .with_wasm_plugin_loader(wasm_plugin_loader_arc.clone());
+            .with_wasm_plugin_loader(wasm_plugin_loader_arc.clone())
+            .with_run_context(Arc::clone(&run));
+        let record_limit = config.global.max_records.map(|max| {
+            info!("Limiting sources to {} record(s) in total", max);
+            Arc::new(RecordLimit::new(max))
+        });
+        if let Some(limit) = &record_limit {
+            builder = builder.with_record_limit(Arc::clone(limit));
+        }
+        if let Some(reporter) = progress {
+            builder = builder.with_progress(reporter);
+        }
+        if let Some(substitute) = substitute {
+            builder = builder.with_substitute(substitute);
+        }
         let executor = builder.build(&config)?;
 
         Ok(Self {
@@ -75,6 +124,8 @@ impl DagPipeline {
             executor,
             plugin_loader: Some(plugin_loader_arc),
             wasm_plugin_loader: Some(wasm_plugin_loader_arc),
+            record_limit,
+            run,
         })
     }
 
@@ -83,11 +134,39 @@ impl DagPipeline {
         self.executor.validate()
     }
 
-    /// Execute the DAG pipeline
+    /// Unique ID of this run, as emitted by `run_info.read`
+    pub fn run_id(&self) -> &str {
+        &self.run.run_id
+    }
+
+    /// Records emitted by sources so far, when `max_records` is set
+    pub fn records_emitted(&self) -> Option<usize> {
+        self.record_limit.as_ref().map(|limit| limit.emitted())
+    }
+
+    /// Execute the DAG pipeline, then send the configured notifications
     pub async fn execute(&mut self) -> Result<()> {
+        let started = Instant::now();
+        let result = self.run().await;
+
+        if !self.config.notifications.is_empty() {
+            let report = ExecutionReport::new(
+                &self.config.pipeline.name,
+                &self.run.run_id,
+                started.elapsed(),
+                &result,
+                self.records_emitted(),
+            );
+            notify::notify(&self.config, &report).await;
+        }
+
+        result
+    }
+
+    async fn run(&mut self) -> Result<()> {
         info!(
-            "Starting DAG pipeline: {} (executor: {:?})",
-            self.config.pipeline.name, self.config.global.executor
+            "Starting DAG pipeline: {} (executor: {:?}, run: {})",
+            self.config.pipeline.name, self.config.global.executor, self.run.run_id
         );
 
         let timeout_duration = Duration::from_secs(self.config.global.timeout_seconds);
@@ -101,6 +180,21 @@ impl DagPipeline {
                     "DAG pipeline '{}' completed successfully",
                     self.config.pipeline.name
                 );
+                if let Some(limit) = &self.record_limit {
+                    if limit.is_reached() {
+                        info!(
+                            "Processed {} record(s): stopped at max_records = {}",
+                            limit.emitted(),
+                            limit.max()
+                        );
+                    } else {
+                        info!(
+                            "Processed {} record(s) (max_records = {} not reached)",
+                            limit.emitted(),
+                            limit.max()
+                        );
+                    }
+                }
                 Ok(())
             }
             Ok(Err(e)) => {
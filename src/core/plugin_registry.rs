@@ -130,6 +130,8 @@ mod tests {
         let platform = detect_platform();
         #[cfg(target_os = "macos")]
         assert!(platform.starts_with("darwin-"));
+        #[cfg(not(target_os = "macos"))]
+        assert_eq!(platform, "unknown");
     }
 
     #[test]
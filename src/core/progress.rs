@@ -0,0 +1,296 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::core::metadata::StageMetadata;
+use crate::core::stage::{Stage, StageRef};
+use crate::core::traits::DataFormat;
+
+/// Whether progress bars should be drawn (`conveyor run --progress`)
+///
+/// Bars are only drawn when requested, when stderr is a terminal, and when logs
+/// are plain text, since redrawing would corrupt JSON log lines.
+pub fn should_render(requested: bool, json_logs: bool, is_terminal: bool) -> bool {
+    requested && !json_logs && is_terminal
+}
+
+/// Owns the progress bars of a pipeline run, one per stage
+///
+/// A disabled reporter draws to a hidden target, so stages wrapped with it
+/// still work but nothing reaches the terminal.
+pub struct ProgressReporter {
+    multi: MultiProgress,
+    enabled: bool,
+}
+
+impl ProgressReporter {
+    /// Create a reporter for the current process, checking whether stderr is a TTY
+    pub fn new(requested: bool, json_logs: bool) -> Self {
+        Self::for_output(requested, json_logs, std::io::stderr().is_terminal())
+    }
+
+    pub fn for_output(requested: bool, json_logs: bool, is_terminal: bool) -> Self {
+        let enabled = should_render(requested, json_logs, is_terminal);
+        let target = if enabled {
+            ProgressDrawTarget::stderr()
+        } else {
+            ProgressDrawTarget::hidden()
+        };
+
+        Self {
+            multi: MultiProgress::with_draw_target(target),
+            enabled,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Log writer that hides the bars while a line is printed
+    pub fn log_writer(&self) -> ProgressLogWriter {
+        ProgressLogWriter {
+            multi: self.multi.clone(),
+        }
+    }
+
+    /// Add a spinner for the stage `id`, counting records as they flow
+    fn stage_bar(&self, id: &str, template: &str) -> ProgressBar {
+        let bar = self.multi.add(ProgressBar::new_spinner());
+        bar.set_style(style(template));
+        bar.set_prefix(id.to_string());
+        if self.enabled {
+            bar.enable_steady_tick(Duration::from_millis(100));
+        }
+        bar
+    }
+}
+
+const SPINNER_TEMPLATE: &str = "{spinner:.green} {prefix:.bold} {human_pos} records {msg}";
+const SINK_TEMPLATE: &str = "{spinner:.green} {prefix:.bold} {msg}";
+const BAR_TEMPLATE: &str =
+    "  {prefix:.bold} [{bar:30.cyan/blue}] {human_pos}/{human_len} records {msg}";
+
+fn style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template)
+        .unwrap_or_else(|_| ProgressStyle::default_spinner())
+        .progress_chars("=> ")
+}
+
+/// Size of the file a source reads from, if its `path` points to one
+fn source_size(config: &HashMap<String, toml::Value>) -> Option<String> {
+    let path = config.get("path")?.as_str()?;
+    let metadata = std::fs::metadata(path).ok()?;
+    metadata
+        .is_file()
+        .then(|| format!("({})", HumanBytes(metadata.len())))
+}
+
+/// Writer for `tracing_subscriber` that keeps log lines from tearing the bars
+#[derive(Clone)]
+pub struct ProgressLogWriter {
+    multi: MultiProgress,
+}
+
+impl Write for ProgressLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.multi.suspend(|| std::io::stdout().write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for ProgressLogWriter {
+    type Writer = ProgressLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Wraps a stage so its progress is shown while the pipeline runs
+///
+/// Streams advance the bar batch by batch. Batch outputs have a known row
+/// count, so their bar is filled in one step when the stage completes.
+pub struct ProgressStage {
+    inner: StageRef,
+    id: String,
+    reporter: Arc<ProgressReporter>,
+}
+
+impl ProgressStage {
+    pub fn new(inner: StageRef, id: String, reporter: Arc<ProgressReporter>) -> Self {
+        Self {
+            inner,
+            id,
+            reporter,
+        }
+    }
+}
+
+#[async_trait]
+impl Stage for ProgressStage {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        self.inner.metadata()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let produces_output = self.inner.produces_output();
+        let template = if produces_output {
+            SPINNER_TEMPLATE
+        } else {
+            SINK_TEMPLATE
+        };
+        let bar = self.reporter.stage_bar(&self.id, template);
+        let size = if inputs.is_empty() {
+            source_size(config)
+        } else {
+            None
+        };
+        if let Some(size) = &size {
+            bar.set_message(size.clone());
+        }
+
+        let output = match self.inner.execute(inputs, config).await {
+            Ok(output) => output,
+            Err(e) => {
+                bar.abandon_with_message("failed");
+                return Err(e);
+            }
+        };
+
+        if !produces_output {
+            bar.finish_with_message("done");
+            return Ok(output);
+        }
+
+        let finish_batch = |rows: usize| {
+            bar.set_style(style(BAR_TEMPLATE));
+            bar.set_length(rows as u64);
+            bar.set_position(rows as u64);
+            bar.finish_with_message(size.clone().unwrap_or_default());
+        };
+
+        match output {
+            DataFormat::DataFrame(df) => {
+                finish_batch(df.height());
+                Ok(DataFormat::DataFrame(df))
+            }
+            DataFormat::RecordBatch(records) => {
+                finish_batch(records.len());
+                Ok(DataFormat::RecordBatch(records))
+            }
+            DataFormat::Raw(bytes) => {
+                bar.finish_with_message(format!("({} raw)", HumanBytes(bytes.len() as u64)));
+                Ok(DataFormat::Raw(bytes))
+            }
+            DataFormat::Stream(mut upstream) => {
+                let stream = async_stream::stream! {
+                    while let Some(item) = upstream.next().await {
+                        match &item {
+                            Ok(records) => bar.inc(records.len() as u64),
+                            Err(_) => bar.set_message("error in stream"),
+                        }
+                        yield item;
+                    }
+                    bar.finish();
+                };
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        self.inner.validate_config(config).await
+    }
+
+    fn produces_output(&self) -> bool {
+        self.inner.produces_output()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::metadata::StageCategory;
+    use serde_json::json;
+
+    struct CountingSource;
+
+    #[async_trait]
+    impl Stage for CountingSource {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn metadata(&self) -> StageMetadata {
+            StageMetadata::builder("counting", StageCategory::Source).build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            let batches = (0..3).map(|i| {
+                let mut record = HashMap::new();
+                record.insert("n".to_string(), json!(i));
+                Ok(vec![record.clone(), record])
+            });
+            Ok(DataFormat::Stream(Box::pin(tokio_stream::iter(batches))))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_progress_suppressed_without_tty() {
+        assert!(should_render(true, false, true));
+        assert!(!should_render(true, false, false));
+        assert!(!should_render(true, true, true));
+        assert!(!should_render(false, false, true));
+
+        let reporter = ProgressReporter::for_output(true, false, false);
+        assert!(!reporter.is_enabled());
+        assert!(reporter.stage_bar("source", SPINNER_TEMPLATE).is_hidden());
+    }
+
+    #[tokio::test]
+    async fn test_progress_stage_passes_stream_through() {
+        let reporter = Arc::new(ProgressReporter::for_output(true, false, false));
+        let stage = ProgressStage::new(
+            Arc::new(CountingSource),
+            "source".to_string(),
+            Arc::clone(&reporter),
+        );
+
+        let mut stream = match stage.execute(HashMap::new(), &HashMap::new()).await {
+            Ok(DataFormat::Stream(stream)) => stream,
+            _ => panic!("expected a stream"),
+        };
+        let mut records = 0;
+        while let Some(batch) = stream.next().await {
+            records += batch.unwrap().len();
+        }
+        assert_eq!(records, 6);
+        assert_eq!(stage.name(), "counting");
+    }
+}
@@ -0,0 +1,341 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::core::config::{DagPipelineConfig, StageConfig};
+use crate::core::dag_builder::with_upstream;
+use crate::core::error::ConveyorError;
+use crate::core::pipeline::DagPipeline;
+
+/// Id of the source stage that feeds the dead-lettered records
+pub const REPLAY_STAGE_ID: &str = "dead_letter_replay";
+
+/// Records of a dead-letter file, in the layout they were stored in
+#[derive(Debug)]
+struct DeadLetters {
+    records: Vec<Value>,
+    jsonl: bool,
+}
+
+impl DeadLetters {
+    /// Read a JSON array or JSON Lines file
+    ///
+    /// Entries written with error metadata (`{"record": {...}, "error": ...}`)
+    /// are unwrapped to their record; plain objects are replayed as they are.
+    fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read dead-letter file {:?}", path))?;
+
+        let trimmed = content.trim_start();
+        let (entries, jsonl) = if trimmed.is_empty() {
+            (Vec::new(), is_jsonl_path(path))
+        } else if trimmed.starts_with('[') {
+            let entries: Vec<Value> = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid JSON array in dead-letter file {:?}", path))?;
+            (entries, false)
+        } else {
+            let entries = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    serde_json::from_str(line).with_context(|| {
+                        format!(
+                            "Invalid JSON on line {} of dead-letter file {:?}",
+                            i + 1,
+                            path
+                        )
+                    })
+                })
+                .collect::<Result<Vec<Value>>>()?;
+            (entries, true)
+        };
+
+        let records = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| match entry {
+                Value::Object(mut object)
+                    if object.contains_key("error") && object.contains_key("record") =>
+                {
+                    Ok(object.remove("record").unwrap_or(Value::Null))
+                }
+                Value::Object(_) => Ok(entry),
+                _ => Err(anyhow::anyhow!(
+                    "Dead-letter entry {} in {:?} is not a JSON object",
+                    i + 1,
+                    path
+                )),
+            })
+            .collect::<Result<Vec<Value>>>()?;
+
+        Ok(Self { records, jsonl })
+    }
+
+    /// Empty the file, keeping its layout
+    fn clear(&self, path: &Path) -> Result<()> {
+        let content = if self.jsonl { "" } else { "[]\n" };
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to clear dead-letter file {:?}", path))
+    }
+}
+
+fn is_jsonl_path(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("jsonl" | "ndjson")
+    )
+}
+
+/// Ids of `id` and of every stage that (transitively) reads from it
+fn with_downstream<'a>(config: &'a DagPipelineConfig, id: &'a str) -> Vec<&'a str> {
+    let mut consumers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for stage in &config.stages {
+        for input in &stage.inputs {
+            consumers
+                .entry(input.as_str())
+                .or_default()
+                .push(stage.id.as_str());
+        }
+    }
+
+    let mut selected = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending = vec![id];
+    while let Some(id) = pending.pop() {
+        if seen.insert(id) {
+            selected.push(id);
+            if let Some(next) = consumers.get(id) {
+                pending.extend(next.iter().copied());
+            }
+        }
+    }
+    selected
+}
+
+/// Config that runs `config` from stage `into` onward, reading `records_path`
+///
+/// Stage `into` reads the JSON Lines file at `records_path` instead of its own
+/// inputs. Only `into`, the stages downstream of it and whatever else those
+/// stages read from are kept, so sources feeding `into` are not run again.
+pub fn partial_config(
+    config: &DagPipelineConfig,
+    into: &str,
+    records_path: &Path,
+) -> Result<DagPipelineConfig> {
+    if !config.stages.iter().any(|stage| stage.id == into) {
+        return Err(ConveyorError::PipelineError(format!(
+            "Cannot replay into unknown stage '{}'",
+            into
+        ))
+        .into());
+    }
+    if config
+        .stages
+        .iter()
+        .any(|stage| stage.id == REPLAY_STAGE_ID)
+    {
+        return Err(ConveyorError::PipelineError(format!(
+            "Stage id '{}' is reserved for replay",
+            REPLAY_STAGE_ID
+        ))
+        .into());
+    }
+
+    let mut partial = config.clone();
+    for stage in &mut partial.stages {
+        if stage.id == into {
+            stage.inputs = vec![REPLAY_STAGE_ID.to_string()];
+            stage.conditions.clear();
+        }
+    }
+    partial.stages.insert(
+        0,
+        StageConfig {
+            id: REPLAY_STAGE_ID.to_string(),
+            function: "json.read".to_string(),
+            inputs: Vec::new(),
+            config: HashMap::from([
+                (
+                    "path".to_string(),
+                    toml::Value::String(records_path.to_string_lossy().into_owned()),
+                ),
+                (
+                    "format".to_string(),
+                    toml::Value::String("jsonl".to_string()),
+                ),
+            ]),
+            empty_output: Default::default(),
+            priority: 0,
+            max_memory_mb: None,
+            concurrent: false,
+            ordered: false,
+            tags: Vec::new(),
+            conditions: HashMap::new(),
+        },
+    );
+
+    let selected: HashSet<String> = {
+        let downstream = with_downstream(&partial, into);
+        with_upstream(&partial, &downstream)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    };
+    partial.stages.retain(|stage| selected.contains(&stage.id));
+    // The replayed stages are chosen explicitly; a tag filter would drop them
+    partial.global.tags.clear();
+
+    partial.validate()?;
+    Ok(partial)
+}
+
+/// Feed the records of a dead-letter file into stage `into` and run the rest
+/// of the pipeline
+///
+/// Returns the number of replayed records. The file is emptied only when the
+/// whole run succeeds; on failure it is left untouched so the replay can be
+/// retried once the cause is fixed.
+pub async fn replay(config: &DagPipelineConfig, into: &str, dead_letter: &Path) -> Result<usize> {
+    let dead_letters = DeadLetters::read(dead_letter)?;
+    if dead_letters.records.is_empty() {
+        tracing::info!("No dead-lettered records in {:?}", dead_letter);
+        return Ok(0);
+    }
+
+    let staged = staged_records_path();
+    let mut lines = String::new();
+    for record in &dead_letters.records {
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+    std::fs::write(&staged, lines)
+        .with_context(|| format!("Failed to stage replay records at {:?}", staged))?;
+
+    let result = async {
+        let partial = partial_config(config, into, &staged)?;
+        DagPipeline::new(partial).await?.execute().await
+    }
+    .await;
+    let _ = std::fs::remove_file(&staged);
+    result?;
+
+    dead_letters.clear(dead_letter)?;
+    tracing::info!(
+        "Replayed {} record(s) from {:?} into stage '{}'",
+        dead_letters.records.len(),
+        dead_letter,
+        into
+    );
+    Ok(dead_letters.records.len())
+}
+
+fn staged_records_path() -> PathBuf {
+    std::env::temp_dir().join(format!("conveyor-replay-{}.jsonl", uuid::Uuid::new_v4()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pipeline(dir: &Path) -> DagPipelineConfig {
+        DagPipelineConfig::from_str(&format!(
+            r#"
+[pipeline]
+name = "orders"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "json.read"
+[stages.config]
+path = "{dir}/orders.json"
+
+[[stages]]
+id = "totals"
+function = "map.apply"
+inputs = ["load"]
+[stages.config]
+expression = "price * 2"
+output_column = "total"
+
+[[stages]]
+id = "save"
+function = "json.write"
+inputs = ["totals"]
+[stages.config]
+path = "{dir}/totals.json"
+"#,
+            dir = dir.display()
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replay_into_transform() {
+        let dir = TempDir::new().unwrap();
+        let config = pipeline(dir.path());
+
+        // Corrected records; the original source file does not even exist,
+        // so the run only succeeds if the upstream stage is skipped
+        let dead_letter = dir.path().join("failed.json");
+        std::fs::write(
+            &dead_letter,
+            r#"[
+  {"record": {"id": 7, "price": 2.5}, "error": "price was a string", "stage_id": "totals"},
+  {"id": 8, "price": 1.5}
+]"#,
+        )
+        .unwrap();
+
+        let replayed = replay(&config, "totals", &dead_letter).await.unwrap();
+        assert_eq!(replayed, 2);
+
+        let output: Vec<Value> =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("totals.json")).unwrap())
+                .unwrap();
+        let totals: Vec<(i64, f64)> = output
+            .iter()
+            .map(|row| (row["id"].as_i64().unwrap(), row["total"].as_f64().unwrap()))
+            .collect();
+        assert_eq!(totals, vec![(7, 5.0), (8, 3.0)]);
+
+        assert_eq!(std::fs::read_to_string(&dead_letter).unwrap(), "[]\n");
+        assert_eq!(replay(&config, "totals", &dead_letter).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_replay_keeps_dead_letters() {
+        let dir = TempDir::new().unwrap();
+        let config = pipeline(dir.path());
+        let dead_letter = dir.path().join("failed.jsonl");
+        let content = "{\"id\": 7, \"cost\": 2.5}\n";
+        std::fs::write(&dead_letter, content).unwrap();
+
+        // 'price' is still missing, so the transform fails again
+        assert!(replay(&config, "totals", &dead_letter).await.is_err());
+        assert_eq!(std::fs::read_to_string(&dead_letter).unwrap(), content);
+
+        let err = replay(&config, "nope", &dead_letter)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Pipeline execution error: Cannot replay into unknown stage 'nope'"
+        );
+    }
+
+    #[test]
+    fn test_partial_config_selects_downstream() {
+        let dir = TempDir::new().unwrap();
+        let config = pipeline(dir.path());
+        let partial = partial_config(&config, "totals", Path::new("records.jsonl")).unwrap();
+
+        let ids: Vec<&str> = partial.stages.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec![REPLAY_STAGE_ID, "totals", "save"]);
+        assert_eq!(partial.stages[1].inputs, vec![REPLAY_STAGE_ID.to_string()]);
+    }
+}
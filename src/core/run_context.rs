@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+use crate::core::config::DagPipelineConfig;
+
+/// Identity of one pipeline run, shared by every `run_info.read` stage of it
+#[derive(Debug, Clone)]
+pub struct RunContext {
+    pub run_id: String,
+    pub pipeline: Option<String>,
+    pub pipeline_version: Option<String>,
+    /// Short SHA-256 of the resolved pipeline configuration
+    pub config_hash: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl RunContext {
+    /// A run that is not tied to a pipeline configuration
+    pub fn new() -> Self {
+        Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            pipeline: None,
+            pipeline_version: None,
+            config_hash: None,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// A run of `config`, starting now
+    pub fn for_config(config: &DagPipelineConfig) -> Self {
+        Self {
+            pipeline: Some(config.pipeline.name.clone()),
+            pipeline_version: Some(config.pipeline.version.clone()),
+            config_hash: config_hash(config),
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// First 12 hex digits of the SHA-256 of the config with object keys sorted,
+/// so the hash only changes when the configuration does
+fn config_hash(config: &DagPipelineConfig) -> Option<String> {
+    let value = serde_json::to_value(config).ok()?;
+    let digest = Sha256::digest(serde_json::to_string(&canonical(value)).ok()?.as_bytes());
+    Some(
+        digest
+            .iter()
+            .take(6)
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    )
+}
+
+fn canonical(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<(String, JsonValue)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            JsonValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonical(v)))
+                    .collect(),
+            )
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.into_iter().map(canonical).collect()),
+        other => other,
+    }
+}
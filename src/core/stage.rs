@@ -2,15 +2,18 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio_stream::{Stream, StreamExt};
 
-use crate::core::metadata::StageMetadata;
+use crate::core::metadata::{ConfigParameter, ParameterType, ParameterValidation, StageMetadata};
 use crate::core::traits::DataFormat;
+use crate::utils::retry::RetryPolicy;
 use crate::wasm_plugin_loader::{
     DataFormat as WasmDataFormat, ExecutionContext as WasmExecutionContext, WasmPluginLoader,
 };
-use conveyor_plugin_api::traits::FfiStage_TO;
+use conveyor_plugin_api::sabi_trait::prelude::TD_Opaque;
+use conveyor_plugin_api::traits::{FfiBatchSink, FfiBatchSink_TO, FfiStage_TO};
 use conveyor_plugin_api::{FfiDataFormat, FfiExecutionContext, RBox, RHashMap, RString};
 
 /// Unified Stage trait - represents any processing unit in the pipeline
@@ -145,7 +148,20 @@ pub struct FfiPluginStageAdapter {
     stage_name: String,
     description: String,
     stage_type: conveyor_plugin_api::traits::StageType,
-    stage_instance: FfiStage_TO<'static, RBox<()>>,
+    stage_instance: Arc<FfiStage_TO<'static, RBox<()>>>,
+}
+
+/// Forwards batches from `FfiStage::execute_stream` into a bounded channel
+struct ChannelBatchSink {
+    tx: tokio::sync::mpsc::Sender<Result<crate::core::traits::RecordBatch>>,
+}
+
+impl FfiBatchSink for ChannelBatchSink {
+    fn emit(&mut self, batch: FfiDataFormat) -> bool {
+        let records = ffi_to_dataformat(&batch).and_then(|data| data.as_record_batch());
+        // Blocks while the channel is full, giving the plugin backpressure
+        self.tx.blocking_send(records).is_ok()
+    }
 }
 
 impl FfiPluginStageAdapter {
@@ -161,9 +177,30 @@ impl FfiPluginStageAdapter {
             stage_name,
             description,
             stage_type,
-            stage_instance,
+            stage_instance: Arc::new(stage_instance),
         }
     }
+
+    /// Run a source via `execute_stream` on a blocking thread, returning its batches as a stream
+    fn execute_streaming(&self, context: FfiExecutionContext) -> DataFormat {
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let stage = Arc::clone(&self.stage_instance);
+        let stage_name = self.stage_name.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let error_tx = tx.clone();
+            let sink = FfiBatchSink_TO::from_value(ChannelBatchSink { tx }, TD_Opaque);
+            if let conveyor_plugin_api::RErr(e) = stage.execute_stream(context, sink) {
+                let _ = error_tx.blocking_send(Err(anyhow::anyhow!(
+                    "FFI plugin '{}' error: {:?}",
+                    stage_name,
+                    e
+                )));
+            }
+        });
+
+        DataFormat::Stream(Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx)))
+    }
 }
 
 #[async_trait]
@@ -201,13 +238,26 @@ impl Stage for FfiPluginStageAdapter {
         }
 
         // Convert config to FFI format
-        tracing::debug!("Original config before conversion: {:?}", config);
+        tracing::debug!(
+            "Original config before conversion: {:?}",
+            crate::core::config::redact_secrets(config)
+        );
         let ffi_config = config_to_ffi(config)?;
         tracing::debug!("FFI config after conversion: {:?}", ffi_config);
 
         // Create execution context
         let context = FfiExecutionContext::new(ffi_inputs, ffi_config);
 
+        // Sources can emit batches incrementally instead of one collected result
+        let streaming = self.stage_type == conveyor_plugin_api::traits::StageType::Source
+            && config
+                .get("stream")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+        if streaming {
+            return Ok(self.execute_streaming(context));
+        }
+
         // Execute FFI stage (synchronous call)
         let result = self.stage_instance.execute(context);
 
@@ -310,6 +360,7 @@ impl Stage for WasmPluginStageAdapter {
 
         // Convert config to WASM format
         let wasm_config = config_to_wasm(config)?;
+        let retry_policy = RetryPolicy::from_config(config)?;
 
         // Create execution context
         let context = WasmExecutionContext {
@@ -317,10 +368,10 @@ impl Stage for WasmPluginStageAdapter {
             config: wasm_config,
         };
 
-        // Execute WASM stage (async)
+        // Execute WASM stage (async), retrying transient guest failures
         let wasm_result = self
             .loader
-            .execute(&self.plugin_name, &self.stage_name, context)
+            .execute_with_retry(&self.plugin_name, &self.stage_name, context, &retry_policy)
             .await?;
 
         // Convert result back
@@ -329,6 +380,7 @@ impl Stage for WasmPluginStageAdapter {
     }
 
     async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        RetryPolicy::from_config(config)?;
         let wasm_config = config_to_wasm(config)?;
 
         self.loader
@@ -337,6 +389,208 @@ impl Stage for WasmPluginStageAdapter {
     }
 }
 
+// ============================================================================
+// Missing Column Policy
+// ============================================================================
+
+/// How a transform reacts when a column it references is absent (`on_missing_column`)
+///
+/// Heterogeneous inputs do not always carry every optional column, so stages
+/// that reference columns by name can be told to skip their operation or to
+/// treat the column as all-null instead of aborting the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingColumnPolicy {
+    /// Fail the stage (the default)
+    #[default]
+    Error,
+    /// Leave out the part of the operation that needs the column
+    Skip,
+    /// Behave as if the column existed and held only nulls
+    Null,
+}
+
+impl MissingColumnPolicy {
+    pub const CONFIG_KEY: &'static str = "on_missing_column";
+
+    pub fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        match config.get(Self::CONFIG_KEY) {
+            None => Ok(Self::Error),
+            Some(toml::Value::String(s)) => match s.as_str() {
+                "error" => Ok(Self::Error),
+                "skip" => Ok(Self::Skip),
+                "null" => Ok(Self::Null),
+                other => anyhow::bail!(
+                    "Invalid '{}' value '{}'. Must be one of: error, skip, null",
+                    Self::CONFIG_KEY,
+                    other
+                ),
+            },
+            Some(_) => anyhow::bail!("'{}' must be a string", Self::CONFIG_KEY),
+        }
+    }
+
+    /// Metadata entry for the option, described in terms of the stage using it
+    pub fn parameter(description: &str) -> ConfigParameter {
+        ConfigParameter::optional(
+            Self::CONFIG_KEY,
+            ParameterType::String,
+            "error",
+            description,
+        )
+        .with_validation(ParameterValidation::allowed_values([
+            "error", "skip", "null",
+        ]))
+    }
+
+    /// Referenced columns that `df` lacks
+    ///
+    /// Under the `error` policy the first missing column fails with
+    /// "<stage>: column '<name>' not found" instead.
+    pub fn missing<'a>(
+        self,
+        stage: &str,
+        df: &polars::prelude::DataFrame,
+        columns: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Vec<&'a str>> {
+        let missing: Vec<&str> = columns
+            .into_iter()
+            .filter(|name| df.column(name).is_err())
+            .collect();
+        if let (Self::Error, Some(name)) = (self, missing.first()) {
+            anyhow::bail!("{}: column '{}' not found", stage, name);
+        }
+        Ok(missing)
+    }
+}
+
+// ============================================================================
+// Record Limit Adapter
+// ============================================================================
+
+/// Run-wide budget of records that sources may emit (`--max-records`)
+///
+/// One budget is shared by every source of a pipeline, so the cap applies to
+/// the run as a whole rather than per stage.
+#[derive(Debug)]
+pub struct RecordLimit {
+    max: usize,
+    emitted: AtomicUsize,
+}
+
+impl RecordLimit {
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            emitted: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Records emitted by sources so far
+    pub fn emitted(&self) -> usize {
+        self.emitted.load(Ordering::SeqCst)
+    }
+
+    pub fn is_reached(&self) -> bool {
+        self.emitted() >= self.max
+    }
+
+    /// Reserve up to `wanted` records, returning how many may be emitted
+    fn take(&self, wanted: usize) -> usize {
+        let mut granted = 0;
+        let _ = self
+            .emitted
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |emitted| {
+                granted = wanted.min(self.max.saturating_sub(emitted));
+                Some(emitted + granted)
+            });
+        granted
+    }
+}
+
+/// Wraps a source so its output counts against a shared [`RecordLimit`]
+///
+/// Batch outputs are truncated to the remaining budget, streams end as soon as
+/// the budget is used up, and sources that start after the limit is reached
+/// are not executed at all.
+pub struct RecordLimitedStage {
+    inner: StageRef,
+    limit: Arc<RecordLimit>,
+}
+
+impl RecordLimitedStage {
+    pub fn new(inner: StageRef, limit: Arc<RecordLimit>) -> Self {
+        Self { inner, limit }
+    }
+}
+
+#[async_trait]
+impl Stage for RecordLimitedStage {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        self.inner.metadata()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        if self.limit.is_reached() {
+            tracing::info!(
+                "Record limit of {} reached, skipping source '{}'",
+                self.limit.max(),
+                self.inner.name()
+            );
+            return Ok(DataFormat::RecordBatch(Vec::new()));
+        }
+
+        match self.inner.execute(inputs, config).await? {
+            DataFormat::DataFrame(df) => {
+                let allowed = self.limit.take(df.height());
+                Ok(DataFormat::DataFrame(df.slice(0, allowed)))
+            }
+            DataFormat::RecordBatch(mut records) => {
+                records.truncate(self.limit.take(records.len()));
+                Ok(DataFormat::RecordBatch(records))
+            }
+            // Raw bytes have no record boundaries to count
+            DataFormat::Raw(bytes) => Ok(DataFormat::Raw(bytes)),
+            DataFormat::Stream(mut upstream) => {
+                let limit = Arc::clone(&self.limit);
+                let stream = async_stream::stream! {
+                    // Dropping the upstream stops the source once the budget is spent
+                    while !limit.is_reached() {
+                        match upstream.next().await {
+                            Some(Ok(mut records)) => {
+                                records.truncate(limit.take(records.len()));
+                                yield Ok(records);
+                            }
+                            Some(Err(e)) => yield Err(e),
+                            None => break,
+                        }
+                    }
+                };
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        self.inner.validate_config(config).await
+    }
+
+    fn produces_output(&self) -> bool {
+        self.inner.produces_output()
+    }
+}
+
 // ============================================================================
 // Conversion Functions
 // ============================================================================
@@ -449,7 +703,7 @@ fn config_to_ffi(config: &HashMap<String, toml::Value>) -> Result<RHashMap<RStri
 }
 
 /// Convert Polars AnyValue to serde_json::Value
-fn anyvalue_to_json(value: &polars::prelude::AnyValue) -> serde_json::Value {
+pub(crate) fn anyvalue_to_json(value: &polars::prelude::AnyValue) -> serde_json::Value {
     use polars::prelude::AnyValue;
     use serde_json::Value as JsonValue;
 
@@ -540,6 +794,24 @@ fn wasm_to_dataformat(wasm_data: &WasmDataFormat) -> Result<DataFormat> {
 fn config_to_wasm(config: &HashMap<String, toml::Value>) -> Result<Vec<(String, String)>> {
     let mut wasm_config = Vec::new();
     for (key, value) in config {
+        // Dotted `secret.<name>` keys parse as a table; pass them on flat so
+        // the loader can resolve them
+        if let (true, toml::Value::Table(secrets)) = (key == "secret", value) {
+            for (name, source) in secrets {
+                let source = source.as_str().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Secret '{}' must name an environment variable as a string",
+                        name
+                    )
+                })?;
+                wasm_config.push((
+                    format!("{}{}", crate::core::config::SECRET_PREFIX, name),
+                    source.to_string(),
+                ));
+            }
+            continue;
+        }
+
         let value_str = match value {
             toml::Value::String(s) => s.clone(),
             toml::Value::Integer(i) => i.to_string(),
@@ -598,4 +870,256 @@ mod tests {
         let result = stage.execute(inputs, &config).await;
         assert!(result.is_ok());
     }
+
+    /// FFI source that emits one single-record batch per "message"
+    struct CountingFfiSource {
+        emitted: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl conveyor_plugin_api::FfiStage for CountingFfiSource {
+        fn name(&self) -> conveyor_plugin_api::RStr<'_> {
+            "counting".into()
+        }
+
+        fn stage_type(&self) -> conveyor_plugin_api::StageType {
+            conveyor_plugin_api::StageType::Source
+        }
+
+        fn execute(
+            &self,
+            _context: FfiExecutionContext,
+        ) -> conveyor_plugin_api::RResult<FfiDataFormat, conveyor_plugin_api::RBoxError> {
+            conveyor_plugin_api::ROk(FfiDataFormat::from_raw(vec![]))
+        }
+
+        fn validate_config(
+            &self,
+            _config: RHashMap<RString, RString>,
+        ) -> conveyor_plugin_api::RResult<(), conveyor_plugin_api::RBoxError> {
+            conveyor_plugin_api::ROk(())
+        }
+
+        fn execute_stream(
+            &self,
+            _context: FfiExecutionContext,
+            mut sink: FfiBatchSink_TO<'static, RBox<()>>,
+        ) -> conveyor_plugin_api::RResult<(), conveyor_plugin_api::RBoxError> {
+            for i in 0..5 {
+                let batch =
+                    match FfiDataFormat::from_json_records(&vec![serde_json::json!({ "id": i })]) {
+                        conveyor_plugin_api::ROk(batch) => batch,
+                        conveyor_plugin_api::RErr(e) => return conveyor_plugin_api::RErr(e),
+                    };
+                if !sink.emit(batch) {
+                    break;
+                }
+                self.emitted
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+            conveyor_plugin_api::ROk(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ffi_source_streams_incrementally() {
+        let emitted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let adapter = FfiPluginStageAdapter::new(
+            "counting".to_string(),
+            "counting".to_string(),
+            "Counting source".to_string(),
+            conveyor_plugin_api::StageType::Source,
+            FfiStage_TO::from_value(
+                CountingFfiSource {
+                    emitted: Arc::clone(&emitted),
+                },
+                TD_Opaque,
+            ),
+        );
+
+        let mut config = HashMap::new();
+        config.insert("stream".to_string(), toml::Value::Boolean(true));
+
+        let result = adapter.execute(HashMap::new(), &config).await.unwrap();
+        let DataFormat::Stream(mut stream) = result else {
+            panic!("Expected a stream");
+        };
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0]["id"], serde_json::json!(0));
+
+        // The bounded channel keeps the plugin from running ahead of the consumer
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(emitted.load(std::sync::atomic::Ordering::SeqCst) <= 2);
+
+        let mut ids = vec![0];
+        while let Some(batch) = stream.next().await {
+            ids.push(batch.unwrap()[0]["id"].as_i64().unwrap());
+        }
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+    }
+
+    /// Source returning `count` single-field records as a RecordBatch
+    struct RecordsSource {
+        count: usize,
+    }
+
+    #[async_trait]
+    impl Stage for RecordsSource {
+        fn name(&self) -> &str {
+            "records"
+        }
+
+        fn metadata(&self) -> StageMetadata {
+            use crate::core::metadata::StageCategory;
+            StageMetadata::builder("records", StageCategory::Source)
+                .description("Fixed records for testing")
+                .build()
+        }
+
+        async fn execute(
+            &self,
+            _inputs: HashMap<String, DataFormat>,
+            _config: &HashMap<String, toml::Value>,
+        ) -> Result<DataFormat> {
+            Ok(DataFormat::RecordBatch(
+                (0..self.count)
+                    .map(|i| HashMap::from([("id".to_string(), serde_json::json!(i))]))
+                    .collect(),
+            ))
+        }
+
+        async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_limit_is_shared_across_sources() {
+        let limit = Arc::new(RecordLimit::new(7));
+        let first = RecordLimitedStage::new(Arc::new(RecordsSource { count: 5 }), limit.clone());
+        let second = RecordLimitedStage::new(Arc::new(RecordsSource { count: 5 }), limit.clone());
+        let third = RecordLimitedStage::new(Arc::new(RecordsSource { count: 5 }), limit.clone());
+
+        let config = HashMap::new();
+        let counts = [
+            first.execute(HashMap::new(), &config).await.unwrap(),
+            second.execute(HashMap::new(), &config).await.unwrap(),
+            third.execute(HashMap::new(), &config).await.unwrap(),
+        ]
+        .map(|data| data.as_record_batch().unwrap().len());
+
+        // The second source gets what is left and the third is skipped
+        assert_eq!(counts, [5, 2, 0]);
+        assert_eq!(limit.emitted(), 7);
+        assert!(limit.is_reached());
+    }
+
+    #[tokio::test]
+    async fn test_record_limit_stops_streaming_source() {
+        let emitted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let adapter = FfiPluginStageAdapter::new(
+            "counting".to_string(),
+            "counting".to_string(),
+            "Counting source".to_string(),
+            conveyor_plugin_api::StageType::Source,
+            FfiStage_TO::from_value(
+                CountingFfiSource {
+                    emitted: Arc::clone(&emitted),
+                },
+                TD_Opaque,
+            ),
+        );
+        let limit = Arc::new(RecordLimit::new(2));
+        let stage = RecordLimitedStage::new(Arc::new(adapter), limit.clone());
+
+        let mut config = HashMap::new();
+        config.insert("stream".to_string(), toml::Value::Boolean(true));
+
+        let DataFormat::Stream(mut stream) = stage.execute(HashMap::new(), &config).await.unwrap()
+        else {
+            panic!("Expected a stream");
+        };
+
+        let mut ids = Vec::new();
+        while let Some(batch) = stream.next().await {
+            ids.extend(
+                batch
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| r["id"].as_i64().unwrap()),
+            );
+        }
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(limit.emitted(), 2);
+
+        // The plugin stops once the stream is dropped instead of emitting all 5
+        drop(stream);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(emitted.load(std::sync::atomic::Ordering::SeqCst) < 5);
+    }
+
+    #[test]
+    fn test_missing_column_policy_from_config() {
+        let policy = |value: &str| {
+            MissingColumnPolicy::from_config(&HashMap::from([(
+                "on_missing_column".to_string(),
+                toml::Value::String(value.to_string()),
+            )]))
+        };
+        assert_eq!(
+            MissingColumnPolicy::from_config(&HashMap::new()).unwrap(),
+            MissingColumnPolicy::Error
+        );
+        assert_eq!(policy("skip").unwrap(), MissingColumnPolicy::Skip);
+        assert_eq!(policy("null").unwrap(), MissingColumnPolicy::Null);
+        assert_eq!(
+            policy("ignore").unwrap_err().to_string(),
+            "Invalid 'on_missing_column' value 'ignore'. Must be one of: error, skip, null"
+        );
+    }
+
+    #[test]
+    fn test_wasm_secret_reaches_guest_but_not_config_dump() {
+        std::env::set_var("CONVEYOR_TEST_WASM_SECRET", "s3cr3t-value");
+        let pipeline = crate::core::config::DagPipelineConfig::from_str(
+            r#"
+[pipeline]
+name = "secrets"
+
+[[stages]]
+id = "enrich"
+function = "wasm.enricher.lookup"
+[stages.config]
+endpoint = "https://api.example.com"
+secret.api_key = "CONVEYOR_TEST_WASM_SECRET"
+"#,
+        )
+        .unwrap();
+
+        // The loader resolves the reference into the config handed to the guest
+        let wasm_config = config_to_wasm(&pipeline.stages[0].config).unwrap();
+        let guest_config = crate::wasm_plugin_loader::resolve_secrets(wasm_config).unwrap();
+        assert!(guest_config.contains(&("api_key".to_string(), "s3cr3t-value".to_string())));
+        assert!(guest_config
+            .iter()
+            .all(|(key, _)| !key.starts_with("secret")));
+
+        // Dumps of the plan show neither the value nor where it comes from
+        let dump = format!("{:?}", pipeline);
+        assert!(dump.contains("[REDACTED]"));
+        assert!(!dump.contains("s3cr3t-value"));
+        assert!(!dump.contains("CONVEYOR_TEST_WASM_SECRET"));
+        assert!(dump.contains("https://api.example.com"));
+
+        std::env::remove_var("CONVEYOR_TEST_WASM_SECRET");
+        let err = crate::wasm_plugin_loader::resolve_secrets(
+            config_to_wasm(&pipeline.stages[0].config).unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Secret 'api_key': environment variable 'CONVEYOR_TEST_WASM_SECRET' is not set"
+        );
+    }
 }
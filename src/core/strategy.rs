@@ -4,6 +4,9 @@ use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{error, warn};
 
+use crate::core::error::ConveyorError;
+use crate::core::traits::DataFormat;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "strategy", rename_all = "lowercase")]
 #[derive(Default)]
@@ -94,6 +97,60 @@ impl ErrorStrategy {
     }
 }
 
+/// What to do when a stage finishes with zero rows
+///
+/// Configured per stage with `empty_output`. Under `continue_on_error` a
+/// failed stage is replaced by an empty DataFrame, so this is also how a
+/// pipeline can refuse to quietly carry that substitution downstream.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EmptyOutputPolicy {
+    /// Empty output is expected and accepted
+    #[default]
+    Ok,
+    /// Log a warning and keep going
+    Warn,
+    /// Fail the pipeline
+    Error,
+}
+
+impl EmptyOutputPolicy {
+    /// Apply the policy to the output of `stage_id`
+    ///
+    /// Streams are not inspected since counting them would consume them.
+    pub fn check(&self, stage_id: &str, output: &DataFormat) -> Result<()> {
+        if *self == EmptyOutputPolicy::Ok {
+            return Ok(());
+        }
+
+        let is_empty = match output {
+            DataFormat::DataFrame(df) => df.height() == 0,
+            DataFormat::RecordBatch(records) => records.is_empty(),
+            DataFormat::Raw(bytes) => bytes.is_empty(),
+            DataFormat::Stream(_) => false,
+        };
+        if !is_empty {
+            return Ok(());
+        }
+
+        match self {
+            EmptyOutputPolicy::Ok => Ok(()),
+            EmptyOutputPolicy::Warn => {
+                warn!(
+                    "Stage '{}' produced 0 rows (empty_output = \"warn\"); downstream stages will receive empty input",
+                    stage_id
+                );
+                Ok(())
+            }
+            EmptyOutputPolicy::Error => Err(ConveyorError::PipelineError(format!(
+                "Stage '{}' produced 0 rows (empty_output = \"error\")",
+                stage_id
+            ))
+            .into()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
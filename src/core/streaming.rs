@@ -1,8 +1,11 @@
 use anyhow::Result;
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use polars::prelude::*;
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
 use std::pin::Pin;
 use tokio_stream::Stream;
 
@@ -199,6 +202,40 @@ impl StreamProcessor {
         Box::pin(stream.map(move |batch_result| batch_result.and_then(&f)))
     }
 
+    /// Map an async function over the batches of a stream, running up to
+    /// `concurrency` of them at once
+    ///
+    /// Batches come out as they finish, so a slow batch is overtaken by later
+    /// ones. With `reorder_buffer = Some(n)` every batch is tagged with its
+    /// sequence number and held back until all earlier batches are out,
+    /// restoring input order. At most `n` batches are then in flight or
+    /// waiting, so a slow batch stalls intake rather than growing the buffer.
+    pub fn map_concurrent<F, Fut>(
+        stream: Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>,
+        concurrency: usize,
+        reorder_buffer: Option<usize>,
+        f: F,
+    ) -> Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>
+    where
+        F: Fn(RecordBatch) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<RecordBatch>> + Send + 'static,
+    {
+        let batches = ConcurrentBatches {
+            input: stream,
+            input_done: false,
+            f,
+            in_flight: FuturesUnordered::new(),
+            finished: BTreeMap::new(),
+            next_in: 0,
+            next_out: 0,
+            concurrency: concurrency.max(1),
+            reorder_buffer: reorder_buffer.map(|n| n.max(1)),
+        };
+        Box::pin(futures::stream::unfold(batches, |mut batches| async move {
+            batches.next().await.map(|item| (item, batches))
+        }))
+    }
+
     /// Filter records in a stream
     pub fn filter<F>(
         stream: Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>,
@@ -218,6 +255,71 @@ impl StreamProcessor {
     }
 }
 
+/// State of [`StreamProcessor::map_concurrent`]
+struct ConcurrentBatches<F> {
+    input: Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>,
+    input_done: bool,
+    f: F,
+    in_flight: FuturesUnordered<BoxFuture<'static, (u64, Result<RecordBatch>)>>,
+    /// Finished batches waiting for an earlier one (ordered mode only)
+    finished: BTreeMap<u64, Result<RecordBatch>>,
+    next_in: u64,
+    next_out: u64,
+    concurrency: usize,
+    reorder_buffer: Option<usize>,
+}
+
+impl<F, Fut> ConcurrentBatches<F>
+where
+    F: Fn(RecordBatch) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<RecordBatch>> + Send + 'static,
+{
+    fn can_take(&self) -> bool {
+        let held = self.in_flight.len() + self.finished.len();
+        !self.input_done
+            && self.in_flight.len() < self.concurrency
+            && self.reorder_buffer.is_none_or(|limit| held < limit)
+    }
+
+    async fn next(&mut self) -> Option<Result<RecordBatch>> {
+        loop {
+            if let Some(result) = self.finished.remove(&self.next_out) {
+                self.next_out += 1;
+                return Some(result);
+            }
+
+            let take = self.can_take();
+            if !take && self.in_flight.is_empty() {
+                return None;
+            }
+
+            tokio::select! {
+                item = self.input.next(), if take => match item {
+                    Some(item) => {
+                        let seq = self.next_in;
+                        self.next_in += 1;
+                        let task: BoxFuture<'static, _> = match item {
+                            Ok(batch) => {
+                                let processed = (self.f)(batch);
+                                Box::pin(async move { (seq, processed.await) })
+                            }
+                            Err(e) => Box::pin(async move { (seq, Err(e)) }),
+                        };
+                        self.in_flight.push(task);
+                    }
+                    None => self.input_done = true,
+                },
+                Some((seq, result)) = self.in_flight.next(), if !self.in_flight.is_empty() => {
+                    if self.reorder_buffer.is_none() {
+                        return Some(result);
+                    }
+                    self.finished.insert(seq, result);
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,4 +400,46 @@ mod tests {
         let batch = result[0].as_ref().unwrap();
         assert_eq!(batch.len(), 2); // Only values >= 10
     }
+
+    #[tokio::test]
+    async fn test_map_concurrent_reorder_buffer_bounds_intake() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let records: Vec<Result<RecordBatch>> = (0..6)
+            .map(|id| {
+                Ok(vec![HashMap::from([(
+                    "id".to_string(),
+                    JsonValue::from(id),
+                )])])
+            })
+            .collect();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let (running_in, peak_in) = (Arc::clone(&running), Arc::clone(&peak));
+        let stream = StreamProcessor::map_concurrent(
+            Box::pin(tokio_stream::iter(records)),
+            4,
+            Some(2),
+            move |batch| {
+                let (running, peak) = (Arc::clone(&running_in), Arc::clone(&peak_in));
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    let id = batch[0]["id"].as_u64().unwrap();
+                    tokio::time::sleep(std::time::Duration::from_millis(20 - 3 * id)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(batch)
+                }
+            },
+        );
+
+        let ids: Vec<u64> = stream
+            .map(|batch| batch.unwrap()[0]["id"].as_u64().unwrap())
+            .collect()
+            .await;
+        assert_eq!(ids, vec![0, 1, 2, 3, 4, 5]);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
 }
@@ -0,0 +1,548 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use polars::prelude::*;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio_stream::StreamExt;
+
+use crate::core::config::{DagPipelineConfig, StageConfig};
+use crate::core::dag_builder::StageSubstitute;
+use crate::core::error::ConveyorError;
+use crate::core::metadata::{StageCategory, StageMetadata};
+use crate::core::pipeline::DagPipeline;
+use crate::core::stage::{Stage, StageRef};
+use crate::core::traits::{DataFormat, RecordBatch};
+
+/// Sample records for each source and expectations for each sink, read from
+/// a TOML test file
+///
+/// ```toml
+/// [sources.load]
+/// records = [{ id = 1, price = 2.5 }]
+///
+/// [sinks.save]
+/// records = [{ id = 1, price = 2.5, total = 5.0 }]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestSuite {
+    #[serde(default)]
+    pub sources: BTreeMap<String, SourceSample>,
+    #[serde(default)]
+    pub sinks: BTreeMap<String, SinkExpectation>,
+}
+
+/// Records a source emits in place of reading its input
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceSample {
+    pub records: Vec<Map<String, Value>>,
+}
+
+/// What a sink is expected to receive
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SinkExpectation {
+    /// Exact records, compared field by field
+    pub records: Option<Vec<Map<String, Value>>>,
+    /// Number of records, for outputs too large or volatile to list
+    pub row_count: Option<usize>,
+    /// Compare `records` regardless of their order
+    #[serde(default)]
+    pub ignore_order: bool,
+}
+
+impl TestSuite {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read test file {:?}", path))?;
+        Self::from_str(&content).with_context(|| format!("Invalid test file {:?}", path))
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Check the suite against the pipeline before anything runs
+    fn validate(&self, config: &DagPipelineConfig) -> Result<()> {
+        let stage = |id: &str| config.stages.iter().find(|stage| stage.id == id);
+
+        for id in self.sources.keys() {
+            match stage(id) {
+                None => return Err(invalid(format!("Sample for unknown stage '{}'", id))),
+                Some(stage) if !stage.inputs.is_empty() => {
+                    return Err(invalid(format!(
+                        "Stage '{}' has inputs; samples can only replace sources",
+                        id
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+        for stage in config.stages.iter().filter(|stage| stage.inputs.is_empty()) {
+            if !self.sources.contains_key(&stage.id) {
+                return Err(invalid(format!(
+                    "Source '{}' has no sample records; add a [sources.{}] table",
+                    stage.id, stage.id
+                )));
+            }
+        }
+
+        if self.sinks.is_empty() {
+            return Err(invalid("Test file has no sink expectations".to_string()));
+        }
+        for (id, expectation) in &self.sinks {
+            if stage(id).is_none() {
+                return Err(invalid(format!("Expectation for unknown stage '{}'", id)));
+            }
+            if expectation.records.is_none() && expectation.row_count.is_none() {
+                return Err(invalid(format!(
+                    "Expectation for '{}' needs 'records' or 'row_count'",
+                    id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: String) -> anyhow::Error {
+    ConveyorError::PipelineError(message).into()
+}
+
+/// Result of checking one sink
+#[derive(Debug)]
+pub struct SinkOutcome {
+    pub sink: String,
+    /// Records the sink received
+    pub records: usize,
+    /// Unmet expectations; empty when the sink passed
+    pub failures: Vec<String>,
+}
+
+impl SinkOutcome {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Outcome of every sink expectation, in sink id order
+#[derive(Debug)]
+pub struct TestReport {
+    pub outcomes: Vec<SinkOutcome>,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(SinkOutcome::passed)
+    }
+}
+
+impl fmt::Display for TestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for outcome in &self.outcomes {
+            if outcome.passed() {
+                writeln!(
+                    f,
+                    "✓ {}: {} record(s) as expected",
+                    outcome.sink, outcome.records
+                )?;
+            } else {
+                for failure in &outcome.failures {
+                    writeln!(f, "✗ {}: {}", outcome.sink, failure)?;
+                }
+            }
+        }
+        let passed = self.outcomes.iter().filter(|o| o.passed()).count();
+        write!(f, "{} of {} sink(s) passed", passed, self.outcomes.len())
+    }
+}
+
+/// Source that emits the sample records of a test file
+struct SampleSource {
+    records: RecordBatch,
+}
+
+#[async_trait]
+impl Stage for SampleSource {
+    fn name(&self) -> &str {
+        "test.sample"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        StageMetadata::builder("test.sample", StageCategory::Source)
+            .description("Emit sample records in place of a source")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        _inputs: HashMap<String, DataFormat>,
+        _config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        Ok(DataFormat::RecordBatch(self.records.clone()))
+    }
+
+    async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Sink that keeps what it receives instead of writing it anywhere
+struct CaptureSink {
+    captured: Arc<Mutex<RecordBatch>>,
+}
+
+#[async_trait]
+impl Stage for CaptureSink {
+    fn name(&self) -> &str {
+        "test.capture"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        StageMetadata::builder("test.capture", StageCategory::Sink)
+            .description("Capture records in place of a sink")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        _config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let mut inputs: Vec<(String, DataFormat)> = inputs.into_iter().collect();
+        inputs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut records = Vec::new();
+        for (_, data) in inputs {
+            records.extend(to_records(data).await?);
+        }
+        self.captured.lock().unwrap().extend(records);
+        Ok(DataFormat::RecordBatch(Vec::new()))
+    }
+
+    async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+        Ok(())
+    }
+
+    fn produces_output(&self) -> bool {
+        false
+    }
+}
+
+/// Records of any data format, keeping nulls and numeric types as they are
+async fn to_records(data: DataFormat) -> Result<RecordBatch> {
+    match data {
+        DataFormat::RecordBatch(records) => Ok(records),
+        DataFormat::DataFrame(mut df) => {
+            let mut buffer = Vec::new();
+            JsonWriter::new(&mut buffer)
+                .with_json_format(JsonFormat::JsonLines)
+                .finish(&mut df)?;
+            parse_records(&buffer)
+        }
+        DataFormat::Raw(bytes) => {
+            parse_records(&bytes).context("Sink received raw bytes that are not JSON records")
+        }
+        DataFormat::Stream(mut stream) => {
+            let mut records = Vec::new();
+            while let Some(batch) = stream.next().await {
+                records.extend(batch?);
+            }
+            Ok(records)
+        }
+    }
+}
+
+/// Parse a JSON array, a single object or JSON Lines
+fn parse_records(bytes: &[u8]) -> Result<RecordBatch> {
+    let content = std::str::from_utf8(bytes)?;
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') {
+        return Ok(serde_json::from_str(trimmed)?);
+    }
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+/// Run `config` with its sources replaced by the suite's samples, and check
+/// what its sinks receive
+///
+/// Every sink is replaced by a capture, so a test never writes output. Other
+/// stages, including plugin stages such as HTTP lookups, run as configured.
+pub async fn run_tests(config: &DagPipelineConfig, suite: &TestSuite) -> Result<TestReport> {
+    suite.validate(config)?;
+
+    let captures: Arc<Mutex<HashMap<String, Arc<Mutex<RecordBatch>>>>> = Arc::default();
+    let non_sinks: Arc<Mutex<HashSet<String>>> = Arc::default();
+
+    let substitute: StageSubstitute = {
+        let samples = suite.sources.clone();
+        let expected: HashSet<String> = suite.sinks.keys().cloned().collect();
+        let captures = Arc::clone(&captures);
+        let non_sinks = Arc::clone(&non_sinks);
+        Arc::new(move |stage_config: &StageConfig, stage: &StageRef| {
+            let id = &stage_config.id;
+            if let Some(sample) = samples.get(id) {
+                let records = sample
+                    .records
+                    .iter()
+                    .map(|record| record.clone().into_iter().collect())
+                    .collect();
+                return Some(Arc::new(SampleSource { records }) as StageRef);
+            }
+
+            let is_sink =
+                stage.metadata().category == StageCategory::Sink || !stage.produces_output();
+            if !is_sink {
+                if expected.contains(id) {
+                    non_sinks.lock().unwrap().insert(id.clone());
+                }
+                return None;
+            }
+            let captured = Arc::new(Mutex::new(Vec::new()));
+            if expected.contains(id) {
+                captures
+                    .lock()
+                    .unwrap()
+                    .insert(id.clone(), Arc::clone(&captured));
+            }
+            Some(Arc::new(CaptureSink { captured }) as StageRef)
+        })
+    };
+
+    let mut config = config.clone();
+    // Tag filters could drop the sinks under test
+    config.global.tags.clear();
+    let mut pipeline = DagPipeline::with_substitute(config, substitute).await?;
+
+    if let Some(id) = non_sinks.lock().unwrap().iter().min() {
+        return Err(invalid(format!(
+            "Stage '{}' is not a sink; expectations can only check sinks",
+            id
+        )));
+    }
+    pipeline.execute().await?;
+
+    let captures = captures.lock().unwrap();
+    let outcomes = suite
+        .sinks
+        .iter()
+        .map(|(sink, expectation)| {
+            let records = captures
+                .get(sink)
+                .map(|captured| captured.lock().unwrap().clone())
+                .unwrap_or_default();
+            SinkOutcome {
+                sink: sink.clone(),
+                records: records.len(),
+                failures: check(expectation, &records),
+            }
+        })
+        .collect();
+    Ok(TestReport { outcomes })
+}
+
+/// Unmet expectations for the records a sink received
+fn check(expectation: &SinkExpectation, records: &RecordBatch) -> Vec<String> {
+    let mut failures = Vec::new();
+    if let Some(row_count) = expectation.row_count {
+        if row_count != records.len() {
+            failures.push(format!(
+                "expected {} row(s), got {}",
+                row_count,
+                records.len()
+            ));
+        }
+    }
+
+    if let Some(expected) = &expectation.records {
+        // Pairs of (compared, shown) values, so messages show numbers as written;
+        // fields are sorted so records print the same way every run
+        let pair = |mut fields: Vec<(String, Value)>| {
+            fields.sort_by(|a, b| a.0.cmp(&b.0));
+            let record = Value::Object(fields.into_iter().collect());
+            (normalize(record.clone()), record)
+        };
+        let mut expected: Vec<(Value, Value)> = expected
+            .iter()
+            .map(|record| pair(record.clone().into_iter().collect()))
+            .collect();
+        let mut actual: Vec<(Value, Value)> = records
+            .iter()
+            .map(|record| pair(record.clone().into_iter().collect()))
+            .collect();
+        if expectation.ignore_order {
+            expected.sort_by_key(|(value, _)| value.to_string());
+            actual.sort_by_key(|(value, _)| value.to_string());
+        }
+
+        if expected.len() != actual.len() {
+            failures.push(format!(
+                "expected {} record(s), got {}",
+                expected.len(),
+                actual.len()
+            ));
+        } else if let Some(index) = (0..expected.len()).find(|&i| expected[i].0 != actual[i].0) {
+            failures.push(format!(
+                "record {} differs: expected {}, got {}",
+                index + 1,
+                expected[index].1,
+                actual[index].1
+            ));
+        }
+    }
+    failures
+}
+
+/// Make `5` and `5.0` equal, since a record may pass through a DataFrame
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Number(number)),
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| (key, normalize(value)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pipeline(dir: &Path) -> DagPipelineConfig {
+        DagPipelineConfig::from_str(&format!(
+            r#"
+[pipeline]
+name = "orders"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "json.read"
+[stages.config]
+path = "{dir}/orders.json"
+
+[[stages]]
+id = "totals"
+function = "map.apply"
+inputs = ["load"]
+[stages.config]
+expression = "price * 2"
+output_column = "total"
+
+[[stages]]
+id = "save"
+function = "json.write"
+inputs = ["totals"]
+[stages.config]
+path = "{dir}/totals.json"
+
+[[stages]]
+id = "archive"
+function = "json.write"
+inputs = ["load"]
+[stages.config]
+path = "{dir}/archive.json"
+"#,
+            dir = dir.display()
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reports_passing_and_failing_expectations() {
+        let dir = TempDir::new().unwrap();
+        let config = pipeline(dir.path());
+        let suite = TestSuite::from_str(
+            r#"
+[sources.load]
+records = [{ id = 1, price = 2.5 }, { id = 2, price = 4 }]
+
+[sinks.save]
+records = [{ id = 2, price = 4, total = 8 }, { id = 1, price = 2.5, total = 5.0 }]
+ignore_order = true
+
+[sinks.archive]
+row_count = 3
+"#,
+        )
+        .unwrap();
+
+        let report = run_tests(&config, &suite).await.unwrap();
+        assert!(!report.passed());
+        assert_eq!(
+            report.to_string(),
+            "✗ archive: expected 3 row(s), got 2\n\
+             ✓ save: 2 record(s) as expected\n\
+             1 of 2 sink(s) passed"
+        );
+
+        // Neither the sources nor the sinks touched the filesystem
+        assert!(!dir.path().join("totals.json").exists());
+        assert!(!dir.path().join("archive.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_reports_first_differing_record() {
+        let dir = TempDir::new().unwrap();
+        let config = pipeline(dir.path());
+        let suite = TestSuite::from_str(
+            r#"
+[sources.load]
+records = [{ id = 1, price = 2.5 }, { id = 2, price = 4 }]
+
+[sinks.save]
+records = [{ id = 1, price = 2.5, total = 5 }, { id = 2, price = 4, total = 9 }]
+"#,
+        )
+        .unwrap();
+
+        let report = run_tests(&config, &suite).await.unwrap();
+        assert_eq!(
+            report.outcomes[0].failures,
+            vec![
+                r#"record 2 differs: expected {"id":2,"price":4,"total":9}, got {"id":2,"price":4.0,"total":8.0}"#
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsampled_source_and_non_sink() {
+        let dir = TempDir::new().unwrap();
+        let config = pipeline(dir.path());
+
+        let suite = TestSuite::from_str("[sinks.save]\nrow_count = 1\n").unwrap();
+        let err = run_tests(&config, &suite).await.unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Pipeline execution error: Source 'load' has no sample records; add a [sources.load] table"
+        );
+
+        let suite =
+            TestSuite::from_str("[sources.load]\nrecords = []\n\n[sinks.totals]\nrow_count = 1\n")
+                .unwrap();
+        let err = run_tests(&config, &suite).await.unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Pipeline execution error: Stage 'totals' is not a sink; expectations can only check sinks"
+        );
+    }
+}
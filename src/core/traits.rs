@@ -18,8 +18,102 @@ pub enum DataFormat {
     Stream(Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>),
 }
 
+/// How to handle a record field holding values of different JSON types, such
+/// as `1` in one record and `"N/A"` in another, when records become a DataFrame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TypeConflict {
+    /// Turn every value of a conflicting field into a string, so nothing is lost
+    String,
+    /// Fail on the first conflicting field
+    Error,
+    /// Leave it to polars' schema inference, which picks a common type from
+    /// the leading records
+    #[default]
+    Infer,
+}
+
+/// JSON type of a value for conflict detection; integers and floats are both
+/// numbers, and nulls fit any type
+fn json_kind(value: &JsonValue) -> Option<&'static str> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(_) => Some("boolean"),
+        JsonValue::Number(_) => Some("number"),
+        JsonValue::String(_) => Some("string"),
+        JsonValue::Array(_) => Some("array"),
+        JsonValue::Object(_) => Some("object"),
+    }
+}
+
+impl TypeConflict {
+    pub const NAMES: [&'static str; 3] = ["string", "error", "infer"];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "string" => Ok(TypeConflict::String),
+            "error" => Ok(TypeConflict::Error),
+            "infer" => Ok(TypeConflict::Infer),
+            _ => anyhow::bail!(
+                "Invalid type_conflict: {}. Must be 'string', 'error', or 'infer'",
+                name
+            ),
+        }
+    }
+
+    /// Apply the policy to `records`: coerce or reject fields with mixed types
+    pub fn resolve(self, mut records: RecordBatch) -> Result<RecordBatch> {
+        if self == TypeConflict::Infer {
+            return Ok(records);
+        }
+
+        // First type seen per field, with the record it was seen in
+        let mut kinds: HashMap<&str, (&'static str, usize)> = HashMap::new();
+        let mut conflicting = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            for (field, value) in record {
+                let Some(kind) = json_kind(value) else {
+                    continue;
+                };
+                let (first, first_index) = *kinds.entry(field).or_insert((kind, index));
+                if first != kind && !conflicting.contains(field) {
+                    if self == TypeConflict::Error {
+                        anyhow::bail!(
+                            "Type conflict in field '{}': {} in record {}, {} in record {}",
+                            field,
+                            first,
+                            first_index,
+                            kind,
+                            index
+                        );
+                    }
+                    conflicting.push(field.clone());
+                }
+            }
+        }
+
+        for record in &mut records {
+            for field in &conflicting {
+                if let Some(value) = record.get_mut(field) {
+                    *value = match value.take() {
+                        JsonValue::Null => JsonValue::Null,
+                        JsonValue::String(s) => JsonValue::String(s),
+                        other => JsonValue::String(other.to_string()),
+                    };
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
 impl DataFormat {
     pub fn as_dataframe(&self) -> Result<DataFrame> {
+        self.as_dataframe_with(TypeConflict::Infer)
+    }
+
+    /// Convert to a DataFrame, resolving fields of record batches that hold
+    /// mixed JSON types according to `type_conflict`
+    pub fn as_dataframe_with(&self, type_conflict: TypeConflict) -> Result<DataFrame> {
         match self {
             DataFormat::DataFrame(df) => Ok(df.clone()),
             DataFormat::RecordBatch(records) => {
@@ -29,7 +123,10 @@ impl DataFormat {
                 }
 
                 // Build DataFrame from records
-                let json_str = serde_json::to_string(records)?;
+                let json_str = match type_conflict {
+                    TypeConflict::Infer => serde_json::to_string(records)?,
+                    policy => serde_json::to_string(&policy.resolve(records.clone())?)?,
+                };
                 let cursor = std::io::Cursor::new(json_str.as_bytes());
                 let df = JsonReader::new(cursor).finish()?;
                 Ok(df)
@@ -96,6 +193,11 @@ impl DataFormat {
                                 let s = col.bool()?;
                                 s.get(i).map(JsonValue::Bool).unwrap_or(JsonValue::Null)
                             }
+                            DataType::Categorical(..) => col
+                                .get(i)?
+                                .get_str()
+                                .map(|v| JsonValue::String(v.to_string()))
+                                .unwrap_or(JsonValue::Null),
                             _ => JsonValue::Null,
                         };
                         record.insert(name, value);
@@ -147,3 +249,76 @@ pub trait StreamingDataSource: Send + Sync {
 }
 
 pub type StreamingDataSourceRef = Arc<dyn StreamingDataSource>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mixed(field: &str, values: &[JsonValue]) -> DataFormat {
+        DataFormat::RecordBatch(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    HashMap::from([("id".to_string(), json!(i)), (field.to_string(), v.clone())])
+                })
+                .collect(),
+        )
+    }
+
+    fn strings(df: &DataFrame, name: &str) -> Vec<Option<String>> {
+        df.column(name)
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.map(|s| s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_type_conflict_policies() {
+        let data = mixed("score", &[json!(1), json!("N/A"), JsonValue::Null]);
+
+        let df = data.as_dataframe_with(TypeConflict::String).unwrap();
+        assert_eq!(
+            strings(&df, "score"),
+            vec![Some("1".to_string()), Some("N/A".to_string()), None]
+        );
+        // Fields without a conflict keep their type
+        assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+
+        let err = data.as_dataframe_with(TypeConflict::Error).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Type conflict in field 'score': number in record 0, string in record 1"
+        );
+
+        // Inference settles on a string column for this pair as well
+        let df = data.as_dataframe_with(TypeConflict::Infer).unwrap();
+        assert_eq!(df.column("score").unwrap().dtype(), &DataType::String);
+
+        assert_eq!(TypeConflict::parse("error").unwrap(), TypeConflict::Error);
+        assert!(TypeConflict::parse("coerce").is_err());
+    }
+
+    #[test]
+    fn test_string_policy_keeps_values_inference_would_lose() {
+        // Inference reads the boolean as a number
+        let data = mixed("flag", &[json!(true), json!(0), json!([1, 2])]);
+        let df = data.as_dataframe_with(TypeConflict::String).unwrap();
+        assert_eq!(
+            strings(&df, "flag"),
+            vec![
+                Some("true".to_string()),
+                Some("0".to_string()),
+                Some("[1,2]".to_string())
+            ]
+        );
+
+        // Integers and floats are both numbers and do not conflict
+        let numbers = mixed("amount", &[json!(1), json!(2.5)]);
+        assert!(numbers.as_dataframe_with(TypeConflict::Error).is_ok());
+    }
+}
@@ -14,7 +14,7 @@ pub use core::error::{ConveyorError, ConveyorResult};
 pub use core::pipeline::DagPipeline;
 pub use core::registry::ModuleRegistry;
 pub use core::stage::Stage;
-pub use core::strategy::ErrorStrategy;
+pub use core::strategy::{EmptyOutputPolicy, ErrorStrategy};
 pub use core::traits::DataFormat;
 pub use plugin_loader::PluginLoader;
 pub use wasm_plugin_loader::WasmPluginLoader;
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -14,7 +15,9 @@ mod update;
 mod utils;
 mod wasm_plugin_loader;
 
+use crate::core::config::DagPipelineConfig;
 use crate::core::pipeline::DagPipeline;
+use crate::core::progress::ProgressReporter;
 
 #[derive(Parser)]
 #[command(
@@ -29,6 +32,21 @@ struct Cli {
 
     #[arg(short, long, global = true, help = "Set the log level")]
     log_level: Option<Level>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        help = "Set the log output format"
+    )]
+    log_format: LogFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -40,6 +58,26 @@ enum Commands {
 
         #[arg(long, help = "Validate configuration without running")]
         dry_run: bool,
+
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Stop all sources after N records in total (run-wide safety cap)"
+        )]
+        max_records: Option<usize>,
+
+        #[arg(
+            long,
+            help = "Show per-stage progress bars (only on a terminal with text logs)"
+        )]
+        progress: bool,
+
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Only run stages with this tag and their upstream stages (repeatable)"
+        )]
+        tags: Vec<String>,
     },
 
     #[command(about = "Validate a pipeline configuration")]
@@ -48,6 +86,31 @@ enum Commands {
         config: PathBuf,
     },
 
+    #[command(about = "Feed dead-lettered records back into a pipeline stage")]
+    Replay {
+        #[arg(help = "Dead-letter file (JSON array or JSON Lines)")]
+        dead_letter: PathBuf,
+
+        #[arg(help = "Path to the TOML configuration file")]
+        config: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "STAGE_ID",
+            help = "Stage that receives the records; it and its downstream stages run"
+        )]
+        into: String,
+    },
+
+    #[command(about = "Run a pipeline against sample data and check its outputs")]
+    Test {
+        #[arg(help = "Path to the TOML configuration file")]
+        config: PathBuf,
+
+        #[arg(help = "Test file with sample records per source and expectations per sink")]
+        tests: PathBuf,
+    },
+
     #[command(about = "List available modules")]
     List {
         #[arg(short = 't', long, help = "Filter by module type")]
@@ -113,18 +176,44 @@ enum StageCommands {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    let progress_requested = matches!(cli.command, Commands::Run { progress: true, .. });
+    let progress = Arc::new(ProgressReporter::new(
+        progress_requested,
+        cli.log_format == LogFormat::Json,
+    ));
+
     // Initialize logging
     let log_level = cli.log_level.unwrap_or(Level::INFO);
-    let subscriber = FmtSubscriber::builder().with_max_level(log_level).finish();
-    tracing::subscriber::set_global_default(subscriber)?;
+    let builder = FmtSubscriber::builder().with_max_level(log_level);
+    match cli.log_format {
+        LogFormat::Json => tracing::subscriber::set_global_default(builder.json().finish())?,
+        // Route log lines around the progress bars so they don't tear them
+        LogFormat::Text if progress.is_enabled() => tracing::subscriber::set_global_default(
+            builder.with_writer(progress.log_writer()).finish(),
+        )?,
+        LogFormat::Text => tracing::subscriber::set_global_default(builder.finish())?,
+    }
 
     // Check for updates (once per 24 hours)
     let _ = update::check_for_updates(false).await;
 
     match cli.command {
-        Commands::Run { config, dry_run } => {
+        Commands::Run {
+            config,
+            dry_run,
+            max_records,
+            progress: _,
+            tags,
+        } => {
             info!("Loading pipeline configuration from {:?}", config);
-            let mut pipeline = DagPipeline::from_file(&config).await?;
+            let mut dag_config = DagPipelineConfig::from_file(&config).await?;
+            if max_records.is_some() {
+                dag_config.global.max_records = max_records;
+            }
+            if !tags.is_empty() {
+                dag_config.global.tags = tags;
+            }
+            let mut pipeline = DagPipeline::with_progress(dag_config, progress).await?;
 
             if dry_run {
                 info!("Dry run mode - validating configuration");
@@ -144,6 +233,28 @@ async fn main() -> Result<()> {
             println!("✓ Configuration is valid");
         }
 
+        Commands::Replay {
+            dead_letter,
+            config,
+            into,
+        } => {
+            info!("Loading pipeline configuration from {:?}", config);
+            let dag_config = DagPipelineConfig::from_file(&config).await?;
+            let replayed = core::replay::replay(&dag_config, &into, &dead_letter).await?;
+            println!("✓ Replayed {} record(s) into stage '{}'", replayed, into);
+        }
+
+        Commands::Test { config, tests } => {
+            info!("Loading pipeline configuration from {:?}", config);
+            let dag_config = DagPipelineConfig::from_file(&config).await?;
+            let suite = core::test_runner::TestSuite::from_file(&tests)?;
+            let report = core::test_runner::run_tests(&dag_config, &suite).await?;
+            println!("{}", report);
+            if !report.passed() {
+                anyhow::bail!("Pipeline test failed");
+            }
+        }
+
         Commands::List { module_type } => {
             info!("Listing available modules");
             cli::list_modules(module_type).await?;
@@ -12,6 +12,7 @@ use std::sync::Arc;
 /// Function-based API where each operation is a named function:
 /// - csv.read, csv.write
 /// - json.read, json.write
+/// - parquet.read, parquet.write
 /// - filter.apply, map.apply, etc.
 pub fn register_functions() -> HashMap<String, StageRef> {
     let mut functions = HashMap::new();
@@ -36,11 +37,53 @@ pub fn register_functions() -> HashMap<String, StageRef> {
         Arc::new(sinks::json::JsonSink) as StageRef,
     );
 
+    // Parquet functions
+    functions.insert(
+        "parquet.read".to_string(),
+        Arc::new(sources::parquet::ParquetSource) as StageRef,
+    );
+    functions.insert(
+        "parquet.write".to_string(),
+        Arc::new(sinks::parquet::ParquetSink) as StageRef,
+    );
+
+    // Materialized view functions
+    functions.insert(
+        "materialize.read".to_string(),
+        Arc::new(sources::materialize::MaterializeSource) as StageRef,
+    );
+    functions.insert(
+        "materialize.write".to_string(),
+        Arc::new(sinks::materialize::MaterializeSink) as StageRef,
+    );
+
     // Stdin/Stdout functions
     functions.insert(
         "stdin.read".to_string(),
         Arc::new(sources::stdin::StdinSource) as StageRef,
     );
+    functions.insert(
+        "socket.read".to_string(),
+        Arc::new(sources::socket::SocketSource) as StageRef,
+    );
+    functions.insert(
+        "tail.read".to_string(),
+        Arc::new(sources::tail::TailSource) as StageRef,
+    );
+    functions.insert(
+        "run_info.read".to_string(),
+        Arc::new(sources::run_info::RunInfoSource::new(Arc::new(
+            crate::core::run_context::RunContext::new(),
+        ))) as StageRef,
+    );
+    functions.insert(
+        "schema.write".to_string(),
+        Arc::new(sinks::schema::SchemaSink) as StageRef,
+    );
+    functions.insert(
+        "compact.apply".to_string(),
+        Arc::new(sinks::compact::CompactSink) as StageRef,
+    );
     functions.insert(
         "stdout.write".to_string(),
         Arc::new(sinks::stdout::StdoutSink) as StageRef,
@@ -111,6 +154,163 @@ pub fn register_functions() -> HashMap<String, StageRef> {
         "json.extract".to_string(),
         Arc::new(transforms::json_extract::JsonExtractTransform) as StageRef,
     );
+    functions.insert(
+        "parse_text.apply".to_string(),
+        Arc::new(transforms::parse_text::ParseTextTransform) as StageRef,
+    );
+    functions.insert(
+        "row_hash.apply".to_string(),
+        Arc::new(transforms::row_hash::RowHashTransform) as StageRef,
+    );
+    functions.insert(
+        "patch.apply".to_string(),
+        Arc::new(transforms::patch::PatchTransform) as StageRef,
+    );
+    functions.insert(
+        "surrogate_key.apply".to_string(),
+        Arc::new(transforms::surrogate_key::SurrogateKeyTransform) as StageRef,
+    );
+    functions.insert(
+        "bucket.apply".to_string(),
+        Arc::new(transforms::bucket::BucketTransform) as StageRef,
+    );
+    functions.insert(
+        "time_convert.apply".to_string(),
+        Arc::new(transforms::time_convert::TimeConvertTransform) as StageRef,
+    );
+    functions.insert(
+        "join.apply".to_string(),
+        Arc::new(transforms::join::JoinTransform) as StageRef,
+    );
+    functions.insert(
+        "fuzzy_join.apply".to_string(),
+        Arc::new(transforms::fuzzy_join::FuzzyJoinTransform) as StageRef,
+    );
+    functions.insert(
+        "entity_resolve.apply".to_string(),
+        Arc::new(transforms::entity_resolve::EntityResolveTransform) as StageRef,
+    );
+    functions.insert(
+        "schema_drift.apply".to_string(),
+        Arc::new(transforms::schema_drift::SchemaDriftTransform) as StageRef,
+    );
+    functions.insert(
+        "set_op.apply".to_string(),
+        Arc::new(transforms::set_op::SetOpTransform) as StageRef,
+    );
+    functions.insert(
+        "map_values.apply".to_string(),
+        Arc::new(transforms::map_values::MapValuesTransform) as StageRef,
+    );
+    functions.insert(
+        "parse_number.apply".to_string(),
+        Arc::new(transforms::parse_number::ParseNumberTransform) as StageRef,
+    );
+    functions.insert(
+        "normalize_category.apply".to_string(),
+        Arc::new(transforms::normalize_category::NormalizeCategoryTransform) as StageRef,
+    );
+    functions.insert(
+        "eav_pivot.apply".to_string(),
+        Arc::new(transforms::eav_pivot::EavPivotTransform) as StageRef,
+    );
+    functions.insert(
+        "crosstab.apply".to_string(),
+        Arc::new(transforms::crosstab::CrosstabTransform) as StageRef,
+    );
+    functions.insert(
+        "pivot.apply".to_string(),
+        Arc::new(transforms::pivot::PivotTransform) as StageRef,
+    );
+    functions.insert(
+        "unpivot.apply".to_string(),
+        Arc::new(transforms::unpivot::UnpivotTransform) as StageRef,
+    );
+    functions.insert(
+        "merge_patch.apply".to_string(),
+        Arc::new(transforms::merge_patch::MergePatchTransform) as StageRef,
+    );
+    functions.insert(
+        "flatten.apply".to_string(),
+        Arc::new(transforms::flatten::FlattenTransform) as StageRef,
+    );
+    functions.insert(
+        "array_ops.apply".to_string(),
+        Arc::new(transforms::array_ops::ArrayOpsTransform) as StageRef,
+    );
+    functions.insert(
+        "scale.apply".to_string(),
+        Arc::new(transforms::scale::ScaleTransform) as StageRef,
+    );
+    functions.insert(
+        "batch.apply".to_string(),
+        Arc::new(transforms::batch::BatchTransform) as StageRef,
+    );
+    functions.insert(
+        "unbatch.apply".to_string(),
+        Arc::new(transforms::batch::UnbatchTransform) as StageRef,
+    );
+    functions.insert(
+        "delta.apply".to_string(),
+        Arc::new(transforms::delta::DeltaTransform) as StageRef,
+    );
+    functions.insert(
+        "geocode.apply".to_string(),
+        Arc::new(transforms::geocode::GeocodeTransform::new()) as StageRef,
+    );
+    functions.insert(
+        "schema_validate.apply".to_string(),
+        Arc::new(transforms::schema_validate::SchemaValidateTransform::new()) as StageRef,
+    );
+    functions.insert(
+        "kv_lookup.apply".to_string(),
+        Arc::new(transforms::kv_lookup::KvLookupTransform::new()) as StageRef,
+    );
+    functions.insert(
+        "ip_enrich.apply".to_string(),
+        Arc::new(transforms::ip_enrich::IpEnrichTransform::new()) as StageRef,
+    );
+    functions.insert(
+        "agg_state.apply".to_string(),
+        Arc::new(transforms::agg_state::AggStateTransform) as StageRef,
+    );
+    functions.insert(
+        "stratified_sample.apply".to_string(),
+        Arc::new(transforms::stratified_sample::StratifiedSampleTransform) as StageRef,
+    );
+    functions.insert(
+        "rolling_time.apply".to_string(),
+        Arc::new(transforms::rolling_time::RollingTimeTransform) as StageRef,
+    );
+    functions.insert(
+        "resample.apply".to_string(),
+        Arc::new(transforms::resample::ResampleTransform) as StageRef,
+    );
+    functions.insert(
+        "funnel.apply".to_string(),
+        Arc::new(transforms::funnel::FunnelTransform) as StageRef,
+    );
+    functions.insert(
+        "outlier.apply".to_string(),
+        Arc::new(transforms::outlier::OutlierTransform) as StageRef,
+    );
+    functions.insert(
+        "quality_score.apply".to_string(),
+        Arc::new(transforms::quality_score::QualityScoreTransform) as StageRef,
+    );
+    functions.insert(
+        "limit.apply".to_string(),
+        Arc::new(transforms::limit::LimitTransform) as StageRef,
+    );
+    functions.insert(
+        "rename.apply".to_string(),
+        Arc::new(transforms::rename::RenameTransform) as StageRef,
+    );
+    #[cfg(feature = "ref-check")]
+    functions.insert(
+        "ref_check.apply".to_string(),
+        Arc::new(transforms::ref_check::RefCheckTransform::new()) as StageRef,
+    );
     functions.insert(
         "ai.generate".to_string(),
         Arc::new(transforms::ai::AiGenerateTransform::new()) as StageRef,
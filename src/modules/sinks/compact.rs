@@ -0,0 +1,465 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use polars::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct CompactSink;
+
+const FORMATS: [&str; 4] = ["parquet", "csv", "json", "jsonl"];
+
+/// How many files each directory is rewritten into
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Files(usize),
+    FileSize(u64),
+}
+
+struct Options {
+    /// Directory searched (recursively) for part files
+    root: PathBuf,
+    /// File name pattern of the part files
+    pattern: String,
+    format: &'static str,
+    target: Target,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let path = config
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Compact stage requires 'path' configuration"))?;
+
+    let path = Path::new(path);
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (root, pattern) = if file_name.contains(['*', '?']) {
+        let root = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        (root.to_path_buf(), Some(file_name))
+    } else {
+        (path.to_path_buf(), None)
+    };
+    if root.to_string_lossy().contains(['*', '?']) {
+        anyhow::bail!("Only the file name of 'path' may contain wildcards");
+    }
+
+    let format = match config.get("format") {
+        Some(value) => {
+            let format = value
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'format' must be a string"))?;
+            FORMATS.into_iter().find(|f| *f == format).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid format: {}. Must be 'parquet', 'csv', 'json', or 'jsonl'",
+                    format
+                )
+            })?
+        }
+        None => pattern
+            .as_deref()
+            .and_then(|p| p.rsplit_once('.'))
+            .and_then(|(_, ext)| FORMATS.into_iter().find(|f| *f == ext))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Compact stage requires 'format' unless 'path' ends in a \
+                    .parquet, .csv, .json or .jsonl pattern"
+                )
+            })?,
+    };
+    let pattern = pattern.unwrap_or_else(|| format!("*.{}", format));
+
+    let target = match (config.get("target_files"), config.get("target_file_size")) {
+        (Some(_), Some(_)) => {
+            anyhow::bail!("Set either 'target_files' or 'target_file_size', not both")
+        }
+        (Some(toml::Value::Integer(n)), None) if *n >= 1 => Target::Files(*n as usize),
+        (Some(_), None) => anyhow::bail!("'target_files' must be a positive integer"),
+        (None, Some(toml::Value::Integer(n))) if *n >= 1 => Target::FileSize(*n as u64),
+        (None, Some(_)) => anyhow::bail!("'target_file_size' must be a positive integer"),
+        (None, None) => Target::Files(1),
+    };
+
+    Ok(Options {
+        root,
+        pattern,
+        format,
+        target,
+    })
+}
+
+/// Match a file name against a pattern with `*` and `?` wildcards
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    let (mut n, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            n += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Part files matching `pattern` per directory under `root`
+///
+/// Hidden files and markers such as `_SUCCESS` are ignored. Directories are
+/// kept apart so partitions (e.g. `date=2024-01-01/`) are compacted separately.
+fn collect_parts(root: &Path, pattern: &str) -> Result<Vec<(PathBuf, Vec<PathBuf>)>> {
+    let mut groups = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut files = Vec::new();
+        let entries = std::fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read directory '{}'", dir.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name.starts_with('_') {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                pending.push(entry.path());
+            } else if matches_pattern(&name, pattern) {
+                files.push(entry.path());
+            }
+        }
+        if !files.is_empty() {
+            files.sort();
+            groups.push((dir, files));
+        }
+    }
+    groups.sort();
+    Ok(groups)
+}
+
+fn read_part(path: &Path, format: &str) -> Result<DataFrame> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open '{}'", path.display()))?;
+    let df = match format {
+        "parquet" => ParquetReader::new(file).finish()?,
+        "csv" => CsvReadOptions::default()
+            .with_has_header(true)
+            .into_reader_with_file_handle(file)
+            .finish()?,
+        "jsonl" => JsonReader::new(file)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish()?,
+        _ => JsonReader::new(file).finish()?,
+    };
+    Ok(df)
+}
+
+fn write_part(path: &Path, format: &str, df: &mut DataFrame) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create '{}'", path.display()))?;
+    match format {
+        "parquet" => {
+            ParquetWriter::new(file).finish(df)?;
+        }
+        "csv" => CsvWriter::new(file).include_header(true).finish(df)?,
+        "jsonl" => JsonWriter::new(file)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(df)?,
+        _ => JsonWriter::new(file)
+            .with_json_format(JsonFormat::Json)
+            .finish(df)?,
+    }
+    Ok(())
+}
+
+/// Rewrite the part files of one directory into the target number of files
+///
+/// The new files are written under hidden names first, so a failure part
+/// way leaves the original parts in place.
+fn compact_directory(dir: &Path, parts: &[PathBuf], options: &Options) -> Result<(usize, usize)> {
+    let frames = parts
+        .iter()
+        .map(|part| read_part(part, options.format))
+        .collect::<Result<Vec<_>>>()?;
+    let df = polars::functions::concat_df_diagonal(&frames)?;
+    let rows = df.height();
+
+    let files = match options.target {
+        Target::Files(n) => n,
+        Target::FileSize(size) => {
+            let total: u64 = parts
+                .iter()
+                .map(|part| std::fs::metadata(part).map(|m| m.len()))
+                .sum::<std::io::Result<u64>>()?;
+            total.div_ceil(size) as usize
+        }
+    }
+    .clamp(1, rows.max(1));
+
+    let rows_per_file = rows.div_ceil(files).max(1);
+    let mut written = Vec::with_capacity(files);
+    for index in 0..files {
+        let mut chunk = df.slice((index * rows_per_file) as i64, rows_per_file);
+        let name = format!("part-{:04}.{}", index + 1, options.format);
+        let staging = dir.join(format!(".{}.compacting", name));
+        if let Err(e) = write_part(&staging, options.format, &mut chunk) {
+            let _ = std::fs::remove_file(&staging);
+            for (staged, _) in &written {
+                let _ = std::fs::remove_file(staged);
+            }
+            return Err(e);
+        }
+        written.push((staging, dir.join(name)));
+    }
+
+    for part in parts {
+        std::fs::remove_file(part)
+            .with_context(|| format!("Failed to remove part file '{}'", part.display()))?;
+    }
+    for (staging, target) in &written {
+        std::fs::rename(staging, target)
+            .with_context(|| format!("Failed to write '{}'", target.display()))?;
+    }
+    Ok((files, rows))
+}
+
+#[async_trait]
+impl Stage for CompactSink {
+    fn name(&self) -> &str {
+        "compact.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "path".to_string(),
+            toml::Value::String("output/events/*.parquet".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "path".to_string(),
+            toml::Value::String("output/daily".to_string()),
+        );
+        example2.insert("format".to_string(), toml::Value::String("csv".to_string()));
+        example2.insert(
+            "target_file_size".to_string(),
+            toml::Value::Integer(128 * 1024 * 1024),
+        );
+
+        StageMetadata::builder("compact.apply", StageCategory::Sink)
+            .description("Coalesce many small output files into fewer large ones")
+            .long_description(
+                "Reads the part files under 'path' (a directory, or a directory and a file name \
+                pattern such as 'out/*.parquet') and rewrites them as part-0001, part-0002, ... \
+                files in the same format. Every directory below 'path' is compacted on its own, \
+                so partition directories stay intact. The number of files per directory is \
+                'target_files', or the total input size divided by 'target_file_size'; without \
+                either, each directory becomes one file. Place the stage after the sink that \
+                writes the parts; its input is ignored. It emits one row per compacted directory.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "Directory of part files, optionally ending in a file name pattern",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "format",
+                    ParameterType::String,
+                    "none",
+                    "File format (defaults to the extension of the pattern)",
+                )
+                .with_validation(ParameterValidation::allowed_values(FORMATS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "target_files",
+                ParameterType::Integer,
+                "1",
+                "Number of files per directory after compaction",
+            ))
+            .parameter(ConfigParameter::optional(
+                "target_file_size",
+                ParameterType::Integer,
+                "none",
+                "Approximate size in bytes of each file after compaction",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Merge Parquet parts",
+                example1,
+                Some("Rewrite every events part file into a single Parquet file"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Size-based compaction",
+                example2,
+                Some("Rewrite each partition of the daily CSV export into ~128 MiB files"),
+            ))
+            .tag("compact")
+            .tag("file")
+            .tag("maintenance")
+            .tag("sink")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        _inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let options = parse_options(config)?;
+
+        let mut summary = Vec::new();
+        for (dir, parts) in collect_parts(&options.root, &options.pattern)? {
+            let (files, rows) = compact_directory(&dir, &parts, &options)?;
+            info!(
+                "Compacted {} file(s) in '{}' into {} ({} rows)",
+                parts.len(),
+                dir.display(),
+                files,
+                rows
+            );
+            summary.push(HashMap::from([
+                (
+                    "directory".to_string(),
+                    json!(dir.to_string_lossy().to_string()),
+                ),
+                ("files_before".to_string(), json!(parts.len())),
+                ("files_after".to_string(), json!(files)),
+                ("rows".to_string(), json!(rows)),
+            ]));
+        }
+
+        Ok(DataFormat::RecordBatch(summary))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_parquet(path: &Path, ids: &[i64]) {
+        let mut df = df! { "id" => ids }.unwrap();
+        ParquetWriter::new(std::fs::File::create(path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_compacts_parquet_parts_into_one_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write_parquet(&dir.path().join("data-0001.parquet"), &[1, 2]);
+        write_parquet(&dir.path().join("data-0002.parquet"), &[3]);
+        write_parquet(&dir.path().join("data-0003.parquet"), &[4, 5, 6]);
+        std::fs::write(dir.path().join("_SUCCESS"), "").unwrap();
+
+        let config = HashMap::from([(
+            "path".to_string(),
+            toml::Value::String(dir.path().join("*.parquet").to_string_lossy().to_string()),
+        )]);
+        let summary = CompactSink
+            .execute(HashMap::new(), &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+
+        assert_eq!(
+            file_names(dir.path()),
+            vec!["_SUCCESS", "part-0001.parquet"]
+        );
+        let df = read_part(&dir.path().join("part-0001.parquet"), "parquet").unwrap();
+        let ids: Vec<i64> = df
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(summary[0]["files_before"], json!(3));
+        assert_eq!(summary[0]["rows"], json!(6));
+    }
+
+    #[tokio::test]
+    async fn test_partitions_are_compacted_separately() {
+        let dir = tempfile::tempdir().unwrap();
+        for (partition, parts) in [("day=1", 3), ("day=2", 2)] {
+            let partition = dir.path().join(partition);
+            std::fs::create_dir(&partition).unwrap();
+            for part in 0..parts {
+                std::fs::write(
+                    partition.join(format!("{}.csv", part)),
+                    format!("id\n{}\n{}\n", part * 2, part * 2 + 1),
+                )
+                .unwrap();
+            }
+        }
+
+        let config = HashMap::from([
+            (
+                "path".to_string(),
+                toml::Value::String(dir.path().to_string_lossy().to_string()),
+            ),
+            ("format".to_string(), toml::Value::String("csv".to_string())),
+            ("target_files".to_string(), toml::Value::Integer(2)),
+        ]);
+        CompactSink.execute(HashMap::new(), &config).await.unwrap();
+
+        assert_eq!(
+            file_names(&dir.path().join("day=1")),
+            vec!["part-0001.csv", "part-0002.csv"]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("day=1").join("part-0002.csv")).unwrap(),
+            "id\n3\n4\n5\n"
+        );
+        assert_eq!(
+            read_part(&dir.path().join("day=2").join("part-0001.csv"), "csv")
+                .unwrap()
+                .height(),
+            2
+        );
+
+        let mut both = config.clone();
+        both.insert("target_file_size".to_string(), toml::Value::Integer(1024));
+        let err = CompactSink.validate_config(&both).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Set either 'target_files' or 'target_file_size', not both"
+        );
+        assert!(matches_pattern("data-0001.parquet", "data-*.parquet"));
+        assert!(!matches_pattern("data.parquet.tmp", "*.parquet"));
+    }
+}
@@ -40,7 +40,7 @@ impl Stage for CsvSink {
             .parameter(ConfigParameter::required(
                 "path",
                 ParameterType::String,
-                "Path to the output CSV file"
+                "Path to the output CSV file; may contain {timestamp}, {date}, {counter} or {uuid}"
             ))
             .parameter(ConfigParameter::optional(
                 "headers",
@@ -61,6 +61,8 @@ impl Stage for CsvSink {
                 min_length: Some(1),
                 max_length: Some(1),
             }))
+            .parameters(super::encryption::parameters())
+            .parameters(super::rolling::parameters())
             .example(crate::core::metadata::ConfigExample::new(
                 "Standard CSV output",
                 example_config,
@@ -103,6 +105,17 @@ impl Stage for CsvSink {
             .and_then(|s| s.chars().next())
             .unwrap_or(',') as u8;
 
+        if super::rolling::is_templated(path) {
+            let encoder = CsvEncoder {
+                has_headers,
+                delimiter,
+            };
+            super::rolling::write(data, config, &encoder).await?;
+            return Ok(DataFormat::RecordBatch(vec![]));
+        }
+
+        let data = super::encryption::encrypt_for_write(data, config)?;
+
         let path_buf = PathBuf::from(path);
 
         // Create parent directory if it doesn't exist
@@ -140,6 +153,24 @@ impl Stage for CsvSink {
             }
         }
 
-        Ok(())
+        super::encryption::validate_config(config)?;
+        super::rolling::validate_config(config)
+    }
+}
+
+/// Writes CSV chunks for templated paths, with the header only at the top of each file
+struct CsvEncoder {
+    has_headers: bool,
+    delimiter: u8,
+}
+
+impl super::rolling::ChunkEncoder for CsvEncoder {
+    fn encode(&self, df: &mut DataFrame, first: bool) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        CsvWriter::new(&mut buffer)
+            .include_header(self.has_headers && first)
+            .with_separator(self.delimiter)
+            .finish(df)?;
+        Ok(buffer)
     }
 }
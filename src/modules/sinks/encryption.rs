@@ -0,0 +1,262 @@
+//! Column-level encryption applied by file sinks just before writing.
+//!
+//! Values are encrypted with the same algorithms and base64(nonce + ciphertext)
+//! encoding as `encrypt.apply`, so a written file can be read back and passed
+//! through `decrypt.apply`. Encrypted columns replace their plaintext in place.
+
+use anyhow::Result;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, ParameterValidation};
+use crate::core::traits::DataFormat;
+use crate::modules::transforms::encrypt::{check_key, encrypt_column};
+
+const DEFAULT_ALGORITHM: &str = "aes-256-gcm";
+
+/// Metadata parameters shared by every sink supporting `encrypt_columns`
+pub(crate) fn parameters() -> Vec<ConfigParameter> {
+    vec![
+        ConfigParameter::optional(
+            "encrypt_columns",
+            ParameterType::Array,
+            "none",
+            "Columns to encrypt at write time (string or array of strings)",
+        ),
+        ConfigParameter::optional(
+            "key",
+            ParameterType::String,
+            "none",
+            "Encryption key for encrypt_columns (16 bytes for AES-128, 32 bytes for AES-256/ChaCha20)",
+        ),
+        ConfigParameter::optional(
+            "encryption_algorithm",
+            ParameterType::String,
+            DEFAULT_ALGORITHM,
+            "Algorithm used for encrypt_columns",
+        )
+        .with_validation(ParameterValidation::allowed_values([
+            "aes-128-gcm",
+            "aes-256-gcm",
+            "chacha20-poly1305",
+        ])),
+    ]
+}
+
+fn parse_columns(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    match config.get("encrypt_columns") {
+        None => Ok(Vec::new()),
+        Some(toml::Value::String(s)) => Ok(vec![s.clone()]),
+        Some(toml::Value::Array(arr)) => arr
+            .iter()
+            .map(|v| {
+                v.as_str().map(|s| s.to_string()).ok_or_else(|| {
+                    anyhow::anyhow!("'encrypt_columns' must contain only column names")
+                })
+            })
+            .collect(),
+        Some(_) => anyhow::bail!("'encrypt_columns' must be a string or array of strings"),
+    }
+}
+
+fn algorithm(config: &HashMap<String, toml::Value>) -> &str {
+    config
+        .get("encryption_algorithm")
+        .and_then(|v| v.as_str())
+        .unwrap_or(DEFAULT_ALGORITHM)
+}
+
+/// Validate the encryption options of a sink config
+pub(crate) fn validate_config(config: &HashMap<String, toml::Value>) -> Result<()> {
+    let columns = parse_columns(config)?;
+    if columns.is_empty() {
+        return Ok(());
+    }
+
+    let key = config
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("'encrypt_columns' requires a 'key' configuration"))?;
+
+    // Keys still written as ${VAR} are resolved later; only check literal keys
+    if !key.starts_with("${") {
+        check_key(key, algorithm(config))?;
+    }
+
+    Ok(())
+}
+
+/// Encrypt the configured columns, returning the data unchanged when none are set
+pub(crate) fn encrypt_for_write(
+    data: DataFormat,
+    config: &HashMap<String, toml::Value>,
+) -> Result<DataFormat> {
+    let columns = parse_columns(config)?;
+    if columns.is_empty() {
+        return Ok(data);
+    }
+
+    let key = config
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("'encrypt_columns' requires a 'key' configuration"))?;
+    let algorithm = algorithm(config);
+
+    let mut df = data.as_dataframe()?;
+    for column in &columns {
+        // Non-string columns are encrypted from their string representation
+        let as_string = df
+            .column(column)
+            .map_err(|_| anyhow::anyhow!("Column '{}' not found for encryption", column))?
+            .cast(&DataType::String)?;
+        df.with_column(as_string)?;
+
+        encrypt_column(&mut df, column, key, algorithm, column)?;
+    }
+
+    Ok(DataFormat::DataFrame(df))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stage::Stage;
+    use crate::modules::sinks::{csv::CsvSink, json::JsonSink};
+    use crate::modules::sources::csv::CsvSource;
+    use crate::modules::transforms::decrypt::DecryptTransform;
+    use polars::df;
+    use tempfile::TempDir;
+
+    const KEY: &str = "12345678901234567890123456789012";
+
+    fn sink_config(path: &std::path::Path) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "path".to_string(),
+            toml::Value::String(path.to_string_lossy().to_string()),
+        );
+        config.insert(
+            "encrypt_columns".to_string(),
+            toml::Value::Array(vec![toml::Value::String("email".to_string())]),
+        );
+        config.insert("key".to_string(), toml::Value::String(KEY.to_string()));
+        config
+    }
+
+    fn sample() -> DataFormat {
+        DataFormat::DataFrame(
+            df! {
+                "id" => &[1i64, 2],
+                "email" => &["alice@example.com", "bob@example.com"],
+            }
+            .unwrap(),
+        )
+    }
+
+    async fn decrypt_email(data: DataFormat) -> Vec<String> {
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("email".to_string()),
+        );
+        config.insert("key".to_string(), toml::Value::String(KEY.to_string()));
+        config.insert("fail_on_error".to_string(), toml::Value::Boolean(true));
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), data);
+
+        let df = DecryptTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        df.column("email_decrypted")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_json_sink_encrypts_columns_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.json");
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), sample());
+        JsonSink.execute(inputs, &sink_config(&path)).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("alice@example.com"));
+
+        let records: Vec<HashMap<String, serde_json::Value>> =
+            serde_json::from_str(&written).unwrap();
+        // Unlisted columns stay plaintext
+        assert_eq!(records[0]["id"], serde_json::json!(1));
+        assert_ne!(records[0]["email"], serde_json::json!("alice@example.com"));
+
+        let decrypted = decrypt_email(DataFormat::RecordBatch(records)).await;
+        assert_eq!(decrypted, vec!["alice@example.com", "bob@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_csv_sink_encrypts_columns_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.csv");
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), sample());
+        CsvSink.execute(inputs, &sink_config(&path)).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("bob@example.com"));
+
+        let mut read_config = HashMap::new();
+        read_config.insert(
+            "path".to_string(),
+            toml::Value::String(path.to_string_lossy().to_string()),
+        );
+        let data = CsvSource
+            .execute(HashMap::new(), &read_config)
+            .await
+            .unwrap();
+
+        let ids: Vec<i64> = data
+            .as_dataframe()
+            .unwrap()
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        let decrypted = decrypt_email(data).await;
+        assert_eq!(decrypted, vec!["alice@example.com", "bob@example.com"]);
+    }
+
+    #[test]
+    fn test_encryption_config_validation() {
+        let mut config = HashMap::new();
+        assert!(validate_config(&config).is_ok());
+
+        config.insert(
+            "encrypt_columns".to_string(),
+            toml::Value::String("email".to_string()),
+        );
+        assert!(validate_config(&config).is_err());
+
+        config.insert("key".to_string(), toml::Value::String("short".to_string()));
+        assert!(validate_config(&config).is_err());
+
+        config.insert(
+            "key".to_string(),
+            toml::Value::String("${ENCRYPTION_KEY}".to_string()),
+        );
+        assert!(validate_config(&config).is_ok());
+    }
+}
@@ -53,7 +53,7 @@ impl Stage for JsonSink {
             .parameter(ConfigParameter::required(
                 "path",
                 ParameterType::String,
-                "Path to the output JSON file"
+                "Path to the output JSON file; may contain {timestamp}, {date}, {counter} or {uuid}"
             ))
             .parameter(ConfigParameter::optional(
                 "format",
@@ -69,6 +69,8 @@ impl Stage for JsonSink {
                 "false",
                 "Pretty-print the JSON output (not applicable to jsonl format)"
             ))
+            .parameters(super::encryption::parameters())
+            .parameters(super::rolling::parameters())
             .example(crate::core::metadata::ConfigExample::new(
                 "Standard JSON output",
                 example1,
@@ -114,6 +116,17 @@ impl Stage for JsonSink {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        if super::rolling::is_templated(path) {
+            let encoder = JsonEncoder {
+                lines: format == "jsonl",
+                pretty,
+            };
+            super::rolling::write(data, config, &encoder).await?;
+            return Ok(DataFormat::RecordBatch(vec![]));
+        }
+
+        let data = super::encryption::encrypt_for_write(data, config)?;
+
         let path_buf = PathBuf::from(path);
 
         // Create parent directory if it doesn't exist
@@ -198,6 +211,7 @@ impl Stage for JsonSink {
 
         let mut file = fs::File::create(&path_buf).await?;
         file.write_all(output.as_bytes()).await?;
+        file.flush().await?;
 
         let row_count = match &data {
             DataFormat::DataFrame(df) => df.height(),
@@ -230,6 +244,51 @@ impl Stage for JsonSink {
             }
         }
 
-        Ok(())
+        let templated = config
+            .get("path")
+            .and_then(|v| v.as_str())
+            .is_some_and(super::rolling::is_templated);
+        if templated && config.get("format").and_then(|v| v.as_str()) == Some("dataframe") {
+            anyhow::bail!("Templated paths support the 'records' and 'jsonl' formats only");
+        }
+
+        super::encryption::validate_config(config)?;
+        super::rolling::validate_config(config)
+    }
+}
+
+/// Writes `records` or `jsonl` chunks for templated paths
+///
+/// Records files are a single array spanning every chunk written to the file.
+struct JsonEncoder {
+    lines: bool,
+    pretty: bool,
+}
+
+impl super::rolling::ChunkEncoder for JsonEncoder {
+    fn encode(&self, df: &mut DataFrame, first: bool) -> Result<Vec<u8>> {
+        let records = DataFormat::DataFrame(df.clone()).as_record_batch()?;
+        let encoded = records
+            .iter()
+            .map(|record| {
+                if self.pretty && !self.lines {
+                    serde_json::to_string_pretty(record)
+                } else {
+                    serde_json::to_string(record)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (separator, open) = if self.lines { ("\n", "") } else { (",", "[") };
+        let prefix = if first { open } else { separator };
+        Ok(format!("{}{}", prefix, encoded.join(separator)).into_bytes())
+    }
+
+    fn finish(&self) -> &'static [u8] {
+        if self.lines {
+            b""
+        } else {
+            b"]"
+        }
     }
 }
@@ -0,0 +1,333 @@
+//! Versioned materialized views.
+//!
+//! `materialize.write` stores each output as a new Arrow IPC version file in
+//! `<path>/<name>/` and then points the `CURRENT` file at it, and
+//! `materialize.read` loads whatever `CURRENT` points to. Both the version
+//! file and the pointer are written to a temporary file and renamed into
+//! place, so a reader sees either the previous view or the new one, never a
+//! partial file, and a failed write leaves the previous version current.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use polars::io::ipc::{IpcReader, IpcWriter};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct MaterializeSink;
+
+const POINTER: &str = "CURRENT";
+
+/// The directory of the view named in `config`
+pub(crate) fn view_dir(config: &HashMap<String, toml::Value>, stage: &str) -> Result<PathBuf> {
+    let path = config
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("{} requires 'path' configuration", stage))?;
+    let name = config
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("{} requires 'name' configuration", stage))?;
+    if name.is_empty()
+        || name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        anyhow::bail!(
+            "Invalid view name: '{}'. Use letters, digits, '_', '-' and '.'",
+            name
+        );
+    }
+    Ok(Path::new(path).join(name))
+}
+
+pub(crate) fn version_file(version: u64) -> String {
+    format!("v{:06}.arrow", version)
+}
+
+fn parse_version(file: &str) -> Option<u64> {
+    file.strip_prefix('v')?.strip_suffix(".arrow")?.parse().ok()
+}
+
+/// Versions stored in `dir`, oldest first
+fn versions(dir: &Path) -> Result<Vec<u64>> {
+    let mut versions = Vec::new();
+    if dir.exists() {
+        for entry in std::fs::read_dir(dir)? {
+            if let Some(version) = entry?.file_name().to_str().and_then(parse_version) {
+                versions.push(version);
+            }
+        }
+    }
+    versions.sort_unstable();
+    Ok(versions)
+}
+
+/// The version file `CURRENT` points to, if the view has been written
+pub(crate) fn current_file(dir: &Path) -> Result<Option<PathBuf>> {
+    let pointer = dir.join(POINTER);
+    if !pointer.exists() {
+        return Ok(None);
+    }
+    let file = std::fs::read_to_string(&pointer)
+        .with_context(|| format!("Failed to read '{}'", pointer.display()))?;
+    Ok(Some(dir.join(file.trim())))
+}
+
+pub(crate) fn read_view(file: &Path) -> Result<DataFrame> {
+    let handle = std::fs::File::open(file)
+        .with_context(|| format!("Failed to open view version '{}'", file.display()))?;
+    Ok(IpcReader::new(handle).finish()?)
+}
+
+/// Write `contents` to `target` through a temporary file renamed into place
+fn write_atomic(
+    target: &Path,
+    contents: impl FnOnce(&mut std::fs::File) -> Result<()>,
+) -> Result<()> {
+    let file_name = target.file_name().unwrap().to_string_lossy();
+    let staging = target.with_file_name(format!(".{}.tmp", file_name));
+    let result = std::fs::File::create(&staging)
+        .with_context(|| format!("Failed to create '{}'", staging.display()))
+        .and_then(|mut file| {
+            contents(&mut file)?;
+            file.sync_all()?;
+            Ok(())
+        })
+        .and_then(|_| {
+            std::fs::rename(&staging, target)
+                .with_context(|| format!("Failed to move '{}' into place", target.display()))
+        });
+    if result.is_err() && staging.is_file() {
+        let _ = std::fs::remove_file(&staging);
+    }
+    result
+}
+
+/// Store `df` as the next version of the view in `dir` and make it current,
+/// keeping the newest `keep` versions
+fn publish(dir: &Path, df: &mut DataFrame, keep: usize) -> Result<u64> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create view directory '{}'", dir.display()))?;
+
+    let existing = versions(dir)?;
+    let version = existing.last().map_or(1, |v| v + 1);
+    let file = version_file(version);
+
+    write_atomic(&dir.join(&file), |handle| {
+        IpcWriter::new(handle).finish(df)?;
+        Ok(())
+    })?;
+    write_atomic(&dir.join(POINTER), |handle| {
+        handle.write_all(file.as_bytes())?;
+        Ok(())
+    })?;
+
+    // Older versions stay around for readers that resolved the pointer before the swap
+    let expired = (existing.len() + 1).saturating_sub(keep);
+    for old in existing.into_iter().take(expired) {
+        let path = dir.join(version_file(old));
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!(
+                "Failed to remove old view version '{}': {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    Ok(version)
+}
+
+fn keep_versions(config: &HashMap<String, toml::Value>) -> Result<usize> {
+    match config.get("keep_versions") {
+        None => Ok(3),
+        Some(toml::Value::Integer(n)) if *n >= 1 => Ok(*n as usize),
+        Some(_) => anyhow::bail!("'keep_versions' must be a positive integer"),
+    }
+}
+
+#[async_trait]
+impl Stage for MaterializeSink {
+    fn name(&self) -> &str {
+        "materialize.write"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example_config = HashMap::new();
+        example_config.insert("path".to_string(), toml::Value::String("views".to_string()));
+        example_config.insert(
+            "name".to_string(),
+            toml::Value::String("daily_revenue".to_string()),
+        );
+        example_config.insert("keep_versions".to_string(), toml::Value::Integer(5));
+
+        StageMetadata::builder("materialize.write", StageCategory::Sink)
+            .description("Publish the input as the current version of a materialized view")
+            .long_description(
+                "Stores the input as a new version of the view 'name' under 'path' (an Arrow \
+                IPC file per version) and then swaps the view's CURRENT pointer to it. Both \
+                steps write a temporary file and rename it into place, so materialize.read in \
+                another pipeline always loads a complete version, and a failed write leaves the \
+                previous version current. The newest 'keep_versions' versions are kept.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "Directory holding the views",
+            ))
+            .parameter(ConfigParameter::required(
+                "name",
+                ParameterType::String,
+                "Name of the view",
+            ))
+            .parameter(ConfigParameter::optional(
+                "keep_versions",
+                ParameterType::Integer,
+                "3",
+                "Number of versions to keep, including the current one",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Dashboard view",
+                example_config,
+                Some("Publish the latest daily revenue for dashboards to read"),
+            ))
+            .tag("materialize")
+            .tag("view")
+            .tag("arrow")
+            .tag("sink")
+            .build()
+    }
+
+    fn produces_output(&self) -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Materialize sink requires input data"))?;
+        let dir = view_dir(config, "Materialize sink")?;
+        let keep = keep_versions(config)?;
+
+        let mut df = data.as_dataframe()?;
+        let (version, rows) = tokio::task::spawn_blocking({
+            let dir = dir.clone();
+            move || publish(&dir, &mut df, keep).map(|version| (version, df.height()))
+        })
+        .await??;
+
+        info!(
+            "Published {} rows as version {} of view '{}'",
+            rows,
+            version,
+            dir.display()
+        );
+
+        // Sinks return empty RecordBatch
+        Ok(DataFormat::RecordBatch(vec![]))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        view_dir(config, "Materialize sink")?;
+        keep_versions(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::sources::materialize::MaterializeSource;
+
+    fn config(dir: &Path) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            (
+                "path".to_string(),
+                toml::Value::String(dir.to_string_lossy().to_string()),
+            ),
+            (
+                "name".to_string(),
+                toml::Value::String("revenue".to_string()),
+            ),
+            ("keep_versions".to_string(), toml::Value::Integer(2)),
+        ])
+    }
+
+    fn revenue(day: &str, total: f64) -> DataFrame {
+        df! { "day" => &[day], "total" => &[total] }.unwrap()
+    }
+
+    async fn write(config: &HashMap<String, toml::Value>, df: DataFrame) -> Result<DataFormat> {
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        MaterializeSink.execute(inputs, config).await
+    }
+
+    async fn read(config: &HashMap<String, toml::Value>) -> DataFrame {
+        MaterializeSource
+            .execute(HashMap::new(), config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_returns_latest_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path());
+
+        let err = match MaterializeSource.execute(HashMap::new(), &config).await {
+            Ok(_) => panic!("expected a missing view error"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.starts_with("Materialized view 'revenue' not found"));
+
+        for (day, total) in [("mon", 10.0), ("tue", 12.5), ("wed", 9.0)] {
+            write(&config, revenue(day, total)).await.unwrap();
+            assert!(read(&config).await.equals(&revenue(day, total)));
+        }
+
+        // Only the newest two versions are kept
+        let view = dir.path().join("revenue");
+        assert_eq!(versions(&view).unwrap(), vec![2, 3]);
+
+        let mut pinned = config.clone();
+        pinned.insert("version".to_string(), toml::Value::Integer(2));
+        assert!(read(&pinned).await.equals(&revenue("tue", 12.5)));
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_keeps_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path());
+        write(&config, revenue("mon", 10.0)).await.unwrap();
+
+        // Block the staging file of the next version so the write fails
+        let view = dir.path().join("revenue");
+        std::fs::create_dir(view.join(format!(".{}.tmp", version_file(2)))).unwrap();
+        assert!(write(&config, revenue("tue", 12.5)).await.is_err());
+
+        assert!(read(&config).await.equals(&revenue("mon", 10.0)));
+        assert_eq!(versions(&view).unwrap(), vec![1]);
+
+        let mut invalid = config.clone();
+        invalid.insert(
+            "name".to_string(),
+            toml::Value::String("../escape".to_string()),
+        );
+        assert!(MaterializeSink.validate_config(&invalid).await.is_err());
+    }
+}
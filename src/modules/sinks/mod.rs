@@ -1,4 +1,10 @@
+pub mod compact;
 pub mod csv;
+pub mod encryption;
 pub mod json;
+pub mod materialize;
+pub mod parquet;
+pub mod rolling;
+pub mod schema;
 pub mod stdout;
 pub mod stdout_stream;
@@ -0,0 +1,268 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct ParquetSink;
+
+const COMPRESSIONS: [&str; 4] = ["snappy", "zstd", "gzip", "none"];
+
+fn compression(config: &HashMap<String, toml::Value>) -> Result<ParquetCompression> {
+    let name = match config.get("compression") {
+        None => "snappy",
+        Some(value) => value
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("'compression' must be a string"))?,
+    };
+    Ok(match name {
+        "snappy" => ParquetCompression::Snappy,
+        "zstd" => ParquetCompression::Zstd(None),
+        "gzip" => ParquetCompression::Gzip(None),
+        "none" => ParquetCompression::Uncompressed,
+        _ => anyhow::bail!(
+            "Invalid compression: {}. Must be 'snappy', 'zstd', 'gzip', or 'none'",
+            name
+        ),
+    })
+}
+
+#[async_trait]
+impl Stage for ParquetSink {
+    fn name(&self) -> &str {
+        "parquet.write"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example_config = HashMap::new();
+        example_config.insert(
+            "path".to_string(),
+            toml::Value::String("lake/events.parquet".to_string()),
+        );
+        example_config.insert(
+            "compression".to_string(),
+            toml::Value::String("zstd".to_string()),
+        );
+
+        StageMetadata::builder("parquet.write", StageCategory::Sink)
+            .description("Write data to Parquet files")
+            .long_description(
+                "Writes the input DataFrame to a Parquet file, keeping its column types. \
+                Supports snappy, zstd and gzip compression or none. \
+                Automatically creates parent directories if they don't exist.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "Path to the output Parquet file",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "compression",
+                    ParameterType::String,
+                    "snappy",
+                    "Compression codec for the column data",
+                )
+                .with_validation(ParameterValidation::allowed_values(COMPRESSIONS)),
+            )
+            .parameters(super::encryption::parameters())
+            .example(crate::core::metadata::ConfigExample::new(
+                "Compressed Parquet output",
+                example_config,
+                Some("Write data to a zstd-compressed Parquet file"),
+            ))
+            .tag("parquet")
+            .tag("file")
+            .tag("io")
+            .tag("sink")
+            .build()
+    }
+
+    fn produces_output(&self) -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Parquet sink requires input data"))?;
+        let path = config
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Parquet sink requires 'path' configuration"))?;
+        let compression = compression(config)?;
+
+        let data = super::encryption::encrypt_for_write(data, config)?;
+
+        let path_buf = PathBuf::from(path);
+
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = path_buf.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut df = data.as_dataframe()?;
+
+        let file = std::fs::File::create(&path_buf)?;
+        ParquetWriter::new(file)
+            .with_compression(compression)
+            .finish(&mut df)?;
+
+        tracing::info!("Written {} rows to Parquet file: {}", df.height(), path);
+
+        // Sinks return empty RecordBatch
+        Ok(DataFormat::RecordBatch(vec![]))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if !config.contains_key("path") {
+            anyhow::bail!("Parquet sink requires 'path' configuration");
+        }
+        compression(config)?;
+        super::encryption::validate_config(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::sources::parquet::ParquetSource;
+    use crate::modules::sources::projection::Projection;
+
+    fn events() -> DataFrame {
+        df! {
+            "id" => &[1i64, 2, 3],
+            "score" => &[Some(0.5), None, Some(2.25)],
+            "event" => &["click", "view", "click"],
+            "active" => &[true, false, true],
+        }
+        .unwrap()
+    }
+
+    fn path_config(path: &std::path::Path) -> HashMap<String, toml::Value> {
+        HashMap::from([(
+            "path".to_string(),
+            toml::Value::String(path.to_string_lossy().to_string()),
+        )])
+    }
+
+    async fn read(config: &HashMap<String, toml::Value>) -> DataFrame {
+        ParquetSource
+            .execute(HashMap::new(), config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_parquet_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        for codec in COMPRESSIONS {
+            let mut config = path_config(&dir.path().join(format!("nested/{}.parquet", codec)));
+            config.insert(
+                "compression".to_string(),
+                toml::Value::String(codec.to_string()),
+            );
+            let inputs = HashMap::from([("events".to_string(), DataFormat::DataFrame(events()))]);
+            ParquetSink.execute(inputs, &config).await.unwrap();
+
+            let df = read(&config).await;
+            assert_eq!(df.schema(), events().schema(), "schema with {}", codec);
+            assert!(df.equals_missing(&events()), "values with {}", codec);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parquet_read_projection_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = path_config(&dir.path().join("events.parquet"));
+        let inputs = HashMap::from([("events".to_string(), DataFormat::DataFrame(events()))]);
+        ParquetSink.execute(inputs, &config).await.unwrap();
+
+        config.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("event".to_string()),
+                toml::Value::String("id".to_string()),
+            ]),
+        );
+        config.insert("n_rows".to_string(), toml::Value::Integer(2));
+        let df = read(&config).await;
+        assert_eq!(df.get_column_names(), vec!["event", "id"]);
+        assert!(df.equals(&events().select(["event", "id"]).unwrap().head(Some(2))));
+
+        config.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![toml::Value::String("missing".to_string())]),
+        );
+        let err = match ParquetSource.execute(HashMap::new(), &config).await {
+            Ok(_) => panic!("expected a missing column error"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.starts_with("Parquet source: column 'missing' not found"));
+
+        config.insert(
+            "compression".to_string(),
+            toml::Value::String("lz4".to_string()),
+        );
+        assert!(ParquetSink.validate_config(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parquet_read_pushes_projection_and_renames() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.parquet");
+        let mut config = path_config(&path);
+        let inputs = HashMap::from([("events".to_string(), DataFormat::DataFrame(events()))]);
+        ParquetSink.execute(inputs, &config).await.unwrap();
+
+        config.extend(
+            toml::from_str::<HashMap<String, toml::Value>>(
+                r#"
+columns = ["event", "id"]
+rename = { event = "kind" }
+"#,
+            )
+            .unwrap(),
+        );
+        let df = read(&config).await;
+        assert_eq!(df.get_column_names(), vec!["kind", "id"]);
+        assert_eq!(
+            df.column("kind").unwrap().str().unwrap().get(1),
+            Some("view")
+        );
+
+        // Only the selected columns are read from the file
+        let lf = Projection::from_config(&config)
+            .unwrap()
+            .apply_lazy(
+                LazyFrame::scan_parquet(&path, ScanArgsParquet::default()).unwrap(),
+                "Parquet source",
+            )
+            .unwrap();
+        let plan = lf.describe_optimized_plan().unwrap();
+        assert!(plan.contains("PROJECT 2/4 COLUMNS"), "{}", plan);
+
+        config.insert(
+            "rename".to_string(),
+            toml::from_str::<toml::Table>("r = { score = \"points\" }").unwrap()["r"].clone(),
+        );
+        let err = match ParquetSource.execute(HashMap::new(), &config).await {
+            Ok(_) => panic!("expected a rename of an unselected column to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(err, "Parquet source: cannot rename missing column 'score'");
+    }
+}
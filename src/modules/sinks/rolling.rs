@@ -0,0 +1,406 @@
+//! Templated output paths and rolling files for file sinks.
+//!
+//! A sink `path` may contain `{timestamp}`, `{date}`, `{counter}` and `{uuid}`
+//! (e.g. `out/data-{date}-{counter}.csv`). Such a path is resolved again for
+//! every file written: each stream batch, or the whole input for batch data,
+//! goes to a new file. With `rollover_records` or `rollover_bytes`, records are
+//! instead appended to the current file until the limit is reached.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+
+use crate::core::metadata::{ConfigParameter, ParameterType};
+use crate::core::traits::DataFormat;
+
+const TOKENS: [&str; 4] = ["{timestamp}", "{date}", "{counter}", "{uuid}"];
+
+/// Metadata parameters shared by every sink supporting templated paths
+pub(crate) fn parameters() -> Vec<ConfigParameter> {
+    vec![
+        ConfigParameter::optional(
+            "rollover_records",
+            ParameterType::Integer,
+            "none",
+            "Start a new file after this many records (path must contain {counter} or {uuid})",
+        ),
+        ConfigParameter::optional(
+            "rollover_bytes",
+            ParameterType::Integer,
+            "none",
+            "Start a new file once the current one reaches this many bytes",
+        ),
+    ]
+}
+
+/// Whether `path` contains a token that is resolved per file
+pub(crate) fn is_templated(path: &str) -> bool {
+    TOKENS.iter().any(|token| path.contains(token))
+}
+
+/// Resolve the tokens of a path template for the `counter`-th file
+pub(crate) fn render_path(template: &str, counter: usize, now: DateTime<Utc>) -> String {
+    let mut path = template
+        .replace("{timestamp}", &now.format("%Y%m%dT%H%M%S").to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{counter}", &format!("{:04}", counter));
+    while path.contains("{uuid}") {
+        path = path.replacen("{uuid}", &uuid::Uuid::new_v4().to_string(), 1);
+    }
+    path
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Rollover {
+    records: Option<usize>,
+    bytes: Option<u64>,
+}
+
+impl Rollover {
+    fn is_set(&self) -> bool {
+        self.records.is_some() || self.bytes.is_some()
+    }
+}
+
+fn positive(config: &HashMap<String, toml::Value>, name: &str) -> Result<Option<i64>> {
+    match config.get(name) {
+        None => Ok(None),
+        Some(toml::Value::Integer(n)) if *n > 0 => Ok(Some(*n)),
+        Some(_) => anyhow::bail!("'{}' must be a positive integer", name),
+    }
+}
+
+fn parse_rollover(config: &HashMap<String, toml::Value>) -> Result<Rollover> {
+    Ok(Rollover {
+        records: positive(config, "rollover_records")?.map(|n| n as usize),
+        bytes: positive(config, "rollover_bytes")?.map(|n| n as u64),
+    })
+}
+
+/// Validate the path template and rollover options of a sink config
+pub(crate) fn validate_config(config: &HashMap<String, toml::Value>) -> Result<()> {
+    let rollover = parse_rollover(config)?;
+    if !rollover.is_set() {
+        return Ok(());
+    }
+
+    let path = config.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    if !path.contains("{counter}") && !path.contains("{uuid}") {
+        anyhow::bail!(
+            "'rollover_records' and 'rollover_bytes' need {{counter}} or {{uuid}} in 'path' \
+            so each file gets its own name"
+        );
+    }
+    Ok(())
+}
+
+/// How a sink turns chunks of rows into file contents
+pub(crate) trait ChunkEncoder: Send + Sync {
+    /// Encode `df`; `first` is true for the first chunk written to a file
+    fn encode(&self, df: &mut DataFrame, first: bool) -> Result<Vec<u8>>;
+
+    /// Bytes closing a file
+    fn finish(&self) -> &'static [u8] {
+        b""
+    }
+}
+
+struct OpenFile {
+    file: tokio::fs::File,
+    records: usize,
+    bytes: u64,
+}
+
+struct RollingFiles<'a> {
+    template: &'a str,
+    encoder: &'a dyn ChunkEncoder,
+    rollover: Rollover,
+    counter: usize,
+    current: Option<OpenFile>,
+    written: Vec<String>,
+}
+
+impl RollingFiles<'_> {
+    async fn open(&mut self) -> Result<()> {
+        self.counter += 1;
+        let path = render_path(self.template, self.counter, Utc::now());
+        if self.written.contains(&path) {
+            anyhow::bail!(
+                "Output path '{}' was already written by this stage; add {{counter}} or {{uuid}} \
+                to 'path' so every file gets its own name",
+                path
+            );
+        }
+
+        if let Some(parent) = Path::new(&path).parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = tokio::fs::File::create(&path).await?;
+        self.current = Some(OpenFile {
+            file,
+            records: 0,
+            bytes: 0,
+        });
+        self.written.push(path);
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if let Some(mut open) = self.current.take() {
+            open.file.write_all(self.encoder.finish()).await?;
+            open.file.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, df: DataFrame) -> Result<()> {
+        let mut offset = 0;
+        while offset < df.height() {
+            if self.current.is_none() {
+                self.open().await?;
+            }
+            let open = self.current.as_mut().expect("file was just opened");
+
+            let room = self
+                .rollover
+                .records
+                .map_or(usize::MAX, |max| max - open.records);
+            let take = room.min(df.height() - offset);
+            let mut slice = df.slice(offset as i64, take);
+            let bytes = self.encoder.encode(&mut slice, open.records == 0)?;
+            open.file.write_all(&bytes).await?;
+            open.records += take;
+            open.bytes += bytes.len() as u64;
+            offset += take;
+
+            let full = self.rollover.records.is_some_and(|max| open.records >= max)
+                || self.rollover.bytes.is_some_and(|max| open.bytes >= max);
+            if full {
+                self.close().await?;
+            }
+        }
+
+        // Without a rollover policy every flush gets its own file
+        if !self.rollover.is_set() {
+            self.close().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `data` to the files named by the `path` template, returning the rows written
+///
+/// `encrypt_columns` is applied to each chunk before it is encoded.
+pub(crate) async fn write(
+    data: DataFormat,
+    config: &HashMap<String, toml::Value>,
+    encoder: &dyn ChunkEncoder,
+) -> Result<usize> {
+    let template = config
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("File sink requires 'path' configuration"))?;
+    let mut files = RollingFiles {
+        template,
+        encoder,
+        rollover: parse_rollover(config)?,
+        counter: 0,
+        current: None,
+        written: Vec::new(),
+    };
+
+    let encrypt = |df: DataFrame| -> Result<DataFrame> {
+        super::encryption::encrypt_for_write(DataFormat::DataFrame(df), config)?.as_dataframe()
+    };
+
+    let mut rows = 0;
+    match data {
+        DataFormat::Stream(mut stream) => {
+            while let Some(batch) = stream.next().await {
+                let df = DataFormat::RecordBatch(batch?).as_dataframe()?;
+                rows += df.height();
+                files.write_chunk(encrypt(df)?).await?;
+            }
+        }
+        data => {
+            let df = data.as_dataframe()?;
+            rows += df.height();
+            files.write_chunk(encrypt(df)?).await?;
+        }
+    }
+    files.close().await?;
+
+    tracing::info!(
+        "Written {} rows to {} file(s): {}",
+        rows,
+        files.written.len(),
+        files.written.join(", ")
+    );
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stage::Stage;
+    use crate::modules::sinks::{csv::CsvSink, json::JsonSink};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// A stream of `batches` batches of `size` records each, numbered from 1
+    fn stream(batches: usize, size: usize) -> DataFormat {
+        let items: Vec<Result<_>> = (0..batches)
+            .map(|b| {
+                Ok((1..=size)
+                    .map(|i| {
+                        let mut record = HashMap::new();
+                        record.insert("id".to_string(), json!((b * size + i) as i64));
+                        record
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .collect();
+        DataFormat::Stream(Box::pin(tokio_stream::iter(items)))
+    }
+
+    fn sink_config(template: &str, rollover: Option<(&str, i64)>) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "path".to_string(),
+            toml::Value::String(template.to_string()),
+        );
+        if let Some((name, limit)) = rollover {
+            config.insert(name.to_string(), toml::Value::Integer(limit));
+        }
+        config
+    }
+
+    fn sorted_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_render_path_tokens() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T12:30:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            render_path("out/data-{date}-{counter}.csv", 1, now),
+            "out/data-2024-01-01-0001.csv"
+        );
+        assert_eq!(
+            render_path("out/{timestamp}.json", 12, now),
+            "out/20240101T123005.json"
+        );
+
+        let with_uuid = render_path("{uuid}-{uuid}.csv", 1, now);
+        let (first, second) = with_uuid.trim_end_matches(".csv").split_at(36);
+        assert_ne!(first, &second[1..]);
+        assert!(!is_templated("out/data.csv"));
+    }
+
+    #[tokio::test]
+    async fn test_counter_template_writes_file_per_batch() {
+        let dir = TempDir::new().unwrap();
+        let template = dir.path().join("data-{counter}.csv");
+        let config = sink_config(&template.to_string_lossy(), None);
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), stream(2, 3));
+        CsvSink.execute(inputs, &config).await.unwrap();
+
+        assert_eq!(
+            sorted_files(dir.path()),
+            vec!["data-0001.csv", "data-0002.csv"]
+        );
+        let second = std::fs::read_to_string(dir.path().join("data-0002.csv")).unwrap();
+        assert_eq!(second.trim(), "id\n4\n5\n6");
+    }
+
+    #[tokio::test]
+    async fn test_rollover_by_records_and_bytes() {
+        let dir = TempDir::new().unwrap();
+        let template = dir.path().join("part-{counter}.jsonl");
+        let mut config = sink_config(&template.to_string_lossy(), Some(("rollover_records", 4)));
+        config.insert(
+            "format".to_string(),
+            toml::Value::String("jsonl".to_string()),
+        );
+
+        // Three batches of three records roll over every four records
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), stream(3, 3));
+        JsonSink.execute(inputs, &config).await.unwrap();
+
+        let files = sorted_files(dir.path());
+        assert_eq!(
+            files,
+            vec!["part-0001.jsonl", "part-0002.jsonl", "part-0003.jsonl"]
+        );
+        let lines: Vec<usize> = files
+            .iter()
+            .map(|f| {
+                std::fs::read_to_string(dir.path().join(f))
+                    .unwrap()
+                    .lines()
+                    .count()
+            })
+            .collect();
+        assert_eq!(lines, vec![4, 4, 1]);
+
+        // A 10-byte limit is reached by the first batch of each file
+        let dir = TempDir::new().unwrap();
+        let template = dir.path().join("part-{counter}.json");
+        let config = sink_config(&template.to_string_lossy(), Some(("rollover_bytes", 10)));
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), stream(2, 2));
+        JsonSink.execute(inputs, &config).await.unwrap();
+
+        let first = std::fs::read_to_string(dir.path().join("part-0001.json")).unwrap();
+        let records: Vec<serde_json::Value> = serde_json::from_str(&first).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(sorted_files(dir.path()).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_template_validation() {
+        assert!(validate_config(&sink_config("out/data.csv", None)).is_ok());
+        assert!(validate_config(&sink_config(
+            "out/{counter}.csv",
+            Some(("rollover_records", 100))
+        ))
+        .is_ok());
+        assert!(validate_config(&sink_config(
+            "out/{date}.csv",
+            Some(("rollover_bytes", 1024))
+        ))
+        .is_err());
+        assert!(validate_config(&sink_config(
+            "out/{counter}.csv",
+            Some(("rollover_records", 0))
+        ))
+        .is_err());
+
+        // A date-only template can't hold more than one flush
+        let dir = TempDir::new().unwrap();
+        let template = dir.path().join("data-{date}.csv");
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), stream(2, 1));
+        let err = CsvSink
+            .execute(inputs, &sink_config(&template.to_string_lossy(), None))
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("was already written by this stage"));
+    }
+}
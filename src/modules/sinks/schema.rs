@@ -0,0 +1,360 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use polars::prelude::*;
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct SchemaSink;
+
+const FORMATS: [&str; 3] = ["json_schema", "avro", "columns"];
+
+/// JSON Schema type for a polars type
+fn json_schema_type(dtype: &DataType) -> JsonValue {
+    match dtype {
+        DataType::Boolean => json!({ "type": "boolean" }),
+        dtype if dtype.is_integer() => json!({ "type": "integer" }),
+        dtype if dtype.is_float() => json!({ "type": "number" }),
+        DataType::Date => json!({ "type": "string", "format": "date" }),
+        DataType::Datetime(_, _) => json!({ "type": "string", "format": "date-time" }),
+        DataType::Time => json!({ "type": "string", "format": "time" }),
+        DataType::Null => json!({ "type": "null" }),
+        DataType::List(inner) => json!({ "type": "array", "items": json_schema_type(inner) }),
+        DataType::Struct(fields) => json!({
+            "type": "object",
+            "properties": fields
+                .iter()
+                .map(|f| (f.name().to_string(), json_schema_type(f.dtype())))
+                .collect::<Map<_, _>>(),
+        }),
+        _ => json!({ "type": "string" }),
+    }
+}
+
+/// Avro type for a polars type
+fn avro_type(name: &str, dtype: &DataType) -> JsonValue {
+    match dtype {
+        DataType::Boolean => json!("boolean"),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::UInt8 | DataType::UInt16 => {
+            json!("int")
+        }
+        dtype if dtype.is_integer() => json!("long"),
+        DataType::Float32 => json!("float"),
+        DataType::Float64 => json!("double"),
+        DataType::Binary => json!("bytes"),
+        DataType::Null => json!("null"),
+        DataType::Date => json!({ "type": "int", "logicalType": "date" }),
+        DataType::Datetime(unit, _) => {
+            let logical = match unit {
+                TimeUnit::Milliseconds => "timestamp-millis",
+                TimeUnit::Microseconds => "timestamp-micros",
+                TimeUnit::Nanoseconds => "timestamp-nanos",
+            };
+            json!({ "type": "long", "logicalType": logical })
+        }
+        DataType::Time => json!({ "type": "long", "logicalType": "time-micros" }),
+        DataType::List(inner) => json!({ "type": "array", "items": avro_type(name, inner) }),
+        DataType::Struct(fields) => json!({
+            "type": "record",
+            "name": name,
+            "fields": fields
+                .iter()
+                .map(|f| json!({ "name": f.name().as_str(), "type": avro_type(f.name(), f.dtype()) }))
+                .collect::<Vec<_>>(),
+        }),
+        _ => json!("string"),
+    }
+}
+
+/// Document describing `df` in `format`
+///
+/// A column is nullable when it holds at least one null in the input.
+fn render(df: &DataFrame, format: &str, name: &str) -> JsonValue {
+    let columns = df.get_columns();
+    match format {
+        "avro" => json!({
+            "type": "record",
+            "name": name,
+            "fields": columns
+                .iter()
+                .map(|c| {
+                    let dtype = avro_type(c.name(), c.dtype());
+                    if c.null_count() > 0 && !matches!(c.dtype(), DataType::Null) {
+                        json!({ "name": c.name().as_str(), "type": ["null", dtype], "default": null })
+                    } else {
+                        json!({ "name": c.name().as_str(), "type": dtype })
+                    }
+                })
+                .collect::<Vec<_>>(),
+        }),
+        "columns" => json!({
+            "columns": columns
+                .iter()
+                .map(|c| json!({
+                    "name": c.name().as_str(),
+                    "type": c.dtype().to_string(),
+                    "nullable": c.null_count() > 0,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        _ => {
+            let properties: Map<String, JsonValue> = columns
+                .iter()
+                .map(|c| {
+                    let mut schema = json_schema_type(c.dtype());
+                    if c.null_count() > 0 && !matches!(c.dtype(), DataType::Null) {
+                        let dtype = schema["type"].clone();
+                        schema["type"] = json!([dtype, "null"]);
+                    }
+                    (c.name().to_string(), schema)
+                })
+                .collect();
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": name,
+                "type": "object",
+                "properties": properties,
+                "required": columns.iter().map(|c| c.name().as_str()).collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+fn write_schema(path: &Path, schema: &JsonValue) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(schema)?)
+        .with_context(|| format!("Failed to write schema '{}'", path.display()))
+}
+
+#[async_trait]
+impl Stage for SchemaSink {
+    fn name(&self) -> &str {
+        "schema.write"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "path".to_string(),
+            toml::Value::String("contracts/orders.schema.json".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "path".to_string(),
+            toml::Value::String("contracts/orders.avsc".to_string()),
+        );
+        example2.insert(
+            "format".to_string(),
+            toml::Value::String("avro".to_string()),
+        );
+        example2.insert("name".to_string(), toml::Value::String("Order".to_string()));
+
+        StageMetadata::builder("schema.write", StageCategory::Sink)
+            .description("Write the schema of the input as a data contract")
+            .long_description(
+                "Derives the column names and types of the input and writes them to 'path' as \
+                a JSON Schema, an Avro record schema, or a plain list of columns with their \
+                types. A column is marked nullable when it contains nulls in the input. The \
+                input is passed through unchanged, so the stage can sit inline before the sinks \
+                that publish the data. The 'columns' format can be used as a \
+                schema_drift.apply baseline.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "Path of the schema file to write",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "format",
+                    ParameterType::String,
+                    "json_schema",
+                    "Schema document to write",
+                )
+                .with_validation(ParameterValidation::allowed_values(FORMATS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "name",
+                ParameterType::String,
+                "Record",
+                "JSON Schema title or Avro record name",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "JSON Schema contract",
+                example1,
+                Some("Publish the schema of the orders output as JSON Schema"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Avro schema",
+                example2,
+                Some("Write an Avro record schema named Order"),
+            ))
+            .tag("schema")
+            .tag("contract")
+            .tag("sink")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        self.validate_config(config).await?;
+
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Schema sink requires input data"))?;
+
+        let path = config.get("path").and_then(|v| v.as_str()).unwrap();
+        let format = config
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("json_schema");
+        let name = config
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Record");
+
+        let df = data.as_dataframe()?;
+        write_schema(Path::new(path), &render(&df, format, name))?;
+        info!(
+            "Wrote {} schema with {} column(s) to '{}'",
+            format,
+            df.width(),
+            path
+        );
+
+        Ok(data)
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if config.get("path").and_then(|v| v.as_str()).is_none() {
+            anyhow::bail!("Schema sink requires 'path' configuration");
+        }
+
+        if let Some(format) = config.get("format") {
+            let format = format
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'format' must be a string"))?;
+            if !FORMATS.contains(&format) {
+                anyhow::bail!(
+                    "Invalid format: {}. Must be 'json_schema', 'avro', or 'columns'",
+                    format
+                );
+            }
+        }
+
+        if config.get("name").is_some_and(|v| v.as_str().is_none()) {
+            anyhow::bail!("'name' must be a string");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders() -> DataFrame {
+        df! {
+            "id" => &[1i64, 2, 3],
+            "amount" => &[Some(9.5), None, Some(12.0)],
+            "customer" => &["ada", "bob", "cy"],
+            "paid" => &[true, false, true],
+        }
+        .unwrap()
+    }
+
+    async fn write(format: &str) -> (DataFormat, JsonValue) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contract.json");
+        let config = HashMap::from([
+            (
+                "path".to_string(),
+                toml::Value::String(path.to_str().unwrap().to_string()),
+            ),
+            (
+                "format".to_string(),
+                toml::Value::String(format.to_string()),
+            ),
+            ("name".to_string(), toml::Value::String("Order".to_string())),
+        ]);
+        let inputs = HashMap::from([("orders".to_string(), DataFormat::DataFrame(orders()))]);
+        let output = SchemaSink.execute(inputs, &config).await.unwrap();
+        let schema = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        (output, schema)
+    }
+
+    #[tokio::test]
+    async fn test_json_schema_matches_input_columns() {
+        let (output, schema) = write("json_schema").await;
+
+        assert_eq!(schema["title"], "Order");
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "id": { "type": "integer" },
+                "amount": { "type": ["number", "null"] },
+                "customer": { "type": "string" },
+                "paid": { "type": "boolean" },
+            })
+        );
+        assert_eq!(
+            schema["required"],
+            json!(["id", "amount", "customer", "paid"])
+        );
+
+        // Data passes through unchanged
+        assert!(output.as_dataframe().unwrap().equals_missing(&orders()));
+    }
+
+    #[tokio::test]
+    async fn test_avro_and_columns_formats() {
+        let (_, avro) = write("avro").await;
+        assert_eq!(avro["type"], "record");
+        assert_eq!(avro["name"], "Order");
+        assert_eq!(
+            avro["fields"],
+            json!([
+                { "name": "id", "type": "long" },
+                { "name": "amount", "type": ["null", "double"], "default": null },
+                { "name": "customer", "type": "string" },
+                { "name": "paid", "type": "boolean" },
+            ])
+        );
+
+        let (_, columns) = write("columns").await;
+        assert_eq!(
+            columns["columns"][1],
+            json!({ "name": "amount", "type": "f64", "nullable": true })
+        );
+
+        let err = SchemaSink
+            .validate_config(&HashMap::from([
+                (
+                    "path".to_string(),
+                    toml::Value::String("out.json".to_string()),
+                ),
+                ("format".to_string(), toml::Value::String("xml".to_string())),
+            ]))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid format: xml. Must be 'json_schema', 'avro', or 'columns'"
+        );
+    }
+}
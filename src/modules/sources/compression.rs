@@ -0,0 +1,183 @@
+//! Transparent decompression of the files read by file sources.
+//!
+//! With `compression = "auto"` (the default) the codec is taken from the file
+//! extension (`.gz`, `.gzip`, `.zst`, `.zstd`); any other extension is
+//! ambiguous and the first bytes of the file are checked for the gzip or zstd
+//! magic number instead. Forcing `none` reads the bytes as they are.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, ParameterValidation};
+
+const MODES: [&str; 4] = ["auto", "gzip", "zstd", "none"];
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Metadata parameters shared by every source supporting `compression`
+pub(crate) fn parameters() -> Vec<ConfigParameter> {
+    vec![ConfigParameter::optional(
+        "compression",
+        ParameterType::String,
+        "auto",
+        "Compression of the input file (auto detects it from the extension or content)",
+    )
+    .with_validation(ParameterValidation::allowed_values(MODES))]
+}
+
+fn mode(config: &HashMap<String, toml::Value>) -> Result<&str> {
+    let mode = match config.get("compression") {
+        None => "auto",
+        Some(value) => value
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("'compression' must be a string"))?,
+    };
+    if !MODES.contains(&mode) {
+        anyhow::bail!(
+            "Invalid compression: {}. Must be 'auto', 'gzip', 'zstd', or 'none'",
+            mode
+        );
+    }
+    Ok(mode)
+}
+
+/// Validate the compression option of a source config
+pub(crate) fn validate_config(config: &HashMap<String, toml::Value>) -> Result<()> {
+    mode(config).map(|_| ())
+}
+
+/// Compression of the file at `path` according to `config`
+pub(crate) fn detect(path: &Path, config: &HashMap<String, toml::Value>) -> Result<Compression> {
+    match mode(config)? {
+        "gzip" => return Ok(Compression::Gzip),
+        "zstd" => return Ok(Compression::Zstd),
+        "none" => return Ok(Compression::None),
+        _ => {}
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("gz" | "gzip") => return Ok(Compression::Gzip),
+        Some("zst" | "zstd") => return Ok(Compression::Zstd),
+        _ => {}
+    }
+
+    let mut magic = Vec::with_capacity(ZSTD_MAGIC.len());
+    std::fs::File::open(path)?
+        .take(ZSTD_MAGIC.len() as u64)
+        .read_to_end(&mut magic)?;
+    Ok(if magic.starts_with(&GZIP_MAGIC) {
+        Compression::Gzip
+    } else if magic.starts_with(&ZSTD_MAGIC) {
+        Compression::Zstd
+    } else {
+        Compression::None
+    })
+}
+
+impl Compression {
+    fn name(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Decompressed bytes of the file at `path`
+    pub(crate) fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        if *self == Compression::None {
+            return Ok(std::fs::read(path)?);
+        }
+
+        let file = std::fs::File::open(path)?;
+        let mut bytes = Vec::new();
+        match self {
+            // Multi-member gzip files (e.g. concatenated .gz parts) are read whole
+            Compression::Gzip => flate2::read::MultiGzDecoder::new(file).read_to_end(&mut bytes),
+            _ => zstd::Decoder::new(file)?.read_to_end(&mut bytes),
+        }
+        .with_context(|| {
+            format!(
+                "Failed to decompress {} file '{}'",
+                self.name(),
+                path.display()
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+/// Decompressed contents of the file at `path`
+pub(crate) fn read(path: &Path, config: &HashMap<String, toml::Value>) -> Result<Vec<u8>> {
+    detect(path, config)?.read(path)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Write `contents` to `path` compressed with `compression`
+    pub(crate) fn write_compressed(path: &Path, compression: Compression, contents: &str) {
+        let bytes = match compression {
+            Compression::None => contents.as_bytes().to_vec(),
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(contents.as_bytes()).unwrap();
+                encoder.finish().unwrap()
+            }
+            Compression::Zstd => zstd::encode_all(contents.as_bytes(), 0).unwrap(),
+        };
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_detect_from_extension_and_magic_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let auto = HashMap::new();
+
+        let gz = dir.path().join("data.csv.gz");
+        write_compressed(&gz, Compression::Gzip, "a\n1\n");
+        assert_eq!(detect(&gz, &auto).unwrap(), Compression::Gzip);
+
+        // Ambiguous extensions fall back to the magic bytes
+        let zst = dir.path().join("export.dat");
+        write_compressed(&zst, Compression::Zstd, "a\n1\n");
+        assert_eq!(detect(&zst, &auto).unwrap(), Compression::Zstd);
+        assert_eq!(read(&zst, &auto).unwrap(), b"a\n1\n");
+
+        let plain = dir.path().join("data.csv");
+        write_compressed(&plain, Compression::None, "a\n1\n");
+        assert_eq!(detect(&plain, &auto).unwrap(), Compression::None);
+
+        let forced = HashMap::from([(
+            "compression".to_string(),
+            toml::Value::String("gzip".to_string()),
+        )]);
+        let err = read(&plain, &forced).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Failed to decompress gzip file"));
+
+        let invalid = HashMap::from([(
+            "compression".to_string(),
+            toml::Value::String("bzip2".to_string()),
+        )]);
+        assert!(validate_config(&invalid).is_err());
+    }
+}
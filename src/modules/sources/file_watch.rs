@@ -1,21 +1,58 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use tokio::time::sleep;
 use tokio_stream::Stream;
 use tracing::{debug, info, warn};
 
 use crate::core::traits::{RecordBatch, StreamingDataSource};
+use crate::utils::state_file::{load_json, save_json};
 
 /// File watch source that monitors files for changes
 /// This is a simple polling-based implementation
 pub struct FileWatchSource;
 
+/// Processed position of a single watched file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileOffset {
+    /// Number of bytes already emitted
+    pub offset: u64,
+    /// Modification time (ms since epoch) when the file was last read
+    pub modified_ms: u64,
+    /// Inode of the file that was read, to notice rotation (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inode: Option<u64>,
+}
+
+/// Persisted watch state, keyed by watched path
+///
+/// Written to `state_file` so a restarted pipeline resumes where it left off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileWatchState {
+    pub files: HashMap<String, FileOffset>,
+}
+
+impl FileWatchState {
+    /// Load state from disk, starting empty if the file does not exist yet
+    pub async fn load(path: &Path) -> Result<Self> {
+        Ok(load_json(path, "file watch state file")
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Save state atomically (write to a temp file, then rename)
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        save_json(path, self).await
+    }
+}
+
 impl Default for FileWatchSource {
     fn default() -> Self {
         Self::new()
@@ -30,7 +67,10 @@ impl FileWatchSource {
     /// Parse file content based on format
     async fn parse_file(path: &PathBuf, format: &str) -> Result<RecordBatch> {
         let content = fs::read_to_string(path).await?;
+        Self::parse_content(&content, format)
+    }
 
+    fn parse_content(content: &str, format: &str) -> Result<RecordBatch> {
         match format {
             "jsonl" => {
                 let mut records = Vec::new();
@@ -44,7 +84,7 @@ impl FileWatchSource {
                 Ok(records)
             }
             "json" => {
-                let records: Vec<HashMap<String, JsonValue>> = serde_json::from_str(&content)?;
+                let records: Vec<HashMap<String, JsonValue>> = serde_json::from_str(content)?;
                 Ok(records)
             }
             "text" => {
@@ -63,6 +103,161 @@ impl FileWatchSource {
             }
         }
     }
+
+    /// Read whatever is new in `path` since `entry`, updating `entry` in place
+    ///
+    /// In `full` mode the whole file is re-read when it has changed. In `tail`
+    /// mode only complete lines appended after `entry.offset` are read; a file
+    /// that shrank is assumed to be truncated, and a file with a new inode to
+    /// have been rotated, and both are read from the start.
+    pub(crate) async fn read_changes(
+        path: &PathBuf,
+        format: &str,
+        mode: &str,
+        metadata: &std::fs::Metadata,
+        entry: &mut FileOffset,
+    ) -> Result<Option<RecordBatch>> {
+        let len = metadata.len();
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        if mode == "tail" {
+            let inode = file_inode(metadata);
+            let mut offset = entry.offset;
+            if entry.inode.is_some() && inode != entry.inode {
+                info!(
+                    "File {:?} was rotated, reading the new file from the start",
+                    path
+                );
+                offset = 0;
+            } else if len < offset {
+                info!("File {:?} was truncated, reading from the start", path);
+                offset = 0;
+            }
+
+            if len == offset {
+                return Ok(None);
+            }
+
+            let mut file = fs::File::open(path).await?;
+            file.seek(std::io::SeekFrom::Start(offset)).await?;
+            let mut buffer = Vec::new();
+            file.read_to_end(&mut buffer).await?;
+
+            // Leave a trailing partial line for the next poll
+            let Some(last_newline) = buffer.iter().rposition(|b| *b == b'\n') else {
+                return Ok(None);
+            };
+            buffer.truncate(last_newline + 1);
+
+            // As in full mode, the position only moves past lines that parsed
+            let read = buffer.len() as u64;
+            let content = String::from_utf8(buffer)
+                .map_err(|e| anyhow::anyhow!("File {:?} is not valid UTF-8: {}", path, e))?;
+            let records = Self::parse_content(&content, format)?;
+            entry.offset = offset + read;
+            entry.modified_ms = modified_ms;
+            entry.inode = inode;
+
+            return Ok(Some(records));
+        }
+
+        if entry.modified_ms == modified_ms && entry.offset == len {
+            return Ok(None);
+        }
+
+        debug!("File {:?} modified, reading...", path);
+        // Only mark the file as read once it parsed, so a malformed file is
+        // retried on the next poll
+        let records = Self::parse_file(path, format).await?;
+        entry.offset = len;
+        entry.modified_ms = modified_ms;
+
+        Ok(Some(records))
+    }
+
+    /// Poll `path` every `poll_interval`, yielding what [`Self::read_changes`] finds
+    ///
+    /// `state` holds the position to start from; it is saved to `state_file`
+    /// (when set) after each batch has been consumed. Also used by `tail.read`.
+    pub(crate) fn watch(
+        path: PathBuf,
+        format: String,
+        mode: String,
+        poll_interval: Duration,
+        state_file: Option<PathBuf>,
+        mut state: FileWatchState,
+    ) -> Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>> {
+        let state_key = path.to_string_lossy().to_string();
+        let stream = async_stream::stream! {
+            loop {
+                match fs::metadata(&path).await {
+                    Ok(metadata) => {
+                        let previous = state.files.get(&state_key).cloned().unwrap_or_default();
+                        let mut entry = previous.clone();
+
+                        let result = Self::read_changes(
+                            &path,
+                            &format,
+                            &mode,
+                            &metadata,
+                            &mut entry,
+                        )
+                        .await;
+
+                        match result {
+                            Ok(Some(records)) => {
+                                if !records.is_empty() {
+                                    info!("Read {} records from {:?}", records.len(), path);
+                                    yield Ok(records);
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                warn!("Failed to parse file {:?}: {}", path, e);
+                                yield Err(e);
+                            }
+                        }
+
+                        // Persist only once the consumer has asked for more,
+                        // so a crash mid-batch re-emits rather than drops it
+                        if entry != previous {
+                            state.files.insert(state_key.clone(), entry);
+                            if let Some(state_path) = &state_file {
+                                if let Err(e) = state.save(state_path).await {
+                                    warn!("Failed to save file watch state {:?}: {}", state_path, e);
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        debug!("File {:?} not found or inaccessible: {}", path, e);
+                        // Don't yield error, just wait for file to appear
+                    }
+                }
+
+                sleep(poll_interval).await;
+            }
+        };
+
+        Box::pin(stream)
+    }
+}
+
+/// Inode of a file, which changes when a rotated log is replaced by a new file
+#[cfg(unix)]
+pub(crate) fn file_inode(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn file_inode(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
 }
 
 #[async_trait]
@@ -90,67 +285,39 @@ impl StreamingDataSource for FileWatchSource {
             .and_then(|v| v.as_integer())
             .unwrap_or(1) as u64;
 
+        let mode = config
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("full")
+            .to_string();
+
+        let state_file = config
+            .get("state_file")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from);
+
         let path = PathBuf::from(path_str);
-        let format_owned = format.to_string();
         let poll_interval = Duration::from_secs(poll_interval_secs);
 
         info!(
-            "Starting file watch on {:?} (format: {}, poll_interval: {}s)",
-            path, format, poll_interval_secs
+            "Starting file watch on {:?} (format: {}, mode: {}, poll_interval: {}s)",
+            path, format, mode, poll_interval_secs
         );
 
-        // Track last modification time
-        let mut last_modified = None;
-
-        let stream = async_stream::stream! {
-            loop {
-                match fs::metadata(&path).await {
-                    Ok(metadata) => {
-                        if let Ok(modified) = metadata.modified() {
-                            let should_read = match last_modified {
-                                None => {
-                                    // First read
-                                    last_modified = Some(modified);
-                                    true
-                                }
-                                Some(last_mod) => {
-                                    if modified > last_mod {
-                                        debug!("File {:?} modified, reading...", path);
-                                        last_modified = Some(modified);
-                                        true
-                                    } else {
-                                        false
-                                    }
-                                }
-                            };
-
-                            if should_read {
-                                match Self::parse_file(&path, &format_owned).await {
-                                    Ok(records) => {
-                                        if !records.is_empty() {
-                                            info!("Read {} records from {:?}", records.len(), path);
-                                            yield Ok(records);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        warn!("Failed to parse file {:?}: {}", path, e);
-                                        yield Err(e);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        debug!("File {:?} not found or inaccessible: {}", path, e);
-                        // Don't yield error, just wait for file to appear
-                    }
-                }
-
-                sleep(poll_interval).await;
-            }
+        // Resume from the persisted offsets, if any
+        let state = match &state_file {
+            Some(state_path) => FileWatchState::load(state_path).await?,
+            None => FileWatchState::default(),
         };
 
-        Ok(Box::pin(stream))
+        Ok(Self::watch(
+            path,
+            format.to_string(),
+            mode,
+            poll_interval,
+            state_file,
+            state,
+        ))
     }
 
     async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
@@ -175,6 +342,31 @@ impl StreamingDataSource for FileWatchSource {
             }
         }
 
+        // Validate mode if provided
+        if let Some(mode_value) = config.get("mode") {
+            let mode = mode_value
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("mode must be a string"))?;
+
+            if mode != "full" && mode != "tail" {
+                anyhow::bail!("Invalid mode: {}. Must be 'full' or 'tail'", mode);
+            }
+
+            let format = config
+                .get("format")
+                .and_then(|v| v.as_str())
+                .unwrap_or("jsonl");
+            if mode == "tail" && format == "json" {
+                anyhow::bail!("mode 'tail' requires a line-based format ('jsonl' or 'text')");
+            }
+        }
+
+        if let Some(state_value) = config.get("state_file") {
+            if state_value.as_str().is_none() {
+                anyhow::bail!("state_file must be a string path");
+            }
+        }
+
         // Validate poll_interval if provided
         if let Some(interval_value) = config.get("poll_interval") {
             if interval_value.as_integer().is_none() {
@@ -244,6 +436,198 @@ mod tests {
         assert!(source.validate_config(&config).await.is_ok());
     }
 
+    async fn poll(path: &PathBuf, mode: &str, entry: &mut FileOffset) -> Option<RecordBatch> {
+        let metadata = tokio::fs::metadata(path).await.unwrap();
+        FileWatchSource::read_changes(path, "text", mode, &metadata, entry)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_full_mode_resumes_from_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("data.txt");
+        let state_path = dir.path().join("watch_state.json");
+        let key = data_path.to_string_lossy().to_string();
+        tokio::fs::write(&data_path, "a\nb\n").await.unwrap();
+
+        // First run processes the file and persists its position
+        let mut state = FileWatchState::load(&state_path).await.unwrap();
+        let mut entry = state.files.get(&key).cloned().unwrap_or_default();
+        let records = poll(&data_path, "full", &mut entry).await.unwrap();
+        assert_eq!(records.len(), 2);
+        state.files.insert(key.clone(), entry);
+        state.save(&state_path).await.unwrap();
+
+        // After a restart the unchanged file is not emitted again
+        let state = FileWatchState::load(&state_path).await.unwrap();
+        let mut entry = state.files.get(&key).cloned().unwrap();
+        assert!(poll(&data_path, "full", &mut entry).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_full_mode_retries_file_that_failed_to_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("data.jsonl");
+        tokio::fs::write(&data_path, "{\"id\": 1}\n{not json\n")
+            .await
+            .unwrap();
+
+        // A malformed file fails and is not marked as read
+        let mut entry = FileOffset::default();
+        let metadata = tokio::fs::metadata(&data_path).await.unwrap();
+        assert!(
+            FileWatchSource::read_changes(&data_path, "jsonl", "full", &metadata, &mut entry)
+                .await
+                .is_err()
+        );
+        assert_eq!(entry, FileOffset::default());
+
+        // Once fixed, the file is read on the next poll
+        tokio::fs::write(&data_path, "{\"id\": 1}\n{\"id\": 2}\n")
+            .await
+            .unwrap();
+        let metadata = tokio::fs::metadata(&data_path).await.unwrap();
+        let records =
+            FileWatchSource::read_changes(&data_path, "jsonl", "full", &metadata, &mut entry)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(entry.offset, metadata.len());
+    }
+
+    #[tokio::test]
+    async fn test_tail_mode_resumes_from_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("app.log");
+        let state_path = dir.path().join("watch_state.json");
+        let key = data_path.to_string_lossy().to_string();
+        tokio::fs::write(&data_path, "first\nsecond\npart")
+            .await
+            .unwrap();
+
+        let mut state = FileWatchState::load(&state_path).await.unwrap();
+        let mut entry = FileOffset::default();
+        let records = poll(&data_path, "tail", &mut entry).await.unwrap();
+        // The trailing partial line is held back
+        assert_eq!(records.len(), 2);
+        assert_eq!(entry.offset, "first\nsecond\n".len() as u64);
+        state.files.insert(key.clone(), entry);
+        state.save(&state_path).await.unwrap();
+
+        // Simulate a restart after more data was appended
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&data_path)
+            .await
+            .unwrap();
+        tokio::io::AsyncWriteExt::write_all(&mut file, b"ial\nthird\n")
+            .await
+            .unwrap();
+
+        let state = FileWatchState::load(&state_path).await.unwrap();
+        let mut entry = state.files.get(&key).cloned().unwrap();
+        let records = poll(&data_path, "tail", &mut entry).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].get("line").unwrap(),
+            &JsonValue::String("partial".to_string())
+        );
+        assert_eq!(
+            records[1].get("line").unwrap(),
+            &JsonValue::String("third".to_string())
+        );
+
+        assert!(poll(&data_path, "tail", &mut entry).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_skips_already_processed_content() {
+        use tokio_stream::StreamExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("events.jsonl");
+        let state_path = dir.path().join("watch_state.json");
+        tokio::fs::write(&data_path, "{\"id\": 1}\n").await.unwrap();
+
+        let mut config = HashMap::new();
+        config.insert(
+            "path".to_string(),
+            toml::Value::String(data_path.to_string_lossy().to_string()),
+        );
+        config.insert("mode".to_string(), toml::Value::String("tail".to_string()));
+        config.insert(
+            "state_file".to_string(),
+            toml::Value::String(state_path.to_string_lossy().to_string()),
+        );
+
+        let source = FileWatchSource::new();
+        {
+            let mut stream = source.stream(&config).await.unwrap();
+            let first = stream.next().await.unwrap().unwrap();
+            assert_eq!(first[0].get("id").unwrap(), &serde_json::json!(1));
+
+            // Requesting the next batch persists the offset of the first one
+            tokio::fs::write(&data_path, "{\"id\": 1}\n{\"id\": 2}\n")
+                .await
+                .unwrap();
+            let second = stream.next().await.unwrap().unwrap();
+            assert_eq!(second.len(), 1);
+            assert_eq!(second[0].get("id").unwrap(), &serde_json::json!(2));
+        }
+
+        // Restarted stream must not re-emit id 1
+        tokio::fs::write(&data_path, "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n")
+            .await
+            .unwrap();
+        let mut stream = source.stream(&config).await.unwrap();
+        let records = stream.next().await.unwrap().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("id").unwrap(), &serde_json::json!(2));
+    }
+
+    #[tokio::test]
+    async fn test_tail_mode_keeps_offset_of_lines_that_failed_to_parse() {
+        use tokio_stream::StreamExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("events.jsonl");
+        let state_path = dir.path().join("watch_state.json");
+        let key = data_path.to_string_lossy().to_string();
+        tokio::fs::write(&data_path, "{\"id\": 1}\n").await.unwrap();
+
+        let mut config = HashMap::new();
+        config.insert("path".to_string(), toml::Value::String(key.clone()));
+        config.insert("mode".to_string(), toml::Value::String("tail".to_string()));
+        config.insert(
+            "state_file".to_string(),
+            toml::Value::String(state_path.to_string_lossy().to_string()),
+        );
+
+        let source = FileWatchSource::new();
+        let mut stream = source.stream(&config).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().len(), 1);
+
+        // The malformed line fails on every poll instead of being skipped
+        tokio::fs::write(&data_path, "{\"id\": 1}\n{not json\n")
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.unwrap().is_err());
+
+        let state = FileWatchState::load(&state_path).await.unwrap();
+        assert_eq!(state.files[&key].offset, "{\"id\": 1}\n".len() as u64);
+
+        // Once the line is fixed it is read from the saved offset
+        tokio::fs::write(&data_path, "{\"id\": 1}\n{\"id\": 2}\n")
+            .await
+            .unwrap();
+        let records = stream.next().await.unwrap().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].get("id").unwrap(), &serde_json::json!(2));
+    }
+
     #[tokio::test]
     async fn test_validate_config_missing_path() {
         let source = FileWatchSource::new();
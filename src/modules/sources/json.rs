@@ -1,18 +1,163 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use polars::prelude::*;
+use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tokio::fs;
 
 use crate::core::metadata::{
     ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
 };
 use crate::core::stage::Stage;
-use crate::core::traits::{DataFormat, RecordBatch};
+use crate::core::traits::{DataFormat, RecordBatch, TypeConflict};
+
+use super::compression;
+use super::projection::{self, Projection};
 
 pub struct JsonSource;
 
+/// Navigate to the records array at `path`, either a JSON pointer
+/// ("/response/rows") or a dotted path ("response.rows", "data.0.items")
+fn resolve_record_path<'a>(root: &'a JsonValue, path: &str) -> Result<&'a Vec<JsonValue>> {
+    let target = if path.starts_with('/') {
+        root.pointer(path)
+    } else {
+        path.split('.')
+            .try_fold(root, |value, segment| match value {
+                JsonValue::Object(map) => map.get(segment),
+                JsonValue::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            })
+    };
+
+    match target {
+        Some(JsonValue::Array(items)) => Ok(items),
+        Some(other) => anyhow::bail!(
+            "record_path '{}' resolves to {}, not an array",
+            path,
+            json_type_name(other)
+        ),
+        None => anyhow::bail!("record_path '{}' not found in JSON document", path),
+    }
+}
+
+fn type_conflict(config: &HashMap<String, toml::Value>) -> Result<TypeConflict> {
+    match config.get("type_conflict") {
+        None => Ok(TypeConflict::default()),
+        Some(value) => TypeConflict::parse(
+            value
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'type_conflict' must be a string"))?,
+        ),
+    }
+}
+
+fn json_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "an object",
+    }
+}
+
+/// Records of the array at `path`, which must all be objects
+fn records_at(root: &JsonValue, path: &str) -> Result<RecordBatch> {
+    resolve_record_path(root, path)?
+        .iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            JsonValue::Object(map) => Ok(map.clone().into_iter().collect()),
+            other => anyhow::bail!(
+                "record_path '{}' element {} is {}, not an object",
+                path,
+                i,
+                json_type_name(other)
+            ),
+        })
+        .collect()
+}
+
+/// Read the file according to `format`, `record_path` and `type_conflict`
+fn read_json(config: &HashMap<String, toml::Value>) -> Result<DataFormat> {
+    let path = config
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("JSON source requires 'path' configuration"))?;
+
+    let format = config
+        .get("format")
+        .and_then(|v| v.as_str())
+        .unwrap_or("records");
+
+    let type_conflict = type_conflict(config)?;
+
+    let path_buf = PathBuf::from(path);
+
+    if !path_buf.exists() {
+        anyhow::bail!("JSON file not found: {}", path);
+    }
+
+    let content = String::from_utf8(compression::read(&path_buf, config)?)
+        .map_err(|_| anyhow::anyhow!("JSON file is not valid UTF-8: {}", path))?;
+
+    if let Some(record_path) = config.get("record_path").and_then(|v| v.as_str()) {
+        let records: RecordBatch = if format == "jsonl" {
+            let mut records = Vec::new();
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                records.extend(records_at(&serde_json::from_str(line)?, record_path)?);
+            }
+            records
+        } else {
+            records_at(&serde_json::from_str(&content)?, record_path)?
+        };
+        let records = type_conflict.resolve(records)?;
+
+        return Ok(if format == "dataframe" {
+            DataFormat::DataFrame(DataFormat::RecordBatch(records).as_dataframe()?)
+        } else {
+            DataFormat::RecordBatch(records)
+        });
+    }
+
+    match format {
+        "records" | "jsonl" => {
+            // Parse as newline-delimited JSON or array of records
+            let records: RecordBatch = if format == "jsonl" {
+                // Parse each line as a separate JSON object
+                content
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(serde_json::from_str)
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                // Parse as a JSON array
+                serde_json::from_str(&content)?
+            };
+
+            Ok(DataFormat::RecordBatch(type_conflict.resolve(records)?))
+        }
+        "dataframe" if type_conflict != TypeConflict::Infer => {
+            let records: RecordBatch = serde_json::from_str(&content)?;
+            Ok(DataFormat::DataFrame(
+                DataFormat::RecordBatch(records).as_dataframe_with(type_conflict)?,
+            ))
+        }
+        "dataframe" => {
+            // Parse directly into DataFrame using Polars
+            let cursor = std::io::Cursor::new(content.as_bytes());
+            let df = JsonReader::new(cursor).finish()?;
+            Ok(DataFormat::DataFrame(df))
+        }
+        _ => anyhow::bail!(
+            "Unknown JSON format: {}. Use 'records', 'jsonl', or 'dataframe'",
+            format
+        ),
+    }
+}
+
 #[async_trait]
 impl Stage for JsonSource {
     fn name(&self) -> &str {
@@ -40,12 +185,29 @@ impl Stage for JsonSource {
             toml::Value::String("jsonl".to_string()),
         );
 
+        let mut example3 = HashMap::new();
+        example3.insert(
+            "path".to_string(),
+            toml::Value::String("api_response.json".to_string()),
+        );
+        example3.insert(
+            "record_path".to_string(),
+            toml::Value::String("response.rows".to_string()),
+        );
+
         StageMetadata::builder("json.read", StageCategory::Source)
             .description("Read data from JSON files")
             .long_description(
                 "Reads JSON files in various formats and converts them into DataFrames or RecordBatches. \
                 Supports standard JSON arrays (records), JSON Lines (jsonl), and Polars DataFrame format. \
-                Handles large files efficiently with streaming options."
+                Handles large files efficiently with streaming options. When the records are nested \
+                inside a wrapper object, 'record_path' selects the array to read, as a dotted path \
+                or JSON pointer; for jsonl it is applied to every line. Gzip and zstd compressed \
+                files (e.g. logs.jsonl.zst) are decompressed transparently. 'type_conflict' \
+                decides what happens to fields holding values of different types across records: \
+                'string' turns every value of such a field into a string, 'error' fails, and \
+                'infer' leaves the choice to schema inference. 'columns' keeps only the listed \
+                fields and 'rename' renames them from a table of old_name = \"new_name\" pairs."
             )
             .parameter(ConfigParameter::required(
                 "path",
@@ -60,6 +222,20 @@ impl Stage for JsonSource {
             ).with_validation(ParameterValidation::allowed_values([
                 "records", "jsonl", "dataframe"
             ])))
+            .parameter(ConfigParameter::optional(
+                "record_path",
+                ParameterType::String,
+                "none",
+                "Dotted path or JSON pointer to the records array (e.g. \"response.rows\")"
+            ))
+            .parameter(ConfigParameter::optional(
+                "type_conflict",
+                ParameterType::String,
+                "infer",
+                "Handling of fields with mixed value types across records"
+            ).with_validation(ParameterValidation::allowed_values(TypeConflict::NAMES)))
+            .parameters(projection::parameters())
+            .parameters(compression::parameters())
             .example(crate::core::metadata::ConfigExample::new(
                 "Read JSON array",
                 example1,
@@ -70,6 +246,11 @@ impl Stage for JsonSource {
                 example2,
                 Some("Read a JSONL file with one JSON object per line")
             ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Read nested records",
+                example3,
+                Some("Read the rows of {\"response\": {\"rows\": [...]}}")
+            ))
             .tag("json")
             .tag("file")
             .tag("io")
@@ -82,52 +263,8 @@ impl Stage for JsonSource {
         _inputs: HashMap<String, DataFormat>,
         config: &HashMap<String, toml::Value>,
     ) -> Result<DataFormat> {
-        let path = config
-            .get("path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("JSON source requires 'path' configuration"))?;
-
-        let format = config
-            .get("format")
-            .and_then(|v| v.as_str())
-            .unwrap_or("records");
-
-        let path_buf = PathBuf::from(path);
-
-        if !path_buf.exists() {
-            anyhow::bail!("JSON file not found: {}", path);
-        }
-
-        let content = fs::read_to_string(&path_buf).await?;
-
-        match format {
-            "records" | "jsonl" => {
-                // Parse as newline-delimited JSON or array of records
-                let records: RecordBatch = if format == "jsonl" {
-                    // Parse each line as a separate JSON object
-                    content
-                        .lines()
-                        .filter(|line| !line.trim().is_empty())
-                        .map(serde_json::from_str)
-                        .collect::<Result<Vec<_>, _>>()?
-                } else {
-                    // Parse as a JSON array
-                    serde_json::from_str(&content)?
-                };
-
-                Ok(DataFormat::RecordBatch(records))
-            }
-            "dataframe" => {
-                // Parse directly into DataFrame using Polars
-                let cursor = std::io::Cursor::new(content.as_bytes());
-                let df = JsonReader::new(cursor).finish()?;
-                Ok(DataFormat::DataFrame(df))
-            }
-            _ => anyhow::bail!(
-                "Unknown JSON format: {}. Use 'records', 'jsonl', or 'dataframe'",
-                format
-            ),
-        }
+        let projection = Projection::from_config(config)?;
+        projection.apply(read_json(config)?, "JSON source")
     }
 
     async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
@@ -150,13 +287,23 @@ impl Stage for JsonSource {
             }
         }
 
-        Ok(())
+        if let Some(record_path) = config.get("record_path") {
+            match record_path.as_str() {
+                Some(p) if !p.is_empty() => {}
+                _ => anyhow::bail!("'record_path' must be a non-empty string"),
+            }
+        }
+
+        type_conflict(config)?;
+        projection::validate_config(config)?;
+        compression::validate_config(config)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::sources::compression::{tests::write_compressed, Compression};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -192,6 +339,199 @@ mod tests {
         }
     }
 
+    fn nested_config(file: &NamedTempFile, record_path: &str) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "path".to_string(),
+            toml::Value::String(file.path().to_string_lossy().to_string()),
+        );
+        config.insert(
+            "record_path".to_string(),
+            toml::Value::String(record_path.to_string()),
+        );
+        config
+    }
+
+    #[tokio::test]
+    async fn test_json_source_record_path() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"{{"response": {{"total": 2, "rows": [{{"id": 1, "name": "Alice"}}, {{"id": 2, "name": "Bob"}}]}}}}"#
+        )
+        .unwrap();
+
+        let source = JsonSource;
+        for record_path in ["response.rows", "/response/rows"] {
+            let config = nested_config(&temp_file, record_path);
+            match source.execute(HashMap::new(), &config).await.unwrap() {
+                DataFormat::RecordBatch(records) => {
+                    assert_eq!(records.len(), 2);
+                    assert_eq!(records[1]["name"], "Bob");
+                }
+                _ => panic!("Expected RecordBatch"),
+            }
+        }
+
+        let mut config = nested_config(&temp_file, "response.rows");
+        config.insert(
+            "format".to_string(),
+            toml::Value::String("dataframe".to_string()),
+        );
+        let df = source
+            .execute(HashMap::new(), &config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        assert_eq!(df.height(), 2);
+        assert!(df.column("id").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_json_source_record_path_errors() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"{{"response": {{"total": 2, "rows": [1, 2]}}}}"#
+        )
+        .unwrap();
+
+        async fn error(file: &NamedTempFile, record_path: &str) -> String {
+            let config = nested_config(file, record_path);
+            match JsonSource.execute(HashMap::new(), &config).await {
+                Ok(_) => panic!("Expected record_path error"),
+                Err(e) => e.to_string(),
+            }
+        }
+
+        assert_eq!(
+            error(&temp_file, "response.total").await,
+            "record_path 'response.total' resolves to a number, not an array"
+        );
+        assert_eq!(
+            error(&temp_file, "response.items").await,
+            "record_path 'response.items' not found in JSON document"
+        );
+        assert_eq!(
+            error(&temp_file, "response.rows").await,
+            "record_path 'response.rows' element 0 is a number, not an object"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_source_reads_zstd() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.jsonl.zst");
+        write_compressed(
+            &path,
+            Compression::Zstd,
+            "{\"id\": 1, \"level\": \"info\"}\n{\"id\": 2, \"level\": \"warn\"}\n",
+        );
+
+        let mut config = HashMap::new();
+        config.insert(
+            "path".to_string(),
+            toml::Value::String(path.to_string_lossy().to_string()),
+        );
+        config.insert(
+            "format".to_string(),
+            toml::Value::String("jsonl".to_string()),
+        );
+        match JsonSource.execute(HashMap::new(), &config).await.unwrap() {
+            DataFormat::RecordBatch(records) => {
+                assert_eq!(records.len(), 2);
+                assert_eq!(records[1]["level"], "warn");
+            }
+            _ => panic!("Expected RecordBatch"),
+        }
+
+        // Forcing no decompression parses the raw zstd bytes and fails
+        config.insert(
+            "compression".to_string(),
+            toml::Value::String("none".to_string()),
+        );
+        assert!(JsonSource.execute(HashMap::new(), &config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_source_type_conflict() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"[{{"id": 1, "score": 1}}, {{"id": 2, "score": "N/A"}}]"#
+        )
+        .unwrap();
+
+        let config = |format: &str, policy: &str| {
+            HashMap::from([
+                (
+                    "path".to_string(),
+                    toml::Value::String(temp_file.path().to_string_lossy().to_string()),
+                ),
+                (
+                    "format".to_string(),
+                    toml::Value::String(format.to_string()),
+                ),
+                (
+                    "type_conflict".to_string(),
+                    toml::Value::String(policy.to_string()),
+                ),
+            ])
+        };
+
+        let df = JsonSource
+            .execute(HashMap::new(), &config("dataframe", "string"))
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        let scores: Vec<Option<&str>> = df
+            .column("score")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(scores, vec![Some("1"), Some("N/A")]);
+
+        match JsonSource
+            .execute(HashMap::new(), &config("records", "string"))
+            .await
+            .unwrap()
+        {
+            DataFormat::RecordBatch(records) => assert_eq!(records[0]["score"], "1"),
+            _ => panic!("Expected RecordBatch"),
+        }
+
+        for format in ["records", "dataframe"] {
+            let err = match JsonSource
+                .execute(HashMap::new(), &config(format, "error"))
+                .await
+            {
+                Ok(_) => panic!("Expected a type conflict error"),
+                Err(e) => e.to_string(),
+            };
+            assert_eq!(
+                err,
+                "Type conflict in field 'score': number in record 0, string in record 1"
+            );
+        }
+
+        let df = JsonSource
+            .execute(HashMap::new(), &config("dataframe", "infer"))
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        assert_eq!(df.height(), 2);
+
+        assert!(JsonSource
+            .validate_config(&config("records", "coerce"))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_json_source_validation() {
         let source = JsonSource;
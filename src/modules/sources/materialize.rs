@@ -0,0 +1,112 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+use crate::modules::sinks::materialize::{current_file, read_view, version_file, view_dir};
+
+pub struct MaterializeSource;
+
+fn version(config: &HashMap<String, toml::Value>) -> Result<Option<u64>> {
+    match config.get("version") {
+        None => Ok(None),
+        Some(toml::Value::Integer(n)) if *n >= 1 => Ok(Some(*n as u64)),
+        Some(_) => anyhow::bail!("'version' must be a positive integer"),
+    }
+}
+
+#[async_trait]
+impl Stage for MaterializeSource {
+    fn name(&self) -> &str {
+        "materialize.read"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example_config = HashMap::new();
+        example_config.insert("path".to_string(), toml::Value::String("views".to_string()));
+        example_config.insert(
+            "name".to_string(),
+            toml::Value::String("daily_revenue".to_string()),
+        );
+
+        StageMetadata::builder("materialize.read", StageCategory::Source)
+            .description("Read the current version of a materialized view")
+            .long_description(
+                "Loads the version of the view 'name' under 'path' that the view's CURRENT \
+                pointer refers to, as published by materialize.write in another pipeline. \
+                Because materialize.write swaps the pointer only after a version is complete, \
+                the data read is always a whole version. 'version' reads an older version \
+                that is still kept instead.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "Directory holding the views",
+            ))
+            .parameter(ConfigParameter::required(
+                "name",
+                ParameterType::String,
+                "Name of the view",
+            ))
+            .parameter(ConfigParameter::optional(
+                "version",
+                ParameterType::Integer,
+                "none",
+                "Specific version to read (defaults to the current one)",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Dashboard view",
+                example_config,
+                Some("Load the latest published daily revenue"),
+            ))
+            .tag("materialize")
+            .tag("view")
+            .tag("arrow")
+            .tag("source")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        _inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let dir = view_dir(config, "Materialize source")?;
+        let name = config.get("name").and_then(|v| v.as_str()).unwrap();
+
+        let file = match version(config)? {
+            Some(version) => {
+                let file = dir.join(version_file(version));
+                if !file.exists() {
+                    anyhow::bail!("Materialized view '{}' has no version {}", name, version);
+                }
+                file
+            }
+            None => current_file(&dir)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Materialized view '{}' not found in {}",
+                    name,
+                    dir.parent().unwrap().display()
+                )
+            })?,
+        };
+
+        let df = read_view(&file)?;
+        info!(
+            "Read {} rows from materialized view: {}",
+            df.height(),
+            file.display()
+        );
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        view_dir(config, "Materialize source")?;
+        version(config)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,12 @@
+pub mod compression;
 pub mod csv;
 pub mod file_watch;
 pub mod json;
+pub mod materialize;
+pub mod parquet;
+pub mod projection;
+pub mod run_info;
+pub mod socket;
 pub mod stdin;
 pub mod stdin_stream;
+pub mod tail;
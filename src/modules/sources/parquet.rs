@@ -0,0 +1,130 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+use super::projection::{self, Projection};
+
+pub struct ParquetSource;
+
+fn n_rows(config: &HashMap<String, toml::Value>) -> Result<Option<usize>> {
+    match config.get("n_rows") {
+        None => Ok(None),
+        Some(toml::Value::Integer(n)) if *n >= 0 => Ok(Some(*n as usize)),
+        Some(_) => anyhow::bail!("'n_rows' must be a non-negative integer"),
+    }
+}
+
+#[async_trait]
+impl Stage for ParquetSource {
+    fn name(&self) -> &str {
+        "parquet.read"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "path".to_string(),
+            toml::Value::String("lake/events.parquet".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "path".to_string(),
+            toml::Value::String("lake/events.parquet".to_string()),
+        );
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("user_id".to_string()),
+                toml::Value::String("event".to_string()),
+            ]),
+        );
+        example2.insert("n_rows".to_string(), toml::Value::Integer(1000));
+        let mut rename = toml::Table::new();
+        rename.insert(
+            "event".to_string(),
+            toml::Value::String("event_type".to_string()),
+        );
+        example2.insert("rename".to_string(), toml::Value::Table(rename));
+
+        StageMetadata::builder("parquet.read", StageCategory::Source)
+            .description("Read data from Parquet files")
+            .long_description(
+                "Reads a Parquet file into a DataFrame, keeping the column types stored in the \
+                file. 'columns' reads only the listed columns and 'n_rows' stops after that many \
+                rows; both are pushed down into the Parquet reader, so skipped columns and row \
+                groups are never decoded. 'rename' then renames columns from a table of \
+                old_name = \"new_name\" pairs.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "Path to the Parquet file to read",
+            ))
+            .parameters(projection::parameters())
+            .parameter(ConfigParameter::optional(
+                "n_rows",
+                ParameterType::Integer,
+                "none",
+                "Maximum number of rows to read",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Basic Parquet reading",
+                example1,
+                Some("Read every column and row of a Parquet file"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Projected sample",
+                example2,
+                Some("Read two columns of the first 1000 rows, renaming one"),
+            ))
+            .tag("parquet")
+            .tag("file")
+            .tag("io")
+            .tag("source")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        _inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let path = config
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Parquet source requires 'path' configuration"))?;
+        let projection = Projection::from_config(config)?;
+        let n_rows = n_rows(config)?;
+
+        if !Path::new(path).exists() {
+            anyhow::bail!("Parquet file not found: {}", path);
+        }
+
+        let args = ScanArgsParquet {
+            n_rows,
+            ..Default::default()
+        };
+        let lf = projection.apply_lazy(LazyFrame::scan_parquet(path, args)?, "Parquet source")?;
+
+        let df = lf.collect()?;
+        tracing::info!("Read {} rows from Parquet file: {}", df.height(), path);
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if !config.contains_key("path") {
+            anyhow::bail!("Parquet source requires 'path' configuration");
+        }
+        projection::validate_config(config)?;
+        n_rows(config)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+//! Column selection and renaming applied by file sources right after reading.
+//!
+//! `columns` keeps only the listed columns, in that order. Readers that can
+//! skip unused columns get the list pushed down: Parquet through the lazy
+//! scan, CSV through the reader's column option. `rename` is a table of
+//! `old_name = "new_name"` pairs applied afterwards, so `columns` names the
+//! columns as they are in the file.
+
+use anyhow::Result;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::metadata::{ConfigParameter, ParameterType};
+use crate::core::traits::{DataFormat, RecordBatch};
+
+/// Metadata parameters shared by every source supporting `columns` and `rename`
+pub(crate) fn parameters() -> Vec<ConfigParameter> {
+    vec![
+        ConfigParameter::optional(
+            "columns",
+            ParameterType::Array,
+            "none",
+            "Columns to read, in this order (defaults to all columns)",
+        ),
+        ConfigParameter::optional(
+            "rename",
+            ParameterType::Object,
+            "none",
+            "Table of old_name = \"new_name\" pairs applied after reading",
+        ),
+    ]
+}
+
+/// Validate the `columns` and `rename` options of a source config
+pub(crate) fn validate_config(config: &HashMap<String, toml::Value>) -> Result<()> {
+    Projection::from_config(config).map(|_| ())
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Projection {
+    pub(crate) columns: Option<Vec<String>>,
+    rename: Vec<(String, String)>,
+}
+
+impl Projection {
+    pub(crate) fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        let columns = match config.get("columns") {
+            None => None,
+            Some(toml::Value::Array(arr)) if !arr.is_empty() => Some(
+                arr.iter()
+                    .map(|v| {
+                        v.as_str()
+                            .map(|s| s.to_string())
+                            .ok_or_else(|| anyhow::anyhow!("'columns' must contain only strings"))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            Some(_) => anyhow::bail!("'columns' must be a non-empty array of column names"),
+        };
+
+        let rename = match config.get("rename") {
+            None => Vec::new(),
+            Some(toml::Value::Table(table)) => table
+                .iter()
+                .map(|(old, new)| match new {
+                    toml::Value::String(new) => Ok((old.clone(), new.clone())),
+                    _ => anyhow::bail!("New name for column '{}' must be a string", old),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => anyhow::bail!("'rename' must be a table of old_name = \"new_name\" pairs"),
+        };
+
+        Ok(Self { columns, rename })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.columns.is_none() && self.rename.is_empty()
+    }
+
+    /// Select and rename on a lazy scan, so the reader skips unused columns
+    pub(crate) fn apply_lazy(&self, mut lf: LazyFrame, source: &str) -> Result<LazyFrame> {
+        if self.is_empty() {
+            return Ok(lf);
+        }
+
+        let schema = lf.collect_schema()?;
+        if let Some(columns) = &self.columns {
+            if let Some(missing) = columns.iter().find(|c| !schema.contains(c.as_str())) {
+                anyhow::bail!("{}: column '{}' not found", source, missing);
+            }
+            lf = lf.select(columns.iter().map(|c| col(c.as_str())).collect::<Vec<_>>());
+        }
+
+        if !self.rename.is_empty() {
+            let kept = |name: &str| match &self.columns {
+                Some(columns) => columns.iter().any(|c| c == name),
+                None => schema.contains(name),
+            };
+            if let Some((missing, _)) = self.rename.iter().find(|(old, _)| !kept(old)) {
+                anyhow::bail!("{}: cannot rename missing column '{}'", source, missing);
+            }
+            lf = lf.rename(
+                self.rename.iter().map(|(old, _)| old),
+                self.rename.iter().map(|(_, new)| new),
+                true,
+            );
+        }
+
+        Ok(lf)
+    }
+
+    /// Select and rename the data a source has read
+    ///
+    /// Records keep their shape: a listed column a record lacks becomes null,
+    /// and renames only touch the records that have the column.
+    pub(crate) fn apply(&self, data: DataFormat, source: &str) -> Result<DataFormat> {
+        if self.is_empty() {
+            return Ok(data);
+        }
+
+        match data {
+            DataFormat::RecordBatch(records) => Ok(DataFormat::RecordBatch(
+                records
+                    .into_iter()
+                    .map(|record| self.apply_record(record))
+                    .collect::<RecordBatch>(),
+            )),
+            other => {
+                let df = other.as_dataframe()?;
+                Ok(DataFormat::DataFrame(
+                    self.apply_lazy(df.lazy(), source)?.collect()?,
+                ))
+            }
+        }
+    }
+
+    fn apply_record(
+        &self,
+        mut record: HashMap<String, serde_json::Value>,
+    ) -> HashMap<String, serde_json::Value> {
+        if let Some(columns) = &self.columns {
+            record = columns
+                .iter()
+                .map(|c| {
+                    let value = record.remove(c).unwrap_or(serde_json::Value::Null);
+                    (c.clone(), value)
+                })
+                .collect();
+        }
+
+        // Take every renamed value out first so that two columns can swap names
+        let renamed: Vec<(&String, serde_json::Value)> = self
+            .rename
+            .iter()
+            .filter_map(|(old, new)| record.remove(old).map(|value| (new, value)))
+            .collect();
+        for (new, value) in renamed {
+            record.insert(new.clone(), value);
+        }
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn projection(toml: &str) -> Projection {
+        Projection::from_config(&toml::from_str(toml).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_projection_on_records() {
+        let projection = projection(
+            r#"
+columns = ["id", "name", "city"]
+rename = { id = "name", name = "id" }
+"#,
+        );
+        let records: RecordBatch = vec![
+            serde_json::from_value(json!({"id": 1, "name": "Ann", "age": 40})).unwrap(),
+            serde_json::from_value(json!({"id": 2, "name": "Bob", "city": "Oslo"})).unwrap(),
+        ];
+
+        let records = projection
+            .apply(DataFormat::RecordBatch(records), "JSON source")
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&records).unwrap(),
+            json!([
+                {"name": 1, "id": "Ann", "city": null},
+                {"name": 2, "id": "Bob", "city": "Oslo"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_projection_config_errors() {
+        for bad in [
+            "columns = []",
+            "columns = \"id\"",
+            "rename = [\"id\"]",
+            "rename = { id = 1 }",
+        ] {
+            let config: HashMap<String, toml::Value> = toml::from_str(bad).unwrap();
+            assert!(validate_config(&config).is_err(), "{}", bad);
+        }
+
+        let df = df! { "id" => [1i64] }.unwrap();
+        let err = match projection("rename = { code = \"id2\" }")
+            .apply(DataFormat::DataFrame(df), "CSV source")
+        {
+            Ok(_) => panic!("expected a missing column error"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(err, "CSV source: cannot rename missing column 'code'");
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{SecondsFormat, Utc};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+use crate::core::metadata::{StageCategory, StageMetadata};
+use crate::core::run_context::RunContext;
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Emits one record describing the current run, for run-history tables
+pub struct RunInfoSource {
+    run: Arc<RunContext>,
+}
+
+impl RunInfoSource {
+    pub fn new(run: Arc<RunContext>) -> Self {
+        Self { run }
+    }
+}
+
+/// Number of records in `data`; streams are drained to count them
+async fn count_records(data: DataFormat) -> Result<usize> {
+    Ok(match data {
+        DataFormat::DataFrame(df) => df.height(),
+        DataFormat::RecordBatch(records) => records.len(),
+        DataFormat::Raw(_) => 0,
+        DataFormat::Stream(mut stream) => {
+            let mut count = 0;
+            while let Some(batch) = stream.next().await {
+                count += batch?.len();
+            }
+            count
+        }
+    })
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value
+        .as_ref()
+        .map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+}
+
+#[async_trait]
+impl Stage for RunInfoSource {
+    fn name(&self) -> &str {
+        "run_info.read"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        StageMetadata::builder("run_info.read", StageCategory::Source)
+            .description("Emit a record describing the current pipeline run")
+            .long_description(
+                "Emits a single record with the run's metadata: run_id (a UUID generated per run), \
+                pipeline and pipeline_version, config_hash (a short SHA-256 of the resolved \
+                pipeline configuration), started_at and emitted_at (RFC 3339, UTC) and elapsed_ms. \
+                When the stage has inputs it runs after them and input_records holds the number of \
+                records they produced; without inputs it is null. Connect it to a sink in append \
+                mode to keep a run history.",
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Record run history",
+                HashMap::new(),
+                Some("inputs = [\"write_orders\"], then send this stage to an appending sink"),
+            ))
+            .tag("audit")
+            .tag("metadata")
+            .tag("lineage")
+            .tag("source")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        _config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let input_records = if inputs.is_empty() {
+            JsonValue::Null
+        } else {
+            let mut total = 0;
+            for data in inputs.into_values() {
+                total += count_records(data).await?;
+            }
+            JsonValue::from(total)
+        };
+
+        let now = Utc::now();
+        let record = HashMap::from([
+            (
+                "run_id".to_string(),
+                JsonValue::String(self.run.run_id.clone()),
+            ),
+            ("pipeline".to_string(), optional_string(&self.run.pipeline)),
+            (
+                "pipeline_version".to_string(),
+                optional_string(&self.run.pipeline_version),
+            ),
+            (
+                "config_hash".to_string(),
+                optional_string(&self.run.config_hash),
+            ),
+            (
+                "started_at".to_string(),
+                JsonValue::String(
+                    self.run
+                        .started_at
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                ),
+            ),
+            (
+                "emitted_at".to_string(),
+                JsonValue::String(now.to_rfc3339_opts(SecondsFormat::Millis, true)),
+            ),
+            (
+                "elapsed_ms".to_string(),
+                JsonValue::from((now - self.run.started_at).num_milliseconds().max(0)),
+            ),
+            ("input_records".to_string(), input_records),
+        ]);
+
+        Ok(DataFormat::RecordBatch(vec![record]))
+    }
+
+    async fn validate_config(&self, _config: &HashMap<String, toml::Value>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::DagPipelineConfig;
+    use polars::prelude::*;
+
+    #[tokio::test]
+    async fn test_run_info_emits_run_id_and_timestamps() {
+        let config: DagPipelineConfig = toml::from_str(
+            r#"
+[pipeline]
+name = "orders"
+version = "2.1"
+
+[[stages]]
+id = "run"
+function = "run_info.read"
+inputs = []
+"#,
+        )
+        .unwrap();
+        let run = Arc::new(RunContext::for_config(&config));
+        let source = RunInfoSource::new(Arc::clone(&run));
+
+        let records = source
+            .execute(HashMap::new(), &HashMap::new())
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+
+        let run_id = record["run_id"].as_str().unwrap();
+        assert_eq!(run_id, run.run_id);
+        assert!(uuid::Uuid::parse_str(run_id).is_ok());
+        assert_eq!(record["pipeline"], "orders");
+        assert_eq!(record["pipeline_version"], "2.1");
+        assert_eq!(record["config_hash"].as_str().unwrap().len(), 12);
+        assert_eq!(record["input_records"], JsonValue::Null);
+
+        let started = record["started_at"].as_str().unwrap();
+        let emitted = record["emitted_at"].as_str().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(started).is_ok());
+        assert!(chrono::DateTime::parse_from_rfc3339(emitted).is_ok());
+        assert!(emitted >= started);
+
+        // The same configuration always hashes the same; a new run gets a new ID
+        let again = RunContext::for_config(&config);
+        assert_eq!(again.config_hash, run.config_hash);
+        assert_ne!(again.run_id, run.run_id);
+    }
+
+    #[tokio::test]
+    async fn test_run_info_counts_input_records() {
+        let source = RunInfoSource::new(Arc::new(RunContext::new()));
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "orders".to_string(),
+            DataFormat::DataFrame(df! { "id" => &[1i64, 2, 3] }.unwrap()),
+        );
+        inputs.insert(
+            "refunds".to_string(),
+            DataFormat::RecordBatch(vec![HashMap::new()]),
+        );
+
+        let records = source
+            .execute(inputs, &HashMap::new())
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(records[0]["input_records"], 4);
+        assert_eq!(records[0]["pipeline"], JsonValue::Null);
+    }
+}
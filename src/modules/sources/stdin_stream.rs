@@ -23,8 +23,8 @@ impl StdinStreamSource {
         Self
     }
 
-    /// Parse a single line based on format
-    fn parse_line(line: String, format: &str) -> Result<HashMap<String, JsonValue>> {
+    /// Parse a single line based on format (also used by `socket.read`)
+    pub(crate) fn parse_line(line: String, format: &str) -> Result<HashMap<String, JsonValue>> {
         match format {
             "json" | "jsonl" => {
                 let record: HashMap<String, JsonValue> = serde_json::from_str(&line)?;
@@ -99,23 +99,30 @@ impl StreamingDataSource for StdinStreamSource {
     }
 
     async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
-        if let Some(format_value) = config.get("format") {
-            let format = format_value
-                .as_str()
-                .ok_or_else(|| anyhow::anyhow!("format must be a string"))?;
-
-            let valid_formats = ["json", "jsonl", "csv", "text"];
-            if !valid_formats.contains(&format) {
-                anyhow::bail!(
-                    "Invalid format: {}. Must be one of: {:?}",
-                    format,
-                    valid_formats
-                );
-            }
-        }
+        validate_format(config)
+    }
+}
 
-        Ok(())
+/// Line framings understood by `parse_line`
+pub(crate) const LINE_FORMATS: [&str; 4] = ["json", "jsonl", "csv", "text"];
+
+/// Check the optional `format` option against `LINE_FORMATS`
+pub(crate) fn validate_format(config: &HashMap<String, toml::Value>) -> Result<()> {
+    if let Some(format_value) = config.get("format") {
+        let format = format_value
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("format must be a string"))?;
+
+        if !LINE_FORMATS.contains(&format) {
+            anyhow::bail!(
+                "Invalid format: {}. Must be one of: {:?}",
+                format,
+                LINE_FORMATS
+            );
+        }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,386 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing::info;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+use crate::modules::sources::file_watch::{self, FileOffset, FileWatchSource, FileWatchState};
+
+/// Streaming source that follows lines appended to a file, like `tail -F`
+pub struct TailSource;
+
+const TAIL_FORMATS: [&str; 2] = ["jsonl", "text"];
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Bytes read per step when scanning backwards for the last lines
+const SCAN_CHUNK: u64 = 64 * 1024;
+
+struct TailOptions {
+    path: PathBuf,
+    format: String,
+    lines: u64,
+    poll_interval: Duration,
+    state_file: Option<PathBuf>,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<TailOptions> {
+    let path = match config.get("path") {
+        Some(toml::Value::String(s)) if !s.is_empty() => PathBuf::from(s),
+        Some(_) => anyhow::bail!("'path' must be a non-empty string"),
+        None => anyhow::bail!("tail.read requires 'path' configuration"),
+    };
+
+    let format = match config.get("format") {
+        None => "jsonl".to_string(),
+        Some(toml::Value::String(s)) if TAIL_FORMATS.contains(&s.as_str()) => s.clone(),
+        Some(_) => anyhow::bail!("'format' must be one of: {:?}", TAIL_FORMATS),
+    };
+
+    let non_negative = |name: &str| -> Result<Option<u64>> {
+        match config.get(name) {
+            None => Ok(None),
+            Some(toml::Value::Integer(n)) if *n >= 0 => Ok(Some(*n as u64)),
+            Some(_) => anyhow::bail!("'{}' must be a non-negative integer", name),
+        }
+    };
+    let lines = non_negative("lines")?.unwrap_or(0);
+    let poll_interval = Duration::from_millis(
+        non_negative("poll_interval_ms")?.unwrap_or(DEFAULT_POLL_INTERVAL_MS),
+    );
+
+    let state_file = match config.get("state_file") {
+        None => None,
+        Some(toml::Value::String(s)) => Some(PathBuf::from(s)),
+        Some(_) => anyhow::bail!("'state_file' must be a string path"),
+    };
+
+    Ok(TailOptions {
+        path,
+        format,
+        lines,
+        poll_interval,
+        state_file,
+    })
+}
+
+/// Offset where the last `lines` complete lines of `path` start
+///
+/// A trailing line without a newline is still being written, so it is not
+/// counted and is emitted once it is complete. With `lines = 0` this is the
+/// end of the last complete line.
+async fn last_lines_offset(path: &Path, len: u64, lines: u64) -> Result<u64> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut newlines = 0;
+    let mut end = len;
+    let mut buffer = Vec::new();
+
+    while end > 0 {
+        let start = end.saturating_sub(SCAN_CHUNK);
+        buffer.resize((end - start) as usize, 0);
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        file.read_exact(&mut buffer).await?;
+
+        for (i, byte) in buffer.iter().enumerate().rev() {
+            if *byte == b'\n' {
+                newlines += 1;
+                // The newline before the first wanted line
+                if newlines > lines {
+                    return Ok(start + i as u64 + 1);
+                }
+            }
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+#[async_trait]
+impl Stage for TailSource {
+    fn name(&self) -> &str {
+        "tail.read"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "path".to_string(),
+            toml::Value::String("/var/log/app/events.jsonl".to_string()),
+        );
+        example1.insert(
+            "state_file".to_string(),
+            toml::Value::String("/var/lib/conveyor/events.tail.json".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "path".to_string(),
+            toml::Value::String("/var/log/nginx/access.log".to_string()),
+        );
+        example2.insert(
+            "format".to_string(),
+            toml::Value::String("text".to_string()),
+        );
+        example2.insert("lines".to_string(), toml::Value::Integer(100));
+        example2.insert("poll_interval_ms".to_string(), toml::Value::Integer(250));
+
+        StageMetadata::builder("tail.read", StageCategory::Source)
+            .description("Follow lines appended to a file")
+            .long_description(
+                "Starts at the end of the file, or 'lines' lines before it, and streams lines \
+                as they are appended, like 'tail -F'. Existing content is not re-read. The file \
+                is polled every 'poll_interval_ms'; when it is truncated or replaced by a new \
+                file (log rotation) it is reopened and read from the start. A line is emitted \
+                once its newline has been written. With 'state_file' the position is saved after \
+                each batch, and a restarted pipeline resumes there instead of at the end.",
+            )
+            .parameter(ConfigParameter::required(
+                "path",
+                ParameterType::String,
+                "File to follow",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "format",
+                    ParameterType::String,
+                    "jsonl",
+                    "Line format: 'jsonl' objects or 'text' lines in a 'line' field",
+                )
+                .with_validation(ParameterValidation::allowed_values(TAIL_FORMATS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "lines",
+                ParameterType::Integer,
+                "0",
+                "Number of existing lines to emit before following",
+            ))
+            .parameter(ConfigParameter::optional(
+                "poll_interval_ms",
+                ParameterType::Integer,
+                "1000",
+                "How often to check the file for new lines",
+            ))
+            .parameter(ConfigParameter::optional(
+                "state_file",
+                ParameterType::String,
+                "none",
+                "JSON file persisting the position so restarts resume where they left off",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Follow a JSON Lines log",
+                example1,
+                Some("Ingest new events, resuming after a restart"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Follow a text log",
+                example2,
+                Some("Emit the last 100 lines, then every new line"),
+            ))
+            .tag("tail")
+            .tag("file")
+            .tag("log")
+            .tag("stream")
+            .tag("source")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        _inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let options = parse_options(config)?;
+        let key = options.path.to_string_lossy().to_string();
+
+        let mut state = match &options.state_file {
+            Some(state_path) => FileWatchState::load(state_path).await?,
+            None => FileWatchState::default(),
+        };
+
+        // Without a saved position, start near the end of what is there now;
+        // a file that does not exist yet is read from its start once it appears
+        if let std::collections::hash_map::Entry::Vacant(entry) = state.files.entry(key) {
+            if let Ok(metadata) = tokio::fs::metadata(&options.path).await {
+                let offset = last_lines_offset(&options.path, metadata.len(), options.lines)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to read {:?}: {}", options.path, e))?;
+                entry.insert(FileOffset {
+                    offset,
+                    modified_ms: 0,
+                    inode: file_watch::file_inode(&metadata),
+                });
+            }
+        }
+
+        info!(
+            "Tailing {:?} (format: {}, poll_interval: {:?})",
+            options.path, options.format, options.poll_interval
+        );
+
+        Ok(DataFormat::Stream(FileWatchSource::watch(
+            options.path,
+            options.format,
+            "tail".to_string(),
+            options.poll_interval,
+            options.state_file,
+            state,
+        )))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::traits::RecordBatch;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use tokio::io::AsyncWriteExt;
+    use tokio_stream::StreamExt;
+
+    fn config(path: &Path, lines: i64) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            (
+                "path".to_string(),
+                toml::Value::String(path.to_string_lossy().to_string()),
+            ),
+            (
+                "format".to_string(),
+                toml::Value::String("text".to_string()),
+            ),
+            ("lines".to_string(), toml::Value::Integer(lines)),
+            ("poll_interval_ms".to_string(), toml::Value::Integer(10)),
+        ])
+    }
+
+    async fn append(path: &Path, content: &str) {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .unwrap();
+        file.write_all(content.as_bytes()).await.unwrap();
+    }
+
+    fn lines(batch: RecordBatch) -> Vec<serde_json::Value> {
+        batch
+            .into_iter()
+            .map(|record| record["line"].clone())
+            .collect()
+    }
+
+    async fn tail(
+        config: &HashMap<String, toml::Value>,
+    ) -> std::pin::Pin<Box<dyn tokio_stream::Stream<Item = Result<RecordBatch>> + Send>> {
+        let DataFormat::Stream(stream) = TailSource.execute(HashMap::new(), config).await.unwrap()
+        else {
+            panic!("tail.read should return a stream");
+        };
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_tail_skips_existing_content() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("app.log");
+        tokio::fs::write(&path, "old 1\nold 2\npart").await.unwrap();
+
+        let mut stream = tail(&config(&path, 0)).await;
+        append(&path, "ial\nnew\n").await;
+
+        // The line being written at startup is emitted once complete
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(lines(batch), vec![json!("partial"), json!("new")]);
+    }
+
+    #[tokio::test]
+    async fn test_tail_last_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("app.log");
+        tokio::fs::write(&path, "a\nb\nc\n").await.unwrap();
+
+        assert_eq!(last_lines_offset(&path, 6, 0).await.unwrap(), 6);
+        assert_eq!(last_lines_offset(&path, 6, 2).await.unwrap(), 2);
+        assert_eq!(last_lines_offset(&path, 6, 10).await.unwrap(), 0);
+
+        let mut stream = tail(&config(&path, 2)).await;
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(lines(batch), vec![json!("b"), json!("c")]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_tail_follows_rotation() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("app.log");
+        tokio::fs::write(&path, "before\n").await.unwrap();
+
+        let mut stream = tail(&config(&path, 0)).await;
+        append(&path, "first\n").await;
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(lines(batch), vec![json!("first")]);
+
+        // Rotate: the old file moves away and a new, longer one takes its place
+        tokio::fs::rename(&path, dir.path().join("app.log.1"))
+            .await
+            .unwrap();
+        tokio::fs::write(&path, "rotated 1\nrotated 2\n")
+            .await
+            .unwrap();
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(lines(batch), vec![json!("rotated 1"), json!("rotated 2")]);
+    }
+
+    #[tokio::test]
+    async fn test_tail_resumes_from_state_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("app.log");
+        let mut config = config(&path, 0);
+        config.insert(
+            "state_file".to_string(),
+            toml::Value::String(dir.path().join("tail.json").to_string_lossy().to_string()),
+        );
+        tokio::fs::write(&path, "old\n").await.unwrap();
+
+        {
+            let mut stream = tail(&config).await;
+            append(&path, "one\n").await;
+            let batch = stream.next().await.unwrap().unwrap();
+            assert_eq!(lines(batch), vec![json!("one")]);
+            // Asking for the next batch saves the position of the first
+            append(&path, "two\n").await;
+            stream.next().await.unwrap().unwrap();
+        }
+
+        // The unacknowledged "two" comes again, and lines written while
+        // stopped are picked up rather than skipped as history
+        append(&path, "three\n").await;
+        let mut stream = tail(&config).await;
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(lines(batch), vec![json!("two"), json!("three")]);
+
+        assert!(TailSource
+            .validate_config(&HashMap::from([(
+                "path".to_string(),
+                toml::Value::String("x.json".to_string())
+            )]))
+            .await
+            .is_ok());
+        let mut invalid = config.clone();
+        invalid.insert(
+            "format".to_string(),
+            toml::Value::String("json".to_string()),
+        );
+        assert!(TailSource.validate_config(&invalid).await.is_err());
+    }
+}
@@ -0,0 +1,643 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::common::string_list_option;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+use crate::utils::state_file::{load_json, save_json};
+
+/// Incremental aggregation transform
+/// Merges each batch's group aggregates into state persisted between runs
+pub struct AggStateTransform;
+
+/// HyperLogLog precision: 2^12 registers, about 1.6% standard error
+const HLL_PRECISION: u32 = 12;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    Sum,
+    Count,
+    Min,
+    Max,
+    DistinctApprox,
+}
+
+impl Operation {
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sum" => Ok(Operation::Sum),
+            "count" => Ok(Operation::Count),
+            "min" => Ok(Operation::Min),
+            "max" => Ok(Operation::Max),
+            "distinct_approx" => Ok(Operation::DistinctApprox),
+            _ => anyhow::bail!(
+                "Unsupported operation '{}'. Supported: sum, count, min, max, distinct_approx",
+                s
+            ),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Sum => "sum",
+            Operation::Count => "count",
+            Operation::Min => "min",
+            Operation::Max => "max",
+            Operation::DistinctApprox => "distinct_approx",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Aggregation {
+    column: String,
+    operation: Operation,
+    output: String,
+}
+
+#[derive(Debug, Clone)]
+struct Options {
+    state_file: PathBuf,
+    by: Vec<String>,
+    aggregations: Vec<Aggregation>,
+}
+
+impl Options {
+    /// Describes what the state was built from, e.g. `total=sum(amount)`
+    fn signature(&self) -> Vec<String> {
+        self.aggregations
+            .iter()
+            .map(|agg| format!("{}={}({})", agg.output, agg.operation.name(), agg.column))
+            .collect()
+    }
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let state_file = match config.get("state_file") {
+        Some(toml::Value::String(s)) if !s.is_empty() => PathBuf::from(s),
+        Some(_) => anyhow::bail!("'state_file' must be a non-empty string path"),
+        None => anyhow::bail!("Missing required 'state_file' configuration"),
+    };
+
+    let by = string_list_option(config, "by")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'by' configuration"))?;
+    if by.is_empty() {
+        anyhow::bail!("'by' must name at least one column");
+    }
+
+    let specs = config
+        .get("aggregations")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'aggregations' configuration"))?;
+    if specs.is_empty() {
+        anyhow::bail!("'aggregations' must contain at least one aggregation");
+    }
+
+    let mut outputs: HashSet<&str> = by.iter().map(String::as_str).collect();
+    let mut aggregations = Vec::with_capacity(specs.len());
+    for spec in specs {
+        let table = spec
+            .as_table()
+            .ok_or_else(|| anyhow::anyhow!("Each aggregation must be a table"))?;
+        let column = table
+            .get("column")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Aggregation requires 'column' field"))?;
+        let operation = Operation::from_str(
+            table
+                .get("operation")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Aggregation requires 'operation' field"))?,
+        )?;
+        let output = table
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or(column);
+        aggregations.push(Aggregation {
+            column: column.to_string(),
+            operation,
+            output: output.to_string(),
+        });
+    }
+    for agg in &aggregations {
+        if !outputs.insert(agg.output.as_str()) {
+            anyhow::bail!(
+                "Output column '{}' is used more than once; set a distinct 'output_column'",
+                agg.output
+            );
+        }
+    }
+
+    Ok(Options {
+        state_file,
+        by,
+        aggregations,
+    })
+}
+
+/// Running value of one aggregation for one group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Accumulator {
+    /// Integer while every added value was an integer
+    Sum {
+        value: JsonValue,
+    },
+    Count {
+        value: u64,
+    },
+    Min {
+        value: JsonValue,
+    },
+    Max {
+        value: JsonValue,
+    },
+    /// HyperLogLog registers
+    DistinctApprox {
+        #[serde(with = "registers_base64")]
+        registers: Vec<u8>,
+    },
+}
+
+mod registers_base64 {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(registers: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(registers))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Accumulator {
+    fn new(operation: Operation) -> Self {
+        match operation {
+            Operation::Sum => Accumulator::Sum {
+                value: JsonValue::from(0),
+            },
+            Operation::Count => Accumulator::Count { value: 0 },
+            Operation::Min => Accumulator::Min {
+                value: JsonValue::Null,
+            },
+            Operation::Max => Accumulator::Max {
+                value: JsonValue::Null,
+            },
+            Operation::DistinctApprox => Accumulator::DistinctApprox {
+                registers: vec![0; HLL_REGISTERS],
+            },
+        }
+    }
+
+    /// Fold one row's value in; nulls are ignored by every operation
+    fn add(&mut self, column: &str, value: &JsonValue) -> Result<()> {
+        if value.is_null() {
+            return Ok(());
+        }
+        match self {
+            Accumulator::Sum { value: total } => {
+                if !value.is_number() {
+                    anyhow::bail!(
+                        "agg_state: 'sum' of column '{}' needs numbers, got {}",
+                        column,
+                        value
+                    );
+                }
+                *total = add_numbers(total, value);
+            }
+            Accumulator::Count { value: count } => *count += 1,
+            Accumulator::Min { value: current } => {
+                if current.is_null() || compare(column, value, current)? == Ordering::Less {
+                    *current = value.clone();
+                }
+            }
+            Accumulator::Max { value: current } => {
+                if current.is_null() || compare(column, value, current)? == Ordering::Greater {
+                    *current = value.clone();
+                }
+            }
+            Accumulator::DistinctApprox { registers } => {
+                let (index, rank) = hll_position(value);
+                registers[index] = registers[index].max(rank);
+            }
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> JsonValue {
+        match self {
+            Accumulator::Sum { value }
+            | Accumulator::Min { value }
+            | Accumulator::Max { value } => value.clone(),
+            Accumulator::Count { value } => JsonValue::from(*value),
+            Accumulator::DistinctApprox { registers } => JsonValue::from(hll_estimate(registers)),
+        }
+    }
+}
+
+fn add_numbers(a: &JsonValue, b: &JsonValue) -> JsonValue {
+    if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+        if let Some(sum) = a.checked_add(b) {
+            return JsonValue::from(sum);
+        }
+    }
+    let sum = a.as_f64().unwrap_or(0.0) + b.as_f64().unwrap_or(0.0);
+    serde_json::Number::from_f64(sum)
+        .map(JsonValue::Number)
+        .unwrap_or(JsonValue::Null)
+}
+
+/// Order two non-null values of the same kind (numbers or strings)
+fn compare(column: &str, a: &JsonValue, b: &JsonValue) -> Result<Ordering> {
+    match (a, b) {
+        (JsonValue::Number(x), JsonValue::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(f64::NAN), y.as_f64().unwrap_or(f64::NAN));
+            Ok(x.partial_cmp(&y).unwrap_or(Ordering::Equal))
+        }
+        (JsonValue::String(x), JsonValue::String(y)) => Ok(x.cmp(y)),
+        _ => anyhow::bail!(
+            "agg_state: cannot compare {} with {} in column '{}'; min and max need numbers or strings",
+            a,
+            b,
+            column
+        ),
+    }
+}
+
+/// Register index and rank of a value, from a hash that is stable across runs
+fn hll_position(value: &JsonValue) -> (usize, u8) {
+    let digest = Sha256::digest(value.to_string().as_bytes());
+    let hash = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    let index = (hash >> (64 - HLL_PRECISION)) as usize;
+    let rest = hash << HLL_PRECISION;
+    let rank = (rest.leading_zeros() + 1).min(64 - HLL_PRECISION + 1) as u8;
+    (index, rank)
+}
+
+fn hll_estimate(registers: &[u8]) -> u64 {
+    let m = registers.len() as f64;
+    let alpha = 0.7213 / (1.0 + 1.079 / m);
+    let sum: f64 = registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+    let estimate = alpha * m * m / sum;
+
+    // Linear counting is more accurate while many registers are still empty
+    let zeros = registers.iter().filter(|&&r| r == 0).count();
+    let estimate = if estimate <= 2.5 * m && zeros > 0 {
+        m * (m / zeros as f64).ln()
+    } else {
+        estimate
+    };
+    estimate.round() as u64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Group {
+    key: Vec<JsonValue>,
+    values: Vec<Accumulator>,
+}
+
+/// Persisted aggregates, keyed by the JSON text of each group's key
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AggState {
+    by: Vec<String>,
+    aggregations: Vec<String>,
+    groups: BTreeMap<String, Group>,
+}
+
+impl AggState {
+    /// Load state from disk, starting empty if the file does not exist yet
+    async fn load(path: &Path, options: &Options) -> Result<Self> {
+        let Some(state) = load_json::<Self>(path, "agg_state state file").await? else {
+            return Ok(Self {
+                by: options.by.clone(),
+                aggregations: options.signature(),
+                groups: BTreeMap::new(),
+            });
+        };
+
+        if state.by != options.by || state.aggregations != options.signature() {
+            anyhow::bail!(
+                "agg_state: state file {:?} was written for by = {:?}, aggregations = {:?}; \
+                delete it to start over with the new configuration",
+                path,
+                state.by,
+                state.aggregations
+            );
+        }
+        Ok(state)
+    }
+
+    /// Save state atomically (write to a temp file, then rename)
+    async fn save(&self, path: &Path) -> Result<()> {
+        save_json(path, self).await
+    }
+
+    fn merge(&mut self, options: &Options, records: &RecordBatch) -> Result<()> {
+        for record in records {
+            let key: Vec<JsonValue> = options
+                .by
+                .iter()
+                .map(|column| record.get(column).cloned().unwrap_or(JsonValue::Null))
+                .collect();
+            let group = self
+                .groups
+                .entry(serde_json::to_string(&key)?)
+                .or_insert_with(|| Group {
+                    key,
+                    values: options
+                        .aggregations
+                        .iter()
+                        .map(|agg| Accumulator::new(agg.operation))
+                        .collect(),
+                });
+
+            for (agg, accumulator) in options.aggregations.iter().zip(&mut group.values) {
+                let value = record.get(&agg.column).unwrap_or(&JsonValue::Null);
+                accumulator.add(&agg.column, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn results(&self, options: &Options) -> RecordBatch {
+        self.groups
+            .values()
+            .map(|group| {
+                let mut row: HashMap<String, JsonValue> = options
+                    .by
+                    .iter()
+                    .cloned()
+                    .zip(group.key.iter().cloned())
+                    .collect();
+                for (agg, accumulator) in options.aggregations.iter().zip(&group.values) {
+                    row.insert(agg.output.clone(), accumulator.result());
+                }
+                row
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Stage for AggStateTransform {
+    fn name(&self) -> &str {
+        "agg_state.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert(
+            "state_file".to_string(),
+            toml::Value::String("state/daily_totals.json".to_string()),
+        );
+        example.insert("by".to_string(), toml::Value::String("region".to_string()));
+        let aggregation = |column: &str, operation: &str, output: &str| {
+            let mut table = toml::map::Map::new();
+            table.insert(
+                "column".to_string(),
+                toml::Value::String(column.to_string()),
+            );
+            table.insert(
+                "operation".to_string(),
+                toml::Value::String(operation.to_string()),
+            );
+            table.insert(
+                "output_column".to_string(),
+                toml::Value::String(output.to_string()),
+            );
+            toml::Value::Table(table)
+        };
+        example.insert(
+            "aggregations".to_string(),
+            toml::Value::Array(vec![
+                aggregation("amount", "sum", "revenue"),
+                aggregation("order_id", "count", "orders"),
+                aggregation("customer_id", "distinct_approx", "customers"),
+            ]),
+        );
+
+        StageMetadata::builder("agg_state.apply", StageCategory::Transform)
+            .description("Group aggregates that accumulate across runs")
+            .long_description(
+                "Groups the rows of each batch by the 'by' columns and merges their aggregates \
+                into the state kept in 'state_file', then writes the state back and outputs the \
+                cumulative aggregate of every group seen so far, one row per group. Operations: \
+                sum, count (non-null values), min, max and distinct_approx, an approximate \
+                distinct count (HyperLogLog, about 1.6% error) that stays correct when the same \
+                value appears in several batches. Nulls are ignored. The state file is created on \
+                the first run; it records the 'by' and 'aggregations' settings and is rejected if \
+                they change.",
+            )
+            .parameter(ConfigParameter::required(
+                "state_file",
+                ParameterType::String,
+                "JSON file holding the aggregates between runs",
+            ))
+            .parameter(ConfigParameter::required(
+                "by",
+                ParameterType::Array,
+                "Column name(s) to group by (string or array of strings)",
+            ))
+            .parameter(ConfigParameter::required(
+                "aggregations",
+                ParameterType::Array,
+                "Array of {column, operation, output_column?}; operation is sum, count, min, max or distinct_approx",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Running daily totals",
+                example,
+                Some("Revenue, order count and approximate distinct customers per region, across all batches"),
+            ))
+            .tag("aggregation")
+            .tag("incremental")
+            .tag("state")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let options = parse_options(config)?;
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("agg_state transform requires input data"))?;
+        let records = data.as_record_batch()?;
+
+        let mut state = AggState::load(&options.state_file, &options).await?;
+        state.merge(&options, &records)?;
+        state.save(&options.state_file).await?;
+        tracing::info!(
+            "agg_state: merged {} record(s), {} group(s) in {:?}",
+            records.len(),
+            state.groups.len(),
+            options.state_file
+        );
+
+        Ok(DataFormat::RecordBatch(state.results(&options)))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(state_file: &Path) -> HashMap<String, toml::Value> {
+        let mut config: HashMap<String, toml::Value> = toml::from_str(
+            r#"
+by = "region"
+aggregations = [
+  { column = "amount", operation = "sum", output_column = "total" },
+  { column = "amount", operation = "count", output_column = "orders" },
+  { column = "amount", operation = "min", output_column = "smallest" },
+  { column = "amount", operation = "max", output_column = "largest" },
+  { column = "user", operation = "distinct_approx", output_column = "users" },
+]
+"#,
+        )
+        .unwrap();
+        config.insert(
+            "state_file".to_string(),
+            toml::Value::String(state_file.to_string_lossy().to_string()),
+        );
+        config
+    }
+
+    fn batch(rows: Vec<JsonValue>) -> HashMap<String, DataFormat> {
+        let records = rows
+            .into_iter()
+            .map(|row| serde_json::from_value(row).unwrap())
+            .collect();
+        HashMap::from([("orders".to_string(), DataFormat::RecordBatch(records))])
+    }
+
+    async fn run(
+        config: &HashMap<String, toml::Value>,
+        rows: Vec<JsonValue>,
+    ) -> Result<HashMap<String, HashMap<String, JsonValue>>> {
+        let records = AggStateTransform
+            .execute(batch(rows), config)
+            .await?
+            .as_record_batch()?;
+        Ok(records
+            .into_iter()
+            .map(|row| (row["region"].as_str().unwrap().to_string(), row))
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_agg_state_accumulates_across_batches() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config(&dir.path().join("state/totals.json"));
+
+        let day1 = run(
+            &config,
+            vec![
+                json!({"region": "eu", "amount": 10, "user": "ann"}),
+                json!({"region": "eu", "amount": 5, "user": "bob"}),
+                json!({"region": "us", "amount": 7, "user": "ann"}),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(day1["eu"]["total"], json!(15));
+        assert_eq!(day1["eu"]["orders"], json!(2));
+        assert_eq!(day1["eu"]["users"], json!(2));
+
+        let day2 = run(
+            &config,
+            vec![
+                json!({"region": "eu", "amount": 2.5, "user": "ann"}),
+                json!({"region": "asia", "amount": 1, "user": "cy"}),
+                json!({"region": "us", "amount": null, "user": "di"}),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(day2.len(), 3);
+        assert_eq!(day2["eu"]["total"], json!(17.5));
+        assert_eq!(day2["eu"]["orders"], json!(3));
+        assert_eq!(day2["eu"]["smallest"], json!(2.5));
+        assert_eq!(day2["eu"]["largest"], json!(10));
+        // 'ann' was already counted on day 1
+        assert_eq!(day2["eu"]["users"], json!(2));
+        assert_eq!(day2["us"]["total"], json!(7));
+        assert_eq!(day2["us"]["orders"], json!(1));
+        assert_eq!(day2["us"]["users"], json!(2));
+        assert_eq!(day2["asia"]["total"], json!(1));
+
+        // An empty batch still reports the cumulative totals
+        let day3 = run(&config, vec![]).await.unwrap();
+        assert_eq!(day3["eu"]["total"], json!(17.5));
+    }
+
+    #[tokio::test]
+    async fn test_distinct_approx_merges_overlapping_batches() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config(&dir.path().join("totals.json"));
+        let rows = |ids: std::ops::Range<i32>| {
+            ids.map(|i| json!({"region": "eu", "user": format!("user-{}", i)}))
+                .collect()
+        };
+
+        run(&config, rows(0..6000)).await.unwrap();
+        let result = run(&config, rows(4000..10000)).await.unwrap();
+        let users = result["eu"]["users"].as_u64().unwrap();
+        assert!((9500..=10500).contains(&users), "estimate {}", users);
+    }
+
+    #[tokio::test]
+    async fn test_agg_state_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state_file = dir.path().join("totals.json");
+        let mut config = config(&state_file);
+        run(&config, vec![json!({"region": "eu", "amount": 1})])
+            .await
+            .unwrap();
+
+        let err = run(&config, vec![json!({"region": "eu", "amount": "ten"})])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "agg_state: 'sum' of column 'amount' needs numbers, got \"ten\""
+        );
+
+        config.insert("by".to_string(), toml::Value::String("country".to_string()));
+        let err = run(&config, vec![]).await.unwrap_err().to_string();
+        assert!(err.contains("delete it to start over"), "{}", err);
+
+        let mut invalid = config.clone();
+        invalid.insert(
+            "aggregations".to_string(),
+            toml::from_str::<toml::Table>("a = [{ column = \"amount\", operation = \"median\" }]")
+                .unwrap()["a"]
+                .clone(),
+        );
+        assert!(AggStateTransform.validate_config(&invalid).await.is_err());
+        invalid.remove("state_file");
+        assert!(AggStateTransform.validate_config(&invalid).await.is_err());
+    }
+}
@@ -11,23 +11,14 @@ use crate::core::metadata::{
 };
 use crate::core::stage::Stage;
 use crate::core::traits::DataFormat;
+use crate::utils::http::HttpContext;
 
-#[derive(Debug, Clone)]
-pub struct AiGenerateTransform {
-    client: reqwest::Client,
-}
-
-impl Default for AiGenerateTransform {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+#[derive(Debug, Clone, Default)]
+pub struct AiGenerateTransform;
 
 impl AiGenerateTransform {
     pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-        }
+        Self
     }
 }
 
@@ -137,6 +128,7 @@ struct OllamaResponse {
 impl AiGenerateTransform {
     async fn call_openai(
         &self,
+        http: &HttpContext,
         api_key: &str,
         model: &str,
         prompt: &str,
@@ -153,13 +145,14 @@ impl AiGenerateTransform {
             temperature,
         };
 
-        let response = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+        let response = http
+            .send(|client| {
+                client
+                    .post("https://api.openai.com/v1/chat/completions")
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            })
             .await?;
 
         if !response.status().is_success() {
@@ -180,6 +173,7 @@ impl AiGenerateTransform {
 
     async fn call_anthropic(
         &self,
+        http: &HttpContext,
         api_key: &str,
         model: &str,
         prompt: &str,
@@ -196,14 +190,15 @@ impl AiGenerateTransform {
             temperature,
         };
 
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+        let response = http
+            .send(|client| {
+                client
+                    .post("https://api.anthropic.com/v1/messages")
+                    .header("x-api-key", api_key)
+                    .header("anthropic-version", "2023-06-01")
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            })
             .await?;
 
         if !response.status().is_success() {
@@ -223,6 +218,7 @@ impl AiGenerateTransform {
 
     async fn call_openrouter(
         &self,
+        http: &HttpContext,
         api_key: &str,
         model: &str,
         prompt: &str,
@@ -240,13 +236,14 @@ impl AiGenerateTransform {
             temperature,
         };
 
-        let response = self
-            .client
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+        let response = http
+            .send(|client| {
+                client
+                    .post("https://openrouter.ai/api/v1/chat/completions")
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            })
             .await?;
 
         if !response.status().is_success() {
@@ -267,6 +264,7 @@ impl AiGenerateTransform {
 
     async fn call_ollama(
         &self,
+        http: &HttpContext,
         base_url: &str,
         model: &str,
         prompt: &str,
@@ -284,12 +282,13 @@ impl AiGenerateTransform {
         };
 
         let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+        let response = http
+            .send(|client| {
+                client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            })
             .await?;
 
         if !response.status().is_success() {
@@ -398,7 +397,7 @@ impl Stage for AiGenerateTransform {
                 "api_key_env",
                 ParameterType::String,
                 "auto-detected",
-                "Environment variable name for API key (defaults: OPENAI_API_KEY, ANTHROPIC_API_KEY, etc.)"
+                "Environment variable this stage reads its API key from, resolved at execution (defaults: OPENAI_API_KEY, ANTHROPIC_API_KEY, etc.)"
             ))
             .parameter(ConfigParameter::optional(
                 "api_base_url",
@@ -406,6 +405,7 @@ impl Stage for AiGenerateTransform {
                 "http://localhost:11434",
                 "Base URL for Ollama (only applies to Ollama provider)"
             ))
+            .parameters(crate::utils::http::parameters())
             .example(crate::core::metadata::ConfigExample::new(
                 "Text summarization with OpenAI",
                 example1,
@@ -468,23 +468,8 @@ impl Stage for AiGenerateTransform {
             .and_then(|v| v.as_float())
             .map(|f| f as f32);
 
-        // Get API key from environment
-        let api_key_env = config
-            .get("api_key_env")
-            .and_then(|v| v.as_str())
-            .unwrap_or(match provider {
-                AiProvider::OpenAI => "OPENAI_API_KEY",
-                AiProvider::Anthropic => "ANTHROPIC_API_KEY",
-                AiProvider::OpenRouter => "OPENROUTER_API_KEY",
-                AiProvider::Ollama => "", // Ollama doesn't need API key
-            });
-
-        let api_key = if !api_key_env.is_empty() {
-            env::var(api_key_env)
-                .map_err(|_| anyhow::anyhow!("Environment variable '{}' not set", api_key_env))?
-        } else {
-            String::new()
-        };
+        // Resolved per stage, so stages using different providers or accounts don't collide
+        let api_key = resolve_api_key(config, &provider)?;
 
         // Get Ollama base URL if using Ollama
         let ollama_base_url = config
@@ -492,6 +477,8 @@ impl Stage for AiGenerateTransform {
             .and_then(|v| v.as_str())
             .unwrap_or("http://localhost:11434");
 
+        let http = HttpContext::from_config(config)?;
+
         let mut df = data.as_dataframe()?;
 
         // Setup Handlebars for template rendering
@@ -542,20 +529,27 @@ impl Stage for AiGenerateTransform {
             // Call appropriate provider
             let response = match provider {
                 AiProvider::OpenAI => {
-                    self.call_openai(&api_key, model, &prompt, max_tokens, temperature)
+                    self.call_openai(&http, &api_key, model, &prompt, max_tokens, temperature)
                         .await?
                 }
                 AiProvider::Anthropic => {
-                    self.call_anthropic(&api_key, model, &prompt, max_tokens, temperature)
+                    self.call_anthropic(&http, &api_key, model, &prompt, max_tokens, temperature)
                         .await?
                 }
                 AiProvider::OpenRouter => {
-                    self.call_openrouter(&api_key, model, &prompt, max_tokens, temperature)
+                    self.call_openrouter(&http, &api_key, model, &prompt, max_tokens, temperature)
                         .await?
                 }
                 AiProvider::Ollama => {
-                    self.call_ollama(ollama_base_url, model, &prompt, max_tokens, temperature)
-                        .await?
+                    self.call_ollama(
+                        &http,
+                        ollama_base_url,
+                        model,
+                        &prompt,
+                        max_tokens,
+                        temperature,
+                    )
+                    .await?
                 }
             };
 
@@ -586,6 +580,104 @@ impl Stage for AiGenerateTransform {
             anyhow::bail!("AI requires 'output_column' configuration");
         }
 
-        Ok(())
+        if let Some(api_key_env) = config.get("api_key_env") {
+            match api_key_env.as_str() {
+                Some(name) if !name.is_empty() => {}
+                _ => anyhow::bail!("'api_key_env' must be a non-empty environment variable name"),
+            }
+        }
+
+        crate::utils::http::validate_config(config)
+    }
+}
+
+/// Read the stage's API key from the environment variable named by `api_key_env`
+///
+/// The error names the variable but never includes its value.
+fn resolve_api_key(config: &HashMap<String, toml::Value>, provider: &AiProvider) -> Result<String> {
+    let api_key_env = config
+        .get("api_key_env")
+        .and_then(|v| v.as_str())
+        .unwrap_or(match provider {
+            AiProvider::OpenAI => "OPENAI_API_KEY",
+            AiProvider::Anthropic => "ANTHROPIC_API_KEY",
+            AiProvider::OpenRouter => "OPENROUTER_API_KEY",
+            AiProvider::Ollama => "", // Ollama doesn't need API key
+        });
+
+    if api_key_env.is_empty() {
+        return Ok(String::new());
+    }
+
+    env::var(api_key_env)
+        .map_err(|_| anyhow::anyhow!("Environment variable '{}' not set", api_key_env))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage_config(api_key_env: &str) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "provider".to_string(),
+            toml::Value::String("openai".to_string()),
+        );
+        config.insert(
+            "api_key_env".to_string(),
+            toml::Value::String(api_key_env.to_string()),
+        );
+        config
+    }
+
+    #[test]
+    fn test_stages_read_their_own_api_key() {
+        std::env::set_var("CONVEYOR_TEST_AI_KEY_PRIMARY", "key-primary");
+        std::env::set_var("CONVEYOR_TEST_AI_KEY_SECONDARY", "key-secondary");
+
+        let primary = stage_config("CONVEYOR_TEST_AI_KEY_PRIMARY");
+        let secondary = stage_config("CONVEYOR_TEST_AI_KEY_SECONDARY");
+
+        assert_eq!(
+            resolve_api_key(&primary, &AiProvider::OpenAI).unwrap(),
+            "key-primary"
+        );
+        assert_eq!(
+            resolve_api_key(&secondary, &AiProvider::Anthropic).unwrap(),
+            "key-secondary"
+        );
+
+        std::env::remove_var("CONVEYOR_TEST_AI_KEY_PRIMARY");
+        std::env::remove_var("CONVEYOR_TEST_AI_KEY_SECONDARY");
+    }
+
+    #[test]
+    fn test_missing_api_key_env() {
+        let err = resolve_api_key(
+            &stage_config("CONVEYOR_TEST_AI_KEY_UNSET"),
+            &AiProvider::OpenAI,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("CONVEYOR_TEST_AI_KEY_UNSET"));
+
+        // Ollama needs no key unless one is configured
+        assert_eq!(
+            resolve_api_key(&HashMap::new(), &AiProvider::Ollama).unwrap(),
+            ""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_key_env_validation() {
+        let transform = AiGenerateTransform::new();
+        let mut config = stage_config("OPENAI_API_KEY");
+        for key in ["model", "prompt", "output_column"] {
+            config.insert(key.to_string(), toml::Value::String("x".to_string()));
+        }
+        assert!(transform.validate_config(&config).await.is_ok());
+
+        config.insert("api_key_env".to_string(), toml::Value::Integer(1));
+        assert!(transform.validate_config(&config).await.is_err());
     }
 }
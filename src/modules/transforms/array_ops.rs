@@ -0,0 +1,470 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct ArrayOpsTransform;
+
+const OPERATIONS: [&str; 5] = ["unique", "sort", "slice", "concat", "length"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArrayOp {
+    Unique,
+    Sort,
+    Slice,
+    Concat,
+    Length,
+}
+
+impl ArrayOp {
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "unique" => Ok(ArrayOp::Unique),
+            "sort" => Ok(ArrayOp::Sort),
+            "slice" => Ok(ArrayOp::Slice),
+            "concat" => Ok(ArrayOp::Concat),
+            "length" => Ok(ArrayOp::Length),
+            _ => anyhow::bail!(
+                "Unknown array operation: '{}'. Supported: {}",
+                s,
+                OPERATIONS.join(", ")
+            ),
+        }
+    }
+}
+
+/// Parsed configuration: the operations chain plus the columns it reads and writes
+struct Plan {
+    ops: Vec<ArrayOp>,
+    sources: Vec<String>,
+    output_column: String,
+    descending: bool,
+    slice_offset: i64,
+    slice_length: Option<i64>,
+}
+
+fn parse_plan(config: &HashMap<String, toml::Value>) -> Result<Plan> {
+    if !config.contains_key("operation") {
+        anyhow::bail!("Missing required 'operation' configuration");
+    }
+    let ops = string_list(config, "operation")?
+        .iter()
+        .map(|op| ArrayOp::from_str(op))
+        .collect::<Result<Vec<_>>>()?;
+    if ops.is_empty() {
+        anyhow::bail!("'operation' must name at least one operation");
+    }
+    if ops.iter().skip(1).any(|op| *op == ArrayOp::Concat) {
+        anyhow::bail!("'concat' can only be the first operation");
+    }
+    if ops.iter().rev().skip(1).any(|op| *op == ArrayOp::Length) {
+        anyhow::bail!("'length' can only be the last operation");
+    }
+
+    let sources = if ops[0] == ArrayOp::Concat {
+        let columns = string_list(config, "columns")?;
+        if columns.len() < 2 {
+            anyhow::bail!("'concat' requires 'columns' with at least two list columns");
+        }
+        columns
+    } else {
+        match config.get("column") {
+            Some(toml::Value::String(s)) => vec![s.clone()],
+            Some(_) => anyhow::bail!("'column' must be a string"),
+            None => anyhow::bail!("Missing required 'column' configuration"),
+        }
+    };
+
+    let output_column = match config.get("output_column") {
+        Some(toml::Value::String(s)) => s.clone(),
+        Some(_) => anyhow::bail!("'output_column' must be a string"),
+        None if ops[0] == ArrayOp::Concat => {
+            anyhow::bail!("'concat' requires an 'output_column'")
+        }
+        None => sources[0].clone(),
+    };
+
+    let descending = match config.get("descending") {
+        Some(toml::Value::Boolean(b)) => *b,
+        Some(_) => anyhow::bail!("'descending' must be a boolean"),
+        None => false,
+    };
+    let slice_offset = match config.get("slice_offset") {
+        Some(toml::Value::Integer(n)) => *n,
+        Some(_) => anyhow::bail!("'slice_offset' must be an integer"),
+        None => 0,
+    };
+    let slice_length = match config.get("slice_length") {
+        Some(toml::Value::Integer(n)) if *n >= 0 => Some(*n),
+        Some(_) => anyhow::bail!("'slice_length' must be a non-negative integer"),
+        None => None,
+    };
+
+    Ok(Plan {
+        ops,
+        sources,
+        output_column,
+        descending,
+        slice_offset,
+        slice_length,
+    })
+}
+
+impl Plan {
+    fn expr(&self) -> Result<Expr> {
+        let mut expr = if self.ops[0] == ArrayOp::Concat {
+            concat_list(self.sources.iter().map(col).collect::<Vec<_>>())?
+        } else {
+            col(self.sources[0].as_str())
+        };
+
+        for op in &self.ops {
+            expr = match op {
+                ArrayOp::Concat => expr,
+                ArrayOp::Unique => expr.list().unique_stable(),
+                ArrayOp::Sort => expr.list().sort(
+                    SortOptions::default()
+                        .with_order_descending(self.descending)
+                        .with_nulls_last(true),
+                ),
+                ArrayOp::Slice => {
+                    // Without a length the slice runs to the end of each list
+                    let length = match self.slice_length {
+                        Some(n) => lit(n),
+                        None => expr.clone().list().len().cast(DataType::Int64),
+                    };
+                    expr.list().slice(lit(self.slice_offset), length)
+                }
+                ArrayOp::Length => expr.list().len(),
+            };
+        }
+        Ok(expr.alias(self.output_column.as_str()))
+    }
+}
+
+/// Ensure every source column exists and holds lists
+fn check_list_columns(df: &DataFrame, columns: &[String]) -> Result<()> {
+    for name in columns {
+        let dtype = df
+            .column(name)
+            .map_err(|_| anyhow::anyhow!("Array ops: column '{}' not found", name))?
+            .dtype();
+        if !matches!(dtype, DataType::List(_)) {
+            anyhow::bail!(
+                "Array ops: column '{}' is {}, not a list; aggregate it into a list first",
+                name,
+                dtype
+            );
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Stage for ArrayOpsTransform {
+    fn name(&self) -> &str {
+        "array_ops.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "column".to_string(),
+            toml::Value::String("tags".to_string()),
+        );
+        example1.insert(
+            "operation".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("unique".to_string()),
+                toml::Value::String("sort".to_string()),
+            ]),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("web_tags".to_string()),
+                toml::Value::String("app_tags".to_string()),
+            ]),
+        );
+        example2.insert(
+            "operation".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("concat".to_string()),
+                toml::Value::String("unique".to_string()),
+                toml::Value::String("length".to_string()),
+            ]),
+        );
+        example2.insert(
+            "output_column".to_string(),
+            toml::Value::String("distinct_tags".to_string()),
+        );
+
+        StageMetadata::builder("array_ops.apply", StageCategory::Transform)
+            .description("Deduplicate, sort, slice, merge or count list-valued columns")
+            .long_description(
+                "Applies list operations to a list-typed column, such as the lists produced by \
+                a group_by aggregation. 'operation' is one operation or a chain applied in order: \
+                'unique' drops duplicate elements keeping first occurrences, 'sort' orders the \
+                elements (nulls last), 'slice' keeps 'slice_length' elements from \
+                'slice_offset' (negative counts from the end), 'concat' merges the list \
+                'columns' row by row and must come first, and 'length' emits the element count \
+                and must come last. The result replaces 'column' unless 'output_column' is set. \
+                Non-list columns are rejected.",
+            )
+            .parameter(
+                ConfigParameter::required(
+                    "operation",
+                    ParameterType::Array,
+                    "Operation or chain of operations (string or array)",
+                )
+                .with_validation(ParameterValidation::allowed_values(OPERATIONS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "column",
+                ParameterType::String,
+                "none",
+                "List column to operate on (required unless the chain starts with concat)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "columns",
+                ParameterType::Array,
+                "none",
+                "List columns merged by 'concat'",
+            ))
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "none",
+                "Column receiving the result (defaults to 'column'; required for concat)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "descending",
+                ParameterType::Boolean,
+                "false",
+                "Sort elements in descending order",
+            ))
+            .parameter(ConfigParameter::optional(
+                "slice_offset",
+                ParameterType::Integer,
+                "0",
+                "First element kept by 'slice' (negative counts from the end)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "slice_length",
+                ParameterType::Integer,
+                "none",
+                "Number of elements kept by 'slice' (defaults to the rest of the list)",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Dedupe and sort tags",
+                example1,
+                Some("[\"b\", \"a\", \"b\"] becomes [\"a\", \"b\"]"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Count distinct tags across two lists",
+                example2,
+                Some("Merge both tag lists, drop duplicates and keep the count"),
+            ))
+            .tag("array")
+            .tag("list")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Array ops transform requires input data"))?;
+
+        let plan = parse_plan(config)?;
+        let df = data.as_dataframe()?;
+        check_list_columns(&df, &plan.sources)?;
+
+        let result = df.lazy().with_column(plan.expr()?).collect()?;
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_plan(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags_frame() -> DataFrame {
+        let tags = Series::new(
+            "tags".into(),
+            [
+                Series::new("".into(), ["b", "a", "b", "c"]),
+                Series::new("".into(), ["x", "x"]),
+                Series::new("".into(), Vec::<&str>::new()),
+            ],
+        );
+        let more = Series::new(
+            "more".into(),
+            [
+                Series::new("".into(), ["c", "d"]),
+                Series::new("".into(), ["y"]),
+                Series::new("".into(), ["z"]),
+            ],
+        );
+        DataFrame::new(vec![
+            Column::new("id".into(), [1i64, 2, 3]),
+            tags.into(),
+            more.into(),
+        ])
+        .unwrap()
+    }
+
+    fn config(column: &str, ops: &[&str]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String(column.to_string()),
+        );
+        config.insert(
+            "operation".to_string(),
+            toml::Value::Array(
+                ops.iter()
+                    .map(|op| toml::Value::String(op.to_string()))
+                    .collect(),
+            ),
+        );
+        config
+    }
+
+    async fn apply(config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(tags_frame()));
+        ArrayOpsTransform
+            .execute(inputs, config)
+            .await?
+            .as_dataframe()
+    }
+
+    fn lists(df: &DataFrame, column: &str) -> Vec<Vec<String>> {
+        df.column(column)
+            .unwrap()
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|list| {
+                list.unwrap()
+                    .str()
+                    .unwrap()
+                    .into_iter()
+                    .map(|v| v.unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_array_ops_unique_then_length() {
+        let result = apply(&config("tags", &["unique"])).await.unwrap();
+        assert_eq!(
+            lists(&result, "tags"),
+            vec![vec!["b", "a", "c"], vec!["x"], vec![]]
+        );
+
+        let mut counted = config("tags", &["unique", "length"]);
+        counted.insert(
+            "output_column".to_string(),
+            toml::Value::String("tag_count".to_string()),
+        );
+        let result = apply(&counted).await.unwrap();
+        let counts: Vec<Option<u32>> = result
+            .column("tag_count")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(counts, vec![Some(3), Some(1), Some(0)]);
+        assert_eq!(lists(&result, "tags")[0], vec!["b", "a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_array_ops_sort_slice_concat() {
+        let mut sorted = config("tags", &["unique", "sort", "slice"]);
+        sorted.insert("descending".to_string(), toml::Value::Boolean(true));
+        sorted.insert("slice_length".to_string(), toml::Value::Integer(2));
+        let result = apply(&sorted).await.unwrap();
+        assert_eq!(
+            lists(&result, "tags"),
+            vec![vec!["c", "b"], vec!["x"], vec![]]
+        );
+
+        let mut last = config("tags", &["slice"]);
+        last.insert("slice_offset".to_string(), toml::Value::Integer(-1));
+        let result = apply(&last).await.unwrap();
+        assert_eq!(lists(&result, "tags"), vec![vec!["c"], vec!["x"], vec![]]);
+
+        let mut merged = config("unused", &["concat", "unique"]);
+        merged.remove("column");
+        merged.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("tags".to_string()),
+                toml::Value::String("more".to_string()),
+            ]),
+        );
+        merged.insert(
+            "output_column".to_string(),
+            toml::Value::String("all_tags".to_string()),
+        );
+        let result = apply(&merged).await.unwrap();
+        assert_eq!(
+            lists(&result, "all_tags"),
+            vec![vec!["b", "a", "c", "d"], vec!["x", "y"], vec!["z"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_array_ops_rejects_non_list_and_bad_chains() {
+        let err = apply(&config("id", &["unique"]))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("column 'id' is i64, not a list"));
+
+        let stage = ArrayOpsTransform;
+        assert!(stage
+            .validate_config(&config("tags", &["unique", "length"]))
+            .await
+            .is_ok());
+        assert!(stage
+            .validate_config(&config("tags", &["length", "unique"]))
+            .await
+            .is_err());
+        assert!(stage
+            .validate_config(&config("tags", &["unique", "concat"]))
+            .await
+            .is_err());
+        assert!(stage
+            .validate_config(&config("tags", &["flatten"]))
+            .await
+            .is_err());
+        // concat needs two source columns and an output column
+        assert!(stage
+            .validate_config(&config("tags", &["concat"]))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,383 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tokio_stream::StreamExt;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+
+/// Groups `size` records into one record holding them as an array
+pub struct BatchTransform;
+
+/// Expands batch records produced by [`BatchTransform`] back into their items
+pub struct UnbatchTransform;
+
+const DEFAULT_SIZE: usize = 100;
+const DEFAULT_ITEMS_COLUMN: &str = "items";
+
+fn parse_size(config: &HashMap<String, toml::Value>) -> Result<usize> {
+    match config.get("size") {
+        Some(toml::Value::Integer(n)) if *n > 0 => Ok(*n as usize),
+        Some(_) => anyhow::bail!("'size' must be a positive integer"),
+        None => Ok(DEFAULT_SIZE),
+    }
+}
+
+fn parse_items_column(config: &HashMap<String, toml::Value>) -> Result<String> {
+    match config.get("items_column") {
+        Some(toml::Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+        Some(_) => anyhow::bail!("'items_column' must be a non-empty string"),
+        None => Ok(DEFAULT_ITEMS_COLUMN.to_string()),
+    }
+}
+
+fn batch_record(items: RecordBatch, items_column: &str) -> HashMap<String, JsonValue> {
+    let items = items
+        .into_iter()
+        .map(|record| JsonValue::Object(record.into_iter().collect()))
+        .collect();
+    HashMap::from([(items_column.to_string(), JsonValue::Array(items))])
+}
+
+fn unbatch_records(
+    batches: RecordBatch,
+    items_column: &str,
+    keep_fields: bool,
+) -> Result<RecordBatch> {
+    let mut records = Vec::new();
+    for (batch_index, mut batch) in batches.into_iter().enumerate() {
+        let items = match batch.remove(items_column) {
+            Some(JsonValue::Array(items)) => items,
+            Some(JsonValue::Null) => Vec::new(),
+            Some(_) => anyhow::bail!(
+                "Unbatch: '{}' of record {} is not an array",
+                items_column,
+                batch_index
+            ),
+            None => anyhow::bail!(
+                "Unbatch: record {} has no '{}' field",
+                batch_index,
+                items_column
+            ),
+        };
+
+        for (item_index, item) in items.into_iter().enumerate() {
+            let JsonValue::Object(item) = item else {
+                anyhow::bail!(
+                    "Unbatch: item {} of record {} is not an object",
+                    item_index,
+                    batch_index
+                );
+            };
+            let mut record: HashMap<String, JsonValue> = if keep_fields {
+                batch.clone()
+            } else {
+                HashMap::new()
+            };
+            // Item fields win over batch-level fields of the same name
+            record.extend(item);
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+#[async_trait]
+impl Stage for BatchTransform {
+    fn name(&self) -> &str {
+        "batch.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert("size".to_string(), toml::Value::Integer(50));
+
+        StageMetadata::builder("batch.apply", StageCategory::Transform)
+            .description("Group every N records into one batch record with an items array")
+            .long_description(
+                "Groups consecutive records into batch records, each holding up to 'size' of the \
+                original records in its 'items_column' array; the last batch may be smaller. Use \
+                it in front of HTTP or AI stages that accept arrays to control how many records go \
+                into each call, and unbatch.apply to expand the batches again. Streams are \
+                re-grouped across incoming batches, so every emitted batch is full except the last.",
+            )
+            .parameter(ConfigParameter::optional(
+                "size",
+                ParameterType::Integer,
+                "100",
+                "Number of records per batch (at least 1)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "items_column",
+                ParameterType::String,
+                DEFAULT_ITEMS_COLUMN,
+                "Field holding each batch's records",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Batch records for a bulk API",
+                example,
+                Some("Send 50 records per request"),
+            ))
+            .tag("batch")
+            .tag("group")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Batch transform requires input data"))?;
+
+        let size = parse_size(config)?;
+        let items_column = parse_items_column(config)?;
+
+        match data {
+            DataFormat::Stream(mut upstream) => {
+                let stream = async_stream::try_stream! {
+                    let mut pending: RecordBatch = Vec::new();
+                    while let Some(records) = upstream.next().await {
+                        pending.extend(records?);
+                        let mut full = Vec::new();
+                        while pending.len() >= size {
+                            let rest = pending.split_off(size);
+                            full.push(batch_record(std::mem::replace(&mut pending, rest), &items_column));
+                        }
+                        if !full.is_empty() {
+                            yield full;
+                        }
+                    }
+                    if !pending.is_empty() {
+                        yield vec![batch_record(pending, &items_column)];
+                    }
+                };
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+            data => {
+                let records = data.as_record_batch()?;
+                let batches = records
+                    .chunks(size)
+                    .map(|chunk| batch_record(chunk.to_vec(), &items_column))
+                    .collect();
+                Ok(DataFormat::RecordBatch(batches))
+            }
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_size(config)?;
+        parse_items_column(config)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Stage for UnbatchTransform {
+    fn name(&self) -> &str {
+        "unbatch.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert(
+            "items_column".to_string(),
+            toml::Value::String("items".to_string()),
+        );
+        example.insert("keep_fields".to_string(), toml::Value::Boolean(true));
+
+        StageMetadata::builder("unbatch.apply", StageCategory::Transform)
+            .description("Expand batch records back into one record per item")
+            .long_description(
+                "The inverse of batch.apply: emits every object in each record's 'items_column' \
+                array as its own record, in order. A null array yields no records; a missing field, \
+                a non-array value or a non-object item is an error. With 'keep_fields', the batch \
+                record's other fields are copied onto each item, with item fields taking \
+                precedence.",
+            )
+            .parameter(ConfigParameter::optional(
+                "items_column",
+                ParameterType::String,
+                DEFAULT_ITEMS_COLUMN,
+                "Field holding each batch's records",
+            ))
+            .parameter(ConfigParameter::optional(
+                "keep_fields",
+                ParameterType::Boolean,
+                "false",
+                "Copy the batch record's other fields onto each item",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Expand batches with their batch-level fields",
+                example,
+                Some("Each item also carries fields added to the batch, e.g. an API status"),
+            ))
+            .tag("batch")
+            .tag("flatten")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Unbatch transform requires input data"))?;
+
+        let items_column = parse_items_column(config)?;
+        let keep_fields = config
+            .get("keep_fields")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        match data {
+            DataFormat::Stream(upstream) => {
+                let stream = upstream
+                    .map(move |batches| unbatch_records(batches?, &items_column, keep_fields));
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+            data => Ok(DataFormat::RecordBatch(unbatch_records(
+                data.as_record_batch()?,
+                &items_column,
+                keep_fields,
+            )?)),
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_items_column(config)?;
+        if let Some(keep_fields) = config.get("keep_fields") {
+            if !keep_fields.is_bool() {
+                anyhow::bail!("'keep_fields' must be a boolean");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rows(n: i64) -> RecordBatch {
+        (0..n)
+            .map(|i| {
+                HashMap::from([
+                    ("id".to_string(), json!(i)),
+                    ("name".to_string(), json!(format!("row{}", i))),
+                ])
+            })
+            .collect()
+    }
+
+    fn single_input(data: DataFormat) -> HashMap<String, DataFormat> {
+        HashMap::from([("input".to_string(), data)])
+    }
+
+    #[tokio::test]
+    async fn test_batch_then_unbatch_round_trip() {
+        let config = HashMap::from([("size".to_string(), toml::Value::Integer(3))]);
+
+        let batches = BatchTransform
+            .execute(single_input(DataFormat::RecordBatch(rows(10))), &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(batches.len(), 4);
+        let sizes: Vec<usize> = batches
+            .iter()
+            .map(|b| b["items"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(sizes, vec![3, 3, 3, 1]);
+        assert_eq!(batches[1]["items"][0]["id"], 3);
+
+        let restored = UnbatchTransform
+            .execute(
+                single_input(DataFormat::RecordBatch(batches)),
+                &HashMap::new(),
+            )
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(restored, rows(10));
+    }
+
+    #[tokio::test]
+    async fn test_batch_regroups_stream() {
+        let upstream = tokio_stream::iter(vec![Ok(rows(2)), Ok(rows(3)), Ok(rows(2))]);
+        let config = HashMap::from([
+            ("size".to_string(), toml::Value::Integer(3)),
+            (
+                "items_column".to_string(),
+                toml::Value::String("records".to_string()),
+            ),
+        ]);
+
+        let output = BatchTransform
+            .execute(
+                single_input(DataFormat::Stream(Box::pin(upstream))),
+                &config,
+            )
+            .await
+            .unwrap();
+        let DataFormat::Stream(stream) = output else {
+            panic!("Expected a stream");
+        };
+        let emitted: Vec<RecordBatch> = stream.map(|b| b.unwrap()).collect().await;
+        let sizes: Vec<Vec<usize>> = emitted
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|b| b["records"].as_array().unwrap().len())
+                    .collect()
+            })
+            .collect();
+        // 2 buffered, then 5 -> one full batch, then 4 -> one full batch and a remainder
+        assert_eq!(sizes, vec![vec![3], vec![3], vec![1]]);
+    }
+
+    #[tokio::test]
+    async fn test_unbatch_keep_fields_and_errors() {
+        let batches = vec![HashMap::from([
+            ("status".to_string(), json!(200)),
+            (
+                "items".to_string(),
+                json!([{"id": 1}, {"id": 2, "status": 404}]),
+            ),
+        ])];
+        let config = HashMap::from([("keep_fields".to_string(), toml::Value::Boolean(true))]);
+        let records = UnbatchTransform
+            .execute(single_input(DataFormat::RecordBatch(batches)), &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["status"], 200);
+        assert_eq!(records[1]["status"], 404);
+
+        let bad = vec![HashMap::from([("items".to_string(), json!([1]))])];
+        let err = match UnbatchTransform
+            .execute(single_input(DataFormat::RecordBatch(bad)), &HashMap::new())
+            .await
+        {
+            Ok(_) => panic!("Expected non-object item to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(err, "Unbatch: item 0 of record 0 is not an object");
+    }
+}
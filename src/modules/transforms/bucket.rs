@@ -0,0 +1,590 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct BucketTransform;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Binning {
+    /// Fixed boundaries; values outside the first/last edge get the overflow label
+    Edges(Vec<f64>),
+    /// Equal-frequency bins
+    Quantile(usize),
+    /// Equal-width bins between the column's min and max
+    Width(usize),
+}
+
+impl Binning {
+    fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        let edges = config.get("edges");
+        let quantile = config.get("quantile");
+        let width = config.get("width");
+
+        let set = [edges, quantile, width]
+            .iter()
+            .filter(|v| v.is_some())
+            .count();
+        if set != 1 {
+            anyhow::bail!(
+                "Bucket transform requires exactly one of 'edges', 'quantile', or 'width'"
+            );
+        }
+
+        if let Some(edges) = edges {
+            let edges = edges
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("'edges' must be an array of numbers"))?
+                .iter()
+                .map(|v| match v {
+                    toml::Value::Integer(i) => Ok(*i as f64),
+                    toml::Value::Float(f) if f.is_finite() => Ok(*f),
+                    _ => anyhow::bail!("'edges' must contain only finite numbers"),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if edges.len() < 2 {
+                anyhow::bail!("'edges' must contain at least two boundaries");
+            }
+            if edges.windows(2).any(|w| w[0] >= w[1]) {
+                anyhow::bail!("'edges' must be strictly increasing");
+            }
+            return Ok(Binning::Edges(edges));
+        }
+
+        let (name, value) = match (quantile, width) {
+            (Some(v), _) => ("quantile", v),
+            (_, Some(v)) => ("width", v),
+            _ => unreachable!(),
+        };
+        let count = value
+            .as_integer()
+            .filter(|n| *n >= 1)
+            .ok_or_else(|| anyhow::anyhow!("'{}' must be a positive integer", name))?
+            as usize;
+
+        Ok(if name == "quantile" {
+            Binning::Quantile(count)
+        } else {
+            Binning::Width(count)
+        })
+    }
+
+    /// Number of labels a user has to provide for this binning
+    fn bin_count(&self) -> usize {
+        match self {
+            Binning::Edges(edges) => edges.len() - 1,
+            Binning::Quantile(n) | Binning::Width(n) => *n,
+        }
+    }
+}
+
+fn parse_labels(config: &HashMap<String, toml::Value>) -> Result<Option<Vec<String>>> {
+    match config.get("labels") {
+        None => Ok(None),
+        Some(toml::Value::Array(arr)) => arr
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| anyhow::anyhow!("'labels' must be an array of strings"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some),
+        Some(_) => anyhow::bail!("'labels' must be an array of strings"),
+    }
+}
+
+#[async_trait]
+impl Stage for BucketTransform {
+    fn name(&self) -> &str {
+        "bucket.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert("column".to_string(), toml::Value::String("age".to_string()));
+        example1.insert(
+            "edges".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::Integer(0),
+                toml::Value::Integer(18),
+                toml::Value::Integer(65),
+                toml::Value::Integer(120),
+            ]),
+        );
+        example1.insert(
+            "labels".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("minor".to_string()),
+                toml::Value::String("adult".to_string()),
+                toml::Value::String("senior".to_string()),
+            ]),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "column".to_string(),
+            toml::Value::String("revenue".to_string()),
+        );
+        example2.insert("quantile".to_string(), toml::Value::Integer(4));
+        example2.insert(
+            "output_column".to_string(),
+            toml::Value::String("revenue_quartile".to_string()),
+        );
+
+        let mut example3 = HashMap::new();
+        example3.insert(
+            "column".to_string(),
+            toml::Value::String("latency_ms".to_string()),
+        );
+        example3.insert("width".to_string(), toml::Value::Integer(10));
+
+        StageMetadata::builder("bucket.apply", StageCategory::Transform)
+            .description("Bin a numeric column into labeled ranges")
+            .long_description(
+                "Assigns each value of a numeric column to a bin and writes the bin label to a \
+                categorical output column. Bins come from exactly one of: fixed 'edges' \
+                (values below the first or above the last edge get 'overflow_label'), \
+                'quantile' equal-frequency bins, or 'width' equal-width bins spanning the \
+                column's min to max. Intervals are right-closed unless 'left_closed' is set. \
+                Labels default to interval notation such as '(0, 18]'. Nulls stay null.",
+            )
+            .parameter(ConfigParameter::required(
+                "column",
+                ParameterType::String,
+                "Numeric column to bin",
+            ))
+            .parameter(ConfigParameter::optional(
+                "edges",
+                ParameterType::Array,
+                "none",
+                "Bin boundaries in increasing order",
+            ))
+            .parameter(ConfigParameter::optional(
+                "quantile",
+                ParameterType::Integer,
+                "none",
+                "Number of equal-frequency bins",
+            ))
+            .parameter(ConfigParameter::optional(
+                "width",
+                ParameterType::Integer,
+                "none",
+                "Number of equal-width bins",
+            ))
+            .parameter(ConfigParameter::optional(
+                "labels",
+                ParameterType::Array,
+                "interval notation",
+                "One label per bin",
+            ))
+            .parameter(ConfigParameter::optional(
+                "overflow_label",
+                ParameterType::String,
+                "overflow",
+                "Label for values outside 'edges'",
+            ))
+            .parameter(ConfigParameter::optional(
+                "left_closed",
+                ParameterType::Boolean,
+                "false",
+                "Use [a, b) intervals instead of (a, b]",
+            ))
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "[column]_bucket",
+                "Name of the output column",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Age groups",
+                example1,
+                Some("Label ages as minor, adult or senior"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Revenue quartiles",
+                example2,
+                Some("Split revenue into four equal-frequency bins"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Latency histogram",
+                example3,
+                Some("Split latency into ten equal-width bins"),
+            ))
+            .tag("bucket")
+            .tag("bin")
+            .tag("histogram")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Bucket transform requires input data"))?;
+
+        let column = config
+            .get("column")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Bucket transform requires 'column' configuration"))?;
+
+        let binning = Binning::from_config(config)?;
+        let labels = parse_labels(config)?;
+        if let Some(labels) = &labels {
+            if labels.len() != binning.bin_count() {
+                anyhow::bail!(
+                    "Expected {} labels for {} bins, got {}",
+                    binning.bin_count(),
+                    binning.bin_count(),
+                    labels.len()
+                );
+            }
+        }
+
+        let overflow_label = config
+            .get("overflow_label")
+            .and_then(|v| v.as_str())
+            .unwrap_or("overflow");
+
+        let left_closed = config
+            .get("left_closed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let default_output = format!("{}_bucket", column);
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&default_output);
+
+        let mut df = data.as_dataframe()?;
+        let values = df
+            .column(column)
+            .with_context(|| format!("Column '{}' not found", column))?
+            .as_materialized_series()
+            .cast(&DataType::Float64)
+            .with_context(|| format!("Column '{}' must be numeric for bucketing", column))?;
+
+        let to_labels =
+            |labels: Vec<String>| labels.into_iter().map(PlSmallStr::from).collect::<Vec<_>>();
+
+        let binned = match &binning {
+            Binning::Edges(edges) => {
+                // cut() adds open-ended bins below the first and above the last edge
+                let labels = labels.unwrap_or_else(|| {
+                    edges
+                        .windows(2)
+                        .map(|w| interval_label(w[0], w[1], left_closed))
+                        .collect()
+                });
+                let mut all = Vec::with_capacity(labels.len() + 2);
+                all.push(overflow_label.to_string());
+                all.extend(labels);
+                all.push(overflow_label.to_string());
+
+                cut(
+                    &values,
+                    edges.clone(),
+                    Some(to_labels(all)),
+                    left_closed,
+                    false,
+                )?
+            }
+            Binning::Quantile(n) => {
+                let probs = (1..*n).map(|i| i as f64 / *n as f64).collect();
+                qcut(
+                    &values,
+                    probs,
+                    labels.map(to_labels),
+                    left_closed,
+                    false,
+                    false,
+                )
+                .with_context(|| {
+                    format!(
+                        "Cannot split '{}' into {} quantile bins (too many repeated values?)",
+                        column, n
+                    )
+                })?
+            }
+            Binning::Width(n) => {
+                let ca = values.f64()?;
+                let (min, max) = match (ca.min(), ca.max()) {
+                    (Some(min), Some(max)) => (min, max),
+                    _ => anyhow::bail!("Column '{}' has no values to bin", column),
+                };
+                let step = (max - min) / *n as f64;
+                let breaks: Vec<f64> = if step > 0.0 {
+                    (1..*n).map(|i| min + step * i as f64).collect()
+                } else {
+                    Vec::new()
+                };
+
+                match labels {
+                    Some(labels) if breaks.len() + 1 == labels.len() => {
+                        cut(&values, breaks, Some(to_labels(labels)), left_closed, false)?
+                    }
+                    Some(_) => anyhow::bail!(
+                        "Column '{}' has a single distinct value; cannot form {} width bins",
+                        column,
+                        n
+                    ),
+                    None => cut(&values, breaks, None, left_closed, false)?,
+                }
+            }
+        };
+
+        df.with_column(binned.with_name(output_column.into()))?;
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if !config.contains_key("column") {
+            anyhow::bail!("Bucket transform requires 'column' configuration");
+        }
+
+        let binning = Binning::from_config(config)?;
+        if let Some(labels) = parse_labels(config)? {
+            if labels.len() != binning.bin_count() {
+                anyhow::bail!(
+                    "Expected {} labels for {} bins, got {}",
+                    binning.bin_count(),
+                    binning.bin_count(),
+                    labels.len()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn interval_label(lo: f64, hi: f64, left_closed: bool) -> String {
+    if left_closed {
+        format!("[{}, {})", lo, hi)
+    } else {
+        format!("({}, {}]", lo, hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::df;
+
+    async fn bucket(df: DataFrame, config: HashMap<String, toml::Value>) -> Vec<Option<String>> {
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or("value_bucket")
+            .to_string();
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+
+        let result = BucketTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+
+        let labels = result
+            .column(&output_column)
+            .unwrap()
+            .cast(&DataType::String)
+            .unwrap();
+        labels
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.map(|s| s.to_string()))
+            .collect()
+    }
+
+    fn strings(values: &[&str]) -> toml::Value {
+        toml::Value::Array(
+            values
+                .iter()
+                .map(|s| toml::Value::String(s.to_string()))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_bucket_edges_with_overflow() {
+        let df = df!("value" => [Some(-5.0), Some(5.0), Some(10.0), Some(15.0), Some(25.0), None])
+            .unwrap();
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        config.insert(
+            "edges".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::Integer(0),
+                toml::Value::Integer(10),
+                toml::Value::Integer(20),
+            ]),
+        );
+        config.insert("labels".to_string(), strings(&["low", "high"]));
+
+        let labels = bucket(df, config).await;
+        assert_eq!(
+            labels,
+            vec![
+                Some("overflow".to_string()),
+                Some("low".to_string()),
+                Some("low".to_string()),
+                Some("high".to_string()),
+                Some("overflow".to_string()),
+                None,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bucket_edges_default_labels() {
+        let df = df!("value" => [1i64, 15]).unwrap();
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        config.insert(
+            "edges".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::Integer(0),
+                toml::Value::Integer(10),
+                toml::Value::Integer(20),
+            ]),
+        );
+
+        let labels = bucket(df, config).await;
+        assert_eq!(
+            labels,
+            vec![Some("(0, 10]".to_string()), Some("(10, 20]".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bucket_quantiles() {
+        // Eight evenly spread values split into quartiles of two values each
+        let df = df!("value" => [8i64, 1, 7, 2, 6, 3, 5, 4]).unwrap();
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        config.insert("quantile".to_string(), toml::Value::Integer(4));
+        config.insert("labels".to_string(), strings(&["q1", "q2", "q3", "q4"]));
+        config.insert(
+            "output_column".to_string(),
+            toml::Value::String("quartile".to_string()),
+        );
+
+        let labels = bucket(df, config).await;
+        let expected: Vec<Option<String>> = ["q4", "q1", "q4", "q1", "q3", "q2", "q3", "q2"]
+            .iter()
+            .map(|s| Some(s.to_string()))
+            .collect();
+        assert_eq!(labels, expected);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_width() {
+        let df = df!("value" => [0.0, 2.0, 5.0, 9.0, 10.0]).unwrap();
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        config.insert("width".to_string(), toml::Value::Integer(2));
+        config.insert("labels".to_string(), strings(&["lower", "upper"]));
+
+        let labels = bucket(df, config).await;
+        assert_eq!(
+            labels,
+            vec![
+                Some("lower".to_string()),
+                Some("lower".to_string()),
+                Some("lower".to_string()),
+                Some("upper".to_string()),
+                Some("upper".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bucket_validation() {
+        let transform = BucketTransform;
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        // No binning strategy
+        assert!(transform.validate_config(&config).await.is_err());
+
+        config.insert("quantile".to_string(), toml::Value::Integer(4));
+        assert!(transform.validate_config(&config).await.is_ok());
+
+        // Conflicting strategies
+        config.insert("width".to_string(), toml::Value::Integer(4));
+        assert!(transform.validate_config(&config).await.is_err());
+
+        // Label count must match bin count
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        config.insert(
+            "edges".to_string(),
+            toml::Value::Array(vec![toml::Value::Integer(0), toml::Value::Integer(10)]),
+        );
+        config.insert("labels".to_string(), strings(&["a", "b"]));
+        assert!(transform.validate_config(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bucket_output_converts_to_records() {
+        let df = df!("value" => [5i64]).unwrap();
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        config.insert("width".to_string(), toml::Value::Integer(1));
+        config.insert("labels".to_string(), strings(&["all"]));
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+        let records = BucketTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+
+        assert_eq!(records[0]["value_bucket"], serde_json::json!("all"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Config and column helpers shared by several transforms
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// A config value given as one string or an array of strings; `None` when absent
+pub(crate) fn string_list_option(
+    config: &HashMap<String, toml::Value>,
+    name: &str,
+) -> Result<Option<Vec<String>>> {
+    match config.get(name) {
+        None => Ok(None),
+        Some(toml::Value::String(s)) => Ok(Some(vec![s.clone()])),
+        Some(toml::Value::Array(arr)) => arr
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| anyhow::anyhow!("'{}' must contain only strings", name))
+            })
+            .collect::<Result<_>>()
+            .map(Some),
+        Some(_) => anyhow::bail!("'{}' must be a string or array of strings", name),
+    }
+}
+
+/// A config value given as one string or an array of strings; empty when absent
+pub(crate) fn string_list(
+    config: &HashMap<String, toml::Value>,
+    name: &str,
+) -> Result<Vec<String>> {
+    Ok(string_list_option(config, name)?.unwrap_or_default())
+}
+
+/// A non-empty string config value; `None` when absent
+pub(crate) fn string_option(
+    config: &HashMap<String, toml::Value>,
+    name: &str,
+) -> Result<Option<String>> {
+    match config.get(name) {
+        None => Ok(None),
+        Some(toml::Value::String(s)) if !s.is_empty() => Ok(Some(s.clone())),
+        Some(_) => anyhow::bail!("'{}' must be a non-empty string", name),
+    }
+}
+
+/// Parse an ISO 8601 timestamp to epoch milliseconds, reading values without
+/// an offset as UTC
+pub(crate) fn parse_timestamp(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis());
+    }
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|p| NaiveDateTime::parse_from_str(s, p).ok())
+    .map(|naive| naive.and_utc().timestamp_millis())
+}
+
+/// The timestamp column as epoch milliseconds, one per row
+///
+/// Datetime and date columns are used as they are, integers are epoch
+/// milliseconds and strings are parsed as ISO 8601. `stage` prefixes the
+/// errors, e.g. "Resample".
+pub(crate) fn epoch_millis(df: &DataFrame, name: &str, stage: &str) -> Result<Vec<i64>> {
+    let series = timestamp_column(df, name, stage)?;
+    let millis = match series.dtype() {
+        DataType::Datetime(_, _) | DataType::Date => series
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+            .cast(&DataType::Int64)?,
+        dtype if dtype.is_integer() => series.cast(&DataType::Int64)?,
+        DataType::String => {
+            return series
+                .str()?
+                .into_no_null_iter()
+                .map(|s| {
+                    parse_timestamp(s).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "{}: cannot parse timestamp '{}' in column '{}'",
+                            stage,
+                            s,
+                            name
+                        )
+                    })
+                })
+                .collect()
+        }
+        dtype => anyhow::bail!(
+            "{}: timestamp column '{}' is {}, not a datetime, integer or string",
+            stage,
+            name,
+            dtype
+        ),
+    };
+    Ok(millis.i64()?.into_no_null_iter().collect())
+}
+
+/// The timestamp column as a datetime series named `name`
+///
+/// Datetime and date columns are kept as they are; other values are read as
+/// [`epoch_millis`] does.
+pub(crate) fn time_index(df: &DataFrame, name: &str, stage: &str) -> Result<Series> {
+    let series = timestamp_column(df, name, stage)?;
+    if matches!(series.dtype(), DataType::Datetime(_, _) | DataType::Date) {
+        return Ok(series.clone());
+    }
+    let millis = epoch_millis(df, name, stage)?;
+    Ok(Int64Chunked::from_vec(name.into(), millis)
+        .into_datetime(TimeUnit::Milliseconds, None)
+        .into_series())
+}
+
+fn timestamp_column<'a>(df: &'a DataFrame, name: &str, stage: &str) -> Result<&'a Series> {
+    let series = df
+        .column(name)
+        .map_err(|_| anyhow::anyhow!("{}: column '{}' not found", stage, name))?
+        .as_materialized_series();
+    if series.null_count() > 0 {
+        anyhow::bail!("{}: timestamp column '{}' has null values", stage, name);
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_list_accepts_string_or_array() {
+        let mut config = HashMap::new();
+        config.insert("one".to_string(), toml::Value::String("a".to_string()));
+        config.insert(
+            "many".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("a".to_string()),
+                toml::Value::String("b".to_string()),
+            ]),
+        );
+        config.insert("bad".to_string(), toml::Value::Integer(1));
+
+        assert_eq!(string_list(&config, "one").unwrap(), vec!["a"]);
+        assert_eq!(string_list(&config, "many").unwrap(), vec!["a", "b"]);
+        assert!(string_list(&config, "missing").unwrap().is_empty());
+        assert!(string_list(&config, "bad").is_err());
+        assert_eq!(string_list_option(&config, "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_string_option_rejects_empty_and_non_strings() {
+        let mut config = HashMap::new();
+        config.insert("name".to_string(), toml::Value::String("a".to_string()));
+        config.insert("empty".to_string(), toml::Value::String(String::new()));
+        config.insert("bad".to_string(), toml::Value::Integer(1));
+
+        assert_eq!(
+            string_option(&config, "name").unwrap().as_deref(),
+            Some("a")
+        );
+        assert_eq!(string_option(&config, "missing").unwrap(), None);
+        assert!(string_option(&config, "empty").is_err());
+        assert!(string_option(&config, "bad").is_err());
+    }
+
+    #[test]
+    fn test_time_index_reads_every_timestamp_type() {
+        let df = df![
+            "text" => ["1970-01-01T00:00:01Z", "1970-01-01 00:00:02"],
+            "millis" => [1000i64, 2000],
+        ]
+        .unwrap();
+
+        assert_eq!(epoch_millis(&df, "text", "Test").unwrap(), vec![1000, 2000]);
+        assert_eq!(
+            epoch_millis(&df, "millis", "Test").unwrap(),
+            vec![1000, 2000]
+        );
+
+        let index = time_index(&df, "millis", "Test").unwrap();
+        assert_eq!(
+            index.dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None)
+        );
+        let with_datetime =
+            DataFrame::new(vec![index.clone().with_name("ts".into()).into()]).unwrap();
+        let kept = time_index(&with_datetime, "ts", "Test").unwrap();
+        assert_eq!(kept, index.with_name("ts".into()));
+
+        let err = epoch_millis(&df, "missing", "Test").unwrap_err();
+        assert_eq!(err.to_string(), "Test: column 'missing' not found");
+    }
+}
@@ -0,0 +1,399 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Cross-tabulates two categorical columns into a contingency table
+pub struct CrosstabTransform;
+
+const AGGREGATIONS: [&str; 7] = ["count", "sum", "mean", "min", "max", "first", "last"];
+
+const DEFAULT_MARGINS_NAME: &str = "Total";
+
+fn required_str<'a>(config: &'a HashMap<String, toml::Value>, name: &str) -> Result<&'a str> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) => Ok(s),
+        Some(_) => anyhow::bail!("'{}' must be a string", name),
+        None => anyhow::bail!("Missing required '{}' configuration", name),
+    }
+}
+
+fn optional_str<'a>(
+    config: &'a HashMap<String, toml::Value>,
+    name: &str,
+) -> Result<Option<&'a str>> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) => Ok(Some(s)),
+        Some(_) => anyhow::bail!("'{}' must be a string", name),
+        None => Ok(None),
+    }
+}
+
+fn parse_aggregate(config: &HashMap<String, toml::Value>) -> Result<&str> {
+    let aggregate = optional_str(config, "aggregate")?.unwrap_or("count");
+    if !AGGREGATIONS.contains(&aggregate) {
+        anyhow::bail!(
+            "Invalid aggregate '{}'. Supported: {}",
+            aggregate,
+            AGGREGATIONS.join(", ")
+        );
+    }
+    if aggregate != "count" && !config.contains_key("values") {
+        anyhow::bail!("aggregate '{}' requires a 'values' column", aggregate);
+    }
+    Ok(aggregate)
+}
+
+fn parse_margins(config: &HashMap<String, toml::Value>) -> Result<bool> {
+    match config.get("margins") {
+        Some(toml::Value::Boolean(b)) => Ok(*b),
+        Some(_) => anyhow::bail!("'margins' must be a boolean"),
+        None => Ok(false),
+    }
+}
+
+/// Combine the cells selected by `filter` with `aggregate`
+///
+/// Counts start at zero for empty cells; the other aggregates leave them null.
+fn cell(values: Option<&str>, columns: &str, aggregate: &str, filter: Option<Expr>) -> Expr {
+    let source = col(values.unwrap_or(columns));
+    let source = match filter {
+        Some(filter) => source.filter(filter),
+        None => source,
+    };
+    let combined = match aggregate {
+        "count" => return source.count().cast(DataType::Int64),
+        "sum" => source.clone().sum(),
+        "mean" => source.clone().mean(),
+        "min" => source.clone().min(),
+        "max" => source.clone().max(),
+        "first" => source.clone().first(),
+        _ => source.clone().last(),
+    };
+    // An empty sum would otherwise be 0
+    when(source.count().gt(lit(0)))
+        .then(combined)
+        .otherwise(lit(NULL))
+}
+
+#[async_trait]
+impl Stage for CrosstabTransform {
+    fn name(&self) -> &str {
+        "crosstab.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "rows".to_string(),
+            toml::Value::String("region".to_string()),
+        );
+        example1.insert(
+            "columns".to_string(),
+            toml::Value::String("plan".to_string()),
+        );
+        example1.insert("margins".to_string(), toml::Value::Boolean(true));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "rows".to_string(),
+            toml::Value::String("product".to_string()),
+        );
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::String("quarter".to_string()),
+        );
+        example2.insert(
+            "values".to_string(),
+            toml::Value::String("revenue".to_string()),
+        );
+        example2.insert(
+            "aggregate".to_string(),
+            toml::Value::String("sum".to_string()),
+        );
+
+        StageMetadata::builder("crosstab.apply", StageCategory::Transform)
+            .description("Cross-tabulate two categorical columns")
+            .long_description(
+                "Builds a contingency table with one row per distinct 'rows' value and one column \
+                per distinct 'columns' value, both sorted and compared as text. Each cell counts \
+                the input rows with that pair of values, or combines a 'values' column with \
+                'aggregate'. Empty cells are 0 for counts and null otherwise. With 'margins', a \
+                total column and a total row are added, computed with the same aggregate over the \
+                whole row, column or table. Rows with a null in either column are left out.",
+            )
+            .parameter(ConfigParameter::required(
+                "rows",
+                ParameterType::String,
+                "Column whose values label the output rows",
+            ))
+            .parameter(ConfigParameter::required(
+                "columns",
+                ParameterType::String,
+                "Column whose values become the output columns",
+            ))
+            .parameter(ConfigParameter::optional(
+                "values",
+                ParameterType::String,
+                "",
+                "Column to aggregate in each cell (required unless aggregate is count)",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "aggregate",
+                    ParameterType::String,
+                    "count",
+                    "How the cell values are combined",
+                )
+                .with_validation(ParameterValidation::allowed_values(AGGREGATIONS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "margins",
+                ParameterType::Boolean,
+                "false",
+                "Add a total column and a total row",
+            ))
+            .parameter(ConfigParameter::optional(
+                "margins_name",
+                ParameterType::String,
+                DEFAULT_MARGINS_NAME,
+                "Label of the total column and row",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Count by region and plan",
+                example1,
+                Some("Customers per region and plan, with totals"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Revenue by product and quarter",
+                example2,
+                Some("Sum of revenue for each product and quarter"),
+            ))
+            .tag("crosstab")
+            .tag("pivot")
+            .tag("aggregate")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Crosstab transform requires input data"))?;
+
+        let rows = required_str(config, "rows")?;
+        let columns = required_str(config, "columns")?;
+        let values = optional_str(config, "values")?;
+        let aggregate = parse_aggregate(config)?;
+        let margins = parse_margins(config)?;
+        let margins_name = optional_str(config, "margins_name")?.unwrap_or(DEFAULT_MARGINS_NAME);
+
+        let df = data.as_dataframe()?;
+        for column in [Some(rows), Some(columns), values].into_iter().flatten() {
+            if df.column(column).is_err() {
+                anyhow::bail!("Crosstab: column '{}' not found", column);
+            }
+        }
+
+        // Labels become row values and column names, so compare them as text
+        let df = df
+            .lazy()
+            .with_columns([
+                col(rows).cast(DataType::String),
+                col(columns).cast(DataType::String),
+            ])
+            .filter(col(rows).is_not_null().and(col(columns).is_not_null()))
+            .collect()?;
+
+        let mut labels: Vec<String> = df
+            .column(columns)?
+            .unique()?
+            .str()?
+            .into_iter()
+            .flatten()
+            .map(|s| s.to_string())
+            .collect();
+        labels.sort();
+        if let Some(clash) = labels.iter().find(|l| l.as_str() == rows) {
+            anyhow::bail!(
+                "Crosstab: column value '{}' has the same name as the rows column",
+                clash
+            );
+        }
+        if margins && labels.iter().any(|l| l == margins_name) {
+            anyhow::bail!(
+                "Crosstab: column value '{}' clashes with margins_name; set another 'margins_name'",
+                margins_name
+            );
+        }
+
+        let mut cells: Vec<Expr> = labels
+            .iter()
+            .map(|label| {
+                let filter = col(columns).eq(lit(label.as_str()));
+                cell(values, columns, aggregate, Some(filter)).alias(label)
+            })
+            .collect();
+        if margins {
+            cells.push(cell(values, columns, aggregate, None).alias(margins_name));
+        }
+
+        let mut table = df
+            .clone()
+            .lazy()
+            .group_by([col(rows)])
+            .agg(cells.clone())
+            .sort([rows], SortMultipleOptions::default())
+            .collect()?;
+
+        if margins {
+            let mut totals = vec![lit(margins_name).alias(rows)];
+            totals.extend(cells);
+            let totals = df.lazy().select(totals).collect()?;
+            table = table.vstack(&totals)?;
+        }
+
+        Ok(DataFormat::DataFrame(table))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        let rows = required_str(config, "rows")?;
+        let columns = required_str(config, "columns")?;
+        let values = optional_str(config, "values")?;
+        parse_aggregate(config)?;
+        parse_margins(config)?;
+        optional_str(config, "margins_name")?;
+
+        if rows == columns || values.is_some_and(|v| v == rows || v == columns) {
+            anyhow::bail!("'rows', 'columns' and 'values' must name different columns");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> DataFrame {
+        df![
+            "region" => ["north", "south", "north", "north", "south", "south"],
+            "plan" => ["pro", "free", "free", "pro", "free", "free"],
+            "seats" => [10i64, 1, 2, 5, 3, 4],
+        ]
+        .unwrap()
+    }
+
+    fn config(pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([
+            (
+                "rows".to_string(),
+                toml::Value::String("region".to_string()),
+            ),
+            (
+                "columns".to_string(),
+                toml::Value::String("plan".to_string()),
+            ),
+        ]);
+        for (key, value) in pairs {
+            config.insert(key.to_string(), value.clone());
+        }
+        config
+    }
+
+    async fn crosstab(config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(frame()))]);
+        CrosstabTransform
+            .execute(inputs, config)
+            .await?
+            .as_dataframe()
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Option<i64>> {
+        df.column(name)
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_crosstab_counts_with_margins() {
+        let df = crosstab(&config(&[])).await.unwrap();
+        assert_eq!(df.get_column_names(), vec!["region", "free", "pro"]);
+        assert_eq!(
+            df.column("region")
+                .unwrap()
+                .str()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some("north"), Some("south")]
+        );
+        assert_eq!(column(&df, "free"), vec![Some(1), Some(3)]);
+        // No south customer is on pro: the cell counts zero
+        assert_eq!(column(&df, "pro"), vec![Some(2), Some(0)]);
+
+        let df = crosstab(&config(&[("margins", toml::Value::Boolean(true))]))
+            .await
+            .unwrap();
+        assert_eq!(
+            df.get_column_names(),
+            vec!["region", "free", "pro", "Total"]
+        );
+        assert_eq!(df.height(), 3);
+        assert_eq!(
+            df.column("region").unwrap().str().unwrap().get(2),
+            Some("Total")
+        );
+        assert_eq!(column(&df, "free"), vec![Some(1), Some(3), Some(4)]);
+        assert_eq!(column(&df, "pro"), vec![Some(2), Some(0), Some(2)]);
+        assert_eq!(column(&df, "Total"), vec![Some(3), Some(3), Some(6)]);
+    }
+
+    #[tokio::test]
+    async fn test_crosstab_aggregates_values() {
+        let df = crosstab(&config(&[
+            ("values", toml::Value::String("seats".to_string())),
+            ("aggregate", toml::Value::String("sum".to_string())),
+            ("margins", toml::Value::Boolean(true)),
+        ]))
+        .await
+        .unwrap();
+        assert_eq!(column(&df, "free"), vec![Some(2), Some(8), Some(10)]);
+        // Empty cells of a sum are null rather than zero
+        assert_eq!(column(&df, "pro"), vec![Some(15), None, Some(15)]);
+        assert_eq!(column(&df, "Total"), vec![Some(17), Some(8), Some(25)]);
+
+        let sum_without_values = config(&[("aggregate", toml::Value::String("sum".to_string()))]);
+        assert!(CrosstabTransform
+            .validate_config(&sum_without_values)
+            .await
+            .is_err());
+        let same_column = config(&[("columns", toml::Value::String("region".to_string()))]);
+        assert!(CrosstabTransform
+            .validate_config(&same_column)
+            .await
+            .is_err());
+        let err = crosstab(&config(&[(
+            "columns",
+            toml::Value::String("tier".to_string()),
+        )]))
+        .await
+        .unwrap_err()
+        .to_string();
+        assert_eq!(err, "Crosstab: column 'tier' not found");
+    }
+}
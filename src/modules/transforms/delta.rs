@@ -0,0 +1,342 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct DeltaTransform;
+
+const ROW_INDEX: &str = "__delta_row";
+
+struct Options {
+    values: Vec<String>,
+    partition_by: Vec<String>,
+    order_by: Vec<String>,
+    periods: i64,
+    fill: Option<f64>,
+    suffix: String,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let values = string_list(config, "value")?;
+    if values.is_empty() {
+        anyhow::bail!("Missing required 'value' configuration");
+    }
+
+    let periods = match config.get("periods") {
+        Some(toml::Value::Integer(0)) => anyhow::bail!("'periods' must not be 0"),
+        Some(toml::Value::Integer(n)) => *n,
+        Some(_) => anyhow::bail!("'periods' must be an integer"),
+        None => 1,
+    };
+    let fill = match config.get("fill") {
+        Some(toml::Value::Integer(n)) => Some(*n as f64),
+        Some(toml::Value::Float(f)) => Some(*f),
+        Some(_) => anyhow::bail!("'fill' must be a number"),
+        None => None,
+    };
+    let suffix = match config.get("suffix") {
+        Some(toml::Value::String(s)) => s.clone(),
+        Some(_) => anyhow::bail!("'suffix' must be a string"),
+        None => "_delta".to_string(),
+    };
+
+    Ok(Options {
+        values,
+        partition_by: string_list(config, "partition_by")?,
+        order_by: string_list(config, "order_by")?,
+        periods,
+        fill,
+        suffix,
+    })
+}
+
+impl Options {
+    /// Evaluate `expr` per partition, or over the whole frame without one
+    fn windowed(&self, expr: Expr) -> Expr {
+        if self.partition_by.is_empty() {
+            expr
+        } else {
+            let partition: Vec<Expr> = self.partition_by.iter().map(|c| col(c.as_str())).collect();
+            expr.over(partition)
+        }
+    }
+
+    /// True for rows with no row `periods` away in their partition
+    fn without_prior_row(&self) -> Expr {
+        // The row index is never null, so a null after shifting means no such row
+        self.windowed(col(ROW_INDEX).shift(lit(self.periods)))
+            .is_null()
+    }
+
+    fn delta(&self, value: &str) -> Expr {
+        let delta =
+            self.windowed(col(value).diff(self.periods, polars::series::ops::NullBehavior::Ignore));
+        let delta = match self.fill {
+            Some(fill) => when(self.without_prior_row())
+                .then(lit(fill))
+                .otherwise(delta),
+            None => delta,
+        };
+        delta.alias(format!("{}{}", value, self.suffix))
+    }
+}
+
+fn check_columns(df: &DataFrame, options: &Options) -> Result<()> {
+    for name in options
+        .partition_by
+        .iter()
+        .chain(&options.order_by)
+        .chain(&options.values)
+    {
+        if df.column(name).is_err() {
+            anyhow::bail!("Delta: column '{}' not found", name);
+        }
+    }
+    for name in &options.values {
+        let dtype = df.column(name)?.dtype();
+        if !dtype.is_numeric() && !dtype.is_temporal() {
+            anyhow::bail!("Delta: column '{}' is {}, not numeric", name, dtype);
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Stage for DeltaTransform {
+    fn name(&self) -> &str {
+        "delta.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "value".to_string(),
+            toml::Value::String("reading".to_string()),
+        );
+        example1.insert(
+            "partition_by".to_string(),
+            toml::Value::String("sensor_id".to_string()),
+        );
+        example1.insert(
+            "order_by".to_string(),
+            toml::Value::String("timestamp".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "value".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("revenue".to_string()),
+                toml::Value::String("orders".to_string()),
+            ]),
+        );
+        example2.insert(
+            "order_by".to_string(),
+            toml::Value::String("week".to_string()),
+        );
+        example2.insert("periods".to_string(), toml::Value::Integer(52));
+        example2.insert("fill".to_string(), toml::Value::Integer(0));
+
+        StageMetadata::builder("delta.apply", StageCategory::Transform)
+            .description("Compute the difference from the previous row, per partition")
+            .long_description(
+                "Adds a '<value><suffix>' column for each value column holding the value minus \
+                the value 'periods' rows earlier (a negative 'periods' looks ahead instead). Rows \
+                are ordered by 'order_by' within each 'partition_by' group, so differences reset \
+                at every partition; the output keeps the input row order. The first rows of each \
+                partition have no prior row and get null, or 'fill' when it is set.",
+            )
+            .parameter(ConfigParameter::required(
+                "value",
+                ParameterType::Array,
+                "Numeric column(s) to difference",
+            ))
+            .parameter(ConfigParameter::optional(
+                "partition_by",
+                ParameterType::Array,
+                "none",
+                "Column(s) whose groups are differenced independently",
+            ))
+            .parameter(ConfigParameter::optional(
+                "order_by",
+                ParameterType::Array,
+                "none",
+                "Column(s) ordering rows within a partition (defaults to input order)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "periods",
+                ParameterType::Integer,
+                "1",
+                "Lag distance in rows (negative to difference against later rows)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "fill",
+                ParameterType::Float,
+                "none",
+                "Value for rows without a prior row in their partition",
+            ))
+            .parameter(ConfigParameter::optional(
+                "suffix",
+                ParameterType::String,
+                "_delta",
+                "Suffix of the output columns",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Per-sensor change",
+                example1,
+                Some("reading_delta = reading - previous reading of the same sensor"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Year-over-year change",
+                example2,
+                Some("Compare each week with the same week a year earlier"),
+            ))
+            .tag("delta")
+            .tag("diff")
+            .tag("timeseries")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Delta transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        check_columns(&df, &options)?;
+
+        let mut lazy = df.lazy().with_row_index(ROW_INDEX, None);
+        if !options.order_by.is_empty() {
+            lazy = lazy.sort(
+                options.order_by.clone(),
+                SortMultipleOptions::default().with_maintain_order(true),
+            );
+        }
+        let deltas: Vec<Expr> = options.values.iter().map(|v| options.delta(v)).collect();
+        let result = lazy
+            .with_columns(deltas)
+            .sort([ROW_INDEX], SortMultipleOptions::default())
+            .drop([ROW_INDEX])
+            .collect()?;
+
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn readings() -> DataFrame {
+        // Rows are deliberately out of time order
+        df! {
+            "sensor" => &["a", "b", "a", "b", "a"],
+            "ts" => &[3i64, 1, 1, 2, 2],
+            "reading" => &[16.0f64, 5.0, 10.0, 7.0, 11.0],
+        }
+        .unwrap()
+    }
+
+    fn config(pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([(
+            "value".to_string(),
+            toml::Value::String("reading".to_string()),
+        )]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    async fn deltas(config: &HashMap<String, toml::Value>) -> Vec<Option<f64>> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(readings()));
+        let df = DeltaTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        df.column("reading_delta")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_delta_resets_per_partition_in_order() {
+        let ordered = config(&[
+            ("partition_by", toml::Value::String("sensor".to_string())),
+            ("order_by", toml::Value::String("ts".to_string())),
+        ]);
+        // a: 10 -> 11 -> 16, b: 5 -> 7; output keeps the input row order
+        assert_eq!(
+            deltas(&ordered).await,
+            vec![Some(5.0), None, None, Some(2.0), Some(1.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delta_periods_and_fill() {
+        let lag = config(&[
+            ("partition_by", toml::Value::String("sensor".to_string())),
+            ("order_by", toml::Value::String("ts".to_string())),
+            ("periods", toml::Value::Integer(2)),
+            ("fill", toml::Value::Integer(0)),
+        ]);
+        assert_eq!(
+            deltas(&lag).await,
+            vec![Some(6.0), Some(0.0), Some(0.0), Some(0.0), Some(0.0)]
+        );
+
+        // Looking ahead fills the last row of each partition instead
+        let lead = config(&[
+            ("partition_by", toml::Value::String("sensor".to_string())),
+            ("order_by", toml::Value::String("ts".to_string())),
+            ("periods", toml::Value::Integer(-1)),
+            ("fill", toml::Value::Float(-1.0)),
+        ]);
+        assert_eq!(
+            deltas(&lead).await,
+            vec![Some(-1.0), Some(-2.0), Some(-1.0), Some(-1.0), Some(-5.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delta_without_partition_uses_input_order() {
+        assert_eq!(
+            deltas(&config(&[])).await,
+            vec![None, Some(-11.0), Some(5.0), Some(-3.0), Some(4.0)]
+        );
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(readings()));
+        let text_value = HashMap::from([(
+            "value".to_string(),
+            toml::Value::String("sensor".to_string()),
+        )]);
+        let err = match DeltaTransform.execute(inputs, &text_value).await {
+            Ok(_) => panic!("Expected a non-numeric value column to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(err, "Delta: column 'sensor' is str, not numeric");
+    }
+}
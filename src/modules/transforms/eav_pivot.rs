@@ -0,0 +1,393 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct EavPivotTransform;
+
+const AGGREGATIONS: [&str; 8] = [
+    "first", "last", "sum", "mean", "min", "max", "count", "error",
+];
+
+fn parse_index(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    let index = string_list_option(config, "index")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'index' configuration"))?;
+    if index.is_empty() {
+        anyhow::bail!("'index' must name at least one column");
+    }
+    Ok(index)
+}
+
+fn required_str<'a>(config: &'a HashMap<String, toml::Value>, name: &str) -> Result<&'a str> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) => Ok(s),
+        Some(_) => anyhow::bail!("'{}' must be a string", name),
+        None => anyhow::bail!("Missing required '{}' configuration", name),
+    }
+}
+
+fn parse_aggregate(config: &HashMap<String, toml::Value>) -> Result<&str> {
+    let aggregate = match config.get("aggregate") {
+        Some(toml::Value::String(s)) => s.as_str(),
+        Some(_) => anyhow::bail!("'aggregate' must be a string"),
+        None => "first",
+    };
+    if !AGGREGATIONS.contains(&aggregate) {
+        anyhow::bail!(
+            "Invalid aggregate '{}'. Supported: {}",
+            aggregate,
+            AGGREGATIONS.join(", ")
+        );
+    }
+    Ok(aggregate)
+}
+
+/// Fail if an entity carries the same attribute more than once with different values
+fn check_conflicts(df: &DataFrame, index: &[String], key: &str, value: &str) -> Result<()> {
+    let mut group: Vec<Expr> = index.iter().map(col).collect();
+    group.push(col(key));
+
+    let conflicts = df
+        .clone()
+        .lazy()
+        .group_by_stable(group)
+        .agg([col(value).n_unique().alias("__values")])
+        .filter(col("__values").gt(lit(1)))
+        .collect()?;
+
+    if conflicts.height() > 0 {
+        let attribute = conflicts.column(key)?.get(0)?;
+        anyhow::bail!(
+            "EAV pivot: {} entity/attribute pair(s) have conflicting values (first: attribute {}); \
+            set 'aggregate' to combine them",
+            conflicts.height(),
+            attribute
+        );
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Stage for EavPivotTransform {
+    fn name(&self) -> &str {
+        "eav_pivot.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "index".to_string(),
+            toml::Value::String("entity".to_string()),
+        );
+        example1.insert("key".to_string(), toml::Value::String("attr".to_string()));
+        example1.insert(
+            "value".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "index".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("device_id".to_string()),
+                toml::Value::String("day".to_string()),
+            ]),
+        );
+        example2.insert("key".to_string(), toml::Value::String("metric".to_string()));
+        example2.insert(
+            "value".to_string(),
+            toml::Value::String("reading".to_string()),
+        );
+        example2.insert(
+            "aggregate".to_string(),
+            toml::Value::String("mean".to_string()),
+        );
+
+        StageMetadata::builder("eav_pivot.apply", StageCategory::Transform)
+            .description("Pivot entity-attribute-value rows into one column per attribute")
+            .long_description(
+                "Turns EAV-shaped rows such as {entity, attr, value} into a wide frame with one \
+                row per distinct 'index' value and one column per distinct attribute found in \
+                'key'. Attribute columns appear in the order attributes are first seen, and rows \
+                in the order entities are first seen; entities without an attribute get null. \
+                When an entity has the same attribute more than once, 'aggregate' decides the \
+                result: first/last keep one value, sum/mean/min/max/count combine them, and \
+                'error' fails if the duplicates disagree. Rows with a null attribute are ignored.",
+            )
+            .parameter(ConfigParameter::required(
+                "index",
+                ParameterType::String,
+                "Entity column or array of columns identifying an output row",
+            ))
+            .parameter(ConfigParameter::required(
+                "key",
+                ParameterType::String,
+                "Column holding the attribute name; each distinct value becomes a column",
+            ))
+            .parameter(ConfigParameter::required(
+                "value",
+                ParameterType::String,
+                "Column holding the attribute value",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "aggregate",
+                    ParameterType::String,
+                    "first",
+                    "How duplicate attributes of an entity are combined",
+                )
+                .with_validation(ParameterValidation::allowed_values(AGGREGATIONS)),
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Pivot entity attributes",
+                example1,
+                Some("{entity: 1, attr: \"color\", value: \"red\"} becomes {entity: 1, color: \"red\"}"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Average repeated readings",
+                example2,
+                Some("One row per device and day, one column per metric averaged over readings"),
+            ))
+            .tag("pivot")
+            .tag("eav")
+            .tag("reshape")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("EAV pivot transform requires input data"))?;
+
+        let index = parse_index(config)?;
+        let key = required_str(config, "key")?;
+        let value = required_str(config, "value")?;
+        let aggregate = parse_aggregate(config)?;
+
+        let df = data.as_dataframe()?;
+        for column in index.iter().map(String::as_str).chain([key, value]) {
+            if df.column(column).is_err() {
+                anyhow::bail!("EAV pivot: column '{}' not found", column);
+            }
+        }
+
+        // Attribute names become column names, so compare them as text
+        let df = df
+            .lazy()
+            .with_column(col(key).cast(DataType::String))
+            .filter(col(key).is_not_null())
+            .collect()?;
+
+        let attributes: Vec<String> = df
+            .column(key)?
+            .unique_stable()?
+            .str()?
+            .into_iter()
+            .flatten()
+            .map(|s| s.to_string())
+            .collect();
+        if let Some(clash) = attributes.iter().find(|a| index.contains(a)) {
+            anyhow::bail!(
+                "EAV pivot: attribute '{}' has the same name as an index column",
+                clash
+            );
+        }
+
+        if aggregate == "error" {
+            check_conflicts(&df, &index, key, value)?;
+        }
+
+        let columns: Vec<Expr> = attributes
+            .iter()
+            .map(|attribute| {
+                let values = col(value).filter(col(key).eq(lit(attribute.as_str())));
+                let combined = match aggregate {
+                    "last" => values.last(),
+                    "sum" => values.sum(),
+                    "mean" => values.mean(),
+                    "min" => values.min(),
+                    "max" => values.max(),
+                    "count" => values.count(),
+                    _ => values.first(),
+                };
+                combined.alias(attribute)
+            })
+            .collect();
+
+        let result = df
+            .lazy()
+            .group_by_stable(index.iter().map(col).collect::<Vec<_>>())
+            .agg(columns)
+            .collect()?;
+
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        let index = parse_index(config)?;
+        let key = required_str(config, "key")?;
+        let value = required_str(config, "value")?;
+        parse_aggregate(config)?;
+
+        if index.iter().any(|c| c == key || c == value) || key == value {
+            anyhow::bail!("'index', 'key' and 'value' must name different columns");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eav_frame() -> DataFrame {
+        df![
+            "entity" => [1, 1, 2, 2, 1, 3],
+            "attr" => ["color", "size", "color", "weight", "color", "size"],
+            "value" => ["red", "L", "blue", "10", "green", "M"],
+        ]
+        .unwrap()
+    }
+
+    fn config(aggregate: Option<&str>) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "index".to_string(),
+            toml::Value::String("entity".to_string()),
+        );
+        config.insert("key".to_string(), toml::Value::String("attr".to_string()));
+        config.insert(
+            "value".to_string(),
+            toml::Value::String("value".to_string()),
+        );
+        if let Some(aggregate) = aggregate {
+            config.insert(
+                "aggregate".to_string(),
+                toml::Value::String(aggregate.to_string()),
+            );
+        }
+        config
+    }
+
+    async fn pivot(df: DataFrame, config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+        EavPivotTransform
+            .execute(inputs, config)
+            .await?
+            .as_dataframe()
+    }
+
+    #[tokio::test]
+    async fn test_eav_pivot_to_wide_frame() {
+        let result = pivot(eav_frame(), &config(None)).await.unwrap();
+
+        let expected = df![
+            "entity" => [1, 2, 3],
+            "color" => [Some("red"), Some("blue"), None],
+            "size" => [Some("L"), None, Some("M")],
+            "weight" => [None, Some("10"), None],
+        ]
+        .unwrap();
+        assert!(result.equals_missing(&expected));
+    }
+
+    #[tokio::test]
+    async fn test_eav_pivot_aggregates_duplicates() {
+        let df = df![
+            "device" => ["a", "a", "a", "b"],
+            "metric" => ["temp", "temp", "humidity", "temp"],
+            "reading" => [20.0, 22.0, 40.0, 18.0],
+        ]
+        .unwrap();
+        let mut config = config(Some("mean"));
+        config.insert(
+            "index".to_string(),
+            toml::Value::String("device".to_string()),
+        );
+        config.insert("key".to_string(), toml::Value::String("metric".to_string()));
+        config.insert(
+            "value".to_string(),
+            toml::Value::String("reading".to_string()),
+        );
+
+        let result = pivot(df.clone(), &config).await.unwrap();
+        let expected = df![
+            "device" => ["a", "b"],
+            "temp" => [21.0, 18.0],
+            "humidity" => [Some(40.0), None],
+        ]
+        .unwrap();
+        assert!(result.equals_missing(&expected));
+
+        config.insert(
+            "aggregate".to_string(),
+            toml::Value::String("last".to_string()),
+        );
+        let result = pivot(df, &config).await.unwrap();
+        let temps: Vec<Option<f64>> = result
+            .column("temp")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(temps, vec![Some(22.0), Some(18.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_eav_pivot_rejects_conflicts() {
+        let err = pivot(eav_frame(), &config(Some("error")))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("1 entity/attribute pair(s) have conflicting values"));
+        assert!(err.contains("\"color\""));
+
+        // Identical duplicates are not a conflict
+        let df = df![
+            "entity" => [1, 1],
+            "attr" => ["color", "color"],
+            "value" => ["red", "red"],
+        ]
+        .unwrap();
+        let result = pivot(df, &config(Some("error"))).await.unwrap();
+        assert_eq!(result.shape(), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn test_eav_pivot_config_validation() {
+        let stage = EavPivotTransform;
+        assert!(stage.validate_config(&config(None)).await.is_ok());
+        assert!(stage
+            .validate_config(&config(Some("median")))
+            .await
+            .is_err());
+
+        let mut same = config(None);
+        same.insert("key".to_string(), toml::Value::String("entity".to_string()));
+        assert!(stage.validate_config(&same).await.is_err());
+
+        let mut missing = config(None);
+        missing.remove("value");
+        assert!(stage.validate_config(&missing).await.is_err());
+
+        let mut misnamed = config(None);
+        misnamed.insert("value".to_string(), toml::Value::String("val".to_string()));
+        let err = pivot(eav_frame(), &misnamed).await.unwrap_err().to_string();
+        assert!(err.contains("column 'val' not found"));
+    }
+}
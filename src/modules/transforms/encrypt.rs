@@ -185,8 +185,6 @@ impl Stage for EncryptTransform {
             .and_then(|v| v.as_str())
             .unwrap_or("aes-256-gcm");
 
-        let algorithm = Algorithm::from_str(algorithm_str)?;
-
         let default_output = format!("{}_encrypted", column);
         let output_column = config
             .get("output_column")
@@ -194,63 +192,8 @@ impl Stage for EncryptTransform {
             .unwrap_or(&default_output);
 
         let df = data.as_dataframe()?;
-
-        // Validate key length
-        let expected_key_size = algorithm.key_size();
-        if key.len() != expected_key_size {
-            anyhow::bail!(
-                "{} requires a key of exactly {} bytes, got {} bytes",
-                algorithm.name(),
-                expected_key_size,
-                key.len()
-            );
-        }
-
-        // Get column data as string
-        let column_data = df
-            .column(column)
-            .with_context(|| format!("Column '{}' not found", column))?
-            .str()
-            .with_context(|| {
-                format!("Column '{}' must be of string type for encryption", column)
-            })?;
-
-        // Encrypt each value based on algorithm
-        let encrypted_values: Vec<Option<String>> = match algorithm {
-            Algorithm::Aes128Gcm => {
-                let key_bytes: [u8; 16] = key
-                    .as_bytes()
-                    .try_into()
-                    .context("Failed to convert key to 16-byte array")?;
-                let cipher = Aes128Gcm::new(&key_bytes.into());
-                encrypt_values_aes(&cipher, column_data)?
-            }
-            Algorithm::Aes256Gcm => {
-                let key_bytes: [u8; 32] = key
-                    .as_bytes()
-                    .try_into()
-                    .context("Failed to convert key to 32-byte array")?;
-                let cipher = Aes256Gcm::new(&key_bytes.into());
-                encrypt_values_aes(&cipher, column_data)?
-            }
-            Algorithm::ChaCha20Poly1305 => {
-                let key_bytes: [u8; 32] = key
-                    .as_bytes()
-                    .try_into()
-                    .context("Failed to convert key to 32-byte array")?;
-                let cipher = ChaCha20Poly1305::new(&key_bytes.into());
-                encrypt_values_chacha(&cipher, column_data)?
-            }
-        };
-
-        // Create new series with encrypted values
-        let encrypted_series = Series::new(output_column.into(), encrypted_values);
-
-        // Add encrypted column to DataFrame
         let mut result_df = df.clone();
-        result_df
-            .with_column(encrypted_series)
-            .context("Failed to add encrypted column to DataFrame")?;
+        encrypt_column(&mut result_df, column, key, algorithm_str, output_column)?;
 
         Ok(DataFormat::DataFrame(result_df))
     }
@@ -295,6 +238,82 @@ impl Stage for EncryptTransform {
     }
 }
 
+/// Parse the algorithm name and verify the key has the length it requires
+pub(crate) fn check_key(key: &str, algorithm: &str) -> Result<()> {
+    let algorithm = Algorithm::from_str(algorithm)?;
+
+    let expected_key_size = algorithm.key_size();
+    if key.len() != expected_key_size {
+        anyhow::bail!(
+            "{} requires a key of exactly {} bytes, got {} bytes",
+            algorithm.name(),
+            expected_key_size,
+            key.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Encrypt a string column in place, writing base64(nonce + ciphertext) into `output_column`
+///
+/// Shared with the file sinks' `encrypt_columns` option so both paths produce
+/// values that `decrypt.apply` can read back.
+pub(crate) fn encrypt_column(
+    df: &mut DataFrame,
+    column: &str,
+    key: &str,
+    algorithm: &str,
+    output_column: &str,
+) -> Result<()> {
+    check_key(key, algorithm)?;
+    let algorithm = Algorithm::from_str(algorithm)?;
+
+    // Get column data as string
+    let column_data = df
+        .column(column)
+        .with_context(|| format!("Column '{}' not found", column))?
+        .str()
+        .with_context(|| format!("Column '{}' must be of string type for encryption", column))?;
+
+    // Encrypt each value based on algorithm
+    let encrypted_values: Vec<Option<String>> = match algorithm {
+        Algorithm::Aes128Gcm => {
+            let key_bytes: [u8; 16] = key
+                .as_bytes()
+                .try_into()
+                .context("Failed to convert key to 16-byte array")?;
+            let cipher = Aes128Gcm::new(&key_bytes.into());
+            encrypt_values_aes(&cipher, column_data)?
+        }
+        Algorithm::Aes256Gcm => {
+            let key_bytes: [u8; 32] = key
+                .as_bytes()
+                .try_into()
+                .context("Failed to convert key to 32-byte array")?;
+            let cipher = Aes256Gcm::new(&key_bytes.into());
+            encrypt_values_aes(&cipher, column_data)?
+        }
+        Algorithm::ChaCha20Poly1305 => {
+            let key_bytes: [u8; 32] = key
+                .as_bytes()
+                .try_into()
+                .context("Failed to convert key to 32-byte array")?;
+            let cipher = ChaCha20Poly1305::new(&key_bytes.into());
+            encrypt_values_chacha(&cipher, column_data)?
+        }
+    };
+
+    // Create new series with encrypted values
+    let encrypted_series = Series::new(output_column.into(), encrypted_values);
+
+    // Add encrypted column to DataFrame
+    df.with_column(encrypted_series)
+        .context("Failed to add encrypted column to DataFrame")?;
+
+    Ok(())
+}
+
 fn encrypt_values_aes<C>(cipher: &C, column_data: &StringChunked) -> Result<Vec<Option<String>>>
 where
     C: Aead,
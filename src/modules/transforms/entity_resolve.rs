@@ -0,0 +1,703 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use super::common::{string_list, string_option};
+use super::fuzzy_join::{key_text, parse_threshold, Metric};
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+
+/// Scores record pairs on several weighted fields and links those that match
+pub struct EntityResolveTransform;
+
+const OUTPUTS: [&str; 2] = ["clusters", "pairs"];
+
+#[derive(Debug, Clone, Copy)]
+enum Similarity {
+    Exact,
+    Fuzzy(Metric),
+}
+
+impl Similarity {
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "exact" => Ok(Similarity::Exact),
+            other => Metric::from_str(other).map(Similarity::Fuzzy).map_err(|_| {
+                anyhow::anyhow!(
+                    "Unknown comparator metric: '{}'. Supported: exact, levenshtein, jaro_winkler",
+                    s
+                )
+            }),
+        }
+    }
+
+    fn score(&self, a: &str, b: &str) -> f64 {
+        match self {
+            Similarity::Exact => {
+                if a == b {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Similarity::Fuzzy(metric) => metric.similarity(a, b),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Comparator {
+    field: String,
+    similarity: Similarity,
+    weight: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Output {
+    Clusters,
+    Pairs,
+}
+
+struct Options {
+    comparators: Vec<Comparator>,
+    threshold: f64,
+    blocking_key: Vec<String>,
+    case_sensitive: bool,
+    output: Output,
+    id: Option<String>,
+    cluster_column: String,
+    score_column: String,
+}
+
+fn parse_comparators(config: &HashMap<String, toml::Value>) -> Result<Vec<Comparator>> {
+    let entries = match config.get("comparators") {
+        Some(toml::Value::Array(arr)) if !arr.is_empty() => arr,
+        Some(_) => anyhow::bail!("'comparators' must be a non-empty array of tables"),
+        None => anyhow::bail!("Missing required 'comparators' configuration"),
+    };
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let table = entry
+                .as_table()
+                .ok_or_else(|| anyhow::anyhow!("Comparator {} must be a table", i + 1))?;
+            let field = table
+                .get("field")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Comparator {} requires a 'field' string", i + 1))?
+                .to_string();
+            let similarity = match table.get("metric") {
+                None => Similarity::Fuzzy(Metric::Levenshtein),
+                Some(toml::Value::String(s)) => Similarity::from_str(s)?,
+                Some(_) => anyhow::bail!("Comparator '{}': 'metric' must be a string", field),
+            };
+            let weight = match table.get("weight") {
+                None => 1.0,
+                Some(toml::Value::Float(f)) => *f,
+                Some(toml::Value::Integer(n)) => *n as f64,
+                Some(_) => anyhow::bail!("Comparator '{}': 'weight' must be a number", field),
+            };
+            if !(weight > 0.0 && weight.is_finite()) {
+                anyhow::bail!(
+                    "Comparator '{}': 'weight' must be positive, got {}",
+                    field,
+                    weight
+                );
+            }
+            Ok(Comparator {
+                field,
+                similarity,
+                weight,
+            })
+        })
+        .collect()
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let blocking_key = string_list(config, "blocking_key")?;
+
+    let output = match string_option(config, "output")?.as_deref() {
+        None | Some("clusters") => Output::Clusters,
+        Some("pairs") => Output::Pairs,
+        Some(other) => anyhow::bail!(
+            "Invalid output: {}. Must be one of: {}",
+            other,
+            OUTPUTS.join(", ")
+        ),
+    };
+
+    let case_sensitive = match config.get("case_sensitive") {
+        None => false,
+        Some(toml::Value::Boolean(b)) => *b,
+        Some(_) => anyhow::bail!("'case_sensitive' must be a boolean"),
+    };
+
+    for option in ["left", "right"] {
+        string_option(config, option)?;
+    }
+
+    Ok(Options {
+        comparators: parse_comparators(config)?,
+        threshold: parse_threshold(config)?,
+        blocking_key,
+        case_sensitive,
+        output,
+        id: string_option(config, "id")?,
+        cluster_column: string_option(config, "cluster_column")?
+            .unwrap_or_else(|| "cluster_id".to_string()),
+        score_column: string_option(config, "score_column")?.unwrap_or_else(|| "score".to_string()),
+    })
+}
+
+impl Options {
+    /// Weighted average similarity of two records
+    ///
+    /// Fields missing or null in either record neither raise nor lower the
+    /// score. Returns `None` when no comparator field is present in both.
+    fn score(&self, a: &HashMap<String, JsonValue>, b: &HashMap<String, JsonValue>) -> Option<f64> {
+        let mut total = 0.0;
+        let mut weights = 0.0;
+        for comparator in &self.comparators {
+            let a = key_text(a.get(&comparator.field), self.case_sensitive);
+            let b = key_text(b.get(&comparator.field), self.case_sensitive);
+            if let (Some(a), Some(b)) = (a, b) {
+                total += comparator.weight * comparator.similarity.score(&a, &b);
+                weights += comparator.weight;
+            }
+        }
+        (weights > 0.0).then(|| total / weights)
+    }
+
+    fn block(&self, record: &HashMap<String, JsonValue>) -> Result<String> {
+        let values: Vec<&JsonValue> = self
+            .blocking_key
+            .iter()
+            .map(|column| record.get(column).unwrap_or(&JsonValue::Null))
+            .collect();
+        Ok(serde_json::to_string(&values)?)
+    }
+
+    /// Identifier of a record in pair output: its `id` field, or its position
+    fn record_id(&self, record: &HashMap<String, JsonValue>, position: usize) -> JsonValue {
+        match &self.id {
+            Some(column) => record.get(column).cloned().unwrap_or(JsonValue::Null),
+            None => JsonValue::from(position),
+        }
+    }
+}
+
+/// Union-find over record positions, keeping the smallest position as root
+struct Clusters {
+    parent: Vec<usize>,
+}
+
+impl Clusters {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    fn root(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.root(a), self.root(b));
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        self.parent[high] = low;
+    }
+}
+
+/// Records to resolve, and the input each came from
+struct Sources {
+    records: RecordBatch,
+    /// Position of the first record of the second input, when linking two
+    second: Option<usize>,
+    names: (String, String),
+}
+
+impl Sources {
+    fn from_inputs(
+        mut inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<Self> {
+        if inputs.len() == 1 {
+            let (name, data) = inputs.into_iter().next().unwrap();
+            return Ok(Self {
+                records: data.as_record_batch()?,
+                second: None,
+                names: (name, String::new()),
+            });
+        }
+
+        let left_name = config
+            .get("left")
+            .and_then(|v| v.as_str())
+            .unwrap_or("left");
+        let right_name = config
+            .get("right")
+            .and_then(|v| v.as_str())
+            .unwrap_or("right");
+
+        let mut available: Vec<&String> = inputs.keys().collect();
+        available.sort();
+        let available = format!("{:?}", available);
+
+        let left = inputs.remove(left_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Entity resolve: left input '{}' not found (available inputs: {})",
+                left_name,
+                available
+            )
+        })?;
+        let right = inputs.remove(right_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Entity resolve: right input '{}' not found (available inputs: {})",
+                right_name,
+                available
+            )
+        })?;
+
+        let mut records = left.as_record_batch()?;
+        let second = records.len();
+        records.extend(right.as_record_batch()?);
+        Ok(Self {
+            records,
+            second: Some(second),
+            names: (left_name.to_string(), right_name.to_string()),
+        })
+    }
+
+    fn is_right(&self, position: usize) -> bool {
+        self.second.is_some_and(|second| position >= second)
+    }
+
+    /// Position within the record's own input
+    fn local_position(&self, position: usize) -> usize {
+        match self.second {
+            Some(second) if position >= second => position - second,
+            _ => position,
+        }
+    }
+
+    /// Whether two records are candidates: any pair within one input, or
+    /// only left-right pairs when linking two inputs
+    fn comparable(&self, a: usize, b: usize) -> bool {
+        self.second.is_none() || self.is_right(a) != self.is_right(b)
+    }
+}
+
+/// Every candidate pair scoring at least the threshold, best first
+fn matching_pairs(sources: &Sources, options: &Options) -> Result<Vec<(usize, usize, f64)>> {
+    let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for (position, record) in sources.records.iter().enumerate() {
+        blocks
+            .entry(options.block(record)?)
+            .or_default()
+            .push(position);
+    }
+
+    let mut pairs = Vec::new();
+    for members in blocks.values() {
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                if !sources.comparable(a, b) {
+                    continue;
+                }
+                if let Some(score) = options.score(&sources.records[a], &sources.records[b]) {
+                    if score >= options.threshold {
+                        pairs.push((a, b, score));
+                    }
+                }
+            }
+        }
+    }
+    pairs.sort_by(|x, y| y.2.total_cmp(&x.2).then((x.0, x.1).cmp(&(y.0, y.1))));
+    Ok(pairs)
+}
+
+#[async_trait]
+impl Stage for EntityResolveTransform {
+    fn name(&self) -> &str {
+        "entity_resolve.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let comparator = |field: &str, metric: &str, weight: f64| {
+            let mut table = toml::map::Map::new();
+            table.insert("field".to_string(), toml::Value::String(field.to_string()));
+            table.insert(
+                "metric".to_string(),
+                toml::Value::String(metric.to_string()),
+            );
+            table.insert("weight".to_string(), toml::Value::Float(weight));
+            toml::Value::Table(table)
+        };
+
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "comparators".to_string(),
+            toml::Value::Array(vec![
+                comparator("name", "jaro_winkler", 2.0),
+                comparator("dob", "exact", 1.0),
+            ]),
+        );
+        example1.insert("threshold".to_string(), toml::Value::Float(0.9));
+        example1.insert(
+            "blocking_key".to_string(),
+            toml::Value::String("postal_code".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "left".to_string(),
+            toml::Value::String("crm_people".to_string()),
+        );
+        example2.insert(
+            "right".to_string(),
+            toml::Value::String("billing_people".to_string()),
+        );
+        example2.insert(
+            "comparators".to_string(),
+            toml::Value::Array(vec![
+                comparator("name", "levenshtein", 1.0),
+                comparator("email", "exact", 3.0),
+            ]),
+        );
+        example2.insert(
+            "output".to_string(),
+            toml::Value::String("pairs".to_string()),
+        );
+        example2.insert("id".to_string(), toml::Value::String("id".to_string()));
+
+        StageMetadata::builder("entity_resolve.apply", StageCategory::Transform)
+            .description("Link records about the same entity by weighted multi-field similarity")
+            .long_description(
+                "Scores pairs of records as the weighted average of per-field similarities from \
+                'comparators' (each a table with 'field', 'metric' and 'weight'). Fields that are \
+                missing or null in either record are left out of that pair's average. Pairs \
+                scoring at least 'threshold' match. With one input, all records of it are \
+                compared; with two, only records of the 'left' input against records of the \
+                'right' input. 'blocking_key' limits comparisons to records with equal values in \
+                those columns, which avoids comparing every pair. output = \"clusters\" returns \
+                every record with a 'cluster_column' shared by transitively matching records \
+                (and a 'source' column naming the input when linking two); output = \"pairs\" \
+                returns one row per match with 'left', 'right' and 'score_column', the sides \
+                identified by the 'id' column or by row position.",
+            )
+            .parameter(ConfigParameter::required(
+                "comparators",
+                ParameterType::Array,
+                "Tables of field, metric (exact, levenshtein, jaro_winkler) and weight",
+            ))
+            .parameter(ConfigParameter::optional(
+                "threshold",
+                ParameterType::Float,
+                "0.8",
+                "Minimum weighted similarity (0 to 1) for a pair to match",
+            ))
+            .parameter(ConfigParameter::optional(
+                "blocking_key",
+                ParameterType::Array,
+                "none",
+                "Column(s) that must be equal before two records are compared",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "clusters",
+                    "Records with cluster ids, or one row per matching pair",
+                )
+                .with_validation(ParameterValidation::allowed_values(OUTPUTS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "id",
+                ParameterType::String,
+                "row position",
+                "Column identifying records in pair output",
+            ))
+            .parameter(ConfigParameter::optional(
+                "case_sensitive",
+                ParameterType::Boolean,
+                "false",
+                "Compare values without lowercasing them",
+            ))
+            .parameter(ConfigParameter::optional(
+                "cluster_column",
+                ParameterType::String,
+                "cluster_id",
+                "Column receiving the cluster id",
+            ))
+            .parameter(ConfigParameter::optional(
+                "score_column",
+                ParameterType::String,
+                "score",
+                "Column receiving the pair score",
+            ))
+            .parameter(ConfigParameter::optional(
+                "left",
+                ParameterType::String,
+                "left",
+                "Input providing the left records when linking two inputs",
+            ))
+            .parameter(ConfigParameter::optional(
+                "right",
+                ParameterType::String,
+                "right",
+                "Input providing the right records when linking two inputs",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Deduplicate people",
+                example1,
+                Some("Cluster people with near-identical names and the same date of birth"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Link two systems",
+                example2,
+                Some("Pairs of CRM and billing ids, weighting an email match highest"),
+            ))
+            .tag("matching")
+            .tag("dedup")
+            .tag("entity-resolution")
+            .tag("fuzzy")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        if inputs.is_empty() {
+            anyhow::bail!("Entity resolve transform requires input data");
+        }
+        let options = parse_options(config)?;
+        let sources = Sources::from_inputs(inputs, config)?;
+        let pairs = matching_pairs(&sources, &options)?;
+
+        let output = match options.output {
+            Output::Pairs => pairs
+                .into_iter()
+                .map(|(a, b, score)| {
+                    HashMap::from([
+                        (
+                            "left".to_string(),
+                            options.record_id(&sources.records[a], sources.local_position(a)),
+                        ),
+                        (
+                            "right".to_string(),
+                            options.record_id(&sources.records[b], sources.local_position(b)),
+                        ),
+                        (options.score_column.clone(), JsonValue::from(score)),
+                    ])
+                })
+                .collect(),
+            Output::Clusters => {
+                let mut clusters = Clusters::new(sources.records.len());
+                for &(a, b, _) in &pairs {
+                    clusters.union(a, b);
+                }
+                let roots: Vec<usize> = (0..sources.records.len())
+                    .map(|i| clusters.root(i))
+                    .collect();
+                let linking = sources.second.is_some();
+                let (left_name, right_name) = sources.names.clone();
+                let right: Vec<bool> = (0..sources.records.len())
+                    .map(|i| sources.is_right(i))
+                    .collect();
+
+                sources
+                    .records
+                    .into_iter()
+                    .zip(roots)
+                    .zip(right)
+                    .map(|((mut record, root), is_right)| {
+                        record.insert(options.cluster_column.clone(), JsonValue::from(root));
+                        if linking {
+                            let source = if is_right { &right_name } else { &left_name };
+                            record.insert("source".to_string(), JsonValue::from(source.clone()));
+                        }
+                        record
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(DataFormat::RecordBatch(output))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn people() -> RecordBatch {
+        [
+            json!({"id": "p1", "name": "Jonathan Smith", "dob": "1980-04-02", "zip": "10001"}),
+            json!({"id": "p2", "name": "Jonathon Smith", "dob": "1980-04-02", "zip": "10001"}),
+            json!({"id": "p3", "name": "JONATHAN SMYTH", "dob": "1980-04-02", "zip": "10001"}),
+            // Same name, another person
+            json!({"id": "p4", "name": "Jonathan Smith", "dob": "1955-11-30", "zip": "10001"}),
+            json!({"id": "p5", "name": "Maria Garcia", "dob": "1991-07-15", "zip": "10001"}),
+            json!({"id": "p6", "name": "Maria Garcia", "dob": "1991-07-15", "zip": "94105"}),
+        ]
+        .into_iter()
+        .map(|v| serde_json::from_value(v).unwrap())
+        .collect()
+    }
+
+    fn config(extra: &str) -> HashMap<String, toml::Value> {
+        toml::from_str(&format!(
+            r#"
+threshold = 0.9
+comparators = [
+  {{ field = "name", metric = "jaro_winkler", weight = 2.0 }},
+  {{ field = "dob", metric = "exact", weight = 1.0 }},
+]
+{}
+"#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    async fn resolve(
+        inputs: Vec<(&str, RecordBatch)>,
+        config: &HashMap<String, toml::Value>,
+    ) -> RecordBatch {
+        let inputs = inputs
+            .into_iter()
+            .map(|(name, records)| (name.to_string(), DataFormat::RecordBatch(records)))
+            .collect();
+        EntityResolveTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    fn cluster_of(records: &RecordBatch) -> HashMap<String, i64> {
+        records
+            .iter()
+            .map(|r| {
+                (
+                    r["id"].as_str().unwrap().to_string(),
+                    r["cluster_id"].as_i64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_name_and_dob_matches_cluster_together() {
+        let records = resolve(vec![("people", people())], &config("")).await;
+        let clusters = cluster_of(&records);
+
+        assert_eq!(clusters["p1"], 0);
+        assert_eq!(clusters["p2"], 0);
+        assert_eq!(clusters["p3"], 0);
+        // A different date of birth keeps an identical name apart
+        assert_eq!(clusters["p4"], 3);
+        assert_eq!(clusters["p5"], 4);
+        assert_eq!(clusters["p6"], 4);
+        assert_eq!(records[0]["name"], json!("Jonathan Smith"));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_and_pairs() {
+        let records = resolve(
+            vec![("people", people())],
+            &config("blocking_key = \"zip\"\noutput = \"pairs\"\nid = \"id\""),
+        )
+        .await;
+        let pairs: Vec<(&str, &str)> = records
+            .iter()
+            .map(|r| (r["left"].as_str().unwrap(), r["right"].as_str().unwrap()))
+            .collect();
+
+        // The two Maria Garcia records are in different zip blocks
+        assert_eq!(pairs.len(), 3);
+        for pair in [("p1", "p2"), ("p1", "p3"), ("p2", "p3")] {
+            assert!(pairs.contains(&pair), "{:?} missing", pair);
+        }
+        let scores: Vec<f64> = records
+            .iter()
+            .map(|r| r["score"].as_f64().unwrap())
+            .collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+        assert!(scores.iter().all(|s| (0.9..=1.0).contains(s)));
+    }
+
+    #[tokio::test]
+    async fn test_link_two_inputs() {
+        let crm: RecordBatch = people()[..2].to_vec();
+        let billing: RecordBatch = vec![
+            serde_json::from_value(json!({"id": "b1", "name": "Jon Smith", "dob": "1980-04-02"}))
+                .unwrap(),
+            serde_json::from_value(json!({"id": "b2", "name": "Alan Turing"})).unwrap(),
+        ];
+
+        let mut config = config("left = \"crm\"\nright = \"billing\"\noutput = \"pairs\"");
+        config.insert("threshold".to_string(), toml::Value::Float(0.85));
+        let pairs = resolve(
+            vec![("crm", crm.clone()), ("billing", billing.clone())],
+            &config,
+        )
+        .await;
+        // Row positions on each side; p1 and p2 are never paired with each other
+        let ids: Vec<(i64, i64)> = pairs
+            .iter()
+            .map(|r| (r["left"].as_i64().unwrap(), r["right"].as_i64().unwrap()))
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.iter().all(|&(_, right)| right == 0));
+
+        config.insert(
+            "output".to_string(),
+            toml::Value::String("clusters".to_string()),
+        );
+        let records = resolve(vec![("crm", crm), ("billing", billing)], &config).await;
+        let clusters = cluster_of(&records);
+        assert_eq!(clusters["p1"], clusters["b1"]);
+        assert_eq!(clusters["p2"], clusters["b1"]);
+        assert_ne!(clusters["b2"], clusters["b1"]);
+        assert_eq!(records[2]["source"], json!("billing"));
+    }
+
+    #[tokio::test]
+    async fn test_entity_resolve_validation() {
+        let transform = EntityResolveTransform;
+        assert!(transform.validate_config(&config("")).await.is_ok());
+        assert!(transform.validate_config(&HashMap::new()).await.is_err());
+
+        for bad in [
+            "comparators = []",
+            "comparators = [{ metric = \"exact\" }]",
+            "comparators = [{ field = \"name\", metric = \"soundex\" }]",
+            "comparators = [{ field = \"name\", weight = 0 }]",
+            "comparators = [{ field = \"name\" }]\noutput = \"graph\"",
+        ] {
+            let config: HashMap<String, toml::Value> = toml::from_str(bad).unwrap();
+            assert!(transform.validate_config(&config).await.is_err(), "{}", bad);
+        }
+    }
+}
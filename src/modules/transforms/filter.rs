@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use crate::core::metadata::{
     ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
 };
-use crate::core::stage::Stage;
+use crate::core::stage::{MissingColumnPolicy, Stage};
 use crate::core::traits::DataFormat;
 
 pub struct FilterTransform;
@@ -49,7 +49,10 @@ impl Stage for FilterTransform {
                 "Filters DataFrame rows using various comparison operators. \
                 Supports numeric comparisons (>, >=, <, <=), equality checks (==, !=), \
                 string operations (contains), and set membership (in). \
-                Uses Polars' lazy evaluation for optimal performance."
+                Uses Polars' lazy evaluation for optimal performance. \
+                When the column is absent, 'on_missing_column' decides whether the stage \
+                fails, passes every row through ('skip'), or compares against nulls ('null'), \
+                which matches no rows."
             )
             .parameter(ConfigParameter::required(
                 "column",
@@ -69,6 +72,9 @@ impl Stage for FilterTransform {
                 ParameterType::String,
                 "Value to compare against (can be string, number, boolean, or array for 'in' operator)"
             ))
+            .parameter(MissingColumnPolicy::parameter(
+                "What to do when the column is absent: 'error', 'skip' the filter, or treat it as 'null'"
+            ))
             .example(crate::core::metadata::ConfigExample::new(
                 "Filter adults (age >= 18)",
                 example1,
@@ -109,8 +115,17 @@ impl Stage for FilterTransform {
             .get("value")
             .ok_or_else(|| anyhow::anyhow!("Filter requires 'value' configuration"))?;
 
+        let policy = MissingColumnPolicy::from_config(config)?;
         let df = data.as_dataframe()?;
 
+        if !policy.missing("Filter", &df, [column])?.is_empty() {
+            return Ok(DataFormat::DataFrame(match policy {
+                // Every comparison against a null is false or null, so nothing matches
+                MissingColumnPolicy::Null => df.clear(),
+                _ => df,
+            }));
+        }
+
         let filtered_df = match operator {
             "==" | "=" => {
                 let expr = col(column).eq(lit(value_to_literal(value)?));
@@ -188,6 +203,8 @@ impl Stage for FilterTransform {
             anyhow::bail!("Filter requires 'value' configuration");
         }
 
+        MissingColumnPolicy::from_config(config)?;
+
         if let Some(operator) = config.get("operator").and_then(|v| v.as_str()) {
             let valid_operators = [
                 "==", "=", "!=", "<>", ">", ">=", "<", "<=", "contains", "in",
@@ -214,3 +231,47 @@ fn value_to_literal(value: &toml::Value) -> Result<LiteralValue> {
         _ => anyhow::bail!("Unsupported value type for filter"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(policy: &str) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            (
+                "column".to_string(),
+                toml::Value::String("tier".to_string()),
+            ),
+            (
+                "operator".to_string(),
+                toml::Value::String("!=".to_string()),
+            ),
+            ("value".to_string(), toml::Value::String("gold".to_string())),
+            (
+                "on_missing_column".to_string(),
+                toml::Value::String(policy.to_string()),
+            ),
+        ])
+    }
+
+    async fn filter(config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let df = df! { "id" => &[1i64, 2, 3] }.unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        FilterTransform
+            .execute(inputs, config)
+            .await?
+            .as_dataframe()
+    }
+
+    #[tokio::test]
+    async fn test_filter_missing_column_policies() {
+        let err = filter(&config("error")).await.unwrap_err().to_string();
+        assert_eq!(err, "Filter: column 'tier' not found");
+
+        assert_eq!(filter(&config("skip")).await.unwrap().height(), 3);
+
+        let nulls = filter(&config("null")).await.unwrap();
+        assert_eq!(nulls.height(), 0);
+        assert_eq!(nulls.get_column_names(), vec!["id"]);
+    }
+}
@@ -0,0 +1,337 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashMap;
+use tokio_stream::StreamExt;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+
+pub struct FlattenTransform;
+
+struct Options {
+    separator: String,
+    max_depth: Option<usize>,
+    explode_arrays: bool,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let separator = match config.get("separator") {
+        Some(toml::Value::String(s)) if !s.is_empty() => s.clone(),
+        Some(_) => anyhow::bail!("'separator' must be a non-empty string"),
+        None => ".".to_string(),
+    };
+    let max_depth = match config.get("max_depth") {
+        Some(toml::Value::Integer(n)) if *n >= 1 => Some(*n as usize),
+        Some(_) => anyhow::bail!("'max_depth' must be a positive integer"),
+        None => None,
+    };
+    let explode_arrays = match config.get("explode_arrays") {
+        Some(toml::Value::Boolean(b)) => *b,
+        Some(_) => anyhow::bail!("'explode_arrays' must be a boolean"),
+        None => false,
+    };
+
+    Ok(Options {
+        separator,
+        max_depth,
+        explode_arrays,
+    })
+}
+
+/// Fields of one output row
+type Fields = Vec<(String, JsonValue)>;
+
+impl Options {
+    /// Whether a value `depth` levels below the record may be flattened further
+    fn within_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+    }
+
+    /// Output rows for `value` stored under `key`, as alternatives when arrays explode
+    fn expand(&self, key: String, value: JsonValue, depth: usize) -> Vec<Fields> {
+        match value {
+            JsonValue::Object(object) if !object.is_empty() && self.within_depth(depth) => {
+                let children = object.into_iter().map(|(child, value)| {
+                    let key = format!("{}{}{}", key, self.separator, child);
+                    self.expand(key, value, depth + 1)
+                });
+                cross(children)
+            }
+            JsonValue::Array(items) if self.explode_arrays && self.within_depth(depth) => {
+                if items.is_empty() {
+                    return vec![vec![(key, JsonValue::Null)]];
+                }
+                // Elements stay under the array's own key, one row each
+                items
+                    .into_iter()
+                    .flat_map(|item| self.expand(key.clone(), item, depth))
+                    .collect()
+            }
+            value => vec![vec![(key, value)]],
+        }
+    }
+
+    fn flatten_record(&self, record: HashMap<String, JsonValue>) -> Result<RecordBatch> {
+        let fields = record
+            .into_iter()
+            .map(|(key, value)| self.expand(key, value, 0));
+
+        cross(fields)
+            .into_iter()
+            .map(|fields| {
+                let mut row = HashMap::with_capacity(fields.len());
+                for (key, value) in fields {
+                    if row.insert(key.clone(), value).is_some() {
+                        anyhow::bail!(
+                            "Flatten: field '{}' appears both nested and flat in a record",
+                            key
+                        );
+                    }
+                }
+                Ok(row)
+            })
+            .collect()
+    }
+
+    fn apply(&self, records: RecordBatch) -> Result<RecordBatch> {
+        let mut output = Vec::with_capacity(records.len());
+        for record in records {
+            output.extend(self.flatten_record(record)?);
+        }
+        Ok(output)
+    }
+}
+
+/// Every combination of one alternative from each part
+fn cross(parts: impl Iterator<Item = Vec<Fields>>) -> Vec<Fields> {
+    let mut rows: Vec<Fields> = vec![Vec::new()];
+    for alternatives in parts {
+        rows = rows
+            .iter()
+            .flat_map(|row| {
+                alternatives.iter().map(move |fields| {
+                    let mut row = row.clone();
+                    row.extend(fields.iter().cloned());
+                    row
+                })
+            })
+            .collect();
+    }
+    rows
+}
+
+#[async_trait]
+impl Stage for FlattenTransform {
+    fn name(&self) -> &str {
+        "flatten.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert("explode_arrays".to_string(), toml::Value::Boolean(true));
+        example.insert("max_depth".to_string(), toml::Value::Integer(3));
+
+        StageMetadata::builder("flatten.apply", StageCategory::Transform)
+            .description("Flatten nested JSON records into dotted columns")
+            .long_description(
+                "Recursively flattens nested objects in JSON records into top-level fields \
+                named by their path, so {\"address\": {\"city\": \"Oslo\"}} becomes \
+                {\"address.city\": \"Oslo\"}. With 'explode_arrays', each element of an array \
+                produces its own row, with object elements flattened under the array's name; \
+                several arrays in one record give every combination of their elements, and an \
+                empty array gives one row with null. 'max_depth' bounds how many levels are \
+                flattened; deeper values are kept as-is. DataFrames are already flat and pass \
+                through unchanged.",
+            )
+            .parameter(ConfigParameter::optional(
+                "separator",
+                ParameterType::String,
+                ".",
+                "Delimiter joining the keys of a nested field's path",
+            ))
+            .parameter(ConfigParameter::optional(
+                "max_depth",
+                ParameterType::Integer,
+                "unlimited",
+                "Levels of nesting to flatten; deeper values are kept as-is",
+            ))
+            .parameter(ConfigParameter::optional(
+                "explode_arrays",
+                ParameterType::Boolean,
+                "false",
+                "Emit one row per array element instead of keeping arrays as values",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "One row per order item",
+                example,
+                Some("{\"id\": 1, \"items\": [{\"sku\": \"A\"}, {\"sku\": \"B\"}]} becomes two rows with 'id' and 'items.sku'"),
+            ))
+            .tag("json")
+            .tag("flatten")
+            .tag("nested")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Flatten transform requires input data"))?;
+
+        let options = parse_options(config)?;
+
+        match data {
+            DataFormat::RecordBatch(records) => {
+                Ok(DataFormat::RecordBatch(options.apply(records)?))
+            }
+            DataFormat::Stream(upstream) => {
+                let stream = upstream.map(move |batch| options.apply(batch?));
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+            DataFormat::Raw(bytes) => {
+                let records: Vec<Map<String, JsonValue>> =
+                    serde_json::from_slice(&bytes).map_err(|e| {
+                        anyhow::anyhow!("Flatten: raw input is not a JSON array of objects: {}", e)
+                    })?;
+                let records = records
+                    .into_iter()
+                    .map(|record| record.into_iter().collect())
+                    .collect();
+                Ok(DataFormat::RecordBatch(options.apply(records)?))
+            }
+            df @ DataFormat::DataFrame(_) => Ok(df),
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: JsonValue) -> HashMap<String, JsonValue> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    async fn flatten(records: Vec<JsonValue>, config: HashMap<String, toml::Value>) -> RecordBatch {
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "input".to_string(),
+            DataFormat::RecordBatch(records.into_iter().map(record).collect()),
+        );
+        FlattenTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_flatten_two_level_object() {
+        let input = json!({
+            "id": 1,
+            "address": {"city": "Oslo", "geo": {"lat": 59.9, "lon": 10.7}},
+            "tags": ["a", "b"]
+        });
+
+        let rows = flatten(vec![input.clone()], HashMap::new()).await;
+        assert_eq!(
+            rows,
+            vec![record(json!({
+                "id": 1,
+                "address.city": "Oslo",
+                "address.geo.lat": 59.9,
+                "address.geo.lon": 10.7,
+                "tags": ["a", "b"]
+            }))]
+        );
+
+        let mut config = HashMap::new();
+        config.insert(
+            "separator".to_string(),
+            toml::Value::String("_".to_string()),
+        );
+        config.insert("max_depth".to_string(), toml::Value::Integer(1));
+        let rows = flatten(vec![input], config).await;
+        assert_eq!(
+            rows,
+            vec![record(json!({
+                "id": 1,
+                "address_city": "Oslo",
+                "address_geo": {"lat": 59.9, "lon": 10.7},
+                "tags": ["a", "b"]
+            }))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flatten_explodes_arrays_into_rows() {
+        let mut config = HashMap::new();
+        config.insert("explode_arrays".to_string(), toml::Value::Boolean(true));
+
+        let rows = flatten(
+            vec![
+                json!({
+                    "order": 7,
+                    "items": [{"sku": "A", "qty": 1}, {"sku": "B", "qty": 2}],
+                    "customer": {"tags": ["new", "vip"]}
+                }),
+                json!({"order": 8, "items": []}),
+            ],
+            config,
+        )
+        .await;
+
+        let mut rows: Vec<String> = rows
+            .into_iter()
+            .map(|row| {
+                let row: std::collections::BTreeMap<_, _> = row.into_iter().collect();
+                serde_json::to_string(&row).unwrap()
+            })
+            .collect();
+        rows.sort();
+        assert_eq!(
+            rows,
+            vec![
+                r#"{"customer.tags":"new","items.qty":1,"items.sku":"A","order":7}"#,
+                r#"{"customer.tags":"new","items.qty":2,"items.sku":"B","order":7}"#,
+                r#"{"customer.tags":"vip","items.qty":1,"items.sku":"A","order":7}"#,
+                r#"{"customer.tags":"vip","items.qty":2,"items.sku":"B","order":7}"#,
+                r#"{"items":null,"order":8}"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flatten_rejects_colliding_fields_and_bad_options() {
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "input".to_string(),
+            DataFormat::RecordBatch(vec![record(json!({"a.b": 1, "a": {"b": 2}}))]),
+        );
+        let err = match FlattenTransform.execute(inputs, &HashMap::new()).await {
+            Ok(_) => panic!("expected colliding fields to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(
+            err,
+            "Flatten: field 'a.b' appears both nested and flat in a record"
+        );
+
+        let mut config = HashMap::new();
+        config.insert("max_depth".to_string(), toml::Value::Integer(0));
+        assert!(FlattenTransform.validate_config(&config).await.is_err());
+    }
+}
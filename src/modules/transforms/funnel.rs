@@ -0,0 +1,413 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::epoch_millis;
+use super::rolling_time::parse_window;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Counts how many users pass through an ordered list of steps
+pub struct FunnelTransform;
+
+struct Options {
+    user: String,
+    event: String,
+    steps: Vec<String>,
+    timestamp: String,
+    /// Longest time allowed between two consecutive steps, in milliseconds
+    window_ms: Option<i64>,
+}
+
+fn column_option(config: &HashMap<String, toml::Value>, name: &str) -> Result<String> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) => Ok(s.clone()),
+        Some(_) => anyhow::bail!("'{}' must be a string", name),
+        None => anyhow::bail!("Missing required '{}' configuration", name),
+    }
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let steps: Vec<String> = match config.get("steps") {
+        Some(toml::Value::Array(arr)) => arr
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| anyhow::anyhow!("'steps' must contain only strings"))
+            })
+            .collect::<Result<_>>()?,
+        Some(_) => anyhow::bail!("'steps' must be an array of event names"),
+        None => anyhow::bail!("Missing required 'steps' configuration"),
+    };
+    if steps.len() < 2 {
+        anyhow::bail!("'steps' must list at least two events");
+    }
+
+    let window_ms = match config.get("window") {
+        None => None,
+        Some(toml::Value::String(s)) => {
+            let window = parse_window(s)?;
+            if !window.is_constant_duration(None) {
+                anyhow::bail!(
+                    "Invalid window: '{}'. Calendar months and years are not supported",
+                    s
+                );
+            }
+            Some(window.duration_ms())
+        }
+        Some(_) => anyhow::bail!("'window' must be a string"),
+    };
+
+    Ok(Options {
+        user: column_option(config, "user")?,
+        event: column_option(config, "event")?,
+        steps,
+        timestamp: column_option(config, "timestamp")?,
+        window_ms,
+    })
+}
+
+fn string_column(df: &DataFrame, name: &str) -> Result<StringChunked> {
+    let column = df
+        .column(name)
+        .map_err(|_| anyhow::anyhow!("Funnel: column '{}' not found", name))?;
+    Ok(column.cast(&DataType::String)?.str()?.clone())
+}
+
+/// Number of steps each user completed, in order
+///
+/// `reached[i]` holds the latest time the user could have completed step
+/// `i`: a later completion leaves the most room for the next step within
+/// the window, so keeping only the latest one is enough.
+fn steps_reached(events: &[(i64, &str)], steps: &[String], window_ms: Option<i64>) -> usize {
+    let mut reached: Vec<Option<i64>> = vec![None; steps.len()];
+    for &(time, event) in events {
+        // Later steps first, so one event cannot complete two steps in a row
+        for i in (0..steps.len()).rev() {
+            if steps[i] != event {
+                continue;
+            }
+            let allowed = if i == 0 {
+                true
+            } else {
+                match reached[i - 1] {
+                    Some(previous) => window_ms.is_none_or(|w| time - previous <= w),
+                    None => false,
+                }
+            };
+            if allowed {
+                reached[i] = Some(time);
+            }
+        }
+    }
+    reached
+        .iter()
+        .rposition(|time| time.is_some())
+        .map_or(0, |i| i + 1)
+}
+
+fn ratio(numerator: u32, denominator: u32) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+#[async_trait]
+impl Stage for FunnelTransform {
+    fn name(&self) -> &str {
+        "funnel.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "user".to_string(),
+            toml::Value::String("user_id".to_string()),
+        );
+        example1.insert(
+            "event".to_string(),
+            toml::Value::String("event_name".to_string()),
+        );
+        example1.insert(
+            "timestamp".to_string(),
+            toml::Value::String("event_time".to_string()),
+        );
+        example1.insert(
+            "steps".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("view_product".to_string()),
+                toml::Value::String("add_to_cart".to_string()),
+                toml::Value::String("checkout".to_string()),
+            ]),
+        );
+
+        let mut example2 = example1.clone();
+        example2.insert("window".to_string(), toml::Value::String("30m".to_string()));
+
+        StageMetadata::builder("funnel.apply", StageCategory::Transform)
+            .description("Count users through ordered steps and their conversion rates")
+            .long_description(
+                "Follows each user's events in timestamp order and finds how far they got \
+                through 'steps': a user reaches a step after reaching the one before it. Other \
+                events in between are ignored. With 'window', each step must follow the \
+                previous one within that time. Outputs one row per step with 'step', 'users' \
+                (users who reached it), 'conversion_rate' (relative to the first step) and \
+                'step_conversion_rate' (relative to the previous step). The timestamp column may \
+                hold datetimes, epoch milliseconds or ISO 8601 strings; rows with a null user or \
+                event are skipped.",
+            )
+            .parameter(ConfigParameter::required(
+                "user",
+                ParameterType::String,
+                "Column identifying the user",
+            ))
+            .parameter(ConfigParameter::required(
+                "event",
+                ParameterType::String,
+                "Column holding the event name",
+            ))
+            .parameter(ConfigParameter::required(
+                "steps",
+                ParameterType::Array,
+                "Event names of the funnel steps, in order (at least two)",
+            ))
+            .parameter(ConfigParameter::required(
+                "timestamp",
+                ParameterType::String,
+                "Column holding the time of each event",
+            ))
+            .parameter(ConfigParameter::optional(
+                "window",
+                ParameterType::String,
+                "none",
+                "Longest time between two consecutive steps, such as 30m or 1d",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Checkout funnel",
+                example1,
+                Some("How many viewers added to cart and then checked out"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Checkout funnel within 30 minutes",
+                example2,
+                Some("Each step must follow the previous one within 30 minutes"),
+            ))
+            .tag("funnel")
+            .tag("analytics")
+            .tag("conversion")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Funnel transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        let users = string_column(&df, &options.user)?;
+        let events = string_column(&df, &options.event)?;
+        let times = epoch_millis(&df, &options.timestamp, "Funnel")?;
+
+        let mut by_user: HashMap<&str, Vec<(i64, &str)>> = HashMap::new();
+        for ((user, event), time) in users.into_iter().zip(&events).zip(times) {
+            if let (Some(user), Some(event)) = (user, event) {
+                by_user.entry(user).or_default().push((time, event));
+            }
+        }
+
+        let mut counts = vec![0u32; options.steps.len()];
+        for events in by_user.values_mut() {
+            // Stable, so events with the same timestamp keep their row order
+            events.sort_by_key(|(time, _)| *time);
+            let reached = steps_reached(events, &options.steps, options.window_ms);
+            for count in &mut counts[..reached] {
+                *count += 1;
+            }
+        }
+
+        let conversion: Vec<Option<f64>> = counts.iter().map(|&n| ratio(n, counts[0])).collect();
+        let step_conversion: Vec<Option<f64>> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| ratio(n, counts[i.saturating_sub(1)]))
+            .collect();
+
+        let result = df! {
+            "step" => &options.steps,
+            "users" => &counts,
+            "conversion_rate" => &conversion,
+            "step_conversion_rate" => &step_conversion,
+        }?;
+
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ten users: u1-u6 view, u1-u4 add to cart, u1-u2 check out, plus noise
+    fn events() -> DataFrame {
+        let rows: &[(&str, &str, &str)] = &[
+            ("u1", "view", "2024-01-01T10:00:00Z"),
+            ("u1", "cart", "2024-01-01T10:05:00Z"),
+            ("u1", "search", "2024-01-01T10:06:00Z"),
+            ("u1", "buy", "2024-01-01T10:10:00Z"),
+            // Rows out of order: u2 still converts in time order
+            ("u2", "buy", "2024-01-01T12:30:00Z"),
+            ("u2", "view", "2024-01-01T12:00:00Z"),
+            ("u2", "cart", "2024-01-01T12:20:00Z"),
+            ("u3", "view", "2024-01-01T09:00:00Z"),
+            ("u3", "cart", "2024-01-01T09:01:00Z"),
+            // u4 adds to cart after a two-hour gap
+            ("u4", "view", "2024-01-01T08:00:00Z"),
+            ("u4", "cart", "2024-01-01T10:00:00Z"),
+            ("u5", "view", "2024-01-01T11:00:00Z"),
+            ("u6", "view", "2024-01-01T11:00:00Z"),
+            ("u6", "view", "2024-01-01T11:30:00Z"),
+            // Buying before viewing does not count
+            ("u7", "buy", "2024-01-01T10:00:00Z"),
+            ("u7", "cart", "2024-01-01T10:01:00Z"),
+        ];
+        df! {
+            "user_id" => rows.iter().map(|r| r.0).collect::<Vec<_>>(),
+            "event" => rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+            "ts" => rows.iter().map(|r| r.2).collect::<Vec<_>>(),
+        }
+        .unwrap()
+    }
+
+    fn config(window: Option<&str>) -> HashMap<String, toml::Value> {
+        let mut config: HashMap<String, toml::Value> = toml::from_str(
+            r#"
+user = "user_id"
+event = "event"
+timestamp = "ts"
+steps = ["view", "cart", "buy"]
+"#,
+        )
+        .unwrap();
+        if let Some(window) = window {
+            config.insert(
+                "window".to_string(),
+                toml::Value::String(window.to_string()),
+            );
+        }
+        config
+    }
+
+    async fn funnel(config: &HashMap<String, toml::Value>) -> DataFrame {
+        let inputs = HashMap::from([("events".to_string(), DataFormat::DataFrame(events()))]);
+        FunnelTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    fn users(df: &DataFrame) -> Vec<u32> {
+        df.column("users")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    fn rates(df: &DataFrame, column: &str) -> Vec<Option<f64>> {
+        df.column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_three_step_funnel() {
+        let df = funnel(&config(None)).await;
+
+        let steps: Vec<&str> = df
+            .column("step")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(steps, vec!["view", "cart", "buy"]);
+        assert_eq!(users(&df), vec![6, 4, 2]);
+        assert_eq!(
+            rates(&df, "conversion_rate"),
+            vec![Some(1.0), Some(4.0 / 6.0), Some(2.0 / 6.0)]
+        );
+        assert_eq!(
+            rates(&df, "step_conversion_rate"),
+            vec![Some(1.0), Some(4.0 / 6.0), Some(0.5)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_window_bounds_each_transition() {
+        // u4's two-hour gap drops out; u2's 20 and 10 minute steps stay in
+        let df = funnel(&config(Some("30m"))).await;
+        assert_eq!(users(&df), vec![6, 3, 2]);
+
+        // Only u1's and u3's transitions are within 5 minutes
+        let df = funnel(&config(Some("5m"))).await;
+        assert_eq!(users(&df), vec![6, 2, 1]);
+    }
+
+    #[test]
+    fn test_later_start_can_complete_funnel() {
+        let steps = vec!["view".to_string(), "buy".to_string()];
+        // The first view is too early, but the second one starts a funnel in time
+        let events = [(0, "view"), (100_000, "view"), (150_000, "buy")];
+        assert_eq!(steps_reached(&events, &steps, Some(60_000)), 2);
+        assert_eq!(steps_reached(&events[..2], &steps, Some(60_000)), 1);
+
+        // A repeated step name needs two separate events
+        let steps = vec!["view".to_string(), "view".to_string()];
+        assert_eq!(steps_reached(&[(0, "view")], &steps, None), 1);
+        assert_eq!(steps_reached(&[(0, "view"), (1, "view")], &steps, None), 2);
+    }
+
+    #[tokio::test]
+    async fn test_funnel_config_errors() {
+        let mut one_step = config(None);
+        one_step.insert(
+            "steps".to_string(),
+            toml::Value::Array(vec![toml::Value::String("view".to_string())]),
+        );
+        assert!(FunnelTransform.validate_config(&one_step).await.is_err());
+        assert!(FunnelTransform
+            .validate_config(&config(Some("1mo")))
+            .await
+            .is_err());
+
+        let mut missing = config(None);
+        missing.insert(
+            "user".to_string(),
+            toml::Value::String("account".to_string()),
+        );
+        let inputs = HashMap::from([("events".to_string(), DataFormat::DataFrame(events()))]);
+        let err = match FunnelTransform.execute(inputs, &missing).await {
+            Ok(_) => panic!("expected a missing column to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(err, "Funnel: column 'account' not found");
+    }
+}
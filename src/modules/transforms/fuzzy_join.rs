@@ -0,0 +1,439 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct FuzzyJoinTransform;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Metric {
+    Levenshtein,
+    JaroWinkler,
+}
+
+impl Metric {
+    pub(crate) fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "levenshtein" => Ok(Metric::Levenshtein),
+            "jaro_winkler" | "jaro-winkler" => Ok(Metric::JaroWinkler),
+            _ => anyhow::bail!(
+                "Unknown similarity metric: '{}'. Supported: levenshtein, jaro_winkler",
+                s
+            ),
+        }
+    }
+
+    /// Similarity between 0.0 (nothing in common) and 1.0 (identical)
+    pub(crate) fn similarity(&self, a: &str, b: &str) -> f64 {
+        match self {
+            Metric::Levenshtein => strsim::normalized_levenshtein(a, b),
+            Metric::JaroWinkler => strsim::jaro_winkler(a, b),
+        }
+    }
+}
+
+pub(crate) fn parse_threshold(config: &HashMap<String, toml::Value>) -> Result<f64> {
+    let threshold = match config.get("threshold") {
+        None => 0.8,
+        Some(toml::Value::Float(f)) => *f,
+        Some(toml::Value::Integer(i)) => *i as f64,
+        Some(_) => anyhow::bail!("'threshold' must be a number between 0 and 1"),
+    };
+    if !(0.0..=1.0).contains(&threshold) {
+        anyhow::bail!("'threshold' must be between 0 and 1, got {}", threshold);
+    }
+    Ok(threshold)
+}
+
+/// Text compared for a key value; null keys never match
+pub(crate) fn key_text(value: Option<&JsonValue>, case_sensitive: bool) -> Option<String> {
+    let text = match value? {
+        JsonValue::Null => return None,
+        JsonValue::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+    };
+    Some(if case_sensitive {
+        text
+    } else {
+        text.to_lowercase()
+    })
+}
+
+#[async_trait]
+impl Stage for FuzzyJoinTransform {
+    fn name(&self) -> &str {
+        "fuzzy_join.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "left".to_string(),
+            toml::Value::String("crm_companies".to_string()),
+        );
+        example1.insert(
+            "right".to_string(),
+            toml::Value::String("billing_companies".to_string()),
+        );
+        example1.insert("on".to_string(), toml::Value::String("name".to_string()));
+        example1.insert("threshold".to_string(), toml::Value::Float(0.85));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "on".to_string(),
+            toml::Value::String("customer_name".to_string()),
+        );
+        example2.insert(
+            "metric".to_string(),
+            toml::Value::String("jaro_winkler".to_string()),
+        );
+        example2.insert(
+            "blocking_key".to_string(),
+            toml::Value::String("postal_code".to_string()),
+        );
+
+        StageMetadata::builder("fuzzy_join.apply", StageCategory::Transform)
+            .description("Join two inputs on approximately matching key values")
+            .long_description(
+                "Compares the 'on' column of every left row with every right row and emits a joined \
+                row for each pair whose similarity reaches 'threshold', with the score in \
+                'score_column'. Keys are trimmed and, unless 'case_sensitive' is set, lowercased \
+                before comparison; null keys never match. Right columns that clash with left columns \
+                get the '_right' suffix. Comparing all pairs is O(n*m), so set 'blocking_key' to \
+                only compare rows that share the same value in that column. The 'left' and 'right' \
+                options name the input stages; they default to inputs called 'left' and 'right'.",
+            )
+            .parameter(ConfigParameter::required(
+                "on",
+                ParameterType::String,
+                "Key column compared in both inputs",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "metric",
+                    ParameterType::String,
+                    "levenshtein",
+                    "Similarity metric",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "levenshtein",
+                    "jaro_winkler",
+                ])),
+            )
+            .parameter(ConfigParameter::optional(
+                "threshold",
+                ParameterType::Float,
+                "0.8",
+                "Minimum similarity (0 to 1) for a pair to match",
+            ))
+            .parameter(ConfigParameter::optional(
+                "blocking_key",
+                ParameterType::String,
+                "none",
+                "Column that must be equal in both rows before keys are compared",
+            ))
+            .parameter(ConfigParameter::optional(
+                "case_sensitive",
+                ParameterType::Boolean,
+                "false",
+                "Compare keys without lowercasing them",
+            ))
+            .parameter(ConfigParameter::optional(
+                "score_column",
+                ParameterType::String,
+                "similarity",
+                "Column receiving the similarity score",
+            ))
+            .parameter(ConfigParameter::optional(
+                "left",
+                ParameterType::String,
+                "left",
+                "Input stage providing the left rows",
+            ))
+            .parameter(ConfigParameter::optional(
+                "right",
+                ParameterType::String,
+                "right",
+                "Input stage providing the right rows",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Match company names",
+                example1,
+                Some("Pair CRM and billing companies whose names are at least 85% similar"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Blocked matching",
+                example2,
+                Some("Only compare customers within the same postal code"),
+            ))
+            .tag("join")
+            .tag("fuzzy")
+            .tag("matching")
+            .tag("dedup")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        mut inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let on = config
+            .get("on")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required 'on' configuration"))?;
+        let metric = Metric::from_str(
+            config
+                .get("metric")
+                .and_then(|v| v.as_str())
+                .unwrap_or("levenshtein"),
+        )?;
+        let threshold = parse_threshold(config)?;
+        let blocking_key = config.get("blocking_key").and_then(|v| v.as_str());
+        let case_sensitive = config
+            .get("case_sensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let score_column = config
+            .get("score_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or("similarity");
+        let left_name = config
+            .get("left")
+            .and_then(|v| v.as_str())
+            .unwrap_or("left");
+        let right_name = config
+            .get("right")
+            .and_then(|v| v.as_str())
+            .unwrap_or("right");
+
+        let mut available: Vec<&String> = inputs.keys().collect();
+        available.sort();
+        let available = format!("{:?}", available);
+
+        let left = inputs.remove(left_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Fuzzy join transform: left input '{}' not found (available inputs: {})",
+                left_name,
+                available
+            )
+        })?;
+        let right = inputs.remove(right_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Fuzzy join transform: right input '{}' not found (available inputs: {})",
+                right_name,
+                available
+            )
+        })?;
+
+        let left_rows = left.as_record_batch()?;
+        let right_rows = right.as_record_batch()?;
+
+        // Group right rows by blocking value so each left row only sees its block
+        let mut blocks: HashMap<String, Vec<(String, &HashMap<String, JsonValue>)>> =
+            HashMap::new();
+        for row in &right_rows {
+            let Some(key) = key_text(row.get(on), case_sensitive) else {
+                continue;
+            };
+            let block = match blocking_key {
+                Some(column) => serde_json::to_string(row.get(column).unwrap_or(&JsonValue::Null))?,
+                None => String::new(),
+            };
+            blocks.entry(block).or_default().push((key, row));
+        }
+
+        let mut joined = Vec::new();
+        for left_row in &left_rows {
+            let Some(left_key) = key_text(left_row.get(on), case_sensitive) else {
+                continue;
+            };
+            let block = match blocking_key {
+                Some(column) => {
+                    serde_json::to_string(left_row.get(column).unwrap_or(&JsonValue::Null))?
+                }
+                None => String::new(),
+            };
+            let Some(candidates) = blocks.get(&block) else {
+                continue;
+            };
+
+            let mut matches: Vec<(f64, &HashMap<String, JsonValue>)> = candidates
+                .iter()
+                .map(|(right_key, row)| (metric.similarity(&left_key, right_key), *row))
+                .filter(|(score, _)| *score >= threshold)
+                .collect();
+            // Best match first for each left row
+            matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+            for (score, right_row) in matches {
+                let mut row = left_row.clone();
+                for (field, value) in right_row {
+                    if row.contains_key(field) {
+                        row.insert(format!("{}_right", field), value.clone());
+                    } else {
+                        row.insert(field.clone(), value.clone());
+                    }
+                }
+                row.insert(score_column.to_string(), JsonValue::from(score));
+                joined.push(row);
+            }
+        }
+
+        Ok(DataFormat::RecordBatch(joined))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if config.get("on").and_then(|v| v.as_str()).is_none() {
+            anyhow::bail!("Missing required 'on' configuration");
+        }
+
+        if let Some(metric) = config.get("metric").and_then(|v| v.as_str()) {
+            Metric::from_str(metric)?;
+        }
+
+        parse_threshold(config)?;
+
+        for option in ["left", "right", "blocking_key", "score_column"] {
+            if let Some(value) = config.get(option) {
+                if value.as_str().is_none() {
+                    anyhow::bail!("'{}' must be a string", option);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(fields: &[(&str, JsonValue)]) -> HashMap<String, JsonValue> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    async fn run_join(
+        left: Vec<HashMap<String, JsonValue>>,
+        right: Vec<HashMap<String, JsonValue>>,
+        config: HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let mut inputs = HashMap::new();
+        inputs.insert("left".to_string(), DataFormat::RecordBatch(left));
+        inputs.insert("right".to_string(), DataFormat::RecordBatch(right));
+
+        FuzzyJoinTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    fn on_name() -> HashMap<String, toml::Value> {
+        HashMap::from([("on".to_string(), toml::Value::String("name".to_string()))])
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_join_near_duplicates_match() {
+        let left = vec![
+            record(&[("id", json!(1)), ("name", json!("Acme Corporation"))]),
+            record(&[("id", json!(2)), ("name", json!("Globex"))]),
+            record(&[("id", json!(3)), ("name", JsonValue::Null)]),
+        ];
+        let right = vec![
+            record(&[("id", json!("b-1")), ("name", json!("ACME Corporatoin"))]),
+            record(&[("id", json!("b-2")), ("name", json!("Initech"))]),
+        ];
+
+        let output = run_join(left, right, on_name()).await;
+
+        // Only the typo/casing variant matches
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["id"], json!(1));
+        assert_eq!(output[0]["id_right"], json!("b-1"));
+        assert_eq!(output[0]["name_right"], json!("ACME Corporatoin"));
+        let score = output[0]["similarity"].as_f64().unwrap();
+        assert!((0.8..1.0).contains(&score));
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_join_jaro_winkler_and_threshold() {
+        let left = vec![record(&[("name", json!("Jonathan"))])];
+        let right = vec![
+            record(&[("name", json!("Jonathon"))]),
+            record(&[("name", json!("Margaret"))]),
+        ];
+
+        let mut config = on_name();
+        config.insert(
+            "metric".to_string(),
+            toml::Value::String("jaro_winkler".to_string()),
+        );
+        config.insert("threshold".to_string(), toml::Value::Float(0.9));
+
+        let output = run_join(left.clone(), right.clone(), config.clone()).await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["name_right"], json!("Jonathon"));
+
+        // Nothing is similar enough at a strict threshold
+        config.insert("threshold".to_string(), toml::Value::Float(0.99));
+        assert!(run_join(left, right, config).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_join_blocking_key() {
+        let left = vec![
+            record(&[("name", json!("Smith & Co")), ("zip", json!("10001"))]),
+            record(&[("name", json!("Smith & Co")), ("zip", json!("94105"))]),
+        ];
+        let right = vec![record(&[
+            ("name", json!("Smith and Co")),
+            ("zip", json!("10001")),
+        ])];
+
+        let mut config = on_name();
+        config.insert("threshold".to_string(), toml::Value::Float(0.7));
+        config.insert(
+            "blocking_key".to_string(),
+            toml::Value::String("zip".to_string()),
+        );
+
+        let output = run_join(left, right, config).await;
+
+        // The second left row is in another block and never compared
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["zip"], json!("10001"));
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_join_validation() {
+        let transform = FuzzyJoinTransform;
+
+        assert!(transform.validate_config(&HashMap::new()).await.is_err());
+        assert!(transform.validate_config(&on_name()).await.is_ok());
+
+        let mut config = on_name();
+        config.insert("threshold".to_string(), toml::Value::Float(1.5));
+        assert!(transform.validate_config(&config).await.is_err());
+
+        let mut config = on_name();
+        config.insert(
+            "metric".to_string(),
+            toml::Value::String("soundex".to_string()),
+        );
+        assert!(transform.validate_config(&config).await.is_err());
+    }
+}
@@ -0,0 +1,544 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt as FuturesStreamExt;
+use handlebars::Handlebars;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+use super::common::string_option;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+use crate::modules::transforms::http_fetch::request_headers;
+use crate::utils::http::{redact_url, HttpContext};
+
+/// Geocode Transform
+/// Looks up each row's address on a geocoding HTTP endpoint and adds lat/lon/components
+pub struct GeocodeTransform {
+    /// Successful responses by request URL, kept for the lifetime of the stage
+    cache: Mutex<HashMap<String, JsonValue>>,
+}
+
+impl Default for GeocodeTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeocodeTransform {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+struct Options {
+    address_column: String,
+    url: String,
+    concurrency: usize,
+    lat_path: String,
+    lon_path: String,
+    components_path: String,
+    headers: HashMap<String, String>,
+    http: HttpContext,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let concurrency = match config.get("concurrency") {
+        Some(toml::Value::Integer(n)) if *n > 0 => *n as usize,
+        Some(_) => anyhow::bail!("'concurrency' must be a positive integer"),
+        None => DEFAULT_CONCURRENCY,
+    };
+
+    let required = |name: &str| -> Result<String> {
+        string_option(config, name)?
+            .ok_or_else(|| anyhow::anyhow!("Missing required '{}' configuration", name))
+    };
+    let path = |name: &str, default: &str| -> Result<String> {
+        Ok(string_option(config, name)?.unwrap_or_else(|| default.to_string()))
+    };
+
+    Ok(Options {
+        address_column: required("address_column")?,
+        url: required("url")?,
+        concurrency,
+        lat_path: path("lat_path", "lat")?,
+        lon_path: path("lon_path", "lon")?,
+        components_path: path("components_path", "address")?,
+        headers: request_headers(config)?,
+        http: HttpContext::from_config(config)?,
+    })
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn address_text(record: &HashMap<String, JsonValue>, column: &str) -> Option<String> {
+    match record.get(column) {
+        Some(JsonValue::String(s)) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Some(JsonValue::Number(n)) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Follow a dotted path into `value`; numeric segments index arrays
+fn lookup<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    path.split('.')
+        .try_fold(value, |current, segment| match current {
+            JsonValue::Object(map) => map.get(segment),
+            JsonValue::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+/// Coordinates arrive as numbers or numeric strings depending on the provider
+fn coordinate(value: Option<&JsonValue>) -> JsonValue {
+    let parsed = match value {
+        Some(JsonValue::Number(n)) => n.as_f64(),
+        Some(JsonValue::String(s)) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    parsed.map_or(JsonValue::Null, JsonValue::from)
+}
+
+impl Options {
+    /// Render the request URL for a record; `{{ address }}` is the URL-encoded address
+    fn request_url(
+        &self,
+        handlebars: &Handlebars,
+        record: &HashMap<String, JsonValue>,
+    ) -> Result<Option<String>> {
+        let Some(address) = address_text(record, &self.address_column) else {
+            return Ok(None);
+        };
+        let mut context: serde_json::Map<String, JsonValue> =
+            record.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        context.insert(
+            "address".to_string(),
+            JsonValue::String(encode_component(&address)),
+        );
+        let url = handlebars
+            .render_template(&self.url, &context)
+            .map_err(|e| anyhow::anyhow!("Failed to render geocode URL template: {}", e))?;
+        Ok(Some(url))
+    }
+
+    /// lat, lon and components from a response; list responses use their first result
+    fn location(&self, response: Option<&JsonValue>) -> [JsonValue; 3] {
+        let result = match response {
+            Some(JsonValue::Array(results)) => results.first(),
+            other => other,
+        };
+        match result {
+            Some(result) => [
+                coordinate(lookup(result, &self.lat_path)),
+                coordinate(lookup(result, &self.lon_path)),
+                lookup(result, &self.components_path)
+                    .cloned()
+                    .unwrap_or(JsonValue::Null),
+            ],
+            None => [JsonValue::Null, JsonValue::Null, JsonValue::Null],
+        }
+    }
+}
+
+impl GeocodeTransform {
+    async fn fetch(&self, url: &str, options: &Options) -> Result<JsonValue> {
+        let response = options
+            .http
+            .send(|client| {
+                let mut request = client.get(url);
+                for (key, value) in &options.headers {
+                    request = request.header(key, value);
+                }
+                request
+            })
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Geocoding request failed with status: {}", status);
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn geocode(&self, records: RecordBatch, options: &Options) -> Result<RecordBatch> {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(handlebars::no_escape);
+
+        let urls = records
+            .iter()
+            .map(|record| options.request_url(&handlebars, record))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Each distinct address is requested once, and never again once cached
+        let pending: Vec<String> = {
+            let cache = self.cache.lock().unwrap();
+            let mut seen = HashSet::new();
+            urls.iter()
+                .flatten()
+                .filter(|url| !cache.contains_key(*url) && seen.insert(url.as_str()))
+                .cloned()
+                .collect()
+        };
+        debug!(
+            "Geocoding {} record(s) with {} new request(s)",
+            records.len(),
+            pending.len()
+        );
+
+        let responses: Vec<(String, Result<JsonValue>)> = futures::stream::iter(pending)
+            .map(|url| async move {
+                let response = self.fetch(&url, options).await;
+                (url, response)
+            })
+            .buffer_unordered(options.concurrency)
+            .collect()
+            .await;
+
+        let mut cache = self.cache.lock().unwrap();
+        for (url, response) in responses {
+            match response {
+                Ok(body) => {
+                    cache.insert(url, body);
+                }
+                // Failures are not cached so a later run retries them
+                Err(e) => warn!("Geocoding request to {} failed: {}", redact_url(&url), e),
+            }
+        }
+
+        Ok(records
+            .into_iter()
+            .zip(urls)
+            .map(|(mut record, url)| {
+                let response = url.as_ref().and_then(|url| cache.get(url));
+                let [lat, lon, components] = options.location(response);
+                record.insert("lat".to_string(), lat);
+                record.insert("lon".to_string(), lon);
+                record.insert("components".to_string(), components);
+                record
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Stage for GeocodeTransform {
+    fn name(&self) -> &str {
+        "geocode.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "address_column".to_string(),
+            toml::Value::String("shipping_address".to_string()),
+        );
+        example1.insert(
+            "url".to_string(),
+            toml::Value::String(
+                "https://nominatim.openstreetmap.org/search?q={{ address }}&format=json&addressdetails=1&limit=1"
+                    .to_string(),
+            ),
+        );
+        let mut headers = toml::map::Map::new();
+        headers.insert(
+            "User-Agent".to_string(),
+            toml::Value::String("conveyor-pipeline".to_string()),
+        );
+        example1.insert("headers".to_string(), toml::Value::Table(headers));
+        example1.insert("concurrency".to_string(), toml::Value::Integer(1));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "address_column".to_string(),
+            toml::Value::String("address".to_string()),
+        );
+        example2.insert(
+            "url".to_string(),
+            toml::Value::String(
+                "https://geocoder.example.com/v1/lookup?text={{ address }}&country={{ country }}"
+                    .to_string(),
+            ),
+        );
+        example2.insert(
+            "lat_path".to_string(),
+            toml::Value::String("geometry.location.lat".to_string()),
+        );
+        example2.insert(
+            "lon_path".to_string(),
+            toml::Value::String("geometry.location.lng".to_string()),
+        );
+        example2.insert(
+            "components_path".to_string(),
+            toml::Value::String("address_components".to_string()),
+        );
+        example2.insert(
+            "api_key_env".to_string(),
+            toml::Value::String("GEOCODER_API_KEY".to_string()),
+        );
+
+        StageMetadata::builder("geocode.apply", StageCategory::Transform)
+            .description("Geocode addresses through an HTTP endpoint into lat/lon/components")
+            .long_description(
+                "Sends a GET request per distinct address to a geocoding endpoint and adds 'lat', \
+                'lon' and 'components' columns. The URL is a Handlebars template over the row, in \
+                which {{ address }} is the URL-encoded value of 'address_column'. Responses are \
+                cached by URL for the lifetime of the stage, so a repeated address is requested once, \
+                whether it repeats within a batch or in a later execution of the stage. If the \
+                response is a list, the first result is used; the *_path options are dotted paths \
+                into it, and coordinates given as strings are parsed. Empty addresses, failed \
+                requests and empty results produce nulls, and failures are retried on a later run.",
+            )
+            .parameter(ConfigParameter::required(
+                "address_column",
+                ParameterType::String,
+                "Column holding the freeform address",
+            ))
+            .parameter(ConfigParameter::required(
+                "url",
+                ParameterType::String,
+                "Request URL template; {{ address }} is the URL-encoded address",
+            ))
+            .parameter(ConfigParameter::optional(
+                "concurrency",
+                ParameterType::Integer,
+                "4",
+                "Maximum number of requests in flight",
+            ))
+            .parameter(ConfigParameter::optional(
+                "lat_path",
+                ParameterType::String,
+                "lat",
+                "Dotted path of the latitude in each result",
+            ))
+            .parameter(ConfigParameter::optional(
+                "lon_path",
+                ParameterType::String,
+                "lon",
+                "Dotted path of the longitude in each result",
+            ))
+            .parameter(ConfigParameter::optional(
+                "components_path",
+                ParameterType::String,
+                "address",
+                "Dotted path of the structured address components in each result",
+            ))
+            .parameter(ConfigParameter::optional(
+                "headers",
+                ParameterType::Object,
+                "none",
+                "Map of HTTP headers to include in requests",
+            ))
+            .parameter(ConfigParameter::optional(
+                "api_key_env",
+                ParameterType::String,
+                "none",
+                "Environment variable holding the API key, resolved at execution",
+            ))
+            .parameter(ConfigParameter::optional(
+                "api_key_header",
+                ParameterType::String,
+                "Authorization",
+                "Header carrying the API key (sent as 'Bearer <key>' for Authorization)",
+            ))
+            .parameters(crate::utils::http::parameters())
+            .example(crate::core::metadata::ConfigExample::new(
+                "OpenStreetMap Nominatim",
+                example1,
+                Some("One request at a time, as the public Nominatim usage policy requires"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Custom geocoder with nested coordinates",
+                example2,
+                Some("Read coordinates from geometry.location and pass the row's country along"),
+            ))
+            .tag("geocode")
+            .tag("address")
+            .tag("http")
+            .tag("enrich")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Geocode transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let records = data.as_record_batch()?;
+        Ok(DataFormat::RecordBatch(
+            self.geocode(records, &options).await?,
+        ))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        let options = parse_options(config)?;
+        Handlebars::new()
+            .render_template(&options.url, &JsonValue::Object(Default::default()))
+            .map_err(|e| anyhow::anyhow!("Invalid geocode URL template: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Answer every request with a canned geocoding result for its `q` parameter
+    async fn serve_geocoder(requests: Arc<AtomicUsize>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                requests.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("");
+
+                let body = if path.contains("q=10%20Downing%20St") {
+                    json!([{"lat": "51.5034", "lon": "-0.1276", "address": {"city": "London"}}])
+                } else if path.contains("q=Unter%20den%20Linden") {
+                    json!([{"lat": 52.517, "lon": 13.389, "address": {"city": "Berlin"}}])
+                } else {
+                    json!([])
+                }
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}/search?q={{{{ address }}}}&format=json", addr)
+    }
+
+    fn config(url: String) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            ("url".to_string(), toml::Value::String(url)),
+            (
+                "address_column".to_string(),
+                toml::Value::String("address".to_string()),
+            ),
+        ])
+    }
+
+    fn addresses(values: &[JsonValue]) -> DataFormat {
+        DataFormat::RecordBatch(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, address)| {
+                    HashMap::from([
+                        ("id".to_string(), json!(i)),
+                        ("address".to_string(), address.clone()),
+                    ])
+                })
+                .collect(),
+        )
+    }
+
+    async fn run(
+        stage: &GeocodeTransform,
+        data: DataFormat,
+        config: &HashMap<String, toml::Value>,
+    ) -> RecordBatch {
+        stage
+            .execute(HashMap::from([("input".to_string(), data)]), config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_geocode_writes_columns_and_caches_by_address() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let config = config(serve_geocoder(Arc::clone(&requests)).await);
+        let stage = GeocodeTransform::new();
+
+        let records = run(
+            &stage,
+            addresses(&[
+                json!("10 Downing St"),
+                json!("Unter den Linden"),
+                json!("10 Downing St"),
+                json!("Nowhere"),
+                JsonValue::Null,
+            ]),
+            &config,
+        )
+        .await;
+
+        // Three distinct addresses; the duplicate and the null add no requests
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(records[0]["lat"], json!(51.5034));
+        assert_eq!(records[0]["lon"], json!(-0.1276));
+        assert_eq!(records[0]["components"], json!({"city": "London"}));
+        assert_eq!(records[1]["lat"], json!(52.517));
+        assert_eq!(records[1]["components"]["city"], "Berlin");
+        assert_eq!(records[2]["lat"], records[0]["lat"]);
+        assert_eq!(records[3]["lat"], JsonValue::Null);
+        assert_eq!(records[3]["components"], JsonValue::Null);
+        assert_eq!(records[4]["lon"], JsonValue::Null);
+        assert_eq!(records[4]["id"], 4);
+
+        // A later batch is served from the cache
+        let again = run(&stage, addresses(&[json!("Unter den Linden")]), &config).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(again[0]["lon"], json!(13.389));
+    }
+
+    #[tokio::test]
+    async fn test_geocode_config_validation() {
+        let stage = GeocodeTransform::new();
+        let mut valid = config("http://localhost/search?q={{ address }}".to_string());
+        assert!(stage.validate_config(&valid).await.is_ok());
+
+        valid.insert("concurrency".to_string(), toml::Value::Integer(0));
+        let err = stage.validate_config(&valid).await.unwrap_err();
+        assert_eq!(err.to_string(), "'concurrency' must be a positive integer");
+
+        let mut missing = config("http://localhost/search".to_string());
+        missing.remove("address_column");
+        let err = stage.validate_config(&missing).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing required 'address_column' configuration"
+        );
+
+        assert_eq!(encode_component("a b&c/é"), "a%20b%26c%2F%C3%A9");
+    }
+}
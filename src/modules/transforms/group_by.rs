@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use polars::prelude::*;
 use std::collections::HashMap;
 
+use crate::core::memory;
 use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
 use crate::core::stage::Stage;
 use crate::core::traits::DataFormat;
@@ -159,12 +160,12 @@ impl Stage for GroupByTransform {
             agg_exprs.push(expr);
         }
 
-        // Use LazyFrame for groupby
-        let result = df
-            .lazy()
-            .group_by(group_columns.iter().map(col).collect::<Vec<_>>())
-            .agg(agg_exprs)
-            .collect()?;
+        // Use LazyFrame for groupby; streams when the stage has a memory limit
+        let result = memory::collect(
+            df.lazy()
+                .group_by(group_columns.iter().map(col).collect::<Vec<_>>())
+                .agg(agg_exprs),
+        )?;
 
         Ok(DataFormat::DataFrame(result))
     }
@@ -2,10 +2,8 @@ use anyhow::Result;
 use async_trait::async_trait;
 use futures::StreamExt as FuturesStreamExt;
 use handlebars::Handlebars;
-use reqwest::Client;
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
-use std::time::Duration;
 use tokio_stream::Stream;
 use tracing::{debug, info, warn};
 
@@ -14,11 +12,11 @@ use crate::core::metadata::{
 };
 use crate::core::stage::{Stage, StreamStage};
 use crate::core::traits::DataFormat;
+use crate::utils::http::{redact_url, HttpContext};
 
 /// HTTP Fetch Transform
 /// Fetches data from HTTP APIs using input data as context for templated requests
 pub struct HttpFetchTransform {
-    client: Client,
     handlebars: Handlebars<'static>,
 }
 
@@ -30,13 +28,7 @@ impl Default for HttpFetchTransform {
 
 impl HttpFetchTransform {
     pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
         Self {
-            client,
             handlebars: Handlebars::new(),
         }
     }
@@ -136,6 +128,19 @@ impl Stage for HttpFetchTransform {
                 "none",
                 "Map of HTTP headers to include in requests"
             ))
+            .parameter(ConfigParameter::optional(
+                "api_key_env",
+                ParameterType::String,
+                "none",
+                "Environment variable holding this stage's API key, resolved at execution"
+            ))
+            .parameter(ConfigParameter::optional(
+                "api_key_header",
+                ParameterType::String,
+                "Authorization",
+                "Header carrying the API key (sent as 'Bearer <key>' for Authorization)"
+            ))
+            .parameters(crate::utils::http::parameters())
             .example(crate::core::metadata::ConfigExample::new(
                 "Per-row API enrichment",
                 example1,
@@ -187,16 +192,8 @@ impl Stage for HttpFetchTransform {
         let body_template = config.get("body").and_then(|v| v.as_str());
 
         // Get headers
-        let mut headers = HashMap::new();
-        if let Some(headers_config) = config.get("headers") {
-            if let Some(headers_table) = headers_config.as_table() {
-                for (key, value) in headers_table {
-                    if let Some(val_str) = value.as_str() {
-                        headers.insert(key.clone(), val_str.to_string());
-                    }
-                }
-            }
-        }
+        let headers = request_headers(config)?;
+        let http = HttpContext::from_config(config)?;
 
         // Convert input data to records
         let records = data.as_record_batch()?;
@@ -204,6 +201,7 @@ impl Stage for HttpFetchTransform {
         match mode {
             "per_row" => {
                 self.fetch_per_row(
+                    &http,
                     records,
                     url_template,
                     method,
@@ -215,6 +213,7 @@ impl Stage for HttpFetchTransform {
             }
             "batch" => {
                 self.fetch_batch(
+                    &http,
                     records,
                     url_template,
                     method,
@@ -237,6 +236,15 @@ impl Stage for HttpFetchTransform {
             anyhow::bail!("http_fetch requires 'url' field");
         }
 
+        for option in ["api_key_env", "api_key_header"] {
+            if let Some(value) = config.get(option) {
+                match value.as_str() {
+                    Some(v) if !v.is_empty() => {}
+                    _ => anyhow::bail!("'{}' must be a non-empty string", option),
+                }
+            }
+        }
+
         // Validate method if present
         if let Some(method) = config.get("method").and_then(|v| v.as_str()) {
             let valid_methods = ["GET", "POST", "PUT", "PATCH", "DELETE"];
@@ -256,14 +264,16 @@ impl Stage for HttpFetchTransform {
             }
         }
 
-        Ok(())
+        crate::utils::http::validate_config(config)
     }
 }
 
 impl HttpFetchTransform {
     /// Fetch data for each row individually
+    #[allow(clippy::too_many_arguments)]
     async fn fetch_per_row(
         &self,
+        http: &HttpContext,
         records: Vec<HashMap<String, JsonValue>>,
         url_template: &str,
         method: &str,
@@ -278,7 +288,7 @@ impl HttpFetchTransform {
 
             // Render URL template
             let url = self.handlebars.render_template(url_template, record)?;
-            debug!("Rendered URL: {}", url);
+            debug!("Rendered URL: {}", redact_url(&url));
 
             // Render body template if present
             let body = if let Some(template) = body_template {
@@ -288,10 +298,7 @@ impl HttpFetchTransform {
             };
 
             // Make HTTP request
-            match self
-                .make_request(&url, method, body.as_deref(), headers)
-                .await
-            {
+            match make_request(http, &url, method, body.as_deref(), headers).await {
                 Ok(response_data) => {
                     // Clone the original record and add the result
                     let mut new_record = record.clone();
@@ -321,8 +328,10 @@ impl HttpFetchTransform {
     }
 
     /// Fetch data in batch mode (single request with all data)
+    #[allow(clippy::too_many_arguments)]
     async fn fetch_batch(
         &self,
+        http: &HttpContext,
         records: Vec<HashMap<String, JsonValue>>,
         url_template: &str,
         method: &str,
@@ -344,9 +353,7 @@ impl HttpFetchTransform {
         };
 
         // Make single request
-        let response_data = self
-            .make_request(&url, method, body.as_deref(), headers)
-            .await?;
+        let response_data = make_request(http, &url, method, body.as_deref(), headers).await?;
 
         // Add result to all records
         let mut result_records = records.clone();
@@ -356,50 +363,6 @@ impl HttpFetchTransform {
 
         Ok(DataFormat::RecordBatch(result_records))
     }
-
-    /// Make HTTP request
-    async fn make_request(
-        &self,
-        url: &str,
-        method: &str,
-        body: Option<&str>,
-        headers: &HashMap<String, String>,
-    ) -> Result<JsonValue> {
-        let mut request = match method {
-            "GET" => self.client.get(url),
-            "POST" => self.client.post(url),
-            "PUT" => self.client.put(url),
-            "PATCH" => self.client.patch(url),
-            "DELETE" => self.client.delete(url),
-            _ => return Err(anyhow::anyhow!("Unsupported HTTP method: {}", method)),
-        };
-
-        // Add headers
-        for (key, value) in headers {
-            request = request.header(key, value);
-        }
-
-        // Add body if present
-        if let Some(body_str) = body {
-            request = request
-                .header("Content-Type", "application/json")
-                .body(body_str.to_string());
-        }
-
-        // Execute request
-        let response = request.send().await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            anyhow::bail!("HTTP request failed with status: {}", status);
-        }
-
-        // Parse response as JSON
-        let text = response.text().await?;
-        let json: JsonValue = serde_json::from_str(&text).unwrap_or(JsonValue::String(text));
-
-        Ok(json)
-    }
 }
 
 // ============================================================================
@@ -451,24 +414,15 @@ impl StreamStage for HttpFetchTransform {
             .map(|s| s.to_string());
 
         // Get headers
-        let mut headers = HashMap::new();
-        if let Some(headers_config) = config.get("headers") {
-            if let Some(headers_table) = headers_config.as_table() {
-                for (key, value) in headers_table {
-                    if let Some(val_str) = value.as_str() {
-                        headers.insert(key.clone(), val_str.to_string());
-                    }
-                }
-            }
-        }
+        let headers = request_headers(config)?;
 
-        // Clone client for use in async blocks
-        let client = self.client.clone();
+        // Shared for use in async blocks
+        let http = HttpContext::from_config(config)?;
         let handlebars = Handlebars::new();
 
         // Create stream of HTTP requests
         Ok(FuturesStreamExt::map(input, move |record_result| {
-            let client = client.clone();
+            let http = http.clone();
             let handlebars = handlebars.clone();
             let url_template = url_template.clone();
             let method = method.clone();
@@ -484,7 +438,7 @@ impl StreamStage for HttpFetchTransform {
                     .render_template(&url_template, &record)
                     .map_err(|e| anyhow::anyhow!("Failed to render URL template: {}", e))?;
 
-                debug!("Parallel HTTP request to: {}", url);
+                debug!("Parallel HTTP request to: {}", redact_url(&url));
 
                 // Render body template if present
                 let body =
@@ -497,7 +451,7 @@ impl StreamStage for HttpFetchTransform {
                     };
 
                 // Make HTTP request
-                match make_request_static(&client, &url, &method, body.as_deref(), &headers).await {
+                match make_request(&http, &url, &method, body.as_deref(), &headers).await {
                     Ok(response_data) => {
                         // Clone the original record and add the result
                         let mut new_record = record.clone();
@@ -505,7 +459,11 @@ impl StreamStage for HttpFetchTransform {
                         Ok(new_record)
                     }
                     Err(e) => {
-                        warn!("Parallel HTTP request failed for URL {}: {}", url, e);
+                        warn!(
+                            "Parallel HTTP request failed for URL {}: {}",
+                            redact_url(&url),
+                            e
+                        );
                         // Add null result on error
                         let mut new_record = record.clone();
                         new_record.insert(result_field.clone(), JsonValue::Null);
@@ -518,37 +476,78 @@ impl StreamStage for HttpFetchTransform {
     }
 }
 
-/// Static version of make_request for use in async closures
-async fn make_request_static(
-    client: &Client,
+/// Collect the configured headers plus the API key header from `api_key_env`
+///
+/// The key is read from the environment per stage and only ever placed in the
+/// header map, which is never logged.
+pub(crate) fn request_headers(
+    config: &HashMap<String, toml::Value>,
+) -> Result<HashMap<String, String>> {
+    let mut headers = HashMap::new();
+    if let Some(headers_config) = config.get("headers") {
+        if let Some(headers_table) = headers_config.as_table() {
+            for (key, value) in headers_table {
+                if let Some(val_str) = value.as_str() {
+                    headers.insert(key.clone(), val_str.to_string());
+                }
+            }
+        }
+    }
+
+    if let Some(api_key_env) = config.get("api_key_env").and_then(|v| v.as_str()) {
+        let api_key = std::env::var(api_key_env)
+            .map_err(|_| anyhow::anyhow!("Environment variable '{}' not set", api_key_env))?;
+        let header = config
+            .get("api_key_header")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Authorization");
+        let value = if header.eq_ignore_ascii_case("authorization") {
+            format!("Bearer {}", api_key)
+        } else {
+            api_key
+        };
+        headers.insert(header.to_string(), value);
+    }
+
+    Ok(headers)
+}
+
+/// Send one request and parse the response as JSON (falling back to a string)
+async fn make_request(
+    http: &HttpContext,
     url: &str,
     method: &str,
     body: Option<&str>,
     headers: &HashMap<String, String>,
 ) -> Result<JsonValue> {
-    let mut request = match method {
-        "GET" => client.get(url),
-        "POST" => client.post(url),
-        "PUT" => client.put(url),
-        "PATCH" => client.patch(url),
-        "DELETE" => client.delete(url),
+    let method = match method {
+        "GET" => reqwest::Method::GET,
+        "POST" => reqwest::Method::POST,
+        "PUT" => reqwest::Method::PUT,
+        "PATCH" => reqwest::Method::PATCH,
+        "DELETE" => reqwest::Method::DELETE,
         _ => return Err(anyhow::anyhow!("Unsupported HTTP method: {}", method)),
     };
 
-    // Add headers
-    for (key, value) in headers {
-        request = request.header(key, value);
-    }
+    // Execute request, rebuilt for every retry
+    let response = http
+        .send(|client| {
+            let mut request = client.request(method.clone(), url);
 
-    // Add body if present
-    if let Some(body_str) = body {
-        request = request
-            .header("Content-Type", "application/json")
-            .body(body_str.to_string());
-    }
+            // Add headers
+            for (key, value) in headers {
+                request = request.header(key, value);
+            }
 
-    // Execute request
-    let response = request.send().await?;
+            // Add body if present
+            if let Some(body_str) = body {
+                request = request
+                    .header("Content-Type", "application/json")
+                    .body(body_str.to_string());
+            }
+            request
+        })
+        .await?;
     let status = response.status();
 
     if !status.is_success() {
@@ -597,4 +596,39 @@ mod tests {
 
         assert_eq!(url, "https://api.example.com/users/123/posts");
     }
+
+    #[test]
+    fn test_api_key_env_per_stage() {
+        std::env::set_var("CONVEYOR_TEST_HTTP_KEY_A", "key-a");
+        std::env::set_var("CONVEYOR_TEST_HTTP_KEY_B", "key-b");
+
+        let mut stage_a = HashMap::new();
+        stage_a.insert(
+            "api_key_env".to_string(),
+            toml::Value::String("CONVEYOR_TEST_HTTP_KEY_A".to_string()),
+        );
+
+        let mut stage_b = HashMap::new();
+        stage_b.insert(
+            "api_key_env".to_string(),
+            toml::Value::String("CONVEYOR_TEST_HTTP_KEY_B".to_string()),
+        );
+        stage_b.insert(
+            "api_key_header".to_string(),
+            toml::Value::String("X-API-Key".to_string()),
+        );
+
+        let headers_a = request_headers(&stage_a).unwrap();
+        assert_eq!(headers_a["Authorization"], "Bearer key-a");
+
+        let headers_b = request_headers(&stage_b).unwrap();
+        assert_eq!(headers_b["X-API-Key"], "key-b");
+        assert!(!headers_b.contains_key("Authorization"));
+
+        std::env::remove_var("CONVEYOR_TEST_HTTP_KEY_A");
+        std::env::remove_var("CONVEYOR_TEST_HTTP_KEY_B");
+
+        let err = request_headers(&stage_a).unwrap_err().to_string();
+        assert!(err.contains("CONVEYOR_TEST_HTTP_KEY_A"));
+    }
 }
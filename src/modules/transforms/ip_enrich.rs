@@ -0,0 +1,444 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use maxminddb::{MaxMindDBError, Reader};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::common::string_option;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// IP Enrich Transform
+/// Adds country, city and ASN columns for an IP column from a local MaxMind database
+pub struct IpEnrichTransform {
+    /// Opened databases by path, read into memory once for the stage's lifetime
+    readers: Mutex<HashMap<PathBuf, Arc<Reader<Vec<u8>>>>>,
+}
+
+impl Default for IpEnrichTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IpEnrichTransform {
+    pub fn new() -> Self {
+        Self {
+            readers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn reader(&self, path: &str) -> Result<Arc<Reader<Vec<u8>>>> {
+        let path = PathBuf::from(path);
+        let mut readers = self.readers.lock().unwrap();
+        if let Some(reader) = readers.get(&path) {
+            return Ok(Arc::clone(reader));
+        }
+
+        let reader = Arc::new(Reader::open_readfile(&path).map_err(|e| {
+            anyhow::anyhow!(
+                "IP enrich: failed to open MaxMind database {:?}: {}",
+                path,
+                e
+            )
+        })?);
+        readers.insert(path, Arc::clone(&reader));
+        Ok(reader)
+    }
+}
+
+const DEFAULT_COLUMN: &str = "ip";
+const DEFAULT_LANGUAGE: &str = "en";
+
+struct Options {
+    db_path: String,
+    column: String,
+    prefix: String,
+    language: String,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let db_path = string_option(config, "db_path")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'db_path' configuration"))?;
+    // An empty prefix is the default, so it is the one string allowed to be empty
+    let prefix = match config.get("prefix") {
+        Some(toml::Value::String(s)) => s.clone(),
+        Some(_) => anyhow::bail!("'prefix' must be a string"),
+        None => String::new(),
+    };
+
+    Ok(Options {
+        db_path,
+        column: string_option(config, "column")?.unwrap_or_else(|| DEFAULT_COLUMN.to_string()),
+        prefix,
+        language: string_option(config, "language")?
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string()),
+    })
+}
+
+/// The parts of a GeoIP2/GeoLite2 City, Country or ASN record that are used
+#[derive(Deserialize, Default)]
+struct GeoRecord {
+    country: Option<Place>,
+    city: Option<Place>,
+    autonomous_system_number: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct Place {
+    iso_code: Option<String>,
+    names: Option<BTreeMap<String, String>>,
+}
+
+/// Record for `value`; malformed addresses and addresses not in the
+/// database have an empty one
+fn lookup(reader: &Reader<Vec<u8>>, value: Option<&JsonValue>) -> Result<GeoRecord> {
+    let Some(ip) = value
+        .and_then(JsonValue::as_str)
+        .and_then(|s| s.trim().parse::<IpAddr>().ok())
+    else {
+        return Ok(GeoRecord::default());
+    };
+    // An IPv6 address would walk an IPv4-only tree to an unrelated record
+    if ip.is_ipv6() && reader.metadata.ip_version == 4 {
+        return Ok(GeoRecord::default());
+    }
+
+    match reader.lookup::<GeoRecord>(ip) {
+        Ok(record) => Ok(record),
+        Err(MaxMindDBError::AddressNotFoundError(_)) => Ok(GeoRecord::default()),
+        Err(e) => anyhow::bail!("IP enrich: lookup of {} failed: {}", ip, e),
+    }
+}
+
+fn text(value: Option<String>) -> JsonValue {
+    value.map(JsonValue::String).unwrap_or(JsonValue::Null)
+}
+
+#[async_trait]
+impl Stage for IpEnrichTransform {
+    fn name(&self) -> &str {
+        "ip_enrich.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "db_path".to_string(),
+            toml::Value::String("data/GeoLite2-City.mmdb".to_string()),
+        );
+        example1.insert(
+            "column".to_string(),
+            toml::Value::String("client_ip".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "db_path".to_string(),
+            toml::Value::String("data/GeoLite2-ASN.mmdb".to_string()),
+        );
+        example2.insert(
+            "column".to_string(),
+            toml::Value::String("src_ip".to_string()),
+        );
+        example2.insert(
+            "prefix".to_string(),
+            toml::Value::String("src_".to_string()),
+        );
+
+        StageMetadata::builder("ip_enrich.apply", StageCategory::Transform)
+            .description("Enrich IP addresses with country, city and ASN from a MaxMind database")
+            .long_description(
+                "Looks up each row's IPv4 or IPv6 address in a local MaxMind DB file (GeoIP2 or \
+                GeoLite2, .mmdb) and adds 'country' (ISO code), 'city' (name in 'language') and \
+                'asn' columns, each preceded by 'prefix'. No network access is needed. Rows whose \
+                address is null, malformed or not in the database get null in all three columns, \
+                as do fields the database does not carry: a City database has no ASN, an ASN \
+                database has no country or city. The database is read once and kept for the \
+                stage's lifetime.",
+            )
+            .parameter(ConfigParameter::required(
+                "db_path",
+                ParameterType::String,
+                "Path of the MaxMind DB (.mmdb) file",
+            ))
+            .parameter(ConfigParameter::optional(
+                "column",
+                ParameterType::String,
+                DEFAULT_COLUMN,
+                "Column holding the IP address",
+            ))
+            .parameter(ConfigParameter::optional(
+                "prefix",
+                ParameterType::String,
+                "",
+                "Prepended to the added column names",
+            ))
+            .parameter(ConfigParameter::optional(
+                "language",
+                ParameterType::String,
+                DEFAULT_LANGUAGE,
+                "Language of the city name",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Locate clients",
+                example1,
+                Some("Add country, city and asn for client_ip from a GeoLite2 City database"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Source networks",
+                example2,
+                Some("Add src_asn for src_ip from a GeoLite2 ASN database"),
+            ))
+            .tag("ip")
+            .tag("geo")
+            .tag("enrich")
+            .tag("security")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let options = parse_options(config)?;
+        let reader = self.reader(&options.db_path)?;
+
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("IP enrich transform requires input data"))?;
+        let records = data.as_record_batch()?;
+
+        let country = format!("{}country", options.prefix);
+        let city = format!("{}city", options.prefix);
+        let asn = format!("{}asn", options.prefix);
+
+        let enriched = records
+            .into_iter()
+            .map(|mut record| {
+                let found = lookup(&reader, record.get(&options.column))?;
+                let city_name = found
+                    .city
+                    .and_then(|c| c.names)
+                    .and_then(|mut names| names.remove(&options.language));
+                record.insert(
+                    country.clone(),
+                    text(found.country.and_then(|c| c.iso_code)),
+                );
+                record.insert(city.clone(), text(city_name));
+                record.insert(
+                    asn.clone(),
+                    found
+                        .autonomous_system_number
+                        .map(JsonValue::from)
+                        .unwrap_or(JsonValue::Null),
+                );
+                Ok(record)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DataFormat::RecordBatch(enriched))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Control byte(s) of a MaxMind DB data field of `kind` and `size`
+    fn control(kind: u8, size: usize) -> Vec<u8> {
+        assert!(size < 29);
+        if kind <= 7 {
+            vec![(kind << 5) | size as u8]
+        } else {
+            vec![size as u8, kind - 7]
+        }
+    }
+
+    fn string(s: &str) -> Vec<u8> {
+        [control(2, s.len()), s.as_bytes().to_vec()].concat()
+    }
+
+    /// Unsigned integer of `kind` (5 = uint16, 6 = uint32, 9 = uint64)
+    fn uint(kind: u8, value: u64) -> Vec<u8> {
+        let bytes: Vec<u8> = value
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        [control(kind, bytes.len()), bytes].concat()
+    }
+
+    fn map(pairs: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut out = control(7, pairs.len());
+        for (key, value) in pairs {
+            out.extend(string(key));
+            out.extend_from_slice(value);
+        }
+        out
+    }
+
+    /// IPv4 database with one record for 81.2.69.0/24
+    fn write_test_database(path: &std::path::Path) {
+        let network = [81u8, 2, 69, 0];
+        let prefix_len = 24;
+        let node_count = prefix_len as u32;
+        // Data pointers count from the end of the tree plus the 16-byte separator
+        let record_pointer = node_count + 16;
+
+        let mut tree = Vec::new();
+        for depth in 0..prefix_len {
+            let bit = (network[depth / 8] >> (7 - depth % 8)) & 1;
+            let next = if depth + 1 == prefix_len {
+                record_pointer
+            } else {
+                depth as u32 + 1
+            };
+            let mut records = [node_count, node_count];
+            records[bit as usize] = next;
+            for record in records {
+                tree.extend_from_slice(&record.to_be_bytes()[1..]);
+            }
+        }
+
+        let data = map(&[
+            ("city", map(&[("names", map(&[("en", string("London"))]))])),
+            (
+                "country",
+                map(&[
+                    ("iso_code", string("GB")),
+                    ("names", map(&[("en", string("United Kingdom"))])),
+                ]),
+            ),
+            ("autonomous_system_number", uint(6, 20712)),
+        ]);
+        let metadata = map(&[
+            ("binary_format_major_version", uint(5, 2)),
+            ("binary_format_minor_version", uint(5, 0)),
+            ("build_epoch", uint(9, 1_700_000_000)),
+            ("database_type", string("Conveyor-Test-City")),
+            ("description", map(&[("en", string("Test database"))])),
+            ("ip_version", uint(5, 4)),
+            ("languages", [control(11, 1), string("en")].concat()),
+            ("node_count", uint(6, node_count as u64)),
+            ("record_size", uint(5, 24)),
+        ]);
+
+        let file = [
+            tree,
+            vec![0; 16],
+            data,
+            b"\xAB\xCD\xEFMaxMind.com".to_vec(),
+            metadata,
+        ]
+        .concat();
+        std::fs::write(path, file).unwrap();
+    }
+
+    fn config(db_path: &std::path::Path) -> HashMap<String, toml::Value> {
+        HashMap::from([(
+            "db_path".to_string(),
+            toml::Value::String(db_path.to_string_lossy().to_string()),
+        )])
+    }
+
+    async fn enrich(
+        stage: &IpEnrichTransform,
+        ips: &[JsonValue],
+        config: &HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let records = ips
+            .iter()
+            .map(|ip| HashMap::from([("ip".to_string(), ip.clone())]))
+            .collect();
+        stage
+            .execute(
+                HashMap::from([("logs".to_string(), DataFormat::RecordBatch(records))]),
+                config,
+            )
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ip_enrich_resolves_known_ip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test-city.mmdb");
+        write_test_database(&db_path);
+        let stage = IpEnrichTransform::new();
+
+        let rows = enrich(&stage, &[json!("81.2.69.160")], &config(&db_path)).await;
+        assert_eq!(rows[0]["country"], json!("GB"));
+        assert_eq!(rows[0]["city"], json!("London"));
+        assert_eq!(rows[0]["asn"], json!(20712));
+
+        // The reader is cached, so the file is not needed again
+        std::fs::remove_file(&db_path).unwrap();
+        let mut prefixed = config(&db_path);
+        prefixed.insert(
+            "prefix".to_string(),
+            toml::Value::String("src_".to_string()),
+        );
+        let rows = enrich(&stage, &[json!(" 81.2.69.1 ")], &prefixed).await;
+        assert_eq!(rows[0]["src_country"], json!("GB"));
+        assert!(!rows[0].contains_key("country"));
+    }
+
+    #[tokio::test]
+    async fn test_ip_enrich_unresolved_ips_are_null() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test-city.mmdb");
+        write_test_database(&db_path);
+        let stage = IpEnrichTransform::new();
+
+        let rows = enrich(
+            &stage,
+            &[
+                json!("not-an-ip"),
+                json!("81.2.69.256"),
+                json!("8.8.8.8"),
+                json!("2001:db8::1"),
+                json!(42),
+                JsonValue::Null,
+            ],
+            &config(&db_path),
+        )
+        .await;
+        for row in &rows {
+            for column in ["country", "city", "asn"] {
+                assert_eq!(row[column], JsonValue::Null, "{:?}", row["ip"]);
+            }
+        }
+
+        assert!(stage
+            .validate_config(&HashMap::from([(
+                "column".to_string(),
+                toml::Value::String("ip".to_string())
+            )]))
+            .await
+            .is_err());
+        let missing = std::path::Path::new("/nonexistent/GeoLite2-City.mmdb");
+        assert!(stage
+            .execute(
+                HashMap::from([("logs".to_string(), DataFormat::RecordBatch(vec![]))]),
+                &config(missing),
+            )
+            .await
+            .is_err());
+    }
+}
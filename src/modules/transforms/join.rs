@@ -0,0 +1,358 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::memory;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Joins two named inputs on key columns
+pub struct JoinTransform;
+
+const DEFAULT_SUFFIX: &str = "_right";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JoinHow {
+    Inner,
+    Left,
+    Right,
+    Outer,
+}
+
+impl JoinHow {
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "inner" => Ok(JoinHow::Inner),
+            "left" => Ok(JoinHow::Left),
+            "right" => Ok(JoinHow::Right),
+            "outer" | "full" => Ok(JoinHow::Outer),
+            _ => anyhow::bail!(
+                "Unknown join type: '{}'. Supported: inner, left, right, outer",
+                s
+            ),
+        }
+    }
+
+    fn join_type(self) -> JoinType {
+        match self {
+            JoinHow::Inner => JoinType::Inner,
+            JoinHow::Left => JoinType::Left,
+            JoinHow::Right => JoinType::Right,
+            JoinHow::Outer => JoinType::Full,
+        }
+    }
+}
+
+fn parse_how(config: &HashMap<String, toml::Value>) -> Result<JoinHow> {
+    match config.get("how") {
+        Some(toml::Value::String(s)) => JoinHow::from_str(s),
+        Some(_) => anyhow::bail!("'how' must be a string"),
+        None => Ok(JoinHow::Inner),
+    }
+}
+
+fn parse_on(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    let on = string_list_option(config, "on")?
+        .ok_or_else(|| anyhow::anyhow!("Join requires 'on' configuration"))?;
+    if on.is_empty() {
+        anyhow::bail!("'on' must name at least one column");
+    }
+    Ok(on)
+}
+
+fn check_on_columns(df: &DataFrame, on: &[String], side: &str) -> Result<()> {
+    for column in on {
+        if df.column(column).is_err() {
+            anyhow::bail!("Join: column '{}' not found in {} input", column, side);
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Stage for JoinTransform {
+    fn name(&self) -> &str {
+        "join.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "left".to_string(),
+            toml::Value::String("orders".to_string()),
+        );
+        example1.insert(
+            "right".to_string(),
+            toml::Value::String("customers".to_string()),
+        );
+        example1.insert(
+            "on".to_string(),
+            toml::Value::String("customer_id".to_string()),
+        );
+        example1.insert("how".to_string(), toml::Value::String("left".to_string()));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "left".to_string(),
+            toml::Value::String("stock_eu".to_string()),
+        );
+        example2.insert(
+            "right".to_string(),
+            toml::Value::String("stock_us".to_string()),
+        );
+        example2.insert(
+            "on".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("sku".to_string()),
+                toml::Value::String("date".to_string()),
+            ]),
+        );
+        example2.insert("how".to_string(), toml::Value::String("outer".to_string()));
+        example2.insert("suffix".to_string(), toml::Value::String("_us".to_string()));
+
+        StageMetadata::builder("join.apply", StageCategory::Transform)
+            .description("Join two inputs on key columns")
+            .long_description(
+                "Combines the rows of a left and a right input whose 'on' columns are equal, like \
+                SQL JOIN. 'inner' keeps matched rows only, 'left' and 'right' also keep the \
+                unmatched rows of that side, and 'outer' keeps unmatched rows of both sides, with \
+                nulls for the columns of the missing side. The key columns appear once in the \
+                output. Other right columns whose name is also on the left get 'suffix' appended. \
+                The 'left' and 'right' options name the input stages; they default to inputs \
+                called 'left' and 'right'.",
+            )
+            .parameter(ConfigParameter::required(
+                "on",
+                ParameterType::Array,
+                "Key column(s) present in both inputs (string or array of strings)",
+            ))
+            .parameter(
+                ConfigParameter::optional("how", ParameterType::String, "inner", "Join type")
+                    .with_validation(ParameterValidation::allowed_values([
+                        "inner", "left", "right", "outer",
+                    ])),
+            )
+            .parameter(ConfigParameter::optional(
+                "left",
+                ParameterType::String,
+                "left",
+                "Input stage providing the left rows",
+            ))
+            .parameter(ConfigParameter::optional(
+                "right",
+                ParameterType::String,
+                "right",
+                "Input stage providing the right rows",
+            ))
+            .parameter(ConfigParameter::optional(
+                "suffix",
+                ParameterType::String,
+                DEFAULT_SUFFIX,
+                "Appended to right columns whose name clashes with a left column",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Enrich orders with customers",
+                example1,
+                Some("Keep every order, adding customer columns where one matches"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Align two regions",
+                example2,
+                Some("All sku/date pairs from either region, US columns suffixed with _us"),
+            ))
+            .tag("join")
+            .tag("merge")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        mut inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let how = parse_how(config)?;
+        let on = parse_on(config)?;
+        let left_name = config
+            .get("left")
+            .and_then(|v| v.as_str())
+            .unwrap_or("left");
+        let right_name = config
+            .get("right")
+            .and_then(|v| v.as_str())
+            .unwrap_or("right");
+        let suffix = config
+            .get("suffix")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_SUFFIX);
+
+        let mut available: Vec<&String> = inputs.keys().collect();
+        available.sort();
+        let available = format!("{:?}", available);
+
+        let left = inputs.remove(left_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Join: left input '{}' not found (available inputs: {})",
+                left_name,
+                available
+            )
+        })?;
+        let right = inputs.remove(right_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Join: right input '{}' not found (available inputs: {})",
+                right_name,
+                available
+            )
+        })?;
+
+        let left = left.as_dataframe()?;
+        let right = right.as_dataframe()?;
+        check_on_columns(&left, &on, "left")?;
+        check_on_columns(&right, &on, "right")?;
+
+        let keys: Vec<Expr> = on.iter().map(|k| col(k.as_str())).collect();
+        // Coalesce so an outer join does not leave a second, half-null key column
+        let args = JoinArgs::new(how.join_type())
+            .with_coalesce(JoinCoalesce::CoalesceColumns)
+            .with_suffix(Some(suffix.into()));
+
+        let result = memory::collect(left.lazy().join(right.lazy(), &keys, &keys, args))?;
+
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_how(config)?;
+        parse_on(config)?;
+
+        for option in ["left", "right", "suffix"] {
+            if let Some(value) = config.get(option) {
+                if value.as_str().is_none() {
+                    anyhow::bail!("'{}' must be a string", option);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> HashMap<String, DataFormat> {
+        let orders = df! {
+            "id" => [1i64, 2, 3],
+            "customer" => ["ann", "bob", "cy"],
+            "amount" => [10i64, 20, 30],
+        }
+        .unwrap();
+        let customers = df! {
+            "id" => [2i64, 3, 4],
+            "customer" => ["Bob", "Cy", "Di"],
+            "city" => ["Oslo", "Rome", "Lima"],
+        }
+        .unwrap();
+        HashMap::from([
+            ("orders".to_string(), DataFormat::DataFrame(orders)),
+            ("customers".to_string(), DataFormat::DataFrame(customers)),
+        ])
+    }
+
+    fn config(how: &str) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            (
+                "left".to_string(),
+                toml::Value::String("orders".to_string()),
+            ),
+            (
+                "right".to_string(),
+                toml::Value::String("customers".to_string()),
+            ),
+            ("on".to_string(), toml::Value::String("id".to_string())),
+            ("how".to_string(), toml::Value::String(how.to_string())),
+        ])
+    }
+
+    async fn join(how: &str) -> DataFrame {
+        let df = JoinTransform
+            .execute(inputs(), &config(how))
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        df.sort(["id"], SortMultipleOptions::default()).unwrap()
+    }
+
+    fn ids(df: &DataFrame) -> Vec<Option<i64>> {
+        df.column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_join_inner() {
+        let df = join("inner").await;
+        assert_eq!(ids(&df), vec![Some(2), Some(3)]);
+        assert_eq!(
+            df.get_column_names(),
+            vec!["id", "customer", "amount", "customer_right", "city"]
+        );
+        assert_eq!(
+            df.column("city").unwrap().str().unwrap().get(0),
+            Some("Oslo")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_left_and_right() {
+        let df = join("left").await;
+        assert_eq!(ids(&df), vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(df.column("city").unwrap().null_count(), 1);
+
+        let df = join("right").await;
+        assert_eq!(ids(&df), vec![Some(2), Some(3), Some(4)]);
+        assert_eq!(df.column("amount").unwrap().null_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_join_outer() {
+        let df = join("outer").await;
+        // One coalesced key column covering both sides
+        assert_eq!(ids(&df), vec![Some(1), Some(2), Some(3), Some(4)]);
+        assert!(df.column("id_right").is_err());
+        assert_eq!(df.column("amount").unwrap().null_count(), 1);
+        assert_eq!(df.column("city").unwrap().null_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_join_missing_input() {
+        let mut missing = config("inner");
+        missing.insert(
+            "right".to_string(),
+            toml::Value::String("accounts".to_string()),
+        );
+        let err = match JoinTransform.execute(inputs(), &missing).await {
+            Ok(_) => panic!("expected a missing input to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(
+            err,
+            "Join: right input 'accounts' not found (available inputs: [\"customers\", \"orders\"])"
+        );
+
+        assert!(JoinTransform
+            .validate_config(&config("sideways"))
+            .await
+            .is_err());
+    }
+}
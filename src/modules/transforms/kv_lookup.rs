@@ -0,0 +1,495 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use redb::{Database, TableDefinition, TableError};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::common::string_option;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+
+/// KV Lookup Transform
+/// Enriches rows with values stored under their key in an embedded redb database
+pub struct KvLookupTransform {
+    /// Opened databases by path; redb allows one open handle per file and process
+    stores: Mutex<HashMap<PathBuf, Arc<Database>>>,
+}
+
+impl Default for KvLookupTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KvLookupTransform {
+    pub fn new() -> Self {
+        Self {
+            stores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn store(&self, path: &str) -> Result<Arc<Database>> {
+        let path = PathBuf::from(path);
+        let mut stores = self.stores.lock().unwrap();
+        if let Some(db) = stores.get(&path) {
+            return Ok(Arc::clone(db));
+        }
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let db = Arc::new(
+            Database::create(&path)
+                .with_context(|| format!("Failed to open key-value store {:?}", path))?,
+        );
+        stores.insert(path, Arc::clone(&db));
+        Ok(db)
+    }
+}
+
+const DEFAULT_TABLE: &str = "lookup";
+
+struct Options {
+    db_path: String,
+    table: String,
+    key: String,
+    columns: Option<Vec<String>>,
+    preload: Option<String>,
+    preload_key: String,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let db_path = string_option(config, "db_path")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'db_path' configuration"))?;
+    let key = string_option(config, "key")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'key' configuration"))?;
+    let columns = match config.get("columns") {
+        None => None,
+        Some(toml::Value::Array(arr)) => Some(
+            arr.iter()
+                .map(|v| {
+                    v.as_str()
+                        .map(|s| s.to_string())
+                        .ok_or_else(|| anyhow::anyhow!("'columns' must contain only strings"))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        Some(_) => anyhow::bail!("'columns' must be an array of strings"),
+    };
+
+    Ok(Options {
+        db_path,
+        table: string_option(config, "table")?.unwrap_or_else(|| DEFAULT_TABLE.to_string()),
+        preload_key: string_option(config, "preload_key")?.unwrap_or_else(|| key.clone()),
+        key,
+        columns,
+        preload: string_option(config, "preload")?,
+    })
+}
+
+/// Text of a key value; null and missing keys have none
+fn key_text(value: Option<&JsonValue>) -> Option<String> {
+    match value {
+        None | Some(JsonValue::Null) => None,
+        Some(JsonValue::String(s)) => Some(s.clone()),
+        Some(other) => Some(other.to_string()),
+    }
+}
+
+/// Write each record's fields, except the key, under its key; returns the number written
+fn preload(db: &Database, table: &str, key: &str, records: RecordBatch) -> Result<usize> {
+    let definition: TableDefinition<&str, &str> = TableDefinition::new(table);
+    let txn = db.begin_write()?;
+    let mut written = 0;
+    {
+        let mut table = txn.open_table(definition)?;
+        for (index, mut record) in records.into_iter().enumerate() {
+            let id = key_text(record.remove(key).as_ref()).ok_or_else(|| {
+                anyhow::anyhow!("KV lookup: preload record {} has no '{}' key", index, key)
+            })?;
+            let value = serde_json::to_string(&record)?;
+            table.insert(id.as_str(), value.as_str())?;
+            written += 1;
+        }
+    }
+    txn.commit()?;
+    Ok(written)
+}
+
+/// Stored fields for each key; keys that are not stored map to None
+fn lookup(
+    db: &Database,
+    table: &str,
+    keys: &[Option<String>],
+) -> Result<Vec<Option<serde_json::Map<String, JsonValue>>>> {
+    let definition: TableDefinition<&str, &str> = TableDefinition::new(table);
+    let txn = db.begin_read()?;
+    let table = match txn.open_table(definition) {
+        Ok(table) => table,
+        // Nothing was ever preloaded: every key is missing
+        Err(TableError::TableDoesNotExist(_)) => return Ok(vec![None; keys.len()]),
+        Err(e) => return Err(e.into()),
+    };
+
+    keys.iter()
+        .map(|key| {
+            let Some(key) = key else {
+                return Ok(None);
+            };
+            match table.get(key.as_str())? {
+                Some(stored) => match serde_json::from_str(stored.value())? {
+                    JsonValue::Object(fields) => Ok(Some(fields)),
+                    _ => {
+                        anyhow::bail!("KV lookup: value stored for key '{}' is not an object", key)
+                    }
+                },
+                None => Ok(None),
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Stage for KvLookupTransform {
+    fn name(&self) -> &str {
+        "kv_lookup.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "db_path".to_string(),
+            toml::Value::String("data/products.redb".to_string()),
+        );
+        example1.insert("key".to_string(), toml::Value::String("sku".to_string()));
+        example1.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("title".to_string()),
+                toml::Value::String("category".to_string()),
+            ]),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "db_path".to_string(),
+            toml::Value::String("data/customers.redb".to_string()),
+        );
+        example2.insert(
+            "key".to_string(),
+            toml::Value::String("customer_id".to_string()),
+        );
+        example2.insert(
+            "preload".to_string(),
+            toml::Value::String("customer_snapshot".to_string()),
+        );
+        example2.insert(
+            "preload_key".to_string(),
+            toml::Value::String("id".to_string()),
+        );
+
+        StageMetadata::builder("kv_lookup.apply", StageCategory::Transform)
+            .description("Enrich rows from an embedded on-disk key-value store")
+            .long_description(
+                "Looks up each row's 'key' value in a redb database at 'db_path' and adds the \
+                fields stored under it as columns, overwriting row fields of the same name. The \
+                store keeps one JSON object per key, so lookups far larger than memory stay fast. \
+                Rows whose key is null or not stored get null for every value column; 'columns' \
+                limits the added columns, otherwise all stored fields found are added. When \
+                'preload' names an input stage, its records are first written to the store under \
+                their 'preload_key' field (replacing existing entries), and the other input is \
+                enriched. The database is opened once and kept open for the stage's lifetime.",
+            )
+            .parameter(ConfigParameter::required(
+                "db_path",
+                ParameterType::String,
+                "Path of the redb database file (created if missing)",
+            ))
+            .parameter(ConfigParameter::required(
+                "key",
+                ParameterType::String,
+                "Column whose value is looked up",
+            ))
+            .parameter(ConfigParameter::optional(
+                "columns",
+                ParameterType::Array,
+                "none",
+                "Stored fields to add (defaults to all)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "table",
+                ParameterType::String,
+                DEFAULT_TABLE,
+                "Table inside the database, to keep several lookups in one file",
+            ))
+            .parameter(ConfigParameter::optional(
+                "preload",
+                ParameterType::String,
+                "none",
+                "Input stage whose records are written to the store before the lookup",
+            ))
+            .parameter(ConfigParameter::optional(
+                "preload_key",
+                ParameterType::String,
+                "key",
+                "Field of the preload records holding their key",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Product attributes",
+                example1,
+                Some("Add title and category from a prebuilt product store"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Refresh and enrich",
+                example2,
+                Some(
+                    "Load the customer snapshot into the store, then enrich orders by customer_id",
+                ),
+            ))
+            .tag("lookup")
+            .tag("enrich")
+            .tag("kv")
+            .tag("join")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        mut inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let options = parse_options(config)?;
+        let db = self.store(&options.db_path)?;
+
+        if let Some(name) = &options.preload {
+            let records = inputs
+                .remove(name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("KV lookup transform: preload input '{}' not found", name)
+                })?
+                .as_record_batch()?;
+            let written = preload(&db, &options.table, &options.preload_key, records)?;
+            tracing::info!(
+                "KV lookup: preloaded {} entries into {} ({})",
+                written,
+                options.db_path,
+                options.table
+            );
+        }
+
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("KV lookup transform requires input data"))?;
+        let records = data.as_record_batch()?;
+
+        let keys: Vec<Option<String>> = records
+            .iter()
+            .map(|record| key_text(record.get(&options.key)))
+            .collect();
+        let found = lookup(&db, &options.table, &keys)?;
+
+        // Every row gets the same value columns, null where the key is missing
+        let columns: Vec<String> = match &options.columns {
+            Some(columns) => columns.clone(),
+            None => found
+                .iter()
+                .flatten()
+                .flat_map(|fields| fields.keys().cloned())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+
+        let enriched = records
+            .into_iter()
+            .zip(found)
+            .map(|(mut record, fields)| {
+                for column in &columns {
+                    let value = fields
+                        .as_ref()
+                        .and_then(|f| f.get(column))
+                        .cloned()
+                        .unwrap_or(JsonValue::Null);
+                    record.insert(column.clone(), value);
+                }
+                record
+            })
+            .collect();
+
+        Ok(DataFormat::RecordBatch(enriched))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(
+        db_path: &std::path::Path,
+        pairs: &[(&str, toml::Value)],
+    ) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([
+            (
+                "db_path".to_string(),
+                toml::Value::String(db_path.to_string_lossy().to_string()),
+            ),
+            ("key".to_string(), toml::Value::String("sku".to_string())),
+        ]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    fn orders() -> DataFormat {
+        DataFormat::RecordBatch(vec![
+            HashMap::from([
+                ("order".to_string(), json!(1)),
+                ("sku".to_string(), json!("A-1")),
+            ]),
+            HashMap::from([
+                ("order".to_string(), json!(2)),
+                ("sku".to_string(), json!("Z-9")),
+            ]),
+            HashMap::from([
+                ("order".to_string(), json!(3)),
+                ("sku".to_string(), json!(42)),
+            ]),
+            HashMap::from([
+                ("order".to_string(), json!(4)),
+                ("sku".to_string(), JsonValue::Null),
+            ]),
+        ])
+    }
+
+    fn products() -> DataFormat {
+        DataFormat::RecordBatch(vec![
+            HashMap::from([
+                ("id".to_string(), json!("A-1")),
+                ("title".to_string(), json!("Lamp")),
+                ("price".to_string(), json!(25.0)),
+            ]),
+            HashMap::from([
+                ("id".to_string(), json!(42)),
+                ("title".to_string(), json!("Desk")),
+                ("price".to_string(), json!(120.0)),
+            ]),
+        ])
+    }
+
+    async fn enrich(
+        stage: &KvLookupTransform,
+        inputs: Vec<(&str, DataFormat)>,
+        config: &HashMap<String, toml::Value>,
+    ) -> RecordBatch {
+        let inputs = inputs
+            .into_iter()
+            .map(|(name, data)| (name.to_string(), data))
+            .collect();
+        stage
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_kv_lookup_preloads_then_enriches() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("products.redb");
+        let stage = KvLookupTransform::new();
+
+        let preload = config(
+            &db_path,
+            &[
+                ("preload", toml::Value::String("products".to_string())),
+                ("preload_key", toml::Value::String("id".to_string())),
+            ],
+        );
+        let rows = enrich(
+            &stage,
+            vec![("orders", orders()), ("products", products())],
+            &preload,
+        )
+        .await;
+
+        assert_eq!(rows[0]["title"], "Lamp");
+        assert_eq!(rows[0]["price"], json!(25.0));
+        // Numeric keys match their text form; the key field itself is not stored
+        assert_eq!(rows[2]["title"], "Desk");
+        assert!(!rows[2].contains_key("id"));
+        // Missing and null keys get null for every value column
+        assert_eq!(rows[1]["title"], JsonValue::Null);
+        assert_eq!(rows[1]["price"], JsonValue::Null);
+        assert_eq!(rows[3]["title"], JsonValue::Null);
+        assert_eq!(rows[3]["order"], 4);
+
+        // Later runs read the stored entries through the cached handle
+        let subset = config(
+            &db_path,
+            &[(
+                "columns",
+                toml::Value::Array(vec![toml::Value::String("price".to_string())]),
+            )],
+        );
+        let rows = enrich(&stage, vec![("orders", orders())], &subset).await;
+        assert_eq!(rows[0]["price"], json!(25.0));
+        assert!(!rows[0].contains_key("title"));
+        assert_eq!(stage.stores.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_kv_lookup_empty_store_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("nested").join("empty.redb");
+        let stage = KvLookupTransform::new();
+
+        // A store that was never preloaded yields nulls for the requested columns
+        let wanted = config(
+            &db_path,
+            &[(
+                "columns",
+                toml::Value::Array(vec![toml::Value::String("title".to_string())]),
+            )],
+        );
+        let rows = enrich(&stage, vec![("orders", orders())], &wanted).await;
+        assert!(rows.iter().all(|r| r["title"].is_null()));
+
+        let missing_preload = config(
+            &db_path,
+            &[("preload", toml::Value::String("products".to_string()))],
+        );
+        let err = match stage
+            .execute(
+                HashMap::from([("orders".to_string(), orders())]),
+                &missing_preload,
+            )
+            .await
+        {
+            Ok(_) => panic!("Expected the missing preload input to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(
+            err,
+            "KV lookup transform: preload input 'products' not found"
+        );
+
+        let no_key = HashMap::from([(
+            "db_path".to_string(),
+            toml::Value::String("x.redb".to_string()),
+        )]);
+        let err = stage.validate_config(&no_key).await.unwrap_err();
+        assert_eq!(err.to_string(), "Missing required 'key' configuration");
+    }
+}
@@ -0,0 +1,210 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio_stream::StreamExt;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Keeps at most `count` rows, starting `offset` rows into the input
+pub struct LimitTransform;
+
+fn parse_count(config: &HashMap<String, toml::Value>, name: &str) -> Result<Option<usize>> {
+    match config.get(name) {
+        Some(toml::Value::Integer(n)) if *n >= 0 => Ok(Some(*n as usize)),
+        Some(_) => anyhow::bail!("'{}' must be a non-negative integer", name),
+        None => Ok(None),
+    }
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<(usize, usize)> {
+    let count = parse_count(config, "count")?
+        .ok_or_else(|| anyhow::anyhow!("Limit transform requires 'count' configuration"))?;
+    let offset = parse_count(config, "offset")?.unwrap_or(0);
+    Ok((count, offset))
+}
+
+#[async_trait]
+impl Stage for LimitTransform {
+    fn name(&self) -> &str {
+        "limit.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert("count".to_string(), toml::Value::Integer(100));
+
+        let mut example2 = HashMap::new();
+        example2.insert("count".to_string(), toml::Value::Integer(50));
+        example2.insert("offset".to_string(), toml::Value::Integer(1000));
+
+        StageMetadata::builder("limit.apply", StageCategory::Transform)
+            .description("Keep only the first N rows")
+            .long_description(
+                "Passes through at most 'count' rows, after skipping the first 'offset' rows. \
+                Useful for running a pipeline against a small slice of its data without editing \
+                the source. Streams end as soon as 'count' rows have been emitted, so the rest \
+                of the upstream is never read.",
+            )
+            .parameter(ConfigParameter::required(
+                "count",
+                ParameterType::Integer,
+                "Maximum number of rows to keep",
+            ))
+            .parameter(ConfigParameter::optional(
+                "offset",
+                ParameterType::Integer,
+                "0",
+                "Number of rows to skip first",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Sample run",
+                example1,
+                Some("Run the rest of the pipeline on the first 100 rows"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Page of rows",
+                example2,
+                Some("Keep rows 1000 to 1049"),
+            ))
+            .tag("limit")
+            .tag("head")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Limit transform requires input data"))?;
+
+        let (count, offset) = parse_options(config)?;
+
+        match data {
+            DataFormat::DataFrame(df) => Ok(DataFormat::DataFrame(df.slice(offset as i64, count))),
+            DataFormat::Stream(mut upstream) => {
+                let stream = async_stream::try_stream! {
+                    let mut skip = offset;
+                    let mut remaining = count;
+                    // Stop pulling from upstream once enough rows were emitted
+                    while remaining > 0 {
+                        let Some(records) = upstream.next().await else {
+                            break;
+                        };
+                        let mut records = records?;
+                        if skip >= records.len() {
+                            skip -= records.len();
+                            continue;
+                        }
+                        records.drain(..skip);
+                        skip = 0;
+                        records.truncate(remaining);
+                        remaining -= records.len();
+                        yield records;
+                    }
+                };
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+            data => Ok(DataFormat::RecordBatch(
+                data.as_record_batch()?
+                    .into_iter()
+                    .skip(offset)
+                    .take(count)
+                    .collect(),
+            )),
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::traits::RecordBatch;
+    use polars::prelude::*;
+    use serde_json::json;
+
+    fn config(count: i64, offset: i64) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            ("count".to_string(), toml::Value::Integer(count)),
+            ("offset".to_string(), toml::Value::Integer(offset)),
+        ])
+    }
+
+    fn rows(ids: std::ops::Range<i64>) -> RecordBatch {
+        ids.map(|i| HashMap::from([("id".to_string(), json!(i))]))
+            .collect()
+    }
+
+    async fn limit(data: DataFormat, config: &HashMap<String, toml::Value>) -> DataFormat {
+        let inputs = HashMap::from([("input".to_string(), data)]);
+        LimitTransform.execute(inputs, config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_limit_dataframe_and_records() {
+        let df = df! { "id" => (0..20i64).collect::<Vec<_>>() }.unwrap();
+        let output = limit(DataFormat::DataFrame(df), &config(5, 10))
+            .await
+            .as_dataframe()
+            .unwrap();
+        let ids: Vec<Option<i64>> = output
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(ids, (10..15).map(Some).collect::<Vec<_>>());
+
+        let records = limit(DataFormat::RecordBatch(rows(0..20)), &config(5, 10))
+            .await
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(records, rows(10..15));
+
+        // An offset past the end leaves nothing
+        let records = limit(DataFormat::RecordBatch(rows(0..20)), &config(5, 30))
+            .await
+            .as_record_batch()
+            .unwrap();
+        assert!(records.is_empty());
+
+        let missing_count = HashMap::from([("offset".to_string(), toml::Value::Integer(1))]);
+        assert!(LimitTransform
+            .validate_config(&missing_count)
+            .await
+            .is_err());
+        assert!(LimitTransform
+            .validate_config(&config(-1, 0))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_limit_truncates_endless_stream() {
+        // Batches of 4 rows forever; the limit must end the stream on its own
+        let upstream =
+            tokio_stream::iter((0..).map(|batch: i64| Ok(rows(batch * 4..batch * 4 + 4))));
+        let output = limit(DataFormat::Stream(Box::pin(upstream)), &config(5, 10)).await;
+        let DataFormat::Stream(stream) = output else {
+            panic!("Expected a stream");
+        };
+
+        let batches: Vec<RecordBatch> = stream.map(|batch| batch.unwrap()).collect().await;
+        assert_eq!(
+            batches.iter().map(|b| b.len()).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(batches.concat(), rows(10..15));
+    }
+}
@@ -4,7 +4,7 @@ use polars::prelude::*;
 use std::collections::HashMap;
 
 use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
-use crate::core::stage::Stage;
+use crate::core::stage::{MissingColumnPolicy, Stage};
 use crate::core::traits::DataFormat;
 
 pub struct MapTransform;
@@ -63,7 +63,9 @@ impl Stage for MapTransform {
                 Supports basic arithmetic operations: +, -, *, /. \
                 Can operate on single columns with constants or between two columns. \
                 Also supports boolean constants (true/false), numeric constants, and string constants (quoted). \
-                Useful for calculated fields and data transformations.",
+                Useful for calculated fields and data transformations. \
+                When a referenced column is absent, 'on_missing_column' decides whether the stage \
+                fails, leaves the output column untouched ('skip'), or writes nulls ('null').",
             )
             .parameter(ConfigParameter::required(
                 "expression",
@@ -75,6 +77,9 @@ impl Stage for MapTransform {
                 ParameterType::String,
                 "Name of the new column to create",
             ))
+            .parameter(MissingColumnPolicy::parameter(
+                "What to do when a referenced column is absent: 'error', 'skip' the column, or write 'null'",
+            ))
             .example(crate::core::metadata::ConfigExample::new(
                 "Add tax calculation",
                 example1,
@@ -122,11 +127,18 @@ impl Stage for MapTransform {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Map requires 'output_column' configuration"))?;
 
+        let policy = MissingColumnPolicy::from_config(config)?;
         let mut df = data.as_dataframe()?;
+        let missing = policy.missing("Map", &df, referenced_columns(expression))?;
 
         // Parse and apply the expression
         // This is a simplified version - in production, you'd want a proper expression parser
-        let new_column = if expression.contains("*") {
+        let new_column = if !missing.is_empty() {
+            if policy == MissingColumnPolicy::Skip {
+                return Ok(DataFormat::DataFrame(df));
+            }
+            Series::full_null(output_column.into(), df.height(), &DataType::Float64)
+        } else if expression.contains("*") {
             // Handle multiplication
             let parts: Vec<&str> = expression.split("*").collect();
             if parts.len() == 2 {
@@ -252,6 +264,82 @@ impl Stage for MapTransform {
             anyhow::bail!("Map requires 'output_column' configuration");
         }
 
+        MissingColumnPolicy::from_config(config)?;
+
         Ok(())
     }
 }
+
+/// Columns `expression` reads, following the same parsing rules as `execute`
+fn referenced_columns(expression: &str) -> Vec<&str> {
+    for operator in ['*', '+', '-', '/'] {
+        if expression.contains(operator) {
+            let parts: Vec<&str> = expression.split(operator).collect();
+            if parts.len() != 2 {
+                // Reported as an invalid expression by `execute`
+                return Vec::new();
+            }
+            let mut columns = vec![parts[0].trim()];
+            if operator == '/' && parts[1].trim().parse::<f64>().is_err() {
+                columns.push(parts[1].trim());
+            }
+            return columns;
+        }
+    }
+
+    let trimmed_expr = expression.trim();
+    let is_constant = trimmed_expr.eq_ignore_ascii_case("true")
+        || trimmed_expr.eq_ignore_ascii_case("false")
+        || (trimmed_expr.starts_with('"') && trimmed_expr.ends_with('"'))
+        || (trimmed_expr.starts_with('\'') && trimmed_expr.ends_with('\''))
+        || expression.parse::<f64>().is_ok();
+    if is_constant {
+        Vec::new()
+    } else {
+        vec![expression]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn map(expression: &str, policy: &str) -> Result<DataFrame> {
+        let df = df! { "price" => &[10.0f64, 20.0] }.unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        let config = HashMap::from([
+            (
+                "expression".to_string(),
+                toml::Value::String(expression.to_string()),
+            ),
+            (
+                "output_column".to_string(),
+                toml::Value::String("out".to_string()),
+            ),
+            (
+                "on_missing_column".to_string(),
+                toml::Value::String(policy.to_string()),
+            ),
+        ]);
+        MapTransform.execute(inputs, &config).await?.as_dataframe()
+    }
+
+    #[tokio::test]
+    async fn test_map_missing_column_policies() {
+        let err = map("discount * 2", "error").await.unwrap_err().to_string();
+        assert_eq!(err, "Map: column 'discount' not found");
+
+        let skipped = map("price / quantity", "skip").await.unwrap();
+        assert_eq!(skipped.get_column_names(), vec!["price"]);
+
+        let nulls = map("discount * 2", "null").await.unwrap();
+        assert_eq!(nulls.column("out").unwrap().null_count(), 2);
+
+        // Constants reference no column, so the policy never applies
+        let constant = map("\"pending\"", "skip").await.unwrap();
+        assert_eq!(
+            constant.column("out").unwrap().str().unwrap().get(0),
+            Some("pending")
+        );
+    }
+}
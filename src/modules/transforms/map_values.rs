@@ -0,0 +1,401 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct MapValuesTransform;
+
+/// Lookup table applied to one column
+#[derive(Debug)]
+struct ColumnMapping {
+    column: String,
+    mapping: HashMap<String, JsonValue>,
+    default: Option<JsonValue>,
+    output_column: String,
+}
+
+impl ColumnMapping {
+    fn from_table(column: &str, table: &toml::Table) -> Result<Self> {
+        let mapping = match table.get("mapping") {
+            Some(toml::Value::Table(entries)) => entries
+                .iter()
+                .map(|(from, to)| Ok((from.clone(), serde_json::to_value(to)?)))
+                .collect::<Result<HashMap<_, _>>>()?,
+            Some(_) => anyhow::bail!("'mapping' for column '{}' must be a table", column),
+            None => anyhow::bail!("Missing 'mapping' for column '{}'", column),
+        };
+
+        let default = table.get("default").map(serde_json::to_value).transpose()?;
+
+        let output_column = match table.get("output_column") {
+            Some(toml::Value::String(s)) => s.clone(),
+            Some(_) => anyhow::bail!("'output_column' for column '{}' must be a string", column),
+            None => column.to_string(),
+        };
+
+        Ok(Self {
+            column: column.to_string(),
+            mapping,
+            default,
+            output_column,
+        })
+    }
+
+    /// Mapped value, the default for unmatched values, or the value unchanged
+    fn apply(&self, value: Option<&JsonValue>) -> JsonValue {
+        let mapped = lookup_key(value).and_then(|key| self.mapping.get(&key));
+        match (mapped, &self.default) {
+            (Some(mapped), _) => mapped.clone(),
+            (None, Some(default)) => default.clone(),
+            (None, None) => value.cloned().unwrap_or(JsonValue::Null),
+        }
+    }
+}
+
+/// Mapping keys are strings, so numbers and booleans match their text form
+fn lookup_key(value: Option<&JsonValue>) -> Option<String> {
+    match value? {
+        JsonValue::Null => None,
+        JsonValue::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Read the single-column form (`column` + `mapping`) or the `columns` table
+fn parse_mappings(config: &HashMap<String, toml::Value>) -> Result<Vec<ColumnMapping>> {
+    let mappings = match (config.get("column"), config.get("columns")) {
+        (Some(_), Some(_)) => {
+            anyhow::bail!("Map values transform accepts either 'column' or 'columns', not both")
+        }
+        (Some(toml::Value::String(column)), None) => {
+            let table: toml::Table = config
+                .iter()
+                .filter(|(k, _)| matches!(k.as_str(), "mapping" | "default" | "output_column"))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            vec![ColumnMapping::from_table(column, &table)?]
+        }
+        (Some(_), None) => anyhow::bail!("'column' must be a string"),
+        (None, Some(toml::Value::Table(columns))) => columns
+            .iter()
+            .map(|(column, spec)| match spec {
+                toml::Value::Table(table) => ColumnMapping::from_table(column, table),
+                _ => anyhow::bail!("'columns.{}' must be a table with a 'mapping'", column),
+            })
+            .collect::<Result<Vec<_>>>()?,
+        (None, Some(_)) => anyhow::bail!("'columns' must be a table of column mappings"),
+        (None, None) => {
+            anyhow::bail!("Map values transform requires 'column' and 'mapping', or 'columns'")
+        }
+    };
+
+    if mappings.is_empty() {
+        anyhow::bail!("'columns' must contain at least one column mapping");
+    }
+
+    let mut outputs = HashSet::new();
+    for mapping in &mappings {
+        if !outputs.insert(mapping.output_column.as_str()) {
+            anyhow::bail!(
+                "Output column '{}' is written by more than one mapping",
+                mapping.output_column
+            );
+        }
+    }
+
+    Ok(mappings)
+}
+
+#[async_trait]
+impl Stage for MapValuesTransform {
+    fn name(&self) -> &str {
+        "map_values.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut mapping = toml::Table::new();
+        mapping.insert("A".to_string(), toml::Value::String("Active".to_string()));
+        mapping.insert("I".to_string(), toml::Value::String("Inactive".to_string()));
+
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "column".to_string(),
+            toml::Value::String("status".to_string()),
+        );
+        example1.insert("mapping".to_string(), toml::Value::Table(mapping));
+        example1.insert(
+            "default".to_string(),
+            toml::Value::String("Unknown".to_string()),
+        );
+        example1.insert(
+            "output_column".to_string(),
+            toml::Value::String("status_label".to_string()),
+        );
+
+        let mut country_mapping = toml::Table::new();
+        country_mapping.insert("KR".to_string(), toml::Value::String("Korea".to_string()));
+        country_mapping.insert(
+            "US".to_string(),
+            toml::Value::String("United States".to_string()),
+        );
+        let mut country = toml::Table::new();
+        country.insert("mapping".to_string(), toml::Value::Table(country_mapping));
+
+        let mut priority_mapping = toml::Table::new();
+        priority_mapping.insert("1".to_string(), toml::Value::String("high".to_string()));
+        priority_mapping.insert("2".to_string(), toml::Value::String("low".to_string()));
+        let mut priority = toml::Table::new();
+        priority.insert("mapping".to_string(), toml::Value::Table(priority_mapping));
+        priority.insert(
+            "default".to_string(),
+            toml::Value::String("normal".to_string()),
+        );
+
+        let mut columns = toml::Table::new();
+        columns.insert("country".to_string(), toml::Value::Table(country));
+        columns.insert("priority".to_string(), toml::Value::Table(priority));
+
+        let mut example2 = HashMap::new();
+        example2.insert("columns".to_string(), toml::Value::Table(columns));
+
+        StageMetadata::builder("map_values.apply", StageCategory::Transform)
+            .description("Replace column values using lookup tables defined inline in the config")
+            .long_description(
+                "Looks up each value of a column in an inline 'mapping' table and writes the \
+                mapped value to 'output_column' (the column itself by default). Values missing \
+                from the table, including nulls, become 'default' when it is set and are kept \
+                unchanged otherwise. Mapping keys are strings, so numbers and booleans match \
+                their text form ('1', 'true'). Use 'column' for a single mapping, or 'columns' \
+                to remap several columns in one stage; every mapping reads the original input \
+                values. Columns without a mapping are left untouched.",
+            )
+            .parameter(ConfigParameter::optional(
+                "column",
+                ParameterType::String,
+                "none",
+                "Column to remap (single-column form)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "mapping",
+                ParameterType::Object,
+                "none",
+                "Lookup table from input value to output value (single-column form)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "default",
+                ParameterType::String,
+                "none",
+                "Value for unmatched inputs; unmatched values are kept when unset",
+            ))
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "none",
+                "Column receiving the mapped values (defaults to the input column)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "columns",
+                ParameterType::Object,
+                "none",
+                "Per-column tables with 'mapping', 'default' and 'output_column'",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Status labels",
+                example1,
+                Some("Turn status codes into labels in a new status_label column"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Several columns",
+                example2,
+                Some("Expand country codes and map priority codes with a fallback"),
+            ))
+            .tag("map")
+            .tag("lookup")
+            .tag("recode")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Map values transform requires input data"))?;
+
+        let mappings = parse_mappings(config)?;
+        let records = data.as_record_batch()?;
+
+        let mapped = records
+            .into_iter()
+            .map(|mut record| {
+                let values: Vec<JsonValue> = mappings
+                    .iter()
+                    .map(|m| m.apply(record.get(&m.column)))
+                    .collect();
+                for (mapping, value) in mappings.iter().zip(values) {
+                    record.insert(mapping.output_column.clone(), value);
+                }
+                record
+            })
+            .collect();
+
+        Ok(DataFormat::RecordBatch(mapped))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_mappings(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(fields: &[(&str, JsonValue)]) -> HashMap<String, JsonValue> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn status_mapping() -> toml::Value {
+        let mut mapping = toml::Table::new();
+        mapping.insert("A".to_string(), toml::Value::String("Active".to_string()));
+        mapping.insert("I".to_string(), toml::Value::String("Inactive".to_string()));
+        toml::Value::Table(mapping)
+    }
+
+    async fn run(
+        records: Vec<HashMap<String, JsonValue>>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let mut inputs = HashMap::new();
+        inputs.insert("data".to_string(), DataFormat::RecordBatch(records));
+        MapValuesTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_map_values_mapped_and_untouched() {
+        let records = vec![
+            record(&[("status", json!("A")), ("name", json!("Alice"))]),
+            record(&[("status", json!("I")), ("name", json!("Bob"))]),
+            record(&[("status", json!("X")), ("name", json!("Carol"))]),
+        ];
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("status".to_string()),
+        );
+        config.insert("mapping".to_string(), status_mapping());
+
+        let output = run(records, &config).await;
+        assert_eq!(output[0]["status"], json!("Active"));
+        assert_eq!(output[1]["status"], json!("Inactive"));
+        // No default: unmatched values are kept
+        assert_eq!(output[2]["status"], json!("X"));
+        // Columns without a mapping are left alone
+        assert_eq!(output[0]["name"], json!("Alice"));
+        assert_eq!(output[0].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_map_values_default_and_output_column() {
+        let records = vec![
+            record(&[("status", json!("A"))]),
+            record(&[("status", json!("Z"))]),
+            record(&[("status", JsonValue::Null)]),
+        ];
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("status".to_string()),
+        );
+        config.insert("mapping".to_string(), status_mapping());
+        config.insert(
+            "default".to_string(),
+            toml::Value::String("Unknown".to_string()),
+        );
+        config.insert(
+            "output_column".to_string(),
+            toml::Value::String("status_label".to_string()),
+        );
+
+        let output = run(records, &config).await;
+        assert_eq!(output[0]["status_label"], json!("Active"));
+        assert_eq!(output[1]["status_label"], json!("Unknown"));
+        assert_eq!(output[2]["status_label"], json!("Unknown"));
+        // The source column keeps its original value
+        assert_eq!(output[1]["status"], json!("Z"));
+    }
+
+    #[tokio::test]
+    async fn test_map_values_multiple_columns() {
+        let records = vec![record(&[
+            ("status", json!("I")),
+            ("priority", json!(1)),
+            ("note", json!("keep")),
+        ])];
+
+        let mut priority_mapping = toml::Table::new();
+        priority_mapping.insert("1".to_string(), toml::Value::String("high".to_string()));
+        let mut priority = toml::Table::new();
+        priority.insert("mapping".to_string(), toml::Value::Table(priority_mapping));
+
+        let mut status = toml::Table::new();
+        status.insert("mapping".to_string(), status_mapping());
+
+        let mut columns = toml::Table::new();
+        columns.insert("priority".to_string(), toml::Value::Table(priority));
+        columns.insert("status".to_string(), toml::Value::Table(status));
+
+        let mut config = HashMap::new();
+        config.insert("columns".to_string(), toml::Value::Table(columns));
+
+        let output = run(records, &config).await;
+        assert_eq!(output[0]["status"], json!("Inactive"));
+        // Numbers match their text form
+        assert_eq!(output[0]["priority"], json!("high"));
+        assert_eq!(output[0]["note"], json!("keep"));
+    }
+
+    #[tokio::test]
+    async fn test_map_values_validation() {
+        let transform = MapValuesTransform;
+
+        assert!(transform.validate_config(&HashMap::new()).await.is_err());
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("status".to_string()),
+        );
+        // Missing mapping
+        assert!(transform.validate_config(&config).await.is_err());
+
+        config.insert("mapping".to_string(), status_mapping());
+        assert!(transform.validate_config(&config).await.is_ok());
+
+        config.insert(
+            "columns".to_string(),
+            toml::Value::Table(toml::Table::new()),
+        );
+        assert!(transform.validate_config(&config).await.is_err());
+    }
+}
@@ -0,0 +1,398 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashMap;
+use tokio_stream::StreamExt;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::{DataFormat, RecordBatch};
+
+pub struct MergePatchTransform;
+
+/// Apply an RFC 7386 merge-patch to `target` in place
+///
+/// Object patches are merged key by key, with null removing the key; any other
+/// patch (including arrays) replaces the target wholesale.
+pub fn merge_patch(target: &mut JsonValue, patch: &JsonValue) {
+    let JsonValue::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = JsonValue::Object(Map::new());
+    }
+    if let JsonValue::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(JsonValue::Null), value);
+            }
+        }
+    }
+}
+
+struct Options {
+    base: String,
+    patch: String,
+    output_column: String,
+    fail_on_error: bool,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let column = |name: &str| -> Result<&str> {
+        match config.get(name) {
+            Some(toml::Value::String(s)) => Ok(s),
+            Some(_) => anyhow::bail!("'{}' must be a column name", name),
+            None => anyhow::bail!("MergePatch requires '{}' configuration", name),
+        }
+    };
+    let base = column("base")?;
+    let patch = column("patch")?;
+
+    let output_column = match config.get("output_column") {
+        Some(toml::Value::String(s)) => s.as_str(),
+        Some(_) => anyhow::bail!("'output_column' must be a string"),
+        None => base,
+    };
+    let fail_on_error = match config.get("fail_on_error") {
+        Some(toml::Value::Boolean(b)) => *b,
+        Some(_) => anyhow::bail!("'fail_on_error' must be a boolean"),
+        None => false,
+    };
+
+    Ok(Options {
+        base: base.to_string(),
+        patch: patch.to_string(),
+        output_column: output_column.to_string(),
+        fail_on_error,
+    })
+}
+
+/// Read a cell holding a JSON document, either as text or as an already parsed value
+fn parse_cell(value: Option<&JsonValue>) -> Result<Option<JsonValue>> {
+    match value {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(JsonValue::String(text)) => Ok(Some(serde_json::from_str(text)?)),
+        Some(other) => Ok(Some(other.clone())),
+    }
+}
+
+impl Options {
+    /// Merge one row; a missing patch leaves the base as-is and a missing base starts empty
+    fn merge(&self, base: Option<&JsonValue>, patch: Option<&JsonValue>) -> Result<JsonValue> {
+        let describe = |column: &str, e: anyhow::Error| {
+            anyhow::anyhow!("MergePatch: '{}' is not valid JSON: {}", column, e)
+        };
+        let base = parse_cell(base).map_err(|e| describe(&self.base, e))?;
+        let patch = parse_cell(patch).map_err(|e| describe(&self.patch, e))?;
+
+        let mut merged = base.unwrap_or_else(|| JsonValue::Object(Map::new()));
+        if let Some(patch) = patch {
+            merge_patch(&mut merged, &patch);
+        }
+        Ok(merged)
+    }
+
+    /// Merge one row, turning invalid JSON into null unless `fail_on_error` is set
+    fn merge_or_null(
+        &self,
+        base: Option<&JsonValue>,
+        patch: Option<&JsonValue>,
+    ) -> Result<JsonValue> {
+        match self.merge(base, patch) {
+            Ok(merged) => Ok(merged),
+            Err(e) if self.fail_on_error => Err(e),
+            Err(e) => {
+                tracing::debug!("{}; writing null", e);
+                Ok(JsonValue::Null)
+            }
+        }
+    }
+
+    fn apply_records(&self, mut records: RecordBatch) -> Result<RecordBatch> {
+        for record in &mut records {
+            let merged = self.merge_or_null(record.get(&self.base), record.get(&self.patch))?;
+            record.insert(self.output_column.clone(), merged);
+        }
+        Ok(records)
+    }
+
+    /// DataFrames carry the documents as JSON text, and so does the output column
+    fn apply_dataframe(&self, mut df: DataFrame) -> Result<DataFrame> {
+        let text_column = |name: &str| -> Result<Vec<Option<JsonValue>>> {
+            let column = df
+                .column(name)
+                .map_err(|_| anyhow::anyhow!("MergePatch: column '{}' not found", name))?
+                .cast(&DataType::String)?;
+            Ok(column
+                .str()?
+                .into_iter()
+                .map(|v| v.map(|s| JsonValue::String(s.to_string())))
+                .collect())
+        };
+        let bases = text_column(&self.base)?;
+        let patches = text_column(&self.patch)?;
+
+        let merged: Vec<Option<String>> = bases
+            .iter()
+            .zip(&patches)
+            .map(|(base, patch)| {
+                let merged = self.merge_or_null(base.as_ref(), patch.as_ref())?;
+                Ok((!merged.is_null()).then(|| merged.to_string()))
+            })
+            .collect::<Result<_>>()?;
+
+        df.with_column(Series::new(self.output_column.as_str().into(), merged))?;
+        Ok(df)
+    }
+}
+
+#[async_trait]
+impl Stage for MergePatchTransform {
+    fn name(&self) -> &str {
+        "merge_patch.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert(
+            "base".to_string(),
+            toml::Value::String("settings".to_string()),
+        );
+        example.insert(
+            "patch".to_string(),
+            toml::Value::String("overrides".to_string()),
+        );
+        example.insert(
+            "output_column".to_string(),
+            toml::Value::String("effective_settings".to_string()),
+        );
+
+        StageMetadata::builder("merge_patch.apply", StageCategory::Transform)
+            .description("Merge a JSON patch column into a JSON base column per row (RFC 7386)")
+            .long_description(
+                "Applies JSON merge-patch (RFC 7386) row by row: objects in the 'patch' column \
+                are merged recursively into the object in the 'base' column, a null in the patch \
+                deletes the key, and any non-object value (arrays included) replaces the base \
+                value wholesale. Columns may hold JSON text or, for record inputs, JSON objects. \
+                A null patch leaves the base unchanged and a null base is treated as {}. Results \
+                are written to 'output_column' (the base column by default), as JSON text for \
+                DataFrames. Invalid JSON gives a null result unless 'fail_on_error' is set.",
+            )
+            .parameter(ConfigParameter::required(
+                "base",
+                ParameterType::String,
+                "Column holding the base JSON object",
+            ))
+            .parameter(ConfigParameter::required(
+                "patch",
+                ParameterType::String,
+                "Column holding the merge-patch JSON object",
+            ))
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "none",
+                "Column receiving the merged document (defaults to the base column)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "fail_on_error",
+                ParameterType::Boolean,
+                "false",
+                "Fail on invalid JSON instead of writing null",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Apply per-customer overrides",
+                example,
+                Some("{\"theme\": \"dark\", \"alerts\": {\"email\": true}} patched with {\"alerts\": {\"email\": null}} drops alerts.email"),
+            ))
+            .tag("json")
+            .tag("merge")
+            .tag("patch")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("MergePatch transform requires input data"))?;
+
+        let options = parse_options(config)?;
+
+        match data {
+            DataFormat::RecordBatch(records) => {
+                Ok(DataFormat::RecordBatch(options.apply_records(records)?))
+            }
+            DataFormat::Stream(upstream) => {
+                let stream = upstream.map(move |batch| options.apply_records(batch?));
+                Ok(DataFormat::Stream(Box::pin(stream)))
+            }
+            data => Ok(DataFormat::DataFrame(
+                options.apply_dataframe(data.as_dataframe()?)?,
+            )),
+        }
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(output_column: Option<&str>) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert("base".to_string(), toml::Value::String("base".to_string()));
+        config.insert(
+            "patch".to_string(),
+            toml::Value::String("patch".to_string()),
+        );
+        if let Some(output) = output_column {
+            config.insert(
+                "output_column".to_string(),
+                toml::Value::String(output.to_string()),
+            );
+        }
+        config
+    }
+
+    fn merged(base: JsonValue, patch: JsonValue) -> JsonValue {
+        let mut target = base;
+        merge_patch(&mut target, &patch);
+        target
+    }
+
+    #[test]
+    fn test_merge_patch_rfc_7386_semantics() {
+        // Nested objects merge key by key
+        assert_eq!(
+            merged(
+                json!({"a": "b", "c": {"d": "e", "f": "g"}}),
+                json!({"a": "z", "c": {"f": null, "h": "i"}})
+            ),
+            json!({"a": "z", "c": {"d": "e", "h": "i"}})
+        );
+
+        // Arrays are replaced, never merged
+        assert_eq!(
+            merged(json!({"tags": ["a", "b"]}), json!({"tags": ["c"]})),
+            json!({"tags": ["c"]})
+        );
+        assert_eq!(
+            merged(json!({"a": [{"b": "c"}]}), json!({"a": [1]})),
+            json!({"a": [1]})
+        );
+
+        // Null deletes a key, and deleting a missing key is a no-op
+        assert_eq!(
+            merged(json!({"a": "b", "b": "c"}), json!({"a": null, "x": null})),
+            json!({"b": "c"})
+        );
+
+        // A non-object patch replaces the target; an object patch over a scalar starts fresh
+        assert_eq!(merged(json!({"a": "b"}), json!(["c"])), json!(["c"]));
+        assert_eq!(merged(json!("text"), json!({"a": 1})), json!({"a": 1}));
+        assert_eq!(
+            merged(json!({"e": null}), json!({"a": 1})),
+            json!({"e": null, "a": 1})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_patch_dataframe_columns() {
+        let df = df![
+            "id" => [1, 2, 3],
+            "base" => [
+                Some(r#"{"theme": "dark", "alerts": {"email": true, "sms": false}}"#),
+                Some(r#"{"tags": ["a", "b"]}"#),
+                None,
+            ],
+            "patch" => [
+                Some(r#"{"alerts": {"email": null, "push": true}}"#),
+                Some(r#"{"tags": ["c"]}"#),
+                Some(r#"{"theme": "light"}"#),
+            ],
+        ]
+        .unwrap();
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+
+        let result = MergePatchTransform
+            .execute(inputs, &config(Some("merged")))
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+
+        let merged: Vec<JsonValue> = result
+            .column("merged")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|v| serde_json::from_str(v.unwrap()).unwrap())
+            .collect();
+        assert_eq!(
+            merged,
+            vec![
+                json!({"theme": "dark", "alerts": {"sms": false, "push": true}}),
+                json!({"tags": ["c"]}),
+                json!({"theme": "light"}),
+            ]
+        );
+        assert!(result.column("base").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_merge_patch_records_and_invalid_json() {
+        let mut record = HashMap::new();
+        record.insert(
+            "base".to_string(),
+            json!({"name": "Ada", "nickname": "ada"}),
+        );
+        record.insert("patch".to_string(), json!({"nickname": null}));
+        let mut broken = HashMap::new();
+        broken.insert("base".to_string(), json!("{not json"));
+        broken.insert("patch".to_string(), json!({"a": 1}));
+
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "input".to_string(),
+            DataFormat::RecordBatch(vec![record.clone(), broken.clone()]),
+        );
+        let records = MergePatchTransform
+            .execute(inputs, &config(None))
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+        assert_eq!(records[0]["base"], json!({"name": "Ada"}));
+        assert_eq!(records[1]["base"], JsonValue::Null);
+
+        let mut strict = config(None);
+        strict.insert("fail_on_error".to_string(), toml::Value::Boolean(true));
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(vec![broken]));
+        let err = match MergePatchTransform.execute(inputs, &strict).await {
+            Ok(_) => panic!("expected invalid JSON to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("'base' is not valid JSON"));
+
+        let mut missing = config(None);
+        missing.remove("patch");
+        assert!(MergePatchTransform.validate_config(&missing).await.is_err());
+    }
+}
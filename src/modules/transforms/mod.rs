@@ -1,16 +1,56 @@
+pub mod agg_state;
 pub mod aggregate_stream;
 pub mod ai;
+pub mod array_ops;
+pub mod batch;
+pub mod bucket;
 pub mod chunk;
+pub(crate) mod common;
+pub mod crosstab;
 pub mod decrypt;
+pub mod delta;
 pub mod distinct;
+pub mod eav_pivot;
 pub mod encrypt;
+pub mod entity_resolve;
 pub mod filter;
+pub mod flatten;
+pub mod funnel;
+pub mod fuzzy_join;
+pub mod geocode;
 pub mod group_by;
 pub mod http_fetch;
+pub mod ip_enrich;
+pub mod join;
 pub mod json_extract;
+pub mod kv_lookup;
+pub mod limit;
 pub mod map;
+pub mod map_values;
+pub mod merge_patch;
+pub mod normalize_category;
+pub mod outlier;
+pub mod parse_number;
+pub mod parse_text;
+pub mod patch;
+pub mod pivot;
+pub mod quality_score;
 pub mod reduce;
+#[cfg(feature = "ref-check")]
+pub mod ref_check;
+pub mod rename;
+pub mod resample;
+pub mod rolling_time;
+pub mod row_hash;
+pub mod scale;
+pub mod schema_drift;
+pub mod schema_validate;
 pub mod select;
+pub mod set_op;
 pub mod sort;
+pub mod stratified_sample;
+pub mod surrogate_key;
+pub mod time_convert;
+pub mod unpivot;
 pub mod validate;
 pub mod window;
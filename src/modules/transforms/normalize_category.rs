@@ -0,0 +1,547 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct NormalizeCategoryTransform;
+
+/// Field added to rejected rows explaining why they were rejected
+const REJECT_REASON: &str = "_reject_reason";
+
+/// What happens to a value that is neither allowed nor an alias
+#[derive(Debug, Clone, PartialEq)]
+enum UnknownPolicy {
+    Null,
+    Default(String),
+    Reject,
+}
+
+impl UnknownPolicy {
+    fn from_config(config: &HashMap<String, toml::Value>, categories: &Categories) -> Result<Self> {
+        let policy = config
+            .get("on_unknown")
+            .and_then(|v| v.as_str())
+            .unwrap_or("null");
+        match policy {
+            "null" => Ok(UnknownPolicy::Null),
+            "default" => {
+                let default = config
+                    .get("default")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("'on_unknown = \"default\"' requires a 'default' value")
+                    })?;
+                // The default goes through the same lookup, so it comes out canonical
+                let canonical = categories.lookup(default).ok_or_else(|| {
+                    anyhow::anyhow!("'default' value '{}' is not in 'allowed'", default)
+                })?;
+                Ok(UnknownPolicy::Default(canonical.to_string()))
+            }
+            "reject" => Ok(UnknownPolicy::Reject),
+            other => anyhow::bail!(
+                "Invalid on_unknown policy: '{}'. Must be 'null', 'default' or 'reject'",
+                other
+            ),
+        }
+    }
+}
+
+/// Canonical values and aliases, keyed by their normalized form
+#[derive(Debug)]
+struct Categories {
+    lookup: HashMap<String, String>,
+    case_sensitive: bool,
+}
+
+impl Categories {
+    fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        let case_sensitive = config
+            .get("case_sensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let allowed: Vec<String> = match config.get("allowed") {
+            Some(toml::Value::Array(arr)) => arr
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .map(|s| s.to_string())
+                        .ok_or_else(|| anyhow::anyhow!("'allowed' must be an array of strings"))
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => anyhow::bail!("'allowed' must be an array of strings"),
+            None => anyhow::bail!("Missing required 'allowed' configuration"),
+        };
+        if allowed.is_empty() {
+            anyhow::bail!("'allowed' must list at least one category");
+        }
+
+        let mut categories = Self {
+            lookup: HashMap::new(),
+            case_sensitive,
+        };
+        for value in &allowed {
+            let key = categories.normalize(value);
+            categories.lookup.insert(key, value.clone());
+        }
+
+        match config.get("aliases") {
+            None => {}
+            Some(toml::Value::Table(aliases)) => {
+                for (alias, target) in aliases {
+                    let target = target
+                        .as_str()
+                        .ok_or_else(|| anyhow::anyhow!("Alias '{}' must map to a string", alias))?;
+                    let canonical = categories
+                        .lookup(target)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Alias '{}' maps to '{}', which is not in 'allowed'",
+                                alias,
+                                target
+                            )
+                        })?
+                        .to_string();
+                    let key = categories.normalize(alias);
+                    categories.lookup.insert(key, canonical);
+                }
+            }
+            Some(_) => anyhow::bail!("'aliases' must be a table of alias = canonical value"),
+        }
+
+        Ok(categories)
+    }
+
+    fn normalize(&self, value: &str) -> String {
+        let value = value.trim();
+        if self.case_sensitive {
+            value.to_string()
+        } else {
+            value.to_lowercase()
+        }
+    }
+
+    fn lookup(&self, value: &str) -> Option<&str> {
+        self.lookup.get(&self.normalize(value)).map(|s| s.as_str())
+    }
+}
+
+#[async_trait]
+impl Stage for NormalizeCategoryTransform {
+    fn name(&self) -> &str {
+        "normalize_category.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut aliases = toml::Table::new();
+        aliases.insert(
+            "NY".to_string(),
+            toml::Value::String("New York".to_string()),
+        );
+        aliases.insert(
+            "NYC".to_string(),
+            toml::Value::String("New York".to_string()),
+        );
+        aliases.insert(
+            "SF".to_string(),
+            toml::Value::String("San Francisco".to_string()),
+        );
+
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "column".to_string(),
+            toml::Value::String("city".to_string()),
+        );
+        example1.insert(
+            "allowed".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("New York".to_string()),
+                toml::Value::String("San Francisco".to_string()),
+                toml::Value::String("Other".to_string()),
+            ]),
+        );
+        example1.insert("aliases".to_string(), toml::Value::Table(aliases));
+        example1.insert(
+            "on_unknown".to_string(),
+            toml::Value::String("default".to_string()),
+        );
+        example1.insert(
+            "default".to_string(),
+            toml::Value::String("Other".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "column".to_string(),
+            toml::Value::String("status".to_string()),
+        );
+        example2.insert(
+            "allowed".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("active".to_string()),
+                toml::Value::String("inactive".to_string()),
+            ]),
+        );
+        example2.insert(
+            "on_unknown".to_string(),
+            toml::Value::String("reject".to_string()),
+        );
+        example2.insert(
+            "output".to_string(),
+            toml::Value::String("rejects".to_string()),
+        );
+
+        StageMetadata::builder("normalize_category.apply", StageCategory::Transform)
+            .description("Normalize a column to a canonical set of categories and catch unknowns")
+            .long_description(
+                "Replaces each value of 'column' with its canonical form from 'allowed', matching \
+                after trimming and, unless 'case_sensitive' is set, ignoring case. 'aliases' maps \
+                extra spellings to an allowed value. Values that match neither are handled by \
+                'on_unknown': 'null' clears them, 'default' replaces them with 'default', and \
+                'reject' removes the row. With 'output = \"rejects\"' the stage emits only the \
+                rejected rows, each with a '_reject_reason' field, so a second stage with the \
+                same settings can route them to a sink. Null values are kept as null.",
+            )
+            .parameter(ConfigParameter::required(
+                "column",
+                ParameterType::String,
+                "Column holding the category values",
+            ))
+            .parameter(ConfigParameter::required(
+                "allowed",
+                ParameterType::Array,
+                "Canonical category values",
+            ))
+            .parameter(ConfigParameter::optional(
+                "aliases",
+                ParameterType::Object,
+                "none",
+                "Table of alias = canonical value",
+            ))
+            .parameter(ConfigParameter::optional(
+                "case_sensitive",
+                ParameterType::Boolean,
+                "false",
+                "Match values without ignoring case",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "on_unknown",
+                    ParameterType::String,
+                    "null",
+                    "Policy for values that are not allowed",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "null", "default", "reject",
+                ])),
+            )
+            .parameter(ConfigParameter::optional(
+                "default",
+                ParameterType::String,
+                "none",
+                "Replacement for unknown values (with on_unknown = \"default\")",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "valid",
+                    "Emit the normalized rows or only the rejected ones",
+                )
+                .with_validation(ParameterValidation::allowed_values(["valid", "rejects"])),
+            )
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "none",
+                "Column receiving the canonical values (defaults to the input column)",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Canonical city names",
+                example1,
+                Some("Map NY/NYC and SF to their full names, everything else to Other"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Collect rejects",
+                example2,
+                Some("Emit the rows whose status is not active or inactive"),
+            ))
+            .tag("category")
+            .tag("normalize")
+            .tag("cleaning")
+            .tag("validation")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Normalize category transform requires input data"))?;
+
+        let column = config
+            .get("column")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required 'column' configuration"))?;
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or(column);
+        let categories = Categories::from_config(config)?;
+        let policy = UnknownPolicy::from_config(config, &categories)?;
+        let emit_rejects = parse_output(config)? == "rejects";
+
+        let records = data.as_record_batch()?;
+        let mut valid = Vec::with_capacity(records.len());
+        let mut rejects = Vec::new();
+
+        for mut record in records {
+            let value = record.get(column).cloned().unwrap_or(JsonValue::Null);
+            let text = match &value {
+                JsonValue::Null => {
+                    record.insert(output_column.to_string(), JsonValue::Null);
+                    valid.push(record);
+                    continue;
+                }
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+
+            let canonical = match (categories.lookup(&text), &policy) {
+                (Some(canonical), _) => JsonValue::String(canonical.to_string()),
+                (None, UnknownPolicy::Null) => JsonValue::Null,
+                (None, UnknownPolicy::Default(default)) => JsonValue::String(default.clone()),
+                (None, UnknownPolicy::Reject) => {
+                    record.insert(
+                        REJECT_REASON.to_string(),
+                        JsonValue::String(format!(
+                            "Unknown category {} in column '{}'",
+                            value, column
+                        )),
+                    );
+                    rejects.push(record);
+                    continue;
+                }
+            };
+            record.insert(output_column.to_string(), canonical);
+            valid.push(record);
+        }
+
+        if !rejects.is_empty() {
+            tracing::warn!(
+                "Normalize category: rejected {} row(s) with unknown '{}' values",
+                rejects.len(),
+                column
+            );
+        }
+
+        Ok(DataFormat::RecordBatch(if emit_rejects {
+            rejects
+        } else {
+            valid
+        }))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if config.get("column").and_then(|v| v.as_str()).is_none() {
+            anyhow::bail!("Missing required 'column' configuration");
+        }
+        let categories = Categories::from_config(config)?;
+        let policy = UnknownPolicy::from_config(config, &categories)?;
+        if parse_output(config)? == "rejects" && policy != UnknownPolicy::Reject {
+            anyhow::bail!("'output = \"rejects\"' requires 'on_unknown = \"reject\"'");
+        }
+        Ok(())
+    }
+}
+
+fn parse_output(config: &HashMap<String, toml::Value>) -> Result<&str> {
+    let output = config
+        .get("output")
+        .and_then(|v| v.as_str())
+        .unwrap_or("valid");
+    match output {
+        "valid" | "rejects" => Ok(output),
+        other => anyhow::bail!("Invalid output: '{}'. Must be 'valid' or 'rejects'", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut aliases = toml::Table::new();
+        aliases.insert(
+            "NYC".to_string(),
+            toml::Value::String("New York".to_string()),
+        );
+        aliases.insert(
+            "sf".to_string(),
+            toml::Value::String("san francisco".to_string()),
+        );
+
+        let mut config = HashMap::new();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("city".to_string()),
+        );
+        config.insert(
+            "allowed".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("New York".to_string()),
+                toml::Value::String("San Francisco".to_string()),
+                toml::Value::String("Other".to_string()),
+            ]),
+        );
+        config.insert("aliases".to_string(), toml::Value::Table(aliases));
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    fn string(s: &str) -> toml::Value {
+        toml::Value::String(s.to_string())
+    }
+
+    async fn run(
+        cities: &[JsonValue],
+        config: &HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let records = cities
+            .iter()
+            .enumerate()
+            .map(|(i, city)| {
+                HashMap::from([
+                    ("id".to_string(), json!(i)),
+                    ("city".to_string(), city.clone()),
+                ])
+            })
+            .collect();
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(records));
+
+        NormalizeCategoryTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_normalize_category_canonical_and_aliases() {
+        let output = run(
+            &[
+                json!("new york"),
+                json!(" NYC "),
+                json!("SF"),
+                json!("San Francisco"),
+                JsonValue::Null,
+            ],
+            &config(&[]),
+        )
+        .await;
+
+        assert_eq!(output[0]["city"], json!("New York"));
+        assert_eq!(output[1]["city"], json!("New York"));
+        // Alias targets resolve to the canonical spelling
+        assert_eq!(output[2]["city"], json!("San Francisco"));
+        assert_eq!(output[3]["city"], json!("San Francisco"));
+        assert_eq!(output[4]["city"], JsonValue::Null);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_category_unknown_null_policy() {
+        let output = run(&[json!("Boston")], &config(&[])).await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["city"], JsonValue::Null);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_category_unknown_default_policy() {
+        let output = run(
+            &[json!("Boston"), json!("nyc")],
+            &config(&[
+                ("on_unknown", string("default")),
+                ("default", string("other")),
+                ("output_column", string("city_clean")),
+            ]),
+        )
+        .await;
+        assert_eq!(output[0]["city_clean"], json!("Other"));
+        assert_eq!(output[1]["city_clean"], json!("New York"));
+        // The input column is untouched when writing elsewhere
+        assert_eq!(output[0]["city"], json!("Boston"));
+    }
+
+    #[tokio::test]
+    async fn test_normalize_category_unknown_reject_policy() {
+        let cities = [json!("Boston"), json!("NYC"), json!(42)];
+
+        let valid = run(&cities, &config(&[("on_unknown", string("reject"))])).await;
+        assert_eq!(valid.len(), 1);
+        assert_eq!(valid[0]["city"], json!("New York"));
+
+        let rejects = run(
+            &cities,
+            &config(&[
+                ("on_unknown", string("reject")),
+                ("output", string("rejects")),
+            ]),
+        )
+        .await;
+        assert_eq!(rejects.len(), 2);
+        assert_eq!(rejects[0]["city"], json!("Boston"));
+        assert_eq!(
+            rejects[0][REJECT_REASON],
+            json!("Unknown category \"Boston\" in column 'city'")
+        );
+        assert_eq!(rejects[1]["id"], json!(2));
+    }
+
+    #[tokio::test]
+    async fn test_normalize_category_validation() {
+        let transform = NormalizeCategoryTransform;
+
+        assert!(transform.validate_config(&config(&[])).await.is_ok());
+
+        let mut missing = config(&[]);
+        missing.remove("allowed");
+        assert!(transform.validate_config(&missing).await.is_err());
+
+        // Default must be one of the allowed values
+        assert!(transform
+            .validate_config(&config(&[
+                ("on_unknown", string("default")),
+                ("default", string("Boston")),
+            ]))
+            .await
+            .is_err());
+
+        assert!(transform
+            .validate_config(&config(&[("output", string("rejects"))]))
+            .await
+            .is_err());
+
+        let mut aliases = toml::Table::new();
+        aliases.insert("LA".to_string(), string("Los Angeles"));
+        assert!(transform
+            .validate_config(&config(&[("aliases", toml::Value::Table(aliases))]))
+            .await
+            .is_err());
+    }
+}
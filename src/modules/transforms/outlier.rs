@@ -0,0 +1,383 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Flags numeric values far from the rest of their column
+pub struct OutlierTransform;
+
+const DEFAULT_FLAG_COLUMN: &str = "_outlier";
+const OUTPUTS: [&str; 3] = ["all", "inliers", "outliers"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Method {
+    /// Outside `[q1 - t * iqr, q3 + t * iqr]`
+    Iqr,
+    /// More than `t` standard deviations from the mean
+    ZScore,
+}
+
+impl Method {
+    fn default_threshold(self) -> f64 {
+        match self {
+            Method::Iqr => 1.5,
+            Method::ZScore => 3.0,
+        }
+    }
+}
+
+struct OutlierOptions {
+    method: Method,
+    threshold: f64,
+    columns: Vec<String>,
+    flag_column: String,
+    per_column: bool,
+    output: String,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<OutlierOptions> {
+    let method = match config.get("method").map(|v| v.as_str()) {
+        None | Some(Some("iqr")) => Method::Iqr,
+        Some(Some("zscore")) => Method::ZScore,
+        Some(Some(other)) => anyhow::bail!("Invalid method: {}. Must be 'iqr' or 'zscore'", other),
+        Some(None) => anyhow::bail!("'method' must be a string"),
+    };
+
+    let threshold = match config.get("threshold") {
+        None => method.default_threshold(),
+        Some(value) => value
+            .as_float()
+            .or_else(|| value.as_integer().map(|n| n as f64))
+            .filter(|t| *t > 0.0)
+            .ok_or_else(|| anyhow::anyhow!("'threshold' must be a positive number"))?,
+    };
+
+    let columns = string_list_option(config, "columns")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'columns' configuration"))?;
+    if columns.is_empty() {
+        anyhow::bail!("'columns' must name at least one column");
+    }
+
+    let string = |name: &str, default: &str| -> Result<String> {
+        match config.get(name) {
+            None => Ok(default.to_string()),
+            Some(toml::Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+            Some(_) => anyhow::bail!("'{}' must be a non-empty string", name),
+        }
+    };
+    let flag_column = string("flag_column", DEFAULT_FLAG_COLUMN)?;
+    let output = string("output", "all")?;
+    if !OUTPUTS.contains(&output.as_str()) {
+        anyhow::bail!(
+            "Invalid output: {}. Must be one of: {}",
+            output,
+            OUTPUTS.join(", ")
+        );
+    }
+
+    let per_column = match config.get("per_column") {
+        None => false,
+        Some(toml::Value::Boolean(b)) => *b,
+        Some(_) => anyhow::bail!("'per_column' must be a boolean"),
+    };
+
+    Ok(OutlierOptions {
+        method,
+        threshold,
+        columns,
+        flag_column,
+        per_column,
+        output,
+    })
+}
+
+fn numeric_values(df: &DataFrame, name: &str) -> Result<Float64Chunked> {
+    let column = df
+        .column(name)
+        .map_err(|_| anyhow::anyhow!("Outlier: column '{}' not found", name))?;
+    if !column.dtype().is_numeric() {
+        anyhow::bail!(
+            "Outlier: column '{}' is {}, not numeric",
+            name,
+            column.dtype()
+        );
+    }
+    Ok(column
+        .as_materialized_series()
+        .cast(&DataType::Float64)?
+        .f64()?
+        .clone())
+}
+
+/// Inclusive range of values that are not outliers, from the whole column
+fn bounds(values: &Float64Chunked, method: Method, threshold: f64) -> Result<Option<(f64, f64)>> {
+    Ok(match method {
+        Method::Iqr => {
+            let q1 = values.quantile(0.25, QuantileMethod::Linear)?;
+            let q3 = values.quantile(0.75, QuantileMethod::Linear)?;
+            q1.zip(q3).map(|(q1, q3)| {
+                let iqr = q3 - q1;
+                (q1 - threshold * iqr, q3 + threshold * iqr)
+            })
+        }
+        Method::ZScore => values
+            .mean()
+            .zip(values.std(0))
+            // A constant column has no outliers
+            .filter(|(_, std)| *std > 0.0)
+            .map(|(mean, std)| (mean - threshold * std, mean + threshold * std)),
+    })
+}
+
+/// Per-row outlier flags for one column; nulls are never outliers
+fn flag(values: &Float64Chunked, method: Method, threshold: f64) -> Result<Vec<bool>> {
+    let bounds = bounds(values, method, threshold)?;
+    Ok(values
+        .into_iter()
+        .map(|value| match (value, bounds) {
+            (Some(v), Some((low, high))) => v < low || v > high,
+            _ => false,
+        })
+        .collect())
+}
+
+#[async_trait]
+impl Stage for OutlierTransform {
+    fn name(&self) -> &str {
+        "outlier.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("amount".to_string()),
+                toml::Value::String("quantity".to_string()),
+            ]),
+        );
+        example1.insert("per_column".to_string(), toml::Value::Boolean(true));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::String("latency_ms".to_string()),
+        );
+        example2.insert(
+            "method".to_string(),
+            toml::Value::String("zscore".to_string()),
+        );
+        example2.insert("threshold".to_string(), toml::Value::Float(2.5));
+        example2.insert(
+            "output".to_string(),
+            toml::Value::String("outliers".to_string()),
+        );
+
+        StageMetadata::builder("outlier.apply", StageCategory::Transform)
+            .description("Flag outlying numeric values by IQR or z-score")
+            .long_description(
+                "Computes statistics over each of the 'columns' and flags values far from the \
+                rest. 'iqr' flags values more than 'threshold' interquartile ranges below the \
+                first or above the third quartile (default 1.5); 'zscore' flags values more than \
+                'threshold' standard deviations from the mean (default 3.0). A boolean \
+                'flag_column' is true when any column is an outlier, and 'per_column' adds a \
+                '<column>_outlier' flag for each one. Nulls are never outliers. With 'output' set \
+                to 'inliers' or 'outliers' only those rows are emitted, so a second stage with the \
+                same settings can route the outliers elsewhere.",
+            )
+            .parameter(ConfigParameter::required(
+                "columns",
+                ParameterType::Array,
+                "Numeric column(s) to check (string or array of strings)",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "method",
+                    ParameterType::String,
+                    "iqr",
+                    "Detection method",
+                )
+                .with_validation(ParameterValidation::allowed_values(["iqr", "zscore"])),
+            )
+            .parameter(ConfigParameter::optional(
+                "threshold",
+                ParameterType::Float,
+                "1.5 (iqr) / 3.0 (zscore)",
+                "IQR multiplier or number of standard deviations",
+            ))
+            .parameter(ConfigParameter::optional(
+                "flag_column",
+                ParameterType::String,
+                DEFAULT_FLAG_COLUMN,
+                "Boolean column set when any checked column is an outlier",
+            ))
+            .parameter(ConfigParameter::optional(
+                "per_column",
+                ParameterType::Boolean,
+                "false",
+                "Also add a '<column>_outlier' flag per checked column",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "all",
+                    "Emit all rows flagged, only the inliers, or only the outliers",
+                )
+                .with_validation(ParameterValidation::allowed_values(OUTPUTS)),
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Flag unusual orders",
+                example1,
+                Some("Mark orders whose amount or quantity is outside 1.5 IQR"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Collect latency spikes",
+                example2,
+                Some("Emit only requests more than 2.5 standard deviations from the mean"),
+            ))
+            .tag("outlier")
+            .tag("anomaly")
+            .tag("quality")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Outlier transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let mut df = data.as_dataframe()?;
+
+        let mut any = vec![false; df.height()];
+        let mut per_column = Vec::new();
+        for name in &options.columns {
+            let values = numeric_values(&df, name)?;
+            let flags = flag(&values, options.method, options.threshold)?;
+            for (any, flagged) in any.iter_mut().zip(&flags) {
+                *any |= *flagged;
+            }
+            if options.per_column {
+                per_column.push(Series::new(format!("{}_outlier", name).into(), flags));
+            }
+        }
+
+        for series in per_column {
+            df.with_column(series)?;
+        }
+        let mask = BooleanChunked::new(options.flag_column.as_str().into(), &any);
+        df.with_column(mask.clone().into_series())?;
+
+        let df = match options.output.as_str() {
+            "inliers" => df.filter(&!&mask)?,
+            "outliers" => df.filter(&mask)?,
+            _ => df,
+        };
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(method: &str) -> HashMap<String, toml::Value> {
+        HashMap::from([
+            (
+                "columns".to_string(),
+                toml::Value::Array(vec![
+                    toml::Value::String("amount".to_string()),
+                    toml::Value::String("qty".to_string()),
+                ]),
+            ),
+            (
+                "method".to_string(),
+                toml::Value::String(method.to_string()),
+            ),
+            // A small sample caps z-scores, so 3 standard deviations is out of reach
+            ("threshold".to_string(), toml::Value::Float(2.0)),
+            ("per_column".to_string(), toml::Value::Boolean(true)),
+        ])
+    }
+
+    async fn detect(config: &HashMap<String, toml::Value>) -> DataFrame {
+        let df = df! {
+            "id" => (1..=8i64).collect::<Vec<_>>(),
+            "amount" => [10.0f64, 11.0, 9.5, 10.5, 10.0, 9.0, 11.5, 500.0],
+            "qty" => [Some(2i64), Some(3), None, Some(2), Some(3), Some(2), Some(3), Some(2)],
+        }
+        .unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        OutlierTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    fn flags(df: &DataFrame, column: &str) -> Vec<Option<bool>> {
+        df.column(column)
+            .unwrap()
+            .bool()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_outlier_flags_anomaly_with_both_methods() {
+        for method in ["iqr", "zscore"] {
+            let df = detect(&config(method)).await;
+            let mut expected = vec![Some(false); 8];
+            expected[7] = Some(true);
+            assert_eq!(flags(&df, "_outlier"), expected, "method {}", method);
+            assert_eq!(flags(&df, "amount_outlier"), expected, "method {}", method);
+            // Normal quantities and the null are not flagged
+            assert_eq!(flags(&df, "qty_outlier"), vec![Some(false); 8]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outlier_output_routing() {
+        let mut outliers = config("iqr");
+        outliers.insert(
+            "output".to_string(),
+            toml::Value::String("outliers".to_string()),
+        );
+        let df = detect(&outliers).await;
+        assert_eq!(df.column("id").unwrap().i64().unwrap().get(0), Some(8));
+        assert_eq!(df.height(), 1);
+
+        outliers.insert(
+            "output".to_string(),
+            toml::Value::String("inliers".to_string()),
+        );
+        assert_eq!(detect(&outliers).await.height(), 7);
+
+        let mut invalid = config("mad");
+        assert!(OutlierTransform.validate_config(&invalid).await.is_err());
+        invalid.insert("method".to_string(), toml::Value::String("iqr".to_string()));
+        invalid.insert("threshold".to_string(), toml::Value::Integer(0));
+        assert!(OutlierTransform.validate_config(&invalid).await.is_err());
+    }
+}
@@ -0,0 +1,499 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use super::common::string_list;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct ParseNumberTransform;
+
+/// Spaces used as thousands separators (regular, no-break, narrow no-break)
+const SPACES: [char; 3] = [' ', '\u{a0}', '\u{202f}'];
+
+/// Decimal and grouping separators of a number format
+#[derive(Debug, Clone, PartialEq)]
+struct NumberFormat {
+    decimal: char,
+    grouping: Option<char>,
+}
+
+impl NumberFormat {
+    fn from_locale(locale: &str) -> Result<Self> {
+        let (decimal, grouping) = match locale.replace('-', "_").to_lowercase().as_str() {
+            "en_us" | "en" | "us" => ('.', Some(',')),
+            "de_de" | "de" | "eu" => (',', Some('.')),
+            "fr_fr" | "fr" => (',', Some(' ')),
+            "de_ch" | "ch" => ('.', Some('\'')),
+            _ => anyhow::bail!(
+                "Unknown locale: '{}'. Supported: en_US, de_DE, fr_FR, de_CH",
+                locale
+            ),
+        };
+        Ok(Self { decimal, grouping })
+    }
+
+    fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        let mut format = Self::from_locale(
+            config
+                .get("locale")
+                .and_then(|v| v.as_str())
+                .unwrap_or("en_US"),
+        )?;
+
+        if let Some(value) = config.get("decimal_separator") {
+            format.decimal = single_char(value, "decimal_separator")?;
+        }
+        if let Some(value) = config.get("grouping_separator") {
+            format.grouping = match value.as_str() {
+                Some("") => None,
+                _ => Some(single_char(value, "grouping_separator")?),
+            };
+        }
+
+        if format.grouping == Some(format.decimal) {
+            anyhow::bail!(
+                "Decimal and grouping separators must differ (both are '{}')",
+                format.decimal
+            );
+        }
+        Ok(format)
+    }
+
+    fn is_grouping(&self, c: char) -> bool {
+        match self.grouping {
+            Some(' ') => SPACES.contains(&c),
+            Some(g) => c == g,
+            None => false,
+        }
+    }
+
+    /// Parse an unsigned number, checking that groups have three digits
+    fn parse_unsigned(&self, text: &str) -> Option<f64> {
+        let mut parts = text.splitn(2, self.decimal);
+        let integer = parts.next()?;
+        let fraction = parts.next();
+
+        let groups: Vec<&str> = integer.split(|c| self.is_grouping(c)).collect();
+        let grouped_ok = match groups.split_first() {
+            Some((first, rest)) if !rest.is_empty() => {
+                (1..=3).contains(&first.len()) && rest.iter().all(|g| g.len() == 3)
+            }
+            _ => true,
+        };
+        let digits: String = groups.concat();
+        let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+
+        if !grouped_ok
+            || !all_digits(&digits)
+            || fraction.is_some_and(|f| !all_digits(f))
+            || (digits.is_empty() && fraction.is_none_or(|f| f.is_empty()))
+        {
+            return None;
+        }
+
+        format!("{}.{}", digits, fraction.unwrap_or("0"))
+            .parse()
+            .ok()
+    }
+}
+
+fn single_char(value: &toml::Value, option: &str) -> Result<char> {
+    let mut chars = value
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("'{}' must be a string", option))?
+        .chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => anyhow::bail!("'{}' must be a single character", option),
+    }
+}
+
+fn parse_symbols(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    string_list(config, "currency_symbol")
+}
+
+/// Settings for turning localized text into numbers
+#[derive(Debug)]
+struct NumberParser {
+    format: NumberFormat,
+    symbols: Vec<String>,
+    parentheses_negative: bool,
+}
+
+impl NumberParser {
+    fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        Ok(Self {
+            format: NumberFormat::from_config(config)?,
+            symbols: parse_symbols(config)?,
+            parentheses_negative: config
+                .get("parentheses_negative")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        })
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        let mut text = text.to_string();
+        for symbol in &self.symbols {
+            text = text.replace(symbol.as_str(), "");
+        }
+        let mut text = text.trim_matches(|c: char| c.is_whitespace() || SPACES.contains(&c));
+
+        let mut negative = false;
+        if self.parentheses_negative {
+            if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+                negative = true;
+                text = inner.trim();
+            }
+        }
+        if let Some(rest) = text.strip_prefix('-') {
+            negative = !negative;
+            text = rest.trim_start();
+        } else if let Some(rest) = text.strip_prefix('+') {
+            text = rest.trim_start();
+        }
+
+        let value = self.format.parse_unsigned(text)?;
+        Some(if negative { -value } else { value })
+    }
+
+    fn parse_value(&self, value: &JsonValue) -> Option<f64> {
+        match value {
+            JsonValue::Number(n) => n.as_f64(),
+            JsonValue::String(s) => self.parse(s),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Stage for ParseNumberTransform {
+    fn name(&self) -> &str {
+        "parse_number.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "column".to_string(),
+            toml::Value::String("amount".to_string()),
+        );
+        example1.insert(
+            "locale".to_string(),
+            toml::Value::String("de_DE".to_string()),
+        );
+        example1.insert(
+            "currency_symbol".to_string(),
+            toml::Value::String("€".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "column".to_string(),
+            toml::Value::String("balance".to_string()),
+        );
+        example2.insert(
+            "currency_symbol".to_string(),
+            toml::Value::String("$".to_string()),
+        );
+        example2.insert(
+            "parentheses_negative".to_string(),
+            toml::Value::Boolean(true),
+        );
+        example2.insert(
+            "output_column".to_string(),
+            toml::Value::String("balance_value".to_string()),
+        );
+
+        StageMetadata::builder("parse_number.apply", StageCategory::Transform)
+            .description("Parse localized number and currency text into a float column")
+            .long_description(
+                "Converts text such as '$1,234.56' or '1.234,56 €' into floats. The 'locale' \
+                sets the decimal and grouping separators (en_US: 1,234.56, de_DE: 1.234,56, \
+                fr_FR: 1 234,56, de_CH: 1'234.56); 'decimal_separator' and \
+                'grouping_separator' override them. Every 'currency_symbol' is stripped before \
+                parsing. A leading '-' marks a negative number, and with \
+                'parentheses_negative' so does '(1,234)'. Grouping must use groups of three \
+                digits, so a value in the wrong locale fails rather than being misread. Values \
+                that cannot be parsed become null unless 'fail_on_error' is set; existing \
+                numbers are passed through as floats.",
+            )
+            .parameter(ConfigParameter::required(
+                "column",
+                ParameterType::String,
+                "Column holding the formatted numbers",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "locale",
+                    ParameterType::String,
+                    "en_US",
+                    "Number format of the input",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "en_US", "de_DE", "fr_FR", "de_CH",
+                ])),
+            )
+            .parameter(ConfigParameter::optional(
+                "decimal_separator",
+                ParameterType::String,
+                "none",
+                "Decimal separator, overriding the locale",
+            ))
+            .parameter(ConfigParameter::optional(
+                "grouping_separator",
+                ParameterType::String,
+                "none",
+                "Thousands separator, overriding the locale (empty for none)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "currency_symbol",
+                ParameterType::Array,
+                "none",
+                "Currency symbol(s) stripped before parsing (string or array of strings)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "parentheses_negative",
+                ParameterType::Boolean,
+                "false",
+                "Treat values in parentheses as negative, e.g. (1,234)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "none",
+                "Column receiving the parsed floats (defaults to the input column)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "fail_on_error",
+                ParameterType::Boolean,
+                "false",
+                "Fail on unparseable values instead of writing null",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "European amounts",
+                example1,
+                Some("Parse '1.234,56 €' into 1234.56"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Accounting negatives",
+                example2,
+                Some("Parse '$(1,234.00)' into -1234.0 in a new column"),
+            ))
+            .tag("number")
+            .tag("currency")
+            .tag("locale")
+            .tag("parse")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Parse number transform requires input data"))?;
+
+        let column = config
+            .get("column")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required 'column' configuration"))?;
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or(column);
+        let parser = NumberParser::from_config(config)?;
+        let fail_on_error = config
+            .get("fail_on_error")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let records = data.as_record_batch()?;
+
+        let parsed = records
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut record)| {
+                let value = record.get(column).cloned().unwrap_or(JsonValue::Null);
+                let output = if value.is_null() {
+                    JsonValue::Null
+                } else {
+                    match parser
+                        .parse_value(&value)
+                        .and_then(serde_json::Number::from_f64)
+                    {
+                        Some(n) => JsonValue::Number(n),
+                        None if fail_on_error => anyhow::bail!(
+                            "Cannot parse {} in column '{}' at row {} as a number",
+                            value,
+                            column,
+                            i
+                        ),
+                        None => JsonValue::Null,
+                    }
+                };
+                record.insert(output_column.to_string(), output);
+                Ok(record)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DataFormat::RecordBatch(parsed))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if config.get("column").and_then(|v| v.as_str()).is_none() {
+            anyhow::bail!("Missing required 'column' configuration");
+        }
+        NumberParser::from_config(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config: HashMap<String, toml::Value> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        config.insert(
+            "column".to_string(),
+            toml::Value::String("amount".to_string()),
+        );
+        config
+    }
+
+    fn string(s: &str) -> toml::Value {
+        toml::Value::String(s.to_string())
+    }
+
+    async fn parse(
+        values: &[&str],
+        config: HashMap<String, toml::Value>,
+    ) -> Result<Vec<JsonValue>> {
+        let records = values
+            .iter()
+            .map(|v| HashMap::from([("amount".to_string(), json!(v))]))
+            .collect();
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(records));
+
+        let output = ParseNumberTransform.execute(inputs, &config).await?;
+        Ok(output
+            .as_record_batch()?
+            .into_iter()
+            .map(|mut r| r.remove("amount").unwrap())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_parse_number_us_format() {
+        let values = parse(
+            &["$1,234.56", "$ 1,000,000", "-$12.5", "0.75", "1.234,56"],
+            config(&[("currency_symbol", string("$"))]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(values[0], json!(1234.56));
+        assert_eq!(values[1], json!(1000000.0));
+        assert_eq!(values[2], json!(-12.5));
+        assert_eq!(values[3], json!(0.75));
+        // European grouping is rejected rather than misread
+        assert_eq!(values[4], JsonValue::Null);
+    }
+
+    #[tokio::test]
+    async fn test_parse_number_european_format() {
+        let values = parse(
+            &["1.234,56 €", "€ 12,5", "1 234,56", "1.234.567"],
+            config(&[
+                ("locale", string("de_DE")),
+                ("currency_symbol", string("€")),
+            ]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(values[0], json!(1234.56));
+        assert_eq!(values[1], json!(12.5));
+        assert_eq!(values[2], JsonValue::Null);
+        assert_eq!(values[3], json!(1234567.0));
+
+        // fr_FR groups with (no-break) spaces
+        let values = parse(
+            &["1\u{a0}234,56 €"],
+            config(&[
+                ("locale", string("fr_FR")),
+                ("currency_symbol", string("€")),
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(values[0], json!(1234.56));
+    }
+
+    #[tokio::test]
+    async fn test_parse_number_parenthesized_negative() {
+        let values = parse(
+            &["(1,234)", "$(99.50)"],
+            config(&[
+                ("parentheses_negative", toml::Value::Boolean(true)),
+                ("currency_symbol", string("$")),
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(values[0], json!(-1234.0));
+        assert_eq!(values[1], json!(-99.5));
+
+        // Without the flag, parentheses are not a number
+        let values = parse(&["(1,234)"], config(&[])).await.unwrap();
+        assert_eq!(values[0], JsonValue::Null);
+    }
+
+    #[tokio::test]
+    async fn test_parse_number_fail_on_error() {
+        let err = parse(
+            &["12", "twelve"],
+            config(&[("fail_on_error", toml::Value::Boolean(true))]),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Cannot parse \"twelve\" in column 'amount' at row 1"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_number_validation() {
+        let transform = ParseNumberTransform;
+
+        assert!(transform.validate_config(&HashMap::new()).await.is_err());
+        assert!(transform.validate_config(&config(&[])).await.is_ok());
+        assert!(transform
+            .validate_config(&config(&[("locale", string("xx_XX"))]))
+            .await
+            .is_err());
+        assert!(transform
+            .validate_config(&config(&[("decimal_separator", string(",,"))]))
+            .await
+            .is_err());
+        // Same separator twice would be ambiguous
+        assert!(transform
+            .validate_config(&config(&[("decimal_separator", string(","))]))
+            .await
+            .is_err());
+    }
+}
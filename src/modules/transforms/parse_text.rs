@@ -0,0 +1,579 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct ParseTextTransform;
+
+/// A single output column parsed out of a line of text
+#[derive(Debug, Clone)]
+struct FieldSpec {
+    name: String,
+    start: usize,
+    length: usize,
+    field_type: String,
+    trim: bool,
+}
+
+#[async_trait]
+impl Stage for ParseTextTransform {
+    fn name(&self) -> &str {
+        "parse_text.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let fixed_field = |name: &str, start: i64, length: i64, field_type: &str| {
+            let mut table = toml::map::Map::new();
+            table.insert("name".to_string(), toml::Value::String(name.to_string()));
+            table.insert("start".to_string(), toml::Value::Integer(start));
+            table.insert("length".to_string(), toml::Value::Integer(length));
+            table.insert(
+                "type".to_string(),
+                toml::Value::String(field_type.to_string()),
+            );
+            toml::Value::Table(table)
+        };
+
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "mode".to_string(),
+            toml::Value::String("fixed_width".to_string()),
+        );
+        example1.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![
+                fixed_field("id", 0, 5, "int"),
+                fixed_field("name", 5, 10, "string"),
+                fixed_field("amount", 15, 8, "float"),
+            ]),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "mode".to_string(),
+            toml::Value::String("delimited".to_string()),
+        );
+        example2.insert(
+            "delimiter".to_string(),
+            toml::Value::String("|".to_string()),
+        );
+        example2.insert(
+            "column".to_string(),
+            toml::Value::String("line".to_string()),
+        );
+        example2.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("timestamp".to_string()),
+                toml::Value::String("level".to_string()),
+                toml::Value::String("message".to_string()),
+            ]),
+        );
+
+        StageMetadata::builder("parse_text.apply", StageCategory::Transform)
+            .description("Parse delimited or fixed-width text into columns")
+            .long_description(
+                "Splits lines of text into typed columns. Works on Raw input (one record per \
+                non-empty line) or on a string column of DataFrame/RecordBatch input. \
+                In 'delimited' mode each line is split on a custom delimiter and fields are \
+                assigned by position. In 'fixed_width' mode each field is cut out of the line \
+                using a 0-based character 'start' and a 'length', as found in mainframe exports. \
+                Fields can be trimmed and cast to string, int, float, or bool; empty values become null.",
+            )
+            .parameter(
+                ConfigParameter::required(
+                    "mode",
+                    ParameterType::String,
+                    "Parsing mode",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "delimited",
+                    "fixed_width",
+                ])),
+            )
+            .parameter(ConfigParameter::required(
+                "fields",
+                ParameterType::Array,
+                "Field specs: names or {name, type, trim} tables for 'delimited', \
+                {name, start, length, type, trim} tables for 'fixed_width'",
+            ))
+            .parameter(ConfigParameter::optional(
+                "delimiter",
+                ParameterType::String,
+                ",",
+                "Field delimiter for 'delimited' mode (may be more than one character)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "column",
+                ParameterType::String,
+                "line",
+                "Column holding the text to parse (ignored for Raw input)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "trim",
+                ParameterType::Boolean,
+                "true",
+                "Trim whitespace from every field (can be overridden per field)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "keep_source",
+                ParameterType::Boolean,
+                "false",
+                "Keep the original text column in the output",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Parse a fixed-width mainframe export",
+                example1,
+                Some("Cut id, name and amount out of fixed character positions"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Parse pipe-delimited log lines",
+                example2,
+                Some("Split the 'line' column on '|' into three columns"),
+            ))
+            .tag("parse")
+            .tag("text")
+            .tag("fixed-width")
+            .tag("delimited")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Parse text transform requires input data"))?;
+
+        let mode = config
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Parse text requires 'mode' configuration"))?;
+
+        let delimiter = config
+            .get("delimiter")
+            .and_then(|v| v.as_str())
+            .unwrap_or(",");
+
+        if delimiter.is_empty() {
+            anyhow::bail!("'delimiter' must not be empty");
+        }
+
+        let column = config
+            .get("column")
+            .and_then(|v| v.as_str())
+            .unwrap_or("line");
+
+        let keep_source = config
+            .get("keep_source")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let trim = config.get("trim").and_then(|v| v.as_bool()).unwrap_or(true);
+
+        let fields = parse_field_specs(mode, config, trim)?;
+
+        let records = match data {
+            DataFormat::Raw(bytes) => {
+                let text = String::from_utf8(bytes)
+                    .map_err(|e| anyhow::anyhow!("Raw input is not valid UTF-8: {}", e))?;
+
+                text.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| {
+                        let mut record = parse_line(line, mode, delimiter, &fields)?;
+                        if keep_source {
+                            record.insert(column.to_string(), JsonValue::String(line.to_string()));
+                        }
+                        Ok(record)
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            other => {
+                let records = other.as_record_batch()?;
+
+                records
+                    .into_iter()
+                    .map(|mut record| {
+                        let line = match record.get(column) {
+                            Some(JsonValue::String(s)) => s.clone(),
+                            Some(JsonValue::Null) | None => String::new(),
+                            Some(other) => other.to_string(),
+                        };
+
+                        let parsed = parse_line(&line, mode, delimiter, &fields)?;
+                        if !keep_source {
+                            record.remove(column);
+                        }
+                        record.extend(parsed);
+                        Ok(record)
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+        };
+
+        Ok(DataFormat::RecordBatch(records))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        let mode = config
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Parse text requires 'mode' configuration"))?;
+
+        if let Some(delimiter) = config.get("delimiter").and_then(|v| v.as_str()) {
+            if delimiter.is_empty() {
+                anyhow::bail!("'delimiter' must not be empty");
+            }
+        }
+
+        parse_field_specs(mode, config, true)?;
+
+        Ok(())
+    }
+}
+
+fn parse_field_specs(
+    mode: &str,
+    config: &HashMap<String, toml::Value>,
+    default_trim: bool,
+) -> Result<Vec<FieldSpec>> {
+    if mode != "delimited" && mode != "fixed_width" {
+        anyhow::bail!(
+            "Invalid parse_text mode: {}. Must be 'delimited' or 'fixed_width'",
+            mode
+        );
+    }
+
+    let fields = config
+        .get("fields")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Parse text requires 'fields' array configuration"))?;
+
+    if fields.is_empty() {
+        anyhow::bail!("'fields' must contain at least one field");
+    }
+
+    fields
+        .iter()
+        .map(|field| match field {
+            toml::Value::String(name) if mode == "delimited" => Ok(FieldSpec {
+                name: name.clone(),
+                start: 0,
+                length: 0,
+                field_type: "string".to_string(),
+                trim: default_trim,
+            }),
+            toml::Value::Table(table) => {
+                let name = table
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Each field requires a 'name'"))?;
+
+                let field_type = table
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("string");
+
+                if !["string", "int", "float", "bool"].contains(&field_type) {
+                    anyhow::bail!(
+                        "Invalid type '{}' for field '{}'. Must be one of: string, int, float, bool",
+                        field_type,
+                        name
+                    );
+                }
+
+                let trim = table
+                    .get("trim")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(default_trim);
+
+                let (start, length) = if mode == "fixed_width" {
+                    let start = table
+                        .get("start")
+                        .and_then(|v| v.as_integer())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("Fixed-width field '{}' requires 'start'", name)
+                        })?;
+                    let length = table
+                        .get("length")
+                        .and_then(|v| v.as_integer())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("Fixed-width field '{}' requires 'length'", name)
+                        })?;
+
+                    if start < 0 || length <= 0 {
+                        anyhow::bail!(
+                            "Fixed-width field '{}' requires start >= 0 and length > 0",
+                            name
+                        );
+                    }
+
+                    (start as usize, length as usize)
+                } else {
+                    (0, 0)
+                };
+
+                Ok(FieldSpec {
+                    name: name.to_string(),
+                    start,
+                    length,
+                    field_type: field_type.to_string(),
+                    trim,
+                })
+            }
+            _ => anyhow::bail!(
+                "'fields' entries must be tables (or strings in 'delimited' mode)"
+            ),
+        })
+        .collect()
+}
+
+fn parse_line(
+    line: &str,
+    mode: &str,
+    delimiter: &str,
+    fields: &[FieldSpec],
+) -> Result<HashMap<String, JsonValue>> {
+    let mut record = HashMap::new();
+
+    if mode == "fixed_width" {
+        // Slice by characters rather than bytes so multi-byte text stays intact
+        let chars: Vec<char> = line.chars().collect();
+        for field in fields {
+            let raw = if field.start < chars.len() {
+                let end = (field.start + field.length).min(chars.len());
+                Some(chars[field.start..end].iter().collect::<String>())
+            } else {
+                None
+            };
+            record.insert(field.name.clone(), cast_value(raw.as_deref(), field)?);
+        }
+    } else {
+        let parts: Vec<&str> = line.split(delimiter).collect();
+        for (index, field) in fields.iter().enumerate() {
+            record.insert(
+                field.name.clone(),
+                cast_value(parts.get(index).copied(), field)?,
+            );
+        }
+    }
+
+    Ok(record)
+}
+
+fn cast_value(raw: Option<&str>, field: &FieldSpec) -> Result<JsonValue> {
+    let Some(raw) = raw else {
+        return Ok(JsonValue::Null);
+    };
+
+    let value = if field.trim { raw.trim() } else { raw };
+
+    if value.is_empty() && field.field_type != "string" {
+        return Ok(JsonValue::Null);
+    }
+
+    match field.field_type.as_str() {
+        "int" => value
+            .trim()
+            .parse::<i64>()
+            .map(JsonValue::from)
+            .map_err(|_| {
+                anyhow::anyhow!("Field '{}': cannot parse '{}' as int", field.name, value)
+            }),
+        "float" => value
+            .trim()
+            .parse::<f64>()
+            .map(JsonValue::from)
+            .map_err(|_| {
+                anyhow::anyhow!("Field '{}': cannot parse '{}' as float", field.name, value)
+            }),
+        "bool" => match value.trim().to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => Ok(JsonValue::Bool(true)),
+            "false" | "f" | "no" | "n" | "0" => Ok(JsonValue::Bool(false)),
+            _ => anyhow::bail!("Field '{}': cannot parse '{}' as bool", field.name, value),
+        },
+        _ => Ok(JsonValue::String(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(name: &str, start: i64, length: i64, field_type: &str) -> toml::Value {
+        let mut table = toml::map::Map::new();
+        table.insert("name".to_string(), toml::Value::String(name.to_string()));
+        table.insert("start".to_string(), toml::Value::Integer(start));
+        table.insert("length".to_string(), toml::Value::Integer(length));
+        table.insert(
+            "type".to_string(),
+            toml::Value::String(field_type.to_string()),
+        );
+        toml::Value::Table(table)
+    }
+
+    #[tokio::test]
+    async fn test_parse_fixed_width_raw() {
+        let transform = ParseTextTransform;
+
+        let text = "00042Alice       0012.50\n00007Bob         0003.25\n";
+
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "input".to_string(),
+            DataFormat::Raw(text.as_bytes().to_vec()),
+        );
+
+        let mut config = HashMap::new();
+        config.insert(
+            "mode".to_string(),
+            toml::Value::String("fixed_width".to_string()),
+        );
+        config.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![
+                field("id", 0, 5, "int"),
+                field("name", 5, 12, "string"),
+                field("amount", 17, 7, "float"),
+            ]),
+        );
+
+        let result = transform.execute(inputs, &config).await.unwrap();
+        let records = result.as_record_batch().unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["id"], json!(42));
+        assert_eq!(records[0]["name"], json!("Alice"));
+        assert_eq!(records[0]["amount"], json!(12.5));
+        assert_eq!(records[1]["id"], json!(7));
+        assert_eq!(records[1]["name"], json!("Bob"));
+        assert_eq!(records[1]["amount"], json!(3.25));
+
+        let df = result.as_dataframe().unwrap();
+        assert_eq!(
+            df.column("id").unwrap().dtype(),
+            &polars::prelude::DataType::Int64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_pipe_delimited_column() {
+        let transform = ParseTextTransform;
+
+        let records = vec![HashMap::from([
+            ("source".to_string(), json!("app1")),
+            (
+                "line".to_string(),
+                json!("2024-01-01| ERROR |disk full| true"),
+            ),
+        ])];
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(records));
+
+        let mut alert = toml::map::Map::new();
+        alert.insert("name".to_string(), toml::Value::String("alert".to_string()));
+        alert.insert("type".to_string(), toml::Value::String("bool".to_string()));
+
+        let mut config = HashMap::new();
+        config.insert(
+            "mode".to_string(),
+            toml::Value::String("delimited".to_string()),
+        );
+        config.insert(
+            "delimiter".to_string(),
+            toml::Value::String("|".to_string()),
+        );
+        config.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("date".to_string()),
+                toml::Value::String("level".to_string()),
+                toml::Value::String("message".to_string()),
+                toml::Value::Table(alert),
+                toml::Value::String("extra".to_string()),
+            ]),
+        );
+
+        let result = transform.execute(inputs, &config).await.unwrap();
+        let records = result.as_record_batch().unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["source"], json!("app1"));
+        assert_eq!(records[0]["date"], json!("2024-01-01"));
+        assert_eq!(records[0]["level"], json!("ERROR"));
+        assert_eq!(records[0]["message"], json!("disk full"));
+        assert_eq!(records[0]["alert"], json!(true));
+        assert_eq!(records[0]["extra"], json!(null));
+        assert!(!records[0].contains_key("line"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_text_cast_error() {
+        let transform = ParseTextTransform;
+
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::Raw(b"abc|1".to_vec()));
+
+        let mut id = toml::map::Map::new();
+        id.insert("name".to_string(), toml::Value::String("id".to_string()));
+        id.insert("type".to_string(), toml::Value::String("int".to_string()));
+
+        let mut config = HashMap::new();
+        config.insert(
+            "mode".to_string(),
+            toml::Value::String("delimited".to_string()),
+        );
+        config.insert(
+            "delimiter".to_string(),
+            toml::Value::String("|".to_string()),
+        );
+        config.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![toml::Value::Table(id)]),
+        );
+
+        let result = transform.execute(inputs, &config).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parse_text_validation() {
+        let transform = ParseTextTransform;
+
+        // Missing mode
+        let config = HashMap::new();
+        assert!(transform.validate_config(&config).await.is_err());
+
+        // Fixed-width field without start/length
+        let mut config = HashMap::new();
+        config.insert(
+            "mode".to_string(),
+            toml::Value::String("fixed_width".to_string()),
+        );
+        config.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![toml::Value::String("id".to_string())]),
+        );
+        assert!(transform.validate_config(&config).await.is_err());
+
+        // Valid fixed-width config
+        config.insert(
+            "fields".to_string(),
+            toml::Value::Array(vec![field("id", 0, 5, "int")]),
+        );
+        assert!(transform.validate_config(&config).await.is_ok());
+    }
+}
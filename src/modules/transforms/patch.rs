@@ -0,0 +1,365 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct PatchTransform;
+
+#[async_trait]
+impl Stage for PatchTransform {
+    fn name(&self) -> &str {
+        "patch.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "base".to_string(),
+            toml::Value::String("customers".to_string()),
+        );
+        example1.insert(
+            "patch".to_string(),
+            toml::Value::String("customer_updates".to_string()),
+        );
+        example1.insert("key".to_string(), toml::Value::String("id".to_string()));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "key".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("region".to_string()),
+                toml::Value::String("sku".to_string()),
+            ]),
+        );
+
+        StageMetadata::builder("patch.apply", StageCategory::Transform)
+            .description("Apply keyed partial updates from a patch input onto a base input")
+            .long_description(
+                "Merges a patch set onto a base dataset by key. For rows whose key matches, \
+                every non-null field in the patch row overwrites the base value and other fields \
+                are kept. Patch rows with no matching base row are appended as inserts, and base \
+                rows without a patch pass through unchanged. The 'base' and 'patch' options name \
+                the input stages; they default to inputs literally called 'base' and 'patch'.",
+            )
+            .parameter(ConfigParameter::required(
+                "key",
+                ParameterType::Array,
+                "Key column(s) used to match patch rows to base rows (string or array of strings)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "base",
+                ParameterType::String,
+                "base",
+                "Input stage providing the base rows",
+            ))
+            .parameter(ConfigParameter::optional(
+                "patch",
+                ParameterType::String,
+                "patch",
+                "Input stage providing the patch rows",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Update customers",
+                example1,
+                Some("Apply changed fields from customer_updates onto customers by id"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Composite key",
+                example2,
+                Some("Match rows on region and sku"),
+            ))
+            .tag("patch")
+            .tag("merge")
+            .tag("upsert")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        mut inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let key = parse_key(config)?;
+        let base_name = config
+            .get("base")
+            .and_then(|v| v.as_str())
+            .unwrap_or("base");
+        let patch_name = config
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .unwrap_or("patch");
+
+        let mut available: Vec<&String> = inputs.keys().collect();
+        available.sort();
+        let available = format!("{:?}", available);
+
+        let base = inputs.remove(base_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Patch transform: base input '{}' not found (available inputs: {})",
+                base_name,
+                available
+            )
+        })?;
+        let patch = inputs.remove(patch_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Patch transform: patch input '{}' not found (available inputs: {})",
+                patch_name,
+                available
+            )
+        })?;
+
+        let mut rows = base.as_record_batch()?;
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for (i, row) in rows.iter().enumerate() {
+            // First occurrence wins if the base has duplicate keys
+            index.entry(row_key(row, &key)?).or_insert(i);
+        }
+
+        for patch_row in patch.as_record_batch()? {
+            let patch_key = row_key(&patch_row, &key)?;
+            match index.get(&patch_key) {
+                Some(&i) => {
+                    let target = &mut rows[i];
+                    for (field, value) in patch_row {
+                        if !value.is_null() {
+                            target.insert(field, value);
+                        }
+                    }
+                }
+                None => {
+                    index.insert(patch_key, rows.len());
+                    rows.push(patch_row);
+                }
+            }
+        }
+
+        Ok(DataFormat::RecordBatch(rows))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        let key = parse_key(config)?;
+        if key.is_empty() {
+            anyhow::bail!("'key' must name at least one column");
+        }
+
+        for option in ["base", "patch"] {
+            if let Some(value) = config.get(option) {
+                if value.as_str().is_none() {
+                    anyhow::bail!("'{}' must be a string naming an input stage", option);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_key(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    string_list_option(config, "key")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'key' configuration"))
+}
+
+/// Build a comparable key from the key columns of a row
+fn row_key(row: &HashMap<String, JsonValue>, key: &[String]) -> Result<String> {
+    let values: Vec<JsonValue> = key
+        .iter()
+        .map(|k| normalize_key_value(row.get(k).unwrap_or(&JsonValue::Null)))
+        .collect();
+    Ok(serde_json::to_string(&values)?)
+}
+
+/// Whole floats become integers, so `1` and `1.0` are the same key
+fn normalize_key_value(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Number(n) if !n.is_i64() && !n.is_u64() => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 => {
+                JsonValue::from(f as i64)
+            }
+            _ => value.clone(),
+        },
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(fields: &[(&str, JsonValue)]) -> HashMap<String, JsonValue> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    async fn run_patch(
+        base: Vec<HashMap<String, JsonValue>>,
+        patch: Vec<HashMap<String, JsonValue>>,
+        config: HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let mut inputs = HashMap::new();
+        inputs.insert("base".to_string(), DataFormat::RecordBatch(base));
+        inputs.insert("patch".to_string(), DataFormat::RecordBatch(patch));
+
+        PatchTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_patch_update_insert_and_passthrough() {
+        let base = vec![
+            record(&[
+                ("id", json!(1)),
+                ("name", json!("Alice")),
+                ("tier", json!("gold")),
+            ]),
+            record(&[
+                ("id", json!(2)),
+                ("name", json!("Bob")),
+                ("tier", json!("silver")),
+            ]),
+        ];
+        let patch = vec![
+            // Partial update: only tier changes, null name must not clobber
+            record(&[
+                ("id", json!(1)),
+                ("name", JsonValue::Null),
+                ("tier", json!("platinum")),
+            ]),
+            // Insert
+            record(&[("id", json!(3)), ("name", json!("Carol"))]),
+        ];
+
+        let mut config = HashMap::new();
+        config.insert("key".to_string(), toml::Value::String("id".to_string()));
+
+        let output = run_patch(base, patch, config).await;
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0]["name"], json!("Alice"));
+        assert_eq!(output[0]["tier"], json!("platinum"));
+        // Untouched base row
+        assert_eq!(output[1]["name"], json!("Bob"));
+        assert_eq!(output[1]["tier"], json!("silver"));
+        // Inserted row
+        assert_eq!(output[2]["id"], json!(3));
+        assert_eq!(output[2]["name"], json!("Carol"));
+    }
+
+    #[tokio::test]
+    async fn test_patch_matches_int_and_float_keys() {
+        // An integer base key column patched from a float one, e.g. after a CSV round trip
+        let base = vec![
+            record(&[("id", json!(1)), ("tier", json!("gold"))]),
+            record(&[("id", json!(2)), ("tier", json!("silver"))]),
+        ];
+        let patch = vec![
+            record(&[("id", json!(1.0)), ("tier", json!("platinum"))]),
+            record(&[("id", json!(2.5)), ("tier", json!("bronze"))]),
+        ];
+
+        let mut config = HashMap::new();
+        config.insert("key".to_string(), toml::Value::String("id".to_string()));
+
+        let output = run_patch(base, patch, config).await;
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0]["tier"], json!("platinum"));
+        assert_eq!(output[1]["tier"], json!("silver"));
+        // A fractional key matches no integer and is inserted
+        assert_eq!(output[2]["id"], json!(2.5));
+    }
+
+    #[tokio::test]
+    async fn test_patch_composite_key_and_named_inputs() {
+        let base = vec![
+            record(&[
+                ("region", json!("eu")),
+                ("sku", json!("A")),
+                ("qty", json!(1)),
+            ]),
+            record(&[
+                ("region", json!("us")),
+                ("sku", json!("A")),
+                ("qty", json!(2)),
+            ]),
+        ];
+        let patch = vec![record(&[
+            ("region", json!("us")),
+            ("sku", json!("A")),
+            ("qty", json!(5)),
+        ])];
+
+        let mut inputs = HashMap::new();
+        inputs.insert("inventory".to_string(), DataFormat::RecordBatch(base));
+        inputs.insert("changes".to_string(), DataFormat::RecordBatch(patch));
+
+        let mut config = HashMap::new();
+        config.insert(
+            "key".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("region".to_string()),
+                toml::Value::String("sku".to_string()),
+            ]),
+        );
+        config.insert(
+            "base".to_string(),
+            toml::Value::String("inventory".to_string()),
+        );
+        config.insert(
+            "patch".to_string(),
+            toml::Value::String("changes".to_string()),
+        );
+
+        let output = PatchTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap();
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0]["qty"], json!(1));
+        assert_eq!(output[1]["qty"], json!(5));
+    }
+
+    #[tokio::test]
+    async fn test_patch_missing_input() {
+        let mut inputs = HashMap::new();
+        inputs.insert("base".to_string(), DataFormat::RecordBatch(vec![]));
+
+        let mut config = HashMap::new();
+        config.insert("key".to_string(), toml::Value::String("id".to_string()));
+
+        let err = match PatchTransform.execute(inputs, &config).await {
+            Ok(_) => panic!("expected missing patch input to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("patch input 'patch' not found"));
+    }
+
+    #[tokio::test]
+    async fn test_patch_validation() {
+        let transform = PatchTransform;
+
+        assert!(transform.validate_config(&HashMap::new()).await.is_err());
+
+        let mut config = HashMap::new();
+        config.insert("key".to_string(), toml::Value::String("id".to_string()));
+        assert!(transform.validate_config(&config).await.is_ok());
+
+        config.insert("base".to_string(), toml::Value::Integer(1));
+        assert!(transform.validate_config(&config).await.is_err());
+    }
+}
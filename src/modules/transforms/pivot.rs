@@ -0,0 +1,352 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::lazy::frame::pivot::pivot_stable;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Reshapes long rows into wide columns, one per distinct value of a column
+pub struct PivotTransform;
+
+const AGGREGATIONS: [&str; 4] = ["first", "sum", "mean", "count"];
+
+struct Options {
+    index: Vec<String>,
+    columns: String,
+    values: String,
+    aggregate: Option<String>,
+}
+
+fn required_str(config: &HashMap<String, toml::Value>, name: &str) -> Result<String> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) => Ok(s.clone()),
+        Some(_) => anyhow::bail!("'{}' must be a string", name),
+        None => anyhow::bail!("Pivot requires '{}' configuration", name),
+    }
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let index = string_list(config, "index")?;
+    if index.is_empty() {
+        anyhow::bail!("Pivot requires 'index' configuration");
+    }
+    let columns = required_str(config, "columns")?;
+    let values = required_str(config, "values")?;
+    for (name, column) in [("columns", &columns), ("values", &values)] {
+        if index.contains(column) {
+            anyhow::bail!(
+                "Pivot: '{}' column '{}' is also an index column",
+                name,
+                column
+            );
+        }
+    }
+    if columns == values {
+        anyhow::bail!("Pivot: 'columns' and 'values' must be different columns");
+    }
+
+    let aggregate = match config.get("aggregate") {
+        Some(toml::Value::String(s)) if AGGREGATIONS.contains(&s.as_str()) => Some(s.clone()),
+        Some(toml::Value::String(s)) => anyhow::bail!(
+            "Invalid aggregate '{}'. Supported: {}",
+            s,
+            AGGREGATIONS.join(", ")
+        ),
+        Some(_) => anyhow::bail!("'aggregate' must be a string"),
+        None => None,
+    };
+
+    Ok(Options {
+        index,
+        columns,
+        values,
+        aggregate,
+    })
+}
+
+impl Options {
+    /// Aggregation applied to the values sharing one index and `columns` value
+    fn agg_expr(&self) -> Option<Expr> {
+        // Pivot aggregations refer to the values column by an empty name
+        let values = col("");
+        self.aggregate.as_deref().map(|aggregate| match aggregate {
+            "sum" => values.sum(),
+            "mean" => values.mean(),
+            "count" => values.count().cast(DataType::Int64),
+            _ => values.first(),
+        })
+    }
+
+    /// Fails when a cell would receive several values and no aggregate combines them
+    fn check_unique_cells(&self, df: &DataFrame) -> Result<()> {
+        let keys: Vec<Expr> = self
+            .index
+            .iter()
+            .chain(std::iter::once(&self.columns))
+            .map(|c| col(c.as_str()))
+            .collect();
+        let duplicates = df
+            .clone()
+            .lazy()
+            .group_by(keys)
+            .agg([len().alias("__pivot_rows")])
+            .filter(col("__pivot_rows").gt(lit(1)))
+            .limit(1)
+            .collect()?;
+        if duplicates.height() > 0 {
+            anyhow::bail!(
+                "Pivot: several rows share the same index and '{}' value; set 'aggregate' to combine them",
+                self.columns
+            );
+        }
+        Ok(())
+    }
+
+    fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        for name in self.index.iter().chain([&self.columns, &self.values]) {
+            if df.column(name).is_err() {
+                anyhow::bail!("Pivot: column '{}' not found", name);
+            }
+        }
+
+        let agg_expr = self.agg_expr();
+        if agg_expr.is_none() {
+            self.check_unique_cells(df)?;
+        }
+
+        pivot_stable(
+            df,
+            [self.columns.as_str()],
+            Some(self.index.iter().map(|c| c.as_str())),
+            Some([self.values.as_str()]),
+            false,
+            agg_expr,
+            None,
+        )
+        .map_err(|e| anyhow::anyhow!("Pivot failed: {}", e))
+    }
+}
+
+#[async_trait]
+impl Stage for PivotTransform {
+    fn name(&self) -> &str {
+        "pivot.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert(
+            "index".to_string(),
+            toml::Value::Array(vec![toml::Value::String("region".to_string())]),
+        );
+        example.insert(
+            "columns".to_string(),
+            toml::Value::String("quarter".to_string()),
+        );
+        example.insert(
+            "values".to_string(),
+            toml::Value::String("revenue".to_string()),
+        );
+        example.insert(
+            "aggregate".to_string(),
+            toml::Value::String("sum".to_string()),
+        );
+
+        StageMetadata::builder("pivot.apply", StageCategory::Transform)
+            .description("Reshape long rows into wide columns (pivot)")
+            .long_description(
+                "The inverse of an unpivot: rows are grouped by the 'index' columns, and each \
+                distinct value of the 'columns' column becomes a new column holding the \
+                'values' column for that group. New columns appear in order of first \
+                appearance, and groups without a value for a column get null. Without \
+                'aggregate' every group may hold at most one row per 'columns' value, and the \
+                stage fails rather than pick one; set 'aggregate' to combine duplicates. Other \
+                columns are dropped.",
+            )
+            .parameter(ConfigParameter::required(
+                "index",
+                ParameterType::Array,
+                "Columns identifying each output row",
+            ))
+            .parameter(ConfigParameter::required(
+                "columns",
+                ParameterType::String,
+                "Column whose distinct values become new columns",
+            ))
+            .parameter(ConfigParameter::required(
+                "values",
+                ParameterType::String,
+                "Column filling the new columns",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "aggregate",
+                    ParameterType::String,
+                    "none",
+                    "How to combine several values for one cell",
+                )
+                .with_validation(ParameterValidation::allowed_values(AGGREGATIONS)),
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Revenue per region and quarter",
+                example,
+                Some("region, quarter, revenue rows become one row per region with a column per quarter"),
+            ))
+            .tag("pivot")
+            .tag("reshape")
+            .tag("aggregate")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Pivot transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        Ok(DataFormat::DataFrame(options.apply(&df)?))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales_frame() -> DataFrame {
+        df![
+            "region" => ["north", "north", "south", "north", "south"],
+            "quarter" => ["Q1", "Q2", "Q1", "Q1", "Q2"],
+            "revenue" => [100i64, 200, 50, 25, 75],
+        ]
+        .unwrap()
+    }
+
+    fn config(aggregate: Option<&str>) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "index".to_string(),
+            toml::Value::String("region".to_string()),
+        );
+        config.insert(
+            "columns".to_string(),
+            toml::Value::String("quarter".to_string()),
+        );
+        config.insert(
+            "values".to_string(),
+            toml::Value::String("revenue".to_string()),
+        );
+        if let Some(aggregate) = aggregate {
+            config.insert(
+                "aggregate".to_string(),
+                toml::Value::String(aggregate.to_string()),
+            );
+        }
+        config
+    }
+
+    async fn pivot(config: HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(sales_frame()));
+        PivotTransform
+            .execute(inputs, &config)
+            .await?
+            .as_dataframe()
+    }
+
+    fn ints(df: &DataFrame, name: &str) -> Vec<Option<i64>> {
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Int64)
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_pivot_sums_duplicate_cells() {
+        let result = pivot(config(Some("sum"))).await.unwrap();
+        assert_eq!(result.get_column_names_str(), vec!["region", "Q1", "Q2"]);
+
+        let regions: Vec<&str> = result
+            .column("region")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(regions, vec!["north", "south"]);
+        assert_eq!(ints(&result, "Q1"), vec![Some(125), Some(50)]);
+        assert_eq!(ints(&result, "Q2"), vec![Some(200), Some(75)]);
+
+        let counts = pivot(config(Some("count"))).await.unwrap();
+        assert_eq!(ints(&counts, "Q1"), vec![Some(2), Some(1)]);
+    }
+
+    #[tokio::test]
+    async fn test_pivot_rejects_duplicates_without_aggregate() {
+        let err = pivot(config(None)).await.unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Pivot: several rows share the same index and 'quarter' value; set 'aggregate' to combine them"
+        );
+
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "input".to_string(),
+            DataFormat::DataFrame(sales_frame().slice(0, 3)),
+        );
+        let result = PivotTransform
+            .execute(inputs, &config(None))
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap();
+        assert_eq!(ints(&result, "Q1"), vec![Some(100), Some(50)]);
+        assert_eq!(ints(&result, "Q2"), vec![Some(200), None]);
+    }
+
+    #[tokio::test]
+    async fn test_pivot_rejects_bad_config() {
+        let mut bad_column = config(None);
+        bad_column.insert(
+            "values".to_string(),
+            toml::Value::String("profit".to_string()),
+        );
+        let err = pivot(bad_column).await.unwrap_err().to_string();
+        assert_eq!(err, "Pivot: column 'profit' not found");
+
+        assert!(PivotTransform
+            .validate_config(&config(Some("median")))
+            .await
+            .is_err());
+        let mut overlapping = config(None);
+        overlapping.insert(
+            "columns".to_string(),
+            toml::Value::String("region".to_string()),
+        );
+        assert!(PivotTransform.validate_config(&overlapping).await.is_err());
+        assert!(PivotTransform
+            .validate_config(&HashMap::new())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,615 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Scores each row by the weighted share of checks it passes
+pub struct QualityScoreTransform;
+
+const DEFAULT_SCORE_COLUMN: &str = "_quality";
+const KINDS: [&str; 3] = ["not_null", "regex", "range"];
+const OUTPUTS: [&str; 3] = ["all", "passing", "failing"];
+
+#[derive(Debug)]
+enum Rule {
+    /// Share of the columns that are not null
+    NotNull,
+    /// Share of the non-null values that match the pattern
+    Regex(Regex),
+    /// Share of the non-null values within the inclusive bounds
+    Range { min: Option<f64>, max: Option<f64> },
+}
+
+#[derive(Debug)]
+struct Check {
+    name: String,
+    columns: Vec<String>,
+    rule: Rule,
+    weight: f64,
+}
+
+struct QualityOptions {
+    checks: Vec<Check>,
+    score_column: String,
+    breakdown_column: Option<String>,
+    threshold: Option<f64>,
+    output: String,
+}
+
+fn number(table: &toml::Table, key: &str) -> Option<f64> {
+    table
+        .get(key)
+        .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|n| n as f64)))
+}
+
+fn parse_check(i: usize, entry: &toml::Value) -> Result<Check> {
+    let table = entry
+        .as_table()
+        .ok_or_else(|| anyhow::anyhow!("Check {} must be a table", i + 1))?;
+
+    let kind = table
+        .get("kind")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Check {} requires a 'kind' string", i + 1))?;
+    let name = match table.get("name") {
+        None => kind.to_string(),
+        Some(toml::Value::String(s)) if !s.is_empty() => s.clone(),
+        Some(_) => anyhow::bail!("Check {}: 'name' must be a non-empty string", i + 1),
+    };
+
+    let columns: Vec<String> = match table.get("columns") {
+        Some(toml::Value::String(s)) => vec![s.clone()],
+        Some(toml::Value::Array(arr)) => arr
+            .iter()
+            .map(|v| {
+                v.as_str().map(|s| s.to_string()).ok_or_else(|| {
+                    anyhow::anyhow!("Check '{}': 'columns' must contain only strings", name)
+                })
+            })
+            .collect::<Result<_>>()?,
+        Some(_) => anyhow::bail!(
+            "Check '{}': 'columns' must be a string or array of strings",
+            name
+        ),
+        None => anyhow::bail!("Check '{}' requires 'columns'", name),
+    };
+    if columns.is_empty() {
+        anyhow::bail!("Check '{}': 'columns' must name at least one column", name);
+    }
+
+    let rule = match kind {
+        "not_null" => Rule::NotNull,
+        "regex" => {
+            let pattern = table
+                .get("pattern")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Check '{}' requires a 'pattern' string", name))?;
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Check '{}': invalid pattern: {}", name, e))?;
+            Rule::Regex(regex)
+        }
+        "range" => {
+            for key in ["min", "max"] {
+                if table.contains_key(key) && number(table, key).is_none() {
+                    anyhow::bail!("Check '{}': '{}' must be a number", name, key);
+                }
+            }
+            let (min, max) = (number(table, "min"), number(table, "max"));
+            match (min, max) {
+                (None, None) => {
+                    anyhow::bail!("Check '{}' requires 'min', 'max' or both", name)
+                }
+                (Some(min), Some(max)) if min > max => anyhow::bail!(
+                    "Check '{}': 'min' ({}) is greater than 'max' ({})",
+                    name,
+                    min,
+                    max
+                ),
+                _ => Rule::Range { min, max },
+            }
+        }
+        other => anyhow::bail!(
+            "Check {}: invalid kind: {}. Must be one of: {}",
+            i + 1,
+            other,
+            KINDS.join(", ")
+        ),
+    };
+
+    let weight = match table.get("weight") {
+        None => 1.0,
+        Some(_) => number(table, "weight")
+            .ok_or_else(|| anyhow::anyhow!("Check '{}': 'weight' must be a number", name))?,
+    };
+    if !(weight > 0.0 && weight.is_finite()) {
+        anyhow::bail!(
+            "Check '{}': 'weight' must be positive, got {}",
+            name,
+            weight
+        );
+    }
+
+    Ok(Check {
+        name,
+        columns,
+        rule,
+        weight,
+    })
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<QualityOptions> {
+    let checks: Vec<Check> = match config.get("checks") {
+        Some(toml::Value::Array(arr)) if !arr.is_empty() => arr
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| parse_check(i, entry))
+            .collect::<Result<_>>()?,
+        Some(_) => anyhow::bail!("'checks' must be a non-empty array of tables"),
+        None => anyhow::bail!("Missing required 'checks' configuration"),
+    };
+    for (i, check) in checks.iter().enumerate() {
+        if checks[..i].iter().any(|c| c.name == check.name) {
+            anyhow::bail!(
+                "Duplicate check name '{}'; give each check a unique 'name'",
+                check.name
+            );
+        }
+    }
+
+    let string = |name: &str| -> Result<Option<String>> {
+        match config.get(name) {
+            None => Ok(None),
+            Some(toml::Value::String(s)) if !s.is_empty() => Ok(Some(s.clone())),
+            Some(_) => anyhow::bail!("'{}' must be a non-empty string", name),
+        }
+    };
+    let score_column = string("score_column")?.unwrap_or_else(|| DEFAULT_SCORE_COLUMN.to_string());
+    let breakdown_column = string("breakdown_column")?;
+    let output = string("output")?.unwrap_or_else(|| "all".to_string());
+    if !OUTPUTS.contains(&output.as_str()) {
+        anyhow::bail!(
+            "Invalid output: {}. Must be one of: {}",
+            output,
+            OUTPUTS.join(", ")
+        );
+    }
+
+    let threshold = match config.get("threshold") {
+        None => None,
+        Some(value) => Some(
+            value
+                .as_float()
+                .or_else(|| value.as_integer().map(|n| n as f64))
+                .filter(|t| (0.0..=1.0).contains(t))
+                .ok_or_else(|| anyhow::anyhow!("'threshold' must be a number from 0 to 1"))?,
+        ),
+    };
+    if threshold.is_none() && output != "all" {
+        anyhow::bail!("output = '{}' requires a 'threshold'", output);
+    }
+
+    Ok(QualityOptions {
+        checks,
+        score_column,
+        breakdown_column,
+        threshold,
+        output,
+    })
+}
+
+fn column<'a>(df: &'a DataFrame, name: &str) -> Result<&'a Column> {
+    df.column(name)
+        .map_err(|_| anyhow::anyhow!("Quality score: column '{}' not found", name))
+}
+
+/// Per-row share of passing values for one check
+///
+/// `None` when the check has nothing to judge in that row, which only
+/// happens to value checks on a row whose columns are all null.
+fn check_scores(df: &DataFrame, check: &Check) -> Result<Vec<Option<f64>>> {
+    let mut passed = vec![0usize; df.height()];
+    let mut judged = vec![0usize; df.height()];
+
+    for name in &check.columns {
+        let column = column(df, name)?;
+        let results: Vec<Option<bool>> = match &check.rule {
+            Rule::NotNull => column
+                .is_not_null()
+                .into_iter()
+                .map(|v| Some(v.unwrap_or(false)))
+                .collect(),
+            Rule::Regex(regex) => column
+                .as_materialized_series()
+                .cast(&DataType::String)?
+                .str()?
+                .into_iter()
+                .map(|v| v.map(|s| regex.is_match(s)))
+                .collect(),
+            Rule::Range { min, max } => {
+                if !column.dtype().is_numeric() {
+                    anyhow::bail!(
+                        "Quality score: check '{}' needs a numeric column, but '{}' is {}",
+                        check.name,
+                        name,
+                        column.dtype()
+                    );
+                }
+                column
+                    .as_materialized_series()
+                    .cast(&DataType::Float64)?
+                    .f64()?
+                    .into_iter()
+                    .map(|v| {
+                        v.map(|v| min.is_none_or(|min| v >= min) && max.is_none_or(|max| v <= max))
+                    })
+                    .collect()
+            }
+        };
+
+        for (row, result) in results.into_iter().enumerate() {
+            if let Some(ok) = result {
+                judged[row] += 1;
+                passed[row] += ok as usize;
+            }
+        }
+    }
+
+    Ok(passed
+        .into_iter()
+        .zip(judged)
+        .map(|(passed, judged)| (judged > 0).then(|| passed as f64 / judged as f64))
+        .collect())
+}
+
+/// Weighted average over the checks that judged the row; a row no check
+/// judged has nothing wrong with it
+fn combine(checks: &[Check], scores: &[Vec<Option<f64>>], row: usize) -> f64 {
+    let (total, weight) = checks
+        .iter()
+        .zip(scores)
+        .filter_map(|(check, scores)| scores[row].map(|s| (s * check.weight, check.weight)))
+        .fold((0.0, 0.0), |(total, weight), (s, w)| {
+            (total + s, weight + w)
+        });
+    if weight > 0.0 {
+        total / weight
+    } else {
+        1.0
+    }
+}
+
+#[async_trait]
+impl Stage for QualityScoreTransform {
+    fn name(&self) -> &str {
+        "quality_score.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let check = |pairs: Vec<(&str, toml::Value)>| {
+            toml::Value::Table(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+        };
+        let strings = |values: &[&str]| {
+            toml::Value::Array(
+                values
+                    .iter()
+                    .map(|s| toml::Value::String(s.to_string()))
+                    .collect(),
+            )
+        };
+
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "checks".to_string(),
+            toml::Value::Array(vec![
+                check(vec![
+                    ("kind", toml::Value::String("not_null".to_string())),
+                    ("columns", strings(&["email", "phone", "country"])),
+                    ("weight", toml::Value::Integer(2)),
+                ]),
+                check(vec![
+                    ("kind", toml::Value::String("regex".to_string())),
+                    ("columns", toml::Value::String("email".to_string())),
+                    (
+                        "pattern",
+                        toml::Value::String("^[^@\\s]+@[^@\\s]+$".to_string()),
+                    ),
+                ]),
+                check(vec![
+                    ("kind", toml::Value::String("range".to_string())),
+                    ("columns", toml::Value::String("age".to_string())),
+                    ("min", toml::Value::Integer(0)),
+                    ("max", toml::Value::Integer(120)),
+                ]),
+            ]),
+        );
+        example1.insert(
+            "breakdown_column".to_string(),
+            toml::Value::String("_quality_breakdown".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "checks".to_string(),
+            toml::Value::Array(vec![check(vec![
+                ("kind", toml::Value::String("not_null".to_string())),
+                ("columns", strings(&["sku", "price", "category"])),
+            ])]),
+        );
+        example2.insert("threshold".to_string(), toml::Value::Float(0.8));
+        example2.insert(
+            "output".to_string(),
+            toml::Value::String("failing".to_string()),
+        );
+
+        StageMetadata::builder("quality_score.apply", StageCategory::Transform)
+            .description("Score each row from 0 to 1 by weighted completeness and validity checks")
+            .long_description(
+                "Runs each of the 'checks' on every row and writes their weighted average to \
+                'score_column'. A 'not_null' check scores the share of its columns that are not \
+                null; 'regex' and 'range' checks score the share of their non-null values that \
+                match 'pattern' or lie within 'min'..'max', and are left out of a row whose \
+                columns are all null. A row no check applies to scores 1. 'breakdown_column' adds \
+                each check's score as a JSON object keyed by check name. With a 'threshold', \
+                'output' can emit only the 'passing' rows (score at or above it) or the \
+                'failing' ones, so a second stage with the same settings can route the rest to \
+                a side output.",
+            )
+            .parameter(ConfigParameter::required(
+                "checks",
+                ParameterType::Array,
+                "Array of {kind, columns, weight, name} tables; regex checks take 'pattern', \
+                range checks 'min' and/or 'max'",
+            ))
+            .parameter(ConfigParameter::optional(
+                "score_column",
+                ParameterType::String,
+                DEFAULT_SCORE_COLUMN,
+                "Column receiving the 0-1 score",
+            ))
+            .parameter(ConfigParameter::optional(
+                "breakdown_column",
+                ParameterType::String,
+                "none",
+                "Column receiving each check's score as a JSON object",
+            ))
+            .parameter(ConfigParameter::optional(
+                "threshold",
+                ParameterType::Float,
+                "none",
+                "Minimum score of a passing row, from 0 to 1",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "all",
+                    "Emit all rows scored, only the passing, or only the failing rows",
+                )
+                .with_validation(ParameterValidation::allowed_values(OUTPUTS)),
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Score customer records",
+                example1,
+                Some("Weight completeness double, and validate email and age"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Triage incomplete products",
+                example2,
+                Some("Emit only products missing more than one in five key fields"),
+            ))
+            .tag("quality")
+            .tag("validation")
+            .tag("score")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Quality score transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let mut df = data.as_dataframe()?;
+
+        let scores: Vec<Vec<Option<f64>>> = options
+            .checks
+            .iter()
+            .map(|check| check_scores(&df, check))
+            .collect::<Result<_>>()?;
+        let total: Vec<f64> = (0..df.height())
+            .map(|row| combine(&options.checks, &scores, row))
+            .collect();
+
+        if let Some(name) = &options.breakdown_column {
+            let breakdown: Vec<String> = (0..df.height())
+                .map(|row| {
+                    let fields: serde_json::Map<String, serde_json::Value> = options
+                        .checks
+                        .iter()
+                        .zip(&scores)
+                        .map(|(check, scores)| (check.name.clone(), scores[row].into()))
+                        .collect();
+                    serde_json::Value::Object(fields).to_string()
+                })
+                .collect();
+            df.with_column(Series::new(name.as_str().into(), breakdown))?;
+        }
+        df.with_column(Series::new(options.score_column.as_str().into(), &total))?;
+
+        let df = match (options.threshold, options.output.as_str()) {
+            (Some(threshold), "passing" | "failing") => {
+                let passing: BooleanChunked = total.iter().map(|s| *s >= threshold).collect();
+                if options.output == "passing" {
+                    df.filter(&passing)?
+                } else {
+                    df.filter(&!&passing)?
+                }
+            }
+            _ => df,
+        };
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(source: &str) -> HashMap<String, toml::Value> {
+        toml::from_str::<toml::Table>(source)
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    async fn score(config: &HashMap<String, toml::Value>) -> DataFrame {
+        let df = df! {
+            "id" => [1i64, 2, 3, 4],
+            "email" => [Some("a@x.io"), None, Some("not-an-email"), None],
+            "phone" => [Some("555-0100"), Some("555-0101"), Some("555-0102"), None],
+            "age" => [Some(34i64), Some(41), Some(250), None],
+        }
+        .unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        QualityScoreTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    fn values(df: &DataFrame, column: &str) -> Vec<f64> {
+        df.column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_completeness_scores_proportionally() {
+        let df = score(&checks(
+            r#"
+            [[checks]]
+            kind = "not_null"
+            columns = ["email", "phone", "age"]
+            "#,
+        ))
+        .await;
+
+        // Fully populated rows score 1, others lose a third per missing column
+        assert_eq!(values(&df, "_quality"), vec![1.0, 2.0 / 3.0, 1.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_checks_and_breakdown() {
+        let df = score(&checks(
+            r#"
+            breakdown_column = "detail"
+
+            [[checks]]
+            kind = "not_null"
+            columns = ["email", "phone"]
+            weight = 2
+
+            [[checks]]
+            kind = "regex"
+            columns = "email"
+            pattern = "^[^@]+@[^@]+$"
+
+            [[checks]]
+            kind = "range"
+            columns = "age"
+            min = 0
+            max = 120
+            "#,
+        ))
+        .await;
+
+        // Row 1 passes everything; row 2 lacks an email, so only its
+        // completeness (1/2, weight 2) and age (1) count: (1 + 1) / 3
+        let scores = values(&df, "_quality");
+        assert_eq!(scores[0], 1.0);
+        assert!((scores[1] - 2.0 / 3.0).abs() < 1e-9);
+        // Row 3 is complete but its email and age are invalid: 2 / 4
+        assert_eq!(scores[2], 0.5);
+        // Row 4 is empty: only completeness judges it
+        assert_eq!(scores[3], 0.0);
+
+        let detail = df.column("detail").unwrap().str().unwrap().get(1).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(detail).unwrap(),
+            serde_json::json!({"not_null": 0.5, "regex": null, "range": 1.0})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_threshold_routing() {
+        let source = r#"
+            threshold = 0.9
+            output = "OUTPUT"
+            score_column = "score"
+
+            [[checks]]
+            kind = "not_null"
+            columns = ["email", "phone", "age"]
+            "#;
+
+        let passing = score(&checks(&source.replace("OUTPUT", "passing"))).await;
+        assert_eq!(
+            passing.column("id").unwrap().i64().unwrap().to_vec(),
+            vec![Some(1), Some(3)]
+        );
+        let failing = score(&checks(&source.replace("OUTPUT", "failing"))).await;
+        assert_eq!(
+            failing.column("id").unwrap().i64().unwrap().to_vec(),
+            vec![Some(2), Some(4)]
+        );
+        assert_eq!(values(&failing, "score"), vec![2.0 / 3.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        for source in [
+            "checks = []",
+            "[[checks]]\nkind = \"unique\"\ncolumns = \"id\"",
+            "[[checks]]\nkind = \"regex\"\ncolumns = \"email\"",
+            "[[checks]]\nkind = \"regex\"\ncolumns = \"email\"\npattern = \"(\"",
+            "[[checks]]\nkind = \"range\"\ncolumns = \"age\"",
+            "[[checks]]\nkind = \"range\"\ncolumns = \"age\"\nmin = 5\nmax = 1",
+            "[[checks]]\nkind = \"not_null\"\ncolumns = \"id\"\nweight = 0",
+            "[[checks]]\nkind = \"not_null\"\ncolumns = \"id\"\n[[checks]]\nkind = \"not_null\"\ncolumns = \"age\"",
+            "output = \"failing\"\n[[checks]]\nkind = \"not_null\"\ncolumns = \"id\"",
+            "threshold = 1.5\n[[checks]]\nkind = \"not_null\"\ncolumns = \"id\"",
+        ] {
+            assert!(
+                QualityScoreTransform
+                    .validate_config(&checks(source))
+                    .await
+                    .is_err(),
+                "{}",
+                source
+            );
+        }
+    }
+}
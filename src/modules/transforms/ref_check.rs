@@ -0,0 +1,670 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use polars::prelude::*;
+use sqlx::any::{AnyRow, AnyTypeInfoKind};
+use sqlx::{AnyConnection, AssertSqlSafe, Connection, Row, ValueRef};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Ref Check Transform
+/// Flags rows whose key has no matching row in a database table
+pub struct RefCheckTransform {
+    /// Lookup results by table and column, kept for the lifetime of the stage
+    known: Mutex<HashMap<String, HashMap<String, bool>>>,
+}
+
+impl Default for RefCheckTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RefCheckTransform {
+    pub fn new() -> Self {
+        Self {
+            known: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+const DEFAULT_FLAG_COLUMN: &str = "_ref_valid";
+const DEFAULT_BATCH_SIZE: usize = 500;
+const OUTPUTS: [&str; 3] = ["all", "valid", "invalid"];
+/// Missing keys named in the error of `on_invalid = "fail"`
+const REPORTED_KEYS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl Dialect {
+    fn from_uri(uri: &str) -> Result<Self> {
+        if uri.starts_with("postgres://") || uri.starts_with("postgresql://") {
+            Ok(Dialect::Postgres)
+        } else if uri.starts_with("mysql://") {
+            Ok(Dialect::MySql)
+        } else if uri.starts_with("sqlite:") {
+            Ok(Dialect::Sqlite)
+        } else {
+            // Only the scheme: the rest of the URI may hold a password
+            anyhow::bail!(
+                "Unsupported database URI scheme '{}': expected postgres://, mysql:// or sqlite:",
+                uri.split(':').next().unwrap_or_default()
+            )
+        }
+    }
+
+    /// Bind placeholder for the `n`th (1-based) parameter of a statement
+    fn placeholder(self, n: usize) -> String {
+        match self {
+            Dialect::Postgres => format!("${}", n),
+            Dialect::MySql | Dialect::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// Quote a possibly schema-qualified identifier such as `dim.customers`
+    fn quote_identifier(self, name: &str) -> String {
+        let quote = match self {
+            Dialect::MySql => "`",
+            Dialect::Postgres | Dialect::Sqlite => "\"",
+        };
+        let escaped = format!("{}{}", quote, quote);
+        name.split('.')
+            .map(|part| format!("{}{}{}", quote, part.replace(quote, &escaped), quote))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+struct RefCheckOptions {
+    uri: String,
+    dialect: Dialect,
+    table: String,
+    key: String,
+    ref_column: String,
+    batch_size: usize,
+    flag_column: String,
+    output: String,
+    fail_on_invalid: bool,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<RefCheckOptions> {
+    let string = |name: &str| -> Result<Option<String>> {
+        match config.get(name) {
+            None => Ok(None),
+            Some(toml::Value::String(s)) if !s.is_empty() => Ok(Some(s.clone())),
+            Some(_) => anyhow::bail!("'{}' must be a non-empty string", name),
+        }
+    };
+    let required = |name: &str| -> Result<String> {
+        string(name)?.ok_or_else(|| anyhow::anyhow!("Missing required '{}' configuration", name))
+    };
+
+    let uri = required("uri")?;
+    let dialect = Dialect::from_uri(&uri)?;
+    let table = required("table")?;
+    let key = required("key")?;
+    let ref_column = string("ref_column")?.unwrap_or_else(|| key.clone());
+
+    let batch_size = match config.get("batch_size") {
+        None => DEFAULT_BATCH_SIZE,
+        Some(toml::Value::Integer(n)) if *n > 0 => *n as usize,
+        Some(_) => anyhow::bail!("'batch_size' must be a positive integer"),
+    };
+
+    let flag_column = string("flag_column")?.unwrap_or_else(|| DEFAULT_FLAG_COLUMN.to_string());
+    let output = string("output")?.unwrap_or_else(|| "all".to_string());
+    if !OUTPUTS.contains(&output.as_str()) {
+        anyhow::bail!(
+            "Invalid output: {}. Must be one of: {}",
+            output,
+            OUTPUTS.join(", ")
+        );
+    }
+
+    let fail_on_invalid = match string("on_invalid")?.as_deref() {
+        None | Some("flag") => false,
+        Some("fail") => true,
+        Some(other) => anyhow::bail!(
+            "Invalid on_invalid policy: '{}'. Must be 'flag' or 'fail'",
+            other
+        ),
+    };
+
+    Ok(RefCheckOptions {
+        uri,
+        dialect,
+        table,
+        key,
+        ref_column,
+        batch_size,
+        flag_column,
+        output,
+        fail_on_invalid,
+    })
+}
+
+/// A key bound into the `IN` list with the type of its column
+#[derive(Debug, Clone, PartialEq)]
+enum KeyValue {
+    Int(i64),
+    Text(String),
+}
+
+impl KeyValue {
+    /// Form used for matching, so `42` and `"42"` are the same key
+    fn text(&self) -> String {
+        match self {
+            KeyValue::Int(n) => n.to_string(),
+            KeyValue::Text(s) => s.clone(),
+        }
+    }
+}
+
+fn key_values(df: &DataFrame, name: &str) -> Result<Vec<Option<KeyValue>>> {
+    let column = df
+        .column(name)
+        .map_err(|_| anyhow::anyhow!("Ref check: column '{}' not found", name))?
+        .as_materialized_series();
+
+    if column.dtype().is_integer() {
+        let values = column.strict_cast(&DataType::Int64)?;
+        Ok(values
+            .i64()?
+            .into_iter()
+            .map(|v| v.map(KeyValue::Int))
+            .collect())
+    } else if column.dtype() == &DataType::String {
+        Ok(column
+            .str()?
+            .into_iter()
+            .map(|v| v.map(|s| KeyValue::Text(s.to_string())))
+            .collect())
+    } else {
+        anyhow::bail!(
+            "Ref check: column '{}' is {}, expected integers or strings",
+            name,
+            column.dtype()
+        )
+    }
+}
+
+/// Matching form of the key a lookup row returned
+fn found_key(row: &AnyRow, options: &RefCheckOptions) -> Result<Option<String>> {
+    let kind = {
+        let raw = row.try_get_raw(0)?;
+        if raw.is_null() {
+            return Ok(None);
+        }
+        raw.type_info().kind()
+    };
+
+    Ok(Some(match kind {
+        AnyTypeInfoKind::SmallInt | AnyTypeInfoKind::Integer | AnyTypeInfoKind::BigInt => {
+            row.try_get::<i64, _>(0)?.to_string()
+        }
+        AnyTypeInfoKind::Text => row.try_get::<String, _>(0)?,
+        _ => anyhow::bail!(
+            "Ref check: column '{}' of '{}' is not an integer or text column",
+            options.ref_column,
+            options.table
+        ),
+    }))
+}
+
+/// Look up one batch of keys, returning the ones present in the table
+async fn lookup(
+    conn: &mut AnyConnection,
+    options: &RefCheckOptions,
+    keys: &[&KeyValue],
+) -> Result<HashSet<String>> {
+    let column = options.dialect.quote_identifier(&options.ref_column);
+    let placeholders = (1..=keys.len())
+        .map(|n| options.dialect.placeholder(n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "SELECT DISTINCT {} FROM {} WHERE {} IN ({})",
+        column,
+        options.dialect.quote_identifier(&options.table),
+        column,
+        placeholders
+    );
+
+    let mut query = sqlx::query(AssertSqlSafe(sql));
+    for key in keys {
+        query = match key {
+            KeyValue::Int(n) => query.bind(*n),
+            KeyValue::Text(s) => query.bind(s.clone()),
+        };
+    }
+    let rows = query
+        .fetch_all(&mut *conn)
+        .await
+        .with_context(|| format!("Ref check: lookup in '{}' failed", options.table))?;
+
+    let mut found = HashSet::new();
+    for row in &rows {
+        if let Some(key) = found_key(row, options)? {
+            found.insert(key);
+        }
+    }
+    Ok(found)
+}
+
+#[async_trait]
+impl Stage for RefCheckTransform {
+    fn name(&self) -> &str {
+        "ref_check.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "uri".to_string(),
+            toml::Value::String("postgres://reader@warehouse/dw".to_string()),
+        );
+        example1.insert(
+            "table".to_string(),
+            toml::Value::String("dim.customers".to_string()),
+        );
+        example1.insert(
+            "key".to_string(),
+            toml::Value::String("customer_id".to_string()),
+        );
+        example1.insert(
+            "ref_column".to_string(),
+            toml::Value::String("id".to_string()),
+        );
+        example1.insert(
+            "output".to_string(),
+            toml::Value::String("valid".to_string()),
+        );
+
+        let mut example2 = example1.clone();
+        example2.remove("output");
+        example2.insert(
+            "on_invalid".to_string(),
+            toml::Value::String("fail".to_string()),
+        );
+        example2.insert("batch_size".to_string(), toml::Value::Integer(2000));
+
+        StageMetadata::builder("ref_check.apply", StageCategory::Transform)
+            .description("Check that key values exist in a database table")
+            .long_description(
+                "Looks up the distinct values of the 'key' column in 'ref_column' of a \
+                PostgreSQL, MySQL or SQLite table with batched SELECT ... WHERE ... IN (...) \
+                queries of 'batch_size' keys, so the referenced table never has to fit in memory. \
+                A boolean 'flag_column' is true for rows whose key exists; null keys are not \
+                looked up and count as valid. Lookup results are cached for the lifetime of the \
+                stage, so each key is queried once per run. With 'output' set to 'valid' or \
+                'invalid' only those rows are emitted, so a second stage with the same settings \
+                can route the invalid rows elsewhere; 'on_invalid = \"fail\"' fails the stage \
+                instead, naming some of the missing keys.",
+            )
+            .parameter(ConfigParameter::required(
+                "uri",
+                ParameterType::String,
+                "Database URI (postgres://, mysql:// or sqlite:)",
+            ))
+            .parameter(ConfigParameter::required(
+                "table",
+                ParameterType::String,
+                "Referenced table, optionally schema-qualified",
+            ))
+            .parameter(ConfigParameter::required(
+                "key",
+                ParameterType::String,
+                "Column holding the foreign key (integers or strings)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "ref_column",
+                ParameterType::String,
+                "value of key",
+                "Column of the referenced table the key must match",
+            ))
+            .parameter(ConfigParameter::optional(
+                "batch_size",
+                ParameterType::Integer,
+                "500",
+                "Keys per lookup query",
+            ))
+            .parameter(ConfigParameter::optional(
+                "flag_column",
+                ParameterType::String,
+                DEFAULT_FLAG_COLUMN,
+                "Boolean column set when the row's key exists",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "all",
+                    "Emit all rows flagged, only the valid rows, or only the invalid ones",
+                )
+                .with_validation(ParameterValidation::allowed_values(OUTPUTS)),
+            )
+            .parameter(
+                ConfigParameter::optional(
+                    "on_invalid",
+                    ParameterType::String,
+                    "flag",
+                    "Policy for rows whose key is missing",
+                )
+                .with_validation(ParameterValidation::allowed_values(["flag", "fail"])),
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Keep facts with a known customer",
+                example1,
+                Some("Drop orders whose customer_id is not in the customer dimension"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Enforce foreign keys",
+                example2,
+                Some("Fail the load when any order references a missing customer"),
+            ))
+            .tag("reference")
+            .tag("foreign-key")
+            .tag("database")
+            .tag("quality")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Ref check transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let mut df = data.as_dataframe()?;
+        let keys = key_values(&df, &options.key)?;
+        let cache_key = format!("{}\n{}\n{}", options.uri, options.table, options.ref_column);
+
+        // Distinct keys not looked up yet, in first-seen order
+        let pending: Vec<&KeyValue> = {
+            let known = self.known.lock().unwrap();
+            let cached = known.get(&cache_key);
+            let mut seen = HashSet::new();
+            keys.iter()
+                .flatten()
+                .filter(|key| {
+                    let text = key.text();
+                    !cached.is_some_and(|c| c.contains_key(&text)) && seen.insert(text)
+                })
+                .collect()
+        };
+
+        if !pending.is_empty() {
+            sqlx::any::install_default_drivers();
+            let mut conn = AnyConnection::connect(&options.uri)
+                .await
+                .context("Ref check: failed to connect to database")?;
+
+            let mut results = HashMap::with_capacity(pending.len());
+            for batch in pending.chunks(options.batch_size) {
+                let found = lookup(&mut conn, &options, batch).await?;
+                for key in batch {
+                    let text = key.text();
+                    let exists = found.contains(&text);
+                    results.insert(text, exists);
+                }
+            }
+            let _ = conn.close().await;
+
+            tracing::debug!(
+                "Ref check: looked up {} key(s) in {}",
+                results.len(),
+                options.table
+            );
+            self.known
+                .lock()
+                .unwrap()
+                .entry(cache_key.clone())
+                .or_default()
+                .extend(results);
+        }
+
+        let valid: Vec<bool> = {
+            let known = self.known.lock().unwrap();
+            let cached = known.get(&cache_key);
+            keys.iter()
+                .map(|key| match key {
+                    None => true,
+                    Some(key) => cached
+                        .and_then(|c| c.get(&key.text()))
+                        .copied()
+                        .unwrap_or(false),
+                })
+                .collect()
+        };
+
+        let invalid = valid.iter().filter(|v| !**v).count();
+        if invalid > 0 {
+            if options.fail_on_invalid {
+                let mut missing: Vec<String> = Vec::new();
+                for (key, _) in keys.iter().zip(&valid).filter(|(_, v)| !**v) {
+                    let text = key.as_ref().map(KeyValue::text).unwrap_or_default();
+                    if !missing.contains(&text) {
+                        missing.push(text);
+                    }
+                    if missing.len() == REPORTED_KEYS {
+                        break;
+                    }
+                }
+                anyhow::bail!(
+                    "Ref check: {} row(s) reference keys missing from {}.{}, e.g. {}",
+                    invalid,
+                    options.table,
+                    options.ref_column,
+                    missing.join(", ")
+                );
+            }
+            tracing::warn!(
+                "Ref check: {} row(s) reference keys missing from {}.{}",
+                invalid,
+                options.table,
+                options.ref_column
+            );
+        }
+
+        let mask = BooleanChunked::new(options.flag_column.as_str().into(), &valid);
+        df.with_column(mask.clone().into_series())?;
+
+        let df = match options.output.as_str() {
+            "valid" => df.filter(&mask)?,
+            "invalid" => df.filter(&!&mask)?,
+            _ => df,
+        };
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A SQLite file with customers 1-3, coded C1-C3
+    async fn customers_db(dir: &tempfile::TempDir) -> String {
+        let uri = format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("dim.db").to_str().unwrap()
+        );
+        sqlx::any::install_default_drivers();
+        let mut conn = AnyConnection::connect(&uri).await.unwrap();
+        for statement in [
+            "CREATE TABLE customers (id INTEGER PRIMARY KEY, code TEXT NOT NULL)",
+            "INSERT INTO customers (id, code) VALUES (1, 'C1'), (2, 'C2'), (3, 'C3')",
+        ] {
+            sqlx::query(statement).execute(&mut conn).await.unwrap();
+        }
+        conn.close().await.unwrap();
+        uri
+    }
+
+    fn config(uri: &str, pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([
+            ("uri".to_string(), toml::Value::String(uri.to_string())),
+            (
+                "table".to_string(),
+                toml::Value::String("customers".to_string()),
+            ),
+            (
+                "key".to_string(),
+                toml::Value::String("customer_id".to_string()),
+            ),
+            (
+                "ref_column".to_string(),
+                toml::Value::String("id".to_string()),
+            ),
+        ]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    fn orders() -> DataFrame {
+        df! {
+            "order_id" => [10i64, 11, 12, 13, 14],
+            "customer_id" => [Some(1i64), Some(4), Some(2), None, Some(4)],
+        }
+        .unwrap()
+    }
+
+    async fn check(
+        stage: &RefCheckTransform,
+        df: DataFrame,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFrame> {
+        let inputs = HashMap::from([("orders".to_string(), DataFormat::DataFrame(df))]);
+        stage.execute(inputs, config).await?.as_dataframe()
+    }
+
+    fn strings(df: &DataFrame, name: &str) -> Vec<Option<String>> {
+        df.column(name)
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.map(str::to_string))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_ref_check_flags_missing_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let uri = customers_db(&dir).await;
+        let stage = RefCheckTransform::new();
+
+        let batched = config(&uri, &[("batch_size", toml::Value::Integer(1))]);
+        let df = check(&stage, orders(), &batched).await.unwrap();
+        assert_eq!(
+            df.column("_ref_valid")
+                .unwrap()
+                .bool()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(true), Some(false), Some(true), Some(true), Some(false)]
+        );
+
+        let invalid = config(&uri, &[("output", toml::Value::String("invalid".into()))]);
+        let df = check(&stage, orders(), &invalid).await.unwrap();
+        assert_eq!(
+            df.column("order_id")
+                .unwrap()
+                .i64()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(11), Some(14)]
+        );
+
+        let fail = config(&uri, &[("on_invalid", toml::Value::String("fail".into()))]);
+        let err = check(&stage, orders(), &fail)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Ref check: 2 row(s) reference keys missing from customers.id, e.g. 4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ref_check_text_keys_and_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let uri = customers_db(&dir).await;
+        let stage = RefCheckTransform::new();
+        let by_code = config(
+            &uri,
+            &[
+                ("key", toml::Value::String("customer".into())),
+                ("ref_column", toml::Value::String("code".into())),
+                ("output", toml::Value::String("valid".into())),
+            ],
+        );
+
+        let df = df! { "customer" => ["C1", "C9", "C3"] }.unwrap();
+        let df = check(&stage, df, &by_code).await.unwrap();
+        assert_eq!(
+            strings(&df, "customer"),
+            vec![Some("C1".to_string()), Some("C3".to_string())]
+        );
+
+        // Keys already checked are answered from the cache, new ones are queried
+        let mut conn = AnyConnection::connect(&uri).await.unwrap();
+        sqlx::query("DELETE FROM customers WHERE code = 'C1'")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        conn.close().await.unwrap();
+
+        let df = df! { "customer" => ["C1", "C2", "C9"] }.unwrap();
+        let df = check(&stage, df, &by_code).await.unwrap();
+        assert_eq!(df.height(), 2);
+        assert_eq!(
+            strings(&df, "customer"),
+            vec![Some("C1".to_string()), Some("C2".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ref_check_config_errors() {
+        let stage = RefCheckTransform::new();
+        for (name, value) in [
+            ("uri", toml::Value::String("mssql://db/app".into())),
+            ("batch_size", toml::Value::Integer(0)),
+            ("output", toml::Value::String("missing".into())),
+            ("on_invalid", toml::Value::String("drop".into())),
+        ] {
+            let config = config("sqlite://dim.db", &[(name, value)]);
+            assert!(stage.validate_config(&config).await.is_err(), "{}", name);
+        }
+
+        let mut config = config("sqlite://dim.db", &[]);
+        assert!(stage.validate_config(&config).await.is_ok());
+        config.remove("table");
+        assert!(stage.validate_config(&config).await.is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Renames DataFrame columns from a table of `old_name = "new_name"` pairs
+pub struct RenameTransform;
+
+fn parse_mappings(config: &HashMap<String, toml::Value>) -> Result<HashMap<String, String>> {
+    let table = match config.get("mappings") {
+        Some(toml::Value::Table(table)) => table,
+        Some(_) => anyhow::bail!("'mappings' must be a table of old_name = \"new_name\" pairs"),
+        None => anyhow::bail!("Rename transform requires 'mappings' configuration"),
+    };
+
+    if table.is_empty() {
+        anyhow::bail!("'mappings' must contain at least one column");
+    }
+
+    table
+        .iter()
+        .map(|(old, new)| match new {
+            toml::Value::String(new) => Ok((old.clone(), new.clone())),
+            _ => anyhow::bail!("New name for column '{}' must be a string", old),
+        })
+        .collect()
+}
+
+fn parse_ignore_missing(config: &HashMap<String, toml::Value>) -> Result<bool> {
+    match config.get("ignore_missing") {
+        Some(toml::Value::Boolean(b)) => Ok(*b),
+        Some(_) => anyhow::bail!("'ignore_missing' must be a boolean"),
+        None => Ok(false),
+    }
+}
+
+#[async_trait]
+impl Stage for RenameTransform {
+    fn name(&self) -> &str {
+        "rename.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut mappings = toml::Table::new();
+        mappings.insert(
+            "cust_id".to_string(),
+            toml::Value::String("customer_id".to_string()),
+        );
+        mappings.insert("amt".to_string(), toml::Value::String("amount".to_string()));
+        let mut example1 = HashMap::new();
+        example1.insert("mappings".to_string(), toml::Value::Table(mappings.clone()));
+
+        let mut example2 = HashMap::new();
+        example2.insert("mappings".to_string(), toml::Value::Table(mappings));
+        example2.insert("ignore_missing".to_string(), toml::Value::Boolean(true));
+
+        StageMetadata::builder("rename.apply", StageCategory::Transform)
+            .description("Rename DataFrame columns")
+            .long_description(
+                "Renames columns using a table of old_name = \"new_name\" pairs. All renames are \
+                applied at once, so two columns can swap names. A source column that does not \
+                exist fails the stage unless 'ignore_missing' is set, in which case it is skipped.",
+            )
+            .parameter(ConfigParameter::required(
+                "mappings",
+                ParameterType::Object,
+                "Table mapping existing column names to their new names",
+            ))
+            .parameter(ConfigParameter::optional(
+                "ignore_missing",
+                ParameterType::Boolean,
+                "false",
+                "Skip mappings whose source column is absent instead of failing",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Rename columns",
+                example1,
+                Some("Give abbreviated columns readable names"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Tolerate absent columns",
+                example2,
+                Some("Rename whichever of the columns are present"),
+            ))
+            .tag("rename")
+            .tag("columns")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Rename transform requires input data"))?;
+
+        let mappings = parse_mappings(config)?;
+        let ignore_missing = parse_ignore_missing(config)?;
+        let mut df = data.as_dataframe()?;
+
+        let current: Vec<String> = df
+            .get_column_names()
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        if !ignore_missing {
+            let mut missing: Vec<&str> = mappings
+                .keys()
+                .filter(|old| !current.contains(old))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                missing.sort_unstable();
+                anyhow::bail!(
+                    "Rename: column(s) not found: {} (available: {})",
+                    missing.join(", "),
+                    current.join(", ")
+                );
+            }
+        }
+
+        // Rename every column in one pass so swaps don't collide midway
+        let renamed: Vec<String> = current
+            .iter()
+            .map(|name| mappings.get(name).unwrap_or(name).clone())
+            .collect();
+        df.set_column_names(renamed.iter().map(String::as_str))
+            .map_err(|e| anyhow::anyhow!("Rename: {}", e))?;
+
+        Ok(DataFormat::DataFrame(df))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_mappings(config)?;
+        parse_ignore_missing(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    fn config(pairs: &[(&str, &str)], ignore_missing: bool) -> HashMap<String, toml::Value> {
+        let mappings: toml::Table = pairs
+            .iter()
+            .map(|(old, new)| (old.to_string(), toml::Value::String(new.to_string())))
+            .collect();
+        HashMap::from([
+            ("mappings".to_string(), toml::Value::Table(mappings)),
+            (
+                "ignore_missing".to_string(),
+                toml::Value::Boolean(ignore_missing),
+            ),
+        ])
+    }
+
+    async fn rename(config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let df = df! {
+            "id" => &[1i64, 2],
+            "nm" => &["a", "b"],
+            "amt" => &[1.5f64, 2.5],
+        }
+        .unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        RenameTransform
+            .execute(inputs, config)
+            .await?
+            .as_dataframe()
+    }
+
+    #[tokio::test]
+    async fn test_rename_multiple_columns() {
+        let df = rename(&config(&[("nm", "name"), ("amt", "amount")], false))
+            .await
+            .unwrap();
+        assert_eq!(df.get_column_names(), vec!["id", "name", "amount"]);
+        assert_eq!(
+            df.column("amount").unwrap().f64().unwrap().get(1),
+            Some(2.5)
+        );
+
+        // Renames apply together, so columns can trade names
+        let df = rename(&config(&[("id", "nm"), ("nm", "id")], false))
+            .await
+            .unwrap();
+        assert_eq!(df.get_column_names(), vec!["nm", "id", "amt"]);
+        assert_eq!(df.column("nm").unwrap().i64().unwrap().get(0), Some(1));
+
+        assert!(rename(&config(&[("nm", "id")], false)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rename_missing_column() {
+        let err = rename(&config(&[("nm", "name"), ("email", "mail")], false))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Rename: column(s) not found: email (available: id, nm, amt)"
+        );
+
+        let df = rename(&config(&[("nm", "name"), ("email", "mail")], true))
+            .await
+            .unwrap();
+        assert_eq!(df.get_column_names(), vec!["id", "name", "amt"]);
+
+        let empty = HashMap::from([(
+            "mappings".to_string(),
+            toml::Value::Table(toml::Table::new()),
+        )]);
+        assert!(RenameTransform.validate_config(&empty).await.is_err());
+    }
+}
@@ -0,0 +1,517 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::epoch_millis;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Buckets irregular events into fixed time intervals and aggregates each bucket
+pub struct ResampleTransform;
+
+const AGGREGATIONS: [&str; 8] = [
+    "mean", "sum", "min", "max", "median", "count", "first", "last",
+];
+const FILLS: [&str; 4] = ["null", "zero", "forward", "skip"];
+
+struct Options {
+    timestamp_column: String,
+    interval: Duration,
+    /// (value column, aggregation) pairs, in column name order
+    aggregates: Vec<(String, String)>,
+    timezone: String,
+    fill: String,
+}
+
+/// Parse an interval such as "1m" or "15m" into a positive duration
+fn parse_interval(interval: &str) -> Result<Duration> {
+    let duration = Duration::try_parse(interval).map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid interval: '{}'. Use a duration such as 30s, 1m, 1h or 1d",
+            interval
+        )
+    })?;
+    if duration.negative() || duration.is_zero() || duration.parsed_int {
+        anyhow::bail!(
+            "Invalid interval: '{}'. Use a positive duration such as 30s, 1m, 1h or 1d",
+            interval
+        );
+    }
+    Ok(duration)
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let timestamp_column = config
+        .get("timestamp_column")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'timestamp_column' configuration"))?
+        .to_string();
+    let interval = config
+        .get("interval")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'interval' configuration"))?;
+    let interval = parse_interval(interval)?;
+
+    let aggregates = match config.get("aggregate") {
+        Some(toml::Value::Table(table)) if !table.is_empty() => {
+            let mut aggregates = table
+                .iter()
+                .map(|(column, aggregation)| {
+                    let aggregation = aggregation.as_str().ok_or_else(|| {
+                        anyhow::anyhow!("Aggregation for '{}' must be a string", column)
+                    })?;
+                    if !AGGREGATIONS.contains(&aggregation) {
+                        anyhow::bail!(
+                            "Invalid aggregation for '{}': {}. Must be one of: {}",
+                            column,
+                            aggregation,
+                            AGGREGATIONS.join(", ")
+                        );
+                    }
+                    if *column == timestamp_column {
+                        anyhow::bail!("Cannot aggregate the timestamp column '{}'", column);
+                    }
+                    Ok((column.clone(), aggregation.to_string()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            aggregates.sort();
+            aggregates
+        }
+        Some(_) => anyhow::bail!(
+            "'aggregate' must be a table of column = aggregation, e.g. {{ cpu = \"mean\" }}"
+        ),
+        None => anyhow::bail!("Missing required 'aggregate' configuration"),
+    };
+
+    let timezone = match config.get("timezone") {
+        Some(toml::Value::String(s)) => {
+            s.parse::<Tz>().map_err(|_| {
+                anyhow::anyhow!(
+                    "Unknown timezone '{}'. Use an IANA name such as 'UTC' or 'Asia/Seoul'",
+                    s
+                )
+            })?;
+            s.clone()
+        }
+        Some(_) => anyhow::bail!("'timezone' must be a string"),
+        None => "UTC".to_string(),
+    };
+
+    let fill = match config.get("fill") {
+        Some(toml::Value::String(s)) if FILLS.contains(&s.as_str()) => s.clone(),
+        Some(toml::Value::String(s)) => {
+            anyhow::bail!("Invalid fill: {}. Must be one of: {}", s, FILLS.join(", "))
+        }
+        Some(_) => anyhow::bail!("'fill' must be a string"),
+        None => "null".to_string(),
+    };
+
+    Ok(Options {
+        timestamp_column,
+        interval,
+        aggregates,
+        timezone,
+        fill,
+    })
+}
+
+/// The timestamp column as a datetime in the target timezone
+///
+/// The values are read as [`epoch_millis`] does; values without an offset
+/// are read as UTC.
+fn time_index(df: &DataFrame, name: &str, timezone: &str) -> Result<Series> {
+    let millis = epoch_millis(df, name, "Resample")?;
+    Ok(Int64Chunked::from_vec(name.into(), millis)
+        .into_datetime(TimeUnit::Milliseconds, Some(timezone.into()))
+        .into_series())
+}
+
+impl Options {
+    fn aggregation(&self, column: &str, aggregation: &str) -> Expr {
+        let value = col(column);
+        match aggregation {
+            "sum" => value.sum(),
+            "min" => value.min(),
+            "max" => value.max(),
+            "median" => value.median(),
+            "count" => value.count().cast(DataType::Int64),
+            "first" => value.first(),
+            "last" => value.last(),
+            _ => value.mean(),
+        }
+        .alias(column)
+    }
+
+    /// How an empty bucket's value is filled; counts of empty buckets are always 0
+    fn filled(&self, column: &str, aggregation: &str) -> Expr {
+        match (aggregation, self.fill.as_str()) {
+            ("count", _) | (_, "zero") => col(column).fill_null(lit(0)),
+            (_, "forward") => col(column).forward_fill(None),
+            _ => col(column),
+        }
+    }
+
+    fn check_columns(&self, df: &DataFrame) -> Result<()> {
+        for (name, aggregation) in &self.aggregates {
+            let column = df
+                .column(name)
+                .map_err(|_| anyhow::anyhow!("Resample: column '{}' not found", name))?;
+            let numeric_only = matches!(aggregation.as_str(), "mean" | "sum" | "median");
+            if numeric_only && !column.dtype().is_numeric() {
+                anyhow::bail!(
+                    "Resample: cannot take the {} of column '{}', which is {}",
+                    aggregation,
+                    name,
+                    column.dtype()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        self.check_columns(df)?;
+        let index = time_index(df, &self.timestamp_column, &self.timezone)?;
+
+        let mut columns = vec![index.into_column()];
+        for (name, _) in &self.aggregates {
+            columns.push(df.column(name)?.clone());
+        }
+        let ts = self.timestamp_column.as_str();
+        let options = DynamicGroupOptions {
+            index_column: ts.into(),
+            every: self.interval,
+            period: self.interval,
+            offset: Duration::parse("0ns"),
+            label: Label::Left,
+            include_boundaries: false,
+            closed_window: ClosedWindow::Left,
+            start_by: StartBy::WindowBound,
+        };
+        let aggregations: Vec<Expr> = self
+            .aggregates
+            .iter()
+            .map(|(column, aggregation)| self.aggregation(column, aggregation))
+            .collect();
+
+        let resampled = DataFrame::new(columns)?
+            .lazy()
+            .sort([ts], Default::default())
+            .group_by_dynamic(col(ts), [], options)
+            .agg(aggregations)
+            .collect()?;
+        if self.fill == "skip" || resampled.height() == 0 {
+            return Ok(resampled);
+        }
+
+        // Add the empty buckets between the first and last one
+        let fills: Vec<Expr> = self
+            .aggregates
+            .iter()
+            .map(|(column, aggregation)| self.filled(column, aggregation))
+            .collect();
+        Ok(resampled
+            .upsample(Vec::<PlSmallStr>::new(), ts, self.interval)?
+            .lazy()
+            .with_columns(fills)
+            .collect()?)
+    }
+}
+
+#[async_trait]
+impl Stage for ResampleTransform {
+    fn name(&self) -> &str {
+        "resample.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut aggregate = toml::map::Map::new();
+        aggregate.insert(
+            "latency_ms".to_string(),
+            toml::Value::String("mean".to_string()),
+        );
+        aggregate.insert(
+            "requests".to_string(),
+            toml::Value::String("sum".to_string()),
+        );
+
+        let mut example = HashMap::new();
+        example.insert(
+            "timestamp_column".to_string(),
+            toml::Value::String("ts".to_string()),
+        );
+        example.insert(
+            "interval".to_string(),
+            toml::Value::String("1m".to_string()),
+        );
+        example.insert("aggregate".to_string(), toml::Value::Table(aggregate));
+        example.insert(
+            "timezone".to_string(),
+            toml::Value::String("Europe/Berlin".to_string()),
+        );
+        example.insert("fill".to_string(), toml::Value::String("zero".to_string()));
+
+        StageMetadata::builder("resample.apply", StageCategory::Transform)
+            .description("Resample irregular events into fixed time intervals")
+            .long_description(
+                "Buckets rows into consecutive intervals of 'interval' length by their \
+                timestamp and aggregates each value column per bucket, giving one row per \
+                interval labelled with the bucket start, e.g. per-minute means. Buckets include \
+                their start and exclude their end, and are aligned in 'timezone', so daily \
+                buckets start at local midnight and follow daylight saving changes. The \
+                timestamp column may hold datetimes, epoch milliseconds or ISO 8601 strings; \
+                values without an offset are read as UTC. Buckets without events between the \
+                first and last event are filled per 'fill': null values, zeros, the previous \
+                bucket's values, or skipped. Counts of empty buckets are always 0. Columns \
+                without an aggregation are dropped.",
+            )
+            .parameter(ConfigParameter::required(
+                "timestamp_column",
+                ParameterType::String,
+                "Column holding the time of each event",
+            ))
+            .parameter(ConfigParameter::required(
+                "interval",
+                ParameterType::String,
+                "Bucket length such as 30s, 1m, 1h or 1d",
+            ))
+            .parameter(ConfigParameter::required(
+                "aggregate",
+                ParameterType::Object,
+                "Aggregation per value column (mean, sum, min, max, median, count, first, last)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "timezone",
+                ParameterType::String,
+                "UTC",
+                "IANA timezone that buckets are aligned in and labelled with",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "fill",
+                    ParameterType::String,
+                    "null",
+                    "How buckets without events are filled",
+                )
+                .with_validation(ParameterValidation::allowed_values(FILLS)),
+            )
+            .example(crate::core::metadata::ConfigExample::new(
+                "Per-minute request metrics",
+                example,
+                Some("Mean latency and total requests per minute, with 0 for idle minutes"),
+            ))
+            .tag("time")
+            .tag("resample")
+            .tag("timeseries")
+            .tag("aggregate")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Resample transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        Ok(DataFormat::DataFrame(options.apply(&df)?))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> DataFrame {
+        df![
+            "ts" => [
+                "2024-03-01T10:00:05Z",
+                "2024-03-01T10:00:40Z",
+                "2024-03-01T10:01:59Z",
+                "2024-03-01T10:04:00Z",
+                "2024-03-01T10:04:30Z",
+            ],
+            "latency" => [10.0, 20.0, 30.0, 40.0, 60.0],
+        ]
+        .unwrap()
+    }
+
+    fn config(fill: Option<&str>) -> HashMap<String, toml::Value> {
+        let mut aggregate = toml::map::Map::new();
+        aggregate.insert(
+            "latency".to_string(),
+            toml::Value::String("mean".to_string()),
+        );
+        let mut config = HashMap::new();
+        config.insert(
+            "timestamp_column".to_string(),
+            toml::Value::String("ts".to_string()),
+        );
+        config.insert(
+            "interval".to_string(),
+            toml::Value::String("1m".to_string()),
+        );
+        config.insert("aggregate".to_string(), toml::Value::Table(aggregate));
+        if let Some(fill) = fill {
+            config.insert("fill".to_string(), toml::Value::String(fill.to_string()));
+        }
+        config
+    }
+
+    async fn resample(df: DataFrame, config: &HashMap<String, toml::Value>) -> DataFrame {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+        ResampleTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    fn minutes(df: &DataFrame) -> Vec<String> {
+        df.column("ts")
+            .unwrap()
+            .cast(&DataType::String)
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .map(|s| s[11..16].to_string())
+            .collect()
+    }
+
+    fn latencies(df: &DataFrame) -> Vec<Option<f64>> {
+        df.column("latency")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_resample_per_minute_means() {
+        let result = resample(events(), &config(None)).await;
+        assert_eq!(result.get_column_names_str(), vec!["ts", "latency"]);
+        assert_eq!(
+            minutes(&result),
+            vec!["10:00", "10:01", "10:02", "10:03", "10:04"]
+        );
+        assert_eq!(
+            latencies(&result),
+            vec![Some(15.0), Some(30.0), None, None, Some(50.0)]
+        );
+
+        let skipped = resample(events(), &config(Some("skip"))).await;
+        assert_eq!(minutes(&skipped), vec!["10:00", "10:01", "10:04"]);
+
+        let forward = resample(events(), &config(Some("forward"))).await;
+        assert_eq!(
+            latencies(&forward),
+            vec![Some(15.0), Some(30.0), Some(30.0), Some(30.0), Some(50.0)]
+        );
+
+        let mut counted = config(Some("skip"));
+        let mut aggregate = toml::map::Map::new();
+        aggregate.insert(
+            "latency".to_string(),
+            toml::Value::String("count".to_string()),
+        );
+        counted.insert("aggregate".to_string(), toml::Value::Table(aggregate));
+        counted.remove("fill");
+        let result = resample(events(), &counted).await;
+        let counts: Vec<i64> = result
+            .column("latency")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(counts, vec![2, 1, 0, 0, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_resample_aligns_buckets_in_timezone() {
+        // 15:30 UTC is already the next day in Seoul (UTC+9)
+        let df = df![
+            "ts" => ["2024-03-01T14:30:00Z", "2024-03-01T15:30:00Z", "2024-03-02T02:00:00Z"],
+            "latency" => [1.0, 2.0, 3.0],
+        ]
+        .unwrap();
+        let mut config = config(None);
+        config.insert(
+            "interval".to_string(),
+            toml::Value::String("1d".to_string()),
+        );
+        config.insert(
+            "timezone".to_string(),
+            toml::Value::String("Asia/Seoul".to_string()),
+        );
+
+        let result = resample(df, &config).await;
+        assert_eq!(
+            result.column("ts").unwrap().dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, Some("Asia/Seoul".into()))
+        );
+        // Local midnights, as the bucket labels are shown in the timezone
+        let days: Vec<String> = result
+            .column("ts")
+            .unwrap()
+            .cast(&DataType::String)
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            days,
+            vec!["2024-03-01 00:00:00.000", "2024-03-02 00:00:00.000"]
+        );
+        assert_eq!(latencies(&result), vec![Some(1.0), Some(2.5)]);
+    }
+
+    #[tokio::test]
+    async fn test_resample_config_validation() {
+        let transform = ResampleTransform;
+        assert!(transform.validate_config(&config(None)).await.is_ok());
+
+        let mut bad = config(Some("interpolate"));
+        let err = transform
+            .validate_config(&bad)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Must be one of: null, zero, forward, skip"));
+
+        bad = config(None);
+        bad.insert(
+            "interval".to_string(),
+            toml::Value::String("0m".to_string()),
+        );
+        assert!(transform.validate_config(&bad).await.is_err());
+
+        bad = config(None);
+        bad.insert(
+            "timezone".to_string(),
+            toml::Value::String("Mars/Olympus".to_string()),
+        );
+        assert!(transform.validate_config(&bad).await.is_err());
+    }
+}
@@ -0,0 +1,510 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::{string_list, time_index};
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct RollingTimeTransform;
+
+const TIME_INDEX: &str = "__rolling_time";
+
+const AGGREGATIONS: [&str; 8] = ["sum", "mean", "min", "max", "median", "std", "var", "count"];
+const CLOSED: [&str; 4] = ["right", "left", "both", "none"];
+const UNSORTED: [&str; 2] = ["sort", "error"];
+
+struct Options {
+    timestamp_column: String,
+    window: Duration,
+    values: Vec<String>,
+    aggregation: String,
+    partition_by: Vec<String>,
+    closed: ClosedWindow,
+    min_periods: usize,
+    sort: bool,
+    suffix: String,
+}
+
+fn string_option<'a>(
+    config: &'a HashMap<String, toml::Value>,
+    name: &str,
+    default: &'a str,
+    allowed: &[&str],
+) -> Result<&'a str> {
+    let value = match config.get(name) {
+        None => default,
+        Some(value) => value
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("'{}' must be a string", name))?,
+    };
+    if !allowed.contains(&value) {
+        anyhow::bail!(
+            "Invalid {}: {}. Must be one of: {}",
+            name,
+            value,
+            allowed.join(", ")
+        );
+    }
+    Ok(value)
+}
+
+/// Parse a window such as "5m" or "1h30m" into a positive duration
+pub(crate) fn parse_window(window: &str) -> Result<Duration> {
+    let duration = Duration::try_parse(window).map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid window: '{}'. Use a duration such as 30s, 5m or 1h",
+            window
+        )
+    })?;
+    if duration.negative() || duration.is_zero() || duration.parsed_int {
+        anyhow::bail!(
+            "Invalid window: '{}'. Use a positive duration such as 30s, 5m or 1h",
+            window
+        );
+    }
+    Ok(duration)
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let timestamp_column = config
+        .get("timestamp_column")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'timestamp_column' configuration"))?
+        .to_string();
+    let window = config
+        .get("window")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'window' configuration"))?;
+    let window = parse_window(window)?;
+
+    let values = string_list(config, "value")?;
+    if values.is_empty() {
+        anyhow::bail!("Missing required 'value' configuration");
+    }
+
+    let aggregation = string_option(config, "aggregation", "sum", &AGGREGATIONS)?.to_string();
+    let closed = match string_option(config, "closed", "right", &CLOSED)? {
+        "left" => ClosedWindow::Left,
+        "both" => ClosedWindow::Both,
+        "none" => ClosedWindow::None,
+        _ => ClosedWindow::Right,
+    };
+    let sort = string_option(config, "unsorted", "sort", &UNSORTED)? == "sort";
+
+    let min_periods = match config.get("min_periods") {
+        Some(toml::Value::Integer(n)) if *n >= 1 => *n as usize,
+        Some(_) => anyhow::bail!("'min_periods' must be a positive integer"),
+        None => 1,
+    };
+    let suffix = match config.get("suffix") {
+        Some(toml::Value::String(s)) => s.clone(),
+        Some(_) => anyhow::bail!("'suffix' must be a string"),
+        None => format!("_rolling_{}", aggregation),
+    };
+
+    Ok(Options {
+        timestamp_column,
+        window,
+        values,
+        aggregation,
+        partition_by: string_list(config, "partition_by")?,
+        closed,
+        min_periods,
+        sort,
+        suffix,
+    })
+}
+
+impl Options {
+    /// Evaluate `expr` per partition, or over the whole frame without one
+    fn windowed(&self, expr: Expr) -> Expr {
+        if self.partition_by.is_empty() {
+            expr
+        } else {
+            let partition: Vec<Expr> = self.partition_by.iter().map(|c| col(c.as_str())).collect();
+            expr.over(partition)
+        }
+    }
+
+    fn rolling(&self, value: &str) -> Expr {
+        let options = RollingOptionsDynamicWindow {
+            window_size: self.window,
+            min_periods: self.min_periods,
+            closed_window: self.closed,
+            fn_params: None,
+        };
+        let by = col(TIME_INDEX);
+        let expr = match self.aggregation.as_str() {
+            "mean" => col(value).rolling_mean_by(by, options),
+            "min" => col(value).rolling_min_by(by, options),
+            "max" => col(value).rolling_max_by(by, options),
+            "median" => col(value).rolling_median_by(by, options),
+            "std" => col(value).rolling_std_by(by, options),
+            "var" => col(value).rolling_var_by(by, options),
+            "count" => col(value)
+                .is_not_null()
+                .cast(DataType::UInt32)
+                .rolling_sum_by(by, options),
+            _ => col(value).rolling_sum_by(by, options),
+        };
+        self.windowed(expr)
+            .alias(format!("{}{}", value, self.suffix))
+    }
+
+    /// True when some row is earlier than the row before it in its partition
+    fn out_of_order(&self) -> Expr {
+        self.windowed(col(TIME_INDEX).lt(col(TIME_INDEX).shift(lit(1))))
+            .any(true)
+    }
+}
+
+fn check_columns(df: &DataFrame, options: &Options) -> Result<()> {
+    for name in options.partition_by.iter().chain(&options.values) {
+        if df.column(name).is_err() {
+            anyhow::bail!("Rolling time: column '{}' not found", name);
+        }
+    }
+    for name in &options.values {
+        let column = df.column(name)?;
+        if !column.dtype().is_numeric() {
+            anyhow::bail!(
+                "Rolling time: column '{}' is {}, not numeric",
+                name,
+                column.dtype()
+            );
+        }
+        if column.null_count() > 0 {
+            anyhow::bail!("Rolling time: column '{}' has null values", name);
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Stage for RollingTimeTransform {
+    fn name(&self) -> &str {
+        "rolling_time.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "timestamp_column".to_string(),
+            toml::Value::String("event_time".to_string()),
+        );
+        example1.insert("window".to_string(), toml::Value::String("5m".to_string()));
+        example1.insert(
+            "value".to_string(),
+            toml::Value::String("amount".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "timestamp_column".to_string(),
+            toml::Value::String("ts".to_string()),
+        );
+        example2.insert("window".to_string(), toml::Value::String("1h".to_string()));
+        example2.insert(
+            "value".to_string(),
+            toml::Value::String("temperature".to_string()),
+        );
+        example2.insert(
+            "aggregation".to_string(),
+            toml::Value::String("mean".to_string()),
+        );
+        example2.insert(
+            "partition_by".to_string(),
+            toml::Value::String("sensor_id".to_string()),
+        );
+        example2.insert(
+            "unsorted".to_string(),
+            toml::Value::String("error".to_string()),
+        );
+
+        StageMetadata::builder("rolling_time.apply", StageCategory::Transform)
+            .description("Aggregate values over a rolling time window")
+            .long_description(
+                "Adds a '<value><suffix>' column for each value column holding the aggregation \
+                of the values whose timestamps fall within 'window' of the current row, e.g. \
+                the sum of the last 5 minutes. By default the window (t - window, t] includes \
+                the current row; 'closed' changes which ends are included. Windows are computed \
+                per 'partition_by' group when it is set. The timestamp column may hold datetimes, \
+                epoch milliseconds or ISO 8601 strings; timestamps and values must not be null. \
+                Rows out of time order are sorted by timestamp by default, or rejected with \
+                unsorted = \"error\".",
+            )
+            .parameter(ConfigParameter::required(
+                "timestamp_column",
+                ParameterType::String,
+                "Column holding the time of each row",
+            ))
+            .parameter(ConfigParameter::required(
+                "window",
+                ParameterType::String,
+                "Window length such as 30s, 5m, 1h or 1d",
+            ))
+            .parameter(ConfigParameter::required(
+                "value",
+                ParameterType::Array,
+                "Numeric column(s) to aggregate",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "aggregation",
+                    ParameterType::String,
+                    "sum",
+                    "Aggregation applied to each window",
+                )
+                .with_validation(ParameterValidation::allowed_values(AGGREGATIONS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "partition_by",
+                ParameterType::Array,
+                "none",
+                "Column(s) whose groups are windowed independently",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "closed",
+                    ParameterType::String,
+                    "right",
+                    "Window ends that are included",
+                )
+                .with_validation(ParameterValidation::allowed_values(CLOSED)),
+            )
+            .parameter(ConfigParameter::optional(
+                "min_periods",
+                ParameterType::Integer,
+                "1",
+                "Rows a window needs before it yields a value instead of null",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "unsorted",
+                    ParameterType::String,
+                    "sort",
+                    "Sort rows out of time order, or fail with 'error'",
+                )
+                .with_validation(ParameterValidation::allowed_values(UNSORTED)),
+            )
+            .parameter(ConfigParameter::optional(
+                "suffix",
+                ParameterType::String,
+                "_rolling_<aggregation>",
+                "Suffix of the output columns",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "5-minute rolling sum",
+                example1,
+                Some("amount_rolling_sum = total amount of the last 5 minutes"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Hourly average per sensor",
+                example2,
+                Some(
+                    "Average temperature of each sensor over the last hour, requiring sorted input",
+                ),
+            ))
+            .tag("rolling")
+            .tag("window")
+            .tag("timeseries")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Rolling time transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        check_columns(&df, &options)?;
+
+        let mut indexed = df.clone();
+        indexed.with_column(
+            time_index(&df, &options.timestamp_column, "Rolling time")?
+                .with_name(TIME_INDEX.into()),
+        )?;
+        let mut lazy = indexed.lazy();
+
+        if options.sort {
+            lazy = lazy.sort(
+                [TIME_INDEX],
+                SortMultipleOptions::default().with_maintain_order(true),
+            );
+        } else {
+            let unsorted = lazy
+                .clone()
+                .select([options.out_of_order().alias(TIME_INDEX)])
+                .collect()?;
+            if unsorted.column(TIME_INDEX)?.bool()?.any() {
+                anyhow::bail!(
+                    "Rolling time: rows are not sorted by '{}'",
+                    options.timestamp_column
+                );
+            }
+        }
+
+        let rolling: Vec<Expr> = options.values.iter().map(|v| options.rolling(v)).collect();
+        let result = lazy.with_columns(rolling).drop([TIME_INDEX]).collect()?;
+
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> DataFrame {
+        df! {
+            "ts" => &[
+                "2024-01-01T10:00:00Z",
+                "2024-01-01T10:02:00Z",
+                "2024-01-01T10:04:00Z",
+                "2024-01-01T10:05:00Z",
+                "2024-01-01T10:09:00Z",
+                "2024-01-01T10:11:00Z",
+            ],
+            "amount" => &[1i64, 2, 3, 4, 5, 6],
+        }
+        .unwrap()
+    }
+
+    fn config(pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([
+            (
+                "timestamp_column".to_string(),
+                toml::Value::String("ts".to_string()),
+            ),
+            ("window".to_string(), toml::Value::String("5m".to_string())),
+            (
+                "value".to_string(),
+                toml::Value::String("amount".to_string()),
+            ),
+        ]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    async fn run(df: DataFrame, config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        RollingTimeTransform
+            .execute(inputs, config)
+            .await?
+            .as_dataframe()
+    }
+
+    fn sums(df: &DataFrame) -> Vec<Option<i64>> {
+        df.column("amount_rolling_sum")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_five_minute_rolling_sum() {
+        let df = run(events(), &config(&[])).await.unwrap();
+        // Each window is (t - 5m, t]: 10:05 drops 10:00, 10:09 keeps 10:05 and 10:09,
+        // 10:11 keeps 10:09 and 10:11
+        assert_eq!(
+            sums(&df),
+            vec![Some(1), Some(3), Some(6), Some(9), Some(9), Some(11)]
+        );
+        assert_eq!(
+            df.get_column_names(),
+            vec!["ts", "amount", "amount_rolling_sum"]
+        );
+
+        // Closing both ends keeps 10:00 in the 10:05 window and 10:04 in the 10:09 window
+        let both = run(
+            events(),
+            &config(&[("closed", toml::Value::String("both".to_string()))]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            sums(&both),
+            vec![Some(1), Some(3), Some(6), Some(10), Some(12), Some(11)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsorted_input_and_partitions() {
+        // Epoch milliseconds, out of order, for two accounts
+        let minute = 60_000i64;
+        let shuffled = df! {
+            "ts" => &[4 * minute, 0, 9 * minute, 2 * minute, 3 * minute, 5 * minute],
+            "account" => &["a", "a", "a", "a", "b", "b"],
+            "amount" => &[3i64, 1, 5, 2, 10, 20],
+        }
+        .unwrap();
+
+        let df = run(shuffled.clone(), &config(&[])).await.unwrap();
+        let ts: Vec<Option<i64>> = df
+            .column("ts")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(ts, [0, 2, 3, 4, 5, 9].map(|m| Some(m * minute)).to_vec());
+        assert_eq!(
+            sums(&df),
+            vec![Some(1), Some(3), Some(13), Some(16), Some(35), Some(25)]
+        );
+
+        let per_account = run(
+            shuffled.clone(),
+            &config(&[("partition_by", toml::Value::String("account".to_string()))]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            sums(&per_account),
+            vec![Some(1), Some(3), Some(10), Some(6), Some(30), Some(5)]
+        );
+
+        let err = run(
+            shuffled,
+            &config(&[("unsorted", toml::Value::String("error".to_string()))]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Rolling time: rows are not sorted by 'ts'");
+
+        // Sorted input is accepted as it is
+        let sorted = run(
+            events(),
+            &config(&[("unsorted", toml::Value::String("error".to_string()))]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(sums(&sorted)[5], Some(11));
+
+        let bad_window = config(&[("window", toml::Value::String("5 minutes".to_string()))]);
+        assert!(RollingTimeTransform
+            .validate_config(&bad_window)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,361 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::{BTreeMap, HashMap};
+
+use super::common::string_list_option;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct RowHashTransform;
+
+#[derive(Debug, Clone, Copy)]
+enum Algorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Algorithm {
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "sha256" | "sha-256" => Ok(Algorithm::Sha256),
+            "sha384" | "sha-384" => Ok(Algorithm::Sha384),
+            "sha512" | "sha-512" => Ok(Algorithm::Sha512),
+            _ => anyhow::bail!(
+                "Unknown hash algorithm: '{}'. Supported: sha256, sha384, sha512",
+                s
+            ),
+        }
+    }
+
+    fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Algorithm::Sha256 => Sha256::digest(bytes).to_vec(),
+            Algorithm::Sha384 => Sha384::digest(bytes).to_vec(),
+            Algorithm::Sha512 => Sha512::digest(bytes).to_vec(),
+        }
+    }
+}
+
+#[async_trait]
+impl Stage for RowHashTransform {
+    fn name(&self) -> &str {
+        "row_hash.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "output_column".to_string(),
+            toml::Value::String("hash".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("name".to_string()),
+                toml::Value::String("email".to_string()),
+                toml::Value::String("address".to_string()),
+            ]),
+        );
+        example2.insert(
+            "algorithm".to_string(),
+            toml::Value::String("sha512".to_string()),
+        );
+        example2.insert(
+            "output_column".to_string(),
+            toml::Value::String("fingerprint".to_string()),
+        );
+
+        StageMetadata::builder("row_hash.apply", StageCategory::Transform)
+            .description("Compute a stable content hash for each row")
+            .long_description(
+                "Computes a fingerprint over the selected columns of each row (all columns by default) \
+                and writes it to an output column. Fields are hashed in sorted name order as canonical JSON, \
+                so the hash is reproducible across runs and does not change when columns are reordered. \
+                Missing selected columns hash as null. Useful for change detection and deduplication.",
+            )
+            .parameter(ConfigParameter::optional(
+                "columns",
+                ParameterType::Array,
+                "all columns",
+                "Columns to include in the hash (string or array of strings)",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "algorithm",
+                    ParameterType::String,
+                    "sha256",
+                    "Hash algorithm",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "sha256", "sha384", "sha512",
+                ])),
+            )
+            .parameter(
+                ConfigParameter::optional(
+                    "encoding",
+                    ParameterType::String,
+                    "hex",
+                    "Encoding of the hash value",
+                )
+                .with_validation(ParameterValidation::allowed_values(["hex", "base64"])),
+            )
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "hash",
+                "Name of the column receiving the hash",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Hash entire rows",
+                example1,
+                Some("Add a SHA-256 'hash' column computed over all columns"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Fingerprint selected columns",
+                example2,
+                Some("Hash only name, email and address with SHA-512"),
+            ))
+            .tag("hash")
+            .tag("fingerprint")
+            .tag("dedup")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Row hash transform requires input data"))?;
+
+        let columns = parse_columns(config)?;
+
+        let algorithm = Algorithm::from_str(
+            config
+                .get("algorithm")
+                .and_then(|v| v.as_str())
+                .unwrap_or("sha256"),
+        )?;
+
+        let encoding = config
+            .get("encoding")
+            .and_then(|v| v.as_str())
+            .unwrap_or("hex");
+
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or("hash");
+
+        let records = data.as_record_batch()?;
+
+        let hashed = records
+            .into_iter()
+            .map(|mut record| {
+                let digest = algorithm.digest(&canonical_row(&record, &columns, output_column)?);
+                let encoded = match encoding {
+                    "base64" => {
+                        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &digest)
+                    }
+                    _ => digest.iter().map(|b| format!("{:02x}", b)).collect(),
+                };
+                record.insert(output_column.to_string(), JsonValue::String(encoded));
+                Ok(record)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DataFormat::RecordBatch(hashed))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_columns(config)?;
+
+        if let Some(algorithm) = config.get("algorithm").and_then(|v| v.as_str()) {
+            Algorithm::from_str(algorithm)?;
+        }
+
+        if let Some(encoding) = config.get("encoding").and_then(|v| v.as_str()) {
+            if encoding != "hex" && encoding != "base64" {
+                anyhow::bail!("Invalid encoding: {}. Must be 'hex' or 'base64'", encoding);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_columns(config: &HashMap<String, toml::Value>) -> Result<Option<Vec<String>>> {
+    string_list_option(config, "columns")
+}
+
+/// Serialize the hashed fields as JSON with every object key sorted
+pub(crate) fn canonical_row(
+    record: &HashMap<String, JsonValue>,
+    columns: &Option<Vec<String>>,
+    output_column: &str,
+) -> Result<Vec<u8>> {
+    let fields: BTreeMap<&str, JsonValue> = match columns {
+        Some(columns) => columns
+            .iter()
+            .map(|c| {
+                let value = record
+                    .get(c)
+                    .map(canonical_value)
+                    .unwrap_or(JsonValue::Null);
+                (c.as_str(), value)
+            })
+            .collect(),
+        None => record
+            .iter()
+            .filter(|(k, _)| k.as_str() != output_column)
+            .map(|(k, v)| (k.as_str(), canonical_value(v)))
+            .collect(),
+    };
+
+    Ok(serde_json::to_vec(&fields)?)
+}
+
+fn canonical_value(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            JsonValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), canonical_value(v)))
+                    .collect(),
+            )
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.iter().map(canonical_value).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn hash_records(
+        records: Vec<HashMap<String, JsonValue>>,
+        config: HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(records));
+
+        RowHashTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_row_hash_ignores_column_order() {
+        let records = vec![
+            HashMap::from([
+                ("id".to_string(), json!(1)),
+                ("name".to_string(), json!("Alice")),
+                ("meta".to_string(), json!({"a": 1, "b": [1, 2]})),
+            ]),
+            HashMap::from([
+                ("meta".to_string(), json!({"b": [1, 2], "a": 1})),
+                ("name".to_string(), json!("Alice")),
+                ("id".to_string(), json!(1)),
+            ]),
+            HashMap::from([
+                ("id".to_string(), json!(2)),
+                ("name".to_string(), json!("Alice")),
+                ("meta".to_string(), json!({"a": 1, "b": [1, 2]})),
+            ]),
+        ];
+
+        let output = hash_records(records, HashMap::new()).await;
+
+        assert_eq!(output[0]["hash"], output[1]["hash"]);
+        assert_ne!(output[0]["hash"], output[2]["hash"]);
+        // SHA-256 in hex
+        assert_eq!(output[0]["hash"].as_str().unwrap().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_row_hash_selected_columns() {
+        let records = vec![
+            HashMap::from([
+                ("id".to_string(), json!(1)),
+                ("email".to_string(), json!("a@example.com")),
+                ("loaded_at".to_string(), json!("2024-01-01")),
+            ]),
+            HashMap::from([
+                ("id".to_string(), json!(1)),
+                ("email".to_string(), json!("a@example.com")),
+                ("loaded_at".to_string(), json!("2024-02-01")),
+            ]),
+        ];
+
+        let mut config = HashMap::new();
+        config.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("email".to_string()),
+                toml::Value::String("id".to_string()),
+            ]),
+        );
+        config.insert(
+            "algorithm".to_string(),
+            toml::Value::String("sha512".to_string()),
+        );
+        config.insert(
+            "output_column".to_string(),
+            toml::Value::String("fingerprint".to_string()),
+        );
+
+        let output = hash_records(records, config).await;
+
+        assert_eq!(output[0]["fingerprint"], output[1]["fingerprint"]);
+        assert_eq!(output[0]["fingerprint"].as_str().unwrap().len(), 128);
+    }
+
+    #[tokio::test]
+    async fn test_row_hash_is_idempotent() {
+        let records = vec![HashMap::from([("id".to_string(), json!(1))])];
+
+        let first = hash_records(records, HashMap::new()).await;
+        let second = hash_records(first.clone(), HashMap::new()).await;
+
+        // Re-hashing ignores the existing output column
+        assert_eq!(first[0]["hash"], second[0]["hash"]);
+    }
+
+    #[tokio::test]
+    async fn test_row_hash_validation() {
+        let transform = RowHashTransform;
+
+        assert!(transform.validate_config(&HashMap::new()).await.is_ok());
+
+        let mut config = HashMap::new();
+        config.insert(
+            "algorithm".to_string(),
+            toml::Value::String("md4".to_string()),
+        );
+        assert!(transform.validate_config(&config).await.is_err());
+
+        let mut config = HashMap::new();
+        config.insert("columns".to_string(), toml::Value::Integer(1));
+        assert!(transform.validate_config(&config).await.is_err());
+    }
+}
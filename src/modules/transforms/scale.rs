@@ -0,0 +1,445 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tracing::info;
+
+use super::common::string_list_option;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+use crate::utils::state_file::{load_json, save_json};
+
+pub struct ScaleTransform;
+
+const METHODS: [&str; 3] = ["minmax", "zscore", "robust"];
+
+/// Fitted parameters stored in `params_file`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ScaleParams {
+    method: String,
+    columns: BTreeMap<String, ColumnParams>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ColumnParams {
+    MinMax { min: f64, max: f64 },
+    ZScore { mean: f64, std: f64 },
+    Robust { median: f64, iqr: f64 },
+}
+
+impl ColumnParams {
+    fn fit(method: &str, name: &str, values: &Float64Chunked) -> Result<Self> {
+        let missing = || anyhow::anyhow!("Scale: column '{}' has no non-null values to fit", name);
+        Ok(match method {
+            "minmax" => ColumnParams::MinMax {
+                min: values.min().ok_or_else(missing)?,
+                max: values.max().ok_or_else(missing)?,
+            },
+            "zscore" => ColumnParams::ZScore {
+                mean: values.mean().ok_or_else(missing)?,
+                // Population standard deviation, as scikit-learn's StandardScaler
+                std: values.std(0).ok_or_else(missing)?,
+            },
+            "robust" => {
+                let q1 = values.quantile(0.25, QuantileMethod::Linear)?;
+                let q3 = values.quantile(0.75, QuantileMethod::Linear)?;
+                ColumnParams::Robust {
+                    median: values.median().ok_or_else(missing)?,
+                    iqr: q3.ok_or_else(missing)? - q1.ok_or_else(missing)?,
+                }
+            }
+            _ => anyhow::bail!(
+                "Invalid method: {}. Must be one of: {}",
+                method,
+                METHODS.join(", ")
+            ),
+        })
+    }
+
+    /// `(offset, divisor)` such that scaled = (x - offset) / divisor
+    fn offset_and_divisor(&self) -> (f64, f64) {
+        let (offset, divisor) = match *self {
+            ColumnParams::MinMax { min, max } => (min, max - min),
+            ColumnParams::ZScore { mean, std } => (mean, std),
+            ColumnParams::Robust { median, iqr } => (median, iqr),
+        };
+        // Constant columns scale to zero rather than dividing by zero
+        (offset, if divisor == 0.0 { 1.0 } else { divisor })
+    }
+
+    fn method(&self) -> &'static str {
+        match self {
+            ColumnParams::MinMax { .. } => "minmax",
+            ColumnParams::ZScore { .. } => "zscore",
+            ColumnParams::Robust { .. } => "robust",
+        }
+    }
+}
+
+fn parse_columns(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    let columns = string_list_option(config, "columns")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'columns' configuration"))?;
+    if columns.is_empty() {
+        anyhow::bail!("'columns' must name at least one column");
+    }
+    Ok(columns)
+}
+
+fn parse_method(config: &HashMap<String, toml::Value>) -> Result<&str> {
+    let method = config
+        .get("method")
+        .and_then(|v| v.as_str())
+        .unwrap_or("minmax");
+    if !METHODS.contains(&method) {
+        anyhow::bail!(
+            "Invalid method: {}. Must be one of: {}",
+            method,
+            METHODS.join(", ")
+        );
+    }
+    Ok(method)
+}
+
+fn numeric_values(df: &DataFrame, name: &str) -> Result<Float64Chunked> {
+    let column = df
+        .column(name)
+        .map_err(|_| anyhow::anyhow!("Scale: column '{}' not found", name))?;
+    if !column.dtype().is_numeric() {
+        anyhow::bail!(
+            "Scale: column '{}' is {}, not numeric",
+            name,
+            column.dtype()
+        );
+    }
+    Ok(column
+        .as_materialized_series()
+        .cast(&DataType::Float64)?
+        .f64()?
+        .clone())
+}
+
+fn fit(df: &DataFrame, method: &str, columns: &[String]) -> Result<ScaleParams> {
+    let mut fitted = BTreeMap::new();
+    for name in columns {
+        let values = numeric_values(df, name)?;
+        fitted.insert(name.clone(), ColumnParams::fit(method, name, &values)?);
+    }
+    Ok(ScaleParams {
+        method: method.to_string(),
+        columns: fitted,
+    })
+}
+
+/// Stored params checked against the stage config; `None` if not fitted yet
+async fn read_params(path: &Path, method: &str, columns: &[String]) -> Result<Option<ScaleParams>> {
+    let Some(params) = load_json::<ScaleParams>(path, "scaling params").await? else {
+        return Ok(None);
+    };
+
+    if params.method != method {
+        anyhow::bail!(
+            "Scaling params '{}' were fitted with method '{}', but the stage uses '{}'",
+            path.display(),
+            params.method,
+            method
+        );
+    }
+    for name in columns {
+        match params.columns.get(name) {
+            Some(p) if p.method() == method => {}
+            Some(_) => anyhow::bail!(
+                "Scaling params '{}' for column '{}' do not match method '{}'",
+                path.display(),
+                name,
+                method
+            ),
+            None => anyhow::bail!(
+                "Scaling params '{}' have no entry for column '{}'",
+                path.display(),
+                name
+            ),
+        }
+    }
+    Ok(Some(params))
+}
+
+#[async_trait]
+impl Stage for ScaleTransform {
+    fn name(&self) -> &str {
+        "scale.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("age".to_string()),
+                toml::Value::String("income".to_string()),
+            ]),
+        );
+        example1.insert(
+            "method".to_string(),
+            toml::Value::String("minmax".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::String("income".to_string()),
+        );
+        example2.insert(
+            "method".to_string(),
+            toml::Value::String("zscore".to_string()),
+        );
+        example2.insert(
+            "params_file".to_string(),
+            toml::Value::String("models/income_scaler.json".to_string()),
+        );
+
+        StageMetadata::builder("scale.apply", StageCategory::Transform)
+            .description("Normalize numeric columns with min-max, z-score or robust scaling")
+            .long_description(
+                "Rescales each listed numeric column to Float64. 'minmax' maps the fitted minimum \
+                and maximum to 0 and 1, 'zscore' subtracts the mean and divides by the population \
+                standard deviation, and 'robust' subtracts the median and divides by the \
+                interquartile range. Constant columns scale to 0 and nulls stay null. Without \
+                'params_file' the scaler is fitted on every run. With 'params_file', the first run \
+                fits the scaler and writes its parameters there as JSON; later runs load them \
+                instead of refitting, so inference data is scaled exactly like the training data.",
+            )
+            .parameter(ConfigParameter::required(
+                "columns",
+                ParameterType::Array,
+                "Numeric column(s) to scale",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "method",
+                    ParameterType::String,
+                    "minmax",
+                    "Scaling method",
+                )
+                .with_validation(ParameterValidation::allowed_values(METHODS)),
+            )
+            .parameter(ConfigParameter::optional(
+                "params_file",
+                ParameterType::String,
+                "none",
+                "JSON file for the fitted parameters (written on first run, reused afterwards)",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Min-max features",
+                example1,
+                Some("Scale age and income into [0, 1]"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Reuse training-time scaling",
+                example2,
+                Some("Fit on the training run, then apply the stored mean/std at inference"),
+            ))
+            .tag("scale")
+            .tag("normalize")
+            .tag("ml")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Scale transform requires input data"))?;
+
+        let columns = parse_columns(config)?;
+        let method = parse_method(config)?;
+        let df = data.as_dataframe()?;
+
+        let params = match config.get("params_file").and_then(|v| v.as_str()) {
+            Some(file) => match read_params(Path::new(file), method, &columns).await? {
+                Some(params) => {
+                    info!("Scaling with stored params from '{}'", file);
+                    params
+                }
+                None => {
+                    let params = fit(&df, method, &columns)?;
+                    save_json(Path::new(file), &params).await?;
+                    info!(
+                        "Scaling params for {} column(s) written to '{}'",
+                        columns.len(),
+                        file
+                    );
+                    params
+                }
+            },
+            None => fit(&df, method, &columns)?,
+        };
+
+        let mut exprs = Vec::with_capacity(columns.len());
+        for name in &columns {
+            // Type-check the column even when the params came from a file
+            numeric_values(&df, name)?;
+            let (offset, divisor) = params.columns[name].offset_and_divisor();
+            exprs.push(
+                ((col(name.as_str()).cast(DataType::Float64) - lit(offset)) / lit(divisor))
+                    .alias(name.as_str()),
+            );
+        }
+
+        let result = df.lazy().with_columns(exprs).collect()?;
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_columns(config)?;
+        parse_method(config)?;
+        if let Some(file) = config.get("params_file") {
+            if !file.is_str() {
+                anyhow::bail!("'params_file' must be a string");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(method: &str, params_file: Option<&Path>) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("age".to_string()),
+                toml::Value::String("income".to_string()),
+            ]),
+        );
+        config.insert(
+            "method".to_string(),
+            toml::Value::String(method.to_string()),
+        );
+        if let Some(path) = params_file {
+            config.insert(
+                "params_file".to_string(),
+                toml::Value::String(path.to_string_lossy().to_string()),
+            );
+        }
+        config
+    }
+
+    async fn run(df: DataFrame, config: &HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+        ScaleTransform.execute(inputs, config).await?.as_dataframe()
+    }
+
+    fn values(df: &DataFrame, name: &str) -> Vec<Option<f64>> {
+        df.column(name)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_minmax_scales_to_unit_range() {
+        let df = df! {
+            "age" => &[Some(20i64), Some(40), None, Some(30)],
+            "income" => &[Some(1000.0f64), Some(3000.0), Some(2000.0), Some(1000.0)],
+            "name" => &["a", "b", "c", "d"],
+        }
+        .unwrap();
+
+        let result = run(df, &config("minmax", None)).await.unwrap();
+        assert_eq!(
+            values(&result, "age"),
+            vec![Some(0.0), Some(1.0), None, Some(0.5)]
+        );
+        assert_eq!(
+            values(&result, "income"),
+            vec![Some(0.0), Some(1.0), Some(0.5), Some(0.0)]
+        );
+        assert_eq!(result.column("name").unwrap().dtype(), &DataType::String);
+    }
+
+    #[tokio::test]
+    async fn test_params_file_is_reused_on_second_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("models").join("scaler.json");
+
+        let training = df! {
+            "age" => &[10i64, 30],
+            "income" => &[100.0f64, 300.0],
+        }
+        .unwrap();
+        let result = run(training, &config("zscore", Some(&path))).await.unwrap();
+        assert_eq!(values(&result, "age"), vec![Some(-1.0), Some(1.0)]);
+
+        let stored: ScaleParams =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            stored.columns["income"],
+            ColumnParams::ZScore {
+                mean: 200.0,
+                std: 100.0
+            }
+        );
+
+        // Inference data is scaled with the training mean/std, not refitted
+        let inference = df! {
+            "age" => &[20i64, 50],
+            "income" => &[200.0f64, 200.0],
+        }
+        .unwrap();
+        let result = run(inference, &config("zscore", Some(&path)))
+            .await
+            .unwrap();
+        assert_eq!(values(&result, "age"), vec![Some(0.0), Some(3.0)]);
+        assert_eq!(values(&result, "income"), vec![Some(0.0), Some(0.0)]);
+
+        // Params fitted with another method are rejected
+        let other = df! { "age" => &[1i64], "income" => &[1.0f64] }.unwrap();
+        let err = run(other, &config("minmax", Some(&path)))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("fitted with method 'zscore'"));
+    }
+
+    #[tokio::test]
+    async fn test_robust_and_non_numeric_column() {
+        let df = df! {
+            "age" => &[1i64, 2, 3, 4, 5],
+            "income" => &[7.0f64, 7.0, 7.0, 7.0, 7.0],
+        }
+        .unwrap();
+        let result = run(df, &config("robust", None)).await.unwrap();
+        // median 3, IQR 4 - 2 = 2
+        assert_eq!(
+            values(&result, "age"),
+            vec![Some(-1.0), Some(-0.5), Some(0.0), Some(0.5), Some(1.0)]
+        );
+        // Constant column scales to zero
+        assert_eq!(values(&result, "income"), vec![Some(0.0); 5]);
+
+        let df = df! { "age" => &["x"], "income" => &[1.0f64] }.unwrap();
+        let err = run(df, &config("minmax", None))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("column 'age' is str, not numeric"));
+    }
+}
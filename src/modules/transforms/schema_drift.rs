@@ -0,0 +1,484 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct SchemaDriftTransform;
+
+/// Column list stored in `baseline_file`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Baseline {
+    columns: Vec<BaselineColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BaselineColumn {
+    name: String,
+    #[serde(rename = "type")]
+    dtype: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Drift {
+    Added {
+        column: String,
+        dtype: String,
+    },
+    Removed {
+        column: String,
+        dtype: String,
+    },
+    TypeChanged {
+        column: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl Drift {
+    /// Removed and retyped columns break downstream consumers; added ones do not
+    fn is_breaking(&self) -> bool {
+        !matches!(self, Drift::Added { .. })
+    }
+
+    fn to_record(&self) -> HashMap<String, JsonValue> {
+        let (column, change, baseline, current) = match self {
+            Drift::Added { column, dtype } => (column, "added", None, Some(dtype)),
+            Drift::Removed { column, dtype } => (column, "removed", Some(dtype), None),
+            Drift::TypeChanged { column, from, to } => {
+                (column, "type_changed", Some(from), Some(to))
+            }
+        };
+        HashMap::from([
+            ("column".to_string(), JsonValue::String(column.clone())),
+            ("change".to_string(), JsonValue::String(change.to_string())),
+            ("breaking".to_string(), JsonValue::Bool(self.is_breaking())),
+            (
+                "baseline_type".to_string(),
+                baseline.map_or(JsonValue::Null, |t| JsonValue::String(t.clone())),
+            ),
+            (
+                "current_type".to_string(),
+                current.map_or(JsonValue::Null, |t| JsonValue::String(t.clone())),
+            ),
+        ])
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Drift::Added { column, dtype } => format!("added column '{}' ({})", column, dtype),
+            Drift::Removed { column, dtype } => format!("removed column '{}' ({})", column, dtype),
+            Drift::TypeChanged { column, from, to } => {
+                format!("column '{}' changed type {} -> {}", column, from, to)
+            }
+        }
+    }
+}
+
+fn current_schema(data: &DataFormat) -> Result<Baseline> {
+    let df = data.as_dataframe()?;
+    Ok(Baseline {
+        columns: df
+            .get_columns()
+            .iter()
+            .map(|c| BaselineColumn {
+                name: c.name().to_string(),
+                dtype: c.dtype().to_string(),
+            })
+            .collect(),
+    })
+}
+
+fn compare(baseline: &Baseline, current: &Baseline) -> Vec<Drift> {
+    let current_types: HashMap<&str, &str> = current
+        .columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.dtype.as_str()))
+        .collect();
+    let baseline_types: HashMap<&str, &str> = baseline
+        .columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.dtype.as_str()))
+        .collect();
+
+    let mut drift = Vec::new();
+    for column in &baseline.columns {
+        match current_types.get(column.name.as_str()) {
+            None => drift.push(Drift::Removed {
+                column: column.name.clone(),
+                dtype: column.dtype.clone(),
+            }),
+            Some(dtype) if *dtype != column.dtype => drift.push(Drift::TypeChanged {
+                column: column.name.clone(),
+                from: column.dtype.clone(),
+                to: dtype.to_string(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for column in &current.columns {
+        if !baseline_types.contains_key(column.name.as_str()) {
+            drift.push(Drift::Added {
+                column: column.name.clone(),
+                dtype: column.dtype.clone(),
+            });
+        }
+    }
+    drift
+}
+
+fn write_baseline(path: &Path, baseline: &Baseline) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(baseline)?)
+        .with_context(|| format!("Failed to write schema baseline '{}'", path.display()))
+}
+
+#[async_trait]
+impl Stage for SchemaDriftTransform {
+    fn name(&self) -> &str {
+        "schema_drift.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "baseline_file".to_string(),
+            toml::Value::String("schemas/orders.json".to_string()),
+        );
+        example1.insert(
+            "fail_on".to_string(),
+            toml::Value::String("breaking".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "baseline_file".to_string(),
+            toml::Value::String("schemas/events.json".to_string()),
+        );
+        example2.insert(
+            "output".to_string(),
+            toml::Value::String("report".to_string()),
+        );
+
+        StageMetadata::builder("schema_drift.apply", StageCategory::Transform)
+            .description("Detect added, removed and retyped columns against a stored baseline")
+            .long_description(
+                "Compares the input's column names and types with the JSON baseline in \
+                'baseline_file'. If the file does not exist yet, the current schema is written as \
+                the baseline and the data passes through. Otherwise each difference is logged as a \
+                warning; removed and retyped columns count as breaking. 'fail_on' controls whether \
+                any drift or only breaking drift fails the stage, and with output = \"report\" the \
+                stage emits one row per difference instead of the data. Set 'update_baseline' to \
+                accept the current schema as the new baseline after comparing.",
+            )
+            .parameter(ConfigParameter::required(
+                "baseline_file",
+                ParameterType::String,
+                "Path of the JSON schema baseline (written on first run)",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "fail_on",
+                    ParameterType::String,
+                    "none",
+                    "Drift that fails the stage",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "none", "breaking", "any",
+                ])),
+            )
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "data",
+                    "Pass the input through, or emit a drift report",
+                )
+                .with_validation(ParameterValidation::allowed_values(["data", "report"])),
+            )
+            .parameter(ConfigParameter::optional(
+                "update_baseline",
+                ParameterType::Boolean,
+                "false",
+                "Overwrite the baseline with the current schema after comparing",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Guard against breaking changes",
+                example1,
+                Some("Fail when upstream removes or retypes an order column"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Drift report",
+                example2,
+                Some("Emit one row per schema difference for monitoring"),
+            ))
+            .tag("schema")
+            .tag("drift")
+            .tag("monitoring")
+            .tag("validation")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Schema drift transform requires input data"))?;
+
+        let baseline_file = config
+            .get("baseline_file")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required 'baseline_file' configuration"))?;
+        let fail_on = config
+            .get("fail_on")
+            .and_then(|v| v.as_str())
+            .unwrap_or("none");
+        let report = config.get("output").and_then(|v| v.as_str()) == Some("report");
+        let update_baseline = config
+            .get("update_baseline")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let path = Path::new(baseline_file);
+        let current = current_schema(&data)?;
+
+        if !path.exists() {
+            write_baseline(path, &current)?;
+            info!(
+                "Schema baseline '{}' created with {} column(s)",
+                baseline_file,
+                current.columns.len()
+            );
+            return Ok(if report {
+                DataFormat::RecordBatch(Vec::new())
+            } else {
+                data
+            });
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read schema baseline '{}'", baseline_file))?;
+        let baseline: Baseline = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid schema baseline '{}'", baseline_file))?;
+
+        let drift = compare(&baseline, &current);
+        for change in &drift {
+            warn!("Schema drift in '{}': {}", baseline_file, change.describe());
+        }
+
+        let failing: Vec<String> = drift
+            .iter()
+            .filter(|d| match fail_on {
+                "any" => true,
+                "breaking" => d.is_breaking(),
+                _ => false,
+            })
+            .map(|d| d.describe())
+            .collect();
+        if !failing.is_empty() {
+            anyhow::bail!(
+                "Schema drift against '{}' (fail_on = \"{}\"): {}",
+                baseline_file,
+                fail_on,
+                failing.join("; ")
+            );
+        }
+
+        if update_baseline && !drift.is_empty() {
+            write_baseline(path, &current)?;
+            info!("Schema baseline '{}' updated", baseline_file);
+        }
+
+        Ok(if report {
+            DataFormat::RecordBatch(drift.iter().map(Drift::to_record).collect())
+        } else {
+            data
+        })
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if config
+            .get("baseline_file")
+            .and_then(|v| v.as_str())
+            .is_none()
+        {
+            anyhow::bail!("Missing required 'baseline_file' configuration");
+        }
+
+        if let Some(fail_on) = config.get("fail_on").and_then(|v| v.as_str()) {
+            if !["none", "breaking", "any"].contains(&fail_on) {
+                anyhow::bail!(
+                    "Invalid fail_on: {}. Must be 'none', 'breaking', or 'any'",
+                    fail_on
+                );
+            }
+        }
+
+        if let Some(output) = config.get("output").and_then(|v| v.as_str()) {
+            if output != "data" && output != "report" {
+                anyhow::bail!("Invalid output: {}. Must be 'data' or 'report'", output);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+    use tempfile::TempDir;
+
+    fn config(path: &Path, extra: &[(&str, &str)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([(
+            "baseline_file".to_string(),
+            toml::Value::String(path.to_string_lossy().to_string()),
+        )]);
+        for (k, v) in extra {
+            config.insert(k.to_string(), toml::Value::String(v.to_string()));
+        }
+        config
+    }
+
+    async fn run(df: DataFrame, config: &HashMap<String, toml::Value>) -> Result<DataFormat> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(df));
+        SchemaDriftTransform.execute(inputs, config).await
+    }
+
+    fn baseline_df() -> DataFrame {
+        df! {
+            "id" => &[1i64, 2],
+            "name" => &["a", "b"],
+            "amount" => &[1.5f64, 2.5],
+        }
+        .unwrap()
+    }
+
+    /// Write the baseline from `baseline_df`, then compare `df` against it as a report
+    async fn report_against_baseline(df: DataFrame) -> Vec<HashMap<String, JsonValue>> {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("schema.json");
+
+        run(baseline_df(), &config(&path, &[])).await.unwrap();
+        assert!(path.exists());
+
+        run(df, &config(&path, &[("output", "report")]))
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_first_run_writes_baseline_and_passes_through() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("schema.json");
+
+        let output = run(baseline_df(), &config(&path, &[])).await.unwrap();
+        assert_eq!(output.as_dataframe().unwrap().height(), 2);
+
+        let baseline: Baseline =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let names: Vec<&str> = baseline.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "amount"]);
+
+        // Unchanged schema produces an empty report
+        let report = run(baseline_df(), &config(&path, &[("output", "report")]))
+            .await
+            .unwrap();
+        assert!(report.as_record_batch().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_added_column() {
+        let mut df = baseline_df();
+        df.with_column(Series::new("region".into(), &["eu", "us"]))
+            .unwrap();
+
+        let report = report_against_baseline(df).await;
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0]["column"], "region");
+        assert_eq!(report[0]["change"], "added");
+        assert_eq!(report[0]["breaking"], false);
+        assert_eq!(report[0]["baseline_type"], JsonValue::Null);
+    }
+
+    #[tokio::test]
+    async fn test_removed_column() {
+        let df = baseline_df().drop("name").unwrap();
+
+        let report = report_against_baseline(df).await;
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0]["column"], "name");
+        assert_eq!(report[0]["change"], "removed");
+        assert_eq!(report[0]["breaking"], true);
+    }
+
+    #[tokio::test]
+    async fn test_type_change_and_fail_policy() {
+        let retyped = || {
+            df! {
+                "id" => &["1", "2"],
+                "name" => &["a", "b"],
+                "amount" => &[1.5f64, 2.5],
+            }
+            .unwrap()
+        };
+
+        let report = report_against_baseline(retyped()).await;
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0]["column"], "id");
+        assert_eq!(report[0]["change"], "type_changed");
+        assert_eq!(report[0]["baseline_type"], "i64");
+        assert_eq!(report[0]["current_type"], "str");
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("schema.json");
+        run(baseline_df(), &config(&path, &[])).await.unwrap();
+
+        let err = match run(retyped(), &config(&path, &[("fail_on", "breaking")])).await {
+            Ok(_) => panic!("expected breaking drift to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("column 'id' changed type i64 -> str"));
+
+        // Accepting the drift makes the next run clean
+        let mut accept = config(&path, &[]);
+        accept.insert("update_baseline".to_string(), toml::Value::Boolean(true));
+        run(retyped(), &accept).await.unwrap();
+        assert!(run(retyped(), &config(&path, &[("fail_on", "any")]))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_schema_drift_validation() {
+        let transform = SchemaDriftTransform;
+        assert!(transform.validate_config(&HashMap::new()).await.is_err());
+
+        let path = Path::new("schema.json");
+        assert!(transform.validate_config(&config(path, &[])).await.is_ok());
+        assert!(transform
+            .validate_config(&config(path, &[("fail_on", "sometimes")]))
+            .await
+            .is_err());
+    }
+}
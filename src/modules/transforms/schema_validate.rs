@@ -0,0 +1,744 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+use crate::modules::transforms::http_fetch::request_headers;
+use crate::utils::http::HttpContext;
+
+/// Schema Validate Transform
+/// Validates records against a subject's schema from a Confluent-compatible schema registry
+pub struct SchemaValidateTransform {
+    /// Fetched schemas by registry URL, converted to JSON Schema
+    schemas: Mutex<HashMap<String, Arc<JsonValue>>>,
+}
+
+impl Default for SchemaValidateTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchemaValidateTransform {
+    pub fn new() -> Self {
+        Self {
+            schemas: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Field added to rejected rows explaining why they were rejected
+const REJECT_REASON: &str = "_reject_reason";
+
+struct Options {
+    schema_url: String,
+    fail_on_invalid: bool,
+    emit_rejects: bool,
+    coerce: bool,
+    headers: HashMap<String, String>,
+    http: HttpContext,
+}
+
+fn required_string<'a>(config: &'a HashMap<String, toml::Value>, name: &str) -> Result<&'a str> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) if !s.is_empty() => Ok(s),
+        Some(_) => anyhow::bail!("'{}' must be a non-empty string", name),
+        None => anyhow::bail!("Missing required '{}' configuration", name),
+    }
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let registry_url = required_string(config, "registry_url")?;
+    let subject = required_string(config, "subject")?;
+    let version = match config.get("version") {
+        Some(toml::Value::Integer(n)) if *n > 0 => n.to_string(),
+        Some(toml::Value::String(s)) if s == "latest" => s.clone(),
+        Some(_) => anyhow::bail!("'version' must be a positive integer or \"latest\""),
+        None => "latest".to_string(),
+    };
+
+    let fail_on_invalid = match config.get("on_invalid").and_then(|v| v.as_str()) {
+        None | Some("reject") => false,
+        Some("fail") => true,
+        Some(other) => anyhow::bail!(
+            "Invalid on_invalid policy: '{}'. Must be 'reject' or 'fail'",
+            other
+        ),
+    };
+    let emit_rejects = match config.get("output").and_then(|v| v.as_str()) {
+        None | Some("valid") => false,
+        Some("rejects") => true,
+        Some(other) => anyhow::bail!("Invalid output '{}'. Must be 'valid' or 'rejects'", other),
+    };
+    let coerce = match config.get("coerce") {
+        Some(toml::Value::Boolean(b)) => *b,
+        Some(_) => anyhow::bail!("'coerce' must be a boolean"),
+        None => false,
+    };
+
+    Ok(Options {
+        schema_url: format!(
+            "{}/subjects/{}/versions/{}",
+            registry_url.trim_end_matches('/'),
+            subject,
+            version
+        ),
+        fail_on_invalid,
+        emit_rejects,
+        coerce,
+        headers: request_headers(config)?,
+        http: HttpContext::from_config(config)?,
+    })
+}
+
+/// Parse a registry response into JSON Schema, converting Avro schemas
+fn registered_schema(response: &JsonValue) -> Result<JsonValue> {
+    let schema_text = response
+        .get("schema")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Schema registry response has no 'schema' string"))?;
+    let schema: JsonValue =
+        serde_json::from_str(schema_text).context("Registered schema is not valid JSON")?;
+
+    // The registry omits schemaType for Avro, its original format
+    match response
+        .get("schemaType")
+        .and_then(|v| v.as_str())
+        .unwrap_or("AVRO")
+    {
+        "JSON" => Ok(schema),
+        "AVRO" => Ok(avro_to_json_schema(&schema)),
+        other => anyhow::bail!(
+            "Schema type {} is not supported; register the subject as JSON or AVRO",
+            other
+        ),
+    }
+}
+
+fn avro_to_json_schema(avro: &JsonValue) -> JsonValue {
+    match avro {
+        JsonValue::String(name) => match name.as_str() {
+            "null" => json!({"type": "null"}),
+            "boolean" => json!({"type": "boolean"}),
+            "int" | "long" => json!({"type": "integer"}),
+            "float" | "double" => json!({"type": "number"}),
+            "string" | "bytes" => json!({"type": "string"}),
+            // References to named types are not resolved
+            _ => json!({}),
+        },
+        JsonValue::Array(branches) => {
+            let branches: Vec<JsonValue> = branches.iter().map(avro_to_json_schema).collect();
+            let types: Vec<JsonValue> = branches
+                .iter()
+                .filter_map(|b| b.get("type").cloned())
+                .collect();
+            if types.len() < branches.len() {
+                return json!({});
+            }
+            // Nested constraints come from the first non-null branch
+            let mut schema = branches
+                .iter()
+                .find(|b| b["type"] != "null")
+                .cloned()
+                .unwrap_or_else(|| json!({}));
+            schema["type"] = JsonValue::Array(types);
+            schema
+        }
+        JsonValue::Object(avro) => match avro.get("type").and_then(|t| t.as_str()) {
+            Some("record") => {
+                let fields = avro
+                    .get("fields")
+                    .and_then(|f| f.as_array())
+                    .cloned()
+                    .unwrap_or_default();
+                let mut properties = serde_json::Map::new();
+                let mut required = Vec::new();
+                for field in &fields {
+                    let Some(name) = field.get("name").and_then(|n| n.as_str()) else {
+                        continue;
+                    };
+                    let schema = avro_to_json_schema(&field["type"]);
+                    if field.get("default").is_none() && !allows_null(&schema) {
+                        required.push(JsonValue::String(name.to_string()));
+                    }
+                    properties.insert(name.to_string(), schema);
+                }
+                json!({"type": "object", "properties": properties, "required": required})
+            }
+            Some("array") => json!({"type": "array", "items": avro_to_json_schema(&avro["items"])}),
+            Some("map") => json!({"type": "object"}),
+            Some("enum") => {
+                json!({"type": "string", "enum": avro.get("symbols").cloned().unwrap_or_default()})
+            }
+            Some("fixed") => json!({"type": "string"}),
+            // A primitive with attributes, e.g. a logical type
+            Some(_) => avro_to_json_schema(&avro["type"]),
+            None => json!({}),
+        },
+        _ => json!({}),
+    }
+}
+
+fn schema_types(schema: &JsonValue) -> Vec<&str> {
+    match schema.get("type") {
+        Some(JsonValue::String(t)) => vec![t.as_str()],
+        Some(JsonValue::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn allows_null(schema: &JsonValue) -> bool {
+    schema_types(schema).contains(&"null")
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &JsonValue, expected: &str) -> bool {
+    match (expected, value) {
+        ("number", JsonValue::Number(_)) => true,
+        ("integer", JsonValue::Number(n)) => n.as_f64().is_some_and(|f| f.fract() == 0.0),
+        _ => type_name(value) == expected,
+    }
+}
+
+/// Convert `value` to `expected` when it is an unambiguous rendering of one
+fn coerced(value: &JsonValue, expected: &str) -> Option<JsonValue> {
+    match (expected, value) {
+        ("integer", JsonValue::String(s)) => s.trim().parse::<i64>().ok().map(JsonValue::from),
+        ("number", JsonValue::String(s)) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .map(JsonValue::from),
+        ("boolean", JsonValue::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(JsonValue::Bool(true)),
+            "false" => Some(JsonValue::Bool(false)),
+            _ => None,
+        },
+        ("string", JsonValue::Number(n)) => Some(JsonValue::String(n.to_string())),
+        ("string", JsonValue::Bool(b)) => Some(JsonValue::String(b.to_string())),
+        _ => None,
+    }
+}
+
+fn field_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+/// Check `value` against `schema`, coercing in place when allowed, and collect errors
+fn check(
+    schema: &JsonValue,
+    value: &mut JsonValue,
+    path: &str,
+    coerce: bool,
+    errors: &mut Vec<String>,
+) {
+    let types = schema_types(schema);
+    if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+        match types
+            .iter()
+            .find_map(|t| coerced(value, t).filter(|_| coerce))
+        {
+            Some(converted) => *value = converted,
+            None => {
+                errors.push(format!(
+                    "field '{}' expected {}, got {}",
+                    path,
+                    types.join(" or "),
+                    type_name(value)
+                ));
+                return;
+            }
+        }
+    }
+
+    if let Some(JsonValue::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "field '{}' value {} is not in the enum",
+                path, value
+            ));
+        }
+    }
+
+    match value {
+        JsonValue::Object(fields) => {
+            if let Some(JsonValue::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(|r| r.as_str()) {
+                    if !fields.contains_key(name) {
+                        errors.push(format!(
+                            "missing required field '{}'",
+                            field_path(path, name)
+                        ));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            let closed = schema.get("additionalProperties") == Some(&JsonValue::Bool(false));
+            for (name, field) in fields.iter_mut() {
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => {
+                        check(field_schema, field, &field_path(path, name), coerce, errors)
+                    }
+                    None if closed => errors.push(format!(
+                        "field '{}' is not allowed by the schema",
+                        field_path(path, name)
+                    )),
+                    None => {}
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter_mut().enumerate() {
+                    check(
+                        item_schema,
+                        item,
+                        &format!("{}[{}]", path, i),
+                        coerce,
+                        errors,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+impl SchemaValidateTransform {
+    /// The schema for `options`, fetched from the registry on first use
+    async fn schema(&self, options: &Options) -> Result<Arc<JsonValue>> {
+        if let Some(schema) = self.schemas.lock().unwrap().get(&options.schema_url) {
+            return Ok(Arc::clone(schema));
+        }
+
+        let response = options
+            .http
+            .send(|client| {
+                let mut request = client.get(&options.schema_url);
+                for (key, value) in &options.headers {
+                    request = request.header(key, value);
+                }
+                request
+            })
+            .await
+            .with_context(|| {
+                format!("Failed to reach schema registry at {}", options.schema_url)
+            })?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!(
+                "Schema registry returned {} for {}",
+                status,
+                options.schema_url
+            );
+        }
+        let body: JsonValue = response.json().await?;
+        let schema = Arc::new(registered_schema(&body)?);
+
+        tracing::info!("Fetched schema from {}", options.schema_url);
+        self.schemas
+            .lock()
+            .unwrap()
+            .insert(options.schema_url.clone(), Arc::clone(&schema));
+        Ok(schema)
+    }
+}
+
+#[async_trait]
+impl Stage for SchemaValidateTransform {
+    fn name(&self) -> &str {
+        "schema_validate.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "registry_url".to_string(),
+            toml::Value::String("http://schema-registry:8081".to_string()),
+        );
+        example1.insert(
+            "subject".to_string(),
+            toml::Value::String("orders-value".to_string()),
+        );
+        example1.insert("coerce".to_string(), toml::Value::Boolean(true));
+
+        let mut example2 = example1.clone();
+        example2.remove("coerce");
+        example2.insert("version".to_string(), toml::Value::Integer(3));
+        example2.insert(
+            "output".to_string(),
+            toml::Value::String("rejects".to_string()),
+        );
+
+        StageMetadata::builder("schema_validate.apply", StageCategory::Transform)
+            .description("Validate records against a schema registry subject")
+            .long_description(
+                "Fetches the schema registered for 'subject' from a Confluent-compatible schema \
+                registry ('registry_url'/subjects/<subject>/versions/<version>) once and caches it \
+                for the lifetime of the stage. JSON schemas are checked for type, required, \
+                properties, additionalProperties, enum and items; Avro record schemas are \
+                converted, with fields lacking a default and a null branch being required. \
+                Protobuf schemas are not supported. With 'coerce', strings holding numbers or \
+                booleans and numbers or booleans where a string is expected are converted instead \
+                of rejected. Invalid records are dropped ('on_invalid = \"reject\"') or fail the \
+                stage ('fail'); with 'output = \"rejects\"' the stage emits only the rejected \
+                records, each with a '_reject_reason' field, so a second stage with the same \
+                settings can route them to a sink.",
+            )
+            .parameter(ConfigParameter::required(
+                "registry_url",
+                ParameterType::String,
+                "Base URL of the schema registry",
+            ))
+            .parameter(ConfigParameter::required(
+                "subject",
+                ParameterType::String,
+                "Registry subject, e.g. 'orders-value'",
+            ))
+            .parameter(ConfigParameter::optional(
+                "version",
+                ParameterType::String,
+                "latest",
+                "Schema version number, or \"latest\"",
+            ))
+            .parameter(ConfigParameter::optional(
+                "coerce",
+                ParameterType::Boolean,
+                "false",
+                "Convert mismatched scalar types when the conversion is unambiguous",
+            ))
+            .parameter(
+                ConfigParameter::optional(
+                    "on_invalid",
+                    ParameterType::String,
+                    "reject",
+                    "Policy for records that do not match the schema",
+                )
+                .with_validation(ParameterValidation::allowed_values(["reject", "fail"])),
+            )
+            .parameter(
+                ConfigParameter::optional(
+                    "output",
+                    ParameterType::String,
+                    "valid",
+                    "Emit the valid records or only the rejected ones",
+                )
+                .with_validation(ParameterValidation::allowed_values(["valid", "rejects"])),
+            )
+            .parameter(ConfigParameter::optional(
+                "headers",
+                ParameterType::Object,
+                "none",
+                "Map of HTTP headers sent to the registry",
+            ))
+            .parameter(ConfigParameter::optional(
+                "api_key_env",
+                ParameterType::String,
+                "none",
+                "Environment variable holding the registry API key, resolved at execution",
+            ))
+            .parameter(ConfigParameter::optional(
+                "api_key_header",
+                ParameterType::String,
+                "Authorization",
+                "Header carrying the API key (sent as 'Bearer <key>' for Authorization)",
+            ))
+            .parameters(crate::utils::http::parameters())
+            .example(crate::core::metadata::ConfigExample::new(
+                "Validate and coerce order events",
+                example1,
+                Some("Keep records matching the latest orders-value schema, fixing stringly-typed numbers"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Collect rejects",
+                example2,
+                Some("Emit the records that do not match version 3 of the schema"),
+            ))
+            .tag("schema")
+            .tag("registry")
+            .tag("validation")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Schema validate transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let schema = self.schema(&options).await?;
+
+        let records = data.as_record_batch()?;
+        let mut valid = Vec::with_capacity(records.len());
+        let mut rejects = Vec::new();
+
+        for (index, record) in records.into_iter().enumerate() {
+            let mut value = JsonValue::Object(record.into_iter().collect());
+            let mut errors = Vec::new();
+            check(&schema, &mut value, "", options.coerce, &mut errors);
+            let JsonValue::Object(fields) = value else {
+                unreachable!("records stay objects when checked");
+            };
+            let mut record: HashMap<String, JsonValue> = fields.into_iter().collect();
+
+            if errors.is_empty() {
+                valid.push(record);
+            } else if options.fail_on_invalid {
+                anyhow::bail!(
+                    "Schema validation failed for record {}: {}",
+                    index,
+                    errors.join("; ")
+                );
+            } else {
+                record.insert(
+                    REJECT_REASON.to_string(),
+                    JsonValue::String(errors.join("; ")),
+                );
+                rejects.push(record);
+            }
+        }
+
+        if !rejects.is_empty() {
+            tracing::warn!(
+                "Schema validate: rejected {} record(s) not matching {}",
+                rejects.len(),
+                options.schema_url
+            );
+        }
+
+        Ok(DataFormat::RecordBatch(if options.emit_rejects {
+            rejects
+        } else {
+            valid
+        }))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const ORDER_SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["order_id", "amount"],
+        "properties": {
+            "order_id": {"type": "integer"},
+            "amount": {"type": "number"},
+            "status": {"type": "string", "enum": ["new", "paid"]}
+        }
+    }"#;
+
+    /// Serve `ORDER_SCHEMA` as a JSON subject, counting requests
+    async fn serve_registry(requests: Arc<AtomicUsize>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                requests.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+
+                let (status, body) =
+                    if request.starts_with("GET /subjects/orders-value/versions/latest ") {
+                        let body = json!({
+                            "subject": "orders-value",
+                            "version": 1,
+                            "id": 7,
+                            "schemaType": "JSON",
+                            "schema": ORDER_SCHEMA,
+                        });
+                        ("200 OK", body.to_string())
+                    } else {
+                        ("404 Not Found", r#"{"error_code": 40401}"#.to_string())
+                    };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    fn config(registry_url: &str, pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([
+            (
+                "registry_url".to_string(),
+                toml::Value::String(registry_url.to_string()),
+            ),
+            (
+                "subject".to_string(),
+                toml::Value::String("orders-value".to_string()),
+            ),
+        ]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    fn orders() -> DataFormat {
+        DataFormat::RecordBatch(vec![
+            HashMap::from([
+                ("order_id".to_string(), json!(1)),
+                ("amount".to_string(), json!("19.99")),
+                ("status".to_string(), json!("paid")),
+            ]),
+            HashMap::from([
+                ("order_id".to_string(), json!(2)),
+                ("status".to_string(), json!("new")),
+            ]),
+        ])
+    }
+
+    async fn run(
+        stage: &SchemaValidateTransform,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<Vec<HashMap<String, JsonValue>>> {
+        stage
+            .execute(HashMap::from([("input".to_string(), orders())]), config)
+            .await?
+            .as_record_batch()
+    }
+
+    #[tokio::test]
+    async fn test_schema_validate_coerces_conforming_record() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let registry = serve_registry(Arc::clone(&requests)).await;
+        let stage = SchemaValidateTransform::new();
+        let coerce = config(&registry, &[("coerce", toml::Value::Boolean(true))]);
+
+        let valid = run(&stage, &coerce).await.unwrap();
+        assert_eq!(valid.len(), 1);
+        assert_eq!(valid[0]["order_id"], 1);
+        assert_eq!(valid[0]["amount"], json!(19.99));
+
+        // Without coercion the string amount is a mismatch; the schema is cached
+        let strict = config(
+            &registry,
+            &[("output", toml::Value::String("rejects".to_string()))],
+        );
+        let rejects = run(&stage, &strict).await.unwrap();
+        assert_eq!(rejects.len(), 2);
+        assert_eq!(
+            rejects[0][REJECT_REASON],
+            "field 'amount' expected number, got string"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let missing = config(
+            &registry,
+            &[("subject", toml::Value::String("users".to_string()))],
+        );
+        let err = run(&stage, &missing).await.unwrap_err().to_string();
+        assert!(err.contains("404 Not Found"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_schema_validate_rejects_missing_required_field() {
+        let registry = serve_registry(Arc::new(AtomicUsize::new(0))).await;
+        let stage = SchemaValidateTransform::new();
+
+        let rejects = config(
+            &registry,
+            &[
+                ("coerce", toml::Value::Boolean(true)),
+                ("output", toml::Value::String("rejects".to_string())),
+            ],
+        );
+        let rejected = run(&stage, &rejects).await.unwrap();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0]["order_id"], 2);
+        assert_eq!(
+            rejected[0][REJECT_REASON],
+            "missing required field 'amount'"
+        );
+
+        let fail = config(
+            &registry,
+            &[
+                ("coerce", toml::Value::Boolean(true)),
+                ("on_invalid", toml::Value::String("fail".to_string())),
+            ],
+        );
+        let err = run(&stage, &fail).await.unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Schema validation failed for record 1: missing required field 'amount'"
+        );
+    }
+
+    #[test]
+    fn test_avro_record_schema_conversion() {
+        let avro = json!({
+            "type": "record",
+            "name": "User",
+            "fields": [
+                {"name": "id", "type": "long"},
+                {"name": "email", "type": ["null", "string"]},
+                {"name": "tier", "type": {"type": "enum", "name": "Tier", "symbols": ["free", "pro"]}, "default": "free"}
+            ]
+        });
+        let schema = registered_schema(&json!({"schema": avro.to_string()})).unwrap();
+        assert_eq!(schema["required"], json!(["id"]));
+
+        let mut user = json!({"id": 1.5, "email": null, "tier": "gold"});
+        let mut errors = Vec::new();
+        check(&schema, &mut user, "", false, &mut errors);
+        assert_eq!(
+            errors,
+            vec![
+                "field 'id' expected integer, got number".to_string(),
+                "field 'tier' value \"gold\" is not in the enum".to_string(),
+            ]
+        );
+
+        let err =
+            registered_schema(&json!({"schema": "{}", "schemaType": "PROTOBUF"})).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Schema type PROTOBUF is not supported"));
+    }
+}
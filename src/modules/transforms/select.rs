@@ -1,9 +1,10 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use polars::prelude::*;
 use std::collections::HashMap;
 
 use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
-use crate::core::stage::Stage;
+use crate::core::stage::{MissingColumnPolicy, Stage};
 use crate::core::traits::DataFormat;
 
 pub struct SelectTransform;
@@ -30,13 +31,18 @@ impl Stage for SelectTransform {
             .long_description(
                 "Selects a subset of columns from the input DataFrame, similar to SQL SELECT. \
                 Can specify columns as a single string or an array of strings. \
-                Useful for reducing data size and focusing on relevant fields.",
+                Useful for reducing data size and focusing on relevant fields. \
+                Absent columns fail the stage unless 'on_missing_column' leaves them out ('skip') \
+                or adds them filled with nulls ('null').",
             )
             .parameter(ConfigParameter::required(
                 "columns",
                 ParameterType::String,
                 "Column name(s) to select (string or array of strings)",
             ))
+            .parameter(MissingColumnPolicy::parameter(
+                "What to do with absent columns: 'error', 'skip' them, or select them as 'null'",
+            ))
             .example(crate::core::metadata::ConfigExample::new(
                 "Select multiple columns",
                 example_config,
@@ -73,7 +79,27 @@ impl Stage for SelectTransform {
             anyhow::bail!("Select requires 'columns' configuration");
         };
 
-        let df = data.as_dataframe()?;
+        let policy = MissingColumnPolicy::from_config(config)?;
+        let mut df = data.as_dataframe()?;
+        let missing: Vec<String> = policy
+            .missing("Select", &df, columns.iter().map(String::as_str))?
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let columns: Vec<String> = match policy {
+            MissingColumnPolicy::Skip => columns
+                .into_iter()
+                .filter(|c| !missing.contains(c))
+                .collect(),
+            _ => {
+                for name in &missing {
+                    let nulls = Series::full_null(name.into(), df.height(), &DataType::Null);
+                    df.with_column(nulls)?;
+                }
+                columns
+            }
+        };
 
         // Select specified columns
         let result = df.select(&columns)?;
@@ -85,6 +111,48 @@ impl Stage for SelectTransform {
             anyhow::bail!("Select requires 'columns' configuration");
         }
 
+        MissingColumnPolicy::from_config(config)?;
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn select(policy: &str) -> Result<DataFrame> {
+        let df = df! { "id" => &[1i64, 2], "name" => &["a", "b"] }.unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        let config = HashMap::from([
+            (
+                "columns".to_string(),
+                toml::Value::Array(vec![
+                    toml::Value::String("email".to_string()),
+                    toml::Value::String("id".to_string()),
+                ]),
+            ),
+            (
+                "on_missing_column".to_string(),
+                toml::Value::String(policy.to_string()),
+            ),
+        ]);
+        SelectTransform
+            .execute(inputs, &config)
+            .await?
+            .as_dataframe()
+    }
+
+    #[tokio::test]
+    async fn test_select_missing_column_policies() {
+        let err = select("error").await.unwrap_err().to_string();
+        assert_eq!(err, "Select: column 'email' not found");
+
+        let skipped = select("skip").await.unwrap();
+        assert_eq!(skipped.get_column_names(), vec!["id"]);
+
+        let nulls = select("null").await.unwrap();
+        assert_eq!(nulls.get_column_names(), vec!["email", "id"]);
+        assert_eq!(nulls.column("email").unwrap().null_count(), 2);
+    }
+}
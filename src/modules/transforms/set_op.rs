@@ -0,0 +1,416 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct SetOpTransform;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SetOp {
+    Intersect,
+    Except,
+    SymmetricDifference,
+    Union,
+}
+
+impl SetOp {
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "intersect" => Ok(SetOp::Intersect),
+            "except" => Ok(SetOp::Except),
+            "symmetric_difference" => Ok(SetOp::SymmetricDifference),
+            "union" => Ok(SetOp::Union),
+            _ => anyhow::bail!(
+                "Unknown set operation: '{}'. Supported: intersect, except, symmetric_difference, union",
+                s
+            ),
+        }
+    }
+}
+
+fn parse_op(config: &HashMap<String, toml::Value>) -> Result<SetOp> {
+    match config.get("op") {
+        Some(toml::Value::String(s)) => SetOp::from_str(s),
+        Some(_) => anyhow::bail!("'op' must be a string"),
+        None => anyhow::bail!("Missing required 'op' configuration"),
+    }
+}
+
+fn parse_key(config: &HashMap<String, toml::Value>) -> Result<Vec<String>> {
+    string_list_option(config, "key")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'key' configuration"))
+}
+
+/// Ensure both inputs carry every key column with the same type
+fn check_key_columns(left: &DataFrame, right: &DataFrame, key: &[String]) -> Result<()> {
+    for column in key {
+        let left_type = left
+            .column(column)
+            .map(|c| c.dtype().clone())
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Set operation: key column '{}' not found in left input",
+                    column
+                )
+            })?;
+        let right_type = right
+            .column(column)
+            .map(|c| c.dtype().clone())
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Set operation: key column '{}' not found in right input",
+                    column
+                )
+            })?;
+        if left_type != right_type {
+            anyhow::bail!(
+                "Set operation: key column '{}' is {} in left input but {} in right input",
+                column,
+                left_type,
+                right_type
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Rows of `left` whose key does (semi) or does not (anti) appear in `right`
+fn filter_by_key(left: &DataFrame, right: &DataFrame, key: &[Expr], how: JoinType) -> LazyFrame {
+    left.clone()
+        .lazy()
+        .join(right.clone().lazy(), key, key, JoinArgs::new(how))
+}
+
+#[async_trait]
+impl Stage for SetOpTransform {
+    fn name(&self) -> &str {
+        "set_op.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "left".to_string(),
+            toml::Value::String("ledger".to_string()),
+        );
+        example1.insert(
+            "right".to_string(),
+            toml::Value::String("bank_statement".to_string()),
+        );
+        example1.insert("op".to_string(), toml::Value::String("except".to_string()));
+        example1.insert(
+            "key".to_string(),
+            toml::Value::String("transaction_id".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "op".to_string(),
+            toml::Value::String("symmetric_difference".to_string()),
+        );
+        example2.insert(
+            "key".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("region".to_string()),
+                toml::Value::String("sku".to_string()),
+            ]),
+        );
+
+        StageMetadata::builder("set_op.apply", StageCategory::Transform)
+            .description("Compute intersections and differences of two inputs by key")
+            .long_description(
+                "Compares the rows of a left and a right input on the 'key' columns. 'intersect' \
+                keeps left rows whose key also appears on the right, 'except' keeps left rows whose \
+                key does not, 'symmetric_difference' returns the unmatched rows of both sides, and \
+                'union' returns every left row plus the right rows whose key is not on the left. \
+                Both inputs must contain the key columns with the same types; null keys never \
+                match. When rows from both sides are combined, columns are aligned by name and \
+                missing ones are filled with nulls. The 'left' and 'right' options name the input \
+                stages; they default to inputs called 'left' and 'right'.",
+            )
+            .parameter(
+                ConfigParameter::required("op", ParameterType::String, "Set operation to apply")
+                    .with_validation(ParameterValidation::allowed_values([
+                        "intersect",
+                        "except",
+                        "symmetric_difference",
+                        "union",
+                    ])),
+            )
+            .parameter(ConfigParameter::required(
+                "key",
+                ParameterType::Array,
+                "Key column(s) identifying a row (string or array of strings)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "left",
+                ParameterType::String,
+                "left",
+                "Input stage providing the left rows",
+            ))
+            .parameter(ConfigParameter::optional(
+                "right",
+                ParameterType::String,
+                "right",
+                "Input stage providing the right rows",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Unreconciled ledger entries",
+                example1,
+                Some("Find ledger transactions missing from the bank statement"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Mismatches on both sides",
+                example2,
+                Some("Rows present in only one of the inputs, keyed on region and sku"),
+            ))
+            .tag("set")
+            .tag("join")
+            .tag("reconciliation")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        mut inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let op = parse_op(config)?;
+        let key = parse_key(config)?;
+        if key.is_empty() {
+            anyhow::bail!("'key' must name at least one column");
+        }
+        let left_name = config
+            .get("left")
+            .and_then(|v| v.as_str())
+            .unwrap_or("left");
+        let right_name = config
+            .get("right")
+            .and_then(|v| v.as_str())
+            .unwrap_or("right");
+
+        let mut available: Vec<&String> = inputs.keys().collect();
+        available.sort();
+        let available = format!("{:?}", available);
+
+        let left = inputs.remove(left_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Set operation: left input '{}' not found (available inputs: {})",
+                left_name,
+                available
+            )
+        })?;
+        let right = inputs.remove(right_name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Set operation: right input '{}' not found (available inputs: {})",
+                right_name,
+                available
+            )
+        })?;
+
+        let left = left.as_dataframe()?;
+        let right = right.as_dataframe()?;
+        check_key_columns(&left, &right, &key)?;
+
+        let key_exprs: Vec<Expr> = key.iter().map(|k| col(k.as_str())).collect();
+
+        let result = match op {
+            SetOp::Intersect => filter_by_key(&left, &right, &key_exprs, JoinType::Semi),
+            SetOp::Except => filter_by_key(&left, &right, &key_exprs, JoinType::Anti),
+            SetOp::SymmetricDifference => concat_lf_diagonal(
+                [
+                    filter_by_key(&left, &right, &key_exprs, JoinType::Anti),
+                    filter_by_key(&right, &left, &key_exprs, JoinType::Anti),
+                ],
+                UnionArgs::default(),
+            )?,
+            SetOp::Union => concat_lf_diagonal(
+                [
+                    left.clone().lazy(),
+                    filter_by_key(&right, &left, &key_exprs, JoinType::Anti),
+                ],
+                UnionArgs::default(),
+            )?,
+        };
+
+        Ok(DataFormat::DataFrame(result.collect()?))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_op(config)?;
+        if parse_key(config)?.is_empty() {
+            anyhow::bail!("'key' must name at least one column");
+        }
+
+        for option in ["left", "right"] {
+            if let Some(value) = config.get(option) {
+                if value.as_str().is_none() {
+                    anyhow::bail!("'{}' must be a string naming an input stage", option);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(left: DataFrame, right: DataFrame) -> HashMap<String, DataFormat> {
+        let mut inputs = HashMap::new();
+        inputs.insert("left".to_string(), DataFormat::DataFrame(left));
+        inputs.insert("right".to_string(), DataFormat::DataFrame(right));
+        inputs
+    }
+
+    fn config(op: &str) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::new();
+        config.insert("op".to_string(), toml::Value::String(op.to_string()));
+        config.insert("key".to_string(), toml::Value::String("id".to_string()));
+        config
+    }
+
+    async fn run(op: &str, left: &DataFrame, right: &DataFrame) -> DataFrame {
+        SetOpTransform
+            .execute(inputs(left.clone(), right.clone()), &config(op))
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    fn sorted_ids(df: &DataFrame) -> Vec<i64> {
+        let mut ids: Vec<i64> = df
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn overlapping() -> (DataFrame, DataFrame) {
+        let left = df! {
+            "id" => [1i64, 2, 3],
+            "amount" => [10i64, 20, 30],
+        }
+        .unwrap();
+        let right = df! {
+            "id" => [2i64, 3, 4],
+            "status" => ["cleared", "cleared", "pending"],
+        }
+        .unwrap();
+        (left, right)
+    }
+
+    fn disjoint() -> (DataFrame, DataFrame) {
+        let left = df! { "id" => [1i64, 2] }.unwrap();
+        let right = df! { "id" => [3i64, 4] }.unwrap();
+        (left, right)
+    }
+
+    #[tokio::test]
+    async fn test_set_op_intersect() {
+        let (left, right) = overlapping();
+        let result = run("intersect", &left, &right).await;
+        assert_eq!(sorted_ids(&result), vec![2, 3]);
+        // Only left columns are kept
+        assert_eq!(result.get_column_names(), vec!["id", "amount"]);
+
+        let (left, right) = disjoint();
+        assert_eq!(run("intersect", &left, &right).await.height(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_op_except() {
+        let (left, right) = overlapping();
+        assert_eq!(sorted_ids(&run("except", &left, &right).await), vec![1]);
+
+        let (left, right) = disjoint();
+        assert_eq!(sorted_ids(&run("except", &left, &right).await), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_set_op_symmetric_difference() {
+        let (left, right) = overlapping();
+        let result = run("symmetric_difference", &left, &right).await;
+        assert_eq!(sorted_ids(&result), vec![1, 4]);
+        // Columns from both sides, nulls where a side lacks them
+        assert_eq!(result.get_column_names(), vec!["id", "amount", "status"]);
+        assert_eq!(result.column("amount").unwrap().null_count(), 1);
+        assert_eq!(result.column("status").unwrap().null_count(), 1);
+
+        let (left, right) = disjoint();
+        assert_eq!(
+            sorted_ids(&run("symmetric_difference", &left, &right).await),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_op_union() {
+        let (left, right) = overlapping();
+        let result = run("union", &left, &right).await;
+        assert_eq!(sorted_ids(&result), vec![1, 2, 3, 4]);
+        // Matched keys keep the left row
+        assert_eq!(result.column("status").unwrap().null_count(), 3);
+
+        let (left, right) = disjoint();
+        assert_eq!(
+            sorted_ids(&run("union", &left, &right).await),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_op_key_validation() {
+        let left = df! { "id" => [1i64] }.unwrap();
+        let right = df! { "other" => [1i64] }.unwrap();
+        let err = match SetOpTransform
+            .execute(inputs(left.clone(), right), &config("intersect"))
+            .await
+        {
+            Ok(_) => panic!("expected missing key column to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("key column 'id' not found in right input"));
+
+        let right = df! { "id" => ["1"] }.unwrap();
+        let err = match SetOpTransform
+            .execute(inputs(left, right), &config("intersect"))
+            .await
+        {
+            Ok(_) => panic!("expected mismatched key types to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("is i64 in left input but str in right input"));
+    }
+
+    #[tokio::test]
+    async fn test_set_op_validation() {
+        let transform = SetOpTransform;
+
+        assert!(transform.validate_config(&config("union")).await.is_ok());
+        assert!(transform.validate_config(&config("merge")).await.is_err());
+
+        let mut missing_key = config("union");
+        missing_key.remove("key");
+        assert!(transform.validate_config(&missing_key).await.is_err());
+
+        let mut bad_input = config("union");
+        bad_input.insert("left".to_string(), toml::Value::Integer(1));
+        assert!(transform.validate_config(&bad_input).await.is_err());
+    }
+}
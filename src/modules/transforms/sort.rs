@@ -3,8 +3,9 @@ use async_trait::async_trait;
 use polars::prelude::*;
 use std::collections::HashMap;
 
+use crate::core::memory;
 use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
-use crate::core::stage::Stage;
+use crate::core::stage::{MissingColumnPolicy, Stage};
 use crate::core::traits::DataFormat;
 
 pub struct SortTransform;
@@ -40,7 +41,10 @@ impl Stage for SortTransform {
             .long_description(
                 "Sorts DataFrame rows by one or more columns in ascending or descending order. \
                 Supports multi-column sorting with per-column sort direction. \
-                Can control null value placement (first or last).",
+                Can control null value placement (first or last). \
+                An absent sort column fails the stage unless 'on_missing_column' is 'skip' or \
+                'null'; either way the column is left out of the ordering, since an all-null \
+                key ties every row.",
             )
             .parameter(ConfigParameter::required(
                 "by",
@@ -59,6 +63,9 @@ impl Stage for SortTransform {
                 "false",
                 "Place null values last instead of first",
             ))
+            .parameter(MissingColumnPolicy::parameter(
+                "What to do with absent sort columns: 'error', 'skip' them, or sort them as 'null'",
+            ))
             .example(crate::core::metadata::ConfigExample::new(
                 "Simple sort",
                 example1,
@@ -124,14 +131,31 @@ impl Stage for SortTransform {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let policy = MissingColumnPolicy::from_config(config)?;
         let df = data.as_dataframe()?;
+        let missing: Vec<String> = policy
+            .missing("Sort", &df, sort_columns.iter().map(String::as_str))?
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        // Absent keys are dropped together with their sort direction
+        let (sort_columns, descending): (Vec<String>, Vec<bool>) = sort_columns
+            .into_iter()
+            .zip(descending)
+            .filter(|(column, _)| !missing.contains(column))
+            .unzip();
+        if sort_columns.is_empty() {
+            return Ok(DataFormat::DataFrame(df));
+        }
 
         // Build sort options
         let sort_options = SortMultipleOptions::default()
             .with_order_descending_multi(descending)
             .with_nulls_last(nulls_last);
 
-        let result = df.sort(&sort_columns, sort_options)?;
+        // Lazy so a stage with a memory limit sorts on the streaming engine
+        let result = memory::collect(df.lazy().sort(sort_columns, sort_options))?;
         Ok(DataFormat::DataFrame(result))
     }
 
@@ -140,6 +164,57 @@ impl Stage for SortTransform {
             anyhow::bail!("Sort requires 'by' configuration");
         }
 
+        MissingColumnPolicy::from_config(config)?;
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn sort(by: &[&str], policy: &str) -> Result<Vec<Option<i64>>> {
+        let df = df! { "id" => &[2i64, 3, 1] }.unwrap();
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(df))]);
+        let config = HashMap::from([
+            (
+                "by".to_string(),
+                toml::Value::Array(
+                    by.iter()
+                        .map(|c| toml::Value::String(c.to_string()))
+                        .collect(),
+                ),
+            ),
+            (
+                "descending".to_string(),
+                toml::Value::Array(vec![toml::Value::Boolean(false); by.len()]),
+            ),
+            (
+                "on_missing_column".to_string(),
+                toml::Value::String(policy.to_string()),
+            ),
+        ]);
+        let df = SortTransform
+            .execute(inputs, &config)
+            .await?
+            .as_dataframe()?;
+        Ok(df.column("id")?.i64()?.into_iter().collect())
+    }
+
+    #[tokio::test]
+    async fn test_sort_missing_column_policies() {
+        let err = sort(&["region", "id"], "error").await.unwrap_err();
+        assert_eq!(err.to_string(), "Sort: column 'region' not found");
+
+        let ordered = vec![Some(1), Some(2), Some(3)];
+        assert_eq!(sort(&["region", "id"], "skip").await.unwrap(), ordered);
+        assert_eq!(sort(&["region", "id"], "null").await.unwrap(), ordered);
+
+        // Nothing left to sort by keeps the input order
+        assert_eq!(
+            sort(&["region"], "skip").await.unwrap(),
+            vec![Some(2), Some(3), Some(1)]
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct StratifiedSampleTransform;
+
+/// How many rows each stratum contributes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SampleSize {
+    /// At most this many rows per group; smaller groups are taken whole
+    PerGroup(usize),
+    /// This share of every group, rounded to the nearest row
+    Fraction(f64),
+}
+
+impl SampleSize {
+    fn for_group(self, len: usize) -> usize {
+        match self {
+            SampleSize::PerGroup(n) => n.min(len),
+            SampleSize::Fraction(fraction) => ((len as f64 * fraction).round() as usize).min(len),
+        }
+    }
+}
+
+struct Options {
+    strata: Vec<String>,
+    size: SampleSize,
+    seed: Option<u64>,
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let strata = string_list_option(config, "strata")?
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'strata' configuration"))?;
+    if strata.is_empty() {
+        anyhow::bail!("'strata' must name at least one column");
+    }
+
+    let size = match (config.get("per_group_n"), config.get("fraction")) {
+        (Some(_), Some(_)) => {
+            anyhow::bail!("Set either 'per_group_n' or 'fraction', not both")
+        }
+        (Some(toml::Value::Integer(n)), None) if *n >= 1 => SampleSize::PerGroup(*n as usize),
+        (Some(_), None) => anyhow::bail!("'per_group_n' must be a positive integer"),
+        (None, Some(value)) => {
+            let fraction = match value {
+                toml::Value::Float(f) => *f,
+                toml::Value::Integer(i) => *i as f64,
+                _ => anyhow::bail!("'fraction' must be a number"),
+            };
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                anyhow::bail!("'fraction' must be greater than 0 and at most 1");
+            }
+            SampleSize::Fraction(fraction)
+        }
+        (None, None) => anyhow::bail!("Stratified sample requires 'per_group_n' or 'fraction'"),
+    };
+
+    let seed = match config.get("seed") {
+        Some(toml::Value::Integer(seed)) => Some(*seed as u64),
+        Some(_) => anyhow::bail!("'seed' must be an integer"),
+        None => None,
+    };
+
+    Ok(Options { strata, size, seed })
+}
+
+/// Row indices to keep, in input order
+///
+/// Groups are visited in order of first appearance so a fixed seed draws the
+/// same rows on every run.
+fn sample_indices(df: &DataFrame, options: &Options) -> Result<Vec<IdxSize>> {
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let groups = df.group_by_stable(options.strata.iter().map(String::as_str))?;
+    let mut keep: Vec<IdxSize> = Vec::new();
+    for rows in groups.get_groups().clone().into_idx().all() {
+        let take = options.size.for_group(rows.len());
+        keep.extend(
+            rand::seq::index::sample(&mut rng, rows.len(), take)
+                .into_iter()
+                .map(|i| rows[i]),
+        );
+    }
+    keep.sort_unstable();
+    Ok(keep)
+}
+
+#[async_trait]
+impl Stage for StratifiedSampleTransform {
+    fn name(&self) -> &str {
+        "stratified_sample.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "strata".to_string(),
+            toml::Value::String("label".to_string()),
+        );
+        example1.insert("per_group_n".to_string(), toml::Value::Integer(100));
+        example1.insert("seed".to_string(), toml::Value::Integer(42));
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "strata".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("country".to_string()),
+                toml::Value::String("plan".to_string()),
+            ]),
+        );
+        example2.insert("fraction".to_string(), toml::Value::Float(0.1));
+
+        StageMetadata::builder("stratified_sample.apply", StageCategory::Transform)
+            .description("Sample rows per group, by fixed count or proportionally")
+            .long_description(
+                "Draws a random sample from every group of rows sharing the same 'strata' \
+                values. 'per_group_n' takes the same number of rows from each group, which \
+                balances the classes; groups smaller than that are taken whole. 'fraction' \
+                takes that share of every group (rounded to the nearest row), which keeps \
+                the original proportions. Sampled rows keep their input order, and a fixed \
+                'seed' draws the same rows on every run.",
+            )
+            .parameter(ConfigParameter::required(
+                "strata",
+                ParameterType::Array,
+                "Column(s) defining the groups to sample from",
+            ))
+            .parameter(ConfigParameter::optional(
+                "per_group_n",
+                ParameterType::Integer,
+                "none",
+                "Rows to take from each group (set this or 'fraction')",
+            ))
+            .parameter(ConfigParameter::optional(
+                "fraction",
+                ParameterType::Float,
+                "none",
+                "Share of each group to take, in (0, 1] (set this or 'per_group_n')",
+            ))
+            .parameter(ConfigParameter::optional(
+                "seed",
+                ParameterType::Integer,
+                "none",
+                "Random seed for a reproducible sample",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Balanced test set",
+                example1,
+                Some("Take 100 rows of every label, the same ones on every run"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Proportional sample",
+                example2,
+                Some("Keep 10% of every country and plan combination"),
+            ))
+            .tag("sample")
+            .tag("stratified")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Stratified sample transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        for name in &options.strata {
+            if df.column(name).is_err() {
+                anyhow::bail!("Stratified sample: column '{}' not found", name);
+            }
+        }
+
+        let keep = sample_indices(&df, &options)?;
+        let result = df.take(&IdxCa::from_vec("".into(), keep))?;
+        Ok(DataFormat::DataFrame(result))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labelled() -> DataFrame {
+        let labels: Vec<&str> = (0..30)
+            .map(|i| match i % 6 {
+                0 => "rare",
+                1 | 2 => "common",
+                _ => "frequent",
+            })
+            .collect();
+        df! {
+            "id" => (0..30i64).collect::<Vec<_>>(),
+            "label" => labels,
+        }
+        .unwrap()
+    }
+
+    fn config(pairs: &[(&str, toml::Value)]) -> HashMap<String, toml::Value> {
+        let mut config = HashMap::from([(
+            "strata".to_string(),
+            toml::Value::String("label".to_string()),
+        )]);
+        for (k, v) in pairs {
+            config.insert(k.to_string(), v.clone());
+        }
+        config
+    }
+
+    async fn sample(config: &HashMap<String, toml::Value>) -> DataFrame {
+        let inputs = HashMap::from([("input".to_string(), DataFormat::DataFrame(labelled()))]);
+        StratifiedSampleTransform
+            .execute(inputs, config)
+            .await
+            .unwrap()
+            .as_dataframe()
+            .unwrap()
+    }
+
+    fn counts(df: &DataFrame) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for label in df
+            .column("label")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+        {
+            *counts.entry(label.to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[tokio::test]
+    async fn test_per_group_n_takes_small_groups_whole() {
+        // 5 rare, 10 common, 15 frequent rows
+        let df = sample(&config(&[("per_group_n", toml::Value::Integer(8))])).await;
+        assert_eq!(
+            counts(&df),
+            HashMap::from([
+                ("rare".to_string(), 5),
+                ("common".to_string(), 8),
+                ("frequent".to_string(), 8),
+            ])
+        );
+
+        // Sampled rows keep their input order
+        let ids: Vec<i64> = df
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_fraction_keeps_proportions() {
+        let df = sample(&config(&[("fraction", toml::Value::Float(0.4))])).await;
+        assert_eq!(
+            counts(&df),
+            HashMap::from([
+                ("rare".to_string(), 2),
+                ("common".to_string(), 4),
+                ("frequent".to_string(), 6),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixed_seed_is_reproducible() {
+        let ids = |df: DataFrame| -> Vec<i64> {
+            df.column("id")
+                .unwrap()
+                .i64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect()
+        };
+        let seeded = |seed| {
+            config(&[
+                ("per_group_n", toml::Value::Integer(3)),
+                ("seed", toml::Value::Integer(seed)),
+            ])
+        };
+        let first = ids(sample(&seeded(7)).await);
+        assert_eq!(first, ids(sample(&seeded(7)).await));
+        assert_ne!(first, ids(sample(&seeded(8)).await));
+
+        let both = config(&[
+            ("per_group_n", toml::Value::Integer(3)),
+            ("fraction", toml::Value::Float(0.5)),
+        ]);
+        let err = StratifiedSampleTransform
+            .validate_config(&both)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Set either 'per_group_n' or 'fraction', not both"
+        );
+    }
+}
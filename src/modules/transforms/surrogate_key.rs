@@ -0,0 +1,323 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use super::common::string_list_option;
+use crate::core::metadata::{
+    ConfigParameter, ParameterType, ParameterValidation, StageCategory, StageMetadata,
+};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+use crate::modules::transforms::row_hash::canonical_row;
+
+pub struct SurrogateKeyTransform;
+
+#[derive(Debug, Clone, PartialEq)]
+enum KeyMode {
+    Sequence { start: i64, step: i64 },
+    Hash { columns: Vec<String> },
+    Uuid,
+}
+
+impl KeyMode {
+    fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        let mode = config
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("sequence");
+
+        match mode {
+            "sequence" => {
+                let start = match config.get("start") {
+                    None => 1,
+                    Some(v) => v
+                        .as_integer()
+                        .ok_or_else(|| anyhow::anyhow!("'start' must be an integer"))?,
+                };
+                let step = match config.get("step") {
+                    None => 1,
+                    Some(v) => v
+                        .as_integer()
+                        .ok_or_else(|| anyhow::anyhow!("'step' must be an integer"))?,
+                };
+                if step == 0 {
+                    anyhow::bail!("'step' must not be zero");
+                }
+                Ok(KeyMode::Sequence { start, step })
+            }
+            "hash" => {
+                let columns = string_list_option(config, "columns")?
+                    .ok_or_else(|| anyhow::anyhow!("Hash mode requires 'columns' configuration"))?;
+                if columns.is_empty() {
+                    anyhow::bail!("Hash mode requires at least one column");
+                }
+                Ok(KeyMode::Hash { columns })
+            }
+            "uuid" => Ok(KeyMode::Uuid),
+            _ => anyhow::bail!(
+                "Invalid mode: '{}'. Must be one of: sequence, hash, uuid",
+                mode
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl Stage for SurrogateKeyTransform {
+    fn name(&self) -> &str {
+        "surrogate_key.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "output_column".to_string(),
+            toml::Value::String("customer_sk".to_string()),
+        );
+        example1.insert("start".to_string(), toml::Value::Integer(1000));
+
+        let mut example2 = HashMap::new();
+        example2.insert("mode".to_string(), toml::Value::String("hash".to_string()));
+        example2.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("source_system".to_string()),
+                toml::Value::String("customer_id".to_string()),
+            ]),
+        );
+
+        let mut example3 = HashMap::new();
+        example3.insert("mode".to_string(), toml::Value::String("uuid".to_string()));
+        example3.insert(
+            "output_column".to_string(),
+            toml::Value::String("row_id".to_string()),
+        );
+
+        StageMetadata::builder("surrogate_key.apply", StageCategory::Transform)
+            .description("Add a surrogate key column to each row")
+            .long_description(
+                "Adds a generated key column. 'sequence' assigns integers from 'start' in \
+                increments of 'step'; 'hash' derives a deterministic positive 63-bit integer from \
+                the SHA-256 of the business key columns, so the same business key always gets the \
+                same surrogate key; 'uuid' assigns a random UUID v4 per row. \
+                Sequences restart for every batch the stage executes on: under parallel or \
+                streaming execution each batch is numbered independently, so keys are only unique \
+                per batch unless a shared counter (or 'hash' mode) is used.",
+            )
+            .parameter(
+                ConfigParameter::optional(
+                    "mode",
+                    ParameterType::String,
+                    "sequence",
+                    "How keys are generated",
+                )
+                .with_validation(ParameterValidation::allowed_values([
+                    "sequence", "hash", "uuid",
+                ])),
+            )
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "surrogate_key",
+                "Name of the key column",
+            ))
+            .parameter(ConfigParameter::optional(
+                "start",
+                ParameterType::Integer,
+                "1",
+                "First value of the sequence (sequence mode)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "step",
+                ParameterType::Integer,
+                "1",
+                "Increment between sequence values (sequence mode)",
+            ))
+            .parameter(ConfigParameter::optional(
+                "columns",
+                ParameterType::Array,
+                "none",
+                "Business key column(s) to hash (required in hash mode)",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Sequential keys",
+                example1,
+                Some("Number rows 1000, 1001, 1002, ... into customer_sk"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Hash-based keys",
+                example2,
+                Some("Derive a stable key from source_system and customer_id"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "UUID per row",
+                example3,
+                Some("Assign a random UUID to each row"),
+            ))
+            .tag("key")
+            .tag("surrogate")
+            .tag("sequence")
+            .tag("warehouse")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Surrogate key transform requires input data"))?;
+
+        let mode = KeyMode::from_config(config)?;
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or("surrogate_key");
+
+        let records = data.as_record_batch()?;
+
+        let keyed = records
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut record)| {
+                let key = match &mode {
+                    KeyMode::Sequence { start, step } => {
+                        let offset = (i as i64).checked_mul(*step);
+                        let value = offset.and_then(|o| start.checked_add(o)).ok_or_else(|| {
+                            anyhow::anyhow!("Sequence overflowed i64 at row {}", i)
+                        })?;
+                        JsonValue::from(value)
+                    }
+                    KeyMode::Hash { columns } => {
+                        let digest =
+                            Sha256::digest(canonical_row(&record, &Some(columns.clone()), "")?);
+                        let mut bytes = [0u8; 8];
+                        bytes.copy_from_slice(&digest[..8]);
+                        JsonValue::from((u64::from_be_bytes(bytes) >> 1) as i64)
+                    }
+                    KeyMode::Uuid => JsonValue::String(uuid::Uuid::new_v4().to_string()),
+                };
+                record.insert(output_column.to_string(), key);
+                Ok(record)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DataFormat::RecordBatch(keyed))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        KeyMode::from_config(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn generate(
+        records: Vec<HashMap<String, JsonValue>>,
+        config: HashMap<String, toml::Value>,
+    ) -> Vec<HashMap<String, JsonValue>> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(records));
+
+        SurrogateKeyTransform
+            .execute(inputs, &config)
+            .await
+            .unwrap()
+            .as_record_batch()
+            .unwrap()
+    }
+
+    fn customers() -> Vec<HashMap<String, JsonValue>> {
+        vec![
+            HashMap::from([
+                ("source".to_string(), json!("crm")),
+                ("id".to_string(), json!(7)),
+            ]),
+            HashMap::from([
+                ("source".to_string(), json!("erp")),
+                ("id".to_string(), json!(7)),
+            ]),
+            HashMap::from([
+                ("source".to_string(), json!("crm")),
+                ("id".to_string(), json!(7)),
+            ]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_sequence_keys() {
+        let output = generate(customers(), HashMap::new()).await;
+        let keys: Vec<_> = output.iter().map(|r| r["surrogate_key"].clone()).collect();
+        assert_eq!(keys, vec![json!(1), json!(2), json!(3)]);
+
+        let mut config = HashMap::new();
+        config.insert("start".to_string(), toml::Value::Integer(100));
+        config.insert("step".to_string(), toml::Value::Integer(-10));
+        config.insert(
+            "output_column".to_string(),
+            toml::Value::String("sk".to_string()),
+        );
+        let output = generate(customers(), config).await;
+        let keys: Vec<_> = output.iter().map(|r| r["sk"].clone()).collect();
+        assert_eq!(keys, vec![json!(100), json!(90), json!(80)]);
+    }
+
+    #[tokio::test]
+    async fn test_hash_keys_are_deterministic() {
+        let mut config = HashMap::new();
+        config.insert("mode".to_string(), toml::Value::String("hash".to_string()));
+        config.insert(
+            "columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("source".to_string()),
+                toml::Value::String("id".to_string()),
+            ]),
+        );
+
+        let first = generate(customers(), config.clone()).await;
+        let second = generate(customers(), config).await;
+
+        let key = |r: &HashMap<String, JsonValue>| r["surrogate_key"].as_i64().unwrap();
+        // Same business key, same surrogate key - within and across runs
+        assert_eq!(key(&first[0]), key(&first[2]));
+        assert_eq!(key(&first[0]), key(&second[0]));
+        assert_ne!(key(&first[0]), key(&first[1]));
+        assert!(key(&first[1]) >= 0);
+    }
+
+    #[tokio::test]
+    async fn test_uuid_keys() {
+        let mut config = HashMap::new();
+        config.insert("mode".to_string(), toml::Value::String("uuid".to_string()));
+
+        let output = generate(customers(), config).await;
+        let first = output[0]["surrogate_key"].as_str().unwrap();
+        assert_eq!(first.len(), 36);
+        assert_ne!(output[0]["surrogate_key"], output[1]["surrogate_key"]);
+    }
+
+    #[tokio::test]
+    async fn test_surrogate_key_validation() {
+        let transform = SurrogateKeyTransform;
+
+        assert!(transform.validate_config(&HashMap::new()).await.is_ok());
+
+        let mut config = HashMap::new();
+        config.insert("mode".to_string(), toml::Value::String("hash".to_string()));
+        assert!(transform.validate_config(&config).await.is_err());
+
+        let mut config = HashMap::new();
+        config.insert("step".to_string(), toml::Value::Integer(0));
+        assert!(transform.validate_config(&config).await.is_err());
+    }
+}
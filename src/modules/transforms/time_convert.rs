@@ -0,0 +1,449 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+pub struct TimeConvertTransform;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TimeFormat {
+    Iso8601,
+    EpochSeconds,
+    EpochMillis,
+    EpochMicros,
+    Pattern(String),
+}
+
+impl TimeFormat {
+    fn from_config(config: &HashMap<String, toml::Value>, key: &str) -> Result<Self> {
+        let value = config
+            .get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required '{}' configuration", key))?;
+
+        match value {
+            "iso8601" => Ok(TimeFormat::Iso8601),
+            "epoch_s" => Ok(TimeFormat::EpochSeconds),
+            "epoch_ms" => Ok(TimeFormat::EpochMillis),
+            "epoch_us" => Ok(TimeFormat::EpochMicros),
+            pattern if pattern.contains('%') => Ok(TimeFormat::Pattern(pattern.to_string())),
+            _ => anyhow::bail!(
+                "Invalid '{}' format: '{}'. Must be iso8601, epoch_s, epoch_ms, epoch_us or a strftime pattern",
+                key,
+                value
+            ),
+        }
+    }
+
+    /// Microseconds per unit for the epoch formats
+    fn epoch_scale(&self) -> Option<i64> {
+        match self {
+            TimeFormat::EpochSeconds => Some(1_000_000),
+            TimeFormat::EpochMillis => Some(1_000),
+            TimeFormat::EpochMicros => Some(1),
+            _ => None,
+        }
+    }
+
+    fn parse(&self, value: &JsonValue, tz: &Tz) -> Option<DateTime<Utc>> {
+        if let Some(scale) = self.epoch_scale() {
+            let micros = match value {
+                JsonValue::Number(n) => match n.as_i64() {
+                    Some(i) => i.checked_mul(scale)?,
+                    None => (n.as_f64()? * scale as f64).round() as i64,
+                },
+                JsonValue::String(s) => match s.trim().parse::<i64>() {
+                    Ok(i) => i.checked_mul(scale)?,
+                    Err(_) => (s.trim().parse::<f64>().ok()? * scale as f64).round() as i64,
+                },
+                _ => return None,
+            };
+            return DateTime::from_timestamp_micros(micros);
+        }
+
+        let s = value.as_str()?.trim();
+        match self {
+            TimeFormat::Iso8601 => {
+                if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+                    return Some(dt.with_timezone(&Utc));
+                }
+                // Values without an offset are wall-clock times in `tz`
+                [
+                    "%Y-%m-%dT%H:%M:%S%.f",
+                    "%Y-%m-%d %H:%M:%S%.f",
+                    "%Y-%m-%dT%H:%M",
+                ]
+                .iter()
+                .find_map(|p| NaiveDateTime::parse_from_str(s, p).ok())
+                .or_else(|| {
+                    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                })
+                .and_then(|naive| localize(naive, tz))
+            }
+            TimeFormat::Pattern(pattern) => {
+                if let Ok(dt) = DateTime::parse_from_str(s, pattern) {
+                    return Some(dt.with_timezone(&Utc));
+                }
+                NaiveDateTime::parse_from_str(s, pattern)
+                    .ok()
+                    .or_else(|| {
+                        NaiveDate::parse_from_str(s, pattern)
+                            .ok()
+                            .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    })
+                    .and_then(|naive| localize(naive, tz))
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&self, dt: &DateTime<Utc>, tz: &Tz) -> JsonValue {
+        match self {
+            TimeFormat::Iso8601 => JsonValue::String(
+                dt.with_timezone(tz)
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            ),
+            TimeFormat::EpochSeconds => JsonValue::from(dt.timestamp()),
+            TimeFormat::EpochMillis => JsonValue::from(dt.timestamp_millis()),
+            TimeFormat::EpochMicros => JsonValue::from(dt.timestamp_micros()),
+            TimeFormat::Pattern(pattern) => {
+                JsonValue::String(dt.with_timezone(tz).format(pattern).to_string())
+            }
+        }
+    }
+}
+
+/// Interpret a wall-clock time in `tz`, taking the earlier instant when a DST
+/// change makes it ambiguous
+fn localize(naive: NaiveDateTime, tz: &Tz) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn parse_tz(config: &HashMap<String, toml::Value>) -> Result<Tz> {
+    let name = config.get("tz").and_then(|v| v.as_str()).unwrap_or("UTC");
+    name.parse::<Tz>().map_err(|_| {
+        anyhow::anyhow!(
+            "Unknown timezone '{}'. Use an IANA name such as 'UTC' or 'Asia/Seoul'",
+            name
+        )
+    })
+}
+
+#[async_trait]
+impl Stage for TimeConvertTransform {
+    fn name(&self) -> &str {
+        "time_convert.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example1 = HashMap::new();
+        example1.insert(
+            "column".to_string(),
+            toml::Value::String("created_ms".to_string()),
+        );
+        example1.insert(
+            "from".to_string(),
+            toml::Value::String("epoch_ms".to_string()),
+        );
+        example1.insert("to".to_string(), toml::Value::String("iso8601".to_string()));
+        example1.insert(
+            "output_column".to_string(),
+            toml::Value::String("created_at".to_string()),
+        );
+
+        let mut example2 = HashMap::new();
+        example2.insert(
+            "column".to_string(),
+            toml::Value::String("order_date".to_string()),
+        );
+        example2.insert(
+            "from".to_string(),
+            toml::Value::String("%d/%m/%Y %H:%M".to_string()),
+        );
+        example2.insert("to".to_string(), toml::Value::String("epoch_s".to_string()));
+        example2.insert(
+            "tz".to_string(),
+            toml::Value::String("Europe/London".to_string()),
+        );
+
+        StageMetadata::builder("time_convert.apply", StageCategory::Transform)
+            .description("Convert a timestamp column between ISO 8601, epoch and custom formats")
+            .long_description(
+                "Parses each value of 'column' using the 'from' format and writes it in the 'to' \
+                format. Formats are iso8601, epoch_s, epoch_ms, epoch_us, or a strftime pattern such \
+                as '%Y-%m-%d %H:%M:%S'. Input without an offset (naive ISO strings, patterns without %z) \
+                is read as wall-clock time in 'tz', and ISO and pattern output is rendered in 'tz'; \
+                epoch values are always UTC-based. Unparseable values become null unless \
+                'fail_on_error' is set.",
+            )
+            .parameter(ConfigParameter::required(
+                "column",
+                ParameterType::String,
+                "Column containing the timestamps",
+            ))
+            .parameter(ConfigParameter::required(
+                "from",
+                ParameterType::String,
+                "Input format: iso8601, epoch_s, epoch_ms, epoch_us or a strftime pattern",
+            ))
+            .parameter(ConfigParameter::required(
+                "to",
+                ParameterType::String,
+                "Output format: iso8601, epoch_s, epoch_ms, epoch_us or a strftime pattern",
+            ))
+            .parameter(ConfigParameter::optional(
+                "output_column",
+                ParameterType::String,
+                "same as column",
+                "Column receiving the converted value",
+            ))
+            .parameter(ConfigParameter::optional(
+                "tz",
+                ParameterType::String,
+                "UTC",
+                "IANA timezone for offset-less input and for ISO/pattern output",
+            ))
+            .parameter(ConfigParameter::optional(
+                "fail_on_error",
+                ParameterType::Boolean,
+                "false",
+                "Fail on unparseable values instead of writing null",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Epoch millis to ISO",
+                example1,
+                Some("Turn created_ms into an ISO 8601 created_at column"),
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Local date strings to epoch seconds",
+                example2,
+                Some("Parse UK-formatted local times and store them as epoch seconds"),
+            ))
+            .tag("time")
+            .tag("timestamp")
+            .tag("epoch")
+            .tag("convert")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Time convert transform requires input data"))?;
+
+        let column = config
+            .get("column")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required 'column' configuration"))?;
+        let output_column = config
+            .get("output_column")
+            .and_then(|v| v.as_str())
+            .unwrap_or(column);
+        let from = TimeFormat::from_config(config, "from")?;
+        let to = TimeFormat::from_config(config, "to")?;
+        let tz = parse_tz(config)?;
+        let fail_on_error = config
+            .get("fail_on_error")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let records = data.as_record_batch()?;
+
+        let converted = records
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut record)| {
+                let value = record.get(column).cloned().unwrap_or(JsonValue::Null);
+                let output = if value.is_null() {
+                    JsonValue::Null
+                } else {
+                    match from.parse(&value, &tz) {
+                        Some(dt) => to.render(&dt, &tz),
+                        None if fail_on_error => anyhow::bail!(
+                            "Cannot parse {} in column '{}' at row {} using format {:?}",
+                            value,
+                            column,
+                            i,
+                            from
+                        ),
+                        None => JsonValue::Null,
+                    }
+                };
+                record.insert(output_column.to_string(), output);
+                Ok(record)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DataFormat::RecordBatch(converted))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        if config.get("column").and_then(|v| v.as_str()).is_none() {
+            anyhow::bail!("Missing required 'column' configuration");
+        }
+        TimeFormat::from_config(config, "from")?;
+        TimeFormat::from_config(config, "to")?;
+        parse_tz(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(pairs: &[(&str, &str)]) -> HashMap<String, toml::Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), toml::Value::String(v.to_string())))
+            .collect()
+    }
+
+    async fn convert(
+        values: Vec<JsonValue>,
+        config: HashMap<String, toml::Value>,
+    ) -> Result<Vec<JsonValue>> {
+        let records = values
+            .into_iter()
+            .map(|v| HashMap::from([("ts".to_string(), v)]))
+            .collect();
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::RecordBatch(records));
+
+        let output = TimeConvertTransform.execute(inputs, &config).await?;
+        Ok(output
+            .as_record_batch()?
+            .into_iter()
+            .map(|mut r| r.remove("out").unwrap_or(JsonValue::Null))
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_epoch_ms_to_iso_and_back() {
+        let iso = convert(
+            vec![json!(1705314600123i64), json!(0)],
+            config(&[
+                ("column", "ts"),
+                ("from", "epoch_ms"),
+                ("to", "iso8601"),
+                ("output_column", "out"),
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            iso,
+            vec![
+                json!("2024-01-15T10:30:00.123Z"),
+                json!("1970-01-01T00:00:00Z")
+            ]
+        );
+
+        let millis = convert(
+            iso,
+            config(&[
+                ("column", "ts"),
+                ("from", "iso8601"),
+                ("to", "epoch_ms"),
+                ("output_column", "out"),
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(millis, vec![json!(1705314600123i64), json!(0)]);
+    }
+
+    #[tokio::test]
+    async fn test_timezone_and_patterns() {
+        // Naive local time in Seoul (UTC+9) to epoch seconds
+        let seconds = convert(
+            vec![json!("15/01/2024 19:30")],
+            config(&[
+                ("column", "ts"),
+                ("from", "%d/%m/%Y %H:%M"),
+                ("to", "epoch_s"),
+                ("output_column", "out"),
+                ("tz", "Asia/Seoul"),
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(seconds, vec![json!(1705314600)]);
+
+        // Epoch seconds rendered as ISO in the configured timezone
+        let iso = convert(
+            seconds,
+            config(&[
+                ("column", "ts"),
+                ("from", "epoch_s"),
+                ("to", "iso8601"),
+                ("output_column", "out"),
+                ("tz", "Asia/Seoul"),
+            ]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(iso, vec![json!("2024-01-15T19:30:00+09:00")]);
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_values() {
+        let mut cfg = config(&[
+            ("column", "ts"),
+            ("from", "iso8601"),
+            ("to", "epoch_us"),
+            ("output_column", "out"),
+        ]);
+
+        let output = convert(
+            vec![json!("not a date"), JsonValue::Null, json!("2024-01-15")],
+            cfg.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            output,
+            vec![JsonValue::Null, JsonValue::Null, json!(1705276800000000i64)]
+        );
+
+        cfg.insert("fail_on_error".to_string(), toml::Value::Boolean(true));
+        let err = convert(vec![json!("not a date")], cfg).await.unwrap_err();
+        assert!(err.to_string().contains("column 'ts' at row 0"));
+    }
+
+    #[tokio::test]
+    async fn test_time_convert_validation() {
+        let transform = TimeConvertTransform;
+
+        let valid = config(&[("column", "ts"), ("from", "epoch_s"), ("to", "%Y-%m-%d")]);
+        assert!(transform.validate_config(&valid).await.is_ok());
+
+        let bad_format = config(&[("column", "ts"), ("from", "epoch_ns"), ("to", "iso8601")]);
+        assert!(transform.validate_config(&bad_format).await.is_err());
+
+        let bad_tz = config(&[
+            ("column", "ts"),
+            ("from", "epoch_s"),
+            ("to", "iso8601"),
+            ("tz", "Mars/Olympus"),
+        ]);
+        assert!(transform.validate_config(&bad_tz).await.is_err());
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::common::string_list;
+use crate::core::metadata::{ConfigParameter, ParameterType, StageCategory, StageMetadata};
+use crate::core::stage::Stage;
+use crate::core::traits::DataFormat;
+
+/// Reshapes wide frames into long `variable`/`value` pairs (melt)
+pub struct UnpivotTransform;
+
+struct Options {
+    id_columns: Vec<String>,
+    value_columns: Vec<String>,
+    variable_name: String,
+    value_name: String,
+}
+
+fn name_option(config: &HashMap<String, toml::Value>, name: &str, default: &str) -> Result<String> {
+    match config.get(name) {
+        Some(toml::Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+        Some(_) => anyhow::bail!("'{}' must be a non-empty string", name),
+        None => Ok(default.to_string()),
+    }
+}
+
+fn parse_options(config: &HashMap<String, toml::Value>) -> Result<Options> {
+    let id_columns = string_list(config, "id_columns")?;
+    let value_columns = string_list(config, "value_columns")?;
+    if id_columns.is_empty() && value_columns.is_empty() {
+        anyhow::bail!("Unpivot requires 'id_columns' or 'value_columns' configuration");
+    }
+    if let Some(both) = value_columns.iter().find(|c| id_columns.contains(c)) {
+        anyhow::bail!(
+            "Unpivot: column '{}' is in both 'id_columns' and 'value_columns'",
+            both
+        );
+    }
+
+    let variable_name = name_option(config, "variable_name", "variable")?;
+    let value_name = name_option(config, "value_name", "value")?;
+    if variable_name == value_name {
+        anyhow::bail!("Unpivot: 'variable_name' and 'value_name' must differ");
+    }
+    for name in [&variable_name, &value_name] {
+        if id_columns.contains(name) {
+            anyhow::bail!("Unpivot: output column '{}' is also an id column", name);
+        }
+    }
+
+    Ok(Options {
+        id_columns,
+        value_columns,
+        variable_name,
+        value_name,
+    })
+}
+
+impl Options {
+    fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        for name in self.id_columns.iter().chain(&self.value_columns) {
+            if df.column(name).is_err() {
+                anyhow::bail!("Unpivot: column '{}' not found", name);
+            }
+        }
+
+        let args = UnpivotArgsIR {
+            on: self
+                .value_columns
+                .iter()
+                .map(|c| c.as_str().into())
+                .collect(),
+            index: self.id_columns.iter().map(|c| c.as_str().into()).collect(),
+            variable_name: Some(self.variable_name.as_str().into()),
+            value_name: Some(self.value_name.as_str().into()),
+        };
+        df.unpivot2(args)
+            .map_err(|e| anyhow::anyhow!("Unpivot failed: {}", e))
+    }
+}
+
+#[async_trait]
+impl Stage for UnpivotTransform {
+    fn name(&self) -> &str {
+        "unpivot.apply"
+    }
+
+    fn metadata(&self) -> StageMetadata {
+        let mut example = HashMap::new();
+        example.insert(
+            "id_columns".to_string(),
+            toml::Value::Array(vec![toml::Value::String("date".to_string())]),
+        );
+        example.insert(
+            "value_columns".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("cpu".to_string()),
+                toml::Value::String("memory".to_string()),
+                toml::Value::String("disk".to_string()),
+            ]),
+        );
+        example.insert(
+            "variable_name".to_string(),
+            toml::Value::String("metric".to_string()),
+        );
+        example.insert(
+            "value_name".to_string(),
+            toml::Value::String("usage".to_string()),
+        );
+
+        StageMetadata::builder("unpivot.apply", StageCategory::Transform)
+            .description("Reshape wide data into long variable/value rows (melt)")
+            .long_description(
+                "The inverse of a pivot: each row becomes one row per value column, holding \
+                the id columns, the name of the value column in 'variable_name' and its value \
+                in 'value_name'. Rows are ordered by value column, then by input row. Without \
+                'value_columns' every column that is not an id column is unpivoted. Value \
+                columns of different types are cast to a common type, such as integers and \
+                floats to floats. Useful for charting tools that expect long-form data.",
+            )
+            .parameter(ConfigParameter::optional(
+                "id_columns",
+                ParameterType::Array,
+                "[]",
+                "Columns kept on every output row to identify it",
+            ))
+            .parameter(ConfigParameter::optional(
+                "value_columns",
+                ParameterType::Array,
+                "all other columns",
+                "Columns turned into variable/value pairs",
+            ))
+            .parameter(ConfigParameter::optional(
+                "variable_name",
+                ParameterType::String,
+                "variable",
+                "Output column holding the name of the unpivoted column",
+            ))
+            .parameter(ConfigParameter::optional(
+                "value_name",
+                ParameterType::String,
+                "value",
+                "Output column holding the unpivoted value",
+            ))
+            .example(crate::core::metadata::ConfigExample::new(
+                "Metrics per day to long form",
+                example,
+                Some("date, cpu, memory, disk becomes date, metric, usage with three rows per day"),
+            ))
+            .tag("unpivot")
+            .tag("melt")
+            .tag("reshape")
+            .tag("transform")
+            .build()
+    }
+
+    async fn execute(
+        &self,
+        inputs: HashMap<String, DataFormat>,
+        config: &HashMap<String, toml::Value>,
+    ) -> Result<DataFormat> {
+        let data = inputs
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Unpivot transform requires input data"))?;
+
+        let options = parse_options(config)?;
+        let df = data.as_dataframe()?;
+        Ok(DataFormat::DataFrame(options.apply(&df)?))
+    }
+
+    async fn validate_config(&self, config: &HashMap<String, toml::Value>) -> Result<()> {
+        parse_options(config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide_frame() -> DataFrame {
+        df![
+            "date" => ["2024-01-01", "2024-01-02"],
+            "cpu" => [0.5, 0.7],
+            "memory" => [0.25, 0.5],
+            "disk" => [0.1, 0.2],
+        ]
+        .unwrap()
+    }
+
+    fn strings(values: &[&str]) -> toml::Value {
+        toml::Value::Array(
+            values
+                .iter()
+                .map(|v| toml::Value::String(v.to_string()))
+                .collect(),
+        )
+    }
+
+    async fn unpivot(config: HashMap<String, toml::Value>) -> Result<DataFrame> {
+        let mut inputs = HashMap::new();
+        inputs.insert("input".to_string(), DataFormat::DataFrame(wide_frame()));
+        UnpivotTransform
+            .execute(inputs, &config)
+            .await?
+            .as_dataframe()
+    }
+
+    #[tokio::test]
+    async fn test_unpivot_three_value_columns() {
+        let mut config = HashMap::new();
+        config.insert("id_columns".to_string(), strings(&["date"]));
+        config.insert(
+            "value_columns".to_string(),
+            strings(&["cpu", "memory", "disk"]),
+        );
+        config.insert(
+            "variable_name".to_string(),
+            toml::Value::String("metric".to_string()),
+        );
+        config.insert(
+            "value_name".to_string(),
+            toml::Value::String("usage".to_string()),
+        );
+
+        let result = unpivot(config).await.unwrap();
+        assert_eq!(result.height(), 6);
+        assert_eq!(
+            result.get_column_names_str(),
+            vec!["date", "metric", "usage"]
+        );
+
+        let metrics: Vec<&str> = result
+            .column("metric")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(
+            metrics,
+            vec!["cpu", "cpu", "memory", "memory", "disk", "disk"]
+        );
+        let usage: Vec<f64> = result
+            .column("usage")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(usage, vec![0.5, 0.7, 0.25, 0.5, 0.1, 0.2]);
+    }
+
+    #[tokio::test]
+    async fn test_unpivot_defaults_to_remaining_columns() {
+        let mut config = HashMap::new();
+        config.insert("id_columns".to_string(), strings(&["date"]));
+
+        let result = unpivot(config).await.unwrap();
+        assert_eq!(result.height(), 6);
+        assert_eq!(
+            result.get_column_names_str(),
+            vec!["date", "variable", "value"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unpivot_rejects_bad_columns() {
+        let mut config = HashMap::new();
+        config.insert("id_columns".to_string(), strings(&["date"]));
+        config.insert("value_columns".to_string(), strings(&["cpu", "gpu"]));
+        let err = unpivot(config).await.unwrap_err().to_string();
+        assert_eq!(err, "Unpivot: column 'gpu' not found");
+
+        let mut config = HashMap::new();
+        config.insert("id_columns".to_string(), strings(&["date"]));
+        config.insert("value_columns".to_string(), strings(&["date"]));
+        assert!(UnpivotTransform.validate_config(&config).await.is_err());
+        assert!(UnpivotTransform
+            .validate_config(&HashMap::new())
+            .await
+            .is_err());
+    }
+}
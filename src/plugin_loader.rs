@@ -6,11 +6,17 @@
 
 use anyhow::{anyhow, Context, Result};
 use conveyor_plugin_api::traits::{FfiStage_TO, StageFactory};
-use conveyor_plugin_api::{PluginCapability, PluginDeclaration, RBox, PLUGIN_API_VERSION};
+use conveyor_plugin_api::{
+    features, FfiConfigParameter, FfiParameterType, PluginCapability, PluginDeclaration, RBox,
+    PLUGIN_API_VERSION,
+};
 use libloading::{Library, Symbol};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+/// Features this host provides to plugin capabilities
+pub const SUPPORTED_FEATURES: &[&str] = &[features::STREAMING, features::INPUT_AWARE];
+
 /// Loaded plugin information
 pub struct LoadedPlugin {
     name: String,
@@ -173,6 +179,23 @@ impl PluginLoader {
             return Err(anyhow!("Plugin '{}' provides no stages", name));
         }
 
+        let capabilities = well_formed_capabilities(name, capabilities);
+        if capabilities.is_empty() {
+            return Err(anyhow!(
+                "Plugin '{}' provides no stages with valid metadata",
+                name
+            ));
+        }
+
+        let capabilities = supported_capabilities(name, capabilities, SUPPORTED_FEATURES);
+        if capabilities.is_empty() {
+            return Err(anyhow!(
+                "Plugin '{}' provides no stages this host supports (host features: {})",
+                name,
+                SUPPORTED_FEATURES.join(", ")
+            ));
+        }
+
         tracing::info!(
             "Plugin '{}' provides {} stage(s): {}",
             name,
@@ -331,6 +354,137 @@ impl Default for PluginLoader {
     }
 }
 
+/// Features listed in `capability.required_features` that `supported` lacks
+fn missing_features(capability: &PluginCapability, supported: &[&str]) -> Vec<String> {
+    capability
+        .required_features
+        .iter()
+        .filter(|feature| !supported.contains(&feature.as_str()))
+        .map(|feature| feature.to_string())
+        .collect()
+}
+
+/// Drop capabilities whose required features the host does not provide, with a warning
+fn supported_capabilities(
+    plugin_name: &str,
+    capabilities: Vec<PluginCapability>,
+    supported: &[&str],
+) -> Vec<PluginCapability> {
+    capabilities
+        .into_iter()
+        .filter(|capability| {
+            let missing = missing_features(capability, supported);
+            if missing.is_empty() {
+                return true;
+            }
+            tracing::warn!(
+                "Skipping stage '{}' from plugin '{}': it requires unsupported feature(s) {} \
+                 (host supports: {}). Upgrade conveyor or use a plugin build for this version.",
+                capability.name,
+                plugin_name,
+                missing.join(", "),
+                supported.join(", ")
+            );
+            false
+        })
+        .collect()
+}
+
+/// Why a parameter's non-empty default does not fit its declaration, if it doesn't
+fn default_problem(parameter: &FfiConfigParameter, param_type: FfiParameterType) -> Option<String> {
+    let default = parameter.default_value.as_str();
+    if default.is_empty() {
+        // An empty default means "unset"
+        return None;
+    }
+    let parses = match param_type {
+        FfiParameterType::Integer => default.parse::<i64>().is_ok(),
+        FfiParameterType::Float => default.parse::<f64>().is_ok(),
+        FfiParameterType::Boolean => default.parse::<bool>().is_ok(),
+        _ => true,
+    };
+    if !parses {
+        return Some(format!(
+            "default '{}' of parameter '{}' is not a valid {}",
+            default,
+            parameter.name,
+            param_type.as_str()
+        ));
+    }
+    if !parameter.allowed_values.is_empty()
+        && !parameter
+            .allowed_values
+            .iter()
+            .any(|v| v.as_str() == default)
+    {
+        return Some(format!(
+            "default '{}' of parameter '{}' is not one of its allowed values",
+            default, parameter.name
+        ));
+    }
+    None
+}
+
+/// Everything wrong with the metadata of `capability`
+fn metadata_problems(capability: &PluginCapability) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (index, parameter) in capability.metadata.parameters.iter().enumerate() {
+        let param_type = match parameter.parameter_type() {
+            Ok(param_type) => param_type,
+            Err(raw_type) => {
+                problems.push(format!(
+                    "parameter #{} ('{}') has unknown type {}",
+                    index + 1,
+                    parameter.name,
+                    raw_type
+                ));
+                // The type can't be inspected any further
+                continue;
+            }
+        };
+
+        let name = parameter.name.as_str();
+        if name.trim().is_empty() {
+            problems.push(format!("parameter #{} has an empty name", index + 1));
+        } else if !seen.insert(name) {
+            problems.push(format!("parameter '{}' is declared more than once", name));
+        }
+        if let Some(problem) = default_problem(parameter, param_type) {
+            problems.push(problem);
+        }
+    }
+
+    problems
+}
+
+/// Drop capabilities whose metadata is malformed, with a warning
+///
+/// CLI commands such as `info` and `list` read the metadata of every loaded
+/// stage, so one bad entry is skipped here rather than breaking them.
+fn well_formed_capabilities(
+    plugin_name: &str,
+    capabilities: Vec<PluginCapability>,
+) -> Vec<PluginCapability> {
+    capabilities
+        .into_iter()
+        .filter(|capability| {
+            let problems = metadata_problems(capability);
+            if problems.is_empty() {
+                return true;
+            }
+            tracing::warn!(
+                "Skipping stage '{}' from plugin '{}': invalid metadata: {}",
+                capability.name,
+                plugin_name,
+                problems.join("; ")
+            );
+            false
+        })
+        .collect()
+}
+
 /// Get platform-specific library name (macOS only)
 fn get_library_name(plugin_name: &str) -> String {
     format!("libconveyor_plugin_{}.dylib", plugin_name)
@@ -379,6 +533,103 @@ mod tests {
         assert!(!loader.is_loaded("nonexistent"));
     }
 
+    #[test]
+    fn test_capability_with_unsupported_feature_is_skipped() {
+        use conveyor_plugin_api::StageType;
+
+        let capabilities = vec![
+            PluginCapability::simple(
+                "plain",
+                StageType::Source,
+                "No requirements",
+                "create_plain",
+            ),
+            PluginCapability::simple("streamed", StageType::Source, "Streams", "create_streamed")
+                .with_required_features([features::STREAMING]),
+            PluginCapability::simple("future", StageType::Sink, "Needs more", "create_future")
+                .with_required_features([features::STREAMING, "arrow_ipc"]),
+        ];
+
+        assert_eq!(
+            missing_features(&capabilities[2], SUPPORTED_FEATURES),
+            vec!["arrow_ipc".to_string()]
+        );
+
+        let kept = supported_capabilities("test", capabilities, SUPPORTED_FEATURES);
+        let names: Vec<&str> = kept.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["plain", "streamed"]);
+    }
+
+    #[test]
+    fn test_capability_with_malformed_metadata_is_skipped() {
+        use conveyor_plugin_api::{FfiStageMetadata, StageType};
+
+        let capability = |name: &str, parameters: Vec<FfiConfigParameter>| {
+            PluginCapability::new(
+                name,
+                StageType::Source,
+                "Test stage",
+                format!("create_{}", name),
+                FfiStageMetadata::new(name, "Test stage", "", parameters, Vec::<&str>::new()),
+            )
+        };
+
+        let capabilities = vec![
+            capability(
+                "duplicated",
+                vec![
+                    FfiConfigParameter::required("url", FfiParameterType::String, "Endpoint"),
+                    FfiConfigParameter::optional("url", FfiParameterType::String, "", "Again"),
+                ],
+            ),
+            capability(
+                "good",
+                vec![
+                    FfiConfigParameter::required("url", FfiParameterType::String, "Endpoint"),
+                    FfiConfigParameter::optional("limit", FfiParameterType::Integer, "", "Limit"),
+                    FfiConfigParameter::optional("mode", FfiParameterType::String, "fast", "Mode")
+                        .with_allowed_values(["fast", "safe"]),
+                ],
+            ),
+            capability("unknown_type", {
+                // A type from a newer plugin API arrives as an unknown number
+                let mut parameter =
+                    FfiConfigParameter::required("shape", FfiParameterType::Object, "Shape");
+                parameter.param_type = 99;
+                vec![parameter]
+            }),
+            capability(
+                "bad_default",
+                vec![
+                    FfiConfigParameter::optional("retries", FfiParameterType::Integer, "x", "N"),
+                    FfiConfigParameter::optional("mode", FfiParameterType::String, "slow", "M")
+                        .with_allowed_values(["fast", "safe"]),
+                ],
+            ),
+        ];
+
+        assert_eq!(
+            metadata_problems(&capabilities[0]),
+            vec!["parameter 'url' is declared more than once".to_string()]
+        );
+        assert!(metadata_problems(&capabilities[1]).is_empty());
+        assert_eq!(
+            metadata_problems(&capabilities[2]),
+            vec!["parameter #1 ('shape') has unknown type 99".to_string()]
+        );
+        assert_eq!(
+            metadata_problems(&capabilities[3]),
+            vec![
+                "default 'x' of parameter 'retries' is not a valid integer".to_string(),
+                "default 'slow' of parameter 'mode' is not one of its allowed values".to_string(),
+            ]
+        );
+
+        let kept = well_formed_capabilities("test", capabilities);
+        let names: Vec<&str> = kept.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["good"]);
+    }
+
     #[test]
     fn test_duplicate_loading() {
         let mut loader = PluginLoader::new();
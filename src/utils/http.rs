@@ -0,0 +1,351 @@
+//! HTTP plumbing shared by the stages that call HTTP APIs.
+//!
+//! Every such stage reads the same knobs from its config:
+//!
+//! - `timeout_ms`: per-request timeout (default 30000). Stages with the same
+//!   timeout share one client from [`HttpClientPool`], and with it one pool
+//!   of keep-alive connections.
+//! - `retry_attempts`, `retry_backoff_ms`, `retry_backoff_multiplier`: the
+//!   shared [`RetryPolicy`], applied to connection errors, timeouts, 429 and
+//!   5xx responses.
+//!
+//! URLs are passed through [`redact_url`] before they are logged or put in
+//! an error, so API keys in query strings don't leak.
+
+use anyhow::Result;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::core::metadata::{ConfigParameter, ParameterType};
+pub use crate::utils::retry::RetryPolicy;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Query parameter names that suggest a credential
+const SECRET_NAME_PATTERN: &str = r"(?i)(key|token|secret|password|passwd|signature|session)";
+
+/// Metadata parameters shared by every HTTP-using stage
+pub(crate) fn parameters() -> Vec<ConfigParameter> {
+    vec![
+        ConfigParameter::optional(
+            "timeout_ms",
+            ParameterType::Integer,
+            "30000",
+            "Timeout of each HTTP request in milliseconds",
+        ),
+        ConfigParameter::optional(
+            "retry_attempts",
+            ParameterType::Integer,
+            "1",
+            "Total attempts per request on connection errors, 429 and 5xx responses",
+        ),
+        ConfigParameter::optional(
+            "retry_backoff_ms",
+            ParameterType::Integer,
+            "500",
+            "Delay before the first retry, unless the response sends Retry-After",
+        ),
+        ConfigParameter::optional(
+            "retry_backoff_multiplier",
+            ParameterType::Float,
+            "2.0",
+            "Growth of the retry delay per attempt",
+        ),
+    ]
+}
+
+/// Validate the shared HTTP options of a stage config
+pub(crate) fn validate_config(config: &HashMap<String, toml::Value>) -> Result<()> {
+    timeout(config)?;
+    RetryPolicy::from_config(config)?;
+    Ok(())
+}
+
+fn timeout(config: &HashMap<String, toml::Value>) -> Result<Duration> {
+    match config.get("timeout_ms") {
+        None => Ok(DEFAULT_TIMEOUT),
+        Some(value) => value
+            .as_integer()
+            .filter(|ms| *ms >= 1)
+            .map(|ms| Duration::from_millis(ms as u64))
+            .ok_or_else(|| anyhow::anyhow!("'timeout_ms' must be a positive integer")),
+    }
+}
+
+/// Clients shared between stages, one per request timeout
+///
+/// A `reqwest::Client` owns its connection pool, so handing out the same
+/// client lets stages reuse each other's keep-alive connections.
+#[derive(Default)]
+pub struct HttpClientPool {
+    clients: Mutex<HashMap<Duration, Client>>,
+}
+
+impl HttpClientPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide pool used by the built-in stages
+    pub fn shared() -> &'static HttpClientPool {
+        static POOL: OnceLock<HttpClientPool> = OnceLock::new();
+        POOL.get_or_init(HttpClientPool::new)
+    }
+
+    /// The client for requests with `timeout`, created on first use
+    pub fn client(&self, timeout: Duration) -> Result<Client> {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(&timeout) {
+            return Ok(client.clone());
+        }
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
+        clients.insert(timeout, client.clone());
+        Ok(client)
+    }
+
+    /// The client for a stage config's `timeout_ms`
+    pub fn for_config(&self, config: &HashMap<String, toml::Value>) -> Result<Client> {
+        self.client(timeout(config)?)
+    }
+
+    /// Number of distinct clients created so far
+    pub fn len(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// HTTP requests retry with the shared policy; a `Retry-After` header on a
+/// 429 or 5xx response overrides the backoff.
+impl RetryPolicy {
+    /// Send the request built by `request` until it gets a response that is
+    /// not worth retrying, or the attempts are used up
+    ///
+    /// The last response is returned whatever its status, so callers report
+    /// failed statuses as before. Connection errors are returned with the
+    /// URL redacted.
+    pub async fn send<F>(&self, request: F) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 1;
+        loop {
+            let retry_in = match request().send().await {
+                Ok(response) if attempt < self.max_attempts && is_retryable(response.status()) => {
+                    let delay = retry_after(&response).unwrap_or_else(|| self.delay(attempt));
+                    tracing::warn!(
+                        "HTTP {} from {} on attempt {}/{}. Retrying in {:?}...",
+                        response.status(),
+                        redact_url(response.url().as_str()),
+                        attempt,
+                        self.max_attempts,
+                        delay
+                    );
+                    delay
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if attempt < self.max_attempts && (e.is_connect() || e.is_timeout()) => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!(
+                        "{} on attempt {}/{}. Retrying in {:?}...",
+                        request_error(e),
+                        attempt,
+                        self.max_attempts,
+                        delay
+                    );
+                    delay
+                }
+                Err(e) => return Err(request_error(e)),
+            };
+            tokio::time::sleep(retry_in).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// What a stage needs to make requests: a pooled client and its retry policy
+#[derive(Clone)]
+pub struct HttpContext {
+    pub client: Client,
+    pub retry: RetryPolicy,
+}
+
+impl HttpContext {
+    /// Client from the shared pool and retry policy for a stage config
+    pub fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        Ok(Self {
+            client: HttpClientPool::shared().for_config(config)?,
+            retry: RetryPolicy::from_config(config)?,
+        })
+    }
+
+    /// Send the request `request` builds on the client, retrying per the policy
+    pub async fn send<F>(&self, request: F) -> Result<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        self.retry.send(|| request(&self.client)).await
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Delay requested by a `Retry-After` header, in seconds or as an HTTP date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+/// A request error with credentials removed from its URL
+fn request_error(error: reqwest::Error) -> anyhow::Error {
+    match error.url().map(|url| redact_url(url.as_str())) {
+        Some(url) => anyhow::anyhow!("Request to {} failed: {}", url, error.without_url()),
+        None => anyhow::anyhow!("Request failed: {}", error),
+    }
+}
+
+/// Mask query parameters whose names suggest a credential (e.g. `?api_key=...`)
+pub fn redact_url(url: &str) -> String {
+    let (Ok(mut parsed), Ok(secret_name)) = (
+        reqwest::Url::parse(url),
+        regex::Regex::new(SECRET_NAME_PATTERN),
+    ) else {
+        return url.to_string();
+    };
+    if parsed.query().is_none() {
+        return url.to_string();
+    }
+
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if secret_name.is_match(&name) {
+                crate::core::config::REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve `responses` in order over keep-alive connections, counting the connections
+    async fn serve(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let responses = Arc::new(Mutex::new(responses.into_iter()));
+
+        let counter = Arc::clone(&connections);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let responses = Arc::clone(&responses);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let Some(head) = responses.lock().unwrap().next() else {
+                            break;
+                        };
+                        let response = format!("{}\r\ncontent-length: 2\r\n\r\nok", head);
+                        socket.write_all(response.as_bytes()).await.unwrap();
+                    }
+                });
+            }
+        });
+        (url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_pool_reuses_client_and_connections() {
+        let pool = HttpClientPool::new();
+        let (url, connections) = serve(vec!["HTTP/1.1 200 OK"; 2]).await;
+
+        // Two stages with the same timeout share one client
+        let config = HashMap::from([("timeout_ms".to_string(), toml::Value::Integer(5000))]);
+        for _ in 0..2 {
+            let client = pool.for_config(&config).unwrap();
+            let response = client.get(&url).send().await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "ok");
+        }
+        assert_eq!(pool.len(), 1);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        pool.client(Duration::from_secs(1)).unwrap();
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_honors_retry_after() {
+        let (url, _) = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nretry-after: 1",
+            "HTTP/1.1 200 OK",
+        ])
+        .await;
+        let client = HttpClientPool::new().client(DEFAULT_TIMEOUT).unwrap();
+
+        // The backoff alone would retry immediately
+        let policy = RetryPolicy::from_config(&HashMap::from([
+            ("retry_attempts".to_string(), toml::Value::Integer(2)),
+            ("retry_backoff_ms".to_string(), toml::Value::Integer(0)),
+        ]))
+        .unwrap();
+        let started = std::time::Instant::now();
+        let response = policy.send(|| client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+
+        // Without retries the failed status is handed back to the caller
+        let (url, _) = serve(vec!["HTTP/1.1 503 Service Unavailable"]).await;
+        let response = RetryPolicy::default()
+            .send(|| client.get(&url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_redact_url_masks_credentials() {
+        assert_eq!(
+            redact_url("https://api.example.com/v1?q=berlin&api_key=sk-123"),
+            "https://api.example.com/v1?q=berlin&api_key=%5BREDACTED%5D"
+        );
+        assert_eq!(
+            redact_url("https://api.example.com/v1"),
+            "https://api.example.com/v1"
+        );
+    }
+}
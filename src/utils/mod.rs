@@ -1,6 +1,4 @@
-// Utility modules - placeholder for future utilities
-// This module can be expanded with common utilities like:
-// - Data validation helpers
-// - Expression parsing
-// - File system utilities
-// - Error recovery helpers
+// Utility modules shared across stages
+pub mod http;
+pub mod retry;
+pub mod state_file;
@@ -0,0 +1,169 @@
+//! Retry with exponential backoff, shared by the stages that retry failed calls.
+//!
+//! Every such stage reads the same knobs from its config: `retry_attempts`
+//! (total attempts, default 1), `retry_backoff_ms` (delay before the first
+//! retry, default 500) and `retry_backoff_multiplier` (growth of the delay per
+//! retry, default 2.0).
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often, and how patiently, a failed call is retried
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Duration,
+    pub backoff_multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Duration::from_millis(500),
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &HashMap<String, toml::Value>) -> Result<Self> {
+        let mut policy = Self::default();
+
+        if let Some(value) = config.get("retry_attempts") {
+            policy.max_attempts = value
+                .as_integer()
+                .filter(|n| *n >= 1)
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| anyhow::anyhow!("'retry_attempts' must be a positive integer"))?;
+        }
+
+        if let Some(value) = config.get("retry_backoff_ms") {
+            let ms = value.as_integer().filter(|n| *n >= 0).ok_or_else(|| {
+                anyhow::anyhow!("'retry_backoff_ms' must be a non-negative integer")
+            })?;
+            policy.backoff = Duration::from_millis(ms as u64);
+        }
+
+        if let Some(value) = config.get("retry_backoff_multiplier") {
+            policy.backoff_multiplier = value
+                .as_float()
+                .or_else(|| value.as_integer().map(|n| n as f64))
+                .filter(|m| *m >= 1.0)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("'retry_backoff_multiplier' must be a number >= 1.0")
+                })?;
+        }
+
+        Ok(policy)
+    }
+
+    /// Delay before retry number `retry` (1-based)
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .mul_f64(self.backoff_multiplier.powi(retry.saturating_sub(1) as i32))
+    }
+
+    /// Run `operation` until it succeeds or the attempts are used up
+    ///
+    /// Every error is retried; `operation_name` prefixes the warnings and the
+    /// final error.
+    pub async fn run<F, Fut, T>(&self, operation_name: &str, operation: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_attempts => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!(
+                        "{} failed on attempt {}/{}: {}. Retrying in {:?}...",
+                        operation_name,
+                        attempt,
+                        self.max_attempts,
+                        e,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) if self.max_attempts > 1 => {
+                    return Err(e.context(format!(
+                        "{} failed after {} attempts",
+                        operation_name, self.max_attempts
+                    )))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_from_config() {
+        assert_eq!(
+            RetryPolicy::from_config(&HashMap::new()).unwrap(),
+            RetryPolicy::default()
+        );
+
+        let mut config = HashMap::new();
+        config.insert("retry_attempts".to_string(), toml::Value::Integer(4));
+        config.insert("retry_backoff_ms".to_string(), toml::Value::Integer(100));
+        config.insert(
+            "retry_backoff_multiplier".to_string(),
+            toml::Value::Float(3.0),
+        );
+        let policy = RetryPolicy::from_config(&config).unwrap();
+        assert_eq!(policy.max_attempts, 4);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(900));
+
+        config.insert("retry_attempts".to_string(), toml::Value::Integer(0));
+        assert!(RetryPolicy::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_recovers_from_transient_failure() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::ZERO,
+            backoff_multiplier: 1.0,
+        };
+
+        // Fails on the first call, succeeds on the second
+        let calls = AtomicU32::new(0);
+        let result = policy
+            .run("flaky", || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => anyhow::bail!("transient failure"),
+                    n => Ok(n),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Gives up once the attempts are used up
+        let calls = AtomicU32::new(0);
+        let err = policy
+            .run("broken", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                anyhow::bail!("still failing")
+            })
+            .await
+            .map(|_: ()| ())
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(err.to_string().contains("broken failed after 3 attempts"));
+    }
+}
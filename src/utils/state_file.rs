@@ -0,0 +1,84 @@
+//! JSON files that stages keep between runs, such as checkpoints and fitted
+//! parameters.
+//!
+//! Saves go to a temp file in the same directory and are then renamed over the
+//! target, so a crash mid-write leaves the previous file intact.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Read a JSON file, or `None` if it does not exist yet
+///
+/// `kind` names the file in errors, e.g. "agg_state state file".
+pub async fn load_json<T: DeserializeOwned>(path: &Path, kind: &str) -> Result<Option<T>> {
+    let content = match fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {} {:?}", kind, path)),
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| anyhow::anyhow!("Invalid {} {:?}: {}", kind, path, e))
+}
+
+/// Write a value as pretty JSON, replacing the file atomically
+pub async fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).await?;
+    }
+    let tmp_path = temp_path(path);
+    fs::write(&tmp_path, serde_json::to_vec_pretty(value)?)
+        .await
+        .with_context(|| format!("Failed to write {:?}", tmp_path))?;
+    fs::rename(&tmp_path, path)
+        .await
+        .with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+/// `<file name>.tmp` next to the target, so the rename stays on one filesystem
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_save_replaces_file_and_leaves_no_temp_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("state.json");
+
+        let missing: Option<BTreeMap<String, u64>> = load_json(&path, "state").await.unwrap();
+        assert!(missing.is_none());
+
+        save_json(&path, &BTreeMap::from([("a".to_string(), 1u64)]))
+            .await
+            .unwrap();
+        save_json(&path, &BTreeMap::from([("a".to_string(), 2u64)]))
+            .await
+            .unwrap();
+
+        let loaded: Option<BTreeMap<String, u64>> = load_json(&path, "state").await.unwrap();
+        assert_eq!(loaded.unwrap()["a"], 2);
+        assert!(!temp_path(&path).exists());
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        let err = load_json::<BTreeMap<String, u64>>(&path, "state")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid state"));
+    }
+}
@@ -11,6 +11,8 @@ use wasmtime::component::{Component, Linker, ResourceTable};
 use wasmtime::{Config, Engine, Store};
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
 
+use crate::utils::retry::RetryPolicy;
+
 // For home directory access
 
 // Generate host-side bindings from WIT file
@@ -247,6 +249,38 @@ impl WasmPluginLoader {
         None
     }
 
+    /// Execute a stage, retrying failed guest calls according to `policy`
+    ///
+    /// Each attempt runs in a fresh `Store`, so no guest state leaks from a
+    /// failed attempt into the next one.
+    pub async fn execute_with_retry(
+        &self,
+        plugin_name: &str,
+        stage_name: &str,
+        context: ExecutionContext,
+        policy: &RetryPolicy,
+    ) -> Result<DataFormat> {
+        // Unknown plugins or stages won't succeed on a retry
+        let handle = self
+            .plugins
+            .get(plugin_name)
+            .ok_or_else(|| anyhow::anyhow!("WASM plugin '{}' not loaded", plugin_name))?;
+        if !handle.has_stage(stage_name) {
+            anyhow::bail!(
+                "WASM plugin '{}' does not provide stage '{}'",
+                plugin_name,
+                stage_name
+            );
+        }
+
+        let operation_name = format!("WASM plugin '{}' stage '{}'", plugin_name, stage_name);
+        policy
+            .run(&operation_name, || {
+                self.execute(plugin_name, stage_name, context.clone())
+            })
+            .await
+    }
+
     /// Execute a stage from a WASM plugin
     ///
     /// This is the unified execution interface for all stages.
@@ -254,7 +288,7 @@ impl WasmPluginLoader {
         &self,
         plugin_name: &str,
         stage_name: &str,
-        context: ExecutionContext,
+        mut context: ExecutionContext,
     ) -> Result<DataFormat> {
         let handle = self
             .plugins
@@ -270,6 +304,8 @@ impl WasmPluginLoader {
             );
         }
 
+        context.config = resolve_secrets(context.config)?;
+
         // Create new store for this execution with file system access
         let current_dir = std::env::current_dir()?;
         let current_dir_str = current_dir
@@ -297,7 +333,7 @@ impl WasmPluginLoader {
         let plugin = Plugin::instantiate_async(&mut store, &handle.component, &linker).await?;
 
         // Call execute function
-        plugin
+        let output = plugin
             .call_execute(&mut store, stage_name, &context)
             .await?
             .map_err(|e| {
@@ -307,7 +343,12 @@ impl WasmPluginLoader {
                     stage_name,
                     e
                 )
-            })
+            })?;
+
+        // Reject output whose bytes don't match the declared format
+        validate_output(plugin_name, stage_name, &output)?;
+
+        Ok(output)
     }
 
     /// Validate plugin configuration for a stage
@@ -317,6 +358,7 @@ impl WasmPluginLoader {
         stage_name: &str,
         config: Vec<(String, String)>,
     ) -> Result<()> {
+        let config = resolve_secrets(config)?;
         let handle = self
             .plugins
             .get(plugin_name)
@@ -378,6 +420,70 @@ impl Default for WasmPluginLoader {
     }
 }
 
+/// Replace `secret.<name>` entries with `<name>` set to the referenced secret
+///
+/// Runs right before each guest call, so resolved values only exist in the
+/// guest's arguments and never in the host-side context, its retries' clones,
+/// or anything the host logs.
+pub fn resolve_secrets(config: Vec<(String, String)>) -> Result<Vec<(String, String)>> {
+    config
+        .into_iter()
+        .map(
+            |(key, value)| match key.strip_prefix(crate::core::config::SECRET_PREFIX) {
+                Some(name) => {
+                    let secret = std::env::var(&value).map_err(|_| {
+                        anyhow::anyhow!(
+                            "Secret '{}': environment variable '{}' is not set",
+                            name,
+                            value
+                        )
+                    })?;
+                    Ok((name.to_string(), secret))
+                }
+                None => Ok((key, value)),
+            },
+        )
+        .collect()
+}
+
+/// Check that a plugin's returned bytes actually match their `DataFormat` tag
+///
+/// `JsonRecords` must parse as a JSON array of objects and `ArrowIpc` must be
+/// a readable Arrow IPC file. `Raw` is opaque and always accepted.
+fn validate_output(plugin_name: &str, stage_name: &str, output: &DataFormat) -> Result<()> {
+    match output {
+        DataFormat::JsonRecords(bytes) => {
+            serde_json::from_slice::<Vec<serde_json::Map<String, serde_json::Value>>>(bytes)
+                .map(|_| ())
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "WASM plugin '{}' stage '{}' returned corrupt json-records output \
+                        ({} bytes): expected a JSON array of objects, {}",
+                        plugin_name,
+                        stage_name,
+                        bytes.len(),
+                        e
+                    )
+                })
+        }
+        DataFormat::ArrowIpc(bytes) => {
+            arrow::ipc::reader::FileReader::try_new(std::io::Cursor::new(bytes.as_slice()), None)
+                .map(|_| ())
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "WASM plugin '{}' stage '{}' returned corrupt arrow-ipc output \
+                        ({} bytes): {}",
+                        plugin_name,
+                        stage_name,
+                        bytes.len(),
+                        e
+                    )
+                })
+        }
+        DataFormat::Raw(_) => Ok(()),
+    }
+}
+
 /// Get WASM plugin search paths in priority order
 fn get_wasm_plugin_search_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
@@ -410,4 +516,45 @@ mod tests {
             .with_plugin_dir("/custom/path");
         assert_eq!(loader.plugin_dir, PathBuf::from("/custom/path"));
     }
+
+    #[test]
+    fn test_validate_output_json_records() {
+        let valid = DataFormat::JsonRecords(br#"[{"id": 1}, {"id": 2}]"#.to_vec());
+        assert!(validate_output("echo", "echo", &valid).is_ok());
+
+        // Bytes that are not JSON at all
+        let garbage = DataFormat::JsonRecords(b"\x00\x01not json".to_vec());
+        let err = validate_output("echo", "echo", &garbage)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("WASM plugin 'echo' stage 'echo'"));
+        assert!(err.contains("corrupt json-records"));
+
+        // Valid JSON, but not an array of records
+        let scalar = DataFormat::JsonRecords(b"42".to_vec());
+        assert!(validate_output("echo", "echo", &scalar).is_err());
+    }
+
+    #[test]
+    fn test_validate_output_arrow_ipc() {
+        use polars::prelude::*;
+
+        let mut df = df!("id" => [1i64, 2, 3]).unwrap();
+        let mut buf = Vec::new();
+        IpcWriter::new(&mut buf).finish(&mut df).unwrap();
+
+        let valid = DataFormat::ArrowIpc(buf);
+        assert!(validate_output("excel", "read", &valid).is_ok());
+
+        // JSON bytes mislabelled as Arrow IPC
+        let mislabelled = DataFormat::ArrowIpc(br#"[{"id": 1}]"#.to_vec());
+        let err = validate_output("excel", "read", &mislabelled)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("WASM plugin 'excel' stage 'read'"));
+        assert!(err.contains("corrupt arrow-ipc"));
+
+        // Raw output is passed through untouched
+        assert!(validate_output("excel", "read", &DataFormat::Raw(vec![0xff])).is_ok());
+    }
 }
@@ -121,12 +121,8 @@ fn test_generated_pipeline_has_correct_metadata() -> Result<()> {
         global.get("log_level").and_then(|v| v.as_str()),
         Some("info")
     );
-    assert_eq!(
-        global
-            .get("max_parallel_tasks")
-            .and_then(|v| v.as_integer()),
-        Some(4)
-    );
+    // Parallelism stays unlimited unless the user opts into a cap
+    assert!(global.get("max_parallel_tasks").is_none());
 
     // Cleanup
     fs::remove_file(path)?;
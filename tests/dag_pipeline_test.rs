@@ -180,3 +180,337 @@ value = "test"
 
     Ok(())
 }
+
+/// Serve `body` with `status` to the first connection and return its URL
+async fn serve_config_once(status: &'static str, body: String) -> Result<String> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/toml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+
+    Ok(format!("http://{}/pipeline.toml", addr))
+}
+
+#[tokio::test]
+async fn test_dag_pipeline_from_remote_config() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let input_path = temp_dir.path().join("input.json");
+    let output_path = temp_dir.path().join("output.json");
+
+    fs::write(
+        &input_path,
+        r#"[{"id": 1, "status": "active"}, {"id": 2, "status": "inactive"}]"#,
+    )?;
+
+    std::env::set_var(
+        "CONVEYOR_REMOTE_TEST_OUTPUT",
+        output_path.to_string_lossy().replace('\\', "/"),
+    );
+
+    // Variables are resolved after the config is fetched
+    let config_str = format!(
+        r#"
+[pipeline]
+name = "remote-config"
+version = "1.0"
+
+[global.variables]
+output = "${{CONVEYOR_REMOTE_TEST_OUTPUT}}"
+
+[[stages]]
+id = "load"
+function = "json.read"
+inputs = []
+
+[stages.config]
+path = "{}"
+
+[[stages]]
+id = "active"
+function = "filter.apply"
+inputs = ["load"]
+
+[stages.config]
+column = "status"
+operator = "=="
+value = "active"
+
+[[stages]]
+id = "save"
+function = "json.write"
+inputs = ["active"]
+
+[stages.config]
+path = "{{{{output}}}}"
+"#,
+        input_path.to_string_lossy().replace('\\', "/")
+    );
+
+    let url = serve_config_once("200 OK", config_str).await?;
+    let mut pipeline = DagPipeline::from_file(&url).await?;
+    pipeline.execute().await?;
+
+    let output_data = fs::read_to_string(&output_path)?;
+    assert!(output_data.contains("\"id\":1"));
+    assert!(!output_data.contains("inactive"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remote_config_errors() -> Result<()> {
+    let url = serve_config_once("200 OK", "[pipeline\nname = ".to_string()).await?;
+    let err = DagPipelineConfig::from_file(&url).await.unwrap_err();
+    assert!(err.to_string().contains("is not valid TOML"));
+
+    let url = serve_config_once("404 Not Found", String::new()).await?;
+    let err = DagPipelineConfig::from_file(&url).await.unwrap_err();
+    assert!(err.to_string().contains("HTTP 404"));
+
+    // s3:// is rejected up front instead of being read as a local path
+    let err = DagPipelineConfig::from_file("s3://bucket/pipeline.toml")
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("s3:// config locations are not supported yet"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dag_pipeline_max_records() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let input_path = temp_dir.path().join("input.json");
+    let output_path = temp_dir.path().join("output.json");
+
+    let records: Vec<serde_json::Value> = (1..=10)
+        .map(|i| serde_json::json!({ "id": i, "status": "active" }))
+        .collect();
+    fs::write(&input_path, serde_json::to_string(&records)?)?;
+
+    let config_str = format!(
+        r#"
+[pipeline]
+name = "max-records"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "json.read"
+inputs = []
+
+[stages.config]
+path = "{}"
+
+[[stages]]
+id = "save"
+function = "json.write"
+inputs = ["load"]
+
+[stages.config]
+path = "{}"
+"#,
+        input_path.to_string_lossy().replace('\\', "/"),
+        output_path.to_string_lossy().replace('\\', "/")
+    );
+
+    // Same override `conveyor run --max-records 4` applies
+    let mut config = DagPipelineConfig::from_str(&config_str)?;
+    config.global.max_records = Some(4);
+
+    let mut pipeline = DagPipeline::new(config).await?;
+    pipeline.execute().await?;
+
+    let output: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(&output_path)?)?;
+    assert_eq!(output.len(), 4);
+    assert_eq!(output[3]["id"], serde_json::json!(4));
+    assert_eq!(pipeline.records_emitted(), Some(4));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dag_pipeline_tag_filter() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let input_path = temp_dir.path().join("input.json");
+    let raw_path = temp_dir.path().join("raw.json");
+    let active_path = temp_dir.path().join("active.json");
+
+    fs::write(
+        &input_path,
+        r#"[{"id": 1, "status": "active"}, {"id": 2, "status": "inactive"}]"#,
+    )?;
+
+    let config_str = format!(
+        r#"
+[pipeline]
+name = "tag-filter"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "json.read"
+inputs = []
+
+[stages.config]
+path = "{}"
+
+[[stages]]
+id = "save_raw"
+function = "json.write"
+inputs = ["load"]
+
+[stages.config]
+path = "{}"
+
+[[stages]]
+id = "filter_active"
+function = "filter.apply"
+inputs = ["load"]
+tags = ["reports"]
+
+[stages.config]
+column = "status"
+operator = "=="
+value = "active"
+
+[[stages]]
+id = "save_active"
+function = "json.write"
+inputs = ["filter_active"]
+tags = ["reports"]
+
+[stages.config]
+path = "{}"
+"#,
+        input_path.to_string_lossy().replace('\\', "/"),
+        raw_path.to_string_lossy().replace('\\', "/"),
+        active_path.to_string_lossy().replace('\\', "/")
+    );
+
+    // Same override `conveyor run --tag reports` applies
+    let mut config = DagPipelineConfig::from_str(&config_str)?;
+    config.global.tags = vec!["reports".to_string()];
+
+    let mut pipeline = DagPipeline::new(config).await?;
+    pipeline.execute().await?;
+
+    // `load` runs because a tagged stage needs it; `save_raw` is pruned
+    let output: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(&active_path)?)?;
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0]["id"], serde_json::json!(1));
+    assert!(!raw_path.exists());
+
+    Ok(())
+}
+
+/// Accept one request, answer 200 and return the request body
+async fn capture_webhook() -> Result<(String, tokio::sync::oneshot::Receiver<String>)> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    tokio::spawn(async move {
+        let Ok((mut socket, _)) = listener.accept().await else {
+            return;
+        };
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = socket.read(&mut buf).await {
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                continue;
+            };
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    let line = line.to_ascii_lowercase();
+                    line.strip_prefix("content-length:")
+                        .and_then(|n| n.trim().parse::<usize>().ok())
+                })
+                .unwrap_or(0);
+            if body.len() >= length {
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                    .await;
+                let _ = tx.send(body.to_string());
+                break;
+            }
+        }
+    });
+
+    Ok((format!("http://{}/hook", addr), rx))
+}
+
+#[tokio::test]
+async fn test_dag_pipeline_notifies_on_failure() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let missing_path = temp_dir.path().join("missing.json");
+    let (url, body) = capture_webhook().await?;
+
+    let config_str = format!(
+        r#"
+[pipeline]
+name = "notify-on-failure"
+version = "1.0"
+
+[[stages]]
+id = "load"
+function = "json.read"
+inputs = []
+
+[stages.config]
+path = "{}"
+
+[[stages]]
+id = "show"
+function = "stdout.write"
+inputs = ["load"]
+
+[[notifications]]
+url = "{}"
+when = "on_success"
+
+[[notifications]]
+url = "{}"
+when = "on_failure"
+template = '{{"text": "{{{{pipeline}}}} {{{{status}}}}", "error": "{{{{error}}}}"}}'
+"#,
+        missing_path.to_string_lossy().replace('\\', "/"),
+        url,
+        url
+    );
+
+    let config = DagPipelineConfig::from_str(&config_str)?;
+    let mut pipeline = DagPipeline::new(config).await?;
+    assert!(pipeline.execute().await.is_err());
+
+    // Only the on_failure notification fires, so the single request is its payload
+    let payload: serde_json::Value = serde_json::from_str(&body.await?)?;
+    assert_eq!(payload["text"], "notify-on-failure failure");
+    assert!(payload["error"].as_str().unwrap().contains("missing.json"));
+
+    Ok(())
+}
@@ -103,3 +103,50 @@ async fn test_multiple_plugins() {
         "Should have at least 1 plugin"
     );
 }
+
+#[tokio::test]
+#[ignore] // WASM plugin not built in CI
+async fn test_echo_plugin_retries_transient_failure() {
+    use conveyor::core::stage::{Stage, WasmPluginStageAdapter};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    let mut loader = WasmPluginLoader::new()
+        .expect("Failed to create loader")
+        .with_plugin_dir("target/wasm32-wasip2/release");
+    loader
+        .load_plugin("echo")
+        .await
+        .expect("Failed to load plugin");
+
+    let stage = WasmPluginStageAdapter::new(
+        "echo".to_string(),
+        "echo".to_string(),
+        "echo".to_string(),
+        "Echo source".to_string(),
+        "source".to_string(),
+        Arc::new(loader),
+    );
+
+    // The marker is resolved inside the guest, relative to the preopened cwd
+    let marker = format!("echo-retry-{}.marker", std::process::id());
+    let _ = std::fs::remove_file(&marker);
+
+    let mut config = HashMap::new();
+    config.insert(
+        "fail_once_marker".to_string(),
+        toml::Value::String(marker.clone()),
+    );
+
+    // Without retries, the first call fails
+    let result = stage.execute(HashMap::new(), &config).await;
+    assert!(result.is_err(), "Expected the first attempt to fail");
+    std::fs::remove_file(&marker).expect("Marker should have been created");
+
+    // With a second attempt, the run recovers
+    config.insert("retry_attempts".to_string(), toml::Value::Integer(2));
+    config.insert("retry_backoff_ms".to_string(), toml::Value::Integer(10));
+    let result = stage.execute(HashMap::new(), &config).await;
+    let _ = std::fs::remove_file(&marker);
+    assert!(result.is_ok(), "Retry did not recover: {:?}", result.err());
+}